@@ -0,0 +1,70 @@
+//! Heap size and equality checks of records and compact records, on the sample files
+//!
+//! `cargo bench --bench intern [FILE.mrc...]`
+
+use marc_rs::{parse, CompactRecord, Encoding, FormatEncoding, Interner, MarcFormat, Record};
+use std::mem::size_of;
+use std::time::Instant;
+
+/// Bytes allocated for a record: its vectors and strings
+fn record_bytes(record: &Record) -> usize {
+    let control: usize = record.control_fields.iter().map(|f| f.tag.capacity() + f.value.capacity()).sum();
+    let data: usize = record.data_fields.iter().map(|f| f.tag.capacity() + f.subfields.capacity() * size_of::<marc_rs::Subfield>() + f.subfields.iter().map(|s| s.value.capacity()).sum::<usize>()).sum();
+    record.control_fields.capacity() * size_of::<marc_rs::ControlField>() + record.data_fields.capacity() * size_of::<marc_rs::DataField>() + control + data
+}
+
+/// Bytes allocated for a compact record, without the values held by the interner
+fn compact_bytes(record: &CompactRecord, max_len: usize) -> usize {
+    let own = |value: &str| if value.len() > max_len { value.len() } else { 0 };
+    let values: usize = record.control_fields.iter().map(|f| own(f.value.as_str())).chain(record.data_fields.iter().flat_map(|f| f.subfields.iter().map(|(_, value)| own(value.as_str())))).sum();
+    size_of_val(&*record.control_fields) + size_of_val(&*record.data_fields) + record.data_fields.iter().map(|f| size_of_val(&*f.subfields)).sum::<usize>() + values
+}
+
+/// Time of comparing each record with itself and with the next one
+fn time_equality<T: PartialEq + Clone>(items: &[T]) -> (f64, usize) {
+    let copies = items.to_vec();
+    let start = Instant::now();
+    let mut equal = 0;
+    for _ in 0..10 {
+        equal += items.iter().zip(&copies).filter(|(a, b)| a == b).count();
+        equal += items.windows(2).filter(|pair| pair[0] == pair[1]).count();
+    }
+    (start.elapsed().as_secs_f64() * 1000.0, equal)
+}
+
+fn main() {
+    let mut paths: Vec<String> = std::env::args().skip(1).filter(|arg| !arg.starts_with("--")).collect();
+    if paths.is_empty() {
+        paths = ["Bloom_CiteThemRight_Videos_2025-12-02.mrc", "Open Book Publishers full record set December 2025.mrc", "SpringerNature_Books_MARC21_20260119_100652.mrc"].iter().map(|name| format!("{}/samples/{}", env!("CARGO_MANIFEST_DIR"), name)).collect();
+    }
+    for path in paths {
+        let Ok(data) = std::fs::read(&path) else {
+            eprintln!("Skipping {}: cannot read it", path);
+            continue;
+        };
+        let records = match parse(&data, FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8)) {
+            Ok(records) => records,
+            Err(e) => {
+                eprintln!("Skipping {}: {}", path, e);
+                continue;
+            }
+        };
+
+        let start = Instant::now();
+        let max_len = 128;
+        let mut interner = Interner::with_max_len(max_len);
+        let compact: Vec<CompactRecord> = records.iter().filter_map(|r| interner.compact(r).ok()).collect();
+        let compact_ms = start.elapsed().as_secs_f64() * 1000.0;
+        // Each shared value also has its reference counts and an entry in the interner's table
+        let shared = interner.bytes() + interner.len() * size_of::<usize>() * 4;
+
+        let record_total: usize = records.iter().map(record_bytes).sum();
+        let compact_total: usize = compact.iter().map(|r| compact_bytes(r, max_len)).sum::<usize>() + shared;
+        let (record_ms, _) = time_equality(&records);
+        let (compact_eq_ms, _) = time_equality(&compact);
+
+        println!("{}: {} records", path.rsplit('/').next().unwrap_or(&path), records.len());
+        println!("  heap (approx.): records {} KiB, compact {} KiB ({} distinct values); compacting took {:.1} ms", record_total / 1024, compact_total / 1024, interner.len(), compact_ms);
+        println!("  equality checks: records {:.1} ms, compact {:.1} ms", record_ms, compact_eq_ms);
+    }
+}
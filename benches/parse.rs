@@ -0,0 +1,73 @@
+//! Parsing throughput of binary records
+//!
+//! `cargo bench --bench parse [--repeat N] [FILE.mrc...]` reads each file (by
+//! default the MARC21 samples), repeated N times in memory, with [`parse`],
+//! the [`RecordReader`] iterator and [`RecordReader::read_into`]. Files larger
+//! than memory are read from disk by the reader only, e.g. multi-GB exports.
+
+use marc_rs::{parse, Encoding, FormatEncoding, Leader, MarcFormat, Record, RecordReader};
+use std::fs::File;
+use std::io::BufReader;
+use std::time::Instant;
+
+/// Files larger than this are only streamed from disk
+const IN_MEMORY_LIMIT: u64 = 1 << 30;
+
+fn report(name: &str, bytes: u64, records: usize, start: Instant) {
+    let seconds = start.elapsed().as_secs_f64();
+    println!("  {:<10} {:>8} records {:>9.1} MB/s {:>10.0} records/s", name, records, bytes as f64 / seconds / 1e6, records as f64 / seconds);
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1).filter(|arg| arg != "--bench");
+    let mut repeat = 1;
+    let mut paths = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--repeat" => repeat = args.next().and_then(|n| n.parse().ok()).expect("--repeat N"),
+            _ => paths.push(arg),
+        }
+    }
+    if paths.is_empty() {
+        paths = ["Open Book Publishers full record set December 2025.mrc", "SpringerNature_Books_MARC21_20260119_100652.mrc"].iter().map(|name| format!("{}/samples/{}", env!("CARGO_MANIFEST_DIR"), name)).collect();
+    }
+    let format = FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8);
+
+    for path in paths {
+        let Ok(size) = std::fs::metadata(&path).map(|m| m.len()) else {
+            eprintln!("Skipping {}: cannot read it", path);
+            continue;
+        };
+        println!("{} ({} MB)", path.rsplit('/').next().unwrap_or(&path), size * repeat as u64 / 1_000_000);
+
+        if size * (repeat as u64) <= IN_MEMORY_LIMIT {
+            let data = std::fs::read(&path).expect("readable file").repeat(repeat);
+            let bytes = data.len() as u64;
+
+            let start = Instant::now();
+            let records = parse(&data, format).expect("valid records").len();
+            report("parse", bytes, records, start);
+
+            let start = Instant::now();
+            let records = RecordReader::new(data.as_slice(), format).try_fold(0, |n, r| r.map(|_| n + 1)).expect("valid records");
+            report("reader", bytes, records, start);
+
+            let start = Instant::now();
+            let mut reader = RecordReader::new(data.as_slice(), format);
+            let mut record = Record {
+                leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+                control_fields: Vec::new(),
+                data_fields: Vec::new(),
+            };
+            let mut records = 0;
+            while reader.read_into(&mut record).expect("valid record") {
+                records += 1;
+            }
+            report("read_into", bytes, records, start);
+        } else {
+            let start = Instant::now();
+            let records = RecordReader::new(BufReader::new(File::open(&path).expect("readable file")), format).try_fold(0, |n, r| r.map(|_| n + 1)).expect("valid records");
+            report("reader", size, records, start);
+        }
+    }
+}
@@ -0,0 +1,350 @@
+//! Generates the field definition and indicator tables from the field lists in data/.
+//!
+//! data/marc21_bibliographic.txt is the Library of Congress field list, as fetched
+//! by scripts/update-marc-data.sh; data/unimarc_bibliographic.txt is written in the
+//! same layout:
+//!
+//! ```text
+//! 245 - TITLE STATEMENT (NR)
+//!    Indicators
+//!       First - Title added entry
+//!          0 - No added entry
+//!          1 - Added entry
+//!       Second - Nonfiling characters
+//!          0 - No nonfiling characters
+//!          1-9 - Number of nonfiling characters
+//!    Subfield Codes
+//!       $a - Title (NR)
+//! ```
+//!
+//! Fields marked `[OBSOLETE]` are kept and listed in a separate table; obsolete
+//! indicator values and subfields are skipped, as are other lines (headings,
+//! character positions).
+//!
+//! The geographic area table comes from data/gacs.xml, the Library of Congress
+//! MARC Code List for Geographic Areas.
+
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+/// Names kept capitalized when sentence-casing the field names of the LoC list
+const PROPER_NOUNS: &[&str] = &[
+    "Canada",
+    "CODEN",
+    "Dewey",
+    "GPO",
+    "International Standard Book Number",
+    "International Standard Serial Number",
+    "ISSN",
+    "Library of Congress",
+    "MARC",
+    "National Agricultural Library",
+    "National Library of Medicine",
+    "Universal Decimal Classification",
+];
+
+struct Subfield {
+    code: char,
+    repeatable: bool,
+    name: String,
+}
+
+struct Indicator {
+    label: String,
+    values: Vec<(char, String)>,
+}
+
+struct Field {
+    tag: String,
+    repeatable: bool,
+    name: String,
+    obsolete: bool,
+    indicators: Vec<Indicator>,
+    subfields: Vec<Subfield>,
+}
+
+#[derive(Clone, Copy)]
+enum Section {
+    Indicators,
+    Subfields,
+    Other,
+}
+
+/// Split `NAME (R)` or `NAME (NR)`, ignoring trailing bracketed notes
+fn split_repeatable(text: &str) -> Option<(&str, bool)> {
+    let mut text = text.trim_end();
+    while let Some(start) = text.strip_suffix(']').and_then(|t| t.rfind('[')) {
+        text = text[..start].trim_end();
+    }
+    if let Some(name) = text.strip_suffix("(R)") {
+        Some((name.trim_end(), true))
+    } else {
+        text.strip_suffix("(NR)").map(|name| (name.trim_end(), false))
+    }
+}
+
+fn is_obsolete(text: &str) -> bool {
+    text.contains("[OBSOLETE")
+}
+
+/// Sentence case for the capitalized names of the LoC list, whose parts are separated by `--`
+fn field_name(name: &str) -> String {
+    if name.chars().any(char::is_lowercase) {
+        return name.to_string();
+    }
+    let mut name = name.to_lowercase().replace("--", " - ");
+    for noun in PROPER_NOUNS {
+        name = name.replace(&noun.to_lowercase(), noun);
+    }
+    let mut capitalize = true;
+    let mut previous = ' ';
+    name.chars()
+        .map(|c| {
+            let c = if capitalize { c.to_ascii_uppercase() } else { c };
+            capitalize = c == '(' || (c == ' ' && previous == '-');
+            previous = c;
+            c
+        })
+        .collect()
+}
+
+/// Values of an indicator value line: a character, `#` for blank, or a range such as `1-9`
+fn parse_indicator_value(text: &str) -> Option<Vec<char>> {
+    let value = |v: &str| match v {
+        "#" => Some(' '),
+        _ => {
+            let mut chars = v.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if c.is_ascii_alphanumeric() => Some(c),
+                _ => None,
+            }
+        }
+    };
+    match text.split_once('-') {
+        Some((start, end)) => Some((value(start)?..=value(end)?).collect()),
+        None => Some(vec![value(text)?]),
+    }
+}
+
+fn parse_field_list(path: &str) -> Vec<Field> {
+    let content = fs::read_to_string(path).unwrap_or_else(|e| panic!("{}: {}", path, e));
+    let mut fields: Vec<Field> = Vec::new();
+    // The field the indented lines belong to, None within skipped entries
+    let mut current: Option<usize> = None;
+    let mut section = Section::Other;
+
+    for (i, line) in content.lines().enumerate() {
+        let line_number = i + 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        if !line.starts_with(char::is_whitespace) {
+            current = None;
+            let Some((tag, rest)) = trimmed.split_once(" - ") else {
+                continue;
+            };
+            let Some((name, repeatable)) = split_repeatable(rest) else {
+                continue;
+            };
+            if tag.len() != 3 || !tag.chars().all(|c| c.is_ascii_digit()) {
+                continue;
+            }
+            if fields.iter().any(|f| f.tag == tag) {
+                panic!("{}:{}: duplicate tag {}", path, line_number, tag);
+            }
+            fields.push(Field {
+                tag: tag.to_string(),
+                repeatable,
+                name: field_name(name),
+                obsolete: is_obsolete(rest),
+                indicators: Vec::new(),
+                subfields: Vec::new(),
+            });
+            current = Some(fields.len() - 1);
+            section = Section::Other;
+            continue;
+        }
+
+        let Some(field) = current.map(|index| &mut fields[index]) else {
+            continue;
+        };
+        let Some((key, rest)) = trimmed.split_once(" - ") else {
+            section = match trimmed {
+                "Indicators" => Section::Indicators,
+                "Subfield Codes" => Section::Subfields,
+                _ => Section::Other,
+            };
+            continue;
+        };
+
+        match section {
+            Section::Indicators if key == "First" || key == "Second" => {
+                if field.indicators.len() != usize::from(key == "Second") {
+                    panic!("{}:{}: {} indicator out of order", path, line_number, key);
+                }
+                field.indicators.push(Indicator {
+                    label: rest.to_string(),
+                    values: Vec::new(),
+                });
+            }
+            Section::Indicators if !is_obsolete(rest) => {
+                let Some(indicator) = field.indicators.last_mut() else {
+                    panic!("{}:{}: indicator value outside of an indicator", path, line_number);
+                };
+                let Some(values) = parse_indicator_value(key) else {
+                    panic!("{}:{}: invalid indicator value {:?}", path, line_number, key);
+                };
+                indicator.values.extend(values.into_iter().map(|value| (value, rest.to_string())));
+            }
+            Section::Subfields if key.starts_with('$') && !is_obsolete(rest) => {
+                let mut code = key[1..].chars();
+                // Ranges such as `$a-z` stand for the subfields of an associated field
+                let (Some(code), None) = (code.next(), code.next()) else {
+                    continue;
+                };
+                let Some((name, repeatable)) = split_repeatable(rest) else {
+                    panic!("{}:{}: expected (R) or (NR) after {:?}", path, line_number, rest);
+                };
+                field.subfields.push(Subfield {
+                    code,
+                    repeatable,
+                    name: name.to_string(),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    for field in &fields {
+        if !matches!(field.indicators.len(), 0 | 2) {
+            panic!("{}: field {} defines only one indicator", path, field.tag);
+        }
+        if let Some(indicator) = field.indicators.iter().find(|i| i.values.is_empty()) {
+            panic!("{}: field {} has no value for indicator {:?}", path, field.tag, indicator.label);
+        }
+    }
+    fields.sort_by(|a, b| a.tag.cmp(&b.tag));
+    fields
+}
+
+fn write_definitions(out: &mut String, constant: &str, fields: &[Field]) {
+    writeln!(out, "const {}: &[FieldDefinition] = &[", constant).unwrap();
+    for field in fields {
+        writeln!(
+            out,
+            "    FieldDefinition {{ tag: {:?}, name: {:?}, repeatable: {}, subfields: &[",
+            field.tag, field.name, field.repeatable
+        )
+        .unwrap();
+        for subfield in &field.subfields {
+            writeln!(
+                out,
+                "        SubfieldDefinition {{ code: {:?}, name: {:?}, repeatable: {} }},",
+                subfield.code, subfield.name, subfield.repeatable
+            )
+            .unwrap();
+        }
+        writeln!(out, "    ] }},").unwrap();
+    }
+    writeln!(out, "];").unwrap();
+}
+
+fn write_obsolete(out: &mut String, constant: &str, fields: &[Field]) {
+    let tags: Vec<&str> = fields.iter().filter(|f| f.obsolete).map(|f| f.tag.as_str()).collect();
+    writeln!(out, "const {}: &[&str] = &{:?};", constant, tags).unwrap();
+}
+
+fn write_indicators(out: &mut String, constant: &str, fields: &[Field]) {
+    writeln!(out, "const {}: &[(&str, Indicators)] = &[", constant).unwrap();
+    for field in fields.iter().filter(|f| !f.indicators.is_empty()) {
+        writeln!(out, "    ({:?}, Indicators {{", field.tag).unwrap();
+        for (position, indicator) in ["first", "second"].iter().zip(&field.indicators) {
+            writeln!(out, "        {}: Indicator {{ label: {:?}, values: &[", position, indicator.label).unwrap();
+            for (value, meaning) in &indicator.values {
+                writeln!(out, "            IndicatorValue {{ value: {:?}, meaning: {:?} }},", value, meaning).unwrap();
+            }
+            writeln!(out, "        ] }},").unwrap();
+        }
+        writeln!(out, "    }}),").unwrap();
+    }
+    writeln!(out, "];").unwrap();
+}
+
+/// Text of the first `<element ...>` of `xml` whose start tag contains `attribute`, unescaped
+fn element_text(xml: &str, element: &str, attribute: &str) -> Option<String> {
+    let mut rest = xml;
+    loop {
+        let start = rest.find(&format!("<{}", element))?;
+        rest = &rest[start..];
+        let tag_end = rest.find('>')?;
+        let end = rest.find(&format!("</{}>", element))?;
+        if rest[..tag_end].contains(attribute) {
+            let text = &rest[tag_end + 1..end];
+            return Some(text.replace("&lt;", "<").replace("&gt;", ">").replace("&quot;", "\"").replace("&apos;", "'").replace("&amp;", "&"));
+        }
+        rest = &rest[end..];
+    }
+}
+
+/// Current (code, authorized name) pairs of the geographic area code list, codes padded to seven characters
+fn parse_geographic_areas(path: &str) -> Vec<(String, String)> {
+    let content = fs::read_to_string(path).unwrap_or_else(|e| panic!("{}: {}", path, e));
+    let mut areas: Vec<(String, String)> = Vec::new();
+
+    for entry in content.split("<gac>").skip(1).chain(content.split("<gac ").skip(1)) {
+        let entry = &entry[..entry.find("</gac>").unwrap_or_else(|| panic!("{}: unterminated <gac> entry", path))];
+        if entry.contains("status=\"obsolete\"") {
+            continue;
+        }
+        let code = element_text(entry, "code", "").unwrap_or_else(|| panic!("{}: entry without code", path));
+        let name = element_text(entry, "name", "authorized=\"yes\"")
+            .or_else(|| element_text(entry, "name", ""))
+            .unwrap_or_else(|| panic!("{}: no name for {}", path, code));
+        let code = format!("{:-<7}", code.trim());
+        if code.len() != 7 || !code.chars().all(|c| c.is_ascii_lowercase() || c == '-') {
+            panic!("{}: invalid code {:?}", path, code);
+        }
+        areas.push((code, name.trim().to_string()));
+    }
+
+    areas.sort();
+    if let Some(pair) = areas.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+        panic!("{}: duplicate code {}", path, pair[0].0);
+    }
+    areas
+}
+
+fn write_geographic_areas(out: &mut String, areas: &[(String, String)]) {
+    writeln!(out, "const GEOGRAPHIC_AREAS: &[(&str, &str)] = &[").unwrap();
+    for (code, name) in areas {
+        writeln!(out, "    ({:?}, {:?}),", code, name).unwrap();
+    }
+    writeln!(out, "];").unwrap();
+}
+
+fn main() {
+    let sources = [("MARC21", "data/marc21_bibliographic.txt"), ("UNIMARC", "data/unimarc_bibliographic.txt")];
+
+    let mut definitions = String::new();
+    let mut indicators = String::new();
+    for (format, path) in sources {
+        println!("cargo:rerun-if-changed={}", path);
+        let fields = parse_field_list(path);
+        write_definitions(&mut definitions, &format!("{}_DEFINITIONS", format), &fields);
+        write_obsolete(&mut definitions, &format!("{}_OBSOLETE", format), &fields);
+        write_indicators(&mut indicators, &format!("{}_INDICATORS", format), &fields);
+    }
+    let mut geographic_areas = String::new();
+    println!("cargo:rerun-if-changed=data/gacs.xml");
+    write_geographic_areas(&mut geographic_areas, &parse_geographic_areas("data/gacs.xml"));
+    println!("cargo:rerun-if-changed=build.rs");
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("field_definitions.rs"), definitions).unwrap();
+    fs::write(Path::new(&out_dir).join("indicator_definitions.rs"), indicators).unwrap();
+    fs::write(Path::new(&out_dir).join("geographic_areas.rs"), geographic_areas).unwrap();
+}
@@ -0,0 +1,230 @@
+//! Derive macro for the `marc-rs` struct mapping.
+//!
+//! `#[derive(MarcRecord)]` implements `marc_rs::FromRecord` and
+//! `marc_rs::ToRecord` for a struct with named fields. See the
+//! `marc_rs::mapping` module for the attribute syntax.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, GenericArgument, LitStr, PathArguments, Type};
+
+const DEFAULT_LEADER: &str = "00000nam a2200000 a 4500";
+
+/// How many values a struct field holds
+enum Multiplicity {
+    Required,
+    Optional,
+    Repeated,
+}
+
+struct Mapping {
+    ident: syn::Ident,
+    inner: Type,
+    multiplicity: Multiplicity,
+    tag: String,
+    subfield: Option<char>,
+    ind1: char,
+    ind2: char,
+}
+
+#[proc_macro_derive(MarcRecord, attributes(marc))]
+pub fn derive_marc_record(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input).unwrap_or_else(|e| e.to_compile_error()).into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let leader = struct_leader(&input)?;
+
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(&input.ident, "MarcRecord can only be derived for structs"));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(&input.ident, "MarcRecord requires named fields"));
+    };
+    let mappings = fields.named.iter().map(field_mapping).collect::<syn::Result<Vec<_>>>()?;
+
+    let reads = mappings.iter().map(read_field);
+    let writes = mappings.iter().map(write_field);
+    let idents = mappings.iter().map(|m| &m.ident);
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::marc_rs::FromRecord for #name #ty_generics #where_clause {
+            fn from_record(record: &::marc_rs::Record) -> ::std::result::Result<Self, ::std::string::String> {
+                #(#reads)*
+                ::std::result::Result::Ok(Self { #(#idents),* })
+            }
+        }
+
+        impl #impl_generics ::marc_rs::ToRecord for #name #ty_generics #where_clause {
+            fn to_record(&self) -> ::marc_rs::Record {
+                let mut record = ::marc_rs::mapping::new_record(#leader);
+                #(#writes)*
+                ::marc_rs::mapping::finish_record(&mut record);
+                record
+            }
+        }
+    })
+}
+
+/// Leader from `#[marc(leader = "...")]` on the struct, or the default one
+fn struct_leader(input: &DeriveInput) -> syn::Result<String> {
+    let mut leader = DEFAULT_LEADER.to_string();
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("marc")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("leader") {
+                let value: LitStr = meta.value()?.parse()?;
+                if value.value().len() != 24 {
+                    return Err(meta.error("leader must be 24 characters"));
+                }
+                leader = value.value();
+                Ok(())
+            } else {
+                Err(meta.error("unknown marc attribute, expected `leader`"))
+            }
+        })?;
+    }
+    Ok(leader)
+}
+
+fn field_mapping(field: &syn::Field) -> syn::Result<Mapping> {
+    let ident = field.ident.clone().expect("named field");
+    let mut tag = None;
+    let mut subfield = None;
+    let mut ind1 = ' ';
+    let mut ind2 = ' ';
+
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("marc")) {
+        attr.parse_nested_meta(|meta| {
+            let value: LitStr = meta.value()?.parse()?;
+            let single_char = || {
+                let text = value.value();
+                let mut chars = text.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Ok(c),
+                    _ => Err(meta.error("expected a single character")),
+                }
+            };
+            if meta.path.is_ident("tag") {
+                if value.value().len() != 3 {
+                    return Err(meta.error("tag must be 3 characters"));
+                }
+                tag = Some(value.value());
+            } else if meta.path.is_ident("subfield") {
+                subfield = Some(single_char()?);
+            } else if meta.path.is_ident("ind1") {
+                ind1 = single_char()?;
+            } else if meta.path.is_ident("ind2") {
+                ind2 = single_char()?;
+            } else {
+                return Err(meta.error("unknown marc attribute, expected `tag`, `subfield`, `ind1` or `ind2`"));
+            }
+            Ok(())
+        })?;
+    }
+
+    let tag = tag.ok_or_else(|| syn::Error::new_spanned(&ident, "missing #[marc(tag = \"...\")]"))?;
+    let (multiplicity, inner) = match wrapped_type(&field.ty, "Option") {
+        Some(inner) => (Multiplicity::Optional, inner),
+        None => match wrapped_type(&field.ty, "Vec") {
+            Some(inner) => (Multiplicity::Repeated, inner),
+            None => (Multiplicity::Required, field.ty.clone()),
+        },
+    };
+
+    Ok(Mapping {
+        ident,
+        inner,
+        multiplicity,
+        tag,
+        subfield,
+        ind1,
+        ind2,
+    })
+}
+
+/// `T` if `ty` is `wrapper<T>`
+fn wrapped_type(ty: &Type, wrapper: &str) -> Option<Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != wrapper {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        GenericArgument::Type(inner) => Some(inner.clone()),
+        _ => None,
+    }
+}
+
+fn read_field(mapping: &Mapping) -> TokenStream2 {
+    let Mapping { ident, inner, tag, .. } = mapping;
+    let (values, location) = match mapping.subfield {
+        Some(code) => (
+            quote! { ::marc_rs::mapping::subfield_values(record, #tag, #code) },
+            format!("{}${}", tag, code),
+        ),
+        None => (
+            quote! { ::marc_rs::mapping::control_value(record, #tag).into_iter().collect::<::std::vec::Vec<&str>>() },
+            tag.clone(),
+        ),
+    };
+    let parse = quote! { ::marc_rs::mapping::parse_value::<#inner>(value, #location) };
+
+    match mapping.multiplicity {
+        Multiplicity::Required => {
+            let missing = format!("missing {}", location);
+            quote! {
+                let #ident = match #values.first() {
+                    ::std::option::Option::Some(value) => #parse?,
+                    ::std::option::Option::None => return ::std::result::Result::Err(::std::string::String::from(#missing)),
+                };
+            }
+        }
+        Multiplicity::Optional => quote! {
+            let #ident = match #values.first() {
+                ::std::option::Option::Some(value) => ::std::option::Option::Some(#parse?),
+                ::std::option::Option::None => ::std::option::Option::None,
+            };
+        },
+        Multiplicity::Repeated => quote! {
+            let #ident = #values.into_iter().map(|value| #parse).collect::<::std::result::Result<::std::vec::Vec<#inner>, ::std::string::String>>()?;
+        },
+    }
+}
+
+fn write_field(mapping: &Mapping) -> TokenStream2 {
+    let Mapping { ident, tag, ind1, ind2, .. } = mapping;
+    let push = match (mapping.subfield, &mapping.multiplicity) {
+        (None, _) => quote! { ::marc_rs::mapping::push_control(&mut record, #tag, value.to_string()); },
+        (Some(code), Multiplicity::Repeated) => quote! { ::marc_rs::mapping::push_field(&mut record, #tag, #ind1, #ind2, #code, value.to_string()); },
+        (Some(code), _) => quote! { ::marc_rs::mapping::push_subfield(&mut record, #tag, #ind1, #ind2, #code, value.to_string()); },
+    };
+
+    match mapping.multiplicity {
+        Multiplicity::Required => quote! {
+            {
+                let value = &self.#ident;
+                #push
+            }
+        },
+        Multiplicity::Optional => quote! {
+            if let ::std::option::Option::Some(value) = &self.#ident {
+                #push
+            }
+        },
+        Multiplicity::Repeated => quote! {
+            for value in &self.#ident {
+                #push
+            }
+        },
+    }
+}
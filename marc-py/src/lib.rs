@@ -0,0 +1,583 @@
+//! Python bindings for marc-rs, with an API close to pymarc's.
+//!
+//! ```python
+//! import marc_rs
+//!
+//! for record in marc_rs.Reader("records.mrc"):
+//!     print(record.title, record.isbn)
+//!     for field in record.get_fields("650"):
+//!         field.delete_subfield("x")
+//!     record.add_ordered_field(marc_rs.Field("590", [" ", " "], [("a", "Checked")]))
+//!
+//! xml = marc_rs.convert(open("records.mrc", "rb").read(), "marc21", "xml")
+//! ```
+//!
+//! Records and fields are plain Python objects: fields returned by a record
+//! are shared with it, so editing them edits the record. They are converted to
+//! marc-rs records when written, which is when leaders and indicators are checked.
+
+use marc_rs as marc;
+use marc_rs::{Encoding, FormatEncoding, MarcFormat};
+use pyo3::exceptions::{PyIOError, PyKeyError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use std::fs::File;
+use std::io::BufReader;
+
+/// Leader of new records: a book, in UTF-8
+const DEFAULT_LEADER: &str = "00000nam a2200000 a 4500";
+
+/// Format read or written by the bindings
+#[derive(Clone, Copy)]
+enum Format {
+    Marc(MarcFormat),
+    Mrk,
+    Json,
+}
+
+impl Format {
+    fn parse(name: &str) -> PyResult<Self> {
+        match name.to_lowercase().as_str() {
+            "marc21" | "marc" | "usmarc" => Ok(Format::Marc(MarcFormat::Marc21)),
+            "unimarc" => Ok(Format::Marc(MarcFormat::Unimarc)),
+            "xml" | "marcxml" => Ok(Format::Marc(MarcFormat::MarcXml)),
+            "mrk" => Ok(Format::Mrk),
+            "json" | "jsonl" => Ok(Format::Json),
+            _ => Err(PyValueError::new_err(format!("Unknown format {:?}. Use: marc21, unimarc, xml, mrk or json", name))),
+        }
+    }
+}
+
+/// Encoding of binary records: the one given, or UTF-8 except for MARC21 whose leader/09 is not `a`
+fn format_encoding(format: MarcFormat, encoding: Option<&str>, data: &[u8]) -> PyResult<FormatEncoding> {
+    let encoding = match encoding {
+        Some(name) => parse_encoding(name)?,
+        None if format == MarcFormat::Marc21 && data.get(9) != Some(&b'a') => Encoding::Marc8,
+        None => Encoding::Utf8,
+    };
+    Ok(FormatEncoding::new(format, encoding))
+}
+
+fn parse_encoding(name: &str) -> PyResult<Encoding> {
+    let encoding = Encoding::from(name);
+    // Encoding::from falls back to UTF-8 for unknown names
+    if encoding == Encoding::Utf8 && !["utf8", "utf-8"].contains(&name.to_lowercase().as_str()) {
+        return Err(PyValueError::new_err(format!("Unknown encoding {:?}", name)));
+    }
+    Ok(encoding)
+}
+
+fn value_error(e: impl std::fmt::Display) -> PyErr {
+    PyValueError::new_err(e.to_string())
+}
+
+fn single_char(name: &str, value: &str) -> PyResult<char> {
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => Err(PyValueError::new_err(format!("{} must be a single character, got {:?}", name, value))),
+    }
+}
+
+/// A control field (tags below 010, with `data`) or a data field (with indicators and subfields)
+#[pyclass(module = "marc_rs")]
+#[derive(Clone)]
+pub struct Field {
+    /// Tag, e.g. "245"
+    #[pyo3(get, set)]
+    tag: String,
+    /// Value of a control field, None for data fields
+    #[pyo3(get, set)]
+    data: Option<String>,
+    ind1: char,
+    ind2: char,
+    subfields: Vec<(char, String)>,
+}
+
+impl Field {
+    fn from_control(field: &marc::ControlField) -> Self {
+        Self {
+            tag: field.tag.clone(),
+            data: Some(field.value.clone()),
+            ind1: ' ',
+            ind2: ' ',
+            subfields: Vec::new(),
+        }
+    }
+
+    fn from_data(field: &marc::DataField) -> Self {
+        Self {
+            tag: field.tag.clone(),
+            data: None,
+            ind1: field.ind1,
+            ind2: field.ind2,
+            subfields: field.subfields.iter().map(|s| (s.code, s.value.clone())).collect(),
+        }
+    }
+
+    fn to_data_field(&self) -> marc::DataField {
+        marc::DataField {
+            tag: self.tag.clone(),
+            ind1: self.ind1,
+            ind2: self.ind2,
+            subfields: self.subfields.iter().map(|(code, value)| marc::Subfield { code: *code, value: value.clone() }).collect(),
+        }
+    }
+}
+
+#[pymethods]
+impl Field {
+    /// `Field("245", ["1", "0"], [("a", "Title")])`, or `Field("001", data="12345")`
+    #[new]
+    #[pyo3(signature = (tag, indicators = None, subfields = None, data = None))]
+    fn new(tag: String, indicators: Option<Vec<String>>, subfields: Option<Vec<(String, String)>>, data: Option<String>) -> PyResult<Self> {
+        let mut field = Self {
+            tag,
+            data,
+            ind1: ' ',
+            ind2: ' ',
+            subfields: Vec::new(),
+        };
+        if let Some(indicators) = indicators {
+            field.set_indicators(indicators)?;
+        }
+        if let Some(subfields) = subfields {
+            field.set_subfields(subfields)?;
+        }
+        Ok(field)
+    }
+
+    #[getter]
+    fn indicator1(&self) -> String {
+        self.ind1.to_string()
+    }
+
+    #[setter]
+    fn set_indicator1(&mut self, value: &str) -> PyResult<()> {
+        self.ind1 = single_char("indicator", value)?;
+        Ok(())
+    }
+
+    #[getter]
+    fn indicator2(&self) -> String {
+        self.ind2.to_string()
+    }
+
+    #[setter]
+    fn set_indicator2(&mut self, value: &str) -> PyResult<()> {
+        self.ind2 = single_char("indicator", value)?;
+        Ok(())
+    }
+
+    /// Both indicators, e.g. `["1", "0"]`
+    #[getter]
+    fn indicators(&self) -> Vec<String> {
+        vec![self.ind1.to_string(), self.ind2.to_string()]
+    }
+
+    #[setter]
+    fn set_indicators(&mut self, indicators: Vec<String>) -> PyResult<()> {
+        match indicators.as_slice() {
+            [ind1, ind2] => {
+                self.ind1 = single_char("indicator", ind1)?;
+                self.ind2 = single_char("indicator", ind2)?;
+                Ok(())
+            }
+            _ => Err(PyValueError::new_err("a field has two indicators")),
+        }
+    }
+
+    /// Subfields as `(code, value)` pairs; a copy, to change with the methods or by assigning a new list
+    #[getter(subfields)]
+    fn subfield_pairs(&self) -> Vec<(String, String)> {
+        self.subfields.iter().map(|(code, value)| (code.to_string(), value.clone())).collect()
+    }
+
+    #[setter]
+    fn set_subfields(&mut self, subfields: Vec<(String, String)>) -> PyResult<()> {
+        self.subfields = subfields.into_iter().map(|(code, value)| Ok((single_char("subfield code", &code)?, value))).collect::<PyResult<_>>()?;
+        Ok(())
+    }
+
+    fn is_control_field(&self) -> bool {
+        self.data.is_some()
+    }
+
+    /// Value of the first subfield with a code; KeyError when there is none
+    fn __getitem__(&self, code: &str) -> PyResult<String> {
+        self.get(code).ok_or_else(|| PyKeyError::new_err(code.to_string()))
+    }
+
+    /// Set the value of the first subfield with a code
+    fn __setitem__(&mut self, code: &str, value: String) -> PyResult<()> {
+        let code = single_char("subfield code", code)?;
+        match self.subfields.iter_mut().find(|(c, _)| *c == code) {
+            Some(subfield) => subfield.1 = value,
+            None => return Err(PyKeyError::new_err(code.to_string())),
+        }
+        Ok(())
+    }
+
+    fn __contains__(&self, code: &str) -> bool {
+        self.get(code).is_some()
+    }
+
+    /// Value of the first subfield with a code, or None
+    fn get(&self, code: &str) -> Option<String> {
+        self.subfields.iter().find(|(c, _)| code.chars().eq(std::iter::once(*c))).map(|(_, value)| value.clone())
+    }
+
+    /// Values of the subfields with any of the codes, in field order
+    #[pyo3(signature = (*codes))]
+    fn get_subfields(&self, codes: Vec<String>) -> Vec<String> {
+        self.subfields.iter().filter(|(c, _)| codes.iter().any(|code| code.chars().eq(std::iter::once(*c)))).map(|(_, value)| value.clone()).collect()
+    }
+
+    /// Add a subfield, at the end or at position `pos`
+    #[pyo3(signature = (code, value, pos = None))]
+    fn add_subfield(&mut self, code: &str, value: String, pos: Option<usize>) -> PyResult<()> {
+        let subfield = (single_char("subfield code", code)?, value);
+        match pos {
+            Some(pos) => self.subfields.insert(pos.min(self.subfields.len()), subfield),
+            None => self.subfields.push(subfield),
+        }
+        Ok(())
+    }
+
+    /// Delete the first subfield with a code, returning its value
+    fn delete_subfield(&mut self, code: &str) -> PyResult<Option<String>> {
+        let code = single_char("subfield code", code)?;
+        Ok(self.subfields.iter().position(|(c, _)| *c == code).map(|i| self.subfields.remove(i).1))
+    }
+
+    /// Control field data, or the subfield values joined with spaces
+    fn value(&self) -> String {
+        match &self.data {
+            Some(data) => data.clone(),
+            None => self.subfields.iter().map(|(_, value)| value.as_str()).collect::<Vec<_>>().join(" "),
+        }
+    }
+
+    /// MarcMaker line of the field, e.g. `=245  10$aTitle`
+    fn __str__(&self) -> String {
+        let record = marc::Record {
+            leader: marc::Leader::from_bytes(DEFAULT_LEADER.as_bytes()).expect("valid default leader"),
+            control_fields: self.data.iter().map(|data| marc::ControlField { tag: self.tag.clone(), value: data.clone() }).collect(),
+            data_fields: if self.data.is_some() { Vec::new() } else { vec![self.to_data_field()] },
+        };
+        record.to_mrk().lines().nth(1).unwrap_or_default().to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<Field {}>", self.__str__())
+    }
+
+    fn __eq__(&self, other: &Field) -> bool {
+        (&self.tag, &self.data, self.ind1, self.ind2, &self.subfields) == (&other.tag, &other.data, other.ind1, other.ind2, &other.subfields)
+    }
+}
+
+/// A record: a leader and a list of fields
+#[pyclass(module = "marc_rs")]
+pub struct Record {
+    leader: String,
+    fields: Vec<Py<Field>>,
+}
+
+impl Record {
+    fn from_record(py: Python<'_>, record: &marc::Record) -> PyResult<Self> {
+        let control = record.control_fields.iter().map(Field::from_control);
+        let data = record.data_fields.iter().map(Field::from_data);
+        Ok(Self {
+            leader: String::from_utf8_lossy(&record.leader.to_bytes()).into_owned(),
+            fields: control.chain(data).map(|field| Py::new(py, field)).collect::<PyResult<_>>()?,
+        })
+    }
+
+    /// The marc-rs record, with control fields before data fields
+    fn to_record(&self, py: Python<'_>) -> PyResult<marc::Record> {
+        let mut record = marc::Record {
+            leader: marc::Leader::from_bytes(self.leader.as_bytes()).map_err(value_error)?,
+            control_fields: Vec::new(),
+            data_fields: Vec::new(),
+        };
+        for field in &self.fields {
+            let field = field.borrow(py);
+            match &field.data {
+                Some(data) => record.control_fields.push(marc::ControlField { tag: field.tag.clone(), value: data.clone() }),
+                None => record.data_fields.push(field.to_data_field()),
+            }
+        }
+        Ok(record)
+    }
+
+    fn fields_with_tags(&self, py: Python<'_>, tags: &[String]) -> Vec<Py<Field>> {
+        self.fields.iter().filter(|f| tags.is_empty() || tags.contains(&f.borrow(py).tag)).map(|f| f.clone_ref(py)).collect()
+    }
+}
+
+#[pymethods]
+impl Record {
+    #[new]
+    #[pyo3(signature = (leader = None))]
+    fn new(leader: Option<String>) -> PyResult<Self> {
+        let mut record = Self {
+            leader: DEFAULT_LEADER.to_string(),
+            fields: Vec::new(),
+        };
+        if let Some(leader) = leader {
+            record.set_leader(leader)?;
+        }
+        Ok(record)
+    }
+
+    /// Leader, 24 characters
+    #[getter]
+    fn leader(&self) -> String {
+        self.leader.clone()
+    }
+
+    #[setter]
+    fn set_leader(&mut self, leader: String) -> PyResult<()> {
+        marc::Leader::from_bytes(leader.as_bytes()).map_err(value_error)?;
+        self.leader = leader;
+        Ok(())
+    }
+
+    /// Fields of the record, shared with it
+    #[getter(fields)]
+    fn field_list(&self, py: Python<'_>) -> Vec<Py<Field>> {
+        self.fields_with_tags(py, &[])
+    }
+
+    #[setter]
+    fn set_fields(&mut self, fields: Vec<Py<Field>>) {
+        self.fields = fields;
+    }
+
+    /// First field with a tag; KeyError when there is none
+    fn __getitem__(&self, py: Python<'_>, tag: &str) -> PyResult<Py<Field>> {
+        self.get(py, tag).ok_or_else(|| PyKeyError::new_err(tag.to_string()))
+    }
+
+    fn __contains__(&self, py: Python<'_>, tag: &str) -> bool {
+        self.get(py, tag).is_some()
+    }
+
+    fn __len__(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// First field with a tag, or None
+    fn get(&self, py: Python<'_>, tag: &str) -> Option<Py<Field>> {
+        self.fields.iter().find(|f| f.borrow(py).tag == tag).map(|f| f.clone_ref(py))
+    }
+
+    /// Fields with any of the tags, or all fields without tags
+    #[pyo3(signature = (*tags))]
+    fn get_fields(&self, py: Python<'_>, tags: Vec<String>) -> Vec<Py<Field>> {
+        self.fields_with_tags(py, &tags)
+    }
+
+    /// Add fields at the end
+    #[pyo3(signature = (*fields))]
+    fn add_field(&mut self, fields: Vec<Py<Field>>) {
+        self.fields.extend(fields);
+    }
+
+    /// Add fields after the last field with a lower or equal tag
+    #[pyo3(signature = (*fields))]
+    fn add_ordered_field(&mut self, py: Python<'_>, fields: Vec<Py<Field>>) {
+        for field in fields {
+            let tag = field.borrow(py).tag.clone();
+            let at = self.fields.iter().rposition(|f| f.borrow(py).tag <= tag).map_or(0, |i| i + 1);
+            self.fields.insert(at, field);
+        }
+    }
+
+    /// Remove fields, which must be in the record
+    #[pyo3(signature = (*fields))]
+    fn remove_field(&mut self, fields: Vec<Py<Field>>) -> PyResult<()> {
+        for field in fields {
+            let i = self.fields.iter().position(|f| f.is(&field)).ok_or_else(|| PyValueError::new_err("field not in the record"))?;
+            self.fields.remove(i);
+        }
+        Ok(())
+    }
+
+    /// Remove all fields with any of the tags
+    #[pyo3(signature = (*tags))]
+    fn remove_fields(&mut self, py: Python<'_>, tags: Vec<String>) {
+        self.fields.retain(|f| !tags.contains(&f.borrow(py).tag));
+    }
+
+    /// Title: 245 `$a` and `$b`
+    #[getter]
+    fn title(&self, py: Python<'_>) -> Option<String> {
+        let field = self.get(py, "245")?;
+        let title = field.borrow(py).get_subfields(vec!["a".to_string(), "b".to_string()]).join(" ");
+        Some(title).filter(|t| !t.is_empty())
+    }
+
+    /// Main entry: 100, 110 or 111 `$a`
+    #[getter]
+    fn author(&self, py: Python<'_>) -> Option<String> {
+        self.fields.iter().map(|f| f.borrow(py)).find(|f| ["100", "110", "111"].contains(&f.tag.as_str())).and_then(|f| f.get("a"))
+    }
+
+    /// First valid ISBN of 020 `$a`, as 13 digits
+    #[getter]
+    fn isbn(&self, py: Python<'_>) -> PyResult<Option<String>> {
+        Ok(self.to_record(py)?.isbns(MarcFormat::Marc21).first().map(|isbn| isbn.as_str().to_string()))
+    }
+
+    /// First valid ISSN of 022 `$a`, e.g. "0317-8471"
+    #[getter]
+    fn issn(&self, py: Python<'_>) -> PyResult<Option<String>> {
+        Ok(self.to_record(py)?.issns(MarcFormat::Marc21).first().map(|issn| issn.to_string()))
+    }
+
+    /// Earliest year of publication, from 008 or 260/264 `$c`
+    #[getter]
+    fn pubyear(&self, py: Python<'_>) -> PyResult<Option<i32>> {
+        Ok(self.to_record(py)?.dates(MarcFormat::Marc21).year())
+    }
+
+    /// ISO 2709 bytes, in UTF-8 unless an encoding is given
+    #[pyo3(signature = (format = "marc21", encoding = "utf8"))]
+    fn as_marc<'py>(&self, py: Python<'py>, format: &str, encoding: &str) -> PyResult<Bound<'py, PyBytes>> {
+        Ok(PyBytes::new(py, &write_records(&[self.to_record(py)?], format, Some(encoding))?))
+    }
+
+    /// MARC XML of the record, as a `<record>` inside a `<collection>`
+    fn as_xml(&self, py: Python<'_>) -> PyResult<String> {
+        let bytes = write_records(&[self.to_record(py)?], "xml", None)?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    /// MARC-in-JSON text of the record
+    fn as_json(&self, py: Python<'_>) -> PyResult<String> {
+        serde_json::to_string(&marc::MarcJson(self.to_record(py)?)).map_err(value_error)
+    }
+
+    /// MarcMaker text of the record
+    fn __str__(&self, py: Python<'_>) -> PyResult<String> {
+        Ok(self.to_record(py)?.to_mrk())
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> String {
+        let id = self.get(py, "001").and_then(|f| f.borrow(py).data.clone());
+        format!("<Record {}>", id.unwrap_or_default())
+    }
+}
+
+/// Python record of a marc-rs record
+fn py_record(py: Python<'_>, record: &marc::Record) -> PyResult<Py<Record>> {
+    Py::new(py, Record::from_record(py, record)?)
+}
+
+/// Records of a file, read one at a time like pymarc's MARCReader
+#[pyclass(module = "marc_rs", unsendable)]
+pub struct Reader {
+    records: Box<dyn Iterator<Item = Result<marc::Record, String>>>,
+}
+
+#[pymethods]
+impl Reader {
+    /// Open a file in a format (default: marc21) and encoding (default: from the leader)
+    #[new]
+    #[pyo3(signature = (path, format = "marc21", encoding = None))]
+    fn new(path: std::path::PathBuf, format: &str, encoding: Option<&str>) -> PyResult<Self> {
+        let format = Format::parse(format)?;
+        let mut file = BufReader::new(File::open(&path).map_err(|e| PyIOError::new_err(format!("Cannot read {}: {}", path.display(), e)))?);
+        let records: Box<dyn Iterator<Item = Result<marc::Record, String>>> = match format {
+            Format::Marc(format) => {
+                let head = std::io::BufRead::fill_buf(&mut file).map_err(|e| PyIOError::new_err(e.to_string()))?.to_vec();
+                let format_encoding = format_encoding(format, encoding, &head)?;
+                Box::new(marc::RecordReader::new(file, format_encoding).map(|r| r.map_err(|e| e.to_string())))
+            }
+            Format::Mrk => Box::new(marc::MrkReader::new(file).map(|r| r.map_err(|e| e.to_string()))),
+            Format::Json => Box::new(marc::JsonLinesReader::new(file).map(|r| r.map_err(|e| e.to_string()))),
+        };
+        Ok(Self { records })
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<Py<Record>>> {
+        match self.records.next() {
+            Some(Ok(record)) => Ok(Some(py_record(py, &record)?)),
+            Some(Err(e)) => Err(PyValueError::new_err(e)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Records of a format from bytes or text
+fn parse_records(data: &[u8], format: &str, encoding: Option<&str>) -> PyResult<Vec<marc::Record>> {
+    match Format::parse(format)? {
+        Format::Marc(format) => marc::parse(data, format_encoding(format, encoding, data)?).map_err(value_error),
+        Format::Mrk => marc::parse_mrk(std::str::from_utf8(data).map_err(value_error)?).map_err(value_error),
+        Format::Json => marc::JsonLinesReader::new(data).collect::<Result<_, _>>().map_err(value_error),
+    }
+}
+
+/// Bytes of records in a format
+fn write_records(records: &[marc::Record], format: &str, encoding: Option<&str>) -> PyResult<Vec<u8>> {
+    let mut output = Vec::new();
+    match Format::parse(format)? {
+        Format::Marc(format) => {
+            let encoding = encoding.map(parse_encoding).transpose()?.unwrap_or(Encoding::Utf8);
+            marc::write(records, FormatEncoding::new(format, encoding), &mut output).map_err(value_error)?;
+        }
+        Format::Mrk => marc::write_mrk(records, &mut output).map_err(value_error)?,
+        Format::Json => {
+            for record in records {
+                serde_json::to_writer(&mut output, &marc::MarcJson(record)).map_err(value_error)?;
+                output.push(b'\n');
+            }
+        }
+    }
+    Ok(output)
+}
+
+/// Parse records: `format` is marc21, unimarc, xml, mrk or json (MARC-in-JSON lines)
+///
+/// The encoding of MARC21 defaults to UTF-8 when leader/09 is `a`, MARC-8 otherwise.
+#[pyfunction]
+#[pyo3(signature = (data, format = "marc21", encoding = None))]
+fn parse(py: Python<'_>, data: &[u8], format: &str, encoding: Option<&str>) -> PyResult<Vec<Py<Record>>> {
+    let records = py.allow_threads(|| parse_records(data, format, encoding))?;
+    records.iter().map(|record| py_record(py, record)).collect()
+}
+
+/// Write records to bytes, in UTF-8 unless an encoding is given
+#[pyfunction]
+#[pyo3(signature = (records, format = "marc21", encoding = None))]
+fn write<'py>(py: Python<'py>, records: Vec<Py<Record>>, format: &str, encoding: Option<&str>) -> PyResult<Bound<'py, PyBytes>> {
+    let records = records.iter().map(|record| record.borrow(py).to_record(py)).collect::<PyResult<Vec<_>>>()?;
+    let bytes = py.allow_threads(|| write_records(&records, format, encoding))?;
+    Ok(PyBytes::new(py, &bytes))
+}
+
+/// Convert records between formats without creating Python objects
+#[pyfunction]
+#[pyo3(signature = (data, from_format, to_format, from_encoding = None, to_encoding = None))]
+fn convert<'py>(py: Python<'py>, data: &[u8], from_format: &str, to_format: &str, from_encoding: Option<&str>, to_encoding: Option<&str>) -> PyResult<Bound<'py, PyBytes>> {
+    let bytes = py.allow_threads(|| write_records(&parse_records(data, from_format, from_encoding)?, to_format, to_encoding))?;
+    Ok(PyBytes::new(py, &bytes))
+}
+
+#[pymodule]
+#[pyo3(name = "marc_rs")]
+fn marc_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Field>()?;
+    m.add_class::<Record>()?;
+    m.add_class::<Reader>()?;
+    m.add_function(wrap_pyfunction!(parse, m)?)?;
+    m.add_function(wrap_pyfunction!(write, m)?)?;
+    m.add_function(wrap_pyfunction!(convert, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,100 @@
+"""Tests of the Python bindings, run after `maturin develop` with `python -m unittest discover tests`."""
+
+import os
+import tempfile
+import unittest
+
+import marc_rs
+
+MRK = """=LDR  00000nam a2200000 a 4500
+=001  1
+=008  000000s1954\\\\\\\\enk|||||||||||||||||eng\\d
+=020  \\\\$a0-261-10221-4 (pbk.)
+=100  1\\$aTolkien, J. R. R.
+=245  14$aThe hobbit :$bor, There and back again /$cTolkien.
+=650  \\0$aHobbits$xFiction.
+
+=LDR  00000nam a2200000 a 4500
+=001  2
+=245  00$aSecond
+"""
+
+
+class TestRecord(unittest.TestCase):
+    def setUp(self):
+        self.records = marc_rs.parse(MRK.encode(), "mrk")
+
+    def test_accessors(self):
+        record = self.records[0]
+        self.assertEqual(len(self.records), 2)
+        self.assertEqual(record.leader, "00000nam a2200000 a 4500")
+        self.assertEqual(record["001"].data, "1")
+        self.assertEqual(record["245"]["a"], "The hobbit :")
+        self.assertEqual(record["245"].indicators, ["1", "4"])
+        self.assertEqual(record.title, "The hobbit : or, There and back again /")
+        self.assertEqual(record.author, "Tolkien, J. R. R.")
+        self.assertEqual(record.isbn, "9780261102217")
+        self.assertEqual(record.pubyear, 1954)
+        self.assertIsNone(self.records[1].isbn)
+        self.assertNotIn("700", record)
+        with self.assertRaises(KeyError):
+            record["700"]
+        self.assertEqual(str(record["650"]), "=650  \\0$aHobbits$xFiction.")
+
+    def test_edit(self):
+        record = self.records[0]
+        # Fields are shared with the record
+        for field in record.get_fields("650"):
+            self.assertEqual(field.delete_subfield("x"), "Fiction.")
+        record["245"]["a"] = "The Hobbit"
+        record.add_ordered_field(marc_rs.Field("500", [" ", " "], [("a", "Note.")]), marc_rs.Field("003", data="OCoLC"))
+        record.remove_fields("020")
+        self.assertEqual([f.tag for f in record.fields], ["001", "003", "008", "100", "245", "500", "650"])
+        self.assertEqual(record["650"].value(), "Hobbits")
+        self.assertEqual(record.get_fields("245")[0].get_subfields("a", "c"), ["The Hobbit", "Tolkien."])
+
+        field = record["500"]
+        record.remove_field(field)
+        with self.assertRaises(ValueError):
+            record.remove_field(field)
+        with self.assertRaises(ValueError):
+            field.indicators = ["1"]
+        with self.assertRaises(ValueError):
+            record.leader = "short"
+
+    def test_write(self):
+        data = marc_rs.write(self.records)
+        self.assertEqual(data[9:10], b"a")
+        again = marc_rs.parse(data)
+        # Same fields, the leader now holding the record lengths
+        self.assertEqual([[str(f) for f in r.fields] for r in again], [[str(f) for f in r.fields] for r in self.records])
+        self.assertEqual(again[1].leader, "00063nam a2200049 a 4500")
+        self.assertIn("<subfield code=\"a\">Second</subfield>", self.records[1].as_xml())
+        self.assertIn('"001":"2"', self.records[1].as_json())
+        self.assertEqual(marc_rs.parse(self.records[1].as_marc())[0]["001"].data, "2")
+
+    def test_convert(self):
+        xml = marc_rs.convert(MRK.encode(), "mrk", "xml")
+        records = marc_rs.parse(xml, "xml")
+        self.assertEqual(records[1]["245"]["a"], "Second")
+        jsonl = marc_rs.convert(xml, "xml", "json")
+        self.assertEqual(len(jsonl.splitlines()), 2)
+        with self.assertRaises(ValueError):
+            marc_rs.convert(xml, "xml", "pdf")
+
+    def test_reader(self):
+        with tempfile.TemporaryDirectory() as directory:
+            path = os.path.join(directory, "records.mrc")
+            with open(path, "wb") as file:
+                file.write(marc_rs.write(self.records))
+            self.assertEqual([r["001"].data for r in marc_rs.Reader(path)], ["1", "2"])
+            with open(path, "ab") as file:
+                file.write(b"00050nam")
+            reader = marc_rs.Reader(path)
+            self.assertEqual(len([next(reader), next(reader)]), 2)
+            with self.assertRaises(ValueError):
+                next(reader)
+
+
+if __name__ == "__main__":
+    unittest.main()
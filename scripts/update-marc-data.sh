@@ -0,0 +1,34 @@
+#!/bin/sh
+# Refresh the vendored format data in data/ from its published source, then
+# rebuild: build.rs generates the field, subfield and indicator tables from it.
+#
+#   data/marc21_bibliographic.txt  MARC 21 bibliographic field list, Library of Congress
+#   data/gacs.xml                  MARC Code List for Geographic Areas, Library of Congress
+#
+# UNIMARC has no machine-readable field list: data/unimarc_bibliographic.txt is
+# maintained by hand from the IFLA UNIMARC manual, in the same layout.
+set -eu
+cd "$(dirname "$0")/.."
+
+MARC21_FIELD_LIST=https://www.loc.gov/marc/bibliographic/ecbdlist.html
+GEOGRAPHIC_AREAS=https://www.loc.gov/standards/codelists/gacs.xml
+
+# The field list is the preformatted text of the page
+curl -fsSL "$MARC21_FIELD_LIST" |
+    sed -n '/<pre/,/<\/pre>/p' |
+    sed -e 's/<[^>]*>//g' -e 's/&lt;/</g' -e 's/&gt;/>/g' -e 's/&quot;/"/g' -e "s/&#39;/'/g" -e 's/&nbsp;/ /g' -e 's/&amp;/\&/g' |
+    tr -d '\r' >data/marc21_bibliographic.txt.new
+if ! grep -q '^245 - TITLE STATEMENT' data/marc21_bibliographic.txt.new; then
+    echo "Unexpected layout of $MARC21_FIELD_LIST, data/marc21_bibliographic.txt left unchanged" >&2
+    rm data/marc21_bibliographic.txt.new
+    exit 1
+fi
+mv data/marc21_bibliographic.txt.new data/marc21_bibliographic.txt
+
+curl -fsSL "$GEOGRAPHIC_AREAS" >data/gacs.xml.new
+if ! grep -q '<gac' data/gacs.xml.new; then
+    echo "Unexpected layout of $GEOGRAPHIC_AREAS, data/gacs.xml left unchanged" >&2
+    rm data/gacs.xml.new
+    exit 1
+fi
+mv data/gacs.xml.new data/gacs.xml
@@ -0,0 +1,347 @@
+//! Authority identifiers of headings: `$0` (authority record) and `$1` (real world object).
+//!
+//! Identifiers come as URIs (`http://id.loc.gov/authorities/names/n79021164`)
+//! or as control numbers with a source prefix (`(DLC)n  79021164`).
+//! [`AuthorityId::parse`] recognizes both for the common sources, so they can
+//! be compared, validated and rewritten as canonical URIs.
+//!
+//! ```
+//! use marc_rs::{AuthorityId, AuthoritySource};
+//!
+//! let id = AuthorityId::parse("(DLC)n  79021164").unwrap();
+//! assert_eq!(id.source, Some(AuthoritySource::Lcnaf));
+//! assert_eq!(id.id, "n79021164");
+//! assert_eq!(id.uri().unwrap(), "http://id.loc.gov/authorities/names/n79021164");
+//! assert!(id.is_valid());
+//! ```
+
+use crate::record::{DataField, Record, Subfield};
+
+/// Source of an authority identifier
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthoritySource {
+    /// Library of Congress Name Authority File
+    Lcnaf,
+    /// Library of Congress Subject Headings
+    Lcsh,
+    /// Library of Congress Genre/Form Terms
+    Lcgft,
+    /// Faceted Application of Subject Terminology
+    Fast,
+    /// Virtual International Authority File
+    Viaf,
+    /// Wikidata
+    Wikidata,
+    /// International Standard Name Identifier
+    Isni,
+    /// Open Researcher and Contributor ID
+    Orcid,
+    /// Gemeinsame Normdatei (German National Library)
+    Gnd,
+    /// IdRef (French higher education union catalogue)
+    Idref,
+}
+
+impl AuthoritySource {
+    /// All sources, in declaration order
+    pub const ALL: &'static [AuthoritySource] = &[
+        AuthoritySource::Lcnaf,
+        AuthoritySource::Lcsh,
+        AuthoritySource::Lcgft,
+        AuthoritySource::Fast,
+        AuthoritySource::Viaf,
+        AuthoritySource::Wikidata,
+        AuthoritySource::Isni,
+        AuthoritySource::Orcid,
+        AuthoritySource::Gnd,
+        AuthoritySource::Idref,
+    ];
+
+    /// Short name of the source, e.g. `lcnaf`
+    pub fn name(&self) -> &'static str {
+        match self {
+            AuthoritySource::Lcnaf => "lcnaf",
+            AuthoritySource::Lcsh => "lcsh",
+            AuthoritySource::Lcgft => "lcgft",
+            AuthoritySource::Fast => "fast",
+            AuthoritySource::Viaf => "viaf",
+            AuthoritySource::Wikidata => "wikidata",
+            AuthoritySource::Isni => "isni",
+            AuthoritySource::Orcid => "orcid",
+            AuthoritySource::Gnd => "gnd",
+            AuthoritySource::Idref => "idref",
+        }
+    }
+
+    /// Get the source from its short name
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|source| source.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Canonical URI of an identifier of this source
+    pub fn uri(&self, id: &str) -> String {
+        match self {
+            AuthoritySource::Lcnaf => format!("http://id.loc.gov/authorities/names/{}", id),
+            AuthoritySource::Lcsh => format!("http://id.loc.gov/authorities/subjects/{}", id),
+            AuthoritySource::Lcgft => format!("http://id.loc.gov/authorities/genreForms/{}", id),
+            AuthoritySource::Fast => format!("http://id.worldcat.org/fast/{}", id.trim_start_matches("fst").trim_start_matches('0')),
+            AuthoritySource::Viaf => format!("http://viaf.org/viaf/{}", id),
+            AuthoritySource::Wikidata => format!("http://www.wikidata.org/entity/{}", id),
+            AuthoritySource::Isni => format!("https://isni.org/isni/{}", id),
+            AuthoritySource::Orcid => format!("https://orcid.org/{}", id),
+            AuthoritySource::Gnd => format!("https://d-nb.info/gnd/{}", id),
+            AuthoritySource::Idref => format!("https://www.idref.fr/{}", id),
+        }
+    }
+
+    /// Whether an identifier has the syntax of this source, with a valid check character where it has one
+    pub fn is_valid_id(&self, id: &str) -> bool {
+        let digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+        match self {
+            AuthoritySource::Lcnaf | AuthoritySource::Lcsh | AuthoritySource::Lcgft => {
+                let prefix = id.bytes().take_while(|b| b.is_ascii_lowercase()).count();
+                let expected = match self {
+                    AuthoritySource::Lcnaf => id.starts_with('n'),
+                    AuthoritySource::Lcsh => id.starts_with("sh") || id.starts_with("sj") || id.starts_with("sp"),
+                    _ => id.starts_with("gf"),
+                };
+                expected && prefix <= 3 && matches!(id.len() - prefix, 8 | 10) && digits(&id[prefix..])
+            }
+            AuthoritySource::Fast => digits(id.strip_prefix("fst").unwrap_or(id)),
+            AuthoritySource::Viaf => digits(id) && id.len() <= 22,
+            AuthoritySource::Wikidata => id.len() > 1 && matches!(id.as_bytes()[0], b'Q' | b'P' | b'L') && digits(&id[1..]) && !id[1..].starts_with('0'),
+            AuthoritySource::Isni => id.len() == 16 && iso7064_mod11_2(id),
+            AuthoritySource::Orcid => {
+                let compact: String = id.split('-').collect();
+                id.len() == 19 && id.split('-').all(|group| group.len() == 4) && iso7064_mod11_2(&compact)
+            }
+            AuthoritySource::Gnd => {
+                let (number, check) = id.split_once('-').unwrap_or((id, ""));
+                let check_ok = check.is_empty() || (check.len() == 1 && (digits(check) || check == "X"));
+                digits(number.trim_end_matches('X')) && check_ok
+            }
+            AuthoritySource::Idref => id.len() == 9 && id.is_ascii() && digits(&id[..8]) && (digits(&id[8..]) || &id[8..] == "X"),
+        }
+    }
+}
+
+/// Check character of ISNI and ORCID identifiers: 15 digits and a digit or `X`
+fn iso7064_mod11_2(id: &str) -> bool {
+    let bytes = id.as_bytes();
+    if bytes.len() != 16 || !bytes[..15].iter().all(|b| b.is_ascii_digit()) {
+        return false;
+    }
+    let total = bytes[..15].iter().fold(0u32, |total, b| (total + (b - b'0') as u32) * 2);
+    let check = (12 - total % 11) % 11;
+    let expected = if check == 10 { b'X' } else { b'0' + check as u8 };
+    bytes[15] == expected
+}
+
+/// An authority identifier of a heading
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthorityId {
+    /// Source, when recognized
+    pub source: Option<AuthoritySource>,
+    /// Identifier within its source, e.g. `n79021164` or `Q892`; the value as found when the source is unknown
+    pub id: String,
+    /// Whether the identifier names a real world object (`$1`) rather than an authority record (`$0`)
+    pub real_world_object: bool,
+}
+
+impl AuthorityId {
+    /// An identifier of a known source, recorded in `$0`
+    pub fn new(source: AuthoritySource, id: &str) -> Self {
+        Self {
+            source: Some(source),
+            id: id.to_string(),
+            real_world_object: false,
+        }
+    }
+
+    /// Read a `$0` or `$1` value: a URI or a control number with a source prefix
+    ///
+    /// Returns `None` for an empty value. Values of unknown sources are kept
+    /// whole with no source.
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if value.is_empty() {
+            return None;
+        }
+        let (source, id) = parse_uri(value).or_else(|| parse_control_number(value)).map_or((None, value.to_string()), |(source, id)| (Some(source), id));
+        Some(Self { source, id, real_world_object: false })
+    }
+
+    /// Read the identifiers of a field, from its `$0` and `$1` subfields
+    pub fn from_field(field: &DataField) -> Vec<Self> {
+        field
+            .subfields
+            .iter()
+            .filter(|s| matches!(s.code, '0' | '1'))
+            .filter_map(|s| Some(Self { real_world_object: s.code == '1', ..Self::parse(&s.value)? }))
+            .collect()
+    }
+
+    /// Canonical URI, when the source is known
+    pub fn uri(&self) -> Option<String> {
+        self.source.map(|source| source.uri(&self.id))
+    }
+
+    /// Whether the identifier has a known source and valid syntax
+    pub fn is_valid(&self) -> bool {
+        self.source.is_some_and(|source| source.is_valid_id(&self.id))
+    }
+
+    /// The identifier as a `$0` or `$1` subfield, with its canonical URI when the source is known
+    pub fn to_subfield(&self) -> Subfield {
+        Subfield {
+            code: if self.real_world_object { '1' } else { '0' },
+            value: self.uri().unwrap_or_else(|| self.id.clone()),
+        }
+    }
+}
+
+/// Source and identifier of a URI
+fn parse_uri(value: &str) -> Option<(AuthoritySource, String)> {
+    let rest = value.strip_prefix("https://").or_else(|| value.strip_prefix("http://"))?;
+    let rest = rest.strip_prefix("www.").unwrap_or(rest);
+    let rest = rest.trim_end_matches('/');
+    let rest = rest.strip_suffix(".html").unwrap_or(rest);
+    let patterns = [
+        ("id.loc.gov/authorities/names/", AuthoritySource::Lcnaf),
+        ("id.loc.gov/authorities/subjects/", AuthoritySource::Lcsh),
+        ("id.loc.gov/authorities/genreForms/", AuthoritySource::Lcgft),
+        ("id.worldcat.org/fast/", AuthoritySource::Fast),
+        ("fast.oclc.org/fast/", AuthoritySource::Fast),
+        ("viaf.org/viaf/", AuthoritySource::Viaf),
+        ("wikidata.org/entity/", AuthoritySource::Wikidata),
+        ("wikidata.org/wiki/", AuthoritySource::Wikidata),
+        ("isni.org/isni/", AuthoritySource::Isni),
+        ("isni.org/", AuthoritySource::Isni),
+        ("orcid.org/", AuthoritySource::Orcid),
+        ("d-nb.info/gnd/", AuthoritySource::Gnd),
+        ("idref.fr/", AuthoritySource::Idref),
+    ];
+    let (source, id) = patterns.iter().find_map(|(prefix, source)| Some((*source, rest.strip_prefix(prefix)?)))?;
+    // Sub-resources, e.g. a VIAF cluster's /justlinks.json
+    let id = id.split('/').next().unwrap_or(id);
+    let id = match source {
+        AuthoritySource::Fast => format!("fst{:0>8}", id.trim_start_matches("fst")),
+        AuthoritySource::Isni => id.replace(' ', ""),
+        _ => id.to_string(),
+    };
+    Some((source, id))
+}
+
+/// Source and identifier of a control number with a source prefix, e.g. `(DLC)sh 85076502`
+fn parse_control_number(value: &str) -> Option<(AuthoritySource, String)> {
+    let (prefix, number) = value.strip_prefix('(')?.split_once(')')?;
+    let number: String = number.split_whitespace().collect();
+    let source = match prefix.to_lowercase().as_str() {
+        "dlc" | "dlc-r" => match number.as_str() {
+            n if n.starts_with("sh") || n.starts_with("sj") || n.starts_with("sp") => AuthoritySource::Lcsh,
+            n if n.starts_with("gf") => AuthoritySource::Lcgft,
+            _ => AuthoritySource::Lcnaf,
+        },
+        "ocolc" if number.starts_with("fst") => AuthoritySource::Fast,
+        "viaf" => AuthoritySource::Viaf,
+        "wikidata" => AuthoritySource::Wikidata,
+        "isni" => AuthoritySource::Isni,
+        "orcid" => AuthoritySource::Orcid,
+        "de-588" => AuthoritySource::Gnd,
+        "idref" | "ppn" => AuthoritySource::Idref,
+        _ => return None,
+    };
+    Some((source, number))
+}
+
+impl DataField {
+    /// Add an identifier to the field, unless it already has one with the same source and identifier
+    ///
+    /// The subfield goes after the existing `$0` and `$1`, or before any
+    /// `$5` and local `$9` at the end of the field. Returns whether it was added.
+    pub fn add_authority_id(&mut self, id: &AuthorityId) -> bool {
+        let present = AuthorityId::from_field(self).iter().any(|existing| existing.source == id.source && existing.id == id.id && existing.real_world_object == id.real_world_object);
+        if present {
+            return false;
+        }
+        let position = match self.subfields.iter().rposition(|s| matches!(s.code, '0' | '1')) {
+            Some(last) => last + 1,
+            None => self.subfields.iter().rposition(|s| !matches!(s.code, '5' | '9')).map_or(0, |last| last + 1),
+        };
+        self.subfields.insert(position, id.to_subfield());
+        true
+    }
+
+    /// Rewrite the `$0` and `$1` identifiers of known sources as their canonical URIs, dropping repeats
+    ///
+    /// Returns the number of subfields changed or removed.
+    pub fn normalize_authority_ids(&mut self) -> usize {
+        let mut seen = Vec::new();
+        let mut changed = 0;
+        self.subfields.retain_mut(|subfield| {
+            if !matches!(subfield.code, '0' | '1') {
+                return true;
+            }
+            let Some(id) = AuthorityId::parse(&subfield.value) else { return true };
+            let key = (subfield.code, id.source, id.id.clone());
+            if seen.contains(&key) {
+                changed += 1;
+                return false;
+            }
+            seen.push(key);
+            if let Some(uri) = id.uri().filter(|uri| *uri != subfield.value) {
+                subfield.value = uri;
+                changed += 1;
+            }
+            true
+        });
+        changed
+    }
+}
+
+/// Whether a field is a heading that may carry authority identifiers: 1XX, 6XX, 7XX except linking entries, and 800-830
+pub fn is_heading_tag(tag: &str) -> bool {
+    match tag.as_bytes() {
+        [b'1', _, _] | [b'6', _, _] => true,
+        [b'7', b'6'..=b'8', _] => false,
+        [b'7', _, _] => true,
+        [b'8', b'0'..=b'2', _] => true,
+        [b'8', b'3', b'0'] => true,
+        _ => false,
+    }
+}
+
+impl Record {
+    /// Identifiers of the headings of the record, with the index of their field
+    pub fn authority_ids(&self) -> Vec<(usize, AuthorityId)> {
+        self.data_fields
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| is_heading_tag(&f.tag))
+            .flat_map(|(i, f)| AuthorityId::from_field(f).into_iter().map(move |id| (i, id)))
+            .collect()
+    }
+
+    /// Add identifiers to headings: `lookup` is called with each heading and returns the identifiers to add
+    ///
+    /// Returns the number of identifiers added.
+    pub fn link_headings<F>(&mut self, mut lookup: F) -> usize
+    where
+        F: FnMut(&DataField) -> Vec<AuthorityId>,
+    {
+        let mut added = 0;
+        for field in self.data_fields.iter_mut().filter(|f| is_heading_tag(&f.tag)) {
+            for id in lookup(field) {
+                added += field.add_authority_id(&id) as usize;
+            }
+        }
+        added
+    }
+
+    /// [Normalize](DataField::normalize_authority_ids) the identifiers of all headings
+    ///
+    /// Returns the number of subfields changed or removed.
+    pub fn normalize_authority_ids(&mut self) -> usize {
+        self.data_fields.iter_mut().filter(|f| is_heading_tag(&f.tag)).map(|f| f.normalize_authority_ids()).sum()
+    }
+}
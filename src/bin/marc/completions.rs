@@ -0,0 +1,14 @@
+use clap::{Args, Command};
+use clap_complete::Shell;
+
+#[derive(Args)]
+pub struct CompletionsArgs {
+    /// Shell: bash, zsh, fish, elvish or powershell
+    pub shell: Shell,
+}
+
+/// Print the completion script of the tool
+pub fn run(args: CompletionsArgs, mut command: Command) -> Result<(), String> {
+    clap_complete::generate(args.shell, &mut command, "marc", &mut std::io::stdout());
+    Ok(())
+}
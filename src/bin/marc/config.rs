@@ -0,0 +1,190 @@
+//! Preferences read from `~/.config/marc/config.toml`
+//!
+//! ```toml
+//! # Format of binary files, which cannot be told apart: marc21 (default) or unimarc
+//! binary-format = "unimarc"
+//!
+//! [input]
+//! # Encoding of MARC21 files whose leader does not say UTF-8 (default: marc8)
+//! encoding = "iso8859-1"
+//!
+//! [output]
+//! # Output format when neither --to nor the file extension gives one
+//! format = "xml"
+//! # Encoding of binary output (default: utf8)
+//! encoding = "utf8"
+//!
+//! [display]
+//! color = false
+//! progress = false
+//!
+//! # Local fields, labelled in views and checked by validate
+//! [local-fields.949]
+//! label = "Local holdings"
+//! # Whether the field may be repeated (default: true)
+//! repeatable = true
+//! # Subfield labels, or tables with a label and whether it may be repeated (default: true)
+//! subfields = { a = { label = "Call number", repeatable = false }, i = "Barcode" }
+//! ```
+//!
+//! The file is `$MARC_CONFIG` when set (empty for none), else `marc/config.toml`
+//! in `$XDG_CONFIG_HOME` or `~/.config`.
+
+use crate::input::{parse_encoding, FileFormat};
+use marc_rs::{Encoding, LocalField, LocalFieldRegistry};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use toml::{Table, Value};
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// Preferences, the defaults when there is no configuration file
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Format of binary files, read or written
+    pub binary_format: FileFormat,
+    /// Encoding of MARC21 input whose leader does not say UTF-8
+    pub input_encoding: Encoding,
+    /// Output format when neither given nor implied by the file extension
+    pub output_format: Option<FileFormat>,
+    /// Encoding of binary output when not given
+    pub output_encoding: Encoding,
+    /// Colors on terminals
+    pub color: bool,
+    /// Progress bars on terminals
+    pub progress: bool,
+    /// Definitions of local fields
+    pub local_fields: LocalFieldRegistry,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            binary_format: FileFormat::Marc21,
+            input_encoding: Encoding::Marc8,
+            output_format: None,
+            output_encoding: Encoding::Utf8,
+            color: true,
+            progress: true,
+            local_fields: LocalFieldRegistry::new(),
+        }
+    }
+}
+
+/// The preferences loaded by [`load`], or the defaults
+pub fn config() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
+
+/// Read the configuration file, if any
+pub fn load() -> Result<(), String> {
+    let config = match path() {
+        Some(path) if path.is_file() => {
+            let text = std::fs::read_to_string(&path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+            parse(&text).map_err(|e| format!("{}: {}", path.display(), e))?
+        }
+        _ => Config::default(),
+    };
+    let _ = CONFIG.set(config);
+    Ok(())
+}
+
+fn path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os("MARC_CONFIG") {
+        return (!path.is_empty()).then(|| PathBuf::from(path));
+    }
+    let directory = match std::env::var_os("XDG_CONFIG_HOME").filter(|path| !path.is_empty()) {
+        Some(path) => PathBuf::from(path),
+        None => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+    };
+    Some(directory.join("marc").join("config.toml"))
+}
+
+fn parse(text: &str) -> Result<Config, String> {
+    let table: Table = text.parse().map_err(|e: toml::de::Error| e.message().to_string())?;
+    let mut config = Config::default();
+    for (key, value) in &table {
+        match (key.as_str(), value) {
+            ("binary-format", Value::String(format)) => {
+                config.binary_format = match FileFormat::parse(format)? {
+                    format @ (FileFormat::Marc21 | FileFormat::Unimarc) => format,
+                    _ => return Err(format!("binary-format must be marc21 or unimarc, not {}", format)),
+                };
+            }
+            ("input", Value::Table(section)) => {
+                for (key, value) in section {
+                    match (key.as_str(), value) {
+                        ("encoding", Value::String(encoding)) => config.input_encoding = parse_encoding(encoding)?,
+                        _ => return Err(unexpected("input", key)),
+                    }
+                }
+            }
+            ("output", Value::Table(section)) => {
+                for (key, value) in section {
+                    match (key.as_str(), value) {
+                        ("format", Value::String(format)) => config.output_format = Some(FileFormat::parse(format)?),
+                        ("encoding", Value::String(encoding)) => config.output_encoding = parse_encoding(encoding)?,
+                        _ => return Err(unexpected("output", key)),
+                    }
+                }
+            }
+            ("display", Value::Table(section)) => {
+                for (key, value) in section {
+                    match (key.as_str(), value) {
+                        ("color", Value::Boolean(color)) => config.color = *color,
+                        ("progress", Value::Boolean(progress)) => config.progress = *progress,
+                        _ => return Err(unexpected("display", key)),
+                    }
+                }
+            }
+            ("local-fields", Value::Table(section)) => {
+                for (tag, value) in section {
+                    let Value::Table(definition) = value else {
+                        return Err(unexpected("local-fields", tag));
+                    };
+                    config.local_fields.register(parse_local_field(tag, definition)?);
+                }
+            }
+            _ => return Err(format!("unexpected key {}, or a value of the wrong type", key)),
+        }
+    }
+    Ok(config)
+}
+
+/// Read a `[local-fields.TAG]` table
+fn parse_local_field(tag: &str, definition: &Table) -> Result<LocalField, String> {
+    let section = format!("local-fields.{}", tag);
+    if tag.len() != 3 {
+        return Err(format!("{}: a tag has three characters", section));
+    }
+    let mut field = LocalField::new(tag, "");
+    for (key, value) in definition {
+        match (key.as_str(), value) {
+            ("label", Value::String(label)) => field.label = label.clone(),
+            ("repeatable", Value::Boolean(repeatable)) => field.repeatable = *repeatable,
+            ("subfields", Value::Table(subfields)) => {
+                for (code, value) in subfields {
+                    let mut chars = code.chars();
+                    let (Some(code), None) = (chars.next(), chars.next()) else {
+                        return Err(format!("{}: invalid subfield code {}", section, code));
+                    };
+                    field = match value {
+                        Value::String(label) => field.subfield(code, label, true),
+                        Value::Table(subfield) => {
+                            let label = subfield.get("label").and_then(Value::as_str).unwrap_or_default();
+                            let repeatable = subfield.get("repeatable").and_then(Value::as_bool).unwrap_or(true);
+                            field.subfield(code, label, repeatable)
+                        }
+                        _ => return Err(unexpected(&format!("{}.subfields", section), &code.to_string())),
+                    };
+                }
+            }
+            _ => return Err(unexpected(&section, key)),
+        }
+    }
+    Ok(field)
+}
+
+fn unexpected(section: &str, key: &str) -> String {
+    format!("unexpected key {}.{}, or a value of the wrong type", section, key)
+}
@@ -0,0 +1,230 @@
+use crate::config::config;
+use crate::filter::{FilterArgs, RangeArgs};
+use crate::input::{is_stdio, parse_format_spec, FileFormat, Input, InputArgs};
+use crate::output::{Output, OutputArgs, RecordSink};
+use crate::progress::{Progress, ProgressArgs};
+use crate::status::Status;
+use clap::Args;
+use marc_rs::*;
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+
+#[derive(Args)]
+pub struct ConvertArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    #[command(flatten)]
+    pub output: OutputArgs,
+
+    /// Output format as FORMAT[:ENCODING] (default: from the output file extension)
+    #[arg(short, long, value_name = "FORMAT")]
+    pub to: Option<String>,
+
+    /// Convert every MARC file of the input directory and its subdirectories into the output directory, keeping their relative paths
+    #[arg(short, long, conflicts_with_all = ["skip", "limit"])]
+    pub recursive: bool,
+
+    /// Number of files converted at once with --recursive (default: the number of CPUs)
+    #[arg(short, long, value_name = "N", requires = "recursive")]
+    pub jobs: Option<NonZeroUsize>,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
+
+    #[command(flatten)]
+    pub range: RangeArgs,
+
+    #[command(flatten)]
+    pub progress: ProgressArgs,
+}
+
+/// Records written, skipped and decoded with losses in one file
+#[derive(Default)]
+struct Counts {
+    converted: usize,
+    skipped: usize,
+    lossy: usize,
+}
+
+/// Convert every record
+///
+/// Records that cannot be written in the output format or encoding are skipped
+/// and reported as errors; records with characters lost when decoding the input
+/// (replacement characters) are converted and counted as warnings.
+pub fn run(args: ConvertArgs) -> Result<Status, String> {
+    if args.recursive {
+        return run_recursive(args);
+    }
+    let (format, encoding) = match &args.to {
+        Some(spec) => parse_format_spec(spec)?,
+        None => (
+            FileFormat::from_path(args.output.path()).or(config().output_format).ok_or_else(|| format!("Cannot infer the output format of {}, use --to", args.output.path().display()))?,
+            None,
+        ),
+    };
+
+    let progress = args.progress.bar(&args.input.file);
+    let mut input = args.input.open_with_progress(&progress)?;
+    args.range.skip(&mut input.records)?;
+    let input_format = input.format;
+    let counts = convert(&args, input, args.output.path(), (format, encoding), &progress, "")?;
+    progress.finish();
+
+    eprintln!("Converted {} record(s) from {} to {}", counts.converted, input_format, format);
+    if counts.lossy > 0 {
+        eprintln!("{} record(s) with characters lost when decoding (U+FFFD)", counts.lossy);
+    }
+    if counts.skipped > 0 {
+        eprintln!("{} record(s) skipped", counts.skipped);
+    }
+    Ok(Status::from_counts(counts.skipped, counts.lossy))
+}
+
+/// Write the records of an input, reporting skipped records with `prefix` before their number
+fn convert(args: &ConvertArgs, input: Input, output: &Path, (format, encoding): (FileFormat, Option<Encoding>), progress: &Progress, prefix: &str) -> Result<Counts, String> {
+    let mut sink = RecordSink::new(Output::create(output)?, format, encoding);
+    let mut counts = Counts::default();
+    for (i, record) in (args.range.skip..).zip(input.records) {
+        let record = record.map_err(|e| format!("Record #{}: {}", i + 1, e))?;
+        if !args.filter.matches(&record) {
+            continue;
+        }
+        let replaced = record.control_fields.iter().map(|f| &f.value).chain(record.data_fields.iter().flat_map(|f| f.subfields.iter().map(|s| &s.value))).any(|v| v.contains('\u{FFFD}'));
+        match sink.try_write(record) {
+            Ok(()) => {}
+            Err(e @ WriteError::IoError(_)) => return Err(e.to_string()),
+            Err(e) => {
+                progress.suspend(|| eprintln!("{}Record #{}: {}, skipped", prefix, i + 1, e));
+                counts.skipped += 1;
+                continue;
+            }
+        }
+        counts.converted += 1;
+        counts.lossy += replaced as usize;
+        if args.range.reached(counts.converted) {
+            break;
+        }
+    }
+    sink.finish()?.commit()?;
+    Ok(counts)
+}
+
+/// Convert the MARC files of a directory tree into another directory, several at a time
+///
+/// Files keep their path relative to the input directory, with the extension of
+/// the output format. A file that cannot be converted, or that would be written to
+/// the same output as another file (`a.mrc` and `a.xml`), is reported and counted
+/// as an error; the others are converted anyway.
+fn run_recursive(args: ConvertArgs) -> Result<Status, String> {
+    let (format, encoding) = match &args.to {
+        Some(spec) => parse_format_spec(spec)?,
+        None => (config().output_format.ok_or("Cannot infer the output format of a directory, use --to")?, None),
+    };
+    let root = &args.input.file;
+    if !root.is_dir() {
+        return Err(format!("{} is not a directory", root.display()));
+    }
+    if is_stdio(args.output.path()) {
+        return Err("--recursive writes to a directory, use -o".to_string());
+    }
+    let mut files = Vec::new();
+    collect_files(root, args.output.path().canonicalize().ok().as_deref(), &mut files)?;
+
+    // Files sharing an output are not converted: one would overwrite the other
+    let mut sources: HashMap<PathBuf, Vec<&PathBuf>> = HashMap::new();
+    for path in &files {
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        sources.entry(args.output.path().join(relative).with_extension(format.extension())).or_default().push(path);
+    }
+    let mut failures: Vec<(&PathBuf, String)> = Vec::new();
+    let mut tasks = Vec::new();
+    for (output, paths) in sources {
+        if paths.len() == 1 {
+            tasks.push((paths[0], output));
+            continue;
+        }
+        for path in &paths {
+            let others: Vec<String> = paths.iter().filter(|other| other != &path).map(|other| other.display().to_string()).collect();
+            failures.push((path, format!("{} is also the output of {}", output.display(), others.join(", "))));
+        }
+    }
+    tasks.sort();
+
+    let jobs = args.jobs.map_or_else(|| std::thread::available_parallelism().map_or(1, NonZeroUsize::get), NonZeroUsize::get);
+    let progress = args.progress.files(tasks.len() as u64);
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    std::thread::scope(|scope| {
+        for _ in 0..jobs.min(tasks.len()) {
+            let sender = sender.clone();
+            let (args, tasks, next, progress) = (&args, &tasks, &next, &progress);
+            scope.spawn(move || {
+                while let Some((path, output)) = tasks.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    let result = output
+                        .parent()
+                        .map_or(Ok(()), |dir| std::fs::create_dir_all(dir).map_err(|e| format!("Cannot create {}: {}", dir.display(), e)))
+                        .and_then(|_| args.input.format.open(path))
+                        .and_then(|input| convert(args, input, output, (format, encoding), progress, &format!("{}: ", path.display())));
+                    progress.inc();
+                    let _ = sender.send((path, result));
+                }
+            });
+        }
+    });
+    drop(sender);
+    progress.finish();
+
+    let mut total = Counts::default();
+    for (path, result) in receiver {
+        match result {
+            Ok(counts) => {
+                total.converted += counts.converted;
+                total.skipped += counts.skipped;
+                total.lossy += counts.lossy;
+            }
+            Err(e) => failures.push((path, e)),
+        }
+    }
+    failures.sort();
+
+    eprintln!("Converted {} record(s) in {} of {} file(s) to {}", total.converted, files.len() - failures.len(), files.len(), format);
+    if total.lossy > 0 {
+        eprintln!("{} record(s) with characters lost when decoding (U+FFFD)", total.lossy);
+    }
+    if total.skipped > 0 {
+        eprintln!("{} record(s) skipped", total.skipped);
+    }
+    if !failures.is_empty() {
+        eprintln!("{} file(s) failed:", failures.len());
+        for (path, error) in &failures {
+            eprintln!("  {}: {}", path.display(), error);
+        }
+    }
+    Ok(Status::from_counts(total.skipped + failures.len(), total.lossy))
+}
+
+/// Files with a MARC extension in a directory tree, by path, skipping hidden files and the output directory
+fn collect_files(dir: &Path, output: Option<&Path>, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let error = |e: std::io::Error| format!("Cannot read {}: {}", dir.display(), e);
+    let mut entries = std::fs::read_dir(dir).map_err(error)?.collect::<Result<Vec<_>, _>>().map_err(error)?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let path = entry.path();
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            if output.is_some() && path.canonicalize().ok().as_deref() == output {
+                continue;
+            }
+            collect_files(&path, output, files)?;
+        } else if FileFormat::from_path(&path).is_some() {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use crate::input::{FileFormat, InputArgs, Records};
+use clap::Args;
+use std::io::BufRead;
+
+#[derive(Args)]
+pub struct CountArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// Count record terminators (0x1D) instead of following the leader record lengths, for binary files with wrong lengths
+    #[arg(long)]
+    pub terminators: bool,
+}
+
+/// Print the number of records without decoding them
+///
+/// Binary records are passed over using their leader record length (or counted
+/// by terminator), MARC XML records by their end tag, JSON Lines as non-blank
+/// lines and MarcMaker records as groups of non-blank lines. JSON arrays and
+/// pretty-printed JSON records are decoded.
+pub fn run(args: CountArgs) -> Result<(), String> {
+    let input = args.input.open()?;
+    let binary = matches!(input.format, FileFormat::Marc21 | FileFormat::Unimarc);
+    let io_error = |e: std::io::Error| format!("Cannot read {}: {}", args.input.file.display(), e);
+
+    let count = match input.records {
+        Records::Marc(reader) if binary && args.terminators => {
+            let mut reader = reader.into_inner();
+            let mut count = 0;
+            loop {
+                let buffer = reader.fill_buf().map_err(io_error)?;
+                if buffer.is_empty() {
+                    break;
+                }
+                count += memchr::memchr_iter(0x1D, buffer).count();
+                let length = buffer.len();
+                reader.consume(length);
+            }
+            count
+        }
+        Records::Marc(mut reader) => reader.skip_records(usize::MAX).map_err(|e| format!("Record #{}: {}", reader.record_index() + 1, e))?,
+        Records::Json(mut reader) => reader.try_fold(0, |count, record| record.map(|_| count + 1)).map_err(|e| e.to_string())?,
+        Records::JsonLines(reader) => count_lines(reader.into_inner(), false).map_err(io_error)?,
+        Records::Mrk(reader) => count_lines(reader.into_inner(), true).map_err(io_error)?,
+    };
+    println!("{}", count);
+    Ok(())
+}
+
+/// Count non-blank lines, or groups of non-blank lines separated by blank lines
+fn count_lines(mut reader: impl BufRead, groups: bool) -> std::io::Result<usize> {
+    let mut line = Vec::new();
+    let (mut count, mut in_group) = (0, false);
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            return Ok(count);
+        }
+        let blank = line.iter().all(|b| b.is_ascii_whitespace());
+        // A group is counted on its first line
+        let counted = if groups { !blank && !in_group } else { !blank };
+        if counted {
+            count += 1;
+        }
+        in_group = !blank;
+    }
+}
@@ -0,0 +1,128 @@
+use crate::input::{is_stdio, parse_format_spec, FileFormat, InputArgs};
+use crate::output::{OutputArgs, RecordSink};
+use clap::Args;
+use marc_rs::*;
+use std::collections::HashMap;
+
+#[derive(Args)]
+pub struct DedupArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    #[command(flatten)]
+    pub output: OutputArgs,
+
+    /// Comma-separated keys: 001, 035 (system numbers), isbn, or match-key (title, main entry and year); records sharing any key are duplicates
+    #[arg(short, long, value_name = "KEYS", value_delimiter = ',', default_value = "001,035", value_parser = DedupKey::parse)]
+    pub by: Vec<DedupKey>,
+
+    /// Record kept among duplicates: first, last, or fullest (most fields and subfields, the first one on ties)
+    #[arg(short, long, default_value = "first", value_parser = Keep::parse)]
+    pub keep: Keep,
+
+    /// Output format as FORMAT[:ENCODING] (default: from the output file extension, or the input format)
+    #[arg(short, long, value_name = "FORMAT")]
+    pub to: Option<String>,
+}
+
+/// Record kept among duplicates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Keep {
+    First,
+    Last,
+    Fullest,
+}
+
+impl Keep {
+    fn parse(keep: &str) -> Result<Self, String> {
+        match keep {
+            "first" => Ok(Keep::First),
+            "last" => Ok(Keep::Last),
+            "fullest" => Ok(Keep::Fullest),
+            _ => Err(format!("Unknown choice: {}. Use: first, last or fullest", keep)),
+        }
+    }
+}
+
+/// Representative of `i` in a union-find forest
+fn root(parents: &mut [usize], mut i: usize) -> usize {
+    while parents[i] != i {
+        parents[i] = parents[parents[i]];
+        i = parents[i];
+    }
+    i
+}
+
+/// Drop duplicate records, reporting each dropped record on stderr
+///
+/// The input is read twice: first to group records sharing a key (transitively),
+/// then to write the record kept in each group, in input order.
+pub fn run(args: DedupArgs) -> Result<(), String> {
+    if is_stdio(&args.input.file) {
+        return Err("dedup reads its input twice and cannot read stdin".to_string());
+    }
+    let input = args.input.open()?;
+    let (format, encoding) = match (&args.to, FileFormat::from_path(args.output.path())) {
+        (Some(spec), _) => parse_format_spec(spec)?,
+        (None, Some(format)) => (format, None),
+        (None, None) => (input.format, Some(input.encoding)),
+    };
+    let record_format = input.format.record_format();
+
+    let mut parents: Vec<usize> = Vec::new();
+    let mut sizes: Vec<usize> = Vec::new();
+    let mut ids: Vec<Option<String>> = Vec::new();
+    let index = DedupIndex::new();
+    for (i, record) in input.records.enumerate() {
+        let record = record.map_err(|e| format!("Record #{}: {}", i + 1, e))?;
+        parents.push(i);
+        sizes.push(record.control_fields.len() + record.data_fields.iter().map(|f| f.subfields.len()).sum::<usize>());
+        ids.push(mapping::control_value(&record, "001").map(str::to_string));
+        for key in record.dedup_keys(&args.by, record_format) {
+            if let Some(j) = index.insert(&key, i) {
+                let (a, b) = (root(&mut parents, i), root(&mut parents, j));
+                parents[a.max(b)] = a.min(b);
+            }
+        }
+    }
+
+    // Record kept in each group, by group root
+    let mut kept: HashMap<usize, usize> = HashMap::new();
+    for i in 0..parents.len() {
+        let group = root(&mut parents, i);
+        let current = kept.entry(group).or_insert(i);
+        let better = match args.keep {
+            Keep::First => false,
+            Keep::Last => true,
+            Keep::Fullest => sizes[i] > sizes[*current],
+        };
+        if better {
+            *current = i;
+        }
+    }
+
+    let describe = |i: usize| match &ids[i] {
+        Some(id) => format!("#{} ({})", i + 1, id),
+        None => format!("#{}", i + 1),
+    };
+    let mut sink = RecordSink::new(args.output.create()?, format, encoding);
+    let (mut count, mut dropped) = (0, 0);
+    for (i, record) in args.input.open()?.records.enumerate() {
+        let record = record.map_err(|e| format!("Record #{}: {}", i + 1, e))?;
+        if i >= parents.len() {
+            return Err(format!("{} changed while being read", args.input.file.display()));
+        }
+        let winner = kept[&root(&mut parents, i)];
+        if winner == i {
+            sink.write(record)?;
+            count += 1;
+        } else {
+            eprintln!("Dropped record {}: duplicate of record {}", describe(i), describe(winner));
+            dropped += 1;
+        }
+    }
+    sink.finish()?.commit()?;
+
+    eprintln!("Kept {} record(s), dropped {} duplicate(s)", count, dropped);
+    Ok(())
+}
@@ -0,0 +1,130 @@
+use crate::input::{is_stdio, InputFormatArgs};
+use crate::output::{write_error, Output};
+use clap::Args;
+use marc_rs::*;
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct DiffArgs {
+    /// Old version of the file, or - for stdin
+    pub old: PathBuf,
+
+    /// New version of the file, or - for stdin
+    pub new: PathBuf,
+
+    #[command(flatten)]
+    pub format: InputFormatArgs,
+
+    /// MARCspec of the value matching records across the files
+    #[arg(short, long, value_name = "SPEC", default_value = "001", value_parser = MarcSpec::parse)]
+    pub key: MarcSpec,
+
+    /// Comma-separated tags left out of the comparison, e.g. 005
+    #[arg(short, long, value_name = "TAGS", value_delimiter = ',')]
+    pub ignore: Vec<String>,
+
+    /// Report format: plain, or json (one object per line for each differing record)
+    #[arg(long = "format", default_value = "plain")]
+    pub report: String,
+
+    /// File to write, replaced only once complete (default: stdout)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+/// Compare two files record by record, matching records on their key
+///
+/// The new file is held in memory; the old one is streamed.
+pub fn run(args: DiffArgs) -> Result<(), String> {
+    let json = match args.report.as_str() {
+        "plain" => false,
+        "json" => true,
+        other => return Err(format!("Unknown report format: {}. Use: plain or json", other)),
+    };
+    if is_stdio(&args.old) && is_stdio(&args.new) {
+        return Err("Only one of the files can be read from stdin".to_string());
+    }
+    let ignore: Vec<&str> = args.ignore.iter().map(|tag| tag.as_str()).collect();
+    let mut output = match &args.output {
+        Some(path) => Output::create(path)?,
+        None => Output::stdout(),
+    };
+
+    // Records of the new file in file order, taken out as they are matched
+    let mut new_records: Vec<Option<(String, Record)>> = Vec::new();
+    let mut new_index: HashMap<String, usize> = HashMap::new();
+    let mut unkeyed = 0;
+    for (i, record) in args.format.open(&args.new)?.records.enumerate() {
+        let record = record.map_err(|e| format!("{}: record #{}: {}", args.new.display(), i + 1, e))?;
+        match args.key.first_value(&record) {
+            Some(key) if !new_index.contains_key(&key) => {
+                new_index.insert(key.clone(), new_records.len());
+                new_records.push(Some((key, record.without(&ignore))));
+            }
+            Some(key) => return Err(format!("{}: record #{}: duplicate key {}", args.new.display(), i + 1, key)),
+            None => unkeyed += 1,
+        }
+    }
+
+    let (mut added, mut removed, mut changed, mut unchanged) = (0, 0, 0, 0);
+    let mut old_keys: HashSet<String> = HashSet::new();
+    for (i, record) in args.format.open(&args.old)?.records.enumerate() {
+        let record = record.map_err(|e| format!("{}: record #{}: {}", args.old.display(), i + 1, e))?;
+        let Some(key) = args.key.first_value(&record) else {
+            unkeyed += 1;
+            continue;
+        };
+        if !old_keys.insert(key.clone()) {
+            return Err(format!("{}: record #{}: duplicate key {}", args.old.display(), i + 1, key));
+        }
+        match new_index.remove(&key).and_then(|index| new_records[index].take()) {
+            Some((_, new)) => {
+                let changes = record.without(&ignore).diff(&new);
+                if changes.is_empty() {
+                    unchanged += 1;
+                } else {
+                    changed += 1;
+                    report(&mut output, json, &key, ChangeKind::Changed, &changes).map_err(write_error)?;
+                }
+            }
+            None => {
+                removed += 1;
+                report(&mut output, json, &key, ChangeKind::Removed, &[]).map_err(write_error)?;
+            }
+        }
+    }
+    for (key, _) in new_records.into_iter().flatten() {
+        added += 1;
+        report(&mut output, json, &key, ChangeKind::Added, &[]).map_err(write_error)?;
+    }
+
+    output.commit()?;
+
+    let summary = format!("{} added, {} removed, {} changed, {} unchanged", added, removed, changed, unchanged);
+    match unkeyed {
+        0 => eprintln!("{}", summary),
+        n => eprintln!("{} ({} record(s) without {} skipped)", summary, n, args.key),
+    }
+    Ok(())
+}
+
+fn report(output: &mut impl Write, json: bool, key: &str, kind: ChangeKind, changes: &[FieldChange]) -> std::io::Result<()> {
+    if json {
+        return writeln!(output, "{}", serde_json::json!({ "key": key, "status": kind.to_string(), "changes": changes }));
+    }
+    match kind {
+        ChangeKind::Added => writeln!(output, "+ {}", key),
+        ChangeKind::Removed => writeln!(output, "- {}", key),
+        ChangeKind::Changed => {
+            writeln!(output, "~ {}", key)?;
+            for change in changes {
+                for line in change.to_string().lines() {
+                    writeln!(output, "    {}", line)?;
+                }
+            }
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use crate::input::{parse_format_spec, FileFormat, InputArgs};
+use crate::output::{OutputArgs, RecordSink};
+use clap::Args;
+use marc_rs::*;
+use std::path::{Path, PathBuf};
+
+#[derive(Args)]
+pub struct EditArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    #[command(flatten)]
+    pub output: OutputArgs,
+
+    /// Patch file of operations, in TOML or JSON (.json)
+    #[arg(short, long, value_name = "FILE")]
+    pub patch: Option<PathBuf>,
+
+    /// Script of operations, one per line, run after those of the patch
+    #[arg(short, long, value_name = "FILE")]
+    pub script: Option<PathBuf>,
+
+    /// Operation run after those of the script, e.g. "delete 9.."; repeatable
+    #[arg(long = "expression", value_name = "OPERATION")]
+    pub expressions: Vec<String>,
+
+    /// Output format as FORMAT[:ENCODING] (default: from the output file extension, or the input format)
+    #[arg(short, long, value_name = "FORMAT")]
+    pub to: Option<String>,
+}
+
+/// Word of a script line
+struct Token {
+    text: String,
+    quoted: bool,
+}
+
+/// Split a line into words; double quotes protect spaces, `""` stands for a quote inside them
+fn tokenize(line: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+        let mut text = String::new();
+        if c == '"' {
+            loop {
+                match chars.next() {
+                    Some('"') if chars.peek() == Some(&'"') => {
+                        chars.next();
+                        text.push('"');
+                    }
+                    Some('"') => break,
+                    Some(c) => text.push(c),
+                    None => return Err("unclosed quote".to_string()),
+                }
+            }
+        } else {
+            text.push(c);
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                text.push(c);
+            }
+        }
+        tokens.push(Token { text, quoted: c == '"' });
+    }
+    Ok(tokens)
+}
+
+/// Read a script line, e.g. `delete 650 if 650$2=fast`
+fn parse_step(line: &str) -> Result<PatchStep, String> {
+    let mut tokens = tokenize(line)?;
+    let condition = match tokens.len() {
+        n if n >= 2 && tokens[n - 2].text == "if" && !tokens[n - 2].quoted => {
+            let condition = SpecCondition::parse(&tokens[n - 1].text)?;
+            tokens.truncate(n - 2);
+            Some(condition)
+        }
+        _ => None,
+    };
+    let words: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+
+    let operation = match words.as_slice() {
+        ["add", field] => PatchOperation::add(field)?,
+        ["delete", spec] => PatchOperation::delete(spec)?,
+        ["replace", spec, find, replacement] => PatchOperation::replace(spec, find, replacement)?,
+        ["copy", source, target] => PatchOperation::copy(source, target)?,
+        ["move", source, target] => PatchOperation::move_to(source, target)?,
+        [command, ..] if ["add", "delete", "replace", "copy", "move"].contains(command) => return Err(format!("wrong arguments for {}", command)),
+        [command, ..] => return Err(format!("unknown operation {:?}. Use: add, delete, replace, copy or move", command)),
+        [] => return Err("empty operation".to_string()),
+    };
+    Ok(PatchStep { operation, condition })
+}
+
+/// Read a patch file, in TOML or JSON according to its extension
+fn read_patch(path: &Path) -> Result<Patch, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    let patch = match path.extension().and_then(|e| e.to_str()) {
+        Some("json") => Patch::from_json(&text),
+        _ => {
+            let table: toml::Table = text.parse().map_err(|e| format!("{}: {}", path.display(), e))?;
+            serde_json::to_value(table).map_err(|e| e.to_string()).and_then(|value| Patch::from_value(&value))
+        }
+    };
+    patch.map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Steps of the patch file, then of the script file, then of the expressions
+fn parse_patch(args: &EditArgs) -> Result<Patch, String> {
+    let mut steps = match &args.patch {
+        Some(path) => read_patch(path)?.steps,
+        None => Vec::new(),
+    };
+    if let Some(path) = &args.script {
+        let script = std::fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        for (n, line) in script.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            steps.push(parse_step(line).map_err(|e| format!("{}, line {}: {}", path.display(), n + 1, e))?);
+        }
+    }
+    for expression in &args.expressions {
+        steps.push(parse_step(expression).map_err(|e| format!("{:?}: {}", expression, e))?);
+    }
+    if steps.is_empty() {
+        return Err("Nothing to do: give a --patch, a --script or an --expression".to_string());
+    }
+    Ok(Patch { steps })
+}
+
+/// Apply the operations of a script to every record
+pub fn run(args: EditArgs) -> Result<(), String> {
+    let patch = parse_patch(&args)?;
+    let input = args.input.open()?;
+    let (format, encoding) = match (&args.to, FileFormat::from_path(args.output.path())) {
+        (Some(spec), _) => parse_format_spec(spec)?,
+        (None, Some(format)) => (format, None),
+        (None, None) => (input.format, Some(input.encoding)),
+    };
+    let mut sink = RecordSink::new(args.output.create()?, format, encoding);
+
+    let (mut count, mut edited) = (0, 0);
+    for (i, record) in input.records.enumerate() {
+        let mut record = record.map_err(|e| format!("Record #{}: {}", i + 1, e))?;
+        if patch.apply(&mut record) {
+            edited += 1;
+        }
+        sink.write(record).map_err(|e| format!("Record #{}: {}", i + 1, e))?;
+        count += 1;
+    }
+    sink.finish()?.commit()?;
+
+    eprintln!("Edited {} of {} record(s)", edited, count);
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use crate::input::{FileFormat, InputArgs};
+use crate::output::write_error;
+use crate::progress::ProgressArgs;
+use clap::Args;
+use marc_rs::*;
+use std::collections::BTreeMap;
+use std::io::{BufRead, Write};
+
+/// Record numbers listed as examples for each guess
+const EXAMPLES: usize = 5;
+
+#[derive(Args)]
+pub struct EncodingReportArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    #[command(flatten)]
+    pub progress: ProgressArgs,
+}
+
+/// Records of a guess, and the numbers of the first ones
+#[derive(Default)]
+struct GuessCount {
+    records: usize,
+    examples: Vec<usize>,
+}
+
+/// Guess the encoding of each record of a binary file and summarize the guesses
+///
+/// The bytes of each record are read undecoded, split on record terminators, so
+/// the report works on files that cannot be decoded with their current settings.
+pub fn run(args: EncodingReportArgs) -> Result<(), String> {
+    let progress = args.progress.bar(&args.input.file);
+    let mut input = args.input.format.open_raw(&args.input.file, Some(&progress))?;
+    if !matches!(input.format, FileFormat::Marc21 | FileFormat::Unimarc) {
+        return Err(format!("Only binary files can be diagnosed, not {}", input.format));
+    }
+
+    let mut guesses: BTreeMap<EncodingGuess, GuessCount> = BTreeMap::new();
+    let (mut count, mut says_utf8, mut invalid_utf8, mut invalid_says_utf8) = (0, 0, 0, 0);
+    let mut buffer = Vec::new();
+    loop {
+        buffer.clear();
+        input.reader.read_until(0x1D, &mut buffer).map_err(|e| format!("Cannot read {}: {}", args.input.file.display(), e))?;
+        let data = buffer.trim_ascii();
+        if data.is_empty() {
+            break;
+        }
+        count += 1;
+
+        let guess = guesses.entry(guess_encoding(data)).or_default();
+        guess.records += 1;
+        if guess.examples.len() < EXAMPLES {
+            guess.examples.push(count);
+        }
+        let utf8_leader = data.get(9) == Some(&b'a');
+        if utf8_leader {
+            says_utf8 += 1;
+        }
+        if std::str::from_utf8(data).is_err() {
+            invalid_utf8 += 1;
+            if utf8_leader {
+                invalid_says_utf8 += 1;
+            }
+        }
+    }
+
+    progress.finish();
+    let mut out = std::io::stdout().lock();
+    writeln!(out, "File: {}", args.input.file.display()).map_err(write_error)?;
+    writeln!(out, "Records: {}", count).map_err(write_error)?;
+    if count == 0 {
+        return Ok(());
+    }
+    if input.format == FileFormat::Marc21 {
+        writeln!(out, "Leader/09: {} say UTF-8 (a), {} say MARC-8 (blank or other)", says_utf8, count - says_utf8).map_err(write_error)?;
+    }
+
+    writeln!(out).map_err(write_error)?;
+    writeln!(out, "SUSPECTED ENCODINGS").map_err(write_error)?;
+    for (guess, counts) in &guesses {
+        let examples = match guess {
+            EncodingGuess::Ascii => String::new(),
+            _ => format!("  e.g. {}", counts.examples.iter().map(|n| format!("#{}", n)).collect::<Vec<_>>().join(", ")),
+        };
+        writeln!(out, "  {:<24}{:>8}{}", guess.description(), counts.records, examples).map_err(write_error)?;
+    }
+    writeln!(out).map_err(write_error)?;
+    writeln!(out, "Records with invalid UTF-8: {}", invalid_utf8).map_err(write_error)?;
+    if invalid_says_utf8 > 0 {
+        writeln!(out, "  {} of them say UTF-8 in leader/09", invalid_says_utf8).map_err(write_error)?;
+    }
+    if let Some(double) = guesses.get(&EncodingGuess::DoubleUtf8) {
+        writeln!(out, "Records likely encoded twice: {}; no --encoding reads them right, their source needs fixing", double.records).map_err(write_error)?;
+    }
+
+    let records = |guess: EncodingGuess| guesses.get(&guess).map_or(0, |counts| counts.records);
+    let candidates = [
+        ("utf8", records(EncodingGuess::Utf8) + records(EncodingGuess::DoubleUtf8)),
+        ("marc8", records(EncodingGuess::Marc8)),
+        ("iso8859-1", records(EncodingGuess::SingleByte)),
+    ];
+    writeln!(out).map_err(write_error)?;
+    let suggestion = match candidates.iter().filter(|(_, n)| *n > 0).max_by_key(|(_, n)| *n) {
+        None => writeln!(out, "Every record is ASCII: any --encoding reads them the same"),
+        Some((encoding, _)) if candidates.iter().filter(|(_, n)| *n > 0).count() > 1 => writeln!(out, "Suggested: --encoding {} (the records mix several encodings)", encoding),
+        Some((encoding, _)) => writeln!(out, "Suggested: --encoding {}", encoding),
+    };
+    suggestion.map_err(write_error)
+}
@@ -0,0 +1,79 @@
+use crate::filter::FilterArgs;
+use crate::input::{parse_format_spec, InputArgs};
+use crate::output::{Output, RecordSink};
+use crate::split::file_name_part;
+use clap::Args;
+use marc_rs::*;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct ExplodeArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// Directory of the output files
+    #[arg(short, long, default_value = ".")]
+    pub dir: PathBuf,
+
+    /// Name files after the value of SPEC (default: 001)
+    #[arg(long, value_name = "SPEC", value_parser = MarcSpec::parse, conflicts_with = "sequence")]
+    pub name: Option<MarcSpec>,
+
+    /// Name files after the record number, 000001, 000002...
+    #[arg(long)]
+    pub sequence: bool,
+
+    /// Output format as FORMAT[:ENCODING] (default: the input format and encoding)
+    #[arg(short, long, value_name = "FORMAT")]
+    pub to: Option<String>,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
+}
+
+/// Write each record to its own file, `VALUE.mrc` or `000001.mrc`
+///
+/// Records without a value for the naming spec are named by their number, and
+/// a name already used gets a `-2`, `-3`... suffix, so no record overwrites
+/// another.
+pub fn run(args: ExplodeArgs) -> Result<(), String> {
+    let input = args.input.open()?;
+    let (format, encoding) = match &args.to {
+        Some(spec) => parse_format_spec(spec)?,
+        None => (input.format, Some(input.encoding)),
+    };
+    let spec = match &args.name {
+        Some(spec) => spec.clone(),
+        None => MarcSpec::parse("001")?,
+    };
+    std::fs::create_dir_all(&args.dir).map_err(|e| format!("Cannot create {}: {}", args.dir.display(), e))?;
+
+    let mut names = HashSet::new();
+    let mut count = 0;
+    for (i, record) in input.records.enumerate() {
+        let record = record.map_err(|e| format!("Record #{}: {}", i + 1, e))?;
+        if !args.filter.matches(&record) {
+            continue;
+        }
+        let value = match args.sequence {
+            true => None,
+            false => spec.first_value(&record).map(|value| file_name_part(&value)).filter(|name| name != "none"),
+        };
+        let base = value.unwrap_or_else(|| format!("{:06}", i + 1));
+        let mut name = base.clone();
+        let mut n = 1;
+        while !names.insert(name.clone()) {
+            n += 1;
+            name = format!("{}-{}", base, n);
+        }
+
+        let path = args.dir.join(format!("{}.{}", name, format.extension()));
+        let mut sink = RecordSink::new(Output::create(&path)?, format, encoding);
+        sink.write(record).map_err(|e| format!("Record #{}: {}", i + 1, e))?;
+        sink.finish()?.commit()?;
+        count += 1;
+    }
+    eprintln!("Wrote {} file(s) to {}", count, args.dir.display());
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use crate::filter::{FilterArgs, RangeArgs};
+use crate::input::InputArgs;
+use crate::output::Output;
+use clap::Args;
+use marc_rs::*;
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct ExtractArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// Comma-separated MARCspec columns, e.g. "001,245$a,020$a"
+    #[arg(short, long, value_name = "SPECS", value_delimiter = ',', required = true, value_parser = MarcSpec::parse)]
+    pub columns: Vec<MarcSpec>,
+
+    /// Cell delimiter: a single character, or "tab"
+    #[arg(short, long, default_value = ",")]
+    pub delimiter: String,
+
+    /// Separator of repeated values within a cell
+    #[arg(short, long, default_value = DEFAULT_FLAT_SEPARATOR)]
+    pub separator: String,
+
+    /// File to write, replaced only once complete (default: stdout)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
+
+    #[command(flatten)]
+    pub range: RangeArgs,
+}
+
+/// Write the selected columns of every record as CSV
+pub fn run(args: ExtractArgs) -> Result<(), String> {
+    let delimiter = match args.delimiter.as_str() {
+        "tab" | "\\t" => '\t',
+        other => {
+            let mut chars = other.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => c,
+                _ => return Err(format!("Invalid delimiter {:?}: expected a single character or \"tab\"", other)),
+            }
+        }
+    };
+
+    let mut input = args.input.open()?;
+    args.range.skip(&mut input.records)?;
+    let output = match &args.output {
+        Some(path) => Output::create(path)?,
+        None => Output::stdout(),
+    };
+    let mut writer = CsvWriter::new(output, args.columns).delimiter(delimiter).separator(&args.separator);
+    for (i, record) in (args.range.skip..).zip(input.records) {
+        let record = record.map_err(|e| format!("Record #{}: {}", i + 1, e))?;
+        if !args.filter.matches(&record) {
+            continue;
+        }
+        writer.write(&record).map_err(|e| e.to_string())?;
+        if args.range.reached(writer.count()) {
+            break;
+        }
+    }
+    writer.finish().map_err(|e| e.to_string())?.commit()
+}
@@ -0,0 +1,52 @@
+use crate::input::Records;
+use clap::Args;
+use marc_rs::*;
+use std::num::NonZeroUsize;
+
+/// Record selection options shared by the subcommands
+#[derive(Args)]
+pub struct FilterArgs {
+    /// Only keep records matching SPEC=VALUE, SPEC!=VALUE or SPEC~VALUE (contains, ignoring case), e.g. "650$a=History"; repeatable, all must match
+    #[arg(long = "filter", value_name = "CONDITION", value_parser = SpecCondition::parse)]
+    pub filters: Vec<SpecCondition>,
+
+    /// Only keep records having a value for SPEC, e.g. 856 or "041$a"; repeatable, all must match
+    #[arg(long = "has", value_name = "SPEC", value_parser = MarcSpec::parse)]
+    pub has: Vec<MarcSpec>,
+}
+
+impl FilterArgs {
+    /// True if any condition was given
+    pub fn is_active(&self) -> bool {
+        !self.filters.is_empty() || !self.has.is_empty()
+    }
+
+    /// True if the record passes every condition
+    pub fn matches(&self, record: &Record) -> bool {
+        self.filters.iter().all(|condition| condition.matches(record)) && self.has.iter().all(|spec| !spec.values(record).is_empty())
+    }
+}
+
+/// Record range options shared by the subcommands
+#[derive(Args)]
+pub struct RangeArgs {
+    /// Start after the first N records of the input, e.g. to resume an interrupted run
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    pub skip: usize,
+
+    /// Stop after M records were written
+    #[arg(long, value_name = "M")]
+    pub limit: Option<NonZeroUsize>,
+}
+
+impl RangeArgs {
+    /// Skip the first records of the input
+    pub fn skip(&self, records: &mut Records) -> Result<(), String> {
+        records.skip_records(self.skip).map(|_| ()).map_err(|e| format!("Cannot skip {} record(s): {}", self.skip, e))
+    }
+
+    /// True once `count` records were written
+    pub fn reached(&self, count: usize) -> bool {
+        self.limit.is_some_and(|limit| count >= limit.get())
+    }
+}
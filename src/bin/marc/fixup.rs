@@ -0,0 +1,74 @@
+use crate::input::{parse_format_spec, FileFormat, InputArgs};
+use crate::output::{OutputArgs, RecordSink};
+use crate::progress::ProgressArgs;
+use crate::status::Status;
+use clap::Args;
+use marc_rs::*;
+
+#[derive(Args)]
+pub struct FixupArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    #[command(flatten)]
+    pub output: OutputArgs,
+
+    /// Output format as FORMAT[:ENCODING] (default: from the output file extension, or the input format)
+    #[arg(short, long, value_name = "FORMAT")]
+    pub to: Option<String>,
+
+    /// Only print the summary, not the repairs of each record
+    #[arg(short, long)]
+    pub quiet: bool,
+
+    #[command(flatten)]
+    pub progress: ProgressArgs,
+}
+
+/// Rewrite a damaged binary file with well-formed records
+///
+/// Records are split on their terminators and rebuilt with [`recover_binary`];
+/// writing them recomputes the leader lengths, directory and terminators. Records
+/// that cannot be recovered are skipped.
+pub fn run(args: FixupArgs) -> Result<Status, String> {
+    let progress = args.progress.bar(&args.input.file);
+    let input = args.input.format.open_raw(&args.input.file, Some(&progress))?;
+    let marc_format = match input.format {
+        FileFormat::Marc21 | FileFormat::Unimarc => input.format.record_format(),
+        format => return Err(format!("Only binary files can be repaired, not {}", format)),
+    };
+    let (format, encoding) = match (&args.to, FileFormat::from_path(args.output.path())) {
+        (Some(spec), _) => parse_format_spec(spec)?,
+        (None, Some(format)) => (format, None),
+        (None, None) => (input.format, Some(input.encoding)),
+    };
+    let mut sink = RecordSink::new(args.output.create()?, format, encoding);
+
+    let (mut count, mut repaired, mut skipped) = (0, 0, 0);
+    for (i, recovered) in RecoveringReader::new(input.reader, FormatEncoding::new(marc_format, input.encoding)).enumerate() {
+        count += 1;
+        let recovered = match recovered {
+            Ok(recovered) => recovered,
+            Err(e) => {
+                progress.suspend(|| eprintln!("Record #{}: {}, skipped", i + 1, e));
+                skipped += 1;
+                continue;
+            }
+        };
+        if !recovered.repairs.is_empty() {
+            repaired += 1;
+            if !args.quiet {
+                progress.suspend(|| recovered.repairs.iter().for_each(|repair| eprintln!("Record #{}: {}", i + 1, repair)));
+            }
+        }
+        sink.write(recovered.record).map_err(|e| format!("Record #{}: {}", i + 1, e))?;
+    }
+    sink.finish()?.commit()?;
+    progress.finish();
+
+    eprintln!("Repaired {} of {} record(s)", repaired, count);
+    if skipped > 0 {
+        eprintln!("{} record(s) could not be recovered, skipped", skipped);
+    }
+    Ok(Status::from_counts(skipped, repaired))
+}
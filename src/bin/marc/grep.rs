@@ -0,0 +1,126 @@
+use crate::input::InputArgs;
+use crate::output::{Output, RecordSink};
+use clap::Args;
+use marc_rs::*;
+use regex::{Regex, RegexBuilder};
+use std::io::Write;
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct GrepArgs {
+    /// Text to look for in control field and subfield values
+    pub pattern: String,
+
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// Treat the pattern as a regular expression
+    #[arg(short = 'E', long)]
+    pub regex: bool,
+
+    /// Ignore case
+    #[arg(short, long)]
+    pub ignore_case: bool,
+
+    /// Only search the values selected by SPEC, e.g. 650 or "245$a"; repeatable
+    #[arg(long = "in", value_name = "SPEC", value_parser = MarcSpec::parse)]
+    pub specs: Vec<MarcSpec>,
+
+    /// Select the records that do not match
+    #[arg(short = 'v', long)]
+    pub invert_match: bool,
+
+    /// Only print the control numbers (001) of the matching records, or #N without one
+    #[arg(short = 'l', long, conflicts_with_all = ["count", "to"])]
+    pub ids: bool,
+
+    /// Only print the number of matching records
+    #[arg(short, long, conflicts_with = "to")]
+    pub count: bool,
+
+    /// Output format of the matching records as FORMAT[:ENCODING]
+    #[arg(short, long, value_name = "FORMAT", default_value = "mrk")]
+    pub to: String,
+
+    /// File to write, replaced only once complete (default: stdout)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+/// Pattern matched against values
+enum Matcher {
+    Text { text: String, ignore_case: bool },
+    Regex(Regex),
+}
+
+impl Matcher {
+    fn is_match(&self, value: &str) -> bool {
+        match self {
+            Matcher::Text { text, ignore_case: false } => value.contains(text.as_str()),
+            Matcher::Text { text, ignore_case: true } => value.to_lowercase().contains(text.as_str()),
+            Matcher::Regex(regex) => regex.is_match(value),
+        }
+    }
+}
+
+/// Print the records having a value that contains, or matches, the pattern
+pub fn run(args: GrepArgs) -> Result<bool, String> {
+    let matcher = match args.regex {
+        true => Matcher::Regex(RegexBuilder::new(&args.pattern).case_insensitive(args.ignore_case).build().map_err(|e| e.to_string())?),
+        false if args.ignore_case => Matcher::Text {
+            text: args.pattern.to_lowercase(),
+            ignore_case: true,
+        },
+        false => Matcher::Text {
+            text: args.pattern.clone(),
+            ignore_case: false,
+        },
+    };
+    let matches = |record: &Record| -> bool {
+        let found = if args.specs.is_empty() {
+            record.control_fields.iter().any(|f| matcher.is_match(&f.value)) || record.data_fields.iter().flat_map(|f| &f.subfields).any(|s| matcher.is_match(&s.value))
+        } else {
+            args.specs.iter().any(|spec| spec.values(record).iter().any(|v| matcher.is_match(v)))
+        };
+        found != args.invert_match
+    };
+
+    let input = args.input.open()?;
+    let mut output = match &args.output {
+        Some(path) => Output::create(path)?,
+        None => Output::stdout(),
+    };
+    let error = |e: std::io::Error| e.to_string();
+
+    let mut count = 0;
+    if args.ids || args.count {
+        for (i, record) in input.records.enumerate() {
+            let record = record.map_err(|e| format!("Record #{}: {}", i + 1, e))?;
+            if !matches(&record) {
+                continue;
+            }
+            count += 1;
+            if args.ids {
+                match mapping::control_value(&record, "001") {
+                    Some(id) => writeln!(output, "{}", id).map_err(error)?,
+                    None => writeln!(output, "#{}", i + 1).map_err(error)?,
+                }
+            }
+        }
+        if args.count {
+            writeln!(output, "{}", count).map_err(error)?;
+        }
+        output.commit()?;
+    } else {
+        let mut sink = RecordSink::from_spec(output, &args.to)?;
+        for (i, record) in input.records.enumerate() {
+            let record = record.map_err(|e| format!("Record #{}: {}", i + 1, e))?;
+            if matches(&record) {
+                sink.write(record)?;
+                count += 1;
+            }
+        }
+        sink.finish()?.commit()?;
+    }
+    Ok(count > 0)
+}
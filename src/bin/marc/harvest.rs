@@ -0,0 +1,101 @@
+use crate::config::config;
+use crate::input::{parse_format_spec, FileFormat};
+use crate::output::{OutputArgs, RecordSink};
+use clap::Args;
+use marc_rs::*;
+
+#[derive(Args)]
+pub struct HarvestArgs {
+    /// Base URL of the OAI-PMH repository, e.g. https://example.org/oai
+    pub url: String,
+
+    #[command(flatten)]
+    pub output: OutputArgs,
+
+    /// Metadata format to request, which must be MARC XML
+    #[arg(long, value_name = "PREFIX", default_value = "marc21")]
+    pub prefix: String,
+
+    /// Only harvest the records of this set
+    #[arg(long)]
+    pub set: Option<String>,
+
+    /// Only harvest the records changed on or after this date, YYYY-MM-DD
+    #[arg(long, value_name = "DATE")]
+    pub from: Option<String>,
+
+    /// Only harvest the records changed on or before this date, YYYY-MM-DD
+    #[arg(long, value_name = "DATE")]
+    pub until: Option<String>,
+
+    /// Go on with an interrupted harvest from the resumption token it printed
+    #[arg(long, value_name = "TOKEN", conflicts_with_all = ["set", "from", "until"])]
+    pub resume: Option<String>,
+
+    /// Output format as FORMAT[:ENCODING] (default: from the output file extension)
+    #[arg(short, long, value_name = "FORMAT")]
+    pub to: Option<String>,
+}
+
+/// Harvest the records of a repository into a file
+///
+/// Deleted records are counted but not written. When the harvest fails midway,
+/// the records harvested so far are written and the resumption token to go on
+/// from is printed.
+pub fn run(args: HarvestArgs) -> Result<(), String> {
+    let (format, encoding) = match &args.to {
+        Some(spec) => parse_format_spec(spec)?,
+        None => (
+            FileFormat::from_path(args.output.path()).or(config().output_format).ok_or_else(|| format!("Cannot infer the output format of {}, use --to", args.output.path().display()))?,
+            None,
+        ),
+    };
+    let mut harvester = Harvester::new(&args.url).metadata_prefix(&args.prefix);
+    if let Some(set) = &args.set {
+        harvester = harvester.set(set);
+    }
+    if let Some(date) = &args.from {
+        harvester = harvester.from(date);
+    }
+    if let Some(date) = &args.until {
+        harvester = harvester.until(date);
+    }
+    if let Some(token) = &args.resume {
+        harvester = harvester.resume(token);
+    }
+    let mut sink = RecordSink::new(args.output.create()?, format, encoding);
+
+    let (mut count, mut deleted, mut skipped) = (0, 0, 0);
+    let mut failure = None;
+    for record in harvester.by_ref() {
+        match record {
+            Ok(OaiRecord { record: Some(record), identifier, .. }) => {
+                sink.write(record).map_err(|e| format!("{}: {}", identifier, e))?;
+                count += 1;
+            }
+            Ok(OaiRecord { deleted: true, .. }) => deleted += 1,
+            Ok(OaiRecord { identifier, .. }) => {
+                eprintln!("{}: no MARC XML metadata, skipped", identifier);
+                skipped += 1;
+            }
+            Err(e) => {
+                failure = Some(e);
+                break;
+            }
+        }
+    }
+    sink.finish()?.commit()?;
+
+    eprintln!("Harvested {} record(s)", count);
+    if deleted > 0 {
+        eprintln!("{} deleted record(s) not written", deleted);
+    }
+    if skipped > 0 {
+        eprintln!("{} record(s) skipped", skipped);
+    }
+    match (failure, harvester.resumption_token()) {
+        (None, _) => Ok(()),
+        (Some(e), Some(token)) => Err(format!("{}. Resume with --resume {:?}, writing to another file", e, token)),
+        (Some(e), None) => Err(e.to_string()),
+    }
+}
@@ -0,0 +1,117 @@
+use crate::input::{is_stdio, parse_format_spec, FileFormat, InputArgs};
+use crate::output::{Output, RecordSink};
+use clap::Args;
+use marc_rs::*;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::PathBuf;
+
+/// Size of the blocks read backwards looking for record terminators
+const BLOCK_SIZE: usize = 64 * 1024;
+
+#[derive(Args)]
+pub struct HeadArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// Number of records
+    #[arg(short = 'n', long, value_name = "N", default_value_t = 10)]
+    pub records: usize,
+
+    /// Output format as FORMAT[:ENCODING] (default: from the output file extension, or mrk on stdout)
+    #[arg(short, long, value_name = "FORMAT")]
+    pub to: Option<String>,
+
+    /// File to write, replaced only once complete (default: stdout)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+impl HeadArgs {
+    fn sink(&self) -> Result<RecordSink<Output>, String> {
+        let (format, encoding) = match (&self.to, &self.output) {
+            (Some(spec), _) => parse_format_spec(spec)?,
+            (None, Some(path)) if !is_stdio(path) => {
+                (FileFormat::from_path(path).ok_or_else(|| format!("Cannot infer the output format of {}, use --to", path.display()))?, None)
+            }
+            (None, _) => (FileFormat::Mrk, None),
+        };
+        let output = match &self.output {
+            Some(path) => Output::create(path)?,
+            None => Output::stdout(),
+        };
+        Ok(RecordSink::new(output, format, encoding))
+    }
+}
+
+/// Write the first records of a file, reading no further
+pub fn run_head(args: HeadArgs) -> Result<(), String> {
+    let input = args.input.open()?;
+    let mut sink = args.sink()?;
+    for (i, record) in input.records.take(args.records).enumerate() {
+        sink.write(record.map_err(|e| format!("Record #{}: {}", i + 1, e))?)?;
+    }
+    sink.finish()?.commit()
+}
+
+/// Write the last records of a file
+///
+/// Binary files are scanned backwards for record terminators, so only the last
+/// records are read. Other formats, and stdin, are read through, keeping the
+/// last records in memory.
+pub fn run_tail(args: HeadArgs) -> Result<(), String> {
+    let input = args.input.open()?;
+    let mut sink = args.sink()?;
+    if args.records == 0 {
+        return sink.finish()?.commit();
+    }
+
+    if matches!(input.format, FileFormat::Marc21 | FileFormat::Unimarc) && !is_stdio(&args.input.file) {
+        let path = &args.input.file;
+        let error = |e: std::io::Error| format!("Cannot read {}: {}", path.display(), e);
+        let mut file = File::open(path).map_err(error)?;
+        let start = tail_offset(&mut file, args.records).map_err(error)?;
+        file.seek(SeekFrom::Start(start)).map_err(error)?;
+        let format_encoding = FormatEncoding::new(input.format.marc_format().unwrap(), input.encoding);
+        for record in RecordReader::new(BufReader::new(file), format_encoding) {
+            sink.write(record.map_err(|e| format!("Record at byte {}: {}", start, e))?)?;
+        }
+        return sink.finish()?.commit();
+    }
+
+    let mut last = VecDeque::with_capacity(args.records);
+    for (i, record) in input.records.enumerate() {
+        if last.len() == args.records {
+            last.pop_front();
+        }
+        last.push_back(record.map_err(|e| format!("Record #{}: {}", i + 1, e))?);
+    }
+    for record in last {
+        sink.write(record)?;
+    }
+    sink.finish()?.commit()
+}
+
+/// Offset of the first of the last `n` records: just after the `n + 1`-th record terminator from the end
+fn tail_offset(file: &mut File, n: usize) -> std::io::Result<u64> {
+    let mut end = file.metadata()?.len();
+    let mut terminators = 0;
+    let mut block = vec![0u8; BLOCK_SIZE];
+    while end > 0 {
+        let start = end.saturating_sub(BLOCK_SIZE as u64);
+        let block = &mut block[..(end - start) as usize];
+        file.seek(SeekFrom::Start(start))?;
+        file.read_exact(block)?;
+        for (i, &byte) in block.iter().enumerate().rev() {
+            if byte == 0x1D {
+                terminators += 1;
+                if terminators > n {
+                    return Ok(start + i as u64 + 1);
+                }
+            }
+        }
+        end = start;
+    }
+    Ok(0)
+}
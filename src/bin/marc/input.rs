@@ -0,0 +1,292 @@
+use crate::config::config;
+use crate::progress::Progress;
+use clap::Args;
+use marc_rs::*;
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Cursor, Read};
+use std::path::{Path, PathBuf};
+
+/// Number of bytes read ahead to detect the input format
+const HEAD_SIZE: usize = 64;
+
+/// File formats read and written by the tool
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    /// Binary MARC21 (ISO 2709)
+    Marc21,
+    /// Binary UNIMARC (ISO 2709)
+    Unimarc,
+    /// MARC XML
+    Xml,
+    /// MARC-in-JSON: an array of records, or pretty-printed records one after another
+    Json,
+    /// MARC-in-JSON, one record per line
+    JsonLines,
+    /// MarcMaker text
+    Mrk,
+}
+
+impl FileFormat {
+    pub fn parse(format: &str) -> Result<Self, String> {
+        match format.to_lowercase().as_str() {
+            "marc21" | "marc" | "mrc" => Ok(FileFormat::Marc21),
+            "unimarc" => Ok(FileFormat::Unimarc),
+            "xml" | "marcxml" | "marc-xml" => Ok(FileFormat::Xml),
+            "json" => Ok(FileFormat::Json),
+            "jsonl" | "ndjson" => Ok(FileFormat::JsonLines),
+            "mrk" | "marcmaker" => Ok(FileFormat::Mrk),
+            _ => Err(format!("Unknown format: {}. Use: marc21, unimarc, xml, json, jsonl, or mrk", format)),
+        }
+    }
+
+    /// Format implied by a file extension; binary extensions stand for the configured binary format
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "mrc" | "marc" | "iso" | "iso2709" => Some(config().binary_format),
+            "xml" | "marcxml" => Some(FileFormat::Xml),
+            "json" => Some(FileFormat::Json),
+            "jsonl" | "ndjson" => Some(FileFormat::JsonLines),
+            "mrk" => Some(FileFormat::Mrk),
+            _ => None,
+        }
+    }
+
+    /// Extension of files written in this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            FileFormat::Marc21 | FileFormat::Unimarc => "mrc",
+            FileFormat::Xml => "xml",
+            FileFormat::Json => "json",
+            FileFormat::JsonLines => "jsonl",
+            FileFormat::Mrk => "mrk",
+        }
+    }
+
+    /// Format whose tags the records use: UNIMARC, or MARC21 for every other file format
+    pub fn record_format(&self) -> MarcFormat {
+        match self {
+            FileFormat::Unimarc => MarcFormat::Unimarc,
+            _ => MarcFormat::Marc21,
+        }
+    }
+
+    /// Library format of the binary and XML formats
+    pub fn marc_format(&self) -> Option<MarcFormat> {
+        match self {
+            FileFormat::Marc21 => Some(MarcFormat::Marc21),
+            FileFormat::Unimarc => Some(MarcFormat::Unimarc),
+            FileFormat::Xml => Some(MarcFormat::MarcXml),
+            FileFormat::Json | FileFormat::JsonLines | FileFormat::Mrk => None,
+        }
+    }
+}
+
+impl Display for FileFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileFormat::Marc21 => write!(f, "marc21"),
+            FileFormat::Unimarc => write!(f, "unimarc"),
+            FileFormat::Xml => write!(f, "xml"),
+            FileFormat::Json => write!(f, "json"),
+            FileFormat::JsonLines => write!(f, "jsonl"),
+            FileFormat::Mrk => write!(f, "mrk"),
+        }
+    }
+}
+
+/// Parse `FORMAT[:ENCODING]`, e.g. `marc21:marc8`
+pub fn parse_format_spec(spec: &str) -> Result<(FileFormat, Option<Encoding>), String> {
+    match spec.split_once(':') {
+        Some((format, encoding)) => Ok((FileFormat::parse(format)?, Some(parse_encoding(encoding)?))),
+        None => Ok((FileFormat::parse(spec)?, None)),
+    }
+}
+
+pub fn parse_encoding(encoding: &str) -> Result<Encoding, String> {
+    match encoding.to_lowercase().as_str() {
+        "utf8" | "utf-8" => Ok(Encoding::Utf8),
+        "marc8" | "marc-8" => Ok(Encoding::Marc8),
+        "iso8859-1" | "latin1" | "latin-1" => Ok(Encoding::Iso8859_1),
+        "iso8859-2" | "latin2" | "latin-2" => Ok(Encoding::Iso8859_2),
+        "iso8859-5" => Ok(Encoding::Iso8859_5),
+        "iso8859-7" => Ok(Encoding::Iso8859_7),
+        "iso8859-15" | "latin9" | "latin-9" => Ok(Encoding::Iso8859_15),
+        "iso5426" | "iso-5426" => Ok(Encoding::Iso5426),
+        _ => Err(format!("Unknown encoding: {}", encoding)),
+    }
+}
+
+/// Input file options shared by the subcommands
+#[derive(Args)]
+pub struct InputArgs {
+    /// MARC file to read, or - for stdin
+    pub file: PathBuf,
+
+    #[command(flatten)]
+    pub format: InputFormatArgs,
+}
+
+/// Format and encoding of the input files, detected unless given
+#[derive(Args)]
+pub struct InputFormatArgs {
+    /// Input format as FORMAT[:ENCODING]: marc21, unimarc, xml, json, jsonl or mrk (default: detected)
+    #[arg(short = 'f', long = "from", value_name = "FORMAT")]
+    pub from: Option<String>,
+
+    /// Input encoding: utf8, marc8, iso8859-1, ... (default: detected)
+    #[arg(short, long)]
+    pub encoding: Option<String>,
+}
+
+/// An opened input: its format and a lazy record iterator
+pub struct Input {
+    pub format: FileFormat,
+    pub encoding: Encoding,
+    pub records: Records,
+}
+
+/// An opened input whose records are left undecoded
+pub struct RawInput {
+    pub format: FileFormat,
+    pub encoding: Encoding,
+    pub reader: Box<dyn BufRead>,
+}
+
+impl InputArgs {
+    /// Open the file, detecting the format and encoding unless given
+    pub fn open(&self) -> Result<Input, String> {
+        self.format.open(&self.file)
+    }
+
+    /// Open the file, advancing `progress` as its bytes are read
+    pub fn open_with_progress(&self, progress: &Progress) -> Result<Input, String> {
+        self.format.open_with(&self.file, Some(progress))
+    }
+}
+
+impl InputFormatArgs {
+    /// Open a file, or stdin for `-`, detecting the format and encoding unless given
+    pub fn open(&self, path: &Path) -> Result<Input, String> {
+        self.open_with(path, None)
+    }
+
+    fn open_with(&self, path: &Path, progress: Option<&Progress>) -> Result<Input, String> {
+        let RawInput { format, encoding, reader } = self.open_raw(path, progress)?;
+        let records = match format.marc_format() {
+            Some(marc_format) => Records::Marc(RecordReader::new(reader, FormatEncoding::new(marc_format, encoding))),
+            None if format == FileFormat::Json => Records::Json(JsonReader::new(reader)),
+            None if format == FileFormat::JsonLines => Records::JsonLines(JsonLinesReader::new(reader)),
+            None => Records::Mrk(MrkReader::new(reader)),
+        };
+        Ok(Input { format, encoding, records })
+    }
+
+    /// Open a file without decoding its records, detecting the format and encoding unless given
+    pub fn open_raw(&self, path: &Path, progress: Option<&Progress>) -> Result<RawInput, String> {
+        let reader: Box<dyn Read> = match is_stdio(path) {
+            true => Box::new(std::io::stdin().lock()),
+            false => Box::new(File::open(path).map_err(|e| format!("Cannot open {}: {}", path.display(), e))?),
+        };
+        let mut reader: Box<dyn BufRead> = match progress {
+            Some(progress) => Box::new(BufReader::new(progress.wrap(reader))),
+            None => Box::new(BufReader::new(reader)),
+        };
+        // Read the head up front: a pipe may deliver it in several reads
+        let mut head = Vec::with_capacity(HEAD_SIZE);
+        (&mut reader).take(HEAD_SIZE as u64).read_to_end(&mut head).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+
+        let (format, spec_encoding) = match &self.from {
+            Some(spec) => parse_format_spec(spec)?,
+            None => (detect_format(&head)?, None),
+        };
+        let encoding = match (&self.encoding, spec_encoding) {
+            (Some(encoding), _) => parse_encoding(encoding)?,
+            (None, Some(encoding)) => encoding,
+            (None, None) => detect_encoding(&head, format),
+        };
+
+        let reader: Box<dyn BufRead> = Box::new(Cursor::new(head).chain(reader));
+        Ok(RawInput { format, encoding, reader })
+    }
+}
+
+/// True for the `-` path, standing for stdin or stdout
+pub fn is_stdio(path: &Path) -> bool {
+    path == Path::new("-")
+}
+
+/// Records of an input, whatever its format
+pub enum Records {
+    Marc(RecordReader<Box<dyn BufRead>>),
+    Json(JsonReader<Box<dyn BufRead>>),
+    JsonLines(JsonLinesReader<Box<dyn BufRead>>),
+    Mrk(MrkReader<Box<dyn BufRead>>),
+}
+
+impl Records {
+    /// Skip the next `n` records, without decoding binary and XML ones
+    pub fn skip_records(&mut self, n: usize) -> Result<usize, ParseError> {
+        match self {
+            Records::Marc(records) => records.skip_records(n),
+            _ => {
+                for skipped in 0..n {
+                    match self.next() {
+                        Some(Ok(_)) => {}
+                        Some(Err(e)) => return Err(e),
+                        None => return Ok(skipped),
+                    }
+                }
+                Ok(n)
+            }
+        }
+    }
+}
+
+impl Iterator for Records {
+    type Item = Result<Record, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Records::Marc(records) => records.next(),
+            Records::Json(records) => records.next(),
+            Records::JsonLines(records) => records.next(),
+            Records::Mrk(records) => records.next(),
+        }
+    }
+}
+
+/// Detect the format from the first bytes of the input; binary input is of the configured binary format
+fn detect_format(head: &[u8]) -> Result<FileFormat, String> {
+    let head = head.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(head).trim_ascii_start();
+    if head.starts_with(b"<") {
+        Ok(FileFormat::Xml)
+    } else if head.starts_with(b"[") {
+        Ok(FileFormat::Json)
+    } else if head.starts_with(b"{") {
+        // A JSON Lines record rarely ends within the head; a line break there is a pretty-printed record
+        // (or short JSON Lines records, which the JSON reader reads as well)
+        match head.contains(&b'\n') {
+            true => Ok(FileFormat::Json),
+            false => Ok(FileFormat::JsonLines),
+        }
+    } else if head.starts_with(b"=LDR") {
+        Ok(FileFormat::Mrk)
+    } else if head.len() >= 24 {
+        Ok(config().binary_format)
+    } else {
+        Err("Cannot detect format. Please specify format explicitly.".to_string())
+    }
+}
+
+/// UTF-8, except for MARC21 whose leader does not say so (position 9 `a`): MARC-8, or the configured encoding
+fn detect_encoding(head: &[u8], format: FileFormat) -> Encoding {
+    match format {
+        FileFormat::Marc21 => match head.get(9) {
+            Some(b'a') => Encoding::Utf8,
+            _ => config().input_encoding,
+        },
+
+        FileFormat::Unimarc | FileFormat::Xml | FileFormat::Json | FileFormat::JsonLines | FileFormat::Mrk => Encoding::Utf8,
+    }
+}
@@ -0,0 +1,131 @@
+use crate::input::InputArgs;
+use crate::progress::ProgressArgs;
+use crate::status::Status;
+use crate::validate::{report, ReportArgs};
+use clap::Args;
+use marc_rs::*;
+use std::path::Path;
+use toml::{Table, Value};
+
+#[derive(Args)]
+pub struct LintArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// Rule set: marc21-minimal, unimarc-minimal, structure, or a TOML rule file; repeatable (default: the minimal set of the input format)
+    #[arg(short, long = "rules", value_name = "RULES")]
+    pub rules: Vec<String>,
+
+    #[command(flatten)]
+    pub report: ReportArgs,
+
+    #[command(flatten)]
+    pub progress: ProgressArgs,
+}
+
+/// Built-in rule set, or the rules of a TOML file
+///
+/// ```toml
+/// extends = "marc21-minimal"   # optional
+///
+/// [[rule]]
+/// required = "245$c"
+/// severity = "warning"         # default: error
+///
+/// [[rule]]
+/// values = ["a", "c", "m"]
+/// spec = "LDR/06"
+/// ```
+///
+/// Each rule has one of `required`, `forbidden`, `not-repeatable` (specs),
+/// `values` (with a `spec`), `isbn = true` or `structure = true`.
+fn load_rules(name: &str) -> Result<RuleSet, String> {
+    if let Some(rules) = RuleSet::named(name) {
+        return Ok(rules);
+    }
+    let path = Path::new(name);
+    if path.extension().is_none_or(|extension| extension != "toml") {
+        return Err(format!("Unknown rule set: {}. Use: {}, or a .toml file", name, RuleSet::NAMES.join(", ")));
+    }
+    let text = std::fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    let table: Table = text.parse().map_err(|e| format!("{}: {}", path.display(), e))?;
+    let error = |message: String| format!("{}: {}", path.display(), message);
+
+    let mut rules = match table.get("extends") {
+        Some(Value::String(name)) => RuleSet::named(name).ok_or_else(|| error(format!("unknown rule set {:?}", name)))?,
+        Some(_) => return Err(error("extends must be the name of a rule set".to_string())),
+        None => RuleSet::default(),
+    };
+    match table.get("rule") {
+        Some(Value::Array(array)) => {
+            for (i, value) in array.iter().enumerate() {
+                let rule = value.as_table().ok_or("not a table".to_string()).and_then(parse_rule).map_err(|e| error(format!("rule #{}: {}", i + 1, e)))?;
+                rules.rules.push(rule);
+            }
+        }
+        Some(_) => return Err(error("rules must be [[rule]] tables".to_string())),
+        None => {}
+    }
+    Ok(rules)
+}
+
+fn parse_rule(table: &Table) -> Result<Rule, String> {
+    let string = |key: &str| match table.get(key) {
+        Some(Value::String(value)) => Ok(Some(value.as_str())),
+        Some(_) => Err(format!("{} must be a string", key)),
+        None => Ok(None),
+    };
+    let spec = |key: &str| string(key)?.map(MarcSpec::parse).transpose();
+    let severity = match string("severity")? {
+        None | Some("error") => Severity::Error,
+        Some("warning") => Severity::Warning,
+        Some(other) => return Err(format!("unknown severity {:?}. Use: error or warning", other)),
+    };
+
+    let mut kinds = Vec::new();
+    if let Some(spec) = spec("required")? {
+        kinds.push(RuleKind::Required(spec));
+    }
+    if let Some(spec) = spec("forbidden")? {
+        kinds.push(RuleKind::Forbidden(spec));
+    }
+    if let Some(spec) = spec("not-repeatable")? {
+        kinds.push(RuleKind::NotRepeatable(spec));
+    }
+    if let Some(values) = table.get("values") {
+        let values = values.as_array().and_then(|values| values.iter().map(|v| v.as_str().map(str::to_string)).collect::<Option<Vec<_>>>()).ok_or("values must be a list of strings")?;
+        kinds.push(RuleKind::Values(spec("spec")?.ok_or("values need a spec")?, values));
+    }
+    if table.get("isbn").and_then(Value::as_bool) == Some(true) {
+        kinds.push(RuleKind::Isbn);
+    }
+    if table.get("structure").and_then(Value::as_bool) == Some(true) {
+        kinds.push(RuleKind::Structure);
+    }
+
+    match kinds.len() {
+        1 => Ok(Rule::new(kinds.remove(0), severity)),
+        0 => Err("expected one of required, forbidden, not-repeatable, values, isbn or structure".to_string()),
+        _ => Err("expected a single check per rule".to_string()),
+    }
+}
+
+/// Check every record against rule sets
+pub fn run(args: LintArgs) -> Result<Status, String> {
+    let progress = args.progress.bar(&args.input.file);
+    let input = args.input.open_with_progress(&progress)?;
+    let format = input.format.record_format();
+
+    let mut rules = RuleSet::default();
+    if args.rules.is_empty() {
+        let name = match format {
+            MarcFormat::Unimarc => "unimarc-minimal",
+            _ => "marc21-minimal",
+        };
+        rules = load_rules(name)?;
+    }
+    for name in &args.rules {
+        rules.rules.extend(load_rules(name)?.rules);
+    }
+    report(input, &args.report, &progress, |record| rules.check(record, format))
+}
@@ -0,0 +1,213 @@
+//! `marc` command-line tool
+
+mod completions;
+mod config;
+mod convert;
+mod count;
+mod dedup;
+mod diff;
+mod edit;
+mod encoding_report;
+mod explode;
+mod extract;
+mod filter;
+mod fixup;
+mod grep;
+#[cfg(feature = "oai")]
+mod harvest;
+mod head;
+mod input;
+mod lint;
+mod merge;
+mod output;
+mod progress;
+mod reencode;
+mod sample;
+mod sort;
+mod split;
+#[cfg(feature = "sru")]
+mod sru;
+mod stats;
+mod status;
+mod style;
+mod validate;
+mod view;
+mod watch;
+#[cfg(feature = "z3950")]
+mod z3950;
+
+use clap::{CommandFactory, Parser, Subcommand};
+use status::Status;
+use std::process::ExitCode;
+
+/// Exit codes of the commands, listed under the global help
+const EXIT_CODES: &str = "\
+Exit codes:
+  validate, lint  0 clean, 1 warnings only, 2 errors in records
+  convert         0 clean, 1 characters lost when decoding, 2 records skipped
+  fixup           0 nothing to repair, 1 records repaired, 2 records not recovered
+  watch --once    0 clean, 2 the subcommand failed on some files
+  grep            0 records selected, 1 no record selected
+  any command     3 fatal error (invalid arguments, unreadable input, ...); otherwise 0";
+
+/// Inspect and process MARC files
+#[derive(Parser)]
+#[command(name = "marc", version, about, after_help = EXIT_CODES)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Display the records of a file
+    View(view::ViewArgs),
+    /// Convert a file to another format or encoding
+    Convert(convert::ConvertArgs),
+    /// Print a shell completion script
+    ///
+    /// e.g. marc completions bash > ~/.local/share/bash-completion/completions/marc
+    Completions(completions::CompletionsArgs),
+    /// Count the records of a file quickly, without decoding them
+    Count(count::CountArgs),
+    /// Drop duplicate records, matched on identifiers or match keys
+    Dedup(dedup::DedupArgs),
+    /// Compare two files record by record
+    Diff(diff::DiffArgs),
+    /// Apply a patch or a script of edits to every record
+    ///
+    /// Scripts have one operation per line; blank lines and lines starting with # are ignored,
+    /// and double quotes protect spaces ("" for a quote):
+    ///
+    ///   add "=TAG  DATA"              add a field, written as a MarcMaker line
+    ///   delete SPEC                   delete fields (650, 9..) or subfields (245$h)
+    ///   replace SPEC FIND REPLACEMENT replace text in values; /FIND/ is a regular expression
+    ///   copy SPEC TAG$c               add a TAG field with a $c subfield for each value of SPEC
+    ///   move SPEC TAG$c               copy, then delete the fields or subfields of SPEC
+    ///
+    /// Any operation may end with "if CONDITION", as for --filter. Deletions and replacements
+    /// check a condition on their own tag against each field: "delete 650 if 650$2=fast" only
+    /// deletes the FAST headings.
+    ///
+    /// Patch files hold the same operations as [[operation]] tables in TOML, or as an
+    /// "operation" list in JSON:
+    ///
+    ///   [[operation]]
+    ///   replace = "020$a"
+    ///   find = "/[^0-9X]/"
+    ///   with = ""
+    ///   if = "020"
+    #[command(verbatim_doc_comment)]
+    Edit(edit::EditArgs),
+    /// Guess the encoding of each record, to choose the --encoding of a file before converting it
+    EncodingReport(encoding_report::EncodingReportArgs),
+    /// Write each record to its own file, named after its 001 or its number
+    Explode(explode::ExplodeArgs),
+    /// Export fields of every record as CSV
+    Extract(extract::ExtractArgs),
+    /// Repair structurally broken binary records: lengths, directory, terminators and leader constants
+    ///
+    /// Exits with 0 when no record needed repairs, 1 when some were repaired and 2 when some could not be recovered.
+    Fixup(fixup::FixupArgs),
+    /// Print the records containing a text or matching a regular expression
+    ///
+    /// Exits with 0 when records were selected and 1 otherwise.
+    Grep(grep::GrepArgs),
+    /// Harvest the records of an OAI-PMH repository into a file
+    ///
+    /// Follows resumption tokens until the whole list is harvested; --from, --until and --set select the records.
+    #[cfg(feature = "oai")]
+    Harvest(harvest::HarvestArgs),
+    /// Print the first records of a file
+    Head(head::HeadArgs),
+    /// Check records against cataloguing rules: built-in rule sets or TOML rule files
+    ///
+    /// Exits with 0 when no issue is found, 1 when only warnings are found, 2 on errors and 3 when the input cannot be read.
+    Lint(lint::LintArgs),
+    /// Concatenate files into one output, optionally dropping duplicates
+    Merge(merge::MergeArgs),
+    /// Convert a binary file to another character encoding
+    Reencode(reencode::ReencodeArgs),
+    /// Write records drawn at random from a file, e.g. to review a sample of a large load
+    Sample(sample::SampleArgs),
+    /// Sort the records of a file on a key
+    Sort(sort::SortArgs),
+    /// Split a file into several files, by record count, size or field value
+    Split(split::SplitArgs),
+    /// Search an SRU server with a CQL query and save the matching records
+    #[cfg(feature = "sru")]
+    Sru(sru::SruArgs),
+    /// Summarize a file: record types, field frequencies, sizes and encoding warnings
+    Stats(stats::StatsArgs),
+    /// Print the last records of a file
+    Tail(head::HeadArgs),
+    /// Check records against the format definitions
+    ///
+    /// Exits with 0 when no issue is found, 1 when only warnings are found, 2 on errors and 3 when the input cannot be read.
+    Validate(validate::ValidateArgs),
+    /// Run a subcommand on every MARC file arriving in a directory
+    ///
+    /// e.g. marc watch incoming --move-to done --exec convert {} -o converted/{stem}.xml
+    ///
+    /// With --once, exits with 2 when the subcommand failed on some files.
+    Watch(watch::WatchArgs),
+    /// Search a Z39.50 target with a PQF query and save the matching records
+    #[cfg(feature = "z3950")]
+    Z3950(z3950::Z3950Args),
+}
+
+fn main() -> ExitCode {
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        Err(e) => {
+            let _ = e.print();
+            // Help and version requests are not errors
+            return match e.use_stderr() {
+                true => Status::Fatal.into(),
+                false => ExitCode::SUCCESS,
+            };
+        }
+    };
+
+    if let Err(e) = config::load() {
+        eprintln!("Error: {}", e);
+        return Status::Fatal.into();
+    }
+
+    let result = match cli.command {
+        Command::View(args) => view::run(args).map(|_| ExitCode::SUCCESS),
+        Command::Convert(args) => convert::run(args).map(ExitCode::from),
+        Command::Completions(args) => completions::run(args, Cli::command()).map(|_| ExitCode::SUCCESS),
+        Command::Count(args) => count::run(args).map(|_| ExitCode::SUCCESS),
+        Command::Dedup(args) => dedup::run(args).map(|_| ExitCode::SUCCESS),
+        Command::Diff(args) => diff::run(args).map(|_| ExitCode::SUCCESS),
+        Command::Edit(args) => edit::run(args).map(|_| ExitCode::SUCCESS),
+        Command::EncodingReport(args) => encoding_report::run(args).map(|_| ExitCode::SUCCESS),
+        Command::Explode(args) => explode::run(args).map(|_| ExitCode::SUCCESS),
+        Command::Extract(args) => extract::run(args).map(|_| ExitCode::SUCCESS),
+        Command::Fixup(args) => fixup::run(args).map(ExitCode::from),
+        Command::Grep(args) => grep::run(args).map(|found| if found { ExitCode::SUCCESS } else { ExitCode::from(1) }),
+        #[cfg(feature = "oai")]
+        Command::Harvest(args) => harvest::run(args).map(|_| ExitCode::SUCCESS),
+        Command::Head(args) => head::run_head(args).map(|_| ExitCode::SUCCESS),
+        Command::Lint(args) => lint::run(args).map(ExitCode::from),
+        Command::Merge(args) => merge::run(args).map(|_| ExitCode::SUCCESS),
+        Command::Reencode(args) => reencode::run(args).map(|_| ExitCode::SUCCESS),
+        Command::Sample(args) => sample::run(args).map(|_| ExitCode::SUCCESS),
+        Command::Sort(args) => sort::run(args).map(|_| ExitCode::SUCCESS),
+        Command::Split(args) => split::run(args).map(|_| ExitCode::SUCCESS),
+        #[cfg(feature = "sru")]
+        Command::Sru(args) => sru::run(args).map(|_| ExitCode::SUCCESS),
+        Command::Stats(args) => stats::run(args).map(|_| ExitCode::SUCCESS),
+        Command::Tail(args) => head::run_tail(args).map(|_| ExitCode::SUCCESS),
+        Command::Validate(args) => validate::run(args).map(ExitCode::from),
+        Command::Watch(args) => watch::run(args).map(ExitCode::from),
+        #[cfg(feature = "z3950")]
+        Command::Z3950(args) => z3950::run(args).map(|_| ExitCode::SUCCESS),
+    };
+
+    result.unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        Status::Fatal.into()
+    })
+}
@@ -0,0 +1,80 @@
+use crate::filter::FilterArgs;
+use crate::input::{parse_format_spec, FileFormat, InputFormatArgs};
+use crate::output::{Output, RecordSink};
+use clap::Args;
+use marc_rs::*;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct MergeArgs {
+    /// MARC files to read, in order, - for stdin; their formats may differ
+    #[arg(required = true)]
+    pub files: Vec<PathBuf>,
+
+    #[command(flatten)]
+    pub format: InputFormatArgs,
+
+    /// File to write, replaced only once complete (default: stdout)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Output format as FORMAT[:ENCODING] (default: from the output file extension, or the format of the first input)
+    #[arg(short, long, value_name = "FORMAT")]
+    pub to: Option<String>,
+
+    /// Drop records whose 001 or one of whose 035$a was already written
+    #[arg(long)]
+    pub dedup: bool,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
+}
+
+pub fn run(args: MergeArgs) -> Result<(), String> {
+    let first = args.format.open(&args.files[0])?;
+    let (format, encoding) = match (&args.to, args.output.as_ref().and_then(|path| FileFormat::from_path(path))) {
+        (Some(spec), _) => parse_format_spec(spec)?,
+        (None, Some(format)) => (format, None),
+        (None, None) => (first.format, Some(first.encoding)),
+    };
+
+    let output = match &args.output {
+        Some(path) => Output::create(path)?,
+        None => Output::stdout(),
+    };
+    let mut sink = RecordSink::new(output, format, encoding);
+
+    let mut seen: HashSet<String> = HashSet::new();
+    let (mut count, mut duplicates) = (0, 0);
+    let mut input = Some(first);
+    for path in &args.files {
+        let records = match input.take() {
+            Some(first) => first.records,
+            None => args.format.open(path)?.records,
+        };
+        for (i, record) in records.enumerate() {
+            let record = record.map_err(|e| format!("{}: record #{}: {}", path.display(), i + 1, e))?;
+            if !args.filter.matches(&record) {
+                continue;
+            }
+            if args.dedup {
+                let keys = record.dedup_keys(&[DedupKey::ControlNumber, DedupKey::SystemNumber], MarcFormat::Marc21);
+                if keys.iter().any(|key| seen.contains(key)) {
+                    duplicates += 1;
+                    continue;
+                }
+                seen.extend(keys);
+            }
+            sink.write(record).map_err(|e| format!("{}: record #{}: {}", path.display(), i + 1, e))?;
+            count += 1;
+        }
+    }
+    sink.finish()?.commit()?;
+
+    match args.dedup {
+        true => eprintln!("Merged {} record(s) from {} file(s), {} duplicate(s) dropped", count, args.files.len(), duplicates),
+        false => eprintln!("Merged {} record(s) from {} file(s)", count, args.files.len()),
+    }
+    Ok(())
+}
@@ -0,0 +1,210 @@
+use crate::config::config;
+use crate::input::{is_stdio, parse_format_spec, FileFormat};
+use clap::Args;
+use marc_rs::*;
+use std::fs::File;
+use std::io::{BufWriter, StdoutLock, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Once;
+
+/// Message of an error writing to the output
+///
+/// A closed stdout, e.g. when piped into `head`, ends the command normally.
+pub fn write_error(e: std::io::Error) -> String {
+    if e.kind() == std::io::ErrorKind::BrokenPipe {
+        std::process::exit(0);
+    }
+    e.to_string()
+}
+
+/// Output file of the commands writing records or reports
+#[derive(Args)]
+pub struct OutputArgs {
+    /// File to write, replaced only once complete (default: stdout)
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<PathBuf>,
+
+    /// Deprecated positional form of --output
+    #[arg(value_name = "OUTPUT", hide = true, conflicts_with = "output")]
+    positional: Option<PathBuf>,
+}
+
+impl OutputArgs {
+    /// File to write, `-` for stdout
+    pub fn path(&self) -> &Path {
+        if self.positional.is_some() {
+            static WARNING: Once = Once::new();
+            WARNING.call_once(|| eprintln!("Warning: the positional output file is deprecated, use -o/--output"));
+        }
+        self.output.as_deref().or(self.positional.as_deref()).unwrap_or(Path::new("-"))
+    }
+
+    /// Open the output for writing
+    pub fn create(&self) -> Result<Output, String> {
+        Output::create(self.path())
+    }
+}
+
+/// Destination of a command: stdout, or a file written atomically
+///
+/// A file is written to a hidden temporary file next to it, renamed over the
+/// path by [`Output::commit`]. A failed or interrupted run leaves any existing
+/// file untouched rather than truncated.
+pub enum Output {
+    Stdout(StdoutLock<'static>),
+    File { writer: BufWriter<File>, temp: TempPath, path: PathBuf },
+}
+
+/// Temporary file removed when dropped, unless kept
+pub struct TempPath(Option<PathBuf>);
+
+impl TempPath {
+    pub fn new(path: PathBuf) -> Self {
+        TempPath(Some(path))
+    }
+
+    pub fn path(&self) -> &Path {
+        self.0.as_deref().unwrap()
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        if let Some(path) = self.0.take() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+impl Output {
+    /// Write to stdout
+    pub fn stdout() -> Self {
+        Output::Stdout(std::io::stdout().lock())
+    }
+
+    /// Write to a file, or to stdout for `-`
+    pub fn create(path: &Path) -> Result<Self, String> {
+        if is_stdio(path) {
+            return Ok(Self::stdout());
+        }
+        let name = path.file_name().ok_or_else(|| format!("Cannot create {}: not a file name", path.display()))?;
+        let temp = path.with_file_name(format!(".{}.{}.tmp", name.to_string_lossy(), std::process::id()));
+        let file = File::create(&temp).map_err(|e| format!("Cannot create {}: {}", path.display(), e))?;
+        Ok(Output::File {
+            writer: BufWriter::new(file),
+            temp: TempPath::new(temp),
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// Flush the output and move the file into place
+    pub fn commit(self) -> Result<(), String> {
+        match self {
+            Output::Stdout(mut stdout) => stdout.flush().map_err(write_error),
+            Output::File { writer, mut temp, path } => {
+                let error = |e: std::io::Error| format!("Cannot write {}: {}", path.display(), e);
+                let file = writer.into_inner().map_err(|e| error(e.into_error()))?;
+                file.sync_all().map_err(error)?;
+                let temp_path = temp.0.take().unwrap();
+                if let Err(e) = std::fs::rename(&temp_path, &path) {
+                    let _ = std::fs::remove_file(&temp_path);
+                    return Err(error(e));
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Output::Stdout(stdout) => stdout.write(buf),
+            Output::File { writer, .. } => writer.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Output::Stdout(stdout) => stdout.flush(),
+            Output::File { writer, .. } => writer.flush(),
+        }
+    }
+}
+
+/// Writer of records in any output format, one record at a time
+pub enum RecordSink<W: Write> {
+    Marc(RecordWriter<W>),
+    /// A JSON array, opened before the first record
+    Json { output: W, records: usize },
+    JsonLines(W),
+    Mrk(W),
+}
+
+impl<W: Write> RecordSink<W> {
+    /// Create a sink for `FORMAT[:ENCODING]`
+    pub fn from_spec(output: W, spec: &str) -> Result<Self, String> {
+        let (format, encoding) = parse_format_spec(spec)?;
+        Ok(Self::new(output, format, encoding))
+    }
+
+    /// Create a sink; binary output defaults to UTF-8, or the configured encoding
+    pub fn new(output: W, format: FileFormat, encoding: Option<Encoding>) -> Self {
+        match format.marc_format() {
+            Some(marc_format) => RecordSink::Marc(RecordWriter::new(output, FormatEncoding::new(marc_format, encoding.unwrap_or(config().output_encoding)))),
+            None if format == FileFormat::Json => RecordSink::Json { output, records: 0 },
+            None if format == FileFormat::JsonLines => RecordSink::JsonLines(output),
+            None => RecordSink::Mrk(output),
+        }
+    }
+
+    /// Write one record, updating leader position 9 to the output encoding
+    pub fn write(&mut self, record: Record) -> Result<(), String> {
+        self.try_write(record).map_err(|e| e.to_string())
+    }
+
+    /// Write one record; binary records failing with other than an I/O error are not written at all
+    ///
+    /// Leader position 9 is set to `a` for UTF-8 output (MARCXML and the text
+    /// formats always are), and blank for binary MARC21 in another encoding.
+    /// Binary UNIMARC leaves it unchanged.
+    pub fn try_write(&mut self, mut record: Record) -> Result<(), WriteError> {
+        let coding = match self {
+            RecordSink::Marc(writer) => match writer.format_encoding() {
+                FormatEncoding { format: MarcFormat::Marc21, encoding } => Some(if encoding == Encoding::Utf8 { 'a' } else { ' ' }),
+                FormatEncoding { format: MarcFormat::MarcXml, .. } => Some('a'),
+                FormatEncoding { format: MarcFormat::Unimarc, .. } => None,
+            },
+            _ => Some('a'),
+        };
+        if let Some(coding) = coding {
+            record.leader.character_coding_scheme = coding;
+        }
+        match self {
+            RecordSink::Marc(writer) => writer.write(&record),
+            RecordSink::Json { output, records } => {
+                output.write_all(if *records == 0 { b"[\n" } else { b",\n" })?;
+                *records += 1;
+                Ok(serde_json::to_writer(&mut *output, &MarcJson(&record))?)
+            }
+            RecordSink::JsonLines(output) => serde_marc::to_jsonl_writer(std::iter::once(&record), output),
+            RecordSink::Mrk(output) => Ok(output.write_all(record.to_mrk().as_bytes())?),
+        }
+    }
+
+    /// Close and flush the output
+    pub fn finish(self) -> Result<W, String> {
+        match self {
+            RecordSink::Marc(writer) => writer.finish().map_err(|e| e.to_string()),
+            RecordSink::Json { mut output, records } => {
+                output.write_all(if records == 0 { b"[]\n" } else { b"\n]\n" }).map_err(|e| e.to_string())?;
+                output.flush().map_err(|e| e.to_string())?;
+                Ok(output)
+            }
+            RecordSink::JsonLines(mut output) | RecordSink::Mrk(mut output) => {
+                output.flush().map_err(|e| e.to_string())?;
+                Ok(output)
+            }
+        }
+    }
+}
@@ -0,0 +1,71 @@
+//! Progress bar of the commands reading a whole file
+
+use crate::config::config;
+use clap::Args;
+use indicatif::{ProgressBar, ProgressStyle};
+use std::io::Read;
+use std::path::Path;
+
+/// Progress options shared by the long-running subcommands
+#[derive(Args)]
+pub struct ProgressArgs {
+    /// Do not show a progress bar (never shown when stderr is not a terminal)
+    #[arg(long)]
+    pub no_progress: bool,
+}
+
+impl ProgressArgs {
+    /// Bar over the bytes of `path`, or a spinner counting bytes for stdin
+    pub fn bar(&self, path: &Path) -> Progress {
+        if self.no_progress || !config().progress {
+            return Progress(ProgressBar::hidden());
+        }
+        // The default target, stderr, draws nothing unless it is a terminal
+        let bar = match std::fs::metadata(path) {
+            Ok(metadata) if metadata.is_file() => ProgressBar::new(metadata.len()).with_style(
+                ProgressStyle::with_template("{elapsed_precise} [{wide_bar}] {bytes}/{total_bytes} ({eta} left)").unwrap().progress_chars("=> "),
+            ),
+            _ => ProgressBar::new_spinner().with_style(ProgressStyle::with_template("{spinner} {elapsed_precise} {bytes} read").unwrap()),
+        };
+        Progress(bar)
+    }
+
+    /// Bar counting files, advanced by [`Progress::inc`]
+    pub fn files(&self, total: u64) -> Progress {
+        if self.no_progress || !config().progress {
+            return Progress(ProgressBar::hidden());
+        }
+        Progress(ProgressBar::new(total).with_style(ProgressStyle::with_template("{elapsed_precise} [{wide_bar}] {pos}/{len} files ({eta} left)").unwrap().progress_chars("=> ")))
+    }
+}
+
+/// Progress of the input, advanced as its bytes are read
+pub struct Progress(ProgressBar);
+
+impl Progress {
+    /// Reader advancing the bar by the bytes read through it
+    pub fn wrap<R: Read>(&self, reader: R) -> impl Read {
+        self.0.wrap_read(reader)
+    }
+
+    /// Advance the bar by one file
+    pub fn inc(&self) {
+        self.0.inc(1);
+    }
+
+    /// Run `f`, printing to the terminal, with the bar hidden
+    pub fn suspend<T>(&self, f: impl FnOnce() -> T) -> T {
+        self.0.suspend(f)
+    }
+
+    /// Remove the bar, before printing a summary
+    pub fn finish(&self) {
+        self.0.finish_and_clear();
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        self.0.finish_and_clear();
+    }
+}
@@ -0,0 +1,63 @@
+use crate::input::{parse_encoding, FileFormat, InputArgs};
+use crate::output::{OutputArgs, RecordSink};
+use clap::Args;
+use marc_rs::*;
+
+#[derive(Args)]
+pub struct ReencodeArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    #[command(flatten)]
+    pub output: OutputArgs,
+
+    /// Output encoding: utf8, marc8, iso8859-1, ...
+    #[arg(short, long, value_name = "ENCODING")]
+    pub to: String,
+
+    /// Character written in place of those the output encoding cannot represent
+    #[arg(long, default_value = "?")]
+    pub replacement: char,
+}
+
+/// Rewrite a binary file in another encoding, updating leader/09 for MARC21
+///
+/// Records that lose characters are listed on stderr: characters the output
+/// encoding cannot represent, and replacement characters (U+FFFD) left by
+/// decoding the input.
+pub fn run(args: ReencodeArgs) -> Result<(), String> {
+    let encoding = parse_encoding(&args.to)?;
+    let input = args.input.open()?;
+    if !matches!(input.format, FileFormat::Marc21 | FileFormat::Unimarc) {
+        return Err(format!("{} files are always UTF-8, only binary MARC21 and UNIMARC can be reencoded", input.format));
+    }
+
+    let mut sink = RecordSink::new(args.output.create()?, input.format, Some(encoding));
+
+    let (mut count, mut lossy) = (0, 0);
+    for (i, record) in input.records.enumerate() {
+        let mut record = record.map_err(|e| format!("Record #{}: {}", i + 1, e))?;
+        let undecoded = record.control_fields.iter().map(|f| &f.value).chain(record.data_fields.iter().flat_map(|f| f.subfields.iter().map(|s| &s.value))).map(|v| v.matches('\u{FFFD}').count()).sum::<usize>();
+        let replaced = record.replace_unencodable(encoding, args.replacement);
+
+        if undecoded + replaced > 0 {
+            lossy += 1;
+            let id = mapping::control_value(&record, "001").map(|id| format!(" ({})", id)).unwrap_or_default();
+            let mut problems = Vec::new();
+            if undecoded > 0 {
+                problems.push(format!("{} character(s) could not be decoded from {}", undecoded, input.encoding));
+            }
+            if replaced > 0 {
+                problems.push(format!("{} character(s) cannot be encoded in {}", replaced, encoding));
+            }
+            eprintln!("Record #{}{}: {}", i + 1, id, problems.join(", "));
+        }
+
+        sink.write(record).map_err(|e| format!("Record #{}: {}", i + 1, e))?;
+        count += 1;
+    }
+    sink.finish()?.commit()?;
+
+    eprintln!("Reencoded {} record(s) from {} to {}, {} lossy", count, input.encoding, encoding, lossy);
+    Ok(())
+}
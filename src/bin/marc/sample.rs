@@ -0,0 +1,91 @@
+use crate::input::{parse_format_spec, FileFormat, InputArgs};
+use crate::output::{OutputArgs, RecordSink};
+use crate::progress::ProgressArgs;
+use clap::Args;
+use std::time::SystemTime;
+
+#[derive(Args)]
+pub struct SampleArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    #[command(flatten)]
+    pub output: OutputArgs,
+
+    /// Number of records to draw
+    #[arg(short = 'n', long, value_name = "N")]
+    pub records: usize,
+
+    /// Seed of the random draw, to draw the same records again (default: printed at the end)
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Output format as FORMAT[:ENCODING] (default: from the output file extension, or the input format)
+    #[arg(short, long, value_name = "FORMAT")]
+    pub to: Option<String>,
+
+    #[command(flatten)]
+    pub progress: ProgressArgs,
+}
+
+/// SplitMix64: small and good enough to draw records, and reproducible across platforms
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Number in `0..n`
+    fn below(&mut self, n: usize) -> usize {
+        ((self.next() as u128 * n as u128) >> 64) as usize
+    }
+}
+
+/// Write records drawn at random, in file order
+///
+/// Reservoir sampling: the file is read once, keeping only the records drawn so
+/// far, so every record has the same chance of being drawn whatever the size of
+/// the file.
+pub fn run(args: SampleArgs) -> Result<(), String> {
+    let progress = args.progress.bar(&args.input.file);
+    let input = args.input.open_with_progress(&progress)?;
+    let (format, encoding) = match (&args.to, FileFormat::from_path(args.output.path())) {
+        (Some(spec), _) => parse_format_spec(spec)?,
+        (None, Some(format)) => (format, None),
+        (None, None) => (input.format, Some(input.encoding)),
+    };
+    let seed = args.seed.unwrap_or_else(|| SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).map_or(0, |d| d.as_nanos() as u64) ^ std::process::id() as u64);
+    let mut random = Random(seed);
+
+    let mut drawn = Vec::with_capacity(args.records);
+    let mut count = 0;
+    for (i, record) in input.records.enumerate() {
+        let record = record.map_err(|e| format!("Record #{}: {}", i + 1, e))?;
+        if drawn.len() < args.records {
+            drawn.push((i, record));
+        } else {
+            let j = random.below(i + 1);
+            if j < args.records {
+                drawn[j] = (i, record);
+            }
+        }
+        count += 1;
+    }
+    progress.finish();
+
+    drawn.sort_by_key(|(i, _)| *i);
+    let sampled = drawn.len();
+    let mut sink = RecordSink::new(args.output.create()?, format, encoding);
+    for (i, record) in drawn {
+        sink.write(record).map_err(|e| format!("Record #{}: {}", i + 1, e))?;
+    }
+    sink.finish()?.commit()?;
+
+    eprintln!("Sampled {} of {} record(s) (seed {})", sampled, count, seed);
+    Ok(())
+}
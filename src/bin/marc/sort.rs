@@ -0,0 +1,196 @@
+use crate::input::{parse_format_spec, FileFormat, InputArgs};
+use crate::output::{OutputArgs, RecordSink, TempPath};
+use crate::split::parse_size;
+use clap::Args;
+use marc_rs::*;
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Lines, Write};
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct SortArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    #[command(flatten)]
+    pub output: OutputArgs,
+
+    /// Sort key: a MARCspec such as 001, 005 or "245$a", or "title" for the title in filing form
+    #[arg(short, long, value_name = "KEY", default_value = "001", value_parser = SortKey::parse)]
+    pub key: SortKey,
+
+    /// Compare keys as numbers; keys that are not numbers sort after the others
+    #[arg(short, long)]
+    pub numeric: bool,
+
+    /// Sort in descending order
+    #[arg(short, long)]
+    pub reverse: bool,
+
+    /// Memory used for records before sorted runs are spilled to disk, e.g. 500M
+    #[arg(long, value_name = "SIZE", default_value = "256M", value_parser = parse_size)]
+    pub memory: u64,
+
+    /// Directory of the spilled runs (default: the system temporary directory)
+    #[arg(long, value_name = "DIR")]
+    pub temp_dir: Option<PathBuf>,
+
+    /// Output format as FORMAT[:ENCODING] (default: from the output file extension, or the input format)
+    #[arg(short, long, value_name = "FORMAT")]
+    pub to: Option<String>,
+}
+
+/// What records are sorted on
+#[derive(Clone)]
+pub enum SortKey {
+    /// First value of a MARCspec
+    Spec(MarcSpec),
+    /// [`Record::sort_title`], ignoring case
+    Title,
+}
+
+impl SortKey {
+    fn parse(key: &str) -> Result<Self, String> {
+        match key {
+            "title" => Ok(SortKey::Title),
+            spec => MarcSpec::parse(spec).map(SortKey::Spec).map_err(|e| e.to_string()),
+        }
+    }
+
+    fn value(&self, record: &Record, format: MarcFormat) -> Option<String> {
+        match self {
+            SortKey::Spec(spec) => spec.first_value(record),
+            SortKey::Title => record.sort_title(format).map(|title| title.to_lowercase()),
+        }
+    }
+}
+
+/// Sort a file on a key, records without a key last, keeping the input order of equal keys
+///
+/// Records are sorted in memory up to `--memory`; larger inputs are written as
+/// sorted runs to temporary files, then merged.
+pub fn run(args: SortArgs) -> Result<(), String> {
+    let input = args.input.open()?;
+    let (format, encoding) = match (&args.to, FileFormat::from_path(args.output.path())) {
+        (Some(spec), _) => parse_format_spec(spec)?,
+        (None, Some(format)) => (format, None),
+        (None, None) => (input.format, Some(input.encoding)),
+    };
+    let marc_format = input.format.record_format();
+    let compare = |a: &Option<String>, b: &Option<String>| compare_keys(a, b, args.numeric, args.reverse);
+
+    let mut runs: Vec<TempPath> = Vec::new();
+    let mut records: Vec<(Option<String>, Record)> = Vec::new();
+    let mut size = 0;
+    let mut count = 0;
+    for (i, record) in input.records.enumerate() {
+        let record = record.map_err(|e| format!("Record #{}: {}", i + 1, e))?;
+        size += record_size(&record);
+        records.push((args.key.value(&record, marc_format), record));
+        count += 1;
+        if size >= args.memory {
+            records.sort_by(|a, b| compare(&a.0, &b.0));
+            runs.push(write_run(&args, runs.len(), &records)?);
+            records.clear();
+            size = 0;
+        }
+    }
+    records.sort_by(|a, b| compare(&a.0, &b.0));
+
+    let mut sink = RecordSink::new(args.output.create()?, format, encoding);
+    if runs.is_empty() {
+        for (_, record) in records {
+            sink.write(record)?;
+        }
+    } else {
+        if !records.is_empty() {
+            runs.push(write_run(&args, runs.len(), &records)?);
+        }
+        let mut readers: Vec<RunReader> = runs.iter().map(RunReader::open).collect::<Result<_, _>>()?;
+        let mut heads: Vec<Option<(Option<String>, Record)>> = readers.iter_mut().map(|reader| reader.next()).collect::<Result<_, _>>()?;
+        // Take the smallest head, the earliest run first on equal keys
+        loop {
+            let mut smallest: Option<usize> = None;
+            for (i, head) in heads.iter().enumerate() {
+                if let Some((key, _)) = head {
+                    if smallest.is_none_or(|j| compare(key, &heads[j].as_ref().unwrap().0) == Ordering::Less) {
+                        smallest = Some(i);
+                    }
+                }
+            }
+            let Some(i) = smallest else {
+                break;
+            };
+            let (_, record) = std::mem::replace(&mut heads[i], readers[i].next()?).unwrap();
+            sink.write(record)?;
+        }
+    }
+    sink.finish()?.commit()?;
+
+    match runs.len() {
+        0 => eprintln!("Sorted {} record(s)", count),
+        n => eprintln!("Sorted {} record(s) in {} run(s) on disk", count, n),
+    }
+    Ok(())
+}
+
+/// Compare sort keys; missing keys, and non-numeric ones with `numeric`, come last in both orders
+fn compare_keys(a: &Option<String>, b: &Option<String>, numeric: bool, reverse: bool) -> Ordering {
+    let order = |ordering: Ordering| if reverse { ordering.reverse() } else { ordering };
+    match (a, b) {
+        (Some(a), Some(b)) if numeric => match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+            (Ok(x), Ok(y)) => order(x.total_cmp(&y)),
+            (Ok(_), Err(_)) => Ordering::Less,
+            (Err(_), Ok(_)) => Ordering::Greater,
+            (Err(_), Err(_)) => order(a.cmp(b)),
+        },
+        (Some(a), Some(b)) => order(a.cmp(b)),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// Approximate memory used by a record
+fn record_size(record: &Record) -> u64 {
+    let control: usize = record.control_fields.iter().map(|f| 64 + f.value.len()).sum();
+    let data: usize = record.data_fields.iter().map(|f| 64 + f.subfields.iter().map(|s| 32 + s.value.len()).sum::<usize>()).sum();
+    (128 + control + data) as u64
+}
+
+/// Write a sorted run as JSON lines of `[key, record]`
+fn write_run(args: &SortArgs, index: usize, records: &[(Option<String>, Record)]) -> Result<TempPath, String> {
+    let dir = args.temp_dir.clone().unwrap_or_else(std::env::temp_dir);
+    let run = TempPath::new(dir.join(format!("marc-sort-{}-{}.jsonl", std::process::id(), index)));
+    let error = |e: std::io::Error| format!("Cannot write {}: {}", run.path().display(), e);
+    let mut output = BufWriter::new(File::create(run.path()).map_err(error)?);
+    for entry in records {
+        serde_json::to_writer(&mut output, entry).map_err(|e| e.to_string())?;
+        output.write_all(b"\n").map_err(error)?;
+    }
+    output.flush().map_err(error)?;
+    Ok(run)
+}
+
+/// Entries of a sorted run, in order
+struct RunReader {
+    lines: Lines<BufReader<File>>,
+}
+
+impl RunReader {
+    fn open(run: &TempPath) -> Result<Self, String> {
+        let file = File::open(run.path()).map_err(|e| format!("Cannot open {}: {}", run.path().display(), e))?;
+        Ok(RunReader { lines: BufReader::new(file).lines() })
+    }
+
+    fn next(&mut self) -> Result<Option<(Option<String>, Record)>, String> {
+        match self.lines.next() {
+            Some(line) => {
+                let line = line.map_err(|e| e.to_string())?;
+                serde_json::from_str(&line).map(Some).map_err(|e| e.to_string())
+            }
+            None => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use crate::filter::FilterArgs;
+use crate::input::{is_stdio, parse_format_spec, InputArgs};
+use crate::output::{Output, RecordSink};
+use clap::Args;
+use marc_rs::*;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+#[derive(Args)]
+pub struct SplitArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// Start a new file every N records
+    #[arg(short = 'n', long, value_name = "N", required_unless_present_any = ["size", "by"], conflicts_with_all = ["size", "by"])]
+    pub records: Option<usize>,
+
+    /// Start a new file once SIZE bytes were written, e.g. 500K or 10M
+    #[arg(long, value_parser = parse_size, conflicts_with = "by")]
+    pub size: Option<u64>,
+
+    /// Write records to one file per value of SPEC, e.g. 003 or "852$b"
+    #[arg(long, value_name = "SPEC", value_parser = MarcSpec::parse)]
+    pub by: Option<MarcSpec>,
+
+    /// Directory of the output files
+    #[arg(short, long, default_value = ".")]
+    pub dir: PathBuf,
+
+    /// Start of the output file names (default: the input file name without extension, or "split" for stdin)
+    #[arg(short, long)]
+    pub prefix: Option<String>,
+
+    /// Output format as FORMAT[:ENCODING] (default: the input format and encoding)
+    #[arg(short, long, value_name = "FORMAT")]
+    pub to: Option<String>,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
+}
+
+/// Writer counting the bytes written through it
+struct CountingWriter<W: Write> {
+    inner: W,
+    count: Rc<Cell<u64>>,
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.count.set(self.count.get() + written as u64);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// An output file being written
+struct Chunk {
+    sink: RecordSink<CountingWriter<Output>>,
+    bytes: Rc<Cell<u64>>,
+    records: usize,
+}
+
+/// Split a file into numbered files (`PREFIX-0001.mrc`, ...) or one file per value (`PREFIX-VALUE.mrc`)
+///
+/// Records without a value for `--by` go to `PREFIX-none`. The created files are
+/// listed on stdout.
+pub fn run(args: SplitArgs) -> Result<(), String> {
+    let input = args.input.open()?;
+    let (format, encoding) = match &args.to {
+        Some(spec) => parse_format_spec(spec)?,
+        None => (input.format, Some(input.encoding)),
+    };
+    let prefix = match &args.prefix {
+        Some(prefix) => prefix.clone(),
+        None if is_stdio(&args.input.file) => "split".to_string(),
+        None => args.input.file.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_else(|| "split".to_string()),
+    };
+    std::fs::create_dir_all(&args.dir).map_err(|e| format!("Cannot create {}: {}", args.dir.display(), e))?;
+
+    let create = |name: &str| -> Result<Chunk, String> {
+        let path = args.dir.join(format!("{}-{}.{}", prefix, name, format.extension()));
+        let file = Output::create(&path)?;
+        println!("{}", path.display());
+        let bytes = Rc::new(Cell::new(0));
+        let output = CountingWriter {
+            inner: file,
+            count: bytes.clone(),
+        };
+        Ok(Chunk {
+            sink: RecordSink::new(output, format, encoding),
+            bytes,
+            records: 0,
+        })
+    };
+
+    let mut count = 0;
+    let mut files = 0;
+    let mut current: Option<Chunk> = None;
+    let mut by_value: HashMap<String, Chunk> = HashMap::new();
+    for (i, record) in input.records.enumerate() {
+        let record = record.map_err(|e| format!("Record #{}: {}", i + 1, e))?;
+        if !args.filter.matches(&record) {
+            continue;
+        }
+        count += 1;
+
+        let chunk = match &args.by {
+            Some(spec) => {
+                let name = spec.first_value(&record).map(|v| file_name_part(&v)).unwrap_or_else(|| "none".to_string());
+                if !by_value.contains_key(&name) {
+                    by_value.insert(name.clone(), create(&name)?);
+                    files += 1;
+                }
+                by_value.get_mut(&name).unwrap()
+            }
+            None => {
+                let full = current.as_ref().is_some_and(|chunk| match (args.records, args.size) {
+                    (Some(n), _) => chunk.records >= n,
+                    (None, Some(size)) => chunk.bytes.get() >= size,
+                    (None, None) => false,
+                });
+                if full {
+                    current.take().unwrap().sink.finish()?.inner.commit()?;
+                }
+                if current.is_none() {
+                    files += 1;
+                    current = Some(create(&format!("{:04}", files))?);
+                }
+                current.as_mut().unwrap()
+            }
+        };
+        chunk.sink.write(record).map_err(|e| format!("Record #{}: {}", i + 1, e))?;
+        chunk.records += 1;
+    }
+
+    for chunk in current.into_iter().chain(by_value.into_values()) {
+        chunk.sink.finish()?.inner.commit()?;
+    }
+    eprintln!("Split {} record(s) into {} file(s)", count, files);
+    Ok(())
+}
+
+/// Parse a byte count with an optional K, M or G suffix (powers of 1024)
+pub fn parse_size(size: &str) -> Result<u64, String> {
+    let upper = size.trim().to_uppercase();
+    let upper = upper.strip_suffix('B').unwrap_or(&upper);
+    let (number, multiplier) = match upper.char_indices().last() {
+        Some((i, 'K')) => (&upper[..i], 1 << 10),
+        Some((i, 'M')) => (&upper[..i], 1 << 20),
+        Some((i, 'G')) => (&upper[..i], 1 << 30),
+        _ => (upper, 1),
+    };
+    match number.trim().parse::<u64>() {
+        Ok(n) if n > 0 => Ok(n * multiplier),
+        _ => Err(format!("Invalid size: {}", size)),
+    }
+}
+
+/// Value usable in a file name: letters, digits, `-`, `_` and `.`, other characters replaced by `_`
+pub fn file_name_part(value: &str) -> String {
+    let part: String = value.trim().chars().map(|c| if c.is_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '_' }).collect();
+    match part.trim_start_matches('.') {
+        "" => "none".to_string(),
+        part => part.to_string(),
+    }
+}
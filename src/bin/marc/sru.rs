@@ -0,0 +1,84 @@
+use crate::config::config;
+use crate::input::{parse_format_spec, FileFormat};
+use crate::output::{OutputArgs, RecordSink};
+use clap::Args;
+use marc_rs::*;
+
+#[derive(Args)]
+pub struct SruArgs {
+    /// Base URL of the SRU server, e.g. https://lx2.loc.gov/sru/voyager
+    pub url: String,
+
+    /// CQL query, e.g. 'dc.title="the hobbit" and dc.creator=tolkien'
+    pub query: String,
+
+    #[command(flatten)]
+    pub output: OutputArgs,
+
+    /// Output format as FORMAT[:ENCODING] (default: from the output file extension)
+    #[arg(short, long, value_name = "FORMAT")]
+    pub to: Option<String>,
+
+    /// Save at most N records
+    #[arg(short = 'n', long, value_name = "N")]
+    pub limit: Option<usize>,
+
+    /// Position of the first result to save, from 1
+    #[arg(long, value_name = "N", default_value_t = 1)]
+    pub start: usize,
+
+    /// Records requested per page
+    #[arg(long, value_name = "N", default_value_t = 50)]
+    pub page_size: usize,
+
+    /// Record schema to request, which must be MARC XML
+    #[arg(long, value_name = "SCHEMA", default_value = "marcxml")]
+    pub schema: String,
+
+    /// SRU version to request
+    #[arg(long = "sru-version", value_name = "VERSION", default_value = "1.1")]
+    pub version: String,
+}
+
+/// Save the records matching a query
+///
+/// When the search fails midway, the records fetched so far are written before
+/// the error is reported.
+pub fn run(args: SruArgs) -> Result<(), String> {
+    let (format, encoding) = match &args.to {
+        Some(spec) => parse_format_spec(spec)?,
+        None => (
+            FileFormat::from_path(args.output.path()).or(config().output_format).ok_or_else(|| format!("Cannot infer the output format of {}, use --to", args.output.path().display()))?,
+            None,
+        ),
+    };
+    let page_size = args.limit.map_or(args.page_size, |limit| limit.min(args.page_size));
+    let mut search = SruSearch::new(&args.url, &args.query).version(&args.version).record_schema(&args.schema).page_size(page_size).start(args.start);
+    let mut sink = RecordSink::new(args.output.create()?, format, encoding);
+
+    let mut count = 0;
+    let mut failure = None;
+    for record in search.by_ref().take(args.limit.unwrap_or(usize::MAX)) {
+        match record {
+            Ok(record) => {
+                sink.write(record).map_err(|e| format!("Record #{}: {}", args.start + count, e))?;
+                count += 1;
+            }
+            Err(e) => {
+                failure = Some(e);
+                break;
+            }
+        }
+    }
+    sink.finish()?.commit()?;
+
+    match search.number_of_records() {
+        Some(total) => eprintln!("Saved {} of {} record(s) matching the query", count, total),
+        None => eprintln!("Saved {} record(s)", count),
+    }
+    match failure {
+        Some(e) if count > 0 => Err(format!("{}. Go on with --start {}", e, args.start + count)),
+        Some(e) => Err(e.to_string()),
+        None => Ok(()),
+    }
+}
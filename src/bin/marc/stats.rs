@@ -0,0 +1,127 @@
+use crate::filter::FilterArgs;
+use crate::input::{FileFormat, InputArgs};
+use crate::output::{write_error, Output};
+use crate::progress::ProgressArgs;
+use clap::Args;
+use marc_rs::*;
+use std::io::Write;
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct StatsArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
+
+    #[command(flatten)]
+    pub progress: ProgressArgs,
+
+    /// Also list the indicator values and subfield codes of each field
+    #[arg(short, long)]
+    pub subfields: bool,
+
+    /// Report format: plain, or json (the whole profile as one object)
+    #[arg(long = "format", default_value = "plain")]
+    pub report: String,
+
+    /// File to write, replaced only once complete (default: stdout)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+pub fn run(args: StatsArgs) -> Result<(), String> {
+    let json = match args.report.as_str() {
+        "plain" => false,
+        "json" => true,
+        other => return Err(format!("Unknown report format: {}. Use: plain or json", other)),
+    };
+    let progress = args.progress.bar(&args.input.file);
+    let input = args.input.open_with_progress(&progress)?;
+    // Sizes are ISO 2709 lengths, in the input encoding for binary files
+    let mut profile = match input.format.marc_format() {
+        Some(format @ (MarcFormat::Marc21 | MarcFormat::Unimarc)) => Profile::new(FormatEncoding::new(format, input.encoding)),
+        _ => Profile::new(FormatEncoding::marc_xml()),
+    };
+
+    for (i, record) in input.records.enumerate() {
+        let record = record.map_err(|e| format!("Record #{}: {}", i + 1, e))?;
+        if args.filter.matches(&record) {
+            profile.add(&record);
+        }
+    }
+
+    progress.finish();
+    let mut out = match &args.output {
+        Some(path) => Output::create(path)?,
+        None => Output::stdout(),
+    };
+    write_profile(&mut out, &args, json, (input.format, input.encoding), &profile).map_err(write_error)?;
+    out.commit()
+}
+
+/// Write the plain or JSON report of a profile
+fn write_profile(out: &mut impl Write, args: &StatsArgs, json: bool, (format, encoding): (FileFormat, Encoding), profile: &Profile) -> std::io::Result<()> {
+    let marc_format = format.record_format();
+    if json {
+        serde_json::to_writer_pretty(&mut *out, profile)?;
+        return writeln!(out);
+    }
+    writeln!(out, "File: {}", args.input.file.display())?;
+    writeln!(out, "Format: {}, Encoding: {}", format, encoding)?;
+    writeln!(out, "Records: {}", profile.records)?;
+    if profile.records == 0 {
+        return Ok(());
+    }
+    if let Some(average) = profile.sizes.average() {
+        writeln!(out, "Record size: min {}, max {}, average {} bytes", profile.sizes.min, profile.sizes.max, average)?;
+    }
+
+    writeln!(out)?;
+    writeln!(out, "RECORD TYPES (leader/06-07)")?;
+    for (codes, n) in &profile.record_types {
+        let mut chars = codes.chars();
+        let name = match (marc_format, chars.next(), chars.next()) {
+            (MarcFormat::Unimarc, _, _) => "",
+            (_, Some(record_type), Some(level)) => MaterialConfiguration::from_codes(record_type, level).map(|c| c.name()).unwrap_or("Unknown"),
+            _ => "Unknown",
+        };
+        writeln!(out, "  {}  {:>8}  {}", codes, n, name)?;
+    }
+
+    writeln!(out)?;
+    writeln!(out, "FIELDS")?;
+    writeln!(out, "  Tag  {:>8}  {:>11}", "Records", "Occurrences")?;
+    for (tag, counts) in &profile.tags {
+        let name = field_definition(tag, marc_format).map(|d| d.name).unwrap_or("");
+        writeln!(out, "  {}  {:>8}  {:>11}  {}", tag, counts.records, counts.occurrences, name)?;
+        if !args.subfields {
+            continue;
+        }
+        let indicators = |values: &std::collections::BTreeMap<char, usize>| values.iter().map(|(c, n)| format!("'{}' {}", c, n)).collect::<Vec<_>>().join(", ");
+        if !counts.ind1.is_empty() {
+            writeln!(out, "       ind1: {}", indicators(&counts.ind1))?;
+            writeln!(out, "       ind2: {}", indicators(&counts.ind2))?;
+        }
+        for (code, subfield) in &counts.subfields {
+            writeln!(out, "       ${}  {:>8}  {:>11}  length {}-{}, average {}", code, subfield.records, subfield.occurrences, subfield.lengths.min, subfield.lengths.max, subfield.lengths.average().unwrap_or_default())?;
+        }
+    }
+
+    let warnings = &profile.encoding;
+    let lines = [
+        (warnings.replacement_characters, "with replacement characters (U+FFFD)".to_string()),
+        (warnings.not_nfc, "with values not in Unicode NFC".to_string()),
+        (warnings.leader_mismatch, format!("whose leader/09 does not match the {} encoding", encoding)),
+        (warnings.not_encodable, format!("that cannot be encoded in {}", encoding)),
+    ];
+    if lines.iter().any(|(n, _)| *n > 0) {
+        writeln!(out)?;
+        writeln!(out, "ENCODING WARNINGS")?;
+        for (n, description) in lines.iter().filter(|(n, _)| *n > 0) {
+            writeln!(out, "  {} record(s) {}", n, description)?;
+        }
+    }
+    Ok(())
+}
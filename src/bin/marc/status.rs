@@ -0,0 +1,33 @@
+//! Exit codes, so that scripts can branch on data quality without parsing the output
+
+use std::process::ExitCode;
+
+/// Outcome of a command, from best to worst
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Status {
+    /// Nothing to report: exit code 0
+    Clean,
+    /// Warnings only: exit code 1
+    Warnings,
+    /// Records with errors, reported or skipped: exit code 2
+    Errors,
+    /// The command could not run to the end (invalid arguments, unreadable input, I/O error): exit code 3
+    Fatal,
+}
+
+impl Status {
+    /// Status of a run that found these numbers of errors and warnings
+    pub fn from_counts(errors: usize, warnings: usize) -> Self {
+        match (errors, warnings) {
+            (0, 0) => Status::Clean,
+            (0, _) => Status::Warnings,
+            _ => Status::Errors,
+        }
+    }
+}
+
+impl From<Status> for ExitCode {
+    fn from(status: Status) -> Self {
+        ExitCode::from(status as u8)
+    }
+}
@@ -0,0 +1,53 @@
+//! ANSI colors of the plain-text output
+
+use crate::config::config;
+use std::io::IsTerminal;
+
+/// Colors of the plain-text output, or none
+#[derive(Debug, Clone, Copy)]
+pub struct Style {
+    color: bool,
+}
+
+impl Style {
+    /// Without colors
+    pub fn plain() -> Self {
+        Style { color: false }
+    }
+
+    /// Colors when stdout is a terminal, unless disabled by `--no-color`, the `NO_COLOR` variable or the configuration
+    pub fn detect(no_color: bool) -> Self {
+        let disabled = no_color || !config().color || std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        Style {
+            color: !disabled && std::io::stdout().is_terminal(),
+        }
+    }
+
+    pub fn heading(&self, text: &str) -> String {
+        self.paint("1", text)
+    }
+
+    pub fn tag(&self, text: &str) -> String {
+        self.paint("1;36", text)
+    }
+
+    pub fn indicators(&self, text: &str) -> String {
+        self.paint("33", text)
+    }
+
+    pub fn code(&self, text: &str) -> String {
+        self.paint("32", text)
+    }
+
+    /// Annotations such as field names
+    pub fn note(&self, text: &str) -> String {
+        self.paint("2", text)
+    }
+
+    fn paint(&self, code: &str, text: &str) -> String {
+        match self.color {
+            true => format!("\x1b[{}m{}\x1b[0m", code, text),
+            false => text.to_string(),
+        }
+    }
+}
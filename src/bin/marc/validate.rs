@@ -0,0 +1,108 @@
+use crate::config::config;
+use crate::input::{Input, InputArgs};
+use crate::output::{write_error, Output};
+use crate::progress::{Progress, ProgressArgs};
+use crate::status::Status;
+use clap::Args;
+use marc_rs::*;
+use std::io::Write;
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct ValidateArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    #[command(flatten)]
+    pub report: ReportArgs,
+
+    #[command(flatten)]
+    pub progress: ProgressArgs,
+}
+
+/// Issue report options shared by validate and lint
+#[derive(Args)]
+pub struct ReportArgs {
+    /// Report format: plain, or json (one object per line for each record with issues)
+    #[arg(long, default_value = "plain")]
+    pub format: String,
+
+    /// Only report errors
+    #[arg(short, long)]
+    pub quiet: bool,
+
+    /// File to write, replaced only once complete (default: stdout)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+/// Validate every record
+pub fn run(args: ValidateArgs) -> Result<Status, String> {
+    let progress = args.progress.bar(&args.input.file);
+    let input = args.input.open_with_progress(&progress)?;
+    let validator = Validator::new(input.format.record_format()).local_fields(config().local_fields.clone());
+    report(input, &args.report, &progress, |record| validator.validate(record))
+}
+
+/// Report the issues `check` finds in every record
+pub fn report(input: Input, args: &ReportArgs, progress: &Progress, check: impl Fn(&Record) -> Vec<Issue>) -> Result<Status, String> {
+    let json = match args.format.as_str() {
+        "plain" => false,
+        "json" => true,
+        other => return Err(format!("Unknown report format: {}. Use: plain or json", other)),
+    };
+
+    let mut output = match &args.output {
+        Some(path) => Output::create(path)?,
+        None => Output::stdout(),
+    };
+
+    let (mut count, mut errors, mut warnings) = (0, 0, 0);
+    for record in input.records {
+        count += 1;
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                errors += 1;
+                progress
+                    .suspend(|| match json {
+                        true => writeln!(output, "{}", serde_json::json!({ "record": count, "error": e })),
+                        false => writeln!(output, "Record #{}: {}", count, e),
+                    })
+                    .map_err(write_error)?;
+                break;
+            }
+        };
+
+        let issues: Vec<Issue> = check(&record).into_iter().filter(|issue| !args.quiet || issue.severity == Severity::Error).collect();
+        if issues.is_empty() {
+            continue;
+        }
+        errors += issues.iter().filter(|issue| issue.severity == Severity::Error).count();
+        warnings += issues.iter().filter(|issue| issue.severity == Severity::Warning).count();
+
+        let id = mapping::control_value(&record, "001");
+        progress
+            .suspend(|| {
+                if json {
+                    return writeln!(output, "{}", serde_json::json!({ "record": count, "id": id, "issues": issues }));
+                }
+                match id {
+                    Some(id) => writeln!(output, "Record #{} ({})", count, id)?,
+                    None => writeln!(output, "Record #{}", count)?,
+                }
+                for issue in &issues {
+                    writeln!(output, "  {:<7} {}: {}", issue.severity, issue.location, issue.message)?;
+                }
+                Ok(())
+            })
+            .map_err(write_error)?;
+    }
+    progress.finish();
+
+    if !json {
+        writeln!(output, "{} record(s), {} error(s), {} warning(s)", count, errors, warnings).map_err(write_error)?;
+    }
+    output.commit()?;
+    Ok(Status::from_counts(errors, warnings))
+}
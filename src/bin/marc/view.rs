@@ -0,0 +1,158 @@
+use crate::config::config;
+use crate::filter::{FilterArgs, RangeArgs};
+use crate::input::InputArgs;
+use crate::output::{Output, RecordSink};
+use crate::style::Style;
+use clap::Args;
+use marc_rs::*;
+use std::io::Write;
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct ViewArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// Output: plain, or FORMAT[:ENCODING] with FORMAT one of marc21, unimarc, xml, json, jsonl or mrk
+    #[arg(short, long, default_value = "plain", value_name = "FORMAT")]
+    pub to: String,
+
+    /// File to write, replaced only once complete (default: stdout)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Plain output without colors (also disabled when not writing to a terminal, or by NO_COLOR)
+    #[arg(long)]
+    pub no_color: bool,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
+
+    #[command(flatten)]
+    pub range: RangeArgs,
+}
+
+pub fn run(args: ViewArgs) -> Result<(), String> {
+    let mut input = args.input.open()?;
+    args.range.skip(&mut input.records)?;
+    let output = match &args.output {
+        Some(path) => Output::create(path)?,
+        None => Output::stdout(),
+    };
+
+    if args.to != "plain" {
+        let mut sink = RecordSink::from_spec(output, &args.to)?;
+        let mut count = 0;
+        for (i, record) in (args.range.skip..).zip(input.records) {
+            let record = record.map_err(|e| format!("Record #{}: {}", i + 1, e))?;
+            if !args.filter.matches(&record) {
+                continue;
+            }
+            sink.write(record)?;
+            count += 1;
+            if args.range.reached(count) {
+                break;
+            }
+        }
+        return sink.finish()?.commit();
+    }
+
+    let style = match args.output {
+        Some(_) => Style::plain(),
+        None => Style::detect(args.no_color),
+    };
+    let marc_format = input.format.record_format();
+    let mut out = output;
+    let error = |e: std::io::Error| e.to_string();
+    writeln!(out, "File: {}", args.input.file.display()).map_err(error)?;
+    writeln!(out, "Format: {}, Encoding: {}", input.format, input.encoding).map_err(error)?;
+    writeln!(out, "{}", "=".repeat(80)).map_err(error)?;
+
+    let mut count = 0;
+    for (i, record) in (args.range.skip..).zip(input.records) {
+        let record = record.map_err(|e| format!("Record #{}: {}", i + 1, e))?;
+        if !args.filter.matches(&record) {
+            continue;
+        }
+        count += 1;
+        writeln!(out, "{}", "─".repeat(80)).map_err(error)?;
+        writeln!(out, "{}", style.heading(&format!("Record #{}", i + 1))).map_err(error)?;
+        writeln!(out, "{}", "─".repeat(80)).map_err(error)?;
+        display_record(&mut out, &record, marc_format, style).map_err(error)?;
+        writeln!(out).map_err(error)?;
+        if args.range.reached(count) {
+            break;
+        }
+    }
+    match count {
+        0 if args.filter.is_active() => eprintln!("No matching records."),
+        0 => eprintln!("No records found in file."),
+        _ => writeln!(out, "{} record(s)", count).map_err(error)?,
+    }
+    out.commit()
+}
+
+/// Widest field content the field names are aligned after
+const MAX_ALIGNED_WIDTH: usize = 60;
+
+fn display_record(out: &mut impl Write, record: &Record, format: MarcFormat, style: Style) -> std::io::Result<()> {
+    let leader = &record.leader;
+    writeln!(out, "{}", style.heading("LEADER"))?;
+    let leader_values: [(&str, &dyn std::fmt::Display); 11] = [
+        ("Record Length", &leader.record_length),
+        ("Status", &leader.record_status),
+        ("Type", &leader.record_type),
+        ("Bibliographic Level", &leader.bibliographic_level),
+        ("Type of Control", &leader.type_of_control),
+        ("Character Coding Scheme", &leader.character_coding_scheme),
+        ("Indicator Count", &leader.indicator_count),
+        ("Subfield Code Count", &leader.subfield_code_count),
+        ("Base Address", &leader.base_address_of_data),
+        ("Encoding Level", &leader.encoding_level),
+        ("Descriptive Cataloging Form", &leader.descriptive_cataloging_form),
+    ];
+    for (label, value) in leader_values {
+        writeln!(out, "  {:<29}{}", format!("{}:", label), value)?;
+    }
+    writeln!(out)?;
+
+    // Each field as its tag, then its content as plain text (to measure it) and styled
+    let indicator = |c: char| if c == ' ' { '_' } else { c };
+    let control: Vec<(&str, String, String)> = record.control_fields.iter().map(|f| (f.tag.as_str(), format!("   {}", f.value), format!("   {}", f.value))).collect();
+    let data: Vec<(&str, String, String)> = record
+        .data_fields
+        .iter()
+        .map(|f| {
+            let indicators = format!("{}{}", indicator(f.ind1), indicator(f.ind2));
+            let plain: Vec<String> = f.subfields.iter().map(|s| format!("${}{}", s.code, s.value)).collect();
+            let styled: Vec<String> = f.subfields.iter().map(|s| format!("{}{}", style.code(&format!("${}", s.code)), s.value)).collect();
+            (f.tag.as_str(), format!("{} {}", indicators, plain.join(" ")), format!("{} {}", style.indicators(&indicators), styled.join(" ")))
+        })
+        .collect();
+    let width = control.iter().chain(&data).map(|(_, plain, _)| plain.chars().count()).filter(|&w| w <= MAX_ALIGNED_WIDTH).max().unwrap_or(0);
+
+    let write_fields = |out: &mut dyn Write, fields: &[(&str, String, String)]| -> std::io::Result<()> {
+        for (tag, plain, styled) in fields {
+            match config().local_fields.field_label(tag, format) {
+                Some(label) => {
+                    let padding = width.saturating_sub(plain.chars().count());
+                    writeln!(out, "  {} {}{}  {}", style.tag(tag), styled, " ".repeat(padding), style.note(label))?
+                }
+                None => writeln!(out, "  {} {}", style.tag(tag), styled)?,
+            }
+        }
+        Ok(())
+    };
+
+    if !control.is_empty() {
+        writeln!(out, "{}", style.heading("CONTROL FIELDS"))?;
+        write_fields(out, &control)?;
+        writeln!(out)?;
+    }
+    if data.is_empty() {
+        writeln!(out, "{}", style.heading("DATA FIELDS: (none)"))?;
+        return Ok(());
+    }
+    writeln!(out, "{}", style.heading("DATA FIELDS"))?;
+    write_fields(out, &data)
+}
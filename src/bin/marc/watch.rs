@@ -0,0 +1,122 @@
+use crate::input::FileFormat;
+use crate::status::Status;
+use clap::Args;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, SystemTime};
+
+#[derive(Args)]
+pub struct WatchArgs {
+    /// Directory to watch
+    pub dir: PathBuf,
+
+    /// Subcommand run for each new file, with its arguments, after every other option: {} stands for the file path, {stem} for its name without extension
+    #[arg(long, value_name = "COMMAND", num_args = 1.., allow_hyphen_values = true, required = true)]
+    pub exec: Vec<String>,
+
+    /// Seconds between two scans of the directory
+    #[arg(long, value_name = "SECONDS", default_value_t = 2)]
+    pub interval: u64,
+
+    /// Move each processed file to this directory
+    #[arg(long, value_name = "DIR")]
+    pub move_to: Option<PathBuf>,
+
+    /// Process the files already there, then exit
+    #[arg(long)]
+    pub once: bool,
+}
+
+/// Size and modification time of a file, unchanged between two scans once it is completely written
+type Snapshot = (u64, SystemTime);
+
+/// Files of the directory with a MARC extension, skipping hidden (e.g. temporary) files, by name
+fn candidates(dir: &Path) -> Result<Vec<(PathBuf, Snapshot)>, String> {
+    let error = |e: std::io::Error| format!("Cannot read {}: {}", dir.display(), e);
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir).map_err(error)? {
+        let entry = entry.map_err(error)?;
+        let path = entry.path();
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if hidden || FileFormat::from_path(&path).is_none() {
+            continue;
+        }
+        match entry.metadata() {
+            Ok(metadata) if metadata.is_file() => files.push((path, (metadata.len(), metadata.modified().map_err(error)?))),
+            // Removed since listed
+            _ => {}
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Run the command on a file; true on success
+fn process(args: &WatchArgs, path: &Path) -> Result<bool, String> {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let command: Vec<String> = args.exec.iter().map(|arg| arg.replace("{stem}", &stem).replace("{}", &path.to_string_lossy())).collect();
+    let executable = std::env::current_exe().map_err(|e| e.to_string())?;
+    eprintln!("Processing {}", path.display());
+    let status = Command::new(executable).args(&command).status().map_err(|e| format!("Cannot run {}: {}", command.join(" "), e))?;
+    if !status.success() {
+        match status.code() {
+            Some(code) => eprintln!("Failed: {} (exit code {})", path.display(), code),
+            None => eprintln!("Failed: {} (killed)", path.display()),
+        }
+        return Ok(false);
+    }
+
+    if let Some(dir) = &args.move_to {
+        let target = dir.join(path.file_name().unwrap_or_default());
+        std::fs::rename(path, &target).map_err(|e| format!("Cannot move {} to {}: {}", path.display(), target.display(), e))?;
+    }
+    Ok(true)
+}
+
+/// Run a command on every MARC file arriving in a directory
+///
+/// The directory is scanned every few seconds; a file is processed once its size
+/// and modification time stayed the same between two scans, so files still being
+/// copied are left alone. A file is processed again only if it changes.
+pub fn run(args: WatchArgs) -> Result<Status, String> {
+    if !args.dir.is_dir() {
+        return Err(format!("{} is not a directory", args.dir.display()));
+    }
+    if let Some(dir) = &args.move_to {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Cannot create {}: {}", dir.display(), e))?;
+    }
+
+    let mut pending: HashMap<PathBuf, Snapshot> = HashMap::new();
+    let mut processed: HashMap<PathBuf, Snapshot> = HashMap::new();
+    let mut failures = 0;
+    if !args.once {
+        eprintln!("Watching {}", args.dir.display());
+    }
+    loop {
+        for (path, snapshot) in candidates(&args.dir)? {
+            if processed.get(&path) == Some(&snapshot) {
+                continue;
+            }
+            if !args.once && pending.insert(path.clone(), snapshot) != Some(snapshot) {
+                // New or still growing: wait for the next scan
+                continue;
+            }
+            pending.remove(&path);
+            match process(&args, &path)? {
+                true if args.move_to.is_some() => {}
+                true => {
+                    processed.insert(path, snapshot);
+                }
+                false => {
+                    failures += 1;
+                    processed.insert(path, snapshot);
+                }
+            }
+        }
+        if args.once {
+            return Ok(Status::from_counts(failures, 0));
+        }
+        std::thread::sleep(Duration::from_secs(args.interval));
+    }
+}
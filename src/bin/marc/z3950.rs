@@ -0,0 +1,102 @@
+use crate::config::config;
+use crate::input::{parse_format_spec, FileFormat};
+use crate::output::{OutputArgs, RecordSink};
+use clap::Args;
+use marc_rs::*;
+
+#[derive(Args)]
+pub struct Z3950Args {
+    /// Target as HOST:PORT, e.g. lx2.loc.gov:210
+    pub address: String,
+
+    /// Database to search, e.g. LCDB
+    pub database: String,
+
+    /// PQF query, e.g. '@and @attr 1=1003 tolkien @attr 1=4 hobbit'
+    pub query: String,
+
+    #[command(flatten)]
+    pub output: OutputArgs,
+
+    /// Output format as FORMAT[:ENCODING] (default: from the output file extension)
+    #[arg(short, long, value_name = "FORMAT")]
+    pub to: Option<String>,
+
+    /// Save at most N records
+    #[arg(short = 'n', long, value_name = "N")]
+    pub limit: Option<usize>,
+
+    /// Position of the first result to save, from 1
+    #[arg(long, value_name = "N", default_value_t = 1)]
+    pub start: usize,
+
+    /// Records requested per present request
+    #[arg(long, value_name = "N", default_value_t = 50)]
+    pub page_size: usize,
+
+    /// Record syntax to request: usmarc or unimarc
+    #[arg(long, value_name = "SYNTAX", default_value = "usmarc")]
+    pub syntax: String,
+
+    /// User for targets that require authentication
+    #[arg(long, requires = "password")]
+    pub user: Option<String>,
+
+    /// Password for targets that require authentication
+    #[arg(long, requires = "user")]
+    pub password: Option<String>,
+}
+
+/// Save the records matching a query
+///
+/// When presenting fails midway, the records fetched so far are written before
+/// the error is reported.
+pub fn run(args: Z3950Args) -> Result<(), String> {
+    let (format, encoding) = match &args.to {
+        Some(spec) => parse_format_spec(spec)?,
+        None => (
+            FileFormat::from_path(args.output.path()).or(config().output_format).ok_or_else(|| format!("Cannot infer the output format of {}, use --to", args.output.path().display()))?,
+            None,
+        ),
+    };
+    let syntax = RecordSyntax::from_name(&args.syntax).ok_or_else(|| format!("Unknown record syntax: {}. Use: usmarc or unimarc", args.syntax))?;
+    let mut client = match (&args.user, &args.password) {
+        (Some(user), Some(password)) => Z3950Client::connect_with_credentials(&args.address, &args.database, user, password),
+        _ => Z3950Client::connect(&args.address, &args.database),
+    }
+    .map_err(|e| e.to_string())?;
+    client.set_record_syntax(syntax);
+    let hits = client.search(&args.query).map_err(|e| e.to_string())?;
+
+    let start = args.start.max(1);
+    let end = hits.min(args.limit.map_or(usize::MAX, |limit| (start - 1).saturating_add(limit)));
+    let mut sink = RecordSink::new(args.output.create()?, format, encoding);
+    let mut position = start;
+    let mut count = 0;
+    let mut failure = None;
+    while position <= end {
+        let batch = args.page_size.max(1).min(end + 1 - position);
+        match client.present(position, batch) {
+            Ok(records) => {
+                for record in records {
+                    sink.write(record).map_err(|e| format!("Record #{}: {}", start + count, e))?;
+                    count += 1;
+                }
+            }
+            Err(e) => {
+                failure = Some(e);
+                break;
+            }
+        }
+        position += batch;
+    }
+    sink.finish()?.commit()?;
+    let _ = client.close();
+
+    eprintln!("Saved {} of {} record(s) matching the query", count, hits);
+    match failure {
+        Some(e) if position > start => Err(format!("{}. Go on with --start {}", e, position)),
+        Some(e) => Err(e.to_string()),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,301 @@
+//! MARC21 to CSL-JSON, the item format of citeproc-based citation processors.
+
+use crate::format::MarcFormat;
+use crate::language::Language;
+use crate::publication::{PublicationFunction, PublicationStatement};
+use crate::punctuation::strip_isbd_punctuation_for_tag;
+use crate::record::{DataField, Record};
+use crate::relator::Relator;
+
+/// A name as CSL splits it: family and given names, or a literal for organizations
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CslName {
+    pub family: Option<String>,
+    pub given: Option<String>,
+    pub literal: Option<String>,
+}
+
+impl CslName {
+    /// Split an inverted personal name, e.g. `Tolkien, J. R. R.`
+    pub fn personal(name: &str) -> Self {
+        match name.split_once(", ") {
+            Some((family, given)) => CslName {
+                family: Some(family.to_string()),
+                given: Some(given.to_string()),
+                literal: None,
+            },
+            None => CslName {
+                family: Some(name.to_string()),
+                ..CslName::default()
+            },
+        }
+    }
+
+    /// A corporate or meeting name, kept whole
+    pub fn literal(name: &str) -> Self {
+        CslName {
+            literal: Some(name.to_string()),
+            ..CslName::default()
+        }
+    }
+}
+
+/// A citation item with the CSL variables MARC21 records provide
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CslItem {
+    /// Item identifier, the control number (001) unless set otherwise
+    pub id: String,
+    /// CSL item type, e.g. `book`, `article-journal` or `thesis`
+    pub kind: String,
+    pub title: Option<String>,
+    pub authors: Vec<CslName>,
+    pub editors: Vec<CslName>,
+    pub translators: Vec<CslName>,
+    /// Year of publication
+    pub issued: Option<i32>,
+    pub publisher: Option<String>,
+    pub publisher_place: Option<String>,
+    pub edition: Option<String>,
+    pub number_of_pages: Option<String>,
+    pub isbn: Option<String>,
+    pub issn: Option<String>,
+    pub doi: Option<String>,
+    pub url: Option<String>,
+    /// Journal or book the item is part of (773 `$t`)
+    pub container_title: Option<String>,
+    pub volume: Option<String>,
+    pub issue: Option<String>,
+    pub page: Option<String>,
+    /// Series (490 or 830)
+    pub collection_title: Option<String>,
+    pub collection_number: Option<String>,
+    /// ISO 639-1 code when there is one, MARC code otherwise
+    pub language: Option<String>,
+    /// CSL `abstract` (520)
+    pub summary: Option<String>,
+}
+
+/// Describe a MARC21 record as a CSL item
+///
+/// | Variable | Fields |
+/// |---|---|
+/// | type | leader/06 and /07, 502, 773 |
+/// | title | 245 `$a $b $n $p`, as `Title: subtitle` |
+/// | author, editor, translator | 100, 110, 111, 700, 710, 711, by `$e` and `$4` relator |
+/// | issued, publisher, publisher-place | 260 and 264 (publication); 008/07-10 for the year |
+/// | edition, number-of-pages | 250 `$a`, 300 `$a` |
+/// | ISBN, ISSN, DOI, URL | 020, 022, 024 with `$2 doi`, 856 `$u` |
+/// | container-title, volume, issue, page | 773 `$t` and `$g` |
+/// | collection-title, collection-number | 490 or 830 `$a` and `$v` |
+/// | language, abstract | 008/35-37 or 041 `$a`, 520 `$a` |
+///
+/// Names without a relator are authors; names with other relators than
+/// editor and translator are left out.
+pub fn to_csl(record: &Record) -> CslItem {
+    let mut item = CslItem {
+        id: record.control_fields.iter().find(|f| f.tag == "001").map(|f| f.value.trim().to_string()).unwrap_or_default(),
+        kind: csl_type(record).to_string(),
+        ..CslItem::default()
+    };
+
+    for field in &record.data_fields {
+        let tag = field.tag.as_str();
+        let value = |code: char| field.subfields.iter().find(|s| s.code == code).map(|s| strip_isbd_punctuation_for_tag(tag, &s.value).to_string()).filter(|v| !v.is_empty());
+        match tag {
+            "245" => item.title = title(field),
+            "100" | "110" | "111" | "700" | "710" | "711" => {
+                let Some(name) = value('a') else { continue };
+                let name = if tag.ends_with("00") { CslName::personal(&name) } else { CslName::literal(strip_isbd_punctuation_for_tag(tag, &field.join_subfields(&['a', 'b'], " "))) };
+                match relators(field).as_slice() {
+                    [] => item.authors.push(name),
+                    relators if relators.contains(&Relator::Author) => item.authors.push(name),
+                    relators if relators.contains(&Relator::Editor) => item.editors.push(name),
+                    relators if relators.contains(&Relator::Translator) => item.translators.push(name),
+                    _ => {}
+                }
+            }
+            "260" | "264" if item.publisher.is_none() => {
+                if let Some(statement) = PublicationStatement::from_field(field, MarcFormat::Marc21).filter(|s| s.function == PublicationFunction::Publication) {
+                    item.publisher = statement.publisher().map(|p| p.to_string());
+                    item.publisher_place = statement.place().map(|p| p.to_string());
+                    item.issued = statement.date().and_then(year);
+                }
+            }
+            "250" if item.edition.is_none() => item.edition = value('a'),
+            "300" if item.number_of_pages.is_none() => item.number_of_pages = value('a').and_then(|extent| page_count(&extent)),
+            "020" if item.isbn.is_none() => item.isbn = value('a').and_then(|isbn| isbn.split_whitespace().next().map(|n| n.to_string())),
+            "022" if item.issn.is_none() => item.issn = value('a'),
+            "024" if item.doi.is_none() && field.subfields.iter().any(|s| s.code == '2' && s.value.eq_ignore_ascii_case("doi")) => item.doi = value('a'),
+            "856" if item.url.is_none() => item.url = field.subfields.iter().find(|s| s.code == 'u').map(|s| s.value.trim().to_string()),
+            "773" if item.container_title.is_none() => {
+                item.container_title = value('t');
+                if let Some(related) = value('g') {
+                    let (volume, issue, page) = related_parts(&related);
+                    (item.volume, item.issue, item.page) = (volume, issue, page);
+                }
+            }
+            "490" | "830" if item.collection_title.is_none() => {
+                item.collection_title = value('a');
+                item.collection_number = value('v');
+            }
+            "041" if item.language.is_none() => item.language = value('a'),
+            "520" if item.summary.is_none() => item.summary = value('a'),
+            _ => {}
+        }
+    }
+
+    let fixed = record.control_fields.iter().find(|f| f.tag == "008").map(|f| f.value.as_str()).unwrap_or("");
+    if item.issued.is_none() {
+        item.issued = fixed.get(7..11).and_then(year);
+    }
+    if let Some(code) = fixed.get(35..38).filter(|code| code.bytes().all(|b| b.is_ascii_lowercase())) {
+        item.language = Some(code.to_string());
+    }
+    item.language = item.language.map(|code| Language::iso639_1_of(&code).map_or(code, |iso| iso.to_string()));
+    item
+}
+
+/// CSL item type of a record
+fn csl_type(record: &Record) -> &'static str {
+    let leader = &record.leader;
+    let has_field = |tag: &str| record.data_fields.iter().any(|f| f.tag == tag);
+    // A component part of a serial has the ISSN of its host
+    let in_serial = record.data_fields.iter().any(|f| f.tag == "773" && f.subfields.iter().any(|s| s.code == 'x'));
+    match (leader.record_type, leader.bibliographic_level) {
+        ('a', 'b') => "article-journal",
+        ('a', 'a') if in_serial => "article-journal",
+        ('a', 'a') => "chapter",
+        ('a', 's' | 'i') => "periodical",
+        ('a' | 't', _) if has_field("502") => "thesis",
+        ('a', _) => "book",
+        ('t', _) => "manuscript",
+        ('c' | 'd', _) => "musical_score",
+        ('e' | 'f', _) => "map",
+        ('g', _) => "motion_picture",
+        ('i' | 'j', _) => "song",
+        ('k', _) => "graphic",
+        ('m', _) => "software",
+        _ => "document",
+    }
+}
+
+/// `Title: subtitle`, with the part number and name
+fn title(field: &DataField) -> Option<String> {
+    let mut title = String::new();
+    for subfield in field.subfields.iter().filter(|s| matches!(s.code, 'a' | 'b' | 'n' | 'p')) {
+        let value = strip_isbd_punctuation_for_tag(&field.tag, &subfield.value);
+        if value.is_empty() {
+            continue;
+        }
+        if !title.is_empty() {
+            title.push_str(if subfield.code == 'b' { ": " } else { ". " });
+        }
+        title.push_str(value);
+    }
+    Some(title).filter(|t| !t.is_empty())
+}
+
+/// Relators of a name, from its `$e` terms and `$4` codes
+fn relators(field: &DataField) -> Vec<Relator> {
+    field
+        .subfields
+        .iter()
+        .filter_map(|s| match s.code {
+            'e' => Relator::from_term(&s.value),
+            '4' => Relator::from_code(&s.value),
+            _ => None,
+        })
+        .collect()
+}
+
+/// First four-digit year of a date, e.g. 2001 in `c2001` or `[2001?]`
+fn year(date: &str) -> Option<i32> {
+    let digits: Vec<char> = date.chars().collect();
+    digits.windows(4).find(|w| w.iter().all(|c| c.is_ascii_digit())).and_then(|w| w.iter().collect::<String>().parse().ok())
+}
+
+/// Number of pages of an extent, e.g. `330` in `xii, 330 p.` or `330 pages`
+fn page_count(extent: &str) -> Option<String> {
+    let words: Vec<&str> = extent.split_whitespace().collect();
+    words.windows(2).find(|w| w[1].starts_with("p.") || w[1].starts_with("pages")).map(|w| w[0].trim_end_matches(',')).filter(|n| n.chars().all(|c| c.is_ascii_digit())).map(|n| n.to_string())
+}
+
+/// Volume, issue and pages of a 773 `$g`, e.g. `Vol. 12, no. 3 (2004), p. 45-67`
+fn related_parts(related: &str) -> (Option<String>, Option<String>, Option<String>) {
+    let (mut volume, mut issue, mut page) = (None, None, None);
+    for part in related.split([',', '(']) {
+        let part = part.trim();
+        let lower = part.to_lowercase();
+        let number = |prefix: &str| Some(part[prefix.len()..].trim().trim_end_matches(')').to_string()).filter(|n| !n.is_empty());
+        if lower.starts_with("vol.") {
+            volume = number("vol.");
+        } else if lower.starts_with("no.") {
+            issue = number("no.");
+        } else if lower.starts_with("pp.") {
+            page = number("pp.");
+        } else if lower.starts_with("p.") {
+            page = number("p.");
+        }
+    }
+    (volume, issue, page)
+}
+
+#[cfg(feature = "serde")]
+impl CslItem {
+    /// The item as a CSL-JSON object, leaving out empty variables
+    pub fn to_json(&self) -> serde_json::Value {
+        use serde_json::{json, Map, Value};
+
+        let names = |names: &[CslName]| -> Value {
+            names
+                .iter()
+                .map(|name| {
+                    let mut object = Map::new();
+                    for (key, value) in [("family", &name.family), ("given", &name.given), ("literal", &name.literal)] {
+                        if let Some(value) = value {
+                            object.insert(key.to_string(), Value::String(value.clone()));
+                        }
+                    }
+                    Value::Object(object)
+                })
+                .collect()
+        };
+
+        let mut object = Map::new();
+        object.insert("id".to_string(), Value::String(self.id.clone()));
+        object.insert("type".to_string(), Value::String(self.kind.clone()));
+        for (key, values) in [("author", &self.authors), ("editor", &self.editors), ("translator", &self.translators)] {
+            if !values.is_empty() {
+                object.insert(key.to_string(), names(values));
+            }
+        }
+        if let Some(year) = self.issued {
+            object.insert("issued".to_string(), json!({ "date-parts": [[year]] }));
+        }
+        let variables = [
+            ("title", &self.title),
+            ("publisher", &self.publisher),
+            ("publisher-place", &self.publisher_place),
+            ("edition", &self.edition),
+            ("number-of-pages", &self.number_of_pages),
+            ("ISBN", &self.isbn),
+            ("ISSN", &self.issn),
+            ("DOI", &self.doi),
+            ("URL", &self.url),
+            ("container-title", &self.container_title),
+            ("volume", &self.volume),
+            ("issue", &self.issue),
+            ("page", &self.page),
+            ("collection-title", &self.collection_title),
+            ("collection-number", &self.collection_number),
+            ("language", &self.language),
+            ("abstract", &self.summary),
+        ];
+        for (key, value) in variables {
+            if let Some(value) = value {
+                object.insert(key.to_string(), Value::String(value.clone()));
+            }
+        }
+        Value::Object(object)
+    }
+}
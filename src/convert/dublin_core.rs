@@ -0,0 +1,309 @@
+//! MARC21 to Dublin Core crosswalk, after the Library of Congress MARC to Dublin
+//! Core and Dublin Core to MARC crosswalks.
+
+use crate::format::MarcFormat;
+use crate::publication::{PublicationConvention, PublicationFunction, PublicationStatement};
+use crate::punctuation::strip_isbd_punctuation_for_tag;
+use crate::record::{DataField, Leader, Record, Subfield};
+use crate::writer::WriteError;
+use std::io::Write;
+
+/// Simple (unqualified) Dublin Core description, one list of values per element
+///
+/// Values have no ISBD punctuation and no duplicates.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DublinCore {
+    pub titles: Vec<String>,
+    pub creators: Vec<String>,
+    pub subjects: Vec<String>,
+    pub descriptions: Vec<String>,
+    pub publishers: Vec<String>,
+    pub contributors: Vec<String>,
+    pub dates: Vec<String>,
+    /// DCMI Type Vocabulary terms from the leader, then genre terms from 655
+    pub types: Vec<String>,
+    pub formats: Vec<String>,
+    pub identifiers: Vec<String>,
+    pub sources: Vec<String>,
+    pub languages: Vec<String>,
+    pub relations: Vec<String>,
+    pub coverages: Vec<String>,
+    pub rights: Vec<String>,
+}
+
+impl DublinCore {
+    /// Every value with its element name, e.g. `("title", "The hobbit")`, in the DCMES order
+    pub fn elements(&self) -> impl Iterator<Item = (&'static str, &str)> {
+        [
+            ("title", &self.titles),
+            ("creator", &self.creators),
+            ("subject", &self.subjects),
+            ("description", &self.descriptions),
+            ("publisher", &self.publishers),
+            ("contributor", &self.contributors),
+            ("date", &self.dates),
+            ("type", &self.types),
+            ("format", &self.formats),
+            ("identifier", &self.identifiers),
+            ("source", &self.sources),
+            ("language", &self.languages),
+            ("relation", &self.relations),
+            ("coverage", &self.coverages),
+            ("rights", &self.rights),
+        ]
+        .into_iter()
+        .flat_map(|(name, values)| values.iter().map(move |value| (name, value.as_str())))
+    }
+
+    /// Write the description as an OAI-PMH `<oai_dc:dc>` element
+    pub fn write_xml(&self, output: &mut dyn Write) -> Result<(), WriteError> {
+        use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+
+        let mut writer = quick_xml::Writer::new(output);
+        let mut start = BytesStart::new("oai_dc:dc");
+        start.push_attribute(("xmlns:oai_dc", "http://www.openarchives.org/OAI/2.0/oai_dc/"));
+        start.push_attribute(("xmlns:dc", "http://purl.org/dc/elements/1.1/"));
+        start.push_attribute(("xmlns:xsi", "http://www.w3.org/2001/XMLSchema-instance"));
+        start.push_attribute(("xsi:schemaLocation", "http://www.openarchives.org/OAI/2.0/oai_dc/ http://www.openarchives.org/OAI/2.0/oai_dc.xsd"));
+        writer.write_event(Event::Start(start))?;
+        for (name, value) in self.elements() {
+            let element = format!("dc:{}", name);
+            writer.write_event(Event::Start(BytesStart::new(element.as_str())))?;
+            writer.write_event(Event::Text(BytesText::new(value)))?;
+            writer.write_event(Event::End(BytesEnd::new(element.as_str())))?;
+        }
+        writer.write_event(Event::End(BytesEnd::new("oai_dc:dc")))?;
+        Ok(())
+    }
+
+    /// The description as an OAI-PMH `<oai_dc:dc>` element
+    pub fn to_xml(&self) -> String {
+        let mut output = Vec::new();
+        self.write_xml(&mut output).expect("writing to a Vec cannot fail");
+        String::from_utf8(output).expect("XML is written as UTF-8")
+    }
+}
+
+/// Describe a MARC21 record in Dublin Core
+///
+/// | Element | Fields |
+/// |---|---|
+/// | title | 245, 246, 130, 240, 730, 740 |
+/// | creator | 100, 110, 111, 720 with `$e author` |
+/// | subject | 600, 610, 611, 630, 650, 653, 050, 060, 080, 082 `$a` |
+/// | description | 5XX except 506, 530, 540, 546 |
+/// | publisher, date | 260 and 264 (publication) `$b` and `$c`; 008/07-10 when there is no date |
+/// | contributor | 700, 710, 711, other 720 |
+/// | type | leader/06 and /07, 655 `$a` |
+/// | format | 340 `$a`, 856 `$q` |
+/// | identifier | 020 and 022 as `URN:ISBN:` and `URN:ISSN:`, 024 `$a`, 856 `$u` |
+/// | source | 786 |
+/// | language | 008/35-37, 041 `$a`, 546 `$a` |
+/// | relation | 530 `$a`, 760-787 except 786 |
+/// | coverage | 651, 662, 752 |
+/// | rights | 506 `$a`, 540 `$a` |
+pub fn to_dc(record: &Record) -> DublinCore {
+    let mut dc = DublinCore::default();
+    if let Some(dcmi_type) = dcmi_type(&record.leader) {
+        push(&mut dc.types, dcmi_type.to_string());
+    }
+    if record.leader.bibliographic_level == 'c' {
+        push(&mut dc.types, "Collection".to_string());
+    }
+
+    for field in &record.data_fields {
+        let tag = field.tag.as_str();
+        match tag {
+            "245" => push(&mut dc.titles, joined(field, &['a', 'b', 'f', 'g', 'k', 'n', 'p', 's'])),
+            "246" | "740" => push(&mut dc.titles, joined(field, &['a', 'b', 'n', 'p'])),
+            "130" | "240" | "730" => push(&mut dc.titles, joined(field, &['a', 'd', 'f', 'g', 'k', 'l', 'm', 'n', 'o', 'p', 'r', 's'])),
+            "100" | "110" | "111" => push(&mut dc.creators, name(field)),
+            "700" | "710" | "711" => push(&mut dc.contributors, name(field)),
+            "720" if field.subfields.iter().any(|s| s.code == 'e' && s.value.contains("author")) => push(&mut dc.creators, name(field)),
+            "720" => push(&mut dc.contributors, name(field)),
+            "600" | "610" | "611" | "630" | "650" | "653" => push(&mut dc.subjects, heading(field)),
+            "050" | "060" | "080" | "082" => values(&mut dc.subjects, field, 'a'),
+            "651" | "662" | "752" => push(&mut dc.coverages, heading(field)),
+            "260" | "264" => {
+                if let Some(statement) = PublicationStatement::from_field(field, MarcFormat::Marc21).filter(|s| s.function == PublicationFunction::Publication) {
+                    statement.publishers.into_iter().for_each(|publisher| push(&mut dc.publishers, publisher));
+                    statement.dates.into_iter().for_each(|date| push(&mut dc.dates, date));
+                }
+            }
+            "506" | "540" => values(&mut dc.rights, field, 'a'),
+            "530" => values(&mut dc.relations, field, 'a'),
+            "546" => values(&mut dc.languages, field, 'a'),
+            "655" => values(&mut dc.types, field, 'a'),
+            "340" => values(&mut dc.formats, field, 'a'),
+            "856" => {
+                values(&mut dc.formats, field, 'q');
+                // URLs keep a final slash or period
+                field.subfields.iter().filter(|s| s.code == 'u').for_each(|s| push(&mut dc.identifiers, s.value.trim().to_string()));
+            }
+            "020" | "022" => {
+                let scheme = if tag == "020" { "ISBN" } else { "ISSN" };
+                for subfield in field.subfields.iter().filter(|s| s.code == 'a') {
+                    if let Some(number) = subfield.value.split_whitespace().next() {
+                        push(&mut dc.identifiers, format!("URN:{}:{}", scheme, number));
+                    }
+                }
+            }
+            "024" => values(&mut dc.identifiers, field, 'a'),
+            "041" => values(&mut dc.languages, field, 'a'),
+            "786" => push(&mut dc.sources, joined(field, &['n', 'o', 't'])),
+            _ if tag.starts_with('5') => values(&mut dc.descriptions, field, 'a'),
+            _ if ("760"..="787").contains(&tag) => push(&mut dc.relations, joined(field, &['n', 'o', 't'])),
+            _ => {}
+        }
+    }
+
+    let fixed = record.control_fields.iter().find(|f| f.tag == "008").map(|f| f.value.as_str()).unwrap_or("");
+    if dc.dates.is_empty() {
+        if let Some(year) = fixed.get(7..11).filter(|year| year.bytes().all(|b| b.is_ascii_digit())) {
+            push(&mut dc.dates, year.to_string());
+        }
+    }
+    if let Some(language) = fixed.get(35..38).filter(|code| code.bytes().all(|b| b.is_ascii_lowercase())) {
+        dc.languages.retain(|l| l != language);
+        dc.languages.insert(0, language.to_string());
+    }
+    dc
+}
+
+/// Build a MARC21 record from a Dublin Core description
+///
+/// Fields follow the Library of Congress Dublin Core to MARC crosswalk: the first
+/// title in 245, the others in 246, creators and contributors in 720, subjects
+/// in 653, descriptions in 520, publishers and dates in 260, types in 655 (and
+/// leader/06 for DCMI types), formats in 856 `$q`, identifiers in 024 (URLs in
+/// 856 `$u`, `URN:ISBN:` and `URN:ISSN:` in 020 and 022), sources in 786,
+/// languages in 546, relations in 787, coverage in 500 and rights in 540.
+/// Coverage therefore comes back as a description through [`to_dc`].
+pub fn from_dc(dc: &DublinCore) -> Record {
+    let record_type = dc.types.iter().find_map(|t| record_type(t)).unwrap_or('a');
+    let level = if dc.types.iter().any(|t| t.eq_ignore_ascii_case("collection")) { 'c' } else { 'm' };
+    let leader = Leader::from_bytes(format!("00000n{}{} a22000003u 4500", record_type, level).as_bytes()).expect("leader is 24 ASCII bytes");
+
+    let mut fields = Vec::new();
+    let mut add = |tag: &str, (ind1, ind2): (char, char), subfields: &[(char, &str)]| {
+        fields.push(DataField {
+            tag: tag.to_string(),
+            ind1,
+            ind2,
+            subfields: subfields.iter().map(|&(code, value)| Subfield { code, value: value.to_string() }).collect(),
+        })
+    };
+    for (i, title) in dc.titles.iter().enumerate() {
+        match i {
+            0 => add("245", ('0', '0'), &[('a', title)]),
+            _ => add("246", ('3', '3'), &[('a', title)]),
+        }
+    }
+    dc.creators.iter().for_each(|creator| add("720", (' ', ' '), &[('a', creator), ('e', "author")]));
+    dc.contributors.iter().for_each(|contributor| add("720", (' ', ' '), &[('a', contributor)]));
+    dc.subjects.iter().for_each(|subject| add("653", (' ', ' '), &[('a', subject)]));
+    dc.descriptions.iter().for_each(|description| add("520", (' ', ' '), &[('a', description)]));
+    dc.types.iter().for_each(|t| add("655", (' ', '7'), &[('a', t), ('2', "local")]));
+    dc.formats.iter().for_each(|format| add("856", (' ', ' '), &[('q', format)]));
+    for identifier in &dc.identifiers {
+        let upper = identifier.to_ascii_uppercase();
+        if upper.starts_with("HTTP://") || upper.starts_with("HTTPS://") {
+            add("856", ('4', '0'), &[('u', identifier)]);
+        } else if upper.starts_with("URN:ISBN:") {
+            add("020", (' ', ' '), &[('a', &identifier[9..])]);
+        } else if upper.starts_with("URN:ISSN:") {
+            add("022", (' ', ' '), &[('a', &identifier[9..])]);
+        } else {
+            add("024", ('8', ' '), &[('a', identifier)]);
+        }
+    }
+    dc.sources.iter().for_each(|source| add("786", ('0', ' '), &[('n', source)]));
+    dc.languages.iter().for_each(|language| add("546", (' ', ' '), &[('a', language)]));
+    dc.relations.iter().for_each(|relation| add("787", ('0', ' '), &[('n', relation)]));
+    dc.coverages.iter().for_each(|coverage| add("500", (' ', ' '), &[('a', coverage)]));
+    dc.rights.iter().for_each(|rights| add("540", (' ', ' '), &[('a', rights)]));
+    if !dc.publishers.is_empty() || !dc.dates.is_empty() {
+        let statement = PublicationStatement {
+            publishers: dc.publishers.clone(),
+            dates: dc.dates.clone(),
+            ..PublicationStatement::new(PublicationFunction::Publication)
+        };
+        fields.push(statement.to_field(PublicationConvention::Imprint));
+    }
+    fields.sort_by(|a, b| a.tag.cmp(&b.tag));
+
+    let mut record = Record {
+        leader,
+        control_fields: Vec::new(),
+        data_fields: fields,
+    };
+    record.update_leader_lengths();
+    record
+}
+
+/// DCMI Type Vocabulary term of a leader/06 type of record
+fn dcmi_type(leader: &Leader) -> Option<&'static str> {
+    match leader.record_type {
+        'a' | 'c' | 'd' | 't' => Some("Text"),
+        'e' | 'f' | 'k' => Some("StillImage"),
+        'g' => Some("MovingImage"),
+        'i' | 'j' => Some("Sound"),
+        'm' => Some("Software"),
+        'r' => Some("PhysicalObject"),
+        'o' | 'p' => Some("Collection"),
+        _ => None,
+    }
+}
+
+/// Leader/06 type of record of a DCMI Type Vocabulary term
+fn record_type(dcmi_type: &str) -> Option<char> {
+    match dcmi_type.to_ascii_lowercase().as_str() {
+        "text" => Some('a'),
+        "image" | "stillimage" => Some('k'),
+        "movingimage" => Some('g'),
+        "sound" => Some('i'),
+        "software" | "dataset" | "interactiveresource" => Some('m'),
+        "physicalobject" => Some('r'),
+        _ => None,
+    }
+}
+
+/// Add a value unless empty or already present
+fn push(values: &mut Vec<String>, value: String) {
+    if !value.is_empty() && !values.contains(&value) {
+        values.push(value);
+    }
+}
+
+/// Add the values of a subfield, without their punctuation
+fn values(values: &mut Vec<String>, field: &DataField, code: char) {
+    for subfield in field.subfields.iter().filter(|s| s.code == code) {
+        push(values, strip_isbd_punctuation_for_tag(&field.tag, &subfield.value).to_string());
+    }
+}
+
+/// Given subfields joined with spaces, without the final punctuation
+fn joined(field: &DataField, codes: &[char]) -> String {
+    strip_isbd_punctuation_for_tag(&field.tag, &field.join_subfields(codes, " ")).to_string()
+}
+
+/// Name of a 1XX/7XX heading, without relator terms and codes
+fn name(field: &DataField) -> String {
+    joined(field, &['a', 'b', 'c', 'd', 'n', 'q'])
+}
+
+/// Subject heading with its subdivisions separated by `--`, e.g. `Dragons--Fiction`
+fn heading(field: &DataField) -> String {
+    let mut heading = String::new();
+    for subfield in field.subfields.iter().filter(|s| s.code.is_ascii_lowercase() && s.code != 'e') {
+        let value = strip_isbd_punctuation_for_tag(&field.tag, &subfield.value);
+        if value.is_empty() {
+            continue;
+        }
+        if !heading.is_empty() {
+            heading.push_str(if matches!(subfield.code, 'v' | 'x' | 'y' | 'z') { "--" } else { " " });
+        }
+        heading.push_str(value);
+    }
+    heading
+}
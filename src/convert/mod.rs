@@ -0,0 +1,11 @@
+//! Crosswalks between MARC records and other metadata schemas.
+
+pub mod csl;
+pub mod dublin_core;
+pub mod mods;
+pub mod onix;
+
+pub use csl::*;
+pub use dublin_core::*;
+pub use mods::*;
+pub use onix::*;
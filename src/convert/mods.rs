@@ -0,0 +1,846 @@
+//! MARC21 to MODS crosswalk, after the Library of Congress MARC to MODS mapping.
+
+use crate::fields::{Subject, SubjectSource};
+use crate::format::MarcFormat;
+use crate::publication::{PublicationConvention, PublicationFunction, PublicationStatement};
+use crate::punctuation::strip_isbd_punctuation_for_tag;
+use crate::record::{ControlField, DataField, Leader, Record, Subfield};
+use crate::relator::Relator;
+use crate::writer::WriteError;
+use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+use std::io::Write;
+
+/// MODS description of a resource, covering the elements MARC21 records map to
+///
+/// Values have no ISBD punctuation.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Mods {
+    /// Control number (001)
+    pub record_identifier: Option<String>,
+    pub titles: Vec<TitleInfo>,
+    pub names: Vec<ModsName>,
+    /// Type of resource from leader/06, e.g. `text` or `cartographic`
+    pub type_of_resource: Option<String>,
+    pub genres: Vec<Genre>,
+    pub origin_info: OriginInfo,
+    /// MARC language codes
+    pub languages: Vec<String>,
+    /// Physical extent (300)
+    pub extent: Option<String>,
+    pub abstracts: Vec<String>,
+    pub notes: Vec<String>,
+    pub subjects: Vec<ModsSubject>,
+    pub identifiers: Vec<ModsIdentifier>,
+    /// Electronic locations (856 `$u`)
+    pub urls: Vec<String>,
+    pub related_items: Vec<RelatedItem>,
+}
+
+/// Kind of title
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TitleType {
+    /// 210 - Abbreviated title
+    Abbreviated,
+    /// 246 with second indicator `1` - Parallel title
+    Translated,
+    /// 246 - Varying form of title
+    Alternative,
+    /// 130, 240 - Uniform title
+    Uniform,
+}
+
+impl TitleType {
+    /// Value of the MODS `type` attribute
+    pub fn code(&self) -> &'static str {
+        match self {
+            TitleType::Abbreviated => "abbreviated",
+            TitleType::Translated => "translated",
+            TitleType::Alternative => "alternative",
+            TitleType::Uniform => "uniform",
+        }
+    }
+}
+
+/// A title, `titleInfo` in MODS
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TitleInfo {
+    /// None for the title proper (245)
+    pub kind: Option<TitleType>,
+    /// Leading article not used for sorting, e.g. `The `
+    pub non_sort: Option<String>,
+    pub title: String,
+    pub sub_title: Option<String>,
+    pub part_number: Option<String>,
+    pub part_name: Option<String>,
+}
+
+/// Kind of name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NameType {
+    /// X00 - Personal name
+    Personal,
+    /// X10 - Corporate name
+    Corporate,
+    /// X11 - Meeting name
+    Conference,
+}
+
+impl NameType {
+    /// Value of the MODS `type` attribute
+    pub fn code(&self) -> &'static str {
+        match self {
+            NameType::Personal => "personal",
+            NameType::Corporate => "corporate",
+            NameType::Conference => "conference",
+        }
+    }
+
+    /// Last two digits of the X00, X10 and X11 tags
+    fn tag_suffix(&self) -> &'static str {
+        match self {
+            NameType::Personal => "00",
+            NameType::Corporate => "10",
+            NameType::Conference => "11",
+        }
+    }
+
+    fn from_tag(tag: &str) -> Option<Self> {
+        match tag.get(1..) {
+            Some("00") => Some(NameType::Personal),
+            Some("10") => Some(NameType::Corporate),
+            Some("11") => Some(NameType::Conference),
+            _ => None,
+        }
+    }
+}
+
+/// Role of a name: a relator term, a relator code or both
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Role {
+    pub term: Option<String>,
+    /// MARC relator code, e.g. `aut`
+    pub code: Option<String>,
+}
+
+impl From<Relator> for Role {
+    fn from(relator: Relator) -> Self {
+        Role {
+            term: Some(relator.label().to_lowercase()),
+            code: Some(relator.code().to_string()),
+        }
+    }
+}
+
+/// A name with its roles, `name` in MODS
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModsName {
+    pub kind: NameType,
+    /// Name, with titles and fuller form (`$a $b $c $q`)
+    pub name: String,
+    /// Dates associated with the name (`$d`)
+    pub date: Option<String>,
+    pub roles: Vec<Role>,
+    /// True for the main entry (1XX)
+    pub primary: bool,
+    /// URI of the authority record (`$0` or `$1`)
+    pub value_uri: Option<String>,
+}
+
+/// Genre or form term, with its vocabulary
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Genre {
+    pub value: String,
+    pub authority: Option<SubjectSource>,
+}
+
+/// Publication details, `originInfo` in MODS
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct OriginInfo {
+    pub places: Vec<String>,
+    pub publishers: Vec<String>,
+    pub dates_issued: Vec<String>,
+    /// Edition statement (250)
+    pub edition: Option<String>,
+}
+
+/// One term of a subject heading
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubjectTerm {
+    Topic(String),
+    Geographic(String),
+    Temporal(String),
+    Genre(String),
+    Name(NameType, String),
+    Title(String),
+}
+
+/// A subject heading and its subdivisions, with its vocabulary
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModsSubject {
+    pub authority: Option<SubjectSource>,
+    pub terms: Vec<SubjectTerm>,
+}
+
+/// An identifier with its type, e.g. `isbn`, `issn`, `lccn` or `doi`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModsIdentifier {
+    pub kind: String,
+    pub value: String,
+    /// True for canceled or invalid identifiers (`$z`)
+    pub invalid: bool,
+}
+
+/// Relation of a related item to the described resource
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelatedItemType {
+    /// 773 - Host item, e.g. the journal of an article
+    Host,
+    /// 490, 830 - Series
+    Series,
+    /// 780 - Preceding entry
+    Preceding,
+    /// 785 - Succeeding entry
+    Succeeding,
+    /// 775 - Other edition
+    OtherVersion,
+    /// 776 - Additional physical form
+    OtherFormat,
+    /// 534 - Original version
+    Original,
+}
+
+impl RelatedItemType {
+    /// Value of the MODS `type` attribute
+    pub fn code(&self) -> &'static str {
+        match self {
+            RelatedItemType::Host => "host",
+            RelatedItemType::Series => "series",
+            RelatedItemType::Preceding => "preceding",
+            RelatedItemType::Succeeding => "succeeding",
+            RelatedItemType::OtherVersion => "otherVersion",
+            RelatedItemType::OtherFormat => "otherFormat",
+            RelatedItemType::Original => "original",
+        }
+    }
+
+    /// Linking entry field of the relation; series are written in 490
+    fn tag(&self) -> &'static str {
+        match self {
+            RelatedItemType::Host => "773",
+            RelatedItemType::Series => "490",
+            RelatedItemType::Preceding => "780",
+            RelatedItemType::Succeeding => "785",
+            RelatedItemType::OtherVersion => "775",
+            RelatedItemType::OtherFormat => "776",
+            RelatedItemType::Original => "534",
+        }
+    }
+}
+
+/// A related resource, `relatedItem` in MODS
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RelatedItem {
+    /// None for an unspecified relation (787)
+    pub kind: Option<RelatedItemType>,
+    pub title: Option<String>,
+    /// Main entry of the related item (`$a`)
+    pub name: Option<String>,
+    /// Location within the related item, e.g. `Vol. 12, no. 3 (2004), p. 45-67` (773 `$g`, 490 `$v`)
+    pub part: Option<String>,
+    pub identifiers: Vec<ModsIdentifier>,
+}
+
+/// Describe a MARC21 record in MODS
+///
+/// Titles come from 245, 246, 210, 130 and 240; names with their `$e` role
+/// terms and `$4` codes from 1XX and 7XX; subjects with their vocabulary from
+/// 600-651 and genres from 655; identifiers from 010, 020, 022 and 024; related
+/// items from 490, 534, 760-787 and 830.
+pub fn to_mods(record: &Record) -> Mods {
+    let mut mods = Mods {
+        record_identifier: record.control_fields.iter().find(|f| f.tag == "001").map(|f| f.value.trim().to_string()),
+        type_of_resource: type_of_resource(record.leader.record_type).map(|t| t.to_string()),
+        ..Mods::default()
+    };
+    if let Some(code) = record.control_fields.iter().find(|f| f.tag == "008").and_then(|f| f.value.get(35..38)).filter(|code| code.bytes().all(|b| b.is_ascii_lowercase())) {
+        mods.languages.push(code.to_string());
+    }
+
+    for field in &record.data_fields {
+        let tag = field.tag.as_str();
+        let value = |code: char| field.subfields.iter().find(|s| s.code == code).map(|s| strip_isbd_punctuation_for_tag(tag, &s.value).to_string()).filter(|v| !v.is_empty());
+        match tag {
+            "245" | "246" | "210" | "130" | "240" => {
+                let kind = match tag {
+                    "245" => None,
+                    "246" if field.ind2 == '1' => Some(TitleType::Translated),
+                    "246" => Some(TitleType::Alternative),
+                    "210" => Some(TitleType::Abbreviated),
+                    _ => Some(TitleType::Uniform),
+                };
+                mods.titles.push(title_info(field, kind));
+            }
+            "100" | "110" | "111" | "700" | "710" | "711" => mods.names.push(name(field, tag.starts_with('1'))),
+            "250" => mods.origin_info.edition = value('a'),
+            "260" | "264" => {
+                if let Some(statement) = PublicationStatement::from_field(field, MarcFormat::Marc21).filter(|s| s.function == PublicationFunction::Publication) {
+                    mods.origin_info.places.extend(statement.places);
+                    mods.origin_info.publishers.extend(statement.publishers);
+                    mods.origin_info.dates_issued.extend(statement.dates);
+                }
+            }
+            "300" => mods.extent = Some(strip_isbd_punctuation_for_tag(tag, &field.join_subfields(&['a', 'b', 'c'], " "))).filter(|v| !v.is_empty()).map(|v| v.to_string()),
+            "041" => field.subfields.iter().filter(|s| s.code == 'a').for_each(|s| {
+                if !mods.languages.contains(&s.value) {
+                    mods.languages.push(s.value.clone())
+                }
+            }),
+            "500" => mods.notes.extend(value('a')),
+            "520" => mods.abstracts.extend(value('a')),
+            "600" | "610" | "611" | "630" | "648" | "650" | "651" => mods.subjects.push(subject(field)),
+            "655" => mods.genres.extend(value('a').map(|value| Genre { value, authority: Subject::source(field, MarcFormat::Marc21) })),
+            "010" => mods.identifiers.extend(identifiers(field, "lccn")),
+            "020" => mods.identifiers.extend(identifiers(field, "isbn")),
+            "022" => mods.identifiers.extend(identifiers(field, "issn")),
+            "024" => {
+                let kind = match field.ind1 {
+                    '0' => "isrc".to_string(),
+                    '1' => "upc".to_string(),
+                    '2' => "ismn".to_string(),
+                    '3' => "ean".to_string(),
+                    '4' => "sici".to_string(),
+                    '7' => field.subfields.iter().find(|s| s.code == '2').map_or("local".to_string(), |s| s.value.clone()),
+                    _ => "local".to_string(),
+                };
+                mods.identifiers.extend(identifiers(field, &kind));
+            }
+            "856" => mods.urls.extend(field.subfields.iter().filter(|s| s.code == 'u').map(|s| s.value.trim().to_string())),
+            "490" | "830" => mods.related_items.push(RelatedItem {
+                kind: Some(RelatedItemType::Series),
+                title: value(if tag == "490" { 'a' } else { 't' }).or_else(|| value('a')),
+                part: value('v'),
+                identifiers: field.subfields.iter().filter(|s| s.code == 'x').map(|s| ModsIdentifier { kind: "issn".to_string(), value: s.value.trim().to_string(), invalid: false }).collect(),
+                ..RelatedItem::default()
+            }),
+            "534" | "773" | "775" | "776" | "780" | "785" | "787" => {
+                let kind = match tag {
+                    "534" => Some(RelatedItemType::Original),
+                    "773" => Some(RelatedItemType::Host),
+                    "775" => Some(RelatedItemType::OtherVersion),
+                    "776" => Some(RelatedItemType::OtherFormat),
+                    "780" => Some(RelatedItemType::Preceding),
+                    "785" => Some(RelatedItemType::Succeeding),
+                    _ => None,
+                };
+                let mut identifiers = Vec::new();
+                for subfield in &field.subfields {
+                    let kind = match subfield.code {
+                        'x' => "issn",
+                        'z' => "isbn",
+                        'w' => "local",
+                        _ => continue,
+                    };
+                    identifiers.push(ModsIdentifier { kind: kind.to_string(), value: subfield.value.trim().to_string(), invalid: false });
+                }
+                mods.related_items.push(RelatedItem {
+                    kind,
+                    title: value('t'),
+                    name: value('a'),
+                    part: if tag == "534" { None } else { value('g') },
+                    identifiers,
+                });
+            }
+            _ => {}
+        }
+    }
+    mods
+}
+
+/// Build a MARC21 record from a MODS description
+///
+/// The first title without a type becomes 245; the primary name goes in 1XX and
+/// the others in 7XX, with their roles in `$e` and `$4`. Subject vocabularies
+/// go in the second indicator, or in `$2` with indicator `7`.
+pub fn from_mods(mods: &Mods) -> Record {
+    let record_type = mods.type_of_resource.as_deref().and_then(record_type).unwrap_or('a');
+    let leader = Leader::from_bytes(format!("00000n{}m a2200000 u 4500", record_type).as_bytes()).expect("leader is 24 ASCII bytes");
+    let control_fields = mods.record_identifier.iter().map(|id| ControlField { tag: "001".to_string(), value: id.clone() }).collect();
+
+    let mut fields = Vec::new();
+    let has_main_entry = mods.names.iter().any(|n| n.primary);
+    let mut title_proper = false;
+    for title in &mods.titles {
+        let (tag, ind1, ind2) = match title.kind {
+            None if !title_proper => {
+                title_proper = true;
+                let non_sort = title.non_sort.as_ref().map_or(0, |n| n.chars().count()).min(9);
+                ("245", if has_main_entry { '1' } else { '0' }, char::from_digit(non_sort as u32, 10).unwrap_or('0'))
+            }
+            None | Some(TitleType::Alternative) => ("246", '3', ' '),
+            Some(TitleType::Translated) => ("246", '3', '1'),
+            Some(TitleType::Abbreviated) => ("210", '0', ' '),
+            Some(TitleType::Uniform) if has_main_entry => ("240", '1', '0'),
+            Some(TitleType::Uniform) => ("130", '0', ' '),
+        };
+        let mut subfields = vec![Subfield { code: 'a', value: format!("{}{}", title.non_sort.as_deref().filter(|_| tag == "245").unwrap_or(""), title.title) }];
+        for (code, value) in [('b', &title.sub_title), ('n', &title.part_number), ('p', &title.part_name)] {
+            subfields.extend(value.iter().map(|value| Subfield { code, value: value.clone() }));
+        }
+        fields.push(DataField { tag: tag.to_string(), ind1, ind2, subfields });
+    }
+
+    let mut main_entry = false;
+    for name in &mods.names {
+        let prefix = if name.primary && !main_entry { '1' } else { '7' };
+        main_entry |= prefix == '1';
+        let mut subfields = vec![Subfield { code: 'a', value: name.name.clone() }];
+        subfields.extend(name.date.iter().map(|date| Subfield { code: 'd', value: date.clone() }));
+        let term_code = if name.kind == NameType::Conference { 'j' } else { 'e' };
+        subfields.extend(name.roles.iter().filter_map(|role| role.term.clone()).map(|value| Subfield { code: term_code, value }));
+        subfields.extend(name.roles.iter().filter_map(|role| role.code.clone()).map(|value| Subfield { code: '4', value }));
+        subfields.extend(name.value_uri.iter().map(|uri| Subfield { code: '0', value: uri.clone() }));
+        let ind1 = match name.kind {
+            NameType::Personal => '1',
+            NameType::Corporate | NameType::Conference => '2',
+        };
+        fields.push(DataField { tag: format!("{}{}", prefix, name.kind.tag_suffix()), ind1, ind2: ' ', subfields });
+    }
+
+    let single = |tag: &str, (ind1, ind2): (char, char), code: char, value: &str| DataField {
+        tag: tag.to_string(),
+        ind1,
+        ind2,
+        subfields: vec![Subfield { code, value: value.to_string() }],
+    };
+    fields.extend(mods.origin_info.edition.iter().map(|edition| single("250", (' ', ' '), 'a', edition)));
+    let origin = &mods.origin_info;
+    if !origin.places.is_empty() || !origin.publishers.is_empty() || !origin.dates_issued.is_empty() {
+        let statement = PublicationStatement {
+            places: origin.places.clone(),
+            publishers: origin.publishers.clone(),
+            dates: origin.dates_issued.clone(),
+            ..PublicationStatement::new(PublicationFunction::Publication)
+        };
+        fields.push(statement.to_field(PublicationConvention::Imprint));
+    }
+    fields.extend(mods.extent.iter().map(|extent| single("300", (' ', ' '), 'a', extent)));
+    if !mods.languages.is_empty() {
+        fields.push(DataField {
+            tag: "041".to_string(),
+            ind1: ' ',
+            ind2: ' ',
+            subfields: mods.languages.iter().map(|language| Subfield { code: 'a', value: language.clone() }).collect(),
+        });
+    }
+    fields.extend(mods.notes.iter().map(|note| single("500", (' ', ' '), 'a', note)));
+    fields.extend(mods.abstracts.iter().map(|text| single("520", (' ', ' '), 'a', text)));
+    for subject in &mods.subjects {
+        fields.extend(subject_field(subject));
+    }
+    for genre in &mods.genres {
+        let mut field = single("655", (' ', ' '), 'a', &genre.value);
+        set_source(&mut field, genre.authority.as_ref());
+        fields.push(field);
+    }
+    for identifier in &mods.identifiers {
+        let (tag, ind1, scheme) = match identifier.kind.as_str() {
+            "lccn" => ("010", ' ', None),
+            "isbn" => ("020", ' ', None),
+            "issn" => ("022", '0', None),
+            "isrc" => ("024", '0', None),
+            "upc" => ("024", '1', None),
+            "ismn" => ("024", '2', None),
+            "ean" => ("024", '3', None),
+            "sici" => ("024", '4', None),
+            "local" => ("024", '8', None),
+            kind => ("024", '7', Some(kind)),
+        };
+        let code = match (tag, identifier.invalid) {
+            (_, false) => 'a',
+            ("022", true) => 'y',
+            (_, true) => 'z',
+        };
+        let mut field = single(tag, (ind1, ' '), code, &identifier.value);
+        field.subfields.extend(scheme.map(|scheme| Subfield { code: '2', value: scheme.to_string() }));
+        fields.push(field);
+    }
+    fields.extend(mods.urls.iter().map(|url| single("856", ('4', '0'), 'u', url)));
+    for item in &mods.related_items {
+        fields.push(related_item_field(item));
+    }
+
+    fields.sort_by(|a, b| a.tag.cmp(&b.tag));
+    let mut record = Record { leader, control_fields, data_fields: fields };
+    record.update_leader_lengths();
+    record
+}
+
+impl Mods {
+    /// Write the description as a MODS 3 `<mods>` element
+    pub fn write_xml(&self, output: &mut dyn Write) -> Result<(), WriteError> {
+        let mut writer = quick_xml::Writer::new(output);
+        let mut start = BytesStart::new("mods");
+        start.push_attribute(("xmlns", "http://www.loc.gov/mods/v3"));
+        start.push_attribute(("version", "3.8"));
+        writer.write_event(Event::Start(start))?;
+
+        for title in &self.titles {
+            let mut attributes = Vec::new();
+            if let Some(kind) = title.kind {
+                attributes.push(("type", kind.code()));
+            }
+            open(&mut writer, "titleInfo", &attributes)?;
+            if let Some(non_sort) = &title.non_sort {
+                text_element(&mut writer, "nonSort", &[], non_sort)?;
+            }
+            text_element(&mut writer, "title", &[], &title.title)?;
+            for (element, value) in [("subTitle", &title.sub_title), ("partNumber", &title.part_number), ("partName", &title.part_name)] {
+                if let Some(value) = value {
+                    text_element(&mut writer, element, &[], value)?;
+                }
+            }
+            close(&mut writer, "titleInfo")?;
+        }
+        for name in &self.names {
+            let mut attributes = vec![("type", name.kind.code())];
+            if name.primary {
+                attributes.push(("usage", "primary"));
+            }
+            if let Some(uri) = &name.value_uri {
+                attributes.push(("valueURI", uri));
+            }
+            open(&mut writer, "name", &attributes)?;
+            text_element(&mut writer, "namePart", &[], &name.name)?;
+            if let Some(date) = &name.date {
+                text_element(&mut writer, "namePart", &[("type", "date")], date)?;
+            }
+            for role in &name.roles {
+                open(&mut writer, "role", &[])?;
+                if let Some(term) = &role.term {
+                    text_element(&mut writer, "roleTerm", &[("type", "text"), ("authority", "marcrelator")], term)?;
+                }
+                if let Some(code) = &role.code {
+                    text_element(&mut writer, "roleTerm", &[("type", "code"), ("authority", "marcrelator")], code)?;
+                }
+                close(&mut writer, "role")?;
+            }
+            close(&mut writer, "name")?;
+        }
+        if let Some(kind) = &self.type_of_resource {
+            text_element(&mut writer, "typeOfResource", &[], kind)?;
+        }
+        for genre in &self.genres {
+            text_element(&mut writer, "genre", &authority(genre.authority.as_ref()), &genre.value)?;
+        }
+        let origin = &self.origin_info;
+        if origin != &OriginInfo::default() {
+            open(&mut writer, "originInfo", &[])?;
+            for place in &origin.places {
+                open(&mut writer, "place", &[])?;
+                text_element(&mut writer, "placeTerm", &[("type", "text")], place)?;
+                close(&mut writer, "place")?;
+            }
+            for publisher in &origin.publishers {
+                text_element(&mut writer, "publisher", &[], publisher)?;
+            }
+            for date in &origin.dates_issued {
+                text_element(&mut writer, "dateIssued", &[], date)?;
+            }
+            if let Some(edition) = &origin.edition {
+                text_element(&mut writer, "edition", &[], edition)?;
+            }
+            close(&mut writer, "originInfo")?;
+        }
+        for language in &self.languages {
+            open(&mut writer, "language", &[])?;
+            text_element(&mut writer, "languageTerm", &[("type", "code"), ("authority", "iso639-2b")], language)?;
+            close(&mut writer, "language")?;
+        }
+        if let Some(extent) = &self.extent {
+            open(&mut writer, "physicalDescription", &[])?;
+            text_element(&mut writer, "extent", &[], extent)?;
+            close(&mut writer, "physicalDescription")?;
+        }
+        for text in &self.abstracts {
+            text_element(&mut writer, "abstract", &[], text)?;
+        }
+        for note in &self.notes {
+            text_element(&mut writer, "note", &[], note)?;
+        }
+        for subject in &self.subjects {
+            open(&mut writer, "subject", &authority(subject.authority.as_ref()))?;
+            for term in &subject.terms {
+                match term {
+                    SubjectTerm::Topic(value) => text_element(&mut writer, "topic", &[], value)?,
+                    SubjectTerm::Geographic(value) => text_element(&mut writer, "geographic", &[], value)?,
+                    SubjectTerm::Temporal(value) => text_element(&mut writer, "temporal", &[], value)?,
+                    SubjectTerm::Genre(value) => text_element(&mut writer, "genre", &[], value)?,
+                    SubjectTerm::Name(kind, value) => {
+                        open(&mut writer, "name", &[("type", kind.code())])?;
+                        text_element(&mut writer, "namePart", &[], value)?;
+                        close(&mut writer, "name")?;
+                    }
+                    SubjectTerm::Title(value) => {
+                        open(&mut writer, "titleInfo", &[])?;
+                        text_element(&mut writer, "title", &[], value)?;
+                        close(&mut writer, "titleInfo")?;
+                    }
+                }
+            }
+            close(&mut writer, "subject")?;
+        }
+        write_identifiers(&mut writer, &self.identifiers)?;
+        if !self.urls.is_empty() {
+            open(&mut writer, "location", &[])?;
+            for url in &self.urls {
+                text_element(&mut writer, "url", &[], url)?;
+            }
+            close(&mut writer, "location")?;
+        }
+        for item in &self.related_items {
+            let attributes: Vec<(&str, &str)> = item.kind.iter().map(|kind| ("type", kind.code())).collect();
+            open(&mut writer, "relatedItem", &attributes)?;
+            if let Some(title) = &item.title {
+                open(&mut writer, "titleInfo", &[])?;
+                text_element(&mut writer, "title", &[], title)?;
+                close(&mut writer, "titleInfo")?;
+            }
+            if let Some(name) = &item.name {
+                open(&mut writer, "name", &[])?;
+                text_element(&mut writer, "namePart", &[], name)?;
+                close(&mut writer, "name")?;
+            }
+            write_identifiers(&mut writer, &item.identifiers)?;
+            if let Some(part) = &item.part {
+                open(&mut writer, "part", &[])?;
+                text_element(&mut writer, "text", &[], part)?;
+                close(&mut writer, "part")?;
+            }
+            close(&mut writer, "relatedItem")?;
+        }
+        if let Some(id) = &self.record_identifier {
+            open(&mut writer, "recordInfo", &[])?;
+            text_element(&mut writer, "recordIdentifier", &[], id)?;
+            close(&mut writer, "recordInfo")?;
+        }
+        close(&mut writer, "mods")
+    }
+
+    /// The description as a MODS 3 `<mods>` element
+    pub fn to_xml(&self) -> String {
+        let mut output = Vec::new();
+        self.write_xml(&mut output).expect("writing to a Vec cannot fail");
+        String::from_utf8(output).expect("XML is written as UTF-8")
+    }
+}
+
+type XmlWriter<'a> = quick_xml::Writer<&'a mut dyn Write>;
+
+fn open(writer: &mut XmlWriter, name: &str, attributes: &[(&str, &str)]) -> Result<(), WriteError> {
+    let mut start = BytesStart::new(name);
+    start.extend_attributes(attributes.iter().copied());
+    writer.write_event(Event::Start(start))?;
+    Ok(())
+}
+
+fn close(writer: &mut XmlWriter, name: &str) -> Result<(), WriteError> {
+    writer.write_event(Event::End(BytesEnd::new(name)))?;
+    Ok(())
+}
+
+fn text_element(writer: &mut XmlWriter, name: &str, attributes: &[(&str, &str)], text: &str) -> Result<(), WriteError> {
+    open(writer, name, attributes)?;
+    writer.write_event(Event::Text(BytesText::new(text)))?;
+    close(writer, name)
+}
+
+fn write_identifiers(writer: &mut XmlWriter, identifiers: &[ModsIdentifier]) -> Result<(), WriteError> {
+    for identifier in identifiers {
+        let mut attributes = vec![("type", identifier.kind.as_str())];
+        if identifier.invalid {
+            attributes.push(("invalid", "yes"));
+        }
+        text_element(writer, "identifier", &attributes, &identifier.value)?;
+    }
+    Ok(())
+}
+
+/// `authority` attribute of a vocabulary, none when not specified
+fn authority(source: Option<&SubjectSource>) -> Vec<(&'static str, &str)> {
+    source.map(|source| source.code()).filter(|code| !code.is_empty()).map(|code| ("authority", code)).into_iter().collect()
+}
+
+/// MODS type of resource of a leader/06 type of record
+fn type_of_resource(record_type: char) -> Option<&'static str> {
+    match record_type {
+        'a' | 't' => Some("text"),
+        'e' | 'f' => Some("cartographic"),
+        'c' | 'd' => Some("notated music"),
+        'i' => Some("sound recording-nonmusical"),
+        'j' => Some("sound recording-musical"),
+        'k' => Some("still image"),
+        'g' => Some("moving image"),
+        'r' => Some("three dimensional object"),
+        'm' => Some("software, multimedia"),
+        'o' | 'p' => Some("mixed material"),
+        _ => None,
+    }
+}
+
+/// Leader/06 type of record of a MODS type of resource
+fn record_type(type_of_resource: &str) -> Option<char> {
+    ['a', 'e', 'c', 'i', 'j', 'k', 'g', 'r', 'm', 'p'].into_iter().find(|&t| self::type_of_resource(t) == Some(type_of_resource))
+}
+
+fn title_info(field: &DataField, kind: Option<TitleType>) -> TitleInfo {
+    let value = |code: char| field.subfields.iter().find(|s| s.code == code).map(|s| strip_isbd_punctuation_for_tag(&field.tag, &s.value).to_string()).filter(|v| !v.is_empty());
+    let mut title = value('a').unwrap_or_default();
+    let mut non_sort = None;
+    if field.tag == "245" {
+        let skip = field.ind2.to_digit(10).unwrap_or(0) as usize;
+        if let Some((index, _)) = title.char_indices().nth(skip).filter(|_| skip > 0) {
+            non_sort = Some(title[..index].to_string());
+            title = title[index..].to_string();
+        }
+    }
+    TitleInfo {
+        kind,
+        non_sort,
+        title,
+        sub_title: value('b'),
+        part_number: value('n'),
+        part_name: value('p'),
+    }
+}
+
+fn name(field: &DataField, primary: bool) -> ModsName {
+    let kind = NameType::from_tag(&field.tag).unwrap_or(NameType::Personal);
+    let term_code = if kind == NameType::Conference { 'j' } else { 'e' };
+    let mut roles: Vec<Role> = Vec::new();
+    for subfield in &field.subfields {
+        let value = strip_isbd_punctuation_for_tag(&field.tag, &subfield.value);
+        if subfield.code == term_code {
+            roles.push(Role { term: Some(value.to_string()), code: Relator::from_term(value).map(|r| r.code().to_string()) });
+        } else if subfield.code == '4' {
+            let code = Relator::from_code(value).map_or(value.to_string(), |r| r.code().to_string());
+            if !roles.iter().any(|role| role.code.as_deref() == Some(code.as_str())) {
+                roles.push(Role { term: None, code: Some(code) });
+            }
+        }
+    }
+    let value_uri = field.subfields.iter().filter(|s| s.code == '1' || s.code == '0').map(|s| s.value.trim()).find(|v| v.starts_with("http")).map(|v| v.to_string());
+    ModsName {
+        kind,
+        name: strip_isbd_punctuation_for_tag(&field.tag, &field.join_subfields(&['a', 'b', 'c', 'q'], " ")).to_string(),
+        date: field.subfields.iter().find(|s| s.code == 'd').map(|s| strip_isbd_punctuation_for_tag(&field.tag, &s.value).to_string()),
+        roles,
+        primary,
+        value_uri,
+    }
+}
+
+fn identifiers(field: &DataField, kind: &str) -> Vec<ModsIdentifier> {
+    field
+        .subfields
+        .iter()
+        .filter(|s| matches!(s.code, 'a' | 'y' | 'z'))
+        .filter_map(|s| {
+            let value = strip_isbd_punctuation_for_tag(&field.tag, &s.value);
+            // Keep the number without its qualifier, e.g. "(pbk.)"
+            let value = if kind == "isbn" { value.split_whitespace().next()? } else { value.trim() };
+            Some(ModsIdentifier { kind: kind.to_string(), value: value.to_string(), invalid: s.code != 'a' })
+        })
+        .collect()
+}
+
+fn subject(field: &DataField) -> ModsSubject {
+    let mut terms = Vec::new();
+    let heading_codes: &[char] = match field.tag.as_str() {
+        "600" => &['a', 'b', 'c', 'd', 'q'],
+        "610" => &['a', 'b'],
+        "611" => &['a', 'c', 'd', 'n'],
+        "630" => &['a', 'n', 'p'],
+        _ => &['a'],
+    };
+    let heading = strip_isbd_punctuation_for_tag(&field.tag, &field.join_subfields(heading_codes, " ")).to_string();
+    if !heading.is_empty() {
+        terms.push(match field.tag.as_str() {
+            "600" | "610" | "611" => SubjectTerm::Name(NameType::from_tag(&field.tag).unwrap_or(NameType::Personal), heading),
+            "630" => SubjectTerm::Title(heading),
+            "648" => SubjectTerm::Temporal(heading),
+            "651" => SubjectTerm::Geographic(heading),
+            _ => SubjectTerm::Topic(heading),
+        });
+    }
+    for subfield in &field.subfields {
+        let value = strip_isbd_punctuation_for_tag(&field.tag, &subfield.value).to_string();
+        terms.push(match subfield.code {
+            'v' => SubjectTerm::Genre(value),
+            'x' => SubjectTerm::Topic(value),
+            'y' => SubjectTerm::Temporal(value),
+            'z' => SubjectTerm::Geographic(value),
+            _ => continue,
+        });
+    }
+    ModsSubject { authority: Subject::source(field, MarcFormat::Marc21), terms }
+}
+
+/// 6XX field of a subject, tagged after its first term
+fn subject_field(subject: &ModsSubject) -> Option<DataField> {
+    let (first, rest) = subject.terms.split_first()?;
+    let (tag, ind1, heading) = match first {
+        SubjectTerm::Name(kind, value) => (format!("6{}", kind.tag_suffix()), if *kind == NameType::Personal { '1' } else { '2' }, value),
+        SubjectTerm::Title(value) => ("630".to_string(), '0', value),
+        SubjectTerm::Temporal(value) => ("648".to_string(), ' ', value),
+        SubjectTerm::Geographic(value) => ("651".to_string(), ' ', value),
+        SubjectTerm::Topic(value) => ("650".to_string(), ' ', value),
+        SubjectTerm::Genre(value) => ("655".to_string(), ' ', value),
+    };
+    let mut subfields = vec![Subfield { code: 'a', value: heading.clone() }];
+    for term in rest {
+        let (code, value) = match term {
+            SubjectTerm::Genre(value) => ('v', value),
+            SubjectTerm::Temporal(value) => ('y', value),
+            SubjectTerm::Geographic(value) => ('z', value),
+            SubjectTerm::Topic(value) | SubjectTerm::Name(_, value) | SubjectTerm::Title(value) => ('x', value),
+        };
+        subfields.push(Subfield { code, value: value.clone() });
+    }
+    let mut field = DataField { tag, ind1, ind2: ' ', subfields };
+    set_source(&mut field, subject.authority.as_ref());
+    Some(field)
+}
+
+/// Record a vocabulary in the second indicator, or in `$2` with indicator `7`
+fn set_source(field: &mut DataField, source: Option<&SubjectSource>) {
+    let source = source.unwrap_or(&SubjectSource::NotSpecified);
+    field.ind2 = source.indicator();
+    if field.ind2 == '7' {
+        field.subfields.push(Subfield { code: '2', value: source.code().to_string() });
+    }
+}
+
+fn related_item_field(item: &RelatedItem) -> DataField {
+    let kind = item.kind;
+    let tag = kind.map_or("787", |kind| kind.tag());
+    let mut subfields = Vec::new();
+    let push = |subfields: &mut Vec<Subfield>, code: char, value: &Option<String>| subfields.extend(value.iter().map(|value| Subfield { code, value: value.clone() }));
+    if kind == Some(RelatedItemType::Series) {
+        push(&mut subfields, 'a', &item.title);
+        push(&mut subfields, 'v', &item.part);
+    } else {
+        push(&mut subfields, 'a', &item.name);
+        push(&mut subfields, 't', &item.title);
+        push(&mut subfields, 'g', &item.part);
+    }
+    for identifier in &item.identifiers {
+        let code = match identifier.kind.as_str() {
+            "issn" => 'x',
+            "isbn" => 'z',
+            _ => 'w',
+        };
+        subfields.push(Subfield { code, value: identifier.value.clone() });
+    }
+    let ind1 = if tag == "534" { ' ' } else { '0' };
+    DataField { tag: tag.to_string(), ind1, ind2: ' ', subfields }
+}
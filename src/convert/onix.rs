@@ -0,0 +1,380 @@
+//! ONIX for Books 3.0 product records to MARC21 bibliographic records.
+
+use crate::parser::ParseError;
+use crate::publication::{PublicationConvention, PublicationFunction, PublicationStatement};
+use crate::record::{ControlField, DataField, Leader, Record, Subfield};
+use crate::relator::Relator;
+
+/// An XML element, with its text kept as unnamed children so mixed content stays in order
+#[derive(Debug, Default)]
+struct Element {
+    name: String,
+    text: String,
+    children: Vec<Element>,
+}
+
+impl Element {
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|e| e.name == name)
+    }
+
+    fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.children.iter().filter(move |e| e.name == name)
+    }
+
+    /// Text of the first child with this name, whitespace collapsed
+    fn value(&self, name: &str) -> Option<String> {
+        self.child(name).map(|e| e.all_text()).filter(|v| !v.is_empty())
+    }
+
+    /// Text of the element and its descendants, whitespace collapsed
+    fn all_text(&self) -> String {
+        fn collect(element: &Element, out: &mut String) {
+            out.push_str(&element.text);
+            for child in &element.children {
+                // Elements in XHTML text break words
+                if !child.name.is_empty() {
+                    out.push(' ');
+                }
+                collect(child, out);
+            }
+        }
+        let mut text = String::new();
+        collect(self, &mut text);
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+}
+
+fn parse_tree(xml: &str) -> Result<Element, ParseError> {
+    use quick_xml::events::Event;
+    use quick_xml::Reader;
+
+    let mut reader = Reader::from_str(xml);
+    let mut stack = vec![Element::default()];
+    let name = |local: &[u8]| String::from_utf8_lossy(local).into_owned();
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => stack.push(Element { name: name(e.local_name().as_ref()), ..Element::default() }),
+            Ok(Event::Empty(e)) => stack.last_mut().expect("document element").children.push(Element { name: name(e.local_name().as_ref()), ..Element::default() }),
+            Ok(Event::End(_)) => {
+                let element = stack.pop().expect("open element");
+                stack.last_mut().ok_or_else(|| ParseError::InvalidXml("unbalanced end tag".to_string()))?.children.push(element);
+            }
+            Ok(Event::Text(e)) => {
+                let text = e.unescape().map(|t| t.into_owned()).unwrap_or_else(|_| String::from_utf8_lossy(&e).into_owned());
+                stack.last_mut().expect("document element").children.push(Element { text, ..Element::default() });
+            }
+            Ok(Event::CData(e)) => stack.last_mut().expect("document element").children.push(Element { text: String::from_utf8_lossy(&e).into_owned(), ..Element::default() }),
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(ParseError::InvalidXml(format!("{} at position {}", e, reader.buffer_position()))),
+            _ => {}
+        }
+    }
+    match stack.pop() {
+        Some(document) if stack.is_empty() => Ok(document),
+        _ => Err(ParseError::UnexpectedEof),
+    }
+}
+
+/// Convert the products of an ONIX for Books 3.0 message to MARC21 bibliographic records
+///
+/// Only reference tag names (`<ONIXMessage>`, `<Product>`) are read; short-tag
+/// messages are rejected.
+///
+/// | ONIX | MARC21 |
+/// |---|---|
+/// | `RecordReference` | 001 |
+/// | `ProductForm` | leader/06, 008/23, 020 `$q`, 336-338 |
+/// | `ProductIdentifier` (ISBN-13, ISBN-10, GTIN-13, DOI) | 020, 024 |
+/// | `Language` | 008/35-37, 041 when there are several or a translation |
+/// | `Contributor` | 100/110 for the first author, 700/710 otherwise, with `$e` and `$4` |
+/// | `TitleDetail` (distinctive title) | 245 |
+/// | `EditionStatement` | 250 |
+/// | `PublishingDetail` | 264 #1, 008/07-10 and 15-17 |
+/// | `Extent` (pages) | 300 |
+/// | `Collection`, or a collection-level `TitleElement` | 490 |
+/// | `TextContent` (description) | 520 |
+/// | `Subject` (BISAC, keywords) | 650 #7, 653 |
+///
+/// Records are minimal-level (leader/17 `7`), or prepublication (`8`) for
+/// forthcoming products, and follow RDA.
+pub fn parse_onix(xml: &str) -> Result<Vec<Record>, ParseError> {
+    let document = parse_tree(xml)?;
+    let message = document.children.iter().find(|e| !e.name.is_empty()).ok_or_else(|| ParseError::InvalidXml("no root element".to_string()))?;
+    match message.name.as_str() {
+        "ONIXMessage" => {}
+        "ONIXmessage" => return Err(ParseError::Other("short-tag ONIX is not supported, use reference tags".to_string())),
+        other => return Err(ParseError::InvalidXml(format!("expected an ONIXMessage, found <{}>", other))),
+    }
+    let sent = message.child("Header").and_then(|header| header.value("SentDateTime"));
+    Ok(message.children("Product").map(|product| product_record(product, sent.as_deref())).collect())
+}
+
+/// What the ONIX product form tells about the MARC description
+struct Form {
+    record_type: char,
+    form_of_item: char,
+    qualifier: Option<&'static str>,
+    content: (&'static str, &'static str),
+    media: Option<(&'static str, &'static str)>,
+    carrier: Option<(&'static str, &'static str)>,
+}
+
+impl Form {
+    /// Read an ONIX product form code (list 150)
+    fn of(code: &str) -> Self {
+        const TEXT: (&str, &str) = ("text", "txt");
+        const SPOKEN: (&str, &str) = ("spoken word", "spw");
+        const UNMEDIATED: Option<(&str, &str)> = Some(("unmediated", "n"));
+        const VOLUME: Option<(&str, &str)> = Some(("volume", "nc"));
+        const COMPUTER: Option<(&str, &str)> = Some(("computer", "c"));
+        const ONLINE: Option<(&str, &str)> = Some(("online resource", "cr"));
+        let form = |record_type, form_of_item, qualifier, content, media, carrier| Form { record_type, form_of_item, qualifier, content, media, carrier };
+        match code {
+            "BB" => form('a', ' ', Some("hardcover"), TEXT, UNMEDIATED, VOLUME),
+            "BC" => form('a', ' ', Some("paperback"), TEXT, UNMEDIATED, VOLUME),
+            _ if code.starts_with('B') => form('a', ' ', None, TEXT, UNMEDIATED, VOLUME),
+            "DG" => form('a', 'o', Some("ebook"), TEXT, COMPUTER, ONLINE),
+            _ if code.starts_with('E') => form('a', 'o', Some("ebook"), TEXT, COMPUTER, ONLINE),
+            "AC" => form('i', ' ', Some("audio CD"), SPOKEN, Some(("audio", "s")), Some(("audio disc", "sd"))),
+            "AJ" | "AN" => form('i', 'o', Some("audio download"), SPOKEN, COMPUTER, ONLINE),
+            _ if code.starts_with('A') => form('i', ' ', None, SPOKEN, Some(("audio", "s")), None),
+            _ => form('a', ' ', None, TEXT, None, None),
+        }
+    }
+}
+
+/// Relator of an ONIX contributor role code (list 17)
+fn relator(role: &str) -> Option<Relator> {
+    match role {
+        "A01" => Some(Relator::Author),
+        "A12" => Some(Relator::Illustrator),
+        "A13" => Some(Relator::Photographer),
+        "A19" => Some(Relator::AuthorOfAfterword),
+        "A23" | "A24" => Some(Relator::AuthorOfIntroduction),
+        "B01" => Some(Relator::Editor),
+        "B06" => Some(Relator::Translator),
+        "E07" => Some(Relator::Narrator),
+        _ => None,
+    }
+}
+
+/// MARC country code of an ISO 3166-1 country, for the most common publishing countries
+fn country(iso: &str) -> &'static str {
+    match iso {
+        "US" => "xxu",
+        "GB" => "xxk",
+        "CA" => "xxc",
+        "AU" => "at ",
+        "NZ" => "nz ",
+        "IE" => "ie ",
+        "FR" => "fr ",
+        "DE" => "gw ",
+        "IT" => "it ",
+        "ES" => "sp ",
+        "NL" => "ne ",
+        "BE" => "be ",
+        "CH" => "sz ",
+        "JP" => "ja ",
+        "IN" => "ii ",
+        _ => "xx ",
+    }
+}
+
+/// Title, nonfiling prefix length, subtitle and part number of a `TitleElement`
+fn title_element(element: &Element) -> Option<(String, usize, Option<String>, Option<String>)> {
+    let (title, nonfiling) = match (element.value("TitlePrefix"), element.value("TitleWithoutPrefix")) {
+        (Some(prefix), Some(rest)) => (format!("{} {}", prefix, rest), prefix.chars().count() + 1),
+        (None, Some(rest)) => (rest, 0),
+        _ => (element.value("TitleText")?, 0),
+    };
+    Some((title, nonfiling, element.value("Subtitle"), element.value("PartNumber")))
+}
+
+fn product_record(product: &Element, sent: Option<&str>) -> Record {
+    let none = Element::default();
+    let descriptive = product.child("DescriptiveDetail").unwrap_or(&none);
+    let publishing = product.child("PublishingDetail").unwrap_or(&none);
+    let form = Form::of(descriptive.value("ProductForm").as_deref().unwrap_or(""));
+
+    let mut fields = Vec::new();
+    let mut add = |tag: &str, (ind1, ind2): (char, char), subfields: Vec<(char, String)>| {
+        fields.push(DataField {
+            tag: tag.to_string(),
+            ind1,
+            ind2,
+            subfields: subfields.into_iter().map(|(code, value)| Subfield { code, value }).collect(),
+        })
+    };
+
+    let identifiers: Vec<(String, String)> = product.children("ProductIdentifier").filter_map(|id| Some((id.value("ProductIDType")?, id.value("IDValue")?))).collect();
+    let is_isbn = |value: &str| identifiers.iter().any(|(kind, isbn)| kind == "15" && isbn == value);
+    for (kind, value) in &identifiers {
+        match kind.as_str() {
+            "15" | "02" => add("020", (' ', ' '), [('a', value.clone())].into_iter().chain(form.qualifier.map(|q| ('q', q.to_string()))).collect()),
+            "03" if !is_isbn(value) => add("024", ('3', ' '), vec![('a', value.clone())]),
+            "06" => add("024", ('7', ' '), vec![('a', value.clone()), ('2', "doi".to_string())]),
+            _ => {}
+        }
+    }
+
+    let languages: Vec<(String, String)> = descriptive.children("Language").filter_map(|l| Some((l.value("LanguageRole")?, l.value("LanguageCode")?.to_lowercase()))).collect();
+    let text_languages: Vec<&str> = languages.iter().filter(|(role, _)| role == "01").map(|(_, code)| code.as_str()).collect();
+    let original_languages: Vec<&str> = languages.iter().filter(|(role, _)| role == "02").map(|(_, code)| code.as_str()).collect();
+    if text_languages.len() > 1 || !original_languages.is_empty() {
+        let ind1 = if original_languages.is_empty() { '0' } else { '1' };
+        let subfields = text_languages.iter().map(|code| ('a', code.to_string())).chain(original_languages.iter().map(|code| ('h', code.to_string()))).collect();
+        add("041", (ind1, ' '), subfields);
+    }
+
+    let mut contributors: Vec<&Element> = descriptive.children("Contributor").collect();
+    contributors.sort_by_key(|c| c.value("SequenceNumber").and_then(|n| n.parse::<usize>().ok()).unwrap_or(usize::MAX));
+    let mut main_entry = false;
+    for contributor in contributors {
+        let roles: Vec<String> = contributor.children("ContributorRole").map(|r| r.all_text()).collect();
+        let relators: Vec<Relator> = roles.iter().filter_map(|role| relator(role)).collect();
+        let personal = contributor
+            .value("PersonNameInverted")
+            .or_else(|| contributor.value("KeyNames").map(|key| contributor.value("NamesBeforeKey").map_or(key.clone(), |before| format!("{}, {}", key, before))))
+            .or_else(|| contributor.value("PersonName"));
+        let (suffix, ind1, name) = match (personal, contributor.value("CorporateName")) {
+            (Some(name), _) => ("00", if name.contains(", ") { '1' } else { '0' }, name),
+            (None, Some(name)) => ("10", '2', name),
+            (None, None) => continue,
+        };
+        let prefix = if !main_entry && roles.iter().any(|r| r == "A01") { '1' } else { '7' };
+        main_entry |= prefix == '1';
+        let mut subfields = vec![('a', name)];
+        subfields.extend(relators.iter().map(|r| ('e', r.label().to_lowercase())));
+        subfields.extend(relators.iter().map(|r| ('4', r.code().to_string())));
+        add(&format!("{}{}", prefix, suffix), (ind1, ' '), subfields);
+    }
+
+    let mut series = Vec::new();
+    let distinctive = descriptive.children("TitleDetail").find(|t| t.value("TitleType").as_deref() == Some("01"));
+    for element in distinctive.iter().flat_map(|t| t.children("TitleElement")) {
+        let Some((title, nonfiling, subtitle, part)) = title_element(element) else { continue };
+        match element.value("TitleElementLevel").as_deref() {
+            Some("01") | None => {
+                let mut subfields = vec![('a', title)];
+                subfields.extend(part.map(|part| ('n', part)));
+                subfields.extend(subtitle.map(|subtitle| ('b', subtitle)));
+                subfields.extend(descriptive.value("ContributorStatement").map(|statement| ('c', statement)));
+                let ind2 = char::from_digit(nonfiling.min(9) as u32, 10).unwrap_or('0');
+                add("245", (if main_entry { '1' } else { '0' }, ind2), subfields);
+            }
+            Some("02") => series.push((title, part)),
+            _ => {}
+        }
+    }
+    for collection in descriptive.children("Collection") {
+        let number = collection.child("CollectionSequence").and_then(|s| s.value("CollectionSequenceNumber"));
+        let title = collection.children("TitleDetail").flat_map(|t| t.children("TitleElement")).find_map(title_element);
+        if let Some((title, _, _, part)) = title {
+            series.push((title, number.or(part)));
+        }
+    }
+    for (title, number) in series {
+        add("490", ('0', ' '), [('a', title)].into_iter().chain(number.map(|n| ('v', n))).collect());
+    }
+
+    if let Some(edition) = descriptive.value("EditionStatement") {
+        add("250", (' ', ' '), vec![('a', edition)]);
+    }
+
+    let date = publishing.children("PublishingDate").find(|d| d.value("PublishingDateRole").as_deref() == Some("01")).and_then(|d| d.value("Date"));
+    let year = date.as_deref().and_then(|d| d.get(..4)).filter(|y| y.bytes().all(|b| b.is_ascii_digit())).map(|y| y.to_string());
+    let statement = PublicationStatement {
+        places: publishing.value("CityOfPublication").into_iter().collect(),
+        publishers: publishing.children("Publisher").filter(|p| p.value("PublishingRole").as_deref().is_none_or(|role| role == "01")).filter_map(|p| p.value("PublisherName")).collect(),
+        dates: year.iter().cloned().collect(),
+        ..PublicationStatement::new(PublicationFunction::Publication)
+    };
+    if !statement.places.is_empty() || !statement.publishers.is_empty() || !statement.dates.is_empty() {
+        fields.push(statement.to_field(PublicationConvention::Rda));
+    }
+
+    let pages = descriptive.children("Extent").find(|e| matches!(e.value("ExtentType").as_deref(), Some("00" | "11")) && e.value("ExtentUnit").as_deref() == Some("03")).and_then(|e| e.value("ExtentValue"));
+    if let Some(pages) = pages {
+        fields.push(DataField {
+            tag: "300".to_string(),
+            ind1: ' ',
+            ind2: ' ',
+            subfields: vec![Subfield { code: 'a', value: format!("{} pages", pages) }],
+        });
+    }
+    let rda = |tag: &str, (term, code): (&str, &str), source: &str| DataField {
+        tag: tag.to_string(),
+        ind1: ' ',
+        ind2: ' ',
+        subfields: vec![Subfield { code: 'a', value: term.to_string() }, Subfield { code: 'b', value: code.to_string() }, Subfield { code: '2', value: source.to_string() }],
+    };
+    fields.push(rda("336", form.content, "rdacontent"));
+    fields.extend(form.media.map(|media| rda("337", media, "rdamedia")));
+    fields.extend(form.carrier.map(|carrier| rda("338", carrier, "rdacarrier")));
+
+    let collateral = product.child("CollateralDetail").unwrap_or(&none);
+    let description = ["03", "02"].iter().find_map(|kind| collateral.children("TextContent").find(|t| t.value("TextType").as_deref() == Some(kind)).and_then(|t| t.value("Text")));
+    if let Some(description) = description {
+        fields.push(DataField {
+            tag: "520".to_string(),
+            ind1: ' ',
+            ind2: ' ',
+            subfields: vec![Subfield { code: 'a', value: strip_tags(&description) }],
+        });
+    }
+
+    for subject in descriptive.children("Subject") {
+        let Some(heading) = subject.value("SubjectHeadingText") else { continue };
+        let field = |tag: &str, ind2: char, subfields: Vec<Subfield>| DataField { tag: tag.to_string(), ind1: ' ', ind2, subfields };
+        match subject.value("SubjectSchemeIdentifier").as_deref() {
+            Some("10") => fields.push(field("650", '7', vec![Subfield { code: 'a', value: heading }, Subfield { code: '2', value: "bisacsh".to_string() }])),
+            Some("20") => fields.push(field("653", ' ', heading.split(';').map(|k| k.trim()).filter(|k| !k.is_empty()).map(|k| Subfield { code: 'a', value: k.to_string() }).collect())),
+            _ => {}
+        }
+    }
+    fields.sort_by(|a, b| a.tag.cmp(&b.tag));
+
+    let forthcoming = publishing.value("PublishingStatus").as_deref() == Some("02");
+    let leader = format!("00000n{}m a2200000{}i 4500", form.record_type, if forthcoming { '8' } else { '7' });
+    let entered = sent.filter(|s| s.len() >= 8 && s[..8].bytes().all(|b| b.is_ascii_digit())).map_or("      ", |s| &s[2..8]);
+    let (date_type, date1) = match &year {
+        Some(year) => ('s', year.as_str()),
+        None => ('n', "uuuu"),
+    };
+    let place = country(publishing.value("CountryOfPublication").as_deref().unwrap_or(""));
+    let language = text_languages.first().copied().filter(|code| code.len() == 3).unwrap_or("und");
+    let fixed = format!("{}{}{}    {}|||||{}|||||||| ||{} d", entered, date_type, date1, place, form.form_of_item, language);
+
+    let mut control_fields = Vec::new();
+    if let Some(reference) = product.value("RecordReference") {
+        control_fields.push(ControlField { tag: "001".to_string(), value: reference });
+    }
+    control_fields.push(ControlField { tag: "008".to_string(), value: fixed });
+
+    let mut record = Record {
+        leader: Leader::from_bytes(leader.as_bytes()).expect("leader is 24 ASCII bytes"),
+        control_fields,
+        data_fields: fields,
+    };
+    record.update_leader_lengths();
+    record
+}
+
+/// Remove the markup of escaped HTML text
+fn strip_tags(text: &str) -> String {
+    let mut out = String::new();
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => {
+                in_tag = true;
+                out.push(' ');
+            }
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
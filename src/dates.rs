@@ -0,0 +1,269 @@
+//! Publication dates: coded dates of 008/06-14 (MARC21) or 100 `$a`/8-16
+//! (UNIMARC), read together with the free-text date of the publication statement.
+//!
+//! Coded dates are preferred; the statement date (260/264 `$c`, 210/214 `$d`)
+//! is interpreted when they are missing or not coded, e.g. `[195-?]` gives the
+//! years 1950 to 1959 and `1968-` a range still open.
+//!
+//! ```
+//! use marc_rs::{parse_mrk, DateType, MarcFormat};
+//!
+//! let record = parse_mrk("=LDR  00000nam a2200000 a 4500\n=008  000000q19uu1925xx\\|||||||||||||||||eng\\d\n").unwrap().remove(0);
+//! let dates = record.dates(MarcFormat::Marc21);
+//! assert_eq!(dates.date_type, DateType::Questionable);
+//! assert_eq!((dates.earliest, dates.latest), (Some(1900), Some(1925)));
+//! ```
+
+use crate::fixed_fields::{GeneralProcessingData, PublicationDateType};
+use crate::format::MarcFormat;
+use crate::publication::PublicationFunction;
+use crate::record::Record;
+
+/// How the publication dates of a record are to be read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateType {
+    /// A single known or probable date (MARC21 `s`, UNIMARC `d`)
+    Single,
+    /// Multiple dates, inclusive dates of a collection or dates of a continuing
+    /// resource: date 1 to date 2 (MARC21 `c`, `d`, `i`, `k`, `m`, `u`, UNIMARC `a`, `b`, `c`, `g`)
+    Range,
+    /// Questionable date, between date 1 and date 2 (MARC21 `q`, UNIMARC `f`)
+    Questionable,
+    /// Reprint or reproduction date, with the original date in date 2 (MARC21 `r`, UNIMARC `e`)
+    Reprint,
+    /// Detailed date, the month and day in date 2 (MARC21 `e`, UNIMARC `j`)
+    Detailed,
+    /// Publication date, with the copyright date in date 2 (MARC21 `t`, UNIMARC `h`)
+    Copyright,
+    /// Release or publication date, with the production date (MARC21 `p`, UNIMARC `i`, `k`)
+    Production,
+    /// No dates, dates unknown or not coded (MARC21 `b`, `n`, `|`, UNIMARC `u`)
+    Unknown,
+}
+
+impl DateType {
+    /// Get the date type from a MARC21 008/06 code
+    pub fn from_marc21(code: char) -> Self {
+        match code {
+            's' => DateType::Single,
+            'c' | 'd' | 'i' | 'k' | 'm' | 'u' => DateType::Range,
+            'q' => DateType::Questionable,
+            'r' => DateType::Reprint,
+            'e' => DateType::Detailed,
+            't' => DateType::Copyright,
+            'p' => DateType::Production,
+            _ => DateType::Unknown,
+        }
+    }
+
+    /// Get the date type from a UNIMARC type of publication date (100 `$a`/8)
+    pub fn from_unimarc(date_type: PublicationDateType) -> Self {
+        match date_type {
+            PublicationDateType::Monograph => DateType::Single,
+            PublicationDateType::CurrentContinuingResource | PublicationDateType::DeadContinuingResource | PublicationDateType::ContinuingResourceUnknownStatus | PublicationDateType::MultiYearMonograph => DateType::Range,
+            PublicationDateType::UncertainDates => DateType::Questionable,
+            PublicationDateType::Reproduction => DateType::Reprint,
+            PublicationDateType::DetailedDate => DateType::Detailed,
+            PublicationDateType::ActualAndCopyrightDates => DateType::Copyright,
+            PublicationDateType::ReleaseAndProductionDates | PublicationDateType::ProductionAndPublicationDates => DateType::Production,
+            PublicationDateType::Unknown => DateType::Unknown,
+        }
+    }
+}
+
+/// Publication dates of a record, see [`Record::dates`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicationDates {
+    /// Date type of the coded dates, or read from the statement date when they are not coded
+    pub date_type: DateType,
+    /// Coded date 1, e.g. `19uu`; empty without coded dates
+    pub date1: String,
+    /// Coded date 2
+    pub date2: String,
+    /// Free-text date of the publication statement, e.g. `c2001` or `[195-?]`
+    pub statement: Option<String>,
+    /// Earliest possible year of publication
+    pub earliest: Option<i32>,
+    /// Latest possible year of publication, `None` when unknown or still ongoing
+    pub latest: Option<i32>,
+    /// True for a range still open: a continuing resource still published, or a statement date like `1968-`
+    pub ongoing: bool,
+    /// Copyright year, from date 2, a 264 copyright statement or a `c2001` statement date
+    pub copyright: Option<i32>,
+    /// Year of the original, for reprints
+    pub original: Option<i32>,
+}
+
+impl PublicationDates {
+    /// Year to display or sort by: the earliest year
+    pub fn year(&self) -> Option<i32> {
+        self.earliest
+    }
+}
+
+impl Record {
+    /// Publication dates of the record
+    ///
+    /// The years come from the coded dates when they have a usable date 1, and
+    /// from the statement date of the first publication statement otherwise.
+    /// Unknown digits (`19uu`, `[19--]`) widen the range to their possible years.
+    pub fn dates(&self, format: MarcFormat) -> PublicationDates {
+        // UNIMARC type k puts the publication date in date 2
+        let (date_type, date1, date2, publication_in_date2) = match format {
+            MarcFormat::Marc21 | MarcFormat::MarcXml => match self.control_fields.iter().find(|f| f.tag == "008") {
+                Some(field) => (
+                    field.value.chars().nth(6).map_or(DateType::Unknown, DateType::from_marc21),
+                    field.value.get(7..11).unwrap_or_default().to_string(),
+                    field.value.get(11..15).unwrap_or_default().to_string(),
+                    false,
+                ),
+                None => (DateType::Unknown, String::new(), String::new(), false),
+            },
+            MarcFormat::Unimarc => match GeneralProcessingData::from_record(self).and_then(|data| data.ok()) {
+                Some(data) => (DateType::from_unimarc(data.publication_date_type), data.date1, data.date2, data.publication_date_type == PublicationDateType::ProductionAndPublicationDates),
+                None => (DateType::Unknown, String::new(), String::new(), false),
+            },
+        };
+
+        let statements = self.publication_statements(format);
+        let statement = statements.iter().find(|s| s.function == PublicationFunction::Publication).and_then(|s| s.date()).map(str::to_string);
+        let text = statement.as_deref().map(TextDates::parse).unwrap_or_default();
+        let copyright_statement = statements.iter().find(|s| s.function == PublicationFunction::Copyright).and_then(|s| s.date()).and_then(|date| TextDates::parse(date).years.first().map(|(low, _)| *low));
+
+        let (year1, year2) = (coded_years(&date1), coded_years(&date2));
+        let publication = if publication_in_date2 { year2 } else { year1 };
+        let mut dates = PublicationDates {
+            date_type,
+            date1: date1.trim().to_string(),
+            date2: date2.trim().to_string(),
+            statement,
+            earliest: None,
+            latest: None,
+            ongoing: false,
+            copyright: None,
+            original: None,
+        };
+        match (date_type, publication) {
+            (DateType::Unknown, _) | (_, None) => {
+                dates.date_type = text.date_type();
+                dates.earliest = text.years.iter().map(|(low, _)| *low).min();
+                dates.latest = text.years.iter().map(|(_, high)| *high).max().filter(|_| !text.open);
+                dates.ongoing = text.open;
+            }
+            (DateType::Range, Some((low, high))) => {
+                dates.earliest = Some(low);
+                dates.ongoing = date2 == "9999";
+                dates.latest = if dates.ongoing { None } else { Some(year2.map_or(high, |(_, high)| high)) };
+            }
+            (DateType::Questionable, Some((low, high))) => {
+                dates.earliest = Some(low);
+                dates.latest = Some(year2.map_or(high, |(_, high)| high));
+            }
+            (_, Some((low, high))) => {
+                dates.earliest = Some(low);
+                dates.latest = Some(high);
+            }
+        }
+        dates.copyright = match date_type {
+            DateType::Copyright => year2.map(|(low, _)| low),
+            _ => None,
+        }
+        .or(copyright_statement)
+        .or(text.copyright);
+        if date_type == DateType::Reprint {
+            dates.original = year2.map(|(low, _)| low);
+        }
+        dates
+    }
+}
+
+/// Earliest and latest years of a coded date, e.g. 1900 and 1999 for `19uu`
+///
+/// The first two digits must be known; `u`, `-` and blanks stand for unknown digits.
+fn coded_years(date: &str) -> Option<(i32, i32)> {
+    let chars: Vec<char> = date.chars().collect();
+    if chars.len() != 4 || !chars[..2].iter().all(|c| c.is_ascii_digit()) || !chars[2..].iter().all(|c| c.is_ascii_digit() || matches!(c, 'u' | '-' | ' ')) {
+        return None;
+    }
+    year_bounds(&chars)
+}
+
+/// Years of a 4-character year with unknown digits replaced by 0 and by 9
+fn year_bounds(chars: &[char]) -> Option<(i32, i32)> {
+    let fill = |digit: char| chars.iter().map(|c| if c.is_ascii_digit() { *c } else { digit }).collect::<String>().parse().ok();
+    Some((fill('0')?, fill('9')?))
+}
+
+/// Years read from a free-text date
+#[derive(Debug, Default)]
+struct TextDates {
+    /// Earliest and latest possible year of each year of publication
+    years: Vec<(i32, i32)>,
+    /// Year preceded by `c`, `©`, `p` or `℗`
+    copyright: Option<i32>,
+    /// Uncertain: a `?`, unknown digits, or words like `between` and `ca.`
+    questionable: bool,
+    /// Ends with a hyphen after a year, e.g. `1968-`
+    open: bool,
+}
+
+impl TextDates {
+    /// Read a date like `c2001`, `[1954?]`, `[19--]`, `1968-1972` or `1995 [i.e. 1996]`
+    fn parse(text: &str) -> Self {
+        // A corrected date replaces the date as printed
+        let text = text.rfind("i.e.").map_or(text, |i| &text[i + 4..]);
+        let chars: Vec<char> = text.chars().collect();
+        let mut dates = TextDates {
+            questionable: text.contains('?') || ["between", " or ", "ca.", "circa", "approximately"].iter().any(|word| text.to_lowercase().contains(word)),
+            ..TextDates::default()
+        };
+        let mut copyright = Vec::new();
+        let mut end = None;
+        let mut i = 0;
+        while i + 4 <= chars.len() {
+            let window = &chars[i..i + 4];
+            let before = i.checked_sub(1).map(|j| chars[j]);
+            let is_year = window[..2].iter().all(|c| c.is_ascii_digit())
+                && window[2..].iter().all(|c| c.is_ascii_digit() || matches!(c, '-' | 'u' | '?'))
+                && !before.is_some_and(|c| c.is_ascii_digit())
+                && !chars.get(i + 4).is_some_and(|c| c.is_ascii_digit());
+            if !is_year {
+                i += 1;
+                continue;
+            }
+            let Some(bounds) = year_bounds(window) else {
+                i += 1;
+                continue;
+            };
+            dates.questionable |= !window.iter().all(|c| c.is_ascii_digit());
+            if before.is_some_and(|c| matches!(c, 'c' | '©' | 'p' | '℗')) {
+                copyright.push(bounds.0);
+            } else {
+                dates.years.push(bounds);
+                end = Some(i + 4);
+            }
+            i += 4;
+        }
+        dates.copyright = copyright.first().copied();
+        // A copyright date alone stands for the date of publication
+        if dates.years.is_empty() {
+            dates.years = copyright.iter().map(|year| (*year, *year)).collect();
+        }
+        dates.open = end.is_some_and(|end| {
+            let rest: String = chars[end..].iter().filter(|c| !c.is_whitespace()).collect();
+            rest.starts_with('-') && rest[1..].chars().all(|c| matches!(c, '.' | ']' | '?' | ')'))
+        });
+        dates
+    }
+
+    /// Date type matching the years read
+    fn date_type(&self) -> DateType {
+        let distinct = self.years.iter().map(|(low, _)| low).min() != self.years.iter().map(|(low, _)| low).max();
+        match self.years.first() {
+            None => DateType::Unknown,
+            Some(_) if self.open || (distinct && !self.questionable) => DateType::Range,
+            Some(_) if self.questionable => DateType::Questionable,
+            Some(_) => DateType::Single,
+        }
+    }
+}
@@ -0,0 +1,173 @@
+//! Duplicate detection over large collections.
+//!
+//! Records are duplicates when they share a key: a control number, a system
+//! number, an ISBN or a [match key](Record::match_key). A [`DedupIndex`]
+//! remembers the first record seen with each key and can be shared by parsing
+//! threads, so that a merge of millions of records is checked as it is read.
+//!
+//! ```
+//! use marc_rs::{parse_mrk, DedupIndex, DedupKey, MarcFormat};
+//!
+//! let records = parse_mrk("=LDR  00000nam a2200000 a 4500\n=001  a1\n=020  \\\\$a0261102214\n\n=LDR  00000nam a2200000 a 4500\n=001  b7\n=020  \\\\$a978-0-261-10221-7 (pbk.)\n").unwrap();
+//! let index = DedupIndex::new();
+//! let keys = [DedupKey::ControlNumber, DedupKey::Isbn];
+//! std::thread::scope(|scope| {
+//!     for (id, record) in records.iter().enumerate() {
+//!         let index = &index;
+//!         scope.spawn(move || index.insert_record(id, record, &keys, MarcFormat::Marc21));
+//!     }
+//! });
+//! // Whatever the order of the threads, the first record holds the shared ISBN
+//! assert_eq!(index.get("isbn 9780261102217"), Some(0));
+//! assert_eq!(index.len(), 3);
+//! ```
+
+use crate::format::MarcFormat;
+use crate::identifiers::normalize_isbn;
+use crate::mapping::subfield_values;
+use crate::record::Record;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, RandomState};
+use std::sync::Mutex;
+
+/// Key identifying duplicate records
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DedupKey {
+    /// Control number (001)
+    ControlNumber,
+    /// System control numbers (035$a)
+    SystemNumber,
+    /// ISBNs (020$a, 010$a in UNIMARC), as ISBN-13
+    Isbn,
+    /// [`Record::match_key`]
+    MatchKey,
+}
+
+impl DedupKey {
+    /// Get a key from its name: `001`, `035`, `isbn` or `match-key`
+    pub fn parse(key: &str) -> Result<Self, String> {
+        match key.trim().to_lowercase().as_str() {
+            "001" => Ok(DedupKey::ControlNumber),
+            "035" => Ok(DedupKey::SystemNumber),
+            "isbn" => Ok(DedupKey::Isbn),
+            "match-key" | "matchkey" => Ok(DedupKey::MatchKey),
+            _ => Err(format!("Unknown key: {}. Use: 001, 035, isbn or match-key", key)),
+        }
+    }
+}
+
+impl Record {
+    /// Keys of the record, prefixed by their kind so that values of different kinds never match,
+    /// e.g. `001 12345` or `isbn 9780261102217`
+    pub fn dedup_keys(&self, keys: &[DedupKey], format: MarcFormat) -> Vec<String> {
+        let mut values = Vec::new();
+        for key in keys {
+            match key {
+                DedupKey::ControlNumber => values.extend(self.control_fields.iter().filter(|f| f.tag == "001").map(|f| format!("001 {}", f.value.trim()))),
+                DedupKey::SystemNumber => values.extend(subfield_values(self, "035", 'a').into_iter().map(|v| format!("035 {}", v.trim()))),
+                DedupKey::Isbn => {
+                    let tag = match format {
+                        MarcFormat::Unimarc => "010",
+                        _ => "020",
+                    };
+                    values.extend(subfield_values(self, tag, 'a').into_iter().filter_map(normalize_isbn).map(|isbn| format!("isbn {}", isbn)));
+                }
+                DedupKey::MatchKey => values.extend(self.match_key(format).map(|key| format!("match-key {}", key))),
+            }
+        }
+        // Empty values identify nothing
+        values.retain(|value| !value.ends_with(' '));
+        values
+    }
+}
+
+/// Thread-safe index of the record holding each key
+///
+/// Records are identified by a number, e.g. their position in the input. Each
+/// key maps to the smallest id inserted with it, so once all records are
+/// inserted the index is the same whatever the order of the threads. Keys are
+/// spread over shards, each behind its own lock, so that threads seldom wait.
+#[derive(Debug)]
+pub struct DedupIndex {
+    shards: Vec<Mutex<HashMap<String, usize>>>,
+    hasher: RandomState,
+}
+
+impl Default for DedupIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DedupIndex {
+    /// An empty index, with 16 shards per available thread
+    pub fn new() -> Self {
+        Self::with_shards(16 * std::thread::available_parallelism().map_or(1, |n| n.get()))
+    }
+
+    /// An empty index with a number of shards
+    pub fn with_shards(shards: usize) -> Self {
+        Self {
+            shards: (0..shards.max(1)).map(|_| Mutex::new(HashMap::new())).collect(),
+            hasher: RandomState::new(),
+        }
+    }
+
+    fn shard(&self, key: &str) -> std::sync::MutexGuard<'_, HashMap<String, usize>> {
+        let shard = &self.shards[self.hasher.hash_one(key) as usize % self.shards.len()];
+        // A panic while holding the lock leaves the map consistent: keep using it
+        shard.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Insert a key for record `id`, returning the other record that had it, if any
+    ///
+    /// When records are inserted out of order, the returned record may come
+    /// after `id`; the key then moves to `id`.
+    pub fn insert(&self, key: &str, id: usize) -> Option<usize> {
+        let mut shard = self.shard(key);
+        match shard.get_mut(key) {
+            Some(holder) if *holder == id => None,
+            Some(holder) => {
+                let other = *holder;
+                *holder = other.min(id);
+                Some(other)
+            }
+            None => {
+                shard.insert(key.to_string(), id);
+                None
+            }
+        }
+    }
+
+    /// Insert the keys of record `id`, returning the first other record that had one of them
+    pub fn insert_keys<I, K>(&self, id: usize, keys: I) -> Option<usize>
+    where
+        I: IntoIterator<Item = K>,
+        K: AsRef<str>,
+    {
+        keys.into_iter().filter_map(|key| self.insert(key.as_ref(), id)).min()
+    }
+
+    /// Insert the keys of a record, see [`Record::dedup_keys`]: have we seen this record?
+    pub fn insert_record(&self, id: usize, record: &Record, keys: &[DedupKey], format: MarcFormat) -> Option<usize> {
+        self.insert_keys(id, record.dedup_keys(keys, format))
+    }
+
+    /// Record holding a key
+    pub fn get(&self, key: &str) -> Option<usize> {
+        self.shard(key).get(key).copied()
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Number of keys
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|shard| shard.lock().unwrap_or_else(|e| e.into_inner()).len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
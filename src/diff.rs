@@ -0,0 +1,163 @@
+//! Field-level differences between two versions of a record.
+//!
+//! Fields are compared as MarcMaker text (see [`mrk`](crate::mrk)): fields
+//! present unchanged in both records, whatever their position, are not
+//! reported. Remaining fields with the same tag are paired in order as changes;
+//! the others are additions or removals.
+//!
+//! ```
+//! use marc_rs::{ChangeKind, ControlField, Leader, Record};
+//!
+//! let old = Record {
+//!     leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+//!     control_fields: vec![ControlField { tag: "001".to_string(), value: "1".to_string() }],
+//!     data_fields: vec![],
+//! };
+//! let mut new = old.clone();
+//! new.control_fields[0].value = "2".to_string();
+//!
+//! let changes = old.diff(&new);
+//! assert_eq!(changes.len(), 1);
+//! assert_eq!(changes[0].kind(), ChangeKind::Changed);
+//! assert_eq!(changes[0].to_string(), "- 001  1\n+ 001  2");
+//! ```
+
+use crate::mrk::{control_field_text, data_field_text, leader_text};
+use crate::record::Record;
+use std::fmt::Display;
+
+/// Kind of a [`FieldChange`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    /// The field only exists in the new record
+    Added,
+    /// The field only exists in the old record
+    Removed,
+    /// The field exists in both records with different content
+    Changed,
+}
+
+impl Display for ChangeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChangeKind::Added => write!(f, "added"),
+            ChangeKind::Removed => write!(f, "removed"),
+            ChangeKind::Changed => write!(f, "changed"),
+        }
+    }
+}
+
+/// A difference between two records
+///
+/// Values are MarcMaker text without the tag: the control field value, or the
+/// indicators followed by the subfields (`10$aTitle`). The leader has the tag
+/// `LDR`; its record length and base address are ignored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldChange {
+    pub tag: String,
+    /// Field in the old record, `None` when added
+    pub old: Option<String>,
+    /// Field in the new record, `None` when removed
+    pub new: Option<String>,
+}
+
+impl FieldChange {
+    /// Whether the field was added, removed or changed
+    pub fn kind(&self) -> ChangeKind {
+        match (&self.old, &self.new) {
+            (None, _) => ChangeKind::Added,
+            (_, None) => ChangeKind::Removed,
+            _ => ChangeKind::Changed,
+        }
+    }
+}
+
+/// `- TAG  old` and/or `+ TAG  new`, on separate lines
+impl Display for FieldChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(old) = &self.old {
+            write!(f, "- {}  {}", self.tag, old)?;
+        }
+        if let Some(new) = &self.new {
+            if self.old.is_some() {
+                writeln!(f)?;
+            }
+            write!(f, "+ {}  {}", self.tag, new)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for FieldChange {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("FieldChange", 4)?;
+        state.serialize_field("tag", &self.tag)?;
+        state.serialize_field("kind", &self.kind().to_string())?;
+        state.serialize_field("old", &self.old)?;
+        state.serialize_field("new", &self.new)?;
+        state.end()
+    }
+}
+
+impl Record {
+    /// Changes turning this record into `other`: the leader, then removed and
+    /// changed fields in this record's order, then added fields
+    pub fn diff(&self, other: &Record) -> Vec<FieldChange> {
+        let mut changes = Vec::new();
+
+        let (old_leader, new_leader) = (comparable_leader(self), comparable_leader(other));
+        if old_leader != new_leader {
+            changes.push(FieldChange {
+                tag: "LDR".to_string(),
+                old: Some(old_leader),
+                new: Some(new_leader),
+            });
+        }
+
+        let mut old = field_lines(self);
+        let mut new = field_lines(other);
+        // Drop the fields found unchanged on both sides
+        old.retain(|line| match new.iter().position(|other| other == line) {
+            Some(i) => {
+                new.remove(i);
+                false
+            }
+            None => true,
+        });
+
+        for (tag, value) in old {
+            match new.iter().position(|(other, _)| *other == tag) {
+                Some(i) => {
+                    let (_, new_value) = new.remove(i);
+                    changes.push(FieldChange {
+                        tag,
+                        old: Some(value),
+                        new: Some(new_value),
+                    });
+                }
+                None => changes.push(FieldChange { tag, old: Some(value), new: None }),
+            }
+        }
+        changes.extend(new.into_iter().map(|(tag, value)| FieldChange { tag, old: None, new: Some(value) }));
+        changes
+    }
+}
+
+fn comparable_leader(record: &Record) -> String {
+    let mut leader = record.leader.clone();
+    leader.record_length = 0;
+    leader.base_address_of_data = 0;
+    leader_text(&leader)
+}
+
+fn field_lines(record: &Record) -> Vec<(String, String)> {
+    let control = record.control_fields.iter().map(|f| (f.tag.clone(), control_field_text(f)));
+    let data = record.data_fields.iter().map(|f| (f.tag.clone(), data_field_text(f)));
+    control.chain(data).collect()
+}
@@ -0,0 +1,182 @@
+use crate::record::{DataField, Record, Subfield};
+
+/// Access method (856 first indicator)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessMethod {
+    /// ` ` - No information provided
+    Unspecified,
+    /// `0` - Email
+    Email,
+    /// `1` - FTP
+    Ftp,
+    /// `2` - Remote login (Telnet)
+    RemoteLogin,
+    /// `3` - Dial-up
+    DialUp,
+    /// `4` - HTTP
+    Http,
+    /// `7` - Method specified in $2
+    SpecifiedInSubfield2,
+}
+
+impl AccessMethod {
+    /// Get the access method from the first indicator
+    pub fn from_indicator(ind: char) -> Option<Self> {
+        match ind {
+            ' ' => Some(AccessMethod::Unspecified),
+            '0' => Some(AccessMethod::Email),
+            '1' => Some(AccessMethod::Ftp),
+            '2' => Some(AccessMethod::RemoteLogin),
+            '3' => Some(AccessMethod::DialUp),
+            '4' => Some(AccessMethod::Http),
+            '7' => Some(AccessMethod::SpecifiedInSubfield2),
+            _ => None,
+        }
+    }
+
+    /// Get the indicator value of this access method
+    pub fn indicator(&self) -> char {
+        match self {
+            AccessMethod::Unspecified => ' ',
+            AccessMethod::Email => '0',
+            AccessMethod::Ftp => '1',
+            AccessMethod::RemoteLogin => '2',
+            AccessMethod::DialUp => '3',
+            AccessMethod::Http => '4',
+            AccessMethod::SpecifiedInSubfield2 => '7',
+        }
+    }
+}
+
+/// Relationship between the electronic resource and the described item (856 second indicator)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relationship {
+    /// ` ` - No information provided
+    Unspecified,
+    /// `0` - Resource
+    Resource,
+    /// `1` - Version of resource
+    VersionOfResource,
+    /// `2` - Related resource
+    RelatedResource,
+    /// `3` - Component part(s) of resource
+    ComponentPartOfResource,
+    /// `4` - Version of component part(s) of resource
+    VersionOfComponentPart,
+    /// `8` - No display constant generated
+    NoDisplayConstant,
+}
+
+impl Relationship {
+    /// Get the relationship from the second indicator
+    pub fn from_indicator(ind: char) -> Option<Self> {
+        match ind {
+            ' ' => Some(Relationship::Unspecified),
+            '0' => Some(Relationship::Resource),
+            '1' => Some(Relationship::VersionOfResource),
+            '2' => Some(Relationship::RelatedResource),
+            '3' => Some(Relationship::ComponentPartOfResource),
+            '4' => Some(Relationship::VersionOfComponentPart),
+            '8' => Some(Relationship::NoDisplayConstant),
+            _ => None,
+        }
+    }
+
+    /// Get the indicator value of this relationship
+    pub fn indicator(&self) -> char {
+        match self {
+            Relationship::Unspecified => ' ',
+            Relationship::Resource => '0',
+            Relationship::VersionOfResource => '1',
+            Relationship::RelatedResource => '2',
+            Relationship::ComponentPartOfResource => '3',
+            Relationship::VersionOfComponentPart => '4',
+            Relationship::NoDisplayConstant => '8',
+        }
+    }
+}
+
+/// Typed view of an 856 electronic location and access field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElectronicLocation {
+    /// $u - Uniform Resource Identifiers
+    pub urls: Vec<String>,
+    /// $y - Link text
+    pub link_text: Option<String>,
+    /// $3 - Materials specified
+    pub materials_specified: Option<String>,
+    /// $z - Public notes
+    pub public_notes: Vec<String>,
+    /// First indicator
+    pub access_method: AccessMethod,
+    /// $2 - Access method, when the first indicator is 7
+    pub access_method_source: Option<String>,
+    /// Second indicator
+    pub relationship: Relationship,
+}
+
+impl ElectronicLocation {
+    /// Build from an 856 field
+    ///
+    /// Unknown indicator values are read as [`AccessMethod::Unspecified`] and
+    /// [`Relationship::Unspecified`].
+    pub fn from_field(field: &DataField) -> Self {
+        let values = |code: char| field.subfields.iter().filter(move |s| s.code == code).map(|s| s.value.clone());
+
+        ElectronicLocation {
+            urls: values('u').collect(),
+            link_text: values('y').next(),
+            materials_specified: values('3').next(),
+            public_notes: values('z').collect(),
+            access_method: AccessMethod::from_indicator(field.ind1).unwrap_or(AccessMethod::Unspecified),
+            access_method_source: values('2').next(),
+            relationship: Relationship::from_indicator(field.ind2).unwrap_or(Relationship::Unspecified),
+        }
+    }
+
+    /// First URL, if any
+    pub fn url(&self) -> Option<&str> {
+        self.urls.first().map(|u| u.as_str())
+    }
+
+    /// Build an 856 field from this location
+    pub fn to_field(&self) -> DataField {
+        let mut subfields = Vec::new();
+        let mut push = |code: char, value: &str| {
+            subfields.push(Subfield {
+                code,
+                value: value.to_string(),
+            })
+        };
+
+        if let Some(materials) = &self.materials_specified {
+            push('3', materials);
+        }
+        for url in &self.urls {
+            push('u', url);
+        }
+        if let Some(text) = &self.link_text {
+            push('y', text);
+        }
+        for note in &self.public_notes {
+            push('z', note);
+        }
+        if let Some(source) = &self.access_method_source {
+            push('2', source);
+        }
+
+        DataField {
+            tag: "856".to_string(),
+            ind1: self.access_method.indicator(),
+            ind2: self.relationship.indicator(),
+            subfields,
+        }
+    }
+}
+
+impl Record {
+    /// All 856 electronic locations of the record
+    pub fn electronic_locations(&self) -> Vec<ElectronicLocation> {
+        self.data_fields.iter().filter(|f| f.tag == "856").map(ElectronicLocation::from_field).collect()
+    }
+}
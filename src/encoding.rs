@@ -1,5 +1,7 @@
 use crate::format::Encoding as MarcEncoding;
+use crate::record::Record;
 use encoding_rs::Encoding;
+use std::borrow::Cow;
 
 /// Convert bytes from a specific encoding to UTF-8
 pub fn convert_to_utf8(data: &[u8], encoding: MarcEncoding) -> Result<String, String> {
@@ -17,6 +19,26 @@ pub fn convert_to_utf8(data: &[u8], encoding: MarcEncoding) -> Result<String, St
     Ok(cow.to_string())
 }
 
+/// Convert bytes to UTF-8 at the end of `output`, without allocating when they are UTF-8 or ASCII
+pub(crate) fn decode_into(data: &[u8], encoding: MarcEncoding, output: &mut String) -> Result<(), String> {
+    // ASCII reads the same in every encoding but ISO 5426, which drops control characters
+    if encoding != MarcEncoding::Iso5426 && data.is_ascii() {
+        output.push_str(std::str::from_utf8(data).expect("ASCII is UTF-8"));
+        return Ok(());
+    }
+    if encoding == MarcEncoding::Iso5426 {
+        output.push_str(&decode_iso5426(data)?);
+        return Ok(());
+    }
+
+    let (cow, _, had_errors) = get_encoding(encoding).decode(data);
+    if had_errors {
+        return Err("Encoding conversion had errors".to_string());
+    }
+    output.push_str(&cow);
+    Ok(())
+}
+
 /// Convert UTF-8 string to a specific encoding
 pub fn convert_from_encoding(text: &str, encoding: MarcEncoding) -> Result<Vec<u8>, String> {
     if encoding == MarcEncoding::Iso5426 {
@@ -33,6 +55,106 @@ pub fn convert_from_encoding(text: &str, encoding: MarcEncoding) -> Result<Vec<u
     Ok(cow.to_vec())
 }
 
+/// Replace the characters that cannot be written in `encoding` with `replacement`
+///
+/// Returns the text, borrowed when nothing was replaced, and the number of
+/// replaced characters.
+pub fn replace_unencodable(text: &str, encoding: MarcEncoding, replacement: char) -> (Cow<'_, str>, usize) {
+    if convert_from_encoding(text, encoding).is_ok() {
+        return (Cow::Borrowed(text), 0);
+    }
+
+    let mut replaced = 0;
+    let mut buffer = [0u8; 4];
+    let text = text
+        .chars()
+        .map(|c| match convert_from_encoding(c.encode_utf8(&mut buffer), encoding) {
+            Ok(_) => c,
+            Err(_) => {
+                replaced += 1;
+                replacement
+            }
+        })
+        .collect();
+    (Cow::Owned(text), replaced)
+}
+
+impl Record {
+    /// Replace the characters of every value that cannot be written in `encoding`
+    /// with `replacement`, returning the number of replaced characters
+    pub fn replace_unencodable(&mut self, encoding: MarcEncoding, replacement: char) -> usize {
+        let values = self.control_fields.iter_mut().map(|f| &mut f.value).chain(self.data_fields.iter_mut().flat_map(|f| f.subfields.iter_mut().map(|s| &mut s.value)));
+        let mut replaced = 0;
+        for value in values {
+            if let (Cow::Owned(text), n) = replace_unencodable(value, encoding, replacement) {
+                *value = text;
+                replaced += n;
+            }
+        }
+        replaced
+    }
+}
+
+/// Encoding suggested by the bytes of a record, see [`guess_encoding`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EncodingGuess {
+    /// ASCII only: every encoding reads it the same
+    Ascii,
+    /// Valid UTF-8 with non-ASCII characters
+    Utf8,
+    /// UTF-8 encoded twice, e.g. `Ã©` for `é`
+    DoubleUtf8,
+    /// Not UTF-8, with MARC-8 escape sequences or combining diacritics before letters
+    Marc8,
+    /// Not UTF-8: a single-byte encoding such as ISO 8859-1
+    SingleByte,
+}
+
+impl EncodingGuess {
+    /// Short description, e.g. "UTF-8 encoded twice"
+    pub fn description(&self) -> &'static str {
+        match self {
+            EncodingGuess::Ascii => "ASCII only",
+            EncodingGuess::Utf8 => "UTF-8",
+            EncodingGuess::DoubleUtf8 => "UTF-8 encoded twice",
+            EncodingGuess::Marc8 => "MARC-8",
+            EncodingGuess::SingleByte => "single-byte (ISO 8859)",
+        }
+    }
+}
+
+/// Guess the encoding of the bytes of a record
+///
+/// Valid UTF-8 is UTF-8, or UTF-8 encoded twice when it has a character of
+/// U+00C2-U+00F4 followed by one of U+0080-U+00BF, the ISO 8859-1 reading of a
+/// UTF-8 sequence. Other bytes are MARC-8 when they hold an escape sequence, or
+/// when each byte of 0xE0-0xFE (MARC-8 combining diacritics, ISO 8859-1 accented
+/// letters) comes before a letter, as diacritics do, and none is unused in MARC-8
+/// (0xC9-0xDF); otherwise a single-byte encoding. These are hints: a record with
+/// a single accented letter, e.g. `Noël`, may fit several guesses.
+pub fn guess_encoding(data: &[u8]) -> EncodingGuess {
+    if data.is_ascii() {
+        return EncodingGuess::Ascii;
+    }
+    if let Ok(text) = std::str::from_utf8(data) {
+        let chars: Vec<char> = text.chars().collect();
+        let twice = chars.windows(2).any(|pair| ('\u{C2}'..='\u{F4}').contains(&pair[0]) && ('\u{80}'..='\u{BF}').contains(&pair[1]));
+        return if twice { EncodingGuess::DoubleUtf8 } else { EncodingGuess::Utf8 };
+    }
+
+    let is_diacritic = |b: &u8| (0xE0..=0xFE).contains(b);
+    let diacritics_before_letters = data.iter().enumerate().filter(|(_, b)| is_diacritic(b)).all(|(i, _)| {
+        let next = data[i + 1..].iter().find(|b| !is_diacritic(b));
+        next.is_some_and(u8::is_ascii_alphabetic)
+    });
+    let unused_in_marc8 = data.iter().any(|b| (0xC9..=0xDF).contains(b));
+    if data.contains(&0x1B) || (data.iter().any(is_diacritic) && diacritics_before_letters && !unused_in_marc8) {
+        EncodingGuess::Marc8
+    } else {
+        EncodingGuess::SingleByte
+    }
+}
+
 /// Get encoding_rs::Encoding for our Encoding enum
 fn get_encoding(encoding: MarcEncoding) -> &'static Encoding {
     match encoding {
@@ -1,49 +1,86 @@
 use crate::format::MarcFormat;
 
 /// Added entry fields (70X-75X in MARC21, 7XX in UNIMARC for responsibility)
+///
+/// Name added entries map to the UNIMARC alternative responsibility tags (701, 711);
+/// use [`Responsibility`](crate::fields::Responsibility) to pick between 70X/71X levels.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AddedEntry {
-    /// 700 - Added entry - Personal name
+    /// 700 - Added entry - Personal name (701 in UNIMARC)
     PersonalName,
-    /// 710 - Added entry - Corporate name
+    /// 710 - Added entry - Corporate name (711 in UNIMARC)
     CorporateName,
-    /// 711 - Added entry - Meeting name
+    /// 711 - Added entry - Meeting name (711 in UNIMARC)
     MeetingName,
-    /// 720 - Added entry - Uncontrolled name
+    /// 720 - Added entry - Uncontrolled name (not in UNIMARC)
     UncontrolledName,
-    /// 730 - Added entry - Uniform title
+    /// 730 - Added entry - Uniform title (500 in UNIMARC)
     UniformTitle,
-    /// 740 - Added entry - Uncontrolled related/analytical title
+    /// 740 - Added entry - Uncontrolled related/analytical title (517 in UNIMARC)
     UncontrolledRelatedAnalyticalTitle,
-    /// 751 - Added entry - Geographic name
+    /// 751 - Added entry - Geographic name (620 in UNIMARC)
     GeographicName,
-    /// 752 - Added entry - Hierarchical place name
+    /// 752 - Added entry - Hierarchical place name (620 in UNIMARC)
     HierarchicalPlaceName,
-    /// 753 - System details access to computer files
+    /// 753 - System details access to computer files (not in UNIMARC)
     SystemDetailsAccessToComputerFiles,
-    /// 754 - Added entry - Taxonomic identification
+    /// 754 - Added entry - Taxonomic identification (not in UNIMARC)
     TaxonomicIdentification,
-    /// 755 - Added entry - Physical characteristics
+    /// 755 - Added entry - Physical characteristics (not in UNIMARC)
     PhysicalCharacteristics,
 }
 
 impl AddedEntry {
+    /// All variants, in declaration order
+    pub const ALL: &'static [AddedEntry] = &[
+        AddedEntry::PersonalName,
+        AddedEntry::CorporateName,
+        AddedEntry::MeetingName,
+        AddedEntry::UncontrolledName,
+        AddedEntry::UniformTitle,
+        AddedEntry::UncontrolledRelatedAnalyticalTitle,
+        AddedEntry::GeographicName,
+        AddedEntry::HierarchicalPlaceName,
+        AddedEntry::SystemDetailsAccessToComputerFiles,
+        AddedEntry::TaxonomicIdentification,
+        AddedEntry::PhysicalCharacteristics,
+    ];
+
     /// Get the tag as string for the given format
-    pub fn tag(&self, _format: MarcFormat) -> &'static str {
-        // In UNIMARC, 7XX is the responsibility block, similar to MARC21's 7XX
-        // Most tags are the same, but usage may differ
-        match self {
-            AddedEntry::PersonalName => "700",
-            AddedEntry::CorporateName => "710",
-            AddedEntry::MeetingName => "711",
-            AddedEntry::UncontrolledName => "720",
-            AddedEntry::UniformTitle => "730",
-            AddedEntry::UncontrolledRelatedAnalyticalTitle => "740",
-            AddedEntry::GeographicName => "751",
-            AddedEntry::HierarchicalPlaceName => "752",
-            AddedEntry::SystemDetailsAccessToComputerFiles => "753",
-            AddedEntry::TaxonomicIdentification => "754",
-            AddedEntry::PhysicalCharacteristics => "755",
+    pub fn tag(&self, format: MarcFormat) -> Option<&'static str> {
+        match (self, format) {
+            (AddedEntry::PersonalName, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("700"),
+            (AddedEntry::PersonalName, MarcFormat::Unimarc) => Some("701"), // Alternative responsibility
+
+            (AddedEntry::CorporateName, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("710"),
+            (AddedEntry::CorporateName, MarcFormat::Unimarc) => Some("711"), // Alternative responsibility
+
+            (AddedEntry::MeetingName, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("711"),
+            (AddedEntry::MeetingName, MarcFormat::Unimarc) => Some("711"), // Alternative responsibility, meeting indicator set
+
+            (AddedEntry::UncontrolledName, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("720"),
+            (AddedEntry::UncontrolledName, MarcFormat::Unimarc) => None, // Not in UNIMARC
+
+            (AddedEntry::UniformTitle, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("730"),
+            (AddedEntry::UniformTitle, MarcFormat::Unimarc) => Some("500"), // Uniform title
+
+            (AddedEntry::UncontrolledRelatedAnalyticalTitle, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("740"),
+            (AddedEntry::UncontrolledRelatedAnalyticalTitle, MarcFormat::Unimarc) => Some("517"), // Other variant titles
+
+            (AddedEntry::GeographicName, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("751"),
+            (AddedEntry::GeographicName, MarcFormat::Unimarc) => Some("620"), // Place access
+
+            (AddedEntry::HierarchicalPlaceName, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("752"),
+            (AddedEntry::HierarchicalPlaceName, MarcFormat::Unimarc) => Some("620"), // Place access
+
+            (AddedEntry::SystemDetailsAccessToComputerFiles, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("753"),
+            (AddedEntry::SystemDetailsAccessToComputerFiles, MarcFormat::Unimarc) => None, // Not in UNIMARC
+
+            (AddedEntry::TaxonomicIdentification, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("754"),
+            (AddedEntry::TaxonomicIdentification, MarcFormat::Unimarc) => None, // Not in UNIMARC
+
+            (AddedEntry::PhysicalCharacteristics, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("755"),
+            (AddedEntry::PhysicalCharacteristics, MarcFormat::Unimarc) => None, // Not in UNIMARC
         }
     }
 }
@@ -0,0 +1,259 @@
+use crate::format::MarcFormat;
+
+/// Authority heading fields (1XX in MARC21 authority, 2XX in UNIMARC/A)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthorityHeading {
+    /// Heading - Personal name (100 in MARC21, 200 in UNIMARC/A)
+    PersonalName,
+    /// Heading - Corporate name (110 in MARC21, 210 in UNIMARC/A)
+    CorporateName,
+    /// Heading - Meeting name (111 in MARC21, 210 in UNIMARC/A)
+    MeetingName,
+    /// Heading - Uniform title (130 in MARC21, 230 in UNIMARC/A)
+    UniformTitle,
+    /// Heading - Chronological term (148 in MARC21, not in UNIMARC/A)
+    ChronologicalTerm,
+    /// Heading - Topical term (150 in MARC21, 250 in UNIMARC/A)
+    TopicalTerm,
+    /// Heading - Geographic name (151 in MARC21, 215 in UNIMARC/A)
+    GeographicName,
+    /// Heading - Genre/form term (155 in MARC21, 280 in UNIMARC/A)
+    GenreFormTerm,
+}
+
+impl AuthorityHeading {
+    /// All variants, in declaration order
+    pub const ALL: &'static [AuthorityHeading] = &[
+        AuthorityHeading::PersonalName,
+        AuthorityHeading::CorporateName,
+        AuthorityHeading::MeetingName,
+        AuthorityHeading::UniformTitle,
+        AuthorityHeading::ChronologicalTerm,
+        AuthorityHeading::TopicalTerm,
+        AuthorityHeading::GeographicName,
+        AuthorityHeading::GenreFormTerm,
+    ];
+
+    /// Get the tag as string for the given format
+    pub fn tag(&self, format: MarcFormat) -> Option<&'static str> {
+        match (self, format) {
+            (AuthorityHeading::PersonalName, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("100"),
+            (AuthorityHeading::PersonalName, MarcFormat::Unimarc) => Some("200"),
+
+            (AuthorityHeading::CorporateName, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("110"),
+            (AuthorityHeading::CorporateName, MarcFormat::Unimarc) => Some("210"),
+
+            (AuthorityHeading::MeetingName, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("111"),
+            (AuthorityHeading::MeetingName, MarcFormat::Unimarc) => Some("210"), // Corporate name with first indicator 1
+
+            (AuthorityHeading::UniformTitle, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("130"),
+            (AuthorityHeading::UniformTitle, MarcFormat::Unimarc) => Some("230"),
+
+            (AuthorityHeading::ChronologicalTerm, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("148"),
+            (AuthorityHeading::ChronologicalTerm, MarcFormat::Unimarc) => None, // Not in UNIMARC/A
+
+            (AuthorityHeading::TopicalTerm, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("150"),
+            (AuthorityHeading::TopicalTerm, MarcFormat::Unimarc) => Some("250"),
+
+            (AuthorityHeading::GeographicName, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("151"),
+            (AuthorityHeading::GeographicName, MarcFormat::Unimarc) => Some("215"), // Territorial or geographical name
+
+            (AuthorityHeading::GenreFormTerm, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("155"),
+            (AuthorityHeading::GenreFormTerm, MarcFormat::Unimarc) => Some("280"), // Form, genre or physical characteristics
+        }
+    }
+}
+
+/// Authority tracing fields (4XX/5XX in both MARC21 authority and UNIMARC/A)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthorityTracing {
+    /// See from tracing - Personal name (400 in MARC21, 400 in UNIMARC/A)
+    SeeFromPersonalName,
+    /// See from tracing - Corporate name (410 in MARC21, 410 in UNIMARC/A)
+    SeeFromCorporateName,
+    /// See from tracing - Meeting name (411 in MARC21, 410 in UNIMARC/A)
+    SeeFromMeetingName,
+    /// See from tracing - Uniform title (430 in MARC21, 430 in UNIMARC/A)
+    SeeFromUniformTitle,
+    /// See from tracing - Chronological term (448 in MARC21, not in UNIMARC/A)
+    SeeFromChronologicalTerm,
+    /// See from tracing - Topical term (450 in MARC21, 450 in UNIMARC/A)
+    SeeFromTopicalTerm,
+    /// See from tracing - Geographic name (451 in MARC21, 415 in UNIMARC/A)
+    SeeFromGeographicName,
+    /// See from tracing - Genre/form term (455 in MARC21, 480 in UNIMARC/A)
+    SeeFromGenreFormTerm,
+    /// See also from tracing - Personal name (500 in MARC21, 500 in UNIMARC/A)
+    SeeAlsoFromPersonalName,
+    /// See also from tracing - Corporate name (510 in MARC21, 510 in UNIMARC/A)
+    SeeAlsoFromCorporateName,
+    /// See also from tracing - Meeting name (511 in MARC21, 510 in UNIMARC/A)
+    SeeAlsoFromMeetingName,
+    /// See also from tracing - Uniform title (530 in MARC21, 530 in UNIMARC/A)
+    SeeAlsoFromUniformTitle,
+    /// See also from tracing - Chronological term (548 in MARC21, not in UNIMARC/A)
+    SeeAlsoFromChronologicalTerm,
+    /// See also from tracing - Topical term (550 in MARC21, 550 in UNIMARC/A)
+    SeeAlsoFromTopicalTerm,
+    /// See also from tracing - Geographic name (551 in MARC21, 515 in UNIMARC/A)
+    SeeAlsoFromGeographicName,
+    /// See also from tracing - Genre/form term (555 in MARC21, 580 in UNIMARC/A)
+    SeeAlsoFromGenreFormTerm,
+}
+
+impl AuthorityTracing {
+    /// All variants, in declaration order
+    pub const ALL: &'static [AuthorityTracing] = &[
+        AuthorityTracing::SeeFromPersonalName,
+        AuthorityTracing::SeeFromCorporateName,
+        AuthorityTracing::SeeFromMeetingName,
+        AuthorityTracing::SeeFromUniformTitle,
+        AuthorityTracing::SeeFromChronologicalTerm,
+        AuthorityTracing::SeeFromTopicalTerm,
+        AuthorityTracing::SeeFromGeographicName,
+        AuthorityTracing::SeeFromGenreFormTerm,
+        AuthorityTracing::SeeAlsoFromPersonalName,
+        AuthorityTracing::SeeAlsoFromCorporateName,
+        AuthorityTracing::SeeAlsoFromMeetingName,
+        AuthorityTracing::SeeAlsoFromUniformTitle,
+        AuthorityTracing::SeeAlsoFromChronologicalTerm,
+        AuthorityTracing::SeeAlsoFromTopicalTerm,
+        AuthorityTracing::SeeAlsoFromGeographicName,
+        AuthorityTracing::SeeAlsoFromGenreFormTerm,
+    ];
+
+    /// Get the tag as string for the given format
+    pub fn tag(&self, format: MarcFormat) -> Option<&'static str> {
+        match (self, format) {
+            (AuthorityTracing::SeeFromPersonalName, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("400"),
+            (AuthorityTracing::SeeFromPersonalName, MarcFormat::Unimarc) => Some("400"),
+
+            (AuthorityTracing::SeeFromCorporateName, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("410"),
+            (AuthorityTracing::SeeFromCorporateName, MarcFormat::Unimarc) => Some("410"),
+
+            (AuthorityTracing::SeeFromMeetingName, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("411"),
+            (AuthorityTracing::SeeFromMeetingName, MarcFormat::Unimarc) => Some("410"), // Corporate name with first indicator 1
+
+            (AuthorityTracing::SeeFromUniformTitle, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("430"),
+            (AuthorityTracing::SeeFromUniformTitle, MarcFormat::Unimarc) => Some("430"),
+
+            (AuthorityTracing::SeeFromChronologicalTerm, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("448"),
+            (AuthorityTracing::SeeFromChronologicalTerm, MarcFormat::Unimarc) => None, // Not in UNIMARC/A
+
+            (AuthorityTracing::SeeFromTopicalTerm, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("450"),
+            (AuthorityTracing::SeeFromTopicalTerm, MarcFormat::Unimarc) => Some("450"),
+
+            (AuthorityTracing::SeeFromGeographicName, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("451"),
+            (AuthorityTracing::SeeFromGeographicName, MarcFormat::Unimarc) => Some("415"),
+
+            (AuthorityTracing::SeeFromGenreFormTerm, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("455"),
+            (AuthorityTracing::SeeFromGenreFormTerm, MarcFormat::Unimarc) => Some("480"),
+
+            (AuthorityTracing::SeeAlsoFromPersonalName, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("500"),
+            (AuthorityTracing::SeeAlsoFromPersonalName, MarcFormat::Unimarc) => Some("500"),
+
+            (AuthorityTracing::SeeAlsoFromCorporateName, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("510"),
+            (AuthorityTracing::SeeAlsoFromCorporateName, MarcFormat::Unimarc) => Some("510"),
+
+            (AuthorityTracing::SeeAlsoFromMeetingName, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("511"),
+            (AuthorityTracing::SeeAlsoFromMeetingName, MarcFormat::Unimarc) => Some("510"), // Corporate name with first indicator 1
+
+            (AuthorityTracing::SeeAlsoFromUniformTitle, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("530"),
+            (AuthorityTracing::SeeAlsoFromUniformTitle, MarcFormat::Unimarc) => Some("530"),
+
+            (AuthorityTracing::SeeAlsoFromChronologicalTerm, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("548"),
+            (AuthorityTracing::SeeAlsoFromChronologicalTerm, MarcFormat::Unimarc) => None, // Not in UNIMARC/A
+
+            (AuthorityTracing::SeeAlsoFromTopicalTerm, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("550"),
+            (AuthorityTracing::SeeAlsoFromTopicalTerm, MarcFormat::Unimarc) => Some("550"),
+
+            (AuthorityTracing::SeeAlsoFromGeographicName, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("551"),
+            (AuthorityTracing::SeeAlsoFromGeographicName, MarcFormat::Unimarc) => Some("515"),
+
+            (AuthorityTracing::SeeAlsoFromGenreFormTerm, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("555"),
+            (AuthorityTracing::SeeAlsoFromGenreFormTerm, MarcFormat::Unimarc) => Some("580"),
+        }
+    }
+}
+
+/// Authority note fields (66X-68X in MARC21 authority, 3XX/8XX in UNIMARC/A)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthorityNote {
+    /// Complex see also reference - Name (663 in MARC21, 305 in UNIMARC/A)
+    ComplexSeeAlsoReference,
+    /// Complex see reference - Subject (664 in MARC21, 310 in UNIMARC/A)
+    ComplexSeeReference,
+    /// General explanatory reference - Name (666 in MARC21, 320 in UNIMARC/A)
+    GeneralExplanatoryReference,
+    /// Nonpublic general note (667 in MARC21, 830 in UNIMARC/A)
+    NonpublicGeneralNote,
+    /// Source data found (670 in MARC21, 810 in UNIMARC/A)
+    SourceDataFound,
+    /// Source data not found (675 in MARC21, 815 in UNIMARC/A)
+    SourceDataNotFound,
+    /// Biographical or historical data (678 in MARC21, 340 in UNIMARC/A)
+    BiographicalOrHistoricalData,
+    /// Public general note (680 in MARC21, 300 in UNIMARC/A)
+    PublicGeneralNote,
+    /// Subject example tracing note (681 in MARC21, 825 in UNIMARC/A)
+    SubjectExampleTracingNote,
+    /// Deleted heading information (682 in MARC21, 835 in UNIMARC/A)
+    DeletedHeadingInformation,
+    /// Application history note (688 in MARC21, not in UNIMARC/A)
+    ApplicationHistoryNote,
+}
+
+impl AuthorityNote {
+    /// All variants, in declaration order
+    pub const ALL: &'static [AuthorityNote] = &[
+        AuthorityNote::ComplexSeeAlsoReference,
+        AuthorityNote::ComplexSeeReference,
+        AuthorityNote::GeneralExplanatoryReference,
+        AuthorityNote::NonpublicGeneralNote,
+        AuthorityNote::SourceDataFound,
+        AuthorityNote::SourceDataNotFound,
+        AuthorityNote::BiographicalOrHistoricalData,
+        AuthorityNote::PublicGeneralNote,
+        AuthorityNote::SubjectExampleTracingNote,
+        AuthorityNote::DeletedHeadingInformation,
+        AuthorityNote::ApplicationHistoryNote,
+    ];
+
+    /// Get the tag as string for the given format
+    pub fn tag(&self, format: MarcFormat) -> Option<&'static str> {
+        match (self, format) {
+            (AuthorityNote::ComplexSeeAlsoReference, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("663"),
+            (AuthorityNote::ComplexSeeAlsoReference, MarcFormat::Unimarc) => Some("305"), // Textual see also reference note
+
+            (AuthorityNote::ComplexSeeReference, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("664"),
+            (AuthorityNote::ComplexSeeReference, MarcFormat::Unimarc) => Some("310"), // Textual see reference note
+
+            (AuthorityNote::GeneralExplanatoryReference, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("666"),
+            (AuthorityNote::GeneralExplanatoryReference, MarcFormat::Unimarc) => Some("320"),
+
+            (AuthorityNote::NonpublicGeneralNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("667"),
+            (AuthorityNote::NonpublicGeneralNote, MarcFormat::Unimarc) => Some("830"), // General cataloguer's note
+
+            (AuthorityNote::SourceDataFound, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("670"),
+            (AuthorityNote::SourceDataFound, MarcFormat::Unimarc) => Some("810"),
+
+            (AuthorityNote::SourceDataNotFound, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("675"),
+            (AuthorityNote::SourceDataNotFound, MarcFormat::Unimarc) => Some("815"),
+
+            (AuthorityNote::BiographicalOrHistoricalData, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("678"),
+            (AuthorityNote::BiographicalOrHistoricalData, MarcFormat::Unimarc) => Some("340"), // Biography and activity note
+
+            (AuthorityNote::PublicGeneralNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("680"),
+            (AuthorityNote::PublicGeneralNote, MarcFormat::Unimarc) => Some("300"), // Information note
+
+            (AuthorityNote::SubjectExampleTracingNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("681"),
+            (AuthorityNote::SubjectExampleTracingNote, MarcFormat::Unimarc) => Some("825"), // Example under note
+
+            (AuthorityNote::DeletedHeadingInformation, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("682"),
+            (AuthorityNote::DeletedHeadingInformation, MarcFormat::Unimarc) => Some("835"),
+
+            (AuthorityNote::ApplicationHistoryNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("688"),
+            (AuthorityNote::ApplicationHistoryNote, MarcFormat::Unimarc) => None, // Not in UNIMARC/A
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use crate::format::MarcFormat;
+use crate::record::{DataField, Record};
+
+/// Classification scheme fields (05X-09X in MARC21, 67X-68X in UNIMARC)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Classification {
+    /// Library of Congress classification (050 in MARC21, 680 in UNIMARC)
+    LibraryOfCongress,
+    /// Universal Decimal Classification (080 in MARC21, 675 in UNIMARC)
+    UniversalDecimal,
+    /// Dewey Decimal Classification (082 in MARC21, 676 in UNIMARC)
+    DeweyDecimal,
+    /// Other classification number (084 in MARC21, 686 in UNIMARC)
+    Other,
+    /// Local LC-type call number (090 in MARC21, not in UNIMARC)
+    LocalLibraryOfCongress,
+}
+
+impl Classification {
+    /// All variants, in declaration order
+    pub const ALL: &'static [Classification] = &[
+        Classification::LibraryOfCongress,
+        Classification::UniversalDecimal,
+        Classification::DeweyDecimal,
+        Classification::Other,
+        Classification::LocalLibraryOfCongress,
+    ];
+
+    /// Get the tag as string for the given format
+    pub fn tag(&self, format: MarcFormat) -> Option<&'static str> {
+        match (self, format) {
+            (Classification::LibraryOfCongress, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("050"),
+            (Classification::LibraryOfCongress, MarcFormat::Unimarc) => Some("680"),
+
+            (Classification::UniversalDecimal, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("080"),
+            (Classification::UniversalDecimal, MarcFormat::Unimarc) => Some("675"),
+
+            (Classification::DeweyDecimal, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("082"),
+            (Classification::DeweyDecimal, MarcFormat::Unimarc) => Some("676"),
+
+            (Classification::Other, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("084"),
+            (Classification::Other, MarcFormat::Unimarc) => Some("686"),
+
+            (Classification::LocalLibraryOfCongress, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("090"),
+            (Classification::LocalLibraryOfCongress, MarcFormat::Unimarc) => None, // Not in UNIMARC
+        }
+    }
+
+    /// Scheme carried by a tag in the given format
+    pub fn from_tag(tag: &str, format: MarcFormat) -> Option<Self> {
+        Classification::ALL.iter().copied().find(|c| c.tag(format) == Some(tag))
+    }
+
+    /// Subfield holding the scheme edition or source code
+    fn edition_subfield(&self, format: MarcFormat) -> char {
+        match (self, format) {
+            (Classification::UniversalDecimal | Classification::DeweyDecimal, MarcFormat::Unimarc) => 'v',
+            _ => '2',
+        }
+    }
+}
+
+/// Structured classification number or call number
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallNumber {
+    pub scheme: Classification,
+    /// Class portion (e.g. "QA76.73.R87" or "005.133")
+    pub class: String,
+    /// Item portion (book number, cutter and date), if present
+    pub item: Option<String>,
+    /// Scheme edition or source code ($2, or $v for UNIMARC UDC/DDC)
+    pub source: Option<String>,
+}
+
+impl CallNumber {
+    /// Read the first call number of a classification field
+    ///
+    /// Dewey segmentation marks (`/` and `'`) are removed. When the field has no item
+    /// subfield, an LC-type class number containing a space is split at the first space.
+    pub fn from_field(field: &DataField, format: MarcFormat) -> Option<Self> {
+        let scheme = Classification::from_tag(&field.tag, format)?;
+        let value = |code: char| field.subfields.iter().find(|s| s.code == code).map(|s| s.value.trim()).filter(|v| !v.is_empty());
+
+        let mut class = value('a')?.to_string();
+        if scheme == Classification::DeweyDecimal {
+            class.retain(|c| c != '/' && c != '\'');
+        }
+
+        let mut item = value('b').map(str::to_string);
+        if item.is_none() && matches!(scheme, Classification::LibraryOfCongress | Classification::LocalLibraryOfCongress) {
+            if let Some((head, tail)) = class.split_once(char::is_whitespace) {
+                item = Some(tail.trim().to_string());
+                class = head.to_string();
+            }
+        }
+
+        Some(Self {
+            scheme,
+            class,
+            item,
+            source: value(scheme.edition_subfield(format)).map(str::to_string),
+        })
+    }
+}
+
+impl std::fmt::Display for CallNumber {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.item {
+            Some(item) => write!(f, "{} {}", self.class, item),
+            None => write!(f, "{}", self.class),
+        }
+    }
+}
+
+impl Record {
+    /// Call numbers from all classification fields, in record order
+    pub fn call_numbers(&self, format: MarcFormat) -> Vec<CallNumber> {
+        self.data_fields.iter().filter_map(|f| CallNumber::from_field(f, format)).collect()
+    }
+
+    /// Class numbers of one scheme, in record order (for faceting)
+    pub fn class_numbers(&self, scheme: Classification, format: MarcFormat) -> Vec<String> {
+        self.call_numbers(format).into_iter().filter(|c| c.scheme == scheme).map(|c| c.class).collect()
+    }
+}
@@ -0,0 +1,176 @@
+use crate::format::MarcFormat;
+
+/// Coded information block (1XX in UNIMARC, mostly 00X/0XX/3XX in MARC21)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodedInformation {
+    /// 100 - General processing data (008 in MARC21)
+    GeneralProcessingData,
+    /// 101 - Language of the resource (041 in MARC21)
+    LanguageOfResource,
+    /// 102 - Country of publication or production (044 in MARC21)
+    CountryOfPublication,
+    /// 105 - Coded data field: textual material, monographic (008 in MARC21)
+    TextualMaterialMonographic,
+    /// 106 - Coded data field: form of resource (008 in MARC21)
+    FormOfResource,
+    /// 110 - Coded data field: continuing resources (008 in MARC21)
+    ContinuingResources,
+    /// 115 - Coded data field: visual projections, videorecordings and motion pictures (007 in MARC21)
+    VisualProjections,
+    /// 116 - Coded data field: graphics (007 in MARC21)
+    Graphics,
+    /// 117 - Coded data field: three-dimensional artefacts and realia (008 in MARC21)
+    ThreeDimensionalArtefacts,
+    /// 120 - Coded data field: cartographic materials, general (008 in MARC21)
+    CartographicGeneral,
+    /// 121 - Coded data field: cartographic materials, physical characteristics (007 in MARC21)
+    CartographicPhysical,
+    /// 122 - Coded data field: time period of item content (045 in MARC21)
+    TimePeriodOfContent,
+    /// 123 - Coded data field: cartographic materials, scale and coordinates (034 in MARC21)
+    CartographicScaleAndCoordinates,
+    /// 125 - Coded data field: sound recordings and music (008 in MARC21)
+    SoundRecordingsAndMusic,
+    /// 126 - Coded data field: sound recordings, physical attributes (007 in MARC21)
+    SoundRecordingsPhysical,
+    /// 127 - Coded data field: duration of sound recordings and printed music (306 in MARC21)
+    DurationOfRecordings,
+    /// 128 - Coded data field: form of musical work and key or mode (382 in MARC21)
+    MusicPerformances,
+    /// 130 - Coded data field: microforms, physical attributes (007 in MARC21)
+    Microforms,
+    /// 131 - Coded data field: cartographic materials, geodetic, grid and vertical measurement (342 in MARC21)
+    Geodetic,
+    /// 135 - Coded data field: electronic resources (007 in MARC21)
+    ElectronicResources,
+    /// 140 - Coded data field: antiquarian, general (not in MARC21)
+    AntiquarianGeneral,
+    /// 141 - Coded data field: antiquarian, copy specific attributes (not in MARC21)
+    AntiquarianCopySpecific,
+    /// 145 - Coded data field: medium of performance (382 in MARC21)
+    MediumOfPerformance,
+    /// 181 - Coded data field: content form (336 in MARC21)
+    ContentForm,
+    /// 182 - Coded data field: media type (337 in MARC21)
+    MediaType,
+    /// 183 - Coded data field: type of carrier (338 in MARC21)
+    TypeOfCarrier,
+}
+
+impl CodedInformation {
+    /// All variants, in declaration order
+    pub const ALL: &'static [CodedInformation] = &[
+        CodedInformation::GeneralProcessingData,
+        CodedInformation::LanguageOfResource,
+        CodedInformation::CountryOfPublication,
+        CodedInformation::TextualMaterialMonographic,
+        CodedInformation::FormOfResource,
+        CodedInformation::ContinuingResources,
+        CodedInformation::VisualProjections,
+        CodedInformation::Graphics,
+        CodedInformation::ThreeDimensionalArtefacts,
+        CodedInformation::CartographicGeneral,
+        CodedInformation::CartographicPhysical,
+        CodedInformation::TimePeriodOfContent,
+        CodedInformation::CartographicScaleAndCoordinates,
+        CodedInformation::SoundRecordingsAndMusic,
+        CodedInformation::SoundRecordingsPhysical,
+        CodedInformation::DurationOfRecordings,
+        CodedInformation::MusicPerformances,
+        CodedInformation::Microforms,
+        CodedInformation::Geodetic,
+        CodedInformation::ElectronicResources,
+        CodedInformation::AntiquarianGeneral,
+        CodedInformation::AntiquarianCopySpecific,
+        CodedInformation::MediumOfPerformance,
+        CodedInformation::ContentForm,
+        CodedInformation::MediaType,
+        CodedInformation::TypeOfCarrier,
+    ];
+
+    /// Get the tag as string for the given format
+    ///
+    /// MARC21 equivalents are the fields carrying the same information, which is often
+    /// spread over fixed-length positions rather than a dedicated field.
+    pub fn tag(&self, format: MarcFormat) -> Option<&'static str> {
+        match (self, format) {
+            (CodedInformation::GeneralProcessingData, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("008"), // Fixed-length data elements
+            (CodedInformation::GeneralProcessingData, MarcFormat::Unimarc) => Some("100"),
+
+            (CodedInformation::LanguageOfResource, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("041"),
+            (CodedInformation::LanguageOfResource, MarcFormat::Unimarc) => Some("101"),
+
+            (CodedInformation::CountryOfPublication, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("044"),
+            (CodedInformation::CountryOfPublication, MarcFormat::Unimarc) => Some("102"),
+
+            (CodedInformation::TextualMaterialMonographic, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("008"), // Books configuration
+            (CodedInformation::TextualMaterialMonographic, MarcFormat::Unimarc) => Some("105"),
+
+            (CodedInformation::FormOfResource, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("008"), // Form of item position
+            (CodedInformation::FormOfResource, MarcFormat::Unimarc) => Some("106"),
+
+            (CodedInformation::ContinuingResources, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("008"), // Continuing resources configuration
+            (CodedInformation::ContinuingResources, MarcFormat::Unimarc) => Some("110"),
+
+            (CodedInformation::VisualProjections, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("007"),
+            (CodedInformation::VisualProjections, MarcFormat::Unimarc) => Some("115"),
+
+            (CodedInformation::Graphics, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("007"),
+            (CodedInformation::Graphics, MarcFormat::Unimarc) => Some("116"),
+
+            (CodedInformation::ThreeDimensionalArtefacts, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("008"), // Visual materials configuration
+            (CodedInformation::ThreeDimensionalArtefacts, MarcFormat::Unimarc) => Some("117"),
+
+            (CodedInformation::CartographicGeneral, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("008"), // Maps configuration
+            (CodedInformation::CartographicGeneral, MarcFormat::Unimarc) => Some("120"),
+
+            (CodedInformation::CartographicPhysical, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("007"),
+            (CodedInformation::CartographicPhysical, MarcFormat::Unimarc) => Some("121"),
+
+            (CodedInformation::TimePeriodOfContent, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("045"),
+            (CodedInformation::TimePeriodOfContent, MarcFormat::Unimarc) => Some("122"),
+
+            (CodedInformation::CartographicScaleAndCoordinates, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("034"),
+            (CodedInformation::CartographicScaleAndCoordinates, MarcFormat::Unimarc) => Some("123"),
+
+            (CodedInformation::SoundRecordingsAndMusic, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("008"), // Music configuration
+            (CodedInformation::SoundRecordingsAndMusic, MarcFormat::Unimarc) => Some("125"),
+
+            (CodedInformation::SoundRecordingsPhysical, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("007"),
+            (CodedInformation::SoundRecordingsPhysical, MarcFormat::Unimarc) => Some("126"),
+
+            (CodedInformation::DurationOfRecordings, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("306"), // Playing time
+            (CodedInformation::DurationOfRecordings, MarcFormat::Unimarc) => Some("127"),
+
+            (CodedInformation::MusicPerformances, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("382"), // Medium of performance
+            (CodedInformation::MusicPerformances, MarcFormat::Unimarc) => Some("128"),
+
+            (CodedInformation::Microforms, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("007"),
+            (CodedInformation::Microforms, MarcFormat::Unimarc) => Some("130"),
+
+            (CodedInformation::Geodetic, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("342"), // Geospatial reference data
+            (CodedInformation::Geodetic, MarcFormat::Unimarc) => Some("131"),
+
+            (CodedInformation::ElectronicResources, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("007"),
+            (CodedInformation::ElectronicResources, MarcFormat::Unimarc) => Some("135"),
+
+            (CodedInformation::AntiquarianGeneral, MarcFormat::Marc21 | MarcFormat::MarcXml) => None, // Not in MARC21
+            (CodedInformation::AntiquarianGeneral, MarcFormat::Unimarc) => Some("140"),
+
+            (CodedInformation::AntiquarianCopySpecific, MarcFormat::Marc21 | MarcFormat::MarcXml) => None, // Not in MARC21
+            (CodedInformation::AntiquarianCopySpecific, MarcFormat::Unimarc) => Some("141"),
+
+            (CodedInformation::MediumOfPerformance, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("382"),
+            (CodedInformation::MediumOfPerformance, MarcFormat::Unimarc) => Some("145"),
+
+            (CodedInformation::ContentForm, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("336"), // Content type
+            (CodedInformation::ContentForm, MarcFormat::Unimarc) => Some("181"),
+
+            (CodedInformation::MediaType, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("337"),
+            (CodedInformation::MediaType, MarcFormat::Unimarc) => Some("182"),
+
+            (CodedInformation::TypeOfCarrier, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("338"), // Carrier type
+            (CodedInformation::TypeOfCarrier, MarcFormat::Unimarc) => Some("183"),
+        }
+    }
+}
@@ -20,6 +20,17 @@ pub enum Control {
 }
 
 impl Control {
+    /// All variants, in declaration order
+    pub const ALL: &'static [Control] = &[
+        Control::ControlNumber,
+        Control::ControlNumberIdentifier,
+        Control::DateAndTimeOfLatestTransaction,
+        Control::FixedLengthDataElementsAdditional,
+        Control::PhysicalDescriptionFixedField,
+        Control::FixedLengthDataElements,
+        Control::LocalControlNumber,
+    ];
+
     /// Get the tag as string for the given format
     pub fn tag(&self, format: MarcFormat) -> Option<&'static str> {
         match (self, format) {
@@ -0,0 +1,53 @@
+use crate::format::MarcFormat;
+
+/// Definition of a subfield, as published in the format documentation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubfieldDefinition {
+    pub code: char,
+    pub name: &'static str,
+    pub repeatable: bool,
+}
+
+/// Definition of a field, as published in the format documentation
+///
+/// Generated at build time from the field lists in `data/`. Fields listed
+/// without subfields have an empty `subfields` slice. Indicator values are
+/// described separately (see [`indicators`](crate::fields::indicators())).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldDefinition {
+    pub tag: &'static str,
+    pub name: &'static str,
+    pub repeatable: bool,
+    pub subfields: &'static [SubfieldDefinition],
+}
+
+impl FieldDefinition {
+    /// Definition of a subfield code, if listed
+    pub fn subfield(&self, code: char) -> Option<&'static SubfieldDefinition> {
+        self.subfields.iter().find(|s| s.code == code)
+    }
+}
+
+include!(concat!(env!("OUT_DIR"), "/field_definitions.rs"));
+
+/// All field definitions of a format, ordered by tag
+pub fn field_definitions(format: MarcFormat) -> &'static [FieldDefinition] {
+    match format {
+        MarcFormat::Marc21 | MarcFormat::MarcXml => MARC21_DEFINITIONS,
+        MarcFormat::Unimarc => UNIMARC_DEFINITIONS,
+    }
+}
+
+/// Definition of a tag in the given format, if listed
+pub fn field_definition(tag: &str, format: MarcFormat) -> Option<&'static FieldDefinition> {
+    let definitions = field_definitions(format);
+    definitions.binary_search_by(|d| d.tag.cmp(tag)).ok().map(|i| &definitions[i])
+}
+
+/// Whether a tag is marked obsolete in the format documentation
+pub(crate) fn is_obsolete(tag: &str, format: MarcFormat) -> bool {
+    match format {
+        MarcFormat::Marc21 | MarcFormat::MarcXml => MARC21_OBSOLETE.contains(&tag),
+        MarcFormat::Unimarc => UNIMARC_OBSOLETE.contains(&tag),
+    }
+}
@@ -18,6 +18,16 @@ pub enum Edition {
 }
 
 impl Edition {
+    /// All variants, in declaration order
+    pub const ALL: &'static [Edition] = &[
+        Edition::EditionStatement,
+        Edition::MusicalPresentationStatement,
+        Edition::CartographicMathematicalData,
+        Edition::ComputerFileCharacteristics,
+        Edition::CountryOfProducingEntity,
+        Edition::PhilatelicIssueData,
+    ];
+
     /// Get the tag as string for the given format
     pub fn tag(&self, format: MarcFormat) -> Option<&'static str> {
         match (self, format) {
@@ -0,0 +1,107 @@
+use crate::format::MarcFormat;
+
+/// Holdings, location and electronic access fields (841-88X in MARC21)
+///
+/// Apart from 856, these fields are not part of UNIMARC bibliographic records
+/// (holdings are carried in the separate UNIMARC/Holdings format).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Holdings {
+    /// 841 - Holdings coded data values
+    HoldingsCodedDataValues,
+    /// 842 - Textual physical form designator
+    TextualPhysicalFormDesignator,
+    /// 843 - Reproduction note
+    ReproductionNote,
+    /// 844 - Name of unit
+    NameOfUnit,
+    /// 845 - Terms governing use and reproduction note
+    TermsGoverningUse,
+    /// 850 - Holding institution
+    HoldingInstitution,
+    /// 852 - Location
+    Location,
+    /// 853 - Captions and pattern - Basic bibliographic unit
+    CaptionsAndPatternBasic,
+    /// 854 - Captions and pattern - Supplementary material
+    CaptionsAndPatternSupplementary,
+    /// 855 - Captions and pattern - Indexes
+    CaptionsAndPatternIndexes,
+    /// 856 - Electronic location and access (856 in UNIMARC)
+    ElectronicLocationAndAccess,
+    /// 863 - Enumeration and chronology - Basic bibliographic unit
+    EnumerationAndChronologyBasic,
+    /// 864 - Enumeration and chronology - Supplementary material
+    EnumerationAndChronologySupplementary,
+    /// 865 - Enumeration and chronology - Indexes
+    EnumerationAndChronologyIndexes,
+    /// 866 - Textual holdings - Basic bibliographic unit
+    TextualHoldingsBasic,
+    /// 867 - Textual holdings - Supplementary material
+    TextualHoldingsSupplementary,
+    /// 868 - Textual holdings - Indexes
+    TextualHoldingsIndexes,
+    /// 876 - Item information - Basic bibliographic unit
+    ItemInformationBasic,
+    /// 877 - Item information - Supplementary material
+    ItemInformationSupplementary,
+    /// 878 - Item information - Indexes
+    ItemInformationIndexes,
+}
+
+impl Holdings {
+    /// All variants, in declaration order
+    pub const ALL: &'static [Holdings] = &[
+        Holdings::HoldingsCodedDataValues,
+        Holdings::TextualPhysicalFormDesignator,
+        Holdings::ReproductionNote,
+        Holdings::NameOfUnit,
+        Holdings::TermsGoverningUse,
+        Holdings::HoldingInstitution,
+        Holdings::Location,
+        Holdings::CaptionsAndPatternBasic,
+        Holdings::CaptionsAndPatternSupplementary,
+        Holdings::CaptionsAndPatternIndexes,
+        Holdings::ElectronicLocationAndAccess,
+        Holdings::EnumerationAndChronologyBasic,
+        Holdings::EnumerationAndChronologySupplementary,
+        Holdings::EnumerationAndChronologyIndexes,
+        Holdings::TextualHoldingsBasic,
+        Holdings::TextualHoldingsSupplementary,
+        Holdings::TextualHoldingsIndexes,
+        Holdings::ItemInformationBasic,
+        Holdings::ItemInformationSupplementary,
+        Holdings::ItemInformationIndexes,
+    ];
+
+    /// Get the tag as string for the given format
+    pub fn tag(&self, format: MarcFormat) -> Option<&'static str> {
+        let tag = match self {
+            Holdings::HoldingsCodedDataValues => "841",
+            Holdings::TextualPhysicalFormDesignator => "842",
+            Holdings::ReproductionNote => "843",
+            Holdings::NameOfUnit => "844",
+            Holdings::TermsGoverningUse => "845",
+            Holdings::HoldingInstitution => "850",
+            Holdings::Location => "852",
+            Holdings::CaptionsAndPatternBasic => "853",
+            Holdings::CaptionsAndPatternSupplementary => "854",
+            Holdings::CaptionsAndPatternIndexes => "855",
+            Holdings::ElectronicLocationAndAccess => "856",
+            Holdings::EnumerationAndChronologyBasic => "863",
+            Holdings::EnumerationAndChronologySupplementary => "864",
+            Holdings::EnumerationAndChronologyIndexes => "865",
+            Holdings::TextualHoldingsBasic => "866",
+            Holdings::TextualHoldingsSupplementary => "867",
+            Holdings::TextualHoldingsIndexes => "868",
+            Holdings::ItemInformationBasic => "876",
+            Holdings::ItemInformationSupplementary => "877",
+            Holdings::ItemInformationIndexes => "878",
+        };
+
+        match (self, format) {
+            (_, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some(tag),
+            (Holdings::ElectronicLocationAndAccess, MarcFormat::Unimarc) => Some(tag),
+            (_, MarcFormat::Unimarc) => None, // Not in UNIMARC bibliographic
+        }
+    }
+}
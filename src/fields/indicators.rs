@@ -0,0 +1,54 @@
+use crate::format::MarcFormat;
+
+/// A valid indicator value and its meaning
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndicatorValue {
+    pub value: char,
+    pub meaning: &'static str,
+}
+
+/// Definition of one indicator position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Indicator {
+    /// What the indicator expresses (e.g. "Nonfiling characters")
+    pub label: &'static str,
+    /// Valid values
+    pub values: &'static [IndicatorValue],
+}
+
+impl Indicator {
+    /// Whether the value is valid for this indicator
+    pub fn is_valid(&self, value: char) -> bool {
+        self.values.iter().any(|v| v.value == value)
+    }
+
+    /// Meaning of the value, if it is valid
+    pub fn meaning(&self, value: char) -> Option<&'static str> {
+        self.values.iter().find(|v| v.value == value).map(|v| v.meaning)
+    }
+
+    /// True when the indicator is undefined (only blank is valid)
+    pub fn is_undefined(&self) -> bool {
+        self.values.len() == 1 && self.values[0].value == ' '
+    }
+}
+
+/// Definitions of both indicators of a field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Indicators {
+    pub first: Indicator,
+    pub second: Indicator,
+}
+
+include!(concat!(env!("OUT_DIR"), "/indicator_definitions.rs"));
+
+/// Valid indicator values for a bibliographic tag, if the tag is described
+///
+/// Generated at build time from the indicator sections of the field lists in `data/`.
+pub fn indicators(tag: &str, format: MarcFormat) -> Option<Indicators> {
+    let table = match format {
+        MarcFormat::Marc21 | MarcFormat::MarcXml => MARC21_INDICATORS,
+        MarcFormat::Unimarc => UNIMARC_INDICATORS,
+    };
+    table.binary_search_by(|(t, _)| (*t).cmp(tag)).ok().map(|i| table[i].1)
+}
@@ -0,0 +1,207 @@
+use crate::fields::{AddedEntry, CodedInformation, Control, Edition, Holdings, Linking, MainEntry, Note, Numbers, Physical, Series, Subject, Title};
+use crate::fields::metadata;
+use crate::format::MarcFormat;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Any bibliographic field enum variant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    /// Control fields (00X)
+    Control(Control),
+    /// Coded information (1XX in UNIMARC, mostly 00X/0XX/3XX in MARC21)
+    CodedInformation(CodedInformation),
+    /// Numbers and codes (01X-09X in MARC21, 0XX/1XX/6XX/8XX in UNIMARC)
+    Numbers(Numbers),
+    /// Main entries (1XX in MARC21, 7X0 in UNIMARC)
+    MainEntry(MainEntry),
+    /// Titles and title-related fields
+    Title(Title),
+    /// Edition fields (25X in MARC21, 2XX in UNIMARC)
+    Edition(Edition),
+    /// Physical description (3XX in MARC21, 2XX in UNIMARC)
+    Physical(Physical),
+    /// Series statements (4XX)
+    Series(Series),
+    /// Notes (5XX in MARC21, 3XX in UNIMARC)
+    Note(Note),
+    /// Subject access (6XX)
+    Subject(Subject),
+    /// Added entries (70X-75X in MARC21, 70X-72X in UNIMARC)
+    AddedEntry(AddedEntry),
+    /// Linking entries (76X-78X in MARC21, 4XX in UNIMARC)
+    Linking(Linking),
+    /// Holdings, location and access (84X-88X)
+    Holdings(Holdings),
+}
+
+impl FieldKind {
+    /// Get the tag as string for the given format
+    pub fn tag(&self, format: MarcFormat) -> Option<&'static str> {
+        match self {
+            FieldKind::Control(f) => f.tag(format),
+            FieldKind::CodedInformation(f) => f.tag(format),
+            FieldKind::Numbers(f) => f.tag(format),
+            FieldKind::MainEntry(f) => Some(f.tag(format)),
+            FieldKind::Title(f) => Some(f.tag(format)),
+            FieldKind::Edition(f) => f.tag(format),
+            FieldKind::Physical(f) => f.tag(format),
+            FieldKind::Series(f) => Some(f.tag(format)),
+            FieldKind::Note(f) => f.tag(format),
+            FieldKind::Subject(f) => f.tag(format),
+            FieldKind::AddedEntry(f) => f.tag(format),
+            FieldKind::Linking(f) => f.tag(format),
+            FieldKind::Holdings(f) => f.tag(format),
+        }
+    }
+
+    /// URL of the field's authoritative definition on the Library of Congress site, for MARC21
+    ///
+    /// ```
+    /// use marc_rs::{FieldKind, MarcFormat, Title};
+    ///
+    /// let kind = FieldKind::Title(Title::TitleStatement);
+    /// assert_eq!(kind.documentation_url(MarcFormat::Marc21).as_deref(), Some("https://www.loc.gov/marc/bibliographic/bd245.html"));
+    /// ```
+    pub fn documentation_url(&self, format: MarcFormat) -> Option<String> {
+        self.tag(format).and_then(|tag| metadata::documentation_url(tag, format))
+    }
+
+    /// All field kinds, in lookup priority order for the given format
+    ///
+    /// When several variants share a tag, the earliest one is the most specific.
+    pub fn all(format: MarcFormat) -> Vec<FieldKind> {
+        let mut kinds = Vec::new();
+
+        // In UNIMARC the 1XX block is the primary meaning of its tags; in MARC21 the
+        // coded information equivalents are loose and only used as a last resort.
+        if format == MarcFormat::Unimarc {
+            kinds.extend(CodedInformation::ALL.iter().copied().map(FieldKind::CodedInformation));
+        }
+        kinds.extend(Control::ALL.iter().copied().map(FieldKind::Control));
+        kinds.extend(Numbers::ALL.iter().copied().map(FieldKind::Numbers));
+        kinds.extend(MainEntry::ALL.iter().copied().map(FieldKind::MainEntry));
+        kinds.extend(Title::ALL.iter().copied().map(FieldKind::Title));
+        // Several descriptive fields loosely map onto UNIMARC 3XX notes; the note reading is the primary one.
+        kinds.extend(Note::ALL.iter().copied().map(FieldKind::Note));
+        kinds.extend(Edition::ALL.iter().copied().map(FieldKind::Edition));
+        kinds.extend(Physical::ALL.iter().copied().map(FieldKind::Physical));
+        kinds.extend(Series::ALL.iter().copied().map(FieldKind::Series));
+        kinds.extend(Subject::ALL.iter().copied().map(FieldKind::Subject));
+        kinds.extend(AddedEntry::ALL.iter().copied().map(FieldKind::AddedEntry));
+        kinds.extend(Linking::ALL.iter().copied().map(FieldKind::Linking));
+        kinds.extend(Holdings::ALL.iter().copied().map(FieldKind::Holdings));
+        if format != MarcFormat::Unimarc {
+            kinds.extend(CodedInformation::ALL.iter().copied().map(FieldKind::CodedInformation));
+        }
+
+        kinds
+    }
+}
+
+impl From<Control> for FieldKind {
+    fn from(field: Control) -> Self {
+        FieldKind::Control(field)
+    }
+}
+
+impl From<CodedInformation> for FieldKind {
+    fn from(field: CodedInformation) -> Self {
+        FieldKind::CodedInformation(field)
+    }
+}
+
+impl From<Numbers> for FieldKind {
+    fn from(field: Numbers) -> Self {
+        FieldKind::Numbers(field)
+    }
+}
+
+impl From<MainEntry> for FieldKind {
+    fn from(field: MainEntry) -> Self {
+        FieldKind::MainEntry(field)
+    }
+}
+
+impl From<Title> for FieldKind {
+    fn from(field: Title) -> Self {
+        FieldKind::Title(field)
+    }
+}
+
+impl From<Edition> for FieldKind {
+    fn from(field: Edition) -> Self {
+        FieldKind::Edition(field)
+    }
+}
+
+impl From<Physical> for FieldKind {
+    fn from(field: Physical) -> Self {
+        FieldKind::Physical(field)
+    }
+}
+
+impl From<Series> for FieldKind {
+    fn from(field: Series) -> Self {
+        FieldKind::Series(field)
+    }
+}
+
+impl From<Note> for FieldKind {
+    fn from(field: Note) -> Self {
+        FieldKind::Note(field)
+    }
+}
+
+impl From<Subject> for FieldKind {
+    fn from(field: Subject) -> Self {
+        FieldKind::Subject(field)
+    }
+}
+
+impl From<AddedEntry> for FieldKind {
+    fn from(field: AddedEntry) -> Self {
+        FieldKind::AddedEntry(field)
+    }
+}
+
+impl From<Linking> for FieldKind {
+    fn from(field: Linking) -> Self {
+        FieldKind::Linking(field)
+    }
+}
+
+impl From<Holdings> for FieldKind {
+    fn from(field: Holdings) -> Self {
+        FieldKind::Holdings(field)
+    }
+}
+
+/// Map a tag back to its field enum variant for the given format
+///
+/// ```
+/// use marc_rs::{fields, FieldKind, MarcFormat, Subject};
+///
+/// assert_eq!(fields::identify("650", MarcFormat::Marc21), Some(FieldKind::Subject(Subject::SubjectTopicalTerm)));
+/// assert_eq!(fields::identify("606", MarcFormat::Unimarc), Some(FieldKind::Subject(Subject::SubjectTopicalTerm)));
+/// ```
+pub fn identify(tag: &str, format: MarcFormat) -> Option<FieldKind> {
+    static MARC21: OnceLock<HashMap<&'static str, FieldKind>> = OnceLock::new();
+    static UNIMARC: OnceLock<HashMap<&'static str, FieldKind>> = OnceLock::new();
+    let table = match format {
+        MarcFormat::Marc21 | MarcFormat::MarcXml => &MARC21,
+        MarcFormat::Unimarc => &UNIMARC,
+    };
+    table.get_or_init(|| kinds_by_tag(format)).get(tag).copied()
+}
+
+/// First kind of each tag, in [`FieldKind::all`] order
+fn kinds_by_tag(format: MarcFormat) -> HashMap<&'static str, FieldKind> {
+    let mut kinds = HashMap::new();
+    for kind in FieldKind::all(format) {
+        if let Some(tag) = kind.tag(format) {
+            kinds.entry(tag).or_insert(kind);
+        }
+    }
+    kinds
+}
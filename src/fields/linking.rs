@@ -36,6 +36,25 @@ pub enum Linking {
 }
 
 impl Linking {
+    /// All variants, in declaration order
+    pub const ALL: &'static [Linking] = &[
+        Linking::MainSeriesEntry,
+        Linking::SubseriesEntry,
+        Linking::OriginalLanguageEntry,
+        Linking::TranslationEntry,
+        Linking::SupplementSpecialIssueEntry,
+        Linking::SupplementParentEntry,
+        Linking::HostItemEntry,
+        Linking::ConstituentUnitEntry,
+        Linking::OtherEditionEntry,
+        Linking::AdditionalPhysicalFormEntry,
+        Linking::IssuedWithEntry,
+        Linking::PrecedingEntry,
+        Linking::SucceedingEntry,
+        Linking::DataSourceEntry,
+        Linking::OtherRelationshipEntry,
+    ];
+
     /// Get the tag as string for the given format
     pub fn tag(&self, format: MarcFormat) -> Option<&'static str> {
         match (self, format) {
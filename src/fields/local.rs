@@ -0,0 +1,199 @@
+use crate::fields::definitions::field_definition;
+use crate::fields::kind::{identify, FieldKind};
+use crate::fields::metadata::{field_status, is_repeatable, FieldStatus};
+use crate::format::MarcFormat;
+use crate::record::{DataField, Record};
+
+/// Definition of a subfield of a locally-defined field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalSubfield {
+    pub code: char,
+    pub label: String,
+    pub repeatable: bool,
+}
+
+/// Definition of a locally-defined field (typically 9XX)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalField {
+    pub tag: String,
+    pub label: String,
+    pub repeatable: bool,
+    pub subfields: Vec<LocalSubfield>,
+}
+
+impl LocalField {
+    /// Create a repeatable field definition with no subfields
+    pub fn new(tag: &str, label: &str) -> Self {
+        Self {
+            tag: tag.to_string(),
+            label: label.to_string(),
+            repeatable: true,
+            subfields: Vec::new(),
+        }
+    }
+
+    /// Set whether the field may occur more than once
+    pub fn repeatable(mut self, repeatable: bool) -> Self {
+        self.repeatable = repeatable;
+        self
+    }
+
+    /// Add a subfield definition
+    pub fn subfield(mut self, code: char, label: &str, repeatable: bool) -> Self {
+        self.subfields.push(LocalSubfield {
+            code,
+            label: label.to_string(),
+            repeatable,
+        });
+        self
+    }
+
+    /// Definition of a subfield code, if declared
+    pub fn get_subfield(&self, code: char) -> Option<&LocalSubfield> {
+        self.subfields.iter().find(|s| s.code == code)
+    }
+
+    /// Check a field against this definition, returning one message per problem
+    ///
+    /// Reports undeclared subfield codes and repeated non-repeatable subfields.
+    pub fn check(&self, field: &DataField) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        for (i, subfield) in field.subfields.iter().enumerate() {
+            match self.get_subfield(subfield.code) {
+                None => problems.push(format!("{}: undefined subfield ${}", field.tag, subfield.code)),
+                Some(definition) => {
+                    let first = field.subfields.iter().position(|s| s.code == subfield.code) == Some(i);
+                    if !definition.repeatable && !first {
+                        problems.push(format!("{}: subfield ${} is not repeatable", field.tag, subfield.code));
+                    }
+                }
+            }
+        }
+
+        problems
+    }
+}
+
+/// What a tag stands for, see [`LocalFieldRegistry::identify`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldIdentity<'a> {
+    /// Field of the format
+    Standard(FieldKind),
+    /// Field registered as a local definition
+    Local(&'a LocalField),
+}
+
+/// Registry of institution-specific field definitions
+///
+/// ```
+/// use marc_rs::{LocalField, LocalFieldRegistry, MarcFormat};
+///
+/// let mut registry = LocalFieldRegistry::new();
+/// registry.register(LocalField::new("949", "Local holdings").subfield('a', "Call number", false).subfield('i', "Barcode", true));
+///
+/// assert_eq!(registry.label("949"), Some("Local holdings"));
+/// assert_eq!(registry.field_label("245", MarcFormat::Marc21), Some("Title statement"));
+/// assert_eq!(registry.subfield_label("949", 'i'), Some("Barcode"));
+/// assert!(registry.is_repeatable("949", MarcFormat::Marc21));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LocalFieldRegistry {
+    fields: Vec<LocalField>,
+}
+
+impl LocalFieldRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a field definition, replacing any previous definition of the tag
+    pub fn register(&mut self, field: LocalField) -> &mut Self {
+        self.fields.retain(|f| f.tag != field.tag);
+        self.fields.push(field);
+        self
+    }
+
+    /// Definition of a tag, if registered
+    pub fn get(&self, tag: &str) -> Option<&LocalField> {
+        self.fields.iter().find(|f| f.tag == tag)
+    }
+
+    /// All registered definitions, in registration order
+    pub fn fields(&self) -> &[LocalField] {
+        &self.fields
+    }
+
+    /// Label of a registered tag
+    pub fn label(&self, tag: &str) -> Option<&str> {
+        self.get(tag).map(|f| f.label.as_str())
+    }
+
+    /// Name of a tag: its registered label, else its name in the format's field list
+    pub fn field_label(&self, tag: &str, format: MarcFormat) -> Option<&str> {
+        self.label(tag).or_else(|| field_definition(tag, format).map(|d| d.name))
+    }
+
+    /// What a tag stands for: its registered definition, else its field enum variant (see [`identify`])
+    pub fn identify(&self, tag: &str, format: MarcFormat) -> Option<FieldIdentity<'_>> {
+        match self.get(tag) {
+            Some(field) => Some(FieldIdentity::Local(field)),
+            None => identify(tag, format).map(FieldIdentity::Standard),
+        }
+    }
+
+    /// Label of a subfield of a registered tag
+    pub fn subfield_label(&self, tag: &str, code: char) -> Option<&str> {
+        self.get(tag).and_then(|f| f.get_subfield(code)).map(|s| s.label.as_str())
+    }
+
+    /// Whether the tag may be repeated, using the registered definition first and
+    /// the format's own rules otherwise
+    pub fn is_repeatable(&self, tag: &str, format: MarcFormat) -> bool {
+        match self.get(tag) {
+            Some(field) => field.repeatable,
+            None => is_repeatable(tag, format),
+        }
+    }
+
+    /// Check the record's registered fields, returning one message per problem
+    ///
+    /// Reports repeated non-repeatable fields, subfield problems (see
+    /// [`LocalField::check`]) and local-range tags that have no definition.
+    pub fn check_record(&self, record: &Record, format: MarcFormat) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        for (i, field) in record.data_fields.iter().enumerate() {
+            match self.get(&field.tag) {
+                Some(definition) => {
+                    let first = record.data_fields.iter().position(|f| f.tag == field.tag) == Some(i);
+                    if !definition.repeatable && !first {
+                        problems.push(format!("{}: field is not repeatable", field.tag));
+                    }
+                    problems.extend(definition.check(field));
+                }
+                None if field_status(&field.tag, format) == FieldStatus::Local => {
+                    let first = record.data_fields.iter().position(|f| f.tag == field.tag) == Some(i);
+                    if first {
+                        problems.push(format!("{}: local field is not defined", field.tag));
+                    }
+                }
+                None => {}
+            }
+        }
+
+        problems
+    }
+
+    /// Human-readable rendering of a registered field, one labelled subfield per line
+    pub fn describe(&self, field: &DataField) -> Option<String> {
+        let definition = self.get(&field.tag)?;
+        let mut lines = vec![format!("{} {} [{}{}]", field.tag, definition.label, field.ind1, field.ind2)];
+        for subfield in &field.subfields {
+            let label = definition.get_subfield(subfield.code).map(|s| s.label.as_str()).unwrap_or("Undefined");
+            lines.push(format!("  ${} {}: {}", subfield.code, label, subfield.value));
+        }
+        Some(lines.join("\n"))
+    }
+}
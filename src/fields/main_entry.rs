@@ -1,19 +1,30 @@
 use crate::format::MarcFormat;
 
-/// Main entry fields (1XX in MARC21, 7XX in UNIMARC for responsibility)
+/// Main entry fields (1XX in MARC21, primary responsibility 7X0 in UNIMARC)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MainEntry {
     /// Personal name (100 in MARC21, 700 in UNIMARC)
     PersonalName,
     /// Corporate name (110 in MARC21, 710 in UNIMARC)
     CorporateName,
-    /// Meeting name (111 in MARC21, 711 in UNIMARC)
+    /// Meeting name (111 in MARC21, 710 with first indicator 1 in UNIMARC)
     MeetingName,
-    /// Uniform title (130 in MARC21, 730 in UNIMARC)
+    /// Uniform title (130 in MARC21, 500 in UNIMARC)
     UniformTitle,
+    /// Family name (100 with first indicator 3 in MARC21, 720 in UNIMARC)
+    FamilyName,
 }
 
 impl MainEntry {
+    /// All variants, in declaration order
+    pub const ALL: &'static [MainEntry] = &[
+        MainEntry::PersonalName,
+        MainEntry::CorporateName,
+        MainEntry::MeetingName,
+        MainEntry::UniformTitle,
+        MainEntry::FamilyName,
+    ];
+
     /// Get the tag as string for the given format
     pub fn tag(&self, format: MarcFormat) -> &'static str {
         match (self, format) {
@@ -24,10 +35,13 @@ impl MainEntry {
             (MainEntry::CorporateName, MarcFormat::Unimarc) => "710",
 
             (MainEntry::MeetingName, MarcFormat::Marc21 | MarcFormat::MarcXml) => "111",
-            (MainEntry::MeetingName, MarcFormat::Unimarc) => "711",
+            (MainEntry::MeetingName, MarcFormat::Unimarc) => "710", // Meeting indicator set
 
             (MainEntry::UniformTitle, MarcFormat::Marc21 | MarcFormat::MarcXml) => "130",
-            (MainEntry::UniformTitle, MarcFormat::Unimarc) => "730",
+            (MainEntry::UniformTitle, MarcFormat::Unimarc) => "500", // Uniform title
+
+            (MainEntry::FamilyName, MarcFormat::Marc21 | MarcFormat::MarcXml) => "100", // Family name indicator set
+            (MainEntry::FamilyName, MarcFormat::Unimarc) => "720",
         }
     }
 }
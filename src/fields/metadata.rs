@@ -0,0 +1,109 @@
+use crate::fields::{AddedEntry, CodedInformation, Control, Edition, FieldKind, Holdings, Linking, MainEntry, Note, Numbers, Physical, Series, Subject, Title};
+use crate::fields::definitions::{field_definition, is_obsolete, FieldDefinition};
+use crate::fields::indicators::{self, Indicators};
+use crate::format::MarcFormat;
+
+/// Whether a tag may occur more than once in a bibliographic record of the given format
+///
+/// Tags without a definition (including local 9XX tags) are considered repeatable.
+pub fn is_repeatable(tag: &str, format: MarcFormat) -> bool {
+    field_definition(tag, format).is_none_or(|d| d.repeatable)
+}
+
+/// Definition status of a tag within a format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldStatus {
+    /// Defined and in current use
+    Current,
+    /// Formerly defined, should no longer be used
+    Obsolete,
+    /// Reserved for locally-defined fields (e.g. 9XX)
+    Local,
+}
+
+/// Status of a tag in a bibliographic record of the given format
+///
+/// MARC21 reserves 09X, 59X, 69X and 9XX for local use; UNIMARC reserves every
+/// tag containing a 9 in its first or second position.
+pub fn field_status(tag: &str, format: MarcFormat) -> FieldStatus {
+    let bytes = tag.as_bytes();
+    if bytes.len() != 3 {
+        return FieldStatus::Current;
+    }
+    let local = match format {
+        MarcFormat::Marc21 | MarcFormat::MarcXml => bytes[0] == b'9' || (bytes[1] == b'9' && matches!(bytes[0], b'0' | b'5' | b'6')),
+        MarcFormat::Unimarc => bytes[0] == b'9' || bytes[1] == b'9',
+    };
+    if local {
+        return FieldStatus::Local;
+    }
+    if is_obsolete(tag, format) {
+        FieldStatus::Obsolete
+    } else {
+        FieldStatus::Current
+    }
+}
+
+/// Base URL of the MARC 21 bibliographic format documentation
+const MARC21_DOCUMENTATION: &str = "https://www.loc.gov/marc/bibliographic/";
+
+/// URL of the authoritative definition of a tag
+///
+/// MARC21 tags link to their Library of Congress page (local 9XX tags to the
+/// shared local fields page). UNIMARC has no published page per tag, so UNIMARC
+/// tags have no URL.
+pub fn documentation_url(tag: &str, format: MarcFormat) -> Option<String> {
+    match format {
+        MarcFormat::Marc21 | MarcFormat::MarcXml if tag.starts_with('9') => Some(format!("{}bd9xx.html", MARC21_DOCUMENTATION)),
+        MarcFormat::Marc21 | MarcFormat::MarcXml => Some(format!("{}bd{}.html", MARC21_DOCUMENTATION, tag.to_ascii_lowercase())),
+        MarcFormat::Unimarc => None,
+    }
+}
+
+/// Format-level metadata shared by all field enums
+pub trait FieldMetadata: Copy + Into<FieldKind> {
+    /// Whether the field may occur more than once, or `None` if it does not exist in the format
+    fn is_repeatable(&self, format: MarcFormat) -> Option<bool> {
+        let kind: FieldKind = (*self).into();
+        kind.tag(format).map(|tag| is_repeatable(tag, format))
+    }
+
+    /// Published definition (name, repeatability, subfields), or `None` if the field does not exist in the format or is not listed
+    fn definition(&self, format: MarcFormat) -> Option<&'static FieldDefinition> {
+        let kind: FieldKind = (*self).into();
+        kind.tag(format).and_then(|tag| field_definition(tag, format))
+    }
+
+    /// Definition status, or `None` if the field does not exist in the format
+    fn status(&self, format: MarcFormat) -> Option<FieldStatus> {
+        let kind: FieldKind = (*self).into();
+        kind.tag(format).map(|tag| field_status(tag, format))
+    }
+
+    /// URL of the field's authoritative definition, or `None` if it does not exist in the format or has no page
+    fn documentation_url(&self, format: MarcFormat) -> Option<String> {
+        let kind: FieldKind = (*self).into();
+        kind.tag(format).and_then(|tag| documentation_url(tag, format))
+    }
+
+    /// Valid indicator values, or `None` if the field does not exist in the format or is not described
+    fn indicators(&self, format: MarcFormat) -> Option<Indicators> {
+        let kind: FieldKind = (*self).into();
+        kind.tag(format).and_then(|tag| indicators::indicators(tag, format))
+    }
+}
+
+impl FieldMetadata for FieldKind {}
+impl FieldMetadata for Control {}
+impl FieldMetadata for CodedInformation {}
+impl FieldMetadata for Numbers {}
+impl FieldMetadata for MainEntry {}
+impl FieldMetadata for Title {}
+impl FieldMetadata for Edition {}
+impl FieldMetadata for Physical {}
+impl FieldMetadata for Series {}
+impl FieldMetadata for Note {}
+impl FieldMetadata for Subject {}
+impl FieldMetadata for AddedEntry {}
+impl FieldMetadata for Linking {}
+impl FieldMetadata for Holdings {}
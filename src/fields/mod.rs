@@ -1,21 +1,45 @@
 pub mod added_entry;
+pub mod authority;
+pub mod classification;
+pub mod coded;
 pub mod control;
+pub mod definitions;
 pub mod edition;
+pub mod holdings;
+pub mod indicators;
+pub mod kind;
 pub mod linking;
+pub mod local;
 pub mod main_entry;
+pub mod metadata;
 pub mod note;
+pub mod numbers;
 pub mod physical;
+pub mod responsibility;
 pub mod series;
 pub mod subject;
+pub mod subfields;
 pub mod title;
 
 pub use added_entry::*;
+pub use authority::*;
+pub use classification::*;
+pub use coded::*;
 pub use control::*;
+pub use definitions::*;
 pub use edition::*;
+pub use holdings::*;
+pub use indicators::*;
+pub use kind::*;
 pub use linking::*;
+pub use local::*;
 pub use main_entry::*;
+pub use metadata::*;
 pub use note::*;
+pub use numbers::*;
 pub use physical::*;
+pub use responsibility::*;
 pub use series::*;
 pub use subject::*;
+pub use subfields::*;
 pub use title::*;
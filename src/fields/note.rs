@@ -3,177 +3,315 @@ use crate::format::MarcFormat;
 /// Note fields (5XX in MARC21, 3XX in UNIMARC)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Note {
-    /// 500 - General note
+    /// 500 - General note (300 in UNIMARC)
     GeneralNote,
-    /// 501 - With note
+    /// 501 - With note (not in UNIMARC)
     WithNote,
-    /// 502 - Dissertation note
+    /// 502 - Dissertation note (328 in UNIMARC)
     DissertationNote,
-    /// 504 - Bibliography, etc. note
+    /// 504 - Bibliography, etc. note (320 in UNIMARC)
     BibliographyNote,
-    /// 505 - Formatted contents note
+    /// 505 - Formatted contents note (327 in UNIMARC)
     FormattedContentsNote,
-    /// 506 - Restrictions on access note
+    /// 506 - Restrictions on access note (371 in UNIMARC)
     RestrictionsOnAccessNote,
-    /// 507 - Scale note for graphic material
+    /// 507 - Scale note for graphic material (not in UNIMARC)
     ScaleNote,
-    /// 508 - Creation/production credits note
+    /// 508 - Creation/production credits note (322 in UNIMARC)
     CreationProductionCreditsNote,
-    /// 510 - Citation/references note
+    /// 510 - Citation/references note (321 in UNIMARC)
     CitationReferencesNote,
-    /// 511 - Participant or performer note
+    /// 511 - Participant or performer note (323 in UNIMARC)
     ParticipantOrPerformerNote,
-    /// 513 - Type of report and period covered note
+    /// 513 - Type of report and period covered note (not in UNIMARC)
     TypeOfReportAndPeriodCoveredNote,
-    /// 514 - Data quality note
+    /// 514 - Data quality note (not in UNIMARC)
     DataQualityNote,
-    /// 515 - Numbering peculiarities note
+    /// 515 - Numbering peculiarities note (315 in UNIMARC)
     NumberingPeculiaritiesNote,
-    /// 516 - Type of computer file or data note
+    /// 516 - Type of computer file or data note (336 in UNIMARC)
     TypeOfComputerFileOrDataNote,
-    /// 518 - Date/time and place of an event note
+    /// 518 - Date/time and place of an event note (not in UNIMARC)
     DateTimeAndPlaceOfEventNote,
-    /// 520 - Summary, etc.
+    /// 520 - Summary, etc. (330 in UNIMARC)
     Summary,
-    /// 521 - Target audience note
+    /// 521 - Target audience note (333 in UNIMARC)
     TargetAudienceNote,
-    /// 522 - Geographic coverage note
+    /// 522 - Geographic coverage note (not in UNIMARC)
     GeographicCoverageNote,
-    /// 524 - Preferred citation of described materials note
+    /// 524 - Preferred citation of described materials note (332 in UNIMARC)
     PreferredCitationNote,
-    /// 525 - Supplement note
+    /// 525 - Supplement note (not in UNIMARC)
     SupplementNote,
-    /// 526 - Study program information note
+    /// 526 - Study program information note (not in UNIMARC)
     StudyProgramInformationNote,
-    /// 530 - Additional physical form available note
+    /// 530 - Additional physical form available note (not in UNIMARC)
     AdditionalPhysicalFormAvailableNote,
-    /// 533 - Reproduction note
+    /// 533 - Reproduction note (325 in UNIMARC)
     ReproductionNote,
-    /// 534 - Original version note
+    /// 534 - Original version note (324 in UNIMARC)
     OriginalVersionNote,
-    /// 535 - Location of originals/duplicates note
+    /// 535 - Location of originals/duplicates note (not in UNIMARC)
     LocationOfOriginalsDuplicatesNote,
-    /// 536 - Funding information note
+    /// 536 - Funding information note (not in UNIMARC)
     FundingInformationNote,
-    /// 538 - System details note
+    /// 538 - System details note (337 in UNIMARC)
     SystemDetailsNote,
-    /// 540 - Terms governing use and reproduction note
+    /// 540 - Terms governing use and reproduction note (371 in UNIMARC)
     TermsGoverningUseAndReproductionNote,
-    /// 541 - Immediate source of acquisition note
+    /// 541 - Immediate source of acquisition note (345 in UNIMARC)
     ImmediateSourceOfAcquisitionNote,
-    /// 542 - Information relating to copyright status
+    /// 542 - Information relating to copyright status (not in UNIMARC)
     InformationRelatingToCopyrightStatus,
-    /// 544 - Location of other archival materials note
+    /// 544 - Location of other archival materials note (not in UNIMARC)
     LocationOfOtherArchivalMaterialsNote,
-    /// 545 - Biographical or historical data
+    /// 545 - Biographical or historical data (not in UNIMARC)
     BiographicalOrHistoricalData,
-    /// 546 - Language note
+    /// 546 - Language note (302 in UNIMARC)
     LanguageNote,
-    /// 547 - Former title complexity note
+    /// 547 - Former title complexity note (312 in UNIMARC)
     FormerTitleComplexityNote,
-    /// 550 - Issuing body note
+    /// 550 - Issuing body note (314 in UNIMARC)
     IssuingBodyNote,
-    /// 552 - Entity and attribute information note
+    /// 552 - Entity and attribute information note (not in UNIMARC)
     EntityAndAttributeInformationNote,
-    /// 555 - Cumulative index/finding aids note
+    /// 555 - Cumulative index/finding aids note (not in UNIMARC)
     CumulativeIndexFindingAidsNote,
-    /// 556 - Information about documentation note
+    /// 556 - Information about documentation note (not in UNIMARC)
     InformationAboutDocumentationNote,
-    /// 561 - Ownership and custodial history
+    /// 561 - Ownership and custodial history (317 in UNIMARC)
     OwnershipAndCustodialHistory,
-    /// 562 - Copy and version identification note
+    /// 562 - Copy and version identification note (316 in UNIMARC)
     CopyAndVersionIdentificationNote,
-    /// 563 - Binding information
+    /// 563 - Binding information (310 in UNIMARC)
     BindingInformation,
-    /// 565 - Case file characteristics note
+    /// 565 - Case file characteristics note (not in UNIMARC)
     CaseFileCharacteristicsNote,
-    /// 567 - Methodology note
+    /// 567 - Methodology note (not in UNIMARC)
     MethodologyNote,
-    /// 580 - Linking entry complexity note
+    /// 580 - Linking entry complexity note (311 in UNIMARC)
     LinkingEntryComplexityNote,
-    /// 581 - Publications about described materials note
+    /// 581 - Publications about described materials note (not in UNIMARC)
     PublicationsAboutDescribedMaterialsNote,
-    /// 583 - Action note
+    /// 583 - Action note (318 in UNIMARC)
     ActionNote,
-    /// 584 - Accumulation and frequency of use note
+    /// 584 - Accumulation and frequency of use note (not in UNIMARC)
     AccumulationAndFrequencyOfUseNote,
-    /// 585 - Exhibitions note
+    /// 585 - Exhibitions note (not in UNIMARC)
     ExhibitionsNote,
-    /// 586 - Awards note
+    /// 586 - Awards note (334 in UNIMARC)
     AwardsNote,
-    /// 588 - Source of description note
+    /// 588 - Source of description note (not in UNIMARC)
     SourceOfDescriptionNote,
 }
 
 impl Note {
+    /// All variants, in declaration order
+    pub const ALL: &'static [Note] = &[
+        Note::GeneralNote,
+        Note::WithNote,
+        Note::DissertationNote,
+        Note::BibliographyNote,
+        Note::FormattedContentsNote,
+        Note::RestrictionsOnAccessNote,
+        Note::ScaleNote,
+        Note::CreationProductionCreditsNote,
+        Note::CitationReferencesNote,
+        Note::ParticipantOrPerformerNote,
+        Note::TypeOfReportAndPeriodCoveredNote,
+        Note::DataQualityNote,
+        Note::NumberingPeculiaritiesNote,
+        Note::TypeOfComputerFileOrDataNote,
+        Note::DateTimeAndPlaceOfEventNote,
+        Note::Summary,
+        Note::TargetAudienceNote,
+        Note::GeographicCoverageNote,
+        Note::PreferredCitationNote,
+        Note::SupplementNote,
+        Note::StudyProgramInformationNote,
+        Note::AdditionalPhysicalFormAvailableNote,
+        Note::ReproductionNote,
+        Note::OriginalVersionNote,
+        Note::LocationOfOriginalsDuplicatesNote,
+        Note::FundingInformationNote,
+        Note::SystemDetailsNote,
+        Note::TermsGoverningUseAndReproductionNote,
+        Note::ImmediateSourceOfAcquisitionNote,
+        Note::InformationRelatingToCopyrightStatus,
+        Note::LocationOfOtherArchivalMaterialsNote,
+        Note::BiographicalOrHistoricalData,
+        Note::LanguageNote,
+        Note::FormerTitleComplexityNote,
+        Note::IssuingBodyNote,
+        Note::EntityAndAttributeInformationNote,
+        Note::CumulativeIndexFindingAidsNote,
+        Note::InformationAboutDocumentationNote,
+        Note::OwnershipAndCustodialHistory,
+        Note::CopyAndVersionIdentificationNote,
+        Note::BindingInformation,
+        Note::CaseFileCharacteristicsNote,
+        Note::MethodologyNote,
+        Note::LinkingEntryComplexityNote,
+        Note::PublicationsAboutDescribedMaterialsNote,
+        Note::ActionNote,
+        Note::AccumulationAndFrequencyOfUseNote,
+        Note::ExhibitionsNote,
+        Note::AwardsNote,
+        Note::SourceOfDescriptionNote,
+    ];
+
     /// Get the tag as string for the given format
-    /// Note: Most note fields are similar between MARC21 and UNIMARC (3XX block in UNIMARC)
-    pub fn tag(&self, format: MarcFormat) -> &'static str {
-        // For most notes, tags are the same or very similar
-        // UNIMARC uses 3XX block for notes, MARC21 uses 5XX
-        let tag = match self {
-            Note::GeneralNote => "500",
-            Note::WithNote => "501",
-            Note::DissertationNote => "502",
-            Note::BibliographyNote => "504",
-            Note::FormattedContentsNote => "505",
-            Note::RestrictionsOnAccessNote => "506",
-            Note::ScaleNote => "507",
-            Note::CreationProductionCreditsNote => "508",
-            Note::CitationReferencesNote => "510",
-            Note::ParticipantOrPerformerNote => "511",
-            Note::TypeOfReportAndPeriodCoveredNote => "513",
-            Note::DataQualityNote => "514",
-            Note::NumberingPeculiaritiesNote => "515",
-            Note::TypeOfComputerFileOrDataNote => "516",
-            Note::DateTimeAndPlaceOfEventNote => "518",
-            Note::Summary => "520",
-            Note::TargetAudienceNote => "521",
-            Note::GeographicCoverageNote => "522",
-            Note::PreferredCitationNote => "524",
-            Note::SupplementNote => "525",
-            Note::StudyProgramInformationNote => "526",
-            Note::AdditionalPhysicalFormAvailableNote => "530",
-            Note::ReproductionNote => "533",
-            Note::OriginalVersionNote => "534",
-            Note::LocationOfOriginalsDuplicatesNote => "535",
-            Note::FundingInformationNote => "536",
-            Note::SystemDetailsNote => "538",
-            Note::TermsGoverningUseAndReproductionNote => "540",
-            Note::ImmediateSourceOfAcquisitionNote => "541",
-            Note::InformationRelatingToCopyrightStatus => "542",
-            Note::LocationOfOtherArchivalMaterialsNote => "544",
-            Note::BiographicalOrHistoricalData => "545",
-            Note::LanguageNote => "546",
-            Note::FormerTitleComplexityNote => "547",
-            Note::IssuingBodyNote => "550",
-            Note::EntityAndAttributeInformationNote => "552",
-            Note::CumulativeIndexFindingAidsNote => "555",
-            Note::InformationAboutDocumentationNote => "556",
-            Note::OwnershipAndCustodialHistory => "561",
-            Note::CopyAndVersionIdentificationNote => "562",
-            Note::BindingInformation => "563",
-            Note::CaseFileCharacteristicsNote => "565",
-            Note::MethodologyNote => "567",
-            Note::LinkingEntryComplexityNote => "580",
-            Note::PublicationsAboutDescribedMaterialsNote => "581",
-            Note::ActionNote => "583",
-            Note::AccumulationAndFrequencyOfUseNote => "584",
-            Note::ExhibitionsNote => "585",
-            Note::AwardsNote => "586",
-            Note::SourceOfDescriptionNote => "588",
-        };
-
-        // Most notes are the same in both formats
-        // Some specific mappings could be added here if needed
-        match format {
-            MarcFormat::Marc21 | MarcFormat::MarcXml => tag,
-            MarcFormat::Unimarc => {
-                // In UNIMARC, notes are in 3XX block
-                // Most correspond to similar tags, but we keep MARC21 tags for simplicity
-                // A full mapping would require detailed UNIMARC specification
-                tag
-            }
+    pub fn tag(&self, format: MarcFormat) -> Option<&'static str> {
+        match (self, format) {
+            (Note::GeneralNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("500"),
+            (Note::GeneralNote, MarcFormat::Unimarc) => Some("300"),
+
+            (Note::WithNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("501"),
+            (Note::WithNote, MarcFormat::Unimarc) => None, // Not in UNIMARC
+
+            (Note::DissertationNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("502"),
+            (Note::DissertationNote, MarcFormat::Unimarc) => Some("328"),
+
+            (Note::BibliographyNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("504"),
+            (Note::BibliographyNote, MarcFormat::Unimarc) => Some("320"),
+
+            (Note::FormattedContentsNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("505"),
+            (Note::FormattedContentsNote, MarcFormat::Unimarc) => Some("327"),
+
+            (Note::RestrictionsOnAccessNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("506"),
+            (Note::RestrictionsOnAccessNote, MarcFormat::Unimarc) => Some("371"),
+
+            (Note::ScaleNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("507"),
+            (Note::ScaleNote, MarcFormat::Unimarc) => None, // Not in UNIMARC
+
+            (Note::CreationProductionCreditsNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("508"),
+            (Note::CreationProductionCreditsNote, MarcFormat::Unimarc) => Some("322"),
+
+            (Note::CitationReferencesNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("510"),
+            (Note::CitationReferencesNote, MarcFormat::Unimarc) => Some("321"),
+
+            (Note::ParticipantOrPerformerNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("511"),
+            (Note::ParticipantOrPerformerNote, MarcFormat::Unimarc) => Some("323"),
+
+            (Note::TypeOfReportAndPeriodCoveredNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("513"),
+            (Note::TypeOfReportAndPeriodCoveredNote, MarcFormat::Unimarc) => None, // Not in UNIMARC
+
+            (Note::DataQualityNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("514"),
+            (Note::DataQualityNote, MarcFormat::Unimarc) => None, // Not in UNIMARC
+
+            (Note::NumberingPeculiaritiesNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("515"),
+            (Note::NumberingPeculiaritiesNote, MarcFormat::Unimarc) => Some("315"),
+
+            (Note::TypeOfComputerFileOrDataNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("516"),
+            (Note::TypeOfComputerFileOrDataNote, MarcFormat::Unimarc) => Some("336"),
+
+            (Note::DateTimeAndPlaceOfEventNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("518"),
+            (Note::DateTimeAndPlaceOfEventNote, MarcFormat::Unimarc) => None, // Not in UNIMARC
+
+            (Note::Summary, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("520"),
+            (Note::Summary, MarcFormat::Unimarc) => Some("330"),
+
+            (Note::TargetAudienceNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("521"),
+            (Note::TargetAudienceNote, MarcFormat::Unimarc) => Some("333"),
+
+            (Note::GeographicCoverageNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("522"),
+            (Note::GeographicCoverageNote, MarcFormat::Unimarc) => None, // Not in UNIMARC
+
+            (Note::PreferredCitationNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("524"),
+            (Note::PreferredCitationNote, MarcFormat::Unimarc) => Some("332"),
+
+            (Note::SupplementNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("525"),
+            (Note::SupplementNote, MarcFormat::Unimarc) => None, // Not in UNIMARC
+
+            (Note::StudyProgramInformationNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("526"),
+            (Note::StudyProgramInformationNote, MarcFormat::Unimarc) => None, // Not in UNIMARC
+
+            (Note::AdditionalPhysicalFormAvailableNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("530"),
+            (Note::AdditionalPhysicalFormAvailableNote, MarcFormat::Unimarc) => None, // Not in UNIMARC
+
+            (Note::ReproductionNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("533"),
+            (Note::ReproductionNote, MarcFormat::Unimarc) => Some("325"),
+
+            (Note::OriginalVersionNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("534"),
+            (Note::OriginalVersionNote, MarcFormat::Unimarc) => Some("324"),
+
+            (Note::LocationOfOriginalsDuplicatesNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("535"),
+            (Note::LocationOfOriginalsDuplicatesNote, MarcFormat::Unimarc) => None, // Not in UNIMARC
+
+            (Note::FundingInformationNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("536"),
+            (Note::FundingInformationNote, MarcFormat::Unimarc) => None, // Not in UNIMARC
+
+            (Note::SystemDetailsNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("538"),
+            (Note::SystemDetailsNote, MarcFormat::Unimarc) => Some("337"),
+
+            (Note::TermsGoverningUseAndReproductionNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("540"),
+            (Note::TermsGoverningUseAndReproductionNote, MarcFormat::Unimarc) => Some("371"),
+
+            (Note::ImmediateSourceOfAcquisitionNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("541"),
+            (Note::ImmediateSourceOfAcquisitionNote, MarcFormat::Unimarc) => Some("345"),
+
+            (Note::InformationRelatingToCopyrightStatus, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("542"),
+            (Note::InformationRelatingToCopyrightStatus, MarcFormat::Unimarc) => None, // Not in UNIMARC
+
+            (Note::LocationOfOtherArchivalMaterialsNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("544"),
+            (Note::LocationOfOtherArchivalMaterialsNote, MarcFormat::Unimarc) => None, // Not in UNIMARC
+
+            (Note::BiographicalOrHistoricalData, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("545"),
+            (Note::BiographicalOrHistoricalData, MarcFormat::Unimarc) => None, // Not in UNIMARC
+
+            (Note::LanguageNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("546"),
+            (Note::LanguageNote, MarcFormat::Unimarc) => Some("302"),
+
+            (Note::FormerTitleComplexityNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("547"),
+            (Note::FormerTitleComplexityNote, MarcFormat::Unimarc) => Some("312"),
+
+            (Note::IssuingBodyNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("550"),
+            (Note::IssuingBodyNote, MarcFormat::Unimarc) => Some("314"),
+
+            (Note::EntityAndAttributeInformationNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("552"),
+            (Note::EntityAndAttributeInformationNote, MarcFormat::Unimarc) => None, // Not in UNIMARC
+
+            (Note::CumulativeIndexFindingAidsNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("555"),
+            (Note::CumulativeIndexFindingAidsNote, MarcFormat::Unimarc) => None, // Not in UNIMARC
+
+            (Note::InformationAboutDocumentationNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("556"),
+            (Note::InformationAboutDocumentationNote, MarcFormat::Unimarc) => None, // Not in UNIMARC
+
+            (Note::OwnershipAndCustodialHistory, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("561"),
+            (Note::OwnershipAndCustodialHistory, MarcFormat::Unimarc) => Some("317"),
+
+            (Note::CopyAndVersionIdentificationNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("562"),
+            (Note::CopyAndVersionIdentificationNote, MarcFormat::Unimarc) => Some("316"),
+
+            (Note::BindingInformation, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("563"),
+            (Note::BindingInformation, MarcFormat::Unimarc) => Some("310"),
+
+            (Note::CaseFileCharacteristicsNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("565"),
+            (Note::CaseFileCharacteristicsNote, MarcFormat::Unimarc) => None, // Not in UNIMARC
+
+            (Note::MethodologyNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("567"),
+            (Note::MethodologyNote, MarcFormat::Unimarc) => None, // Not in UNIMARC
+
+            (Note::LinkingEntryComplexityNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("580"),
+            (Note::LinkingEntryComplexityNote, MarcFormat::Unimarc) => Some("311"),
+
+            (Note::PublicationsAboutDescribedMaterialsNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("581"),
+            (Note::PublicationsAboutDescribedMaterialsNote, MarcFormat::Unimarc) => None, // Not in UNIMARC
+
+            (Note::ActionNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("583"),
+            (Note::ActionNote, MarcFormat::Unimarc) => Some("318"),
+
+            (Note::AccumulationAndFrequencyOfUseNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("584"),
+            (Note::AccumulationAndFrequencyOfUseNote, MarcFormat::Unimarc) => None, // Not in UNIMARC
+
+            (Note::ExhibitionsNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("585"),
+            (Note::ExhibitionsNote, MarcFormat::Unimarc) => None, // Not in UNIMARC
+
+            (Note::AwardsNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("586"),
+            (Note::AwardsNote, MarcFormat::Unimarc) => Some("334"),
+
+            (Note::SourceOfDescriptionNote, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("588"),
+            (Note::SourceOfDescriptionNote, MarcFormat::Unimarc) => None, // Not in UNIMARC
         }
     }
 }
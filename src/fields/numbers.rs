@@ -0,0 +1,106 @@
+use crate::format::MarcFormat;
+
+/// Number and code fields (01X-09X in MARC21, 0XX/1XX/6XX/8XX in UNIMARC)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Numbers {
+    /// Library of Congress Control Number (010 in MARC21, not in UNIMARC)
+    Lccn,
+    /// National bibliography number (015 in MARC21, 020 in UNIMARC)
+    NationalBibliographyNumber,
+    /// National bibliographic agency control number (016 in MARC21, not in UNIMARC)
+    NationalBibliographicAgencyControlNumber,
+    /// International Standard Book Number (020 in MARC21, 010 in UNIMARC)
+    Isbn,
+    /// International Standard Serial Number (022 in MARC21, 011 in UNIMARC)
+    Issn,
+    /// Other standard identifier (024 in MARC21, 017 in UNIMARC)
+    OtherStandardIdentifier,
+    /// Publisher or distributor number (028 in MARC21, 071 in UNIMARC)
+    PublisherNumber,
+    /// System control number (035 in both)
+    SystemControlNumber,
+    /// Cataloging source (040 in MARC21, 801 in UNIMARC)
+    CatalogingSource,
+    /// Language code (041 in MARC21, 101 in UNIMARC)
+    LanguageCode,
+    /// Geographic area code (043 in MARC21, 660 in UNIMARC)
+    GeographicAreaCode,
+    /// Country of publishing/producing entity code (044 in MARC21, 102 in UNIMARC)
+    CountryOfPublishingCode,
+    /// Library of Congress call number (050 in MARC21, 680 in UNIMARC)
+    LcCallNumber,
+    /// Dewey Decimal classification number (082 in MARC21, 676 in UNIMARC)
+    DeweyClassificationNumber,
+    /// Other classification number (084 in MARC21, 686 in UNIMARC)
+    OtherClassificationNumber,
+}
+
+impl Numbers {
+    /// All variants, in declaration order
+    pub const ALL: &'static [Numbers] = &[
+        Numbers::Lccn,
+        Numbers::NationalBibliographyNumber,
+        Numbers::NationalBibliographicAgencyControlNumber,
+        Numbers::Isbn,
+        Numbers::Issn,
+        Numbers::OtherStandardIdentifier,
+        Numbers::PublisherNumber,
+        Numbers::SystemControlNumber,
+        Numbers::CatalogingSource,
+        Numbers::LanguageCode,
+        Numbers::GeographicAreaCode,
+        Numbers::CountryOfPublishingCode,
+        Numbers::LcCallNumber,
+        Numbers::DeweyClassificationNumber,
+        Numbers::OtherClassificationNumber,
+    ];
+
+    /// Get the tag as string for the given format
+    pub fn tag(&self, format: MarcFormat) -> Option<&'static str> {
+        match (self, format) {
+            (Numbers::Lccn, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("010"),
+            (Numbers::Lccn, MarcFormat::Unimarc) => None, // Not in UNIMARC
+
+            (Numbers::NationalBibliographyNumber, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("015"),
+            (Numbers::NationalBibliographyNumber, MarcFormat::Unimarc) => Some("020"),
+
+            (Numbers::NationalBibliographicAgencyControlNumber, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("016"),
+            (Numbers::NationalBibliographicAgencyControlNumber, MarcFormat::Unimarc) => None, // Not in UNIMARC
+
+            (Numbers::Isbn, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("020"),
+            (Numbers::Isbn, MarcFormat::Unimarc) => Some("010"),
+
+            (Numbers::Issn, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("022"),
+            (Numbers::Issn, MarcFormat::Unimarc) => Some("011"),
+
+            (Numbers::OtherStandardIdentifier, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("024"),
+            (Numbers::OtherStandardIdentifier, MarcFormat::Unimarc) => Some("017"),
+
+            (Numbers::PublisherNumber, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("028"),
+            (Numbers::PublisherNumber, MarcFormat::Unimarc) => Some("071"),
+
+            (Numbers::SystemControlNumber, _) => Some("035"),
+
+            (Numbers::CatalogingSource, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("040"),
+            (Numbers::CatalogingSource, MarcFormat::Unimarc) => Some("801"), // Originating source
+
+            (Numbers::LanguageCode, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("041"),
+            (Numbers::LanguageCode, MarcFormat::Unimarc) => Some("101"), // Language of the item
+
+            (Numbers::GeographicAreaCode, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("043"),
+            (Numbers::GeographicAreaCode, MarcFormat::Unimarc) => Some("660"),
+
+            (Numbers::CountryOfPublishingCode, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("044"),
+            (Numbers::CountryOfPublishingCode, MarcFormat::Unimarc) => Some("102"), // Country of publication or production
+
+            (Numbers::LcCallNumber, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("050"),
+            (Numbers::LcCallNumber, MarcFormat::Unimarc) => Some("680"), // Library of Congress classification
+
+            (Numbers::DeweyClassificationNumber, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("082"),
+            (Numbers::DeweyClassificationNumber, MarcFormat::Unimarc) => Some("676"),
+
+            (Numbers::OtherClassificationNumber, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("084"),
+            (Numbers::OtherClassificationNumber, MarcFormat::Unimarc) => Some("686"),
+        }
+    }
+}
@@ -58,6 +58,36 @@ pub enum Physical {
 }
 
 impl Physical {
+    /// All variants, in declaration order
+    pub const ALL: &'static [Physical] = &[
+        Physical::PhysicalDescription,
+        Physical::PlayingTime,
+        Physical::Hours,
+        Physical::CurrentPublicationFrequency,
+        Physical::FormerPublicationFrequency,
+        Physical::PhysicalMedium,
+        Physical::GeospatialReferenceData,
+        Physical::PlanarCoordinateData,
+        Physical::OrganizationAndArrangement,
+        Physical::DigitalGraphicRepresentation,
+        Physical::SecurityClassificationControl,
+        Physical::OriginatorDisseminationControl,
+        Physical::DatesOfPublication,
+        Physical::NormalizedDate,
+        Physical::TradePrice,
+        Physical::TradeAvailabilityInformation,
+        Physical::AssociatedPlace,
+        Physical::AssociatedLanguage,
+        Physical::FormOfWork,
+        Physical::OtherDistinguishingCharacteristics,
+        Physical::MediumOfPerformance,
+        Physical::NumericDesignationOfMusicalWork,
+        Physical::Key,
+        Physical::AudienceCharacteristics,
+        Physical::CreatorContributorCharacteristics,
+        Physical::TimePeriodOfCreation,
+    ];
+
     /// Get the tag as string for the given format
     pub fn tag(&self, format: MarcFormat) -> Option<&'static str> {
         match (self, format) {
@@ -131,7 +161,7 @@ impl Physical {
             (Physical::Key, MarcFormat::Unimarc) => Some("125"), // Musical key
 
             (Physical::AudienceCharacteristics, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("385"),
-            (Physical::AudienceCharacteristics, MarcFormat::Unimarc) => Some("330"), // Audience
+            (Physical::AudienceCharacteristics, MarcFormat::Unimarc) => Some("333"), // Users/intended audience note
 
             (Physical::CreatorContributorCharacteristics, MarcFormat::Marc21 | MarcFormat::MarcXml) => Some("386"),
             (Physical::CreatorContributorCharacteristics, MarcFormat::Unimarc) => None, // Not in UNIMARC
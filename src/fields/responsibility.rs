@@ -0,0 +1,109 @@
+use crate::fields::MainEntry;
+use crate::format::MarcFormat;
+use crate::record::DataField;
+use crate::relator::Relator;
+
+/// Relators that denote authorship-level (alternative) rather than secondary responsibility
+const AUTHORIAL_RELATORS: [Relator; 9] = [
+    Relator::Author,
+    Relator::Creator,
+    Relator::Composer,
+    Relator::Artist,
+    Relator::Cartographer,
+    Relator::Photographer,
+    Relator::Librettist,
+    Relator::Lyricist,
+    Relator::Screenwriter,
+];
+
+/// Level of intellectual responsibility carried by a UNIMARC 7XX tag
+///
+/// UNIMARC distinguishes primary (700/710/720), alternative (701/711/721) and
+/// secondary (702/712/722) responsibility, where MARC21 only has 1XX and 7XX.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Responsibility {
+    /// Main entry (700, 710, 720); at most one per record
+    Primary,
+    /// Co-author level (701, 711, 721)
+    Alternative,
+    /// Editors, translators, illustrators, etc. (702, 712, 722)
+    Secondary,
+}
+
+impl Responsibility {
+    /// Level encoded by a UNIMARC name tag
+    pub fn from_unimarc_tag(tag: &str) -> Option<Self> {
+        match tag {
+            "700" | "710" | "720" => Some(Responsibility::Primary),
+            "701" | "711" | "721" => Some(Responsibility::Alternative),
+            "702" | "712" | "722" => Some(Responsibility::Secondary),
+            _ => None,
+        }
+    }
+
+    /// UNIMARC tag for a name entity at this level
+    ///
+    /// Corporate and meeting names share 71X (meetings set the first indicator to 1);
+    /// uniform titles have no responsibility tag.
+    pub fn unimarc_tag(&self, entity: MainEntry) -> Option<&'static str> {
+        let tags = match entity {
+            MainEntry::PersonalName => ["700", "701", "702"],
+            MainEntry::CorporateName | MainEntry::MeetingName => ["710", "711", "712"],
+            MainEntry::FamilyName => ["720", "721", "722"],
+            MainEntry::UniformTitle => return None,
+        };
+        Some(match self {
+            Responsibility::Primary => tags[0],
+            Responsibility::Alternative => tags[1],
+            Responsibility::Secondary => tags[2],
+        })
+    }
+
+    /// Level of a name field
+    ///
+    /// UNIMARC fields take the level from their tag. MARC21 1XX fields are primary;
+    /// 700/710/711 fields are alternative when they carry no relator or an authorial
+    /// one (author, creator, composer, ...) and secondary otherwise.
+    pub fn of_field(field: &DataField, format: MarcFormat) -> Option<Self> {
+        match format {
+            MarcFormat::Unimarc => Responsibility::from_unimarc_tag(&field.tag),
+            MarcFormat::Marc21 | MarcFormat::MarcXml => match field.tag.as_str() {
+                "100" | "110" | "111" => Some(Responsibility::Primary),
+                "700" | "710" | "711" => {
+                    let relators = field.relators();
+                    if relators.is_empty() || relators.iter().any(|r| AUTHORIAL_RELATORS.contains(r)) {
+                        Some(Responsibility::Alternative)
+                    } else {
+                        Some(Responsibility::Secondary)
+                    }
+                }
+                _ => None,
+            },
+        }
+    }
+}
+
+impl MainEntry {
+    /// Name entity of a responsibility field (personal, corporate, meeting or family name)
+    ///
+    /// UNIMARC 71X fields are meetings when their first indicator is 1; MARC21
+    /// 100/700 fields are family names when their first indicator is 3.
+    pub fn entity_of(field: &DataField, format: MarcFormat) -> Option<MainEntry> {
+        match format {
+            MarcFormat::Unimarc => match field.tag.as_str() {
+                "700" | "701" | "702" => Some(MainEntry::PersonalName),
+                "710" | "711" | "712" if field.ind1 == '1' => Some(MainEntry::MeetingName),
+                "710" | "711" | "712" => Some(MainEntry::CorporateName),
+                "720" | "721" | "722" => Some(MainEntry::FamilyName),
+                _ => None,
+            },
+            MarcFormat::Marc21 | MarcFormat::MarcXml => match field.tag.as_str() {
+                "100" | "700" if field.ind1 == '3' => Some(MainEntry::FamilyName),
+                "100" | "700" => Some(MainEntry::PersonalName),
+                "110" | "710" => Some(MainEntry::CorporateName),
+                "111" | "711" => Some(MainEntry::MeetingName),
+                _ => None,
+            },
+        }
+    }
+}
@@ -16,6 +16,15 @@ pub enum Series {
 }
 
 impl Series {
+    /// All variants, in declaration order
+    pub const ALL: &'static [Series] = &[
+        Series::SeriesPersonalName,
+        Series::SeriesCorporateName,
+        Series::SeriesMeetingName,
+        Series::SeriesTitle,
+        Series::SeriesStatement,
+    ];
+
     /// Get the tag as string for the given format
     pub fn tag(&self, format: MarcFormat) -> &'static str {
         match (self, format) {
@@ -0,0 +1,634 @@
+use crate::record::DataField;
+
+/// Subfield codes of the MARC21 title statement (245)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Title245Sub {
+    /// $a - Title
+    Title,
+    /// $b - Remainder of title
+    RemainderOfTitle,
+    /// $c - Statement of responsibility, etc.
+    StatementOfResponsibility,
+    /// $f - Inclusive dates
+    InclusiveDates,
+    /// $g - Bulk dates
+    BulkDates,
+    /// $h - Medium
+    Medium,
+    /// $k - Form
+    Form,
+    /// $n - Number of part/section of a work
+    NumberOfPart,
+    /// $p - Name of part/section of a work
+    NameOfPart,
+    /// $s - Version
+    Version,
+    /// $6 - Linkage
+    Linkage,
+    /// $8 - Field link and sequence number
+    FieldLink,
+}
+
+impl Title245Sub {
+    /// Get the subfield code
+    pub fn code(&self) -> char {
+        match self {
+            Title245Sub::Title => 'a',
+            Title245Sub::RemainderOfTitle => 'b',
+            Title245Sub::StatementOfResponsibility => 'c',
+            Title245Sub::InclusiveDates => 'f',
+            Title245Sub::BulkDates => 'g',
+            Title245Sub::Medium => 'h',
+            Title245Sub::Form => 'k',
+            Title245Sub::NumberOfPart => 'n',
+            Title245Sub::NameOfPart => 'p',
+            Title245Sub::Version => 's',
+            Title245Sub::Linkage => '6',
+            Title245Sub::FieldLink => '8',
+        }
+    }
+
+    /// Get the variant for a subfield code
+    pub fn from_code(code: char) -> Option<Self> {
+        match code {
+            'a' => Some(Title245Sub::Title),
+            'b' => Some(Title245Sub::RemainderOfTitle),
+            'c' => Some(Title245Sub::StatementOfResponsibility),
+            'f' => Some(Title245Sub::InclusiveDates),
+            'g' => Some(Title245Sub::BulkDates),
+            'h' => Some(Title245Sub::Medium),
+            'k' => Some(Title245Sub::Form),
+            'n' => Some(Title245Sub::NumberOfPart),
+            'p' => Some(Title245Sub::NameOfPart),
+            's' => Some(Title245Sub::Version),
+            '6' => Some(Title245Sub::Linkage),
+            '8' => Some(Title245Sub::FieldLink),
+            _ => None,
+        }
+    }
+}
+
+impl From<Title245Sub> for char {
+    fn from(code: Title245Sub) -> Self {
+        code.code()
+    }
+}
+
+/// Subfield codes of MARC21 personal name fields (100, 600, 700, 800)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Name100Sub {
+    /// $a - Personal name
+    PersonalName,
+    /// $b - Numeration
+    Numeration,
+    /// $c - Titles and other words associated with a name
+    TitlesAssociated,
+    /// $d - Dates associated with a name
+    Dates,
+    /// $e - Relator term
+    RelatorTerm,
+    /// $q - Fuller form of name
+    FullerForm,
+    /// $t - Title of a work
+    TitleOfWork,
+    /// $u - Affiliation
+    Affiliation,
+    /// $0 - Authority record control number or standard number
+    AuthorityRecordControlNumber,
+    /// $1 - Real World Object URI
+    RealWorldObjectUri,
+    /// $4 - Relationship code
+    RelatorCode,
+    /// $6 - Linkage
+    Linkage,
+    /// $8 - Field link and sequence number
+    FieldLink,
+}
+
+impl Name100Sub {
+    /// Get the subfield code
+    pub fn code(&self) -> char {
+        match self {
+            Name100Sub::PersonalName => 'a',
+            Name100Sub::Numeration => 'b',
+            Name100Sub::TitlesAssociated => 'c',
+            Name100Sub::Dates => 'd',
+            Name100Sub::RelatorTerm => 'e',
+            Name100Sub::FullerForm => 'q',
+            Name100Sub::TitleOfWork => 't',
+            Name100Sub::Affiliation => 'u',
+            Name100Sub::AuthorityRecordControlNumber => '0',
+            Name100Sub::RealWorldObjectUri => '1',
+            Name100Sub::RelatorCode => '4',
+            Name100Sub::Linkage => '6',
+            Name100Sub::FieldLink => '8',
+        }
+    }
+
+    /// Get the variant for a subfield code
+    pub fn from_code(code: char) -> Option<Self> {
+        match code {
+            'a' => Some(Name100Sub::PersonalName),
+            'b' => Some(Name100Sub::Numeration),
+            'c' => Some(Name100Sub::TitlesAssociated),
+            'd' => Some(Name100Sub::Dates),
+            'e' => Some(Name100Sub::RelatorTerm),
+            'q' => Some(Name100Sub::FullerForm),
+            't' => Some(Name100Sub::TitleOfWork),
+            'u' => Some(Name100Sub::Affiliation),
+            '0' => Some(Name100Sub::AuthorityRecordControlNumber),
+            '1' => Some(Name100Sub::RealWorldObjectUri),
+            '4' => Some(Name100Sub::RelatorCode),
+            '6' => Some(Name100Sub::Linkage),
+            '8' => Some(Name100Sub::FieldLink),
+            _ => None,
+        }
+    }
+}
+
+impl From<Name100Sub> for char {
+    fn from(code: Name100Sub) -> Self {
+        code.code()
+    }
+}
+
+/// Subfield codes of MARC21 publication fields (260, 264)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Publication260Sub {
+    /// $a - Place of publication, distribution, etc.
+    Place,
+    /// $b - Name of publisher, distributor, etc.
+    Name,
+    /// $c - Date of publication, distribution, etc.
+    Date,
+    /// $e - Place of manufacture (260 only)
+    PlaceOfManufacture,
+    /// $f - Manufacturer (260 only)
+    Manufacturer,
+    /// $g - Date of manufacture (260 only)
+    DateOfManufacture,
+    /// $3 - Materials specified
+    MaterialsSpecified,
+    /// $6 - Linkage
+    Linkage,
+    /// $8 - Field link and sequence number
+    FieldLink,
+}
+
+impl Publication260Sub {
+    /// Get the subfield code
+    pub fn code(&self) -> char {
+        match self {
+            Publication260Sub::Place => 'a',
+            Publication260Sub::Name => 'b',
+            Publication260Sub::Date => 'c',
+            Publication260Sub::PlaceOfManufacture => 'e',
+            Publication260Sub::Manufacturer => 'f',
+            Publication260Sub::DateOfManufacture => 'g',
+            Publication260Sub::MaterialsSpecified => '3',
+            Publication260Sub::Linkage => '6',
+            Publication260Sub::FieldLink => '8',
+        }
+    }
+
+    /// Get the variant for a subfield code
+    pub fn from_code(code: char) -> Option<Self> {
+        match code {
+            'a' => Some(Publication260Sub::Place),
+            'b' => Some(Publication260Sub::Name),
+            'c' => Some(Publication260Sub::Date),
+            'e' => Some(Publication260Sub::PlaceOfManufacture),
+            'f' => Some(Publication260Sub::Manufacturer),
+            'g' => Some(Publication260Sub::DateOfManufacture),
+            '3' => Some(Publication260Sub::MaterialsSpecified),
+            '6' => Some(Publication260Sub::Linkage),
+            '8' => Some(Publication260Sub::FieldLink),
+            _ => None,
+        }
+    }
+}
+
+impl From<Publication260Sub> for char {
+    fn from(code: Publication260Sub) -> Self {
+        code.code()
+    }
+}
+
+/// Subfield codes of the MARC21 topical term subject field (650)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Subject650Sub {
+    /// $a - Topical term or geographic name entry element
+    TopicalTerm,
+    /// $b - Topical term following geographic name entry element
+    TopicalTermFollowingGeographicName,
+    /// $c - Location of event
+    LocationOfEvent,
+    /// $d - Active dates
+    ActiveDates,
+    /// $e - Relator term
+    RelatorTerm,
+    /// $v - Form subdivision
+    FormSubdivision,
+    /// $x - General subdivision
+    GeneralSubdivision,
+    /// $y - Chronological subdivision
+    ChronologicalSubdivision,
+    /// $z - Geographic subdivision
+    GeographicSubdivision,
+    /// $0 - Authority record control number or standard number
+    AuthorityRecordControlNumber,
+    /// $1 - Real World Object URI
+    RealWorldObjectUri,
+    /// $2 - Source of heading or term
+    SourceOfHeading,
+    /// $4 - Relationship code
+    RelatorCode,
+    /// $6 - Linkage
+    Linkage,
+    /// $8 - Field link and sequence number
+    FieldLink,
+}
+
+impl Subject650Sub {
+    /// Get the subfield code
+    pub fn code(&self) -> char {
+        match self {
+            Subject650Sub::TopicalTerm => 'a',
+            Subject650Sub::TopicalTermFollowingGeographicName => 'b',
+            Subject650Sub::LocationOfEvent => 'c',
+            Subject650Sub::ActiveDates => 'd',
+            Subject650Sub::RelatorTerm => 'e',
+            Subject650Sub::FormSubdivision => 'v',
+            Subject650Sub::GeneralSubdivision => 'x',
+            Subject650Sub::ChronologicalSubdivision => 'y',
+            Subject650Sub::GeographicSubdivision => 'z',
+            Subject650Sub::AuthorityRecordControlNumber => '0',
+            Subject650Sub::RealWorldObjectUri => '1',
+            Subject650Sub::SourceOfHeading => '2',
+            Subject650Sub::RelatorCode => '4',
+            Subject650Sub::Linkage => '6',
+            Subject650Sub::FieldLink => '8',
+        }
+    }
+
+    /// Get the variant for a subfield code
+    pub fn from_code(code: char) -> Option<Self> {
+        match code {
+            'a' => Some(Subject650Sub::TopicalTerm),
+            'b' => Some(Subject650Sub::TopicalTermFollowingGeographicName),
+            'c' => Some(Subject650Sub::LocationOfEvent),
+            'd' => Some(Subject650Sub::ActiveDates),
+            'e' => Some(Subject650Sub::RelatorTerm),
+            'v' => Some(Subject650Sub::FormSubdivision),
+            'x' => Some(Subject650Sub::GeneralSubdivision),
+            'y' => Some(Subject650Sub::ChronologicalSubdivision),
+            'z' => Some(Subject650Sub::GeographicSubdivision),
+            '0' => Some(Subject650Sub::AuthorityRecordControlNumber),
+            '1' => Some(Subject650Sub::RealWorldObjectUri),
+            '2' => Some(Subject650Sub::SourceOfHeading),
+            '4' => Some(Subject650Sub::RelatorCode),
+            '6' => Some(Subject650Sub::Linkage),
+            '8' => Some(Subject650Sub::FieldLink),
+            _ => None,
+        }
+    }
+}
+
+impl From<Subject650Sub> for char {
+    fn from(code: Subject650Sub) -> Self {
+        code.code()
+    }
+}
+
+/// Subfield codes of the electronic location and access field (856)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location856Sub {
+    /// $u - Uniform Resource Identifier
+    Uri,
+    /// $y - Link text
+    LinkText,
+    /// $z - Public note
+    PublicNote,
+    /// $x - Nonpublic note
+    NonpublicNote,
+    /// $q - Electronic format type
+    ElectronicFormatType,
+    /// $2 - Access method
+    AccessMethod,
+    /// $3 - Materials specified
+    MaterialsSpecified,
+    /// $6 - Linkage
+    Linkage,
+    /// $8 - Field link and sequence number
+    FieldLink,
+}
+
+impl Location856Sub {
+    /// Get the subfield code
+    pub fn code(&self) -> char {
+        match self {
+            Location856Sub::Uri => 'u',
+            Location856Sub::LinkText => 'y',
+            Location856Sub::PublicNote => 'z',
+            Location856Sub::NonpublicNote => 'x',
+            Location856Sub::ElectronicFormatType => 'q',
+            Location856Sub::AccessMethod => '2',
+            Location856Sub::MaterialsSpecified => '3',
+            Location856Sub::Linkage => '6',
+            Location856Sub::FieldLink => '8',
+        }
+    }
+
+    /// Get the variant for a subfield code
+    pub fn from_code(code: char) -> Option<Self> {
+        match code {
+            'u' => Some(Location856Sub::Uri),
+            'y' => Some(Location856Sub::LinkText),
+            'z' => Some(Location856Sub::PublicNote),
+            'x' => Some(Location856Sub::NonpublicNote),
+            'q' => Some(Location856Sub::ElectronicFormatType),
+            '2' => Some(Location856Sub::AccessMethod),
+            '3' => Some(Location856Sub::MaterialsSpecified),
+            '6' => Some(Location856Sub::Linkage),
+            '8' => Some(Location856Sub::FieldLink),
+            _ => None,
+        }
+    }
+}
+
+impl From<Location856Sub> for char {
+    fn from(code: Location856Sub) -> Self {
+        code.code()
+    }
+}
+
+/// Subfield codes of the UNIMARC title and statement of responsibility (200)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnimarcTitle200Sub {
+    /// $a - Title proper
+    TitleProper,
+    /// $b - General material designation
+    GeneralMaterialDesignation,
+    /// $c - Title proper by another author
+    TitleProperByAnotherAuthor,
+    /// $d - Parallel title proper
+    ParallelTitleProper,
+    /// $e - Other title information
+    OtherTitleInformation,
+    /// $f - First statement of responsibility
+    FirstStatementOfResponsibility,
+    /// $g - Subsequent statement of responsibility
+    SubsequentStatementOfResponsibility,
+    /// $h - Number of a part
+    NumberOfPart,
+    /// $i - Name of a part
+    NameOfPart,
+    /// $v - Volume designation
+    VolumeDesignation,
+    /// $z - Language of parallel title proper
+    LanguageOfParallelTitle,
+}
+
+impl UnimarcTitle200Sub {
+    /// Get the subfield code
+    pub fn code(&self) -> char {
+        match self {
+            UnimarcTitle200Sub::TitleProper => 'a',
+            UnimarcTitle200Sub::GeneralMaterialDesignation => 'b',
+            UnimarcTitle200Sub::TitleProperByAnotherAuthor => 'c',
+            UnimarcTitle200Sub::ParallelTitleProper => 'd',
+            UnimarcTitle200Sub::OtherTitleInformation => 'e',
+            UnimarcTitle200Sub::FirstStatementOfResponsibility => 'f',
+            UnimarcTitle200Sub::SubsequentStatementOfResponsibility => 'g',
+            UnimarcTitle200Sub::NumberOfPart => 'h',
+            UnimarcTitle200Sub::NameOfPart => 'i',
+            UnimarcTitle200Sub::VolumeDesignation => 'v',
+            UnimarcTitle200Sub::LanguageOfParallelTitle => 'z',
+        }
+    }
+
+    /// Get the variant for a subfield code
+    pub fn from_code(code: char) -> Option<Self> {
+        match code {
+            'a' => Some(UnimarcTitle200Sub::TitleProper),
+            'b' => Some(UnimarcTitle200Sub::GeneralMaterialDesignation),
+            'c' => Some(UnimarcTitle200Sub::TitleProperByAnotherAuthor),
+            'd' => Some(UnimarcTitle200Sub::ParallelTitleProper),
+            'e' => Some(UnimarcTitle200Sub::OtherTitleInformation),
+            'f' => Some(UnimarcTitle200Sub::FirstStatementOfResponsibility),
+            'g' => Some(UnimarcTitle200Sub::SubsequentStatementOfResponsibility),
+            'h' => Some(UnimarcTitle200Sub::NumberOfPart),
+            'i' => Some(UnimarcTitle200Sub::NameOfPart),
+            'v' => Some(UnimarcTitle200Sub::VolumeDesignation),
+            'z' => Some(UnimarcTitle200Sub::LanguageOfParallelTitle),
+            _ => None,
+        }
+    }
+}
+
+impl From<UnimarcTitle200Sub> for char {
+    fn from(code: UnimarcTitle200Sub) -> Self {
+        code.code()
+    }
+}
+
+/// Subfield codes of the UNIMARC publication, distribution, etc. field (210)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnimarcPublication210Sub {
+    /// $a - Place of publication, distribution, etc.
+    Place,
+    /// $b - Address of publisher, distributor, etc.
+    Address,
+    /// $c - Name of publisher, distributor, etc.
+    Name,
+    /// $d - Date of publication, distribution, etc.
+    Date,
+    /// $e - Place of manufacture
+    PlaceOfManufacture,
+    /// $f - Address of manufacturer
+    AddressOfManufacturer,
+    /// $g - Name of manufacturer
+    Manufacturer,
+    /// $h - Date of manufacture
+    DateOfManufacture,
+}
+
+impl UnimarcPublication210Sub {
+    /// Get the subfield code
+    pub fn code(&self) -> char {
+        match self {
+            UnimarcPublication210Sub::Place => 'a',
+            UnimarcPublication210Sub::Address => 'b',
+            UnimarcPublication210Sub::Name => 'c',
+            UnimarcPublication210Sub::Date => 'd',
+            UnimarcPublication210Sub::PlaceOfManufacture => 'e',
+            UnimarcPublication210Sub::AddressOfManufacturer => 'f',
+            UnimarcPublication210Sub::Manufacturer => 'g',
+            UnimarcPublication210Sub::DateOfManufacture => 'h',
+        }
+    }
+
+    /// Get the variant for a subfield code
+    pub fn from_code(code: char) -> Option<Self> {
+        match code {
+            'a' => Some(UnimarcPublication210Sub::Place),
+            'b' => Some(UnimarcPublication210Sub::Address),
+            'c' => Some(UnimarcPublication210Sub::Name),
+            'd' => Some(UnimarcPublication210Sub::Date),
+            'e' => Some(UnimarcPublication210Sub::PlaceOfManufacture),
+            'f' => Some(UnimarcPublication210Sub::AddressOfManufacturer),
+            'g' => Some(UnimarcPublication210Sub::Manufacturer),
+            'h' => Some(UnimarcPublication210Sub::DateOfManufacture),
+            _ => None,
+        }
+    }
+}
+
+impl From<UnimarcPublication210Sub> for char {
+    fn from(code: UnimarcPublication210Sub) -> Self {
+        code.code()
+    }
+}
+
+/// Subfield codes of the UNIMARC topical name used as subject field (606)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnimarcSubject606Sub {
+    /// $a - Entry element
+    EntryElement,
+    /// $j - Form subdivision
+    FormSubdivision,
+    /// $x - Topical subdivision
+    TopicalSubdivision,
+    /// $y - Geographical subdivision
+    GeographicalSubdivision,
+    /// $z - Chronological subdivision
+    ChronologicalSubdivision,
+    /// $2 - System code
+    SystemCode,
+    /// $3 - Authority record number
+    AuthorityRecordNumber,
+}
+
+impl UnimarcSubject606Sub {
+    /// Get the subfield code
+    pub fn code(&self) -> char {
+        match self {
+            UnimarcSubject606Sub::EntryElement => 'a',
+            UnimarcSubject606Sub::FormSubdivision => 'j',
+            UnimarcSubject606Sub::TopicalSubdivision => 'x',
+            UnimarcSubject606Sub::GeographicalSubdivision => 'y',
+            UnimarcSubject606Sub::ChronologicalSubdivision => 'z',
+            UnimarcSubject606Sub::SystemCode => '2',
+            UnimarcSubject606Sub::AuthorityRecordNumber => '3',
+        }
+    }
+
+    /// Get the variant for a subfield code
+    pub fn from_code(code: char) -> Option<Self> {
+        match code {
+            'a' => Some(UnimarcSubject606Sub::EntryElement),
+            'j' => Some(UnimarcSubject606Sub::FormSubdivision),
+            'x' => Some(UnimarcSubject606Sub::TopicalSubdivision),
+            'y' => Some(UnimarcSubject606Sub::GeographicalSubdivision),
+            'z' => Some(UnimarcSubject606Sub::ChronologicalSubdivision),
+            '2' => Some(UnimarcSubject606Sub::SystemCode),
+            '3' => Some(UnimarcSubject606Sub::AuthorityRecordNumber),
+            _ => None,
+        }
+    }
+}
+
+impl From<UnimarcSubject606Sub> for char {
+    fn from(code: UnimarcSubject606Sub) -> Self {
+        code.code()
+    }
+}
+
+/// Subfield codes of UNIMARC personal name responsibility fields (700, 701, 702)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnimarcName700Sub {
+    /// $a - Entry element
+    EntryElement,
+    /// $b - Part of name other than entry element
+    PartOfName,
+    /// $c - Additions to name other than dates
+    Additions,
+    /// $d - Roman numerals
+    RomanNumerals,
+    /// $f - Dates
+    Dates,
+    /// $g - Expansion of initials of forename
+    ExpansionOfInitials,
+    /// $p - Affiliation/address
+    Affiliation,
+    /// $3 - Authority record number
+    AuthorityRecordNumber,
+    /// $4 - Relator code
+    RelatorCode,
+}
+
+impl UnimarcName700Sub {
+    /// Get the subfield code
+    pub fn code(&self) -> char {
+        match self {
+            UnimarcName700Sub::EntryElement => 'a',
+            UnimarcName700Sub::PartOfName => 'b',
+            UnimarcName700Sub::Additions => 'c',
+            UnimarcName700Sub::RomanNumerals => 'd',
+            UnimarcName700Sub::Dates => 'f',
+            UnimarcName700Sub::ExpansionOfInitials => 'g',
+            UnimarcName700Sub::Affiliation => 'p',
+            UnimarcName700Sub::AuthorityRecordNumber => '3',
+            UnimarcName700Sub::RelatorCode => '4',
+        }
+    }
+
+    /// Get the variant for a subfield code
+    pub fn from_code(code: char) -> Option<Self> {
+        match code {
+            'a' => Some(UnimarcName700Sub::EntryElement),
+            'b' => Some(UnimarcName700Sub::PartOfName),
+            'c' => Some(UnimarcName700Sub::Additions),
+            'd' => Some(UnimarcName700Sub::RomanNumerals),
+            'f' => Some(UnimarcName700Sub::Dates),
+            'g' => Some(UnimarcName700Sub::ExpansionOfInitials),
+            'p' => Some(UnimarcName700Sub::Affiliation),
+            '3' => Some(UnimarcName700Sub::AuthorityRecordNumber),
+            '4' => Some(UnimarcName700Sub::RelatorCode),
+            _ => None,
+        }
+    }
+}
+
+impl From<UnimarcName700Sub> for char {
+    fn from(code: UnimarcName700Sub) -> Self {
+        code.code()
+    }
+}
+
+impl DataField {
+    /// Get the first value of a subfield, by code or subfield enum
+    ///
+    /// ```
+    /// use marc_rs::{DataField, Subfield, Title245Sub};
+    ///
+    /// let field = DataField {
+    ///     tag: "245".to_string(),
+    ///     ind1: '1',
+    ///     ind2: '0',
+    ///     subfields: vec![Subfield { code: 'a', value: "Dune".to_string() }],
+    /// };
+    /// assert_eq!(field.subfield(Title245Sub::Title), Some("Dune"));
+    /// assert_eq!(field.subfield('a'), Some("Dune"));
+    /// ```
+    pub fn subfield<C: Into<char>>(&self, code: C) -> Option<&str> {
+        let code = code.into();
+        self.subfields.iter().find(|s| s.code == code).map(|s| s.value.as_str())
+    }
+
+    /// Get all values of a subfield, by code or subfield enum
+    pub fn subfield_values<C: Into<char>>(&self, code: C) -> impl Iterator<Item = &str> {
+        let code = code.into();
+        self.subfields.iter().filter(move |s| s.code == code).map(|s| s.value.as_str())
+    }
+}
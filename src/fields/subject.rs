@@ -1,4 +1,5 @@
 use crate::format::MarcFormat;
+use crate::record::DataField;
 
 /// Subject access fields (6XX in MARC21, 6XX in UNIMARC with different structure)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -34,6 +35,24 @@ pub enum Subject {
 }
 
 impl Subject {
+    /// All variants, in declaration order
+    pub const ALL: &'static [Subject] = &[
+        Subject::SubjectPersonalName,
+        Subject::SubjectCorporateName,
+        Subject::SubjectMeetingName,
+        Subject::SubjectUniformTitle,
+        Subject::SubjectTopicalTerm,
+        Subject::SubjectGeographicName,
+        Subject::IndexTermUncontrolled,
+        Subject::SubjectFacetedTopicalTerms,
+        Subject::IndexTermGenreForm,
+        Subject::IndexTermOccupation,
+        Subject::IndexTermFunction,
+        Subject::IndexTermCurriculumObjective,
+        Subject::SubjectHierarchicalPlaceName,
+        Subject::SubjectTypeOfEntityUnspecified,
+    ];
+
     /// Get the tag as string for the given format
     pub fn tag(&self, format: MarcFormat) -> Option<&'static str> {
         match (self, format) {
@@ -74,3 +93,145 @@ impl Subject {
         }
     }
 }
+
+/// Thesaurus or vocabulary a subject heading comes from
+///
+/// Sourced from the MARC21 second indicator (values 0-6) or from `$2`
+/// (second indicator 7 in MARC21, always in UNIMARC).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SubjectSource {
+    /// Library of Congress Subject Headings (ind2 0, `lcsh`)
+    Lcsh,
+    /// LC subject headings for children's literature (ind2 1, `lcshac`)
+    LcChildrens,
+    /// Medical Subject Headings (ind2 2, `mesh`)
+    Mesh,
+    /// National Agricultural Library subject authority file (ind2 3, `nal`)
+    Nal,
+    /// Source not specified (ind2 4)
+    NotSpecified,
+    /// Canadian Subject Headings (ind2 5, `cash`)
+    Cash,
+    /// Répertoire de vedettes-matière (ind2 6, `rvm`)
+    Rvm,
+    /// Faceted Application of Subject Terminology (`fast`)
+    Fast,
+    /// Répertoire d'autorité-matière encyclopédique et alphabétique unifié (`rameau`)
+    Rameau,
+    /// Gemeinsame Normdatei (`gnd`)
+    Gnd,
+    /// Library of Congress Genre/Form Terms (`lcgft`)
+    Lcgft,
+    /// Art & Architecture Thesaurus (`aat`)
+    Aat,
+    /// BISAC Subject Headings (`bisacsh`)
+    Bisacsh,
+    /// Sears List of Subject Headings (`sears`)
+    Sears,
+    /// Thesaurus for Graphic Materials (`lctgm`)
+    Lctgm,
+    /// Any other `$2` source code, as recorded
+    Other(String),
+}
+
+impl SubjectSource {
+    /// Source code, as recorded in `$2` (empty for [`SubjectSource::NotSpecified`])
+    pub fn code(&self) -> &str {
+        match self {
+            SubjectSource::Lcsh => "lcsh",
+            SubjectSource::LcChildrens => "lcshac",
+            SubjectSource::Mesh => "mesh",
+            SubjectSource::Nal => "nal",
+            SubjectSource::NotSpecified => "",
+            SubjectSource::Cash => "cash",
+            SubjectSource::Rvm => "rvm",
+            SubjectSource::Fast => "fast",
+            SubjectSource::Rameau => "rameau",
+            SubjectSource::Gnd => "gnd",
+            SubjectSource::Lcgft => "lcgft",
+            SubjectSource::Aat => "aat",
+            SubjectSource::Bisacsh => "bisacsh",
+            SubjectSource::Sears => "sears",
+            SubjectSource::Lctgm => "lctgm",
+            SubjectSource::Other(code) => code,
+        }
+    }
+
+    /// Parse a `$2` source code (case-insensitive, surrounding punctuation ignored)
+    pub fn from_code(code: &str) -> Self {
+        let code = code.trim().trim_end_matches('.').to_lowercase();
+        match code.as_str() {
+            "lcsh" => SubjectSource::Lcsh,
+            "lcshac" => SubjectSource::LcChildrens,
+            "mesh" => SubjectSource::Mesh,
+            "nal" => SubjectSource::Nal,
+            "cash" => SubjectSource::Cash,
+            "rvm" => SubjectSource::Rvm,
+            "fast" => SubjectSource::Fast,
+            "rameau" => SubjectSource::Rameau,
+            "gnd" => SubjectSource::Gnd,
+            "lcgft" => SubjectSource::Lcgft,
+            "aat" => SubjectSource::Aat,
+            "bisacsh" => SubjectSource::Bisacsh,
+            "sears" => SubjectSource::Sears,
+            "lctgm" | "gmgpc" => SubjectSource::Lctgm,
+            _ => SubjectSource::Other(code),
+        }
+    }
+
+    /// Source implied by a MARC21 6XX second indicator (`7` means "see $2" and yields `None`)
+    pub fn from_indicator(ind2: char) -> Option<Self> {
+        match ind2 {
+            '0' => Some(SubjectSource::Lcsh),
+            '1' => Some(SubjectSource::LcChildrens),
+            '2' => Some(SubjectSource::Mesh),
+            '3' => Some(SubjectSource::Nal),
+            '4' => Some(SubjectSource::NotSpecified),
+            '5' => Some(SubjectSource::Cash),
+            '6' => Some(SubjectSource::Rvm),
+            _ => None,
+        }
+    }
+
+    /// MARC21 second indicator value for this source (`7` when it must go in `$2`)
+    pub fn indicator(&self) -> char {
+        match self {
+            SubjectSource::Lcsh => '0',
+            SubjectSource::LcChildrens => '1',
+            SubjectSource::Mesh => '2',
+            SubjectSource::Nal => '3',
+            SubjectSource::NotSpecified => '4',
+            SubjectSource::Cash => '5',
+            SubjectSource::Rvm => '6',
+            _ => '7',
+        }
+    }
+}
+
+/// MARC21 6XX tags whose second indicator is a thesaurus
+const MARC21_THESAURUS_TAGS: [&str; 9] = ["600", "610", "611", "630", "647", "648", "650", "651", "655"];
+
+impl Subject {
+    /// Vocabulary the subject field comes from, if it can be determined
+    ///
+    /// ```
+    /// use marc_rs::{DataField, MarcFormat, Subfield, Subject, SubjectSource};
+    ///
+    /// let field = DataField {
+    ///     tag: "650".to_string(),
+    ///     ind1: ' ',
+    ///     ind2: '7',
+    ///     subfields: vec![Subfield { code: 'a', value: "Cats".to_string() }, Subfield { code: '2', value: "fast".to_string() }],
+    /// };
+    /// assert_eq!(Subject::source(&field, MarcFormat::Marc21), Some(SubjectSource::Fast));
+    /// ```
+    pub fn source(field: &DataField, format: MarcFormat) -> Option<SubjectSource> {
+        let from_subfield = || field.subfields.iter().find(|s| s.code == '2').map(|s| SubjectSource::from_code(&s.value));
+        match format {
+            MarcFormat::Marc21 | MarcFormat::MarcXml if MARC21_THESAURUS_TAGS.contains(&field.tag.as_str()) => {
+                SubjectSource::from_indicator(field.ind2).or_else(from_subfield)
+            }
+            _ => from_subfield(),
+        }
+    }
+}
@@ -16,6 +16,15 @@ pub enum Title {
 }
 
 impl Title {
+    /// All variants, in declaration order
+    pub const ALL: &'static [Title] = &[
+        Title::TitleStatement,
+        Title::VaryingFormOfTitle,
+        Title::FormerTitle,
+        Title::ParallelTitle,
+        Title::OtherTitleInformation,
+    ];
+
     /// Get the tag as string for the given format
     pub fn tag(&self, format: MarcFormat) -> &'static str {
         match (self, format) {
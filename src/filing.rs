@@ -0,0 +1,79 @@
+use crate::fields::Title;
+use crate::format::MarcFormat;
+use crate::record::{DataField, Record};
+
+/// Non-sort begin/end markers used in UNIMARC (and MARC21 UTF-8) data
+///
+/// ISO 5426 encodes them as 0x88/0x89, which decode to U+0088/U+0089; UTF-8
+/// records use U+0098 (START OF STRING) and U+009C (STRING TERMINATOR).
+const NON_SORT_BEGIN: [char; 2] = ['\u{0088}', '\u{0098}'];
+const NON_SORT_END: [char; 2] = ['\u{0089}', '\u{009C}'];
+
+/// Subfields making up the filing form of a title field
+const TITLE_FILING_SUBFIELDS: [char; 4] = ['a', 'b', 'n', 'p'];
+
+/// Remove text enclosed in NSB/NSE markers, along with the markers themselves
+pub fn strip_non_sorting(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut skipping = false;
+
+    for ch in text.chars() {
+        if NON_SORT_BEGIN.contains(&ch) {
+            skipping = true;
+        } else if NON_SORT_END.contains(&ch) {
+            skipping = false;
+        } else if !skipping {
+            result.push(ch);
+        }
+    }
+
+    result
+}
+
+impl DataField {
+    /// Number of non-filing characters declared by the indicators, for MARC21 title fields
+    ///
+    /// Returns 0 for fields that carry no non-filing indicator or hold a non-digit value.
+    pub fn non_filing_characters(&self) -> usize {
+        let indicator = match self.tag.as_str() {
+            "130" | "630" | "730" | "740" => self.ind1,
+            "222" | "240" | "242" | "243" | "245" | "440" | "830" => self.ind2,
+            _ => return 0,
+        };
+        indicator.to_digit(10).unwrap_or(0) as usize
+    }
+
+    /// Filing form of the field: the given subfields joined, with non-filing characters
+    /// (indicator count and NSB/NSE spans) removed
+    pub fn filing_form(&self, codes: &[char]) -> String {
+        let text = self
+            .subfields
+            .iter()
+            .filter(|s| codes.contains(&s.code))
+            .map(|s| s.value.trim())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let skipped: String = text.chars().skip(self.non_filing_characters()).collect();
+        strip_non_sorting(&skipped).trim().to_string()
+    }
+}
+
+impl Record {
+    /// Title in filing form, for sorting
+    ///
+    /// Uses 245 in MARC21 (honouring the second indicator) and 200 in UNIMARC
+    /// (honouring NSB/NSE markers), falling back to the uniform title 240/500.
+    pub fn sort_title(&self, format: MarcFormat) -> Option<String> {
+        let uniform_title = match format {
+            MarcFormat::Marc21 | MarcFormat::MarcXml => "240",
+            MarcFormat::Unimarc => "500",
+        };
+
+        [Title::TitleStatement.tag(format), uniform_title]
+            .iter()
+            .filter_map(|tag| self.data_fields.iter().find(|f| f.tag == *tag))
+            .map(|f| f.filing_form(&TITLE_FILING_SUBFIELDS))
+            .find(|title| !title.is_empty())
+    }
+}
@@ -0,0 +1,508 @@
+use crate::record::{DataField, Leader, Record, Subfield};
+use std::fmt::Display;
+
+/// Type of publication date (UNIMARC 100$a/8)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PublicationDateType {
+    /// `a` - Continuing resource currently published
+    CurrentContinuingResource,
+    /// `b` - Continuing resource no longer published
+    DeadContinuingResource,
+    /// `c` - Continuing resource of unknown status
+    ContinuingResourceUnknownStatus,
+    /// `d` - Monograph complete when issued, or issued within one calendar year
+    Monograph,
+    /// `e` - Reproduction of a document
+    Reproduction,
+    /// `f` - Monograph, date of publication uncertain
+    UncertainDates,
+    /// `g` - Monograph whose publication continues for more than a year
+    MultiYearMonograph,
+    /// `h` - Monograph with both actual and copyright/privilege date
+    ActualAndCopyrightDates,
+    /// `i` - Monograph having both release/issue date and production date
+    ReleaseAndProductionDates,
+    /// `j` - Document with detailed date of publication
+    DetailedDate,
+    /// `k` - Monograph with both date of production and date of publication
+    ProductionAndPublicationDates,
+    /// `u` - Dates of publication unknown
+    Unknown,
+}
+
+impl PublicationDateType {
+    /// Get the type from its code
+    pub fn from_code(code: char) -> Option<Self> {
+        match code {
+            'a' => Some(PublicationDateType::CurrentContinuingResource),
+            'b' => Some(PublicationDateType::DeadContinuingResource),
+            'c' => Some(PublicationDateType::ContinuingResourceUnknownStatus),
+            'd' => Some(PublicationDateType::Monograph),
+            'e' => Some(PublicationDateType::Reproduction),
+            'f' => Some(PublicationDateType::UncertainDates),
+            'g' => Some(PublicationDateType::MultiYearMonograph),
+            'h' => Some(PublicationDateType::ActualAndCopyrightDates),
+            'i' => Some(PublicationDateType::ReleaseAndProductionDates),
+            'j' => Some(PublicationDateType::DetailedDate),
+            'k' => Some(PublicationDateType::ProductionAndPublicationDates),
+            'u' => Some(PublicationDateType::Unknown),
+            _ => None,
+        }
+    }
+
+    /// Get the code of this type
+    pub fn code(&self) -> char {
+        match self {
+            PublicationDateType::CurrentContinuingResource => 'a',
+            PublicationDateType::DeadContinuingResource => 'b',
+            PublicationDateType::ContinuingResourceUnknownStatus => 'c',
+            PublicationDateType::Monograph => 'd',
+            PublicationDateType::Reproduction => 'e',
+            PublicationDateType::UncertainDates => 'f',
+            PublicationDateType::MultiYearMonograph => 'g',
+            PublicationDateType::ActualAndCopyrightDates => 'h',
+            PublicationDateType::ReleaseAndProductionDates => 'i',
+            PublicationDateType::DetailedDate => 'j',
+            PublicationDateType::ProductionAndPublicationDates => 'k',
+            PublicationDateType::Unknown => 'u',
+        }
+    }
+}
+
+/// UNIMARC 100$a general processing data (36 characters)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeneralProcessingData {
+    /// 0-7 - Date entered on file (YYYYMMDD)
+    pub date_entered: String,
+    /// 8 - Type of publication date
+    pub publication_date_type: PublicationDateType,
+    /// 9-12 - Publication date 1
+    pub date1: String,
+    /// 13-16 - Publication date 2
+    pub date2: String,
+    /// 17-19 - Target audience codes
+    pub target_audience: String,
+    /// 20 - Government publication code
+    pub government_publication: char,
+    /// 21 - Modified record code
+    pub modified_record: char,
+    /// 22-24 - Language of cataloguing
+    pub cataloguing_language: String,
+    /// 25 - Transliteration code
+    pub transliteration: char,
+    /// 26-29 - Character sets
+    pub character_sets: String,
+    /// 30-33 - Additional character sets
+    pub additional_character_sets: String,
+    /// 34-35 - Script of title
+    pub script_of_title: String,
+}
+
+impl GeneralProcessingData {
+    /// Parse a 100$a value
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let chars: Vec<char> = value.chars().collect();
+        if chars.len() != 36 {
+            return Err(format!("UNIMARC 100$a must be 36 characters, got {}", chars.len()));
+        }
+        let slice = |start: usize, end: usize| chars[start..end].iter().collect::<String>();
+
+        let publication_date_type =
+            PublicationDateType::from_code(chars[8]).ok_or_else(|| format!("Invalid type of publication date at position 8: '{}'", chars[8]))?;
+
+        Ok(GeneralProcessingData {
+            date_entered: slice(0, 8),
+            publication_date_type,
+            date1: slice(9, 13),
+            date2: slice(13, 17),
+            target_audience: slice(17, 20),
+            government_publication: chars[20],
+            modified_record: chars[21],
+            cataloguing_language: slice(22, 25),
+            transliteration: chars[25],
+            character_sets: slice(26, 30),
+            additional_character_sets: slice(30, 34),
+            script_of_title: slice(34, 36),
+        })
+    }
+
+    /// Read the general processing data from a record's 100 field
+    pub fn from_record(record: &Record) -> Option<Result<Self, String>> {
+        record
+            .data_fields
+            .iter()
+            .find(|f| f.tag == "100")
+            .and_then(|f| f.subfields.iter().find(|s| s.code == 'a'))
+            .map(|s| Self::parse(&s.value))
+    }
+
+    /// Build a 100 field carrying this data in $a
+    pub fn to_field(&self) -> DataField {
+        DataField {
+            tag: "100".to_string(),
+            ind1: ' ',
+            ind2: ' ',
+            subfields: vec![Subfield {
+                code: 'a',
+                value: self.to_string(),
+            }],
+        }
+    }
+
+    /// Publication date 1 as a year, if it is fully numeric
+    pub fn date1_year(&self) -> Option<u16> {
+        parse_year(&self.date1)
+    }
+
+    /// Publication date 2 as a year, if it is fully numeric
+    pub fn date2_year(&self) -> Option<u16> {
+        parse_year(&self.date2)
+    }
+}
+
+impl Display for GeneralProcessingData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:<8.8}{}{:<4.4}{:<4.4}{:<3.3}{}{}{:<3.3}{}{:<4.4}{:<4.4}{:<2.2}",
+            self.date_entered,
+            self.publication_date_type.code(),
+            self.date1,
+            self.date2,
+            self.target_audience,
+            self.government_publication,
+            self.modified_record,
+            self.cataloguing_language,
+            self.transliteration,
+            self.character_sets,
+            self.additional_character_sets,
+            self.script_of_title,
+        )
+    }
+}
+
+/// Position range of a MARC21 008 data element
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedFieldElement {
+    /// First character position
+    pub start: usize,
+    /// Number of characters
+    pub length: usize,
+    pub name: &'static str,
+}
+
+const fn element(start: usize, length: usize, name: &'static str) -> FixedFieldElement {
+    FixedFieldElement { start, length, name }
+}
+
+/// 008 elements shared by all configurations (00-17 and 35-39)
+const COMMON_ELEMENTS: &[FixedFieldElement] = &[
+    element(0, 6, "Date entered on file"),
+    element(6, 1, "Type of date/Publication status"),
+    element(7, 4, "Date 1"),
+    element(11, 4, "Date 2"),
+    element(15, 3, "Place of publication, production, or execution"),
+    element(35, 3, "Language"),
+    element(38, 1, "Modified record"),
+    element(39, 1, "Cataloging source"),
+];
+
+const BOOKS_ELEMENTS: &[FixedFieldElement] = &[
+    element(18, 4, "Illustrations"),
+    element(22, 1, "Target audience"),
+    element(23, 1, "Form of item"),
+    element(24, 4, "Nature of contents"),
+    element(28, 1, "Government publication"),
+    element(29, 1, "Conference publication"),
+    element(30, 1, "Festschrift"),
+    element(31, 1, "Index"),
+    element(32, 1, "Undefined"),
+    element(33, 1, "Literary form"),
+    element(34, 1, "Biography"),
+];
+
+const CONTINUING_RESOURCES_ELEMENTS: &[FixedFieldElement] = &[
+    element(18, 1, "Frequency"),
+    element(19, 1, "Regularity"),
+    element(20, 1, "Undefined"),
+    element(21, 1, "Type of continuing resource"),
+    element(22, 1, "Form of original item"),
+    element(23, 1, "Form of item"),
+    element(24, 1, "Nature of entire work"),
+    element(25, 3, "Nature of contents"),
+    element(28, 1, "Government publication"),
+    element(29, 1, "Conference publication"),
+    element(30, 3, "Undefined"),
+    element(33, 1, "Original alphabet or script of title"),
+    element(34, 1, "Entry convention"),
+];
+
+const MAPS_ELEMENTS: &[FixedFieldElement] = &[
+    element(18, 4, "Relief"),
+    element(22, 2, "Projection"),
+    element(24, 1, "Undefined"),
+    element(25, 1, "Type of cartographic material"),
+    element(26, 2, "Undefined"),
+    element(28, 1, "Government publication"),
+    element(29, 1, "Form of item"),
+    element(30, 1, "Undefined"),
+    element(31, 1, "Index"),
+    element(32, 1, "Undefined"),
+    element(33, 2, "Special format characteristics"),
+];
+
+const MUSIC_ELEMENTS: &[FixedFieldElement] = &[
+    element(18, 2, "Form of composition"),
+    element(20, 1, "Format of music"),
+    element(21, 1, "Music parts"),
+    element(22, 1, "Target audience"),
+    element(23, 1, "Form of item"),
+    element(24, 6, "Accompanying matter"),
+    element(30, 2, "Literary text for sound recordings"),
+    element(32, 1, "Undefined"),
+    element(33, 1, "Transposition and arrangement"),
+    element(34, 1, "Undefined"),
+];
+
+const VISUAL_MATERIALS_ELEMENTS: &[FixedFieldElement] = &[
+    element(18, 3, "Running time for motion pictures and videorecordings"),
+    element(21, 1, "Undefined"),
+    element(22, 1, "Target audience"),
+    element(23, 5, "Undefined"),
+    element(28, 1, "Government publication"),
+    element(29, 1, "Form of item"),
+    element(30, 3, "Undefined"),
+    element(33, 1, "Type of visual material"),
+    element(34, 1, "Technique"),
+];
+
+const COMPUTER_FILES_ELEMENTS: &[FixedFieldElement] = &[
+    element(18, 4, "Undefined"),
+    element(22, 1, "Target audience"),
+    element(23, 1, "Form of item"),
+    element(24, 2, "Undefined"),
+    element(26, 1, "Type of computer file"),
+    element(27, 1, "Undefined"),
+    element(28, 1, "Government publication"),
+    element(29, 6, "Undefined"),
+];
+
+const MIXED_MATERIALS_ELEMENTS: &[FixedFieldElement] = &[element(18, 5, "Undefined"), element(23, 1, "Form of item"), element(24, 11, "Undefined")];
+
+/// Configuration of the MARC21 008/18-34 material-specific elements
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaterialConfiguration {
+    /// BK - Language material (a, t) with bibliographic level a, c, d or m
+    Books,
+    /// CR - Language material (a) with bibliographic level b, i or s
+    ContinuingResources,
+    /// MP - Cartographic material (e, f)
+    Maps,
+    /// MU - Music and sound recordings (c, d, i, j)
+    Music,
+    /// VM - Projected, two-dimensional and three-dimensional visual material, kits (g, k, o, r)
+    VisualMaterials,
+    /// MX - Mixed materials (p)
+    MixedMaterials,
+    /// CF - Computer files (m)
+    ComputerFiles,
+}
+
+impl MaterialConfiguration {
+    /// All configurations, in declaration order
+    pub const ALL: &'static [MaterialConfiguration] = &[
+        MaterialConfiguration::Books,
+        MaterialConfiguration::ContinuingResources,
+        MaterialConfiguration::Maps,
+        MaterialConfiguration::Music,
+        MaterialConfiguration::VisualMaterials,
+        MaterialConfiguration::MixedMaterials,
+        MaterialConfiguration::ComputerFiles,
+    ];
+
+    /// Configuration for a type of record (leader/06) and bibliographic level (leader/07)
+    ///
+    /// Returns `None` for combinations the format does not define, e.g. language
+    /// material with an undefined bibliographic level.
+    pub fn from_codes(record_type: char, bibliographic_level: char) -> Option<Self> {
+        match (record_type, bibliographic_level) {
+            ('a' | 't', 'a' | 'c' | 'd' | 'm') => Some(MaterialConfiguration::Books),
+            ('a', 'b' | 'i' | 's') => Some(MaterialConfiguration::ContinuingResources),
+            ('e' | 'f', _) => Some(MaterialConfiguration::Maps),
+            ('c' | 'd' | 'i' | 'j', _) => Some(MaterialConfiguration::Music),
+            ('g' | 'k' | 'o' | 'r', _) => Some(MaterialConfiguration::VisualMaterials),
+            ('p', _) => Some(MaterialConfiguration::MixedMaterials),
+            ('m', _) => Some(MaterialConfiguration::ComputerFiles),
+            _ => None,
+        }
+    }
+
+    /// Configuration for a record leader
+    pub fn from_leader(leader: &Leader) -> Option<Self> {
+        Self::from_codes(leader.record_type, leader.bibliographic_level)
+    }
+
+    /// Two-letter code used in the MARC21 documentation
+    pub fn code(&self) -> &'static str {
+        match self {
+            MaterialConfiguration::Books => "BK",
+            MaterialConfiguration::ContinuingResources => "CR",
+            MaterialConfiguration::Maps => "MP",
+            MaterialConfiguration::Music => "MU",
+            MaterialConfiguration::VisualMaterials => "VM",
+            MaterialConfiguration::MixedMaterials => "MX",
+            MaterialConfiguration::ComputerFiles => "CF",
+        }
+    }
+
+    /// Name of the configuration
+    pub fn name(&self) -> &'static str {
+        match self {
+            MaterialConfiguration::Books => "Books",
+            MaterialConfiguration::ContinuingResources => "Continuing resources",
+            MaterialConfiguration::Maps => "Maps",
+            MaterialConfiguration::Music => "Music",
+            MaterialConfiguration::VisualMaterials => "Visual materials",
+            MaterialConfiguration::MixedMaterials => "Mixed materials",
+            MaterialConfiguration::ComputerFiles => "Computer files",
+        }
+    }
+
+    /// Material-specific elements of 008/18-34, in position order
+    pub fn elements(&self) -> &'static [FixedFieldElement] {
+        match self {
+            MaterialConfiguration::Books => BOOKS_ELEMENTS,
+            MaterialConfiguration::ContinuingResources => CONTINUING_RESOURCES_ELEMENTS,
+            MaterialConfiguration::Maps => MAPS_ELEMENTS,
+            MaterialConfiguration::Music => MUSIC_ELEMENTS,
+            MaterialConfiguration::VisualMaterials => VISUAL_MATERIALS_ELEMENTS,
+            MaterialConfiguration::MixedMaterials => MIXED_MATERIALS_ELEMENTS,
+            MaterialConfiguration::ComputerFiles => COMPUTER_FILES_ELEMENTS,
+        }
+    }
+
+    /// All 008 elements of this configuration (common and material-specific), in position order
+    pub fn all_elements(&self) -> Vec<FixedFieldElement> {
+        let mut elements: Vec<FixedFieldElement> = COMMON_ELEMENTS.iter().chain(self.elements()).copied().collect();
+        elements.sort_by_key(|e| e.start);
+        elements
+    }
+
+    /// Position of the form of item code (23, or 29 for maps and visual materials)
+    pub fn form_of_item_position(&self) -> usize {
+        match self {
+            MaterialConfiguration::Maps | MaterialConfiguration::VisualMaterials => 29,
+            _ => 23,
+        }
+    }
+}
+
+impl Leader {
+    /// 008 configuration matching this leader's type of record and bibliographic level
+    pub fn material_configuration(&self) -> Option<MaterialConfiguration> {
+        MaterialConfiguration::from_leader(self)
+    }
+}
+
+/// MARC21 008 fixed-length data elements (40 characters)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixedLengthData {
+    /// Configuration of positions 18-34, from the leader
+    pub configuration: Option<MaterialConfiguration>,
+    /// 00-05 - Date entered on file (YYMMDD)
+    pub date_entered: String,
+    /// 06 - Type of date/Publication status
+    pub date_type: char,
+    /// 07-10 - Date 1
+    pub date1: String,
+    /// 11-14 - Date 2
+    pub date2: String,
+    /// 15-17 - Place of publication, production, or execution
+    pub place: String,
+    /// 18-34 - Material-specific elements
+    pub material_specific: String,
+    /// 35-37 - Language
+    pub language: String,
+    /// 38 - Modified record
+    pub modified_record: char,
+    /// 39 - Cataloging source
+    pub cataloging_source: char,
+}
+
+impl FixedLengthData {
+    /// Parse an 008 value, using the leader to select the configuration of positions 18-34
+    pub fn parse(value: &str, leader: &Leader) -> Result<Self, String> {
+        let chars: Vec<char> = value.chars().collect();
+        if chars.len() != 40 {
+            return Err(format!("MARC21 008 must be 40 characters, got {}", chars.len()));
+        }
+        let slice = |start: usize, end: usize| chars[start..end].iter().collect::<String>();
+
+        Ok(FixedLengthData {
+            configuration: MaterialConfiguration::from_leader(leader),
+            date_entered: slice(0, 6),
+            date_type: chars[6],
+            date1: slice(7, 11),
+            date2: slice(11, 15),
+            place: slice(15, 18),
+            material_specific: slice(18, 35),
+            language: slice(35, 38),
+            modified_record: chars[38],
+            cataloging_source: chars[39],
+        })
+    }
+
+    /// Read the fixed-length data from a record's 008 field
+    pub fn from_record(record: &Record) -> Option<Result<Self, String>> {
+        record.control_fields.iter().find(|f| f.tag == "008").map(|f| Self::parse(&f.value, &record.leader))
+    }
+
+    /// Value of a material-specific element, by its name in the configuration
+    pub fn element(&self, name: &str) -> Option<&str> {
+        let element = self.configuration?.elements().iter().find(|e| e.name == name)?;
+        let start = element.start - 18;
+        self.material_specific.get(start..start + element.length)
+    }
+
+    /// Form of item code, read at the position given by the configuration
+    pub fn form_of_item(&self) -> Option<char> {
+        let position = self.configuration?.form_of_item_position();
+        self.material_specific.chars().nth(position - 18)
+    }
+
+    /// Date 1 as a year, if it is fully numeric
+    pub fn date1_year(&self) -> Option<u16> {
+        parse_year(&self.date1)
+    }
+
+    /// Date 2 as a year, if it is fully numeric
+    pub fn date2_year(&self) -> Option<u16> {
+        parse_year(&self.date2)
+    }
+}
+
+impl Display for FixedLengthData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:<6.6}{}{:<4.4}{:<4.4}{:<3.3}{:<17.17}{:<3.3}{}{}",
+            self.date_entered,
+            self.date_type,
+            self.date1,
+            self.date2,
+            self.place,
+            self.material_specific,
+            self.language,
+            self.modified_record,
+            self.cataloging_source,
+        )
+    }
+}
+
+fn parse_year(value: &str) -> Option<u16> {
+    if value.len() == 4 && value.chars().all(|c| c.is_ascii_digit()) {
+        value.parse().ok()
+    } else {
+        None
+    }
+}
@@ -0,0 +1,240 @@
+use crate::record::Record;
+use crate::spec::MarcSpec;
+use crate::writer::WriteError;
+use std::collections::BTreeMap;
+use std::io::Write;
+
+/// Default separator of repeated values in flattened records
+pub const DEFAULT_FLAT_SEPARATOR: &str = " | ";
+
+/// One field of a record as a flat row, for tabular and columnar exports
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldRow {
+    /// Control number (001) of the record, if any
+    pub record_id: Option<String>,
+    pub tag: String,
+    /// 0-based occurrence of the tag in the record
+    pub occurrence: usize,
+    /// Indicators, `None` for control fields
+    pub ind1: Option<char>,
+    pub ind2: Option<char>,
+    /// Control field value, or subfields as `$aValue$bValue`
+    pub value: String,
+}
+
+impl Record {
+    /// One row per field: control fields first, then data fields
+    pub fn field_rows(&self) -> Vec<FieldRow> {
+        let record_id = self.control_fields.iter().find(|f| f.tag == "001").map(|f| f.value.clone());
+        let mut occurrences: BTreeMap<String, usize> = BTreeMap::new();
+        let mut next_occurrence = |tag: &str| {
+            let count = occurrences.entry(tag.to_string()).or_insert(0);
+            *count += 1;
+            *count - 1
+        };
+
+        let mut rows = Vec::with_capacity(self.control_fields.len() + self.data_fields.len());
+        for field in &self.control_fields {
+            rows.push(FieldRow {
+                record_id: record_id.clone(),
+                tag: field.tag.clone(),
+                occurrence: next_occurrence(&field.tag),
+                ind1: None,
+                ind2: None,
+                value: field.value.clone(),
+            });
+        }
+        for field in &self.data_fields {
+            rows.push(FieldRow {
+                record_id: record_id.clone(),
+                tag: field.tag.clone(),
+                occurrence: next_occurrence(&field.tag),
+                ind1: Some(field.ind1),
+                ind2: Some(field.ind2),
+                value: field.subfields.iter().map(|s| format!("${}{}", s.code, s.value)).collect(),
+            });
+        }
+        rows
+    }
+
+    /// One column per control field tag (`001`) and per subfield (`245$a`), plus `leader`
+    ///
+    /// Repeated values are joined with `separator`, in record order. Columns are
+    /// sorted by name.
+    pub fn flatten(&self, separator: &str) -> BTreeMap<String, String> {
+        let mut columns: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+        for field in &self.control_fields {
+            columns.entry(field.tag.clone()).or_default().push(&field.value);
+        }
+        for field in &self.data_fields {
+            for subfield in &field.subfields {
+                columns.entry(format!("{}${}", field.tag, subfield.code)).or_default().push(&subfield.value);
+            }
+        }
+
+        let mut flat: BTreeMap<String, String> = columns.into_iter().map(|(k, v)| (k, v.join(separator))).collect();
+        flat.insert("leader".to_string(), String::from_utf8_lossy(&self.leader.to_bytes()).into_owned());
+        flat
+    }
+}
+
+/// Writer of records as CSV, one row per record and one column per [`MarcSpec`]
+///
+/// The header row holds the specs. Repeated values are joined with the
+/// separator ([`DEFAULT_FLAT_SEPARATOR`] by default); cells are quoted when they
+/// contain the delimiter, a quote or a line break.
+///
+/// ```
+/// use marc_rs::{CsvWriter, MarcSpec};
+///
+/// let columns = vec![MarcSpec::parse("001").unwrap(), MarcSpec::parse("245$a").unwrap()];
+/// let writer = CsvWriter::new(Vec::new(), columns);
+/// assert_eq!(writer.finish().unwrap(), b"001,245$a\n");
+/// ```
+pub struct CsvWriter<W: Write> {
+    output: W,
+    columns: Vec<MarcSpec>,
+    delimiter: char,
+    separator: String,
+    count: usize,
+}
+
+impl<W: Write> CsvWriter<W> {
+    /// Create a comma-separated writer
+    pub fn new(output: W, columns: Vec<MarcSpec>) -> Self {
+        Self {
+            output,
+            columns,
+            delimiter: ',',
+            separator: DEFAULT_FLAT_SEPARATOR.to_string(),
+            count: 0,
+        }
+    }
+
+    /// Use another cell delimiter, e.g. `'\t'`
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Join repeated values with `separator`
+    pub fn separator(mut self, separator: &str) -> Self {
+        self.separator = separator.to_string();
+        self
+    }
+
+    /// Number of records written so far
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Write the row of one record, preceded by the header row for the first one
+    pub fn write(&mut self, record: &Record) -> Result<(), WriteError> {
+        if self.count == 0 {
+            self.write_header()?;
+        }
+        let row: Vec<String> = self.columns.iter().map(|spec| spec.values(record).join(&self.separator)).collect();
+        self.write_row(&row)?;
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Write the header row if no record was written, and flush the output
+    pub fn finish(mut self) -> Result<W, WriteError> {
+        if self.count == 0 {
+            self.write_header()?;
+        }
+        self.output.flush()?;
+        Ok(self.output)
+    }
+
+    fn write_header(&mut self) -> Result<(), WriteError> {
+        let header: Vec<String> = self.columns.iter().map(|spec| spec.to_string()).collect();
+        self.write_row(&header)
+    }
+
+    fn write_row(&mut self, cells: &[String]) -> Result<(), WriteError> {
+        let mut line = String::new();
+        for (i, cell) in cells.iter().enumerate() {
+            if i > 0 {
+                line.push(self.delimiter);
+            }
+            if cell.contains([self.delimiter, '"', '\n', '\r']) {
+                line.push('"');
+                line.push_str(&cell.replace('"', "\"\""));
+                line.push('"');
+            } else {
+                line.push_str(cell);
+            }
+        }
+        line.push('\n');
+        self.output.write_all(line.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// Wrapper serializing a record as a single flat map (see [`Record::flatten`])
+///
+/// Serializes any `T: Borrow<Record>`.
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Flat<T> {
+    pub record: T,
+    pub separator: String,
+}
+
+#[cfg(feature = "serde")]
+impl<T> Flat<T> {
+    /// Wrap a record, joining repeated values with [`DEFAULT_FLAT_SEPARATOR`]
+    pub fn new(record: T) -> Self {
+        Self {
+            record,
+            separator: DEFAULT_FLAT_SEPARATOR.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: std::borrow::Borrow<Record>> serde::Serialize for Flat<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_map(self.record.borrow().flatten(&self.separator))
+    }
+}
+
+/// Wrapper serializing a record as a sequence of [`FieldRow`]s
+///
+/// Serializes any `T: Borrow<Record>`.
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldRows<T>(pub T);
+
+#[cfg(feature = "serde")]
+impl<T: std::borrow::Borrow<Record>> serde::Serialize for FieldRows<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(self.0.borrow().field_rows())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for FieldRow {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("FieldRow", 6)?;
+        state.serialize_field("record_id", &self.record_id)?;
+        state.serialize_field("tag", &self.tag)?;
+        state.serialize_field("occurrence", &self.occurrence)?;
+        state.serialize_field("ind1", &self.ind1)?;
+        state.serialize_field("ind2", &self.ind2)?;
+        state.serialize_field("value", &self.value)?;
+        state.end()
+    }
+}
@@ -0,0 +1,221 @@
+//! Work and expression clustering, after the FRBR model.
+//!
+//! Records describe manifestations: a translation, a paperback reissue and an
+//! ebook of a novel each have their own record. [`cluster_works`] groups the
+//! records of the same work, and within a work those of the same expression
+//! (same content type and language), so discovery displays can show one entry
+//! per work with its editions.
+//!
+//! Records fall in the same work when they share a [work key](Record::work_key)
+//! or a work authority URI, or are linked by an identifier: a shared ISBN,
+//! ISSN or control number, or a linking entry (765, 767, 775, 776) whose `$w`
+//! is the control number of another record. Clustering is transitive.
+//!
+//! ```
+//! use marc_rs::{cluster_works, parse_mrk, MarcFormat};
+//!
+//! let records = parse_mrk(
+//!     "=LDR  00000nam a2200000 a 4500\n=008  000000s1954\\\\\\\\enk|||||||||||||||||eng\\d\n=100  1\\$aTolkien, J. R. R.\n=245  14$aThe fellowship of the ring /$cJ.R.R. Tolkien.\n\n\
+//!      =LDR  00000nam a2200000 a 4500\n=008  000000s1972\\\\\\\\fr\\|||||||||||||||||fre\\d\n=100  1\\$aTolkien, J. R. R.\n=240  10$aFellowship of the ring.$lFrench\n=245  13$aLa communauté de l'anneau.\n",
+//! )
+//! .unwrap();
+//! let clusters = cluster_works(&records, MarcFormat::Marc21);
+//! assert_eq!(clusters.works, vec![0, 0]);
+//! assert_eq!(clusters.expressions, vec![0, 1]);
+//! ```
+
+use crate::format::MarcFormat;
+use crate::identifiers::normalize_isbn;
+use crate::matching::match_text;
+use crate::record::{DataField, Record};
+use std::collections::HashMap;
+
+/// Subfields of a title that identify the work: title, part number and part name
+const WORK_TITLE_SUBFIELDS: [char; 3] = ['a', 'n', 'p'];
+
+/// Work and expression clusters of a list of records
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct WorkClusters {
+    /// Work cluster of each record, numbered from 0 in the order of their first record
+    pub works: Vec<usize>,
+    /// Expression cluster of each record, numbered from 0 in the order of their first record
+    pub expressions: Vec<usize>,
+}
+
+impl WorkClusters {
+    /// Number of works
+    pub fn work_count(&self) -> usize {
+        self.works.iter().max().map_or(0, |max| max + 1)
+    }
+
+    /// Number of expressions
+    pub fn expression_count(&self) -> usize {
+        self.expressions.iter().max().map_or(0, |max| max + 1)
+    }
+
+    /// Indexes of the records of a work
+    pub fn work_members(&self, work: usize) -> Vec<usize> {
+        (0..self.works.len()).filter(|&i| self.works[i] == work).collect()
+    }
+
+    /// Indexes of the records of an expression
+    pub fn expression_members(&self, expression: usize) -> Vec<usize> {
+        (0..self.expressions.len()).filter(|&i| self.expressions[i] == expression).collect()
+    }
+}
+
+impl Record {
+    /// Work key `title/name` of the record, `None` without a title
+    ///
+    /// The title is the uniform title when there is one (130/240 in MARC21, 500
+    /// in UNIMARC), the title proper (245/200) otherwise, with its part number
+    /// and name but no subtitle. The name is the `$a` of the main entry, or of
+    /// the first added entry for records entered under title; a 130 uniform
+    /// title stands alone. Both are reduced by [`match_text`].
+    pub fn work_key(&self, format: MarcFormat) -> Option<String> {
+        let (uniform_tags, title_tag, main_tags, added_tags): (&[&str], _, &[&str], &[&str]) = match format {
+            MarcFormat::Marc21 | MarcFormat::MarcXml => (&["130", "240"], "245", &["100", "110", "111"], &["700", "710", "711"]),
+            MarcFormat::Unimarc => (&["500"], "200", &["700", "710", "720"], &["701", "702", "711", "712"]),
+        };
+        let field = |tags: &[&str]| self.data_fields.iter().find(|f| tags.contains(&f.tag.as_str()));
+        let title_field = field(uniform_tags).filter(|f| !work_title(f).is_empty()).or_else(|| field(&[title_tag]))?;
+        let title = work_title(title_field);
+        if title.is_empty() {
+            return None;
+        }
+        let name = match title_field.tag.as_str() {
+            "130" => None,
+            _ => field(main_tags).or_else(|| field(added_tags)),
+        };
+        let name = name.and_then(|f| f.subfields.iter().find(|s| s.code == 'a')).map(|s| match_text(&s.value)).unwrap_or_default();
+        Some(format!("{}/{}", title, name))
+    }
+}
+
+/// Title of a field reduced to its work-identifying subfields, without non-filing characters
+fn work_title(field: &DataField) -> String {
+    match_text(&field.filing_form(&WORK_TITLE_SUBFIELDS))
+}
+
+/// Keys a record shares with the records of its expression, and with those of its work only
+fn link_keys(record: &Record, format: MarcFormat) -> (Vec<String>, Vec<String>) {
+    let (mut expression, mut work) = (Vec::new(), Vec::new());
+    let control_number = |tag: &str| record.control_fields.iter().find(|f| f.tag == tag).map(|f| f.value.trim().to_string()).filter(|v| !v.is_empty());
+    if let Some(number) = control_number("001") {
+        expression.push(format!("control {}", number));
+        if let Some(organization) = control_number("003") {
+            expression.push(format!("control ({}){}", organization, number));
+        }
+    }
+
+    let (isbn_tag, issn_tag) = match format {
+        MarcFormat::Marc21 | MarcFormat::MarcXml => ("020", "022"),
+        MarcFormat::Unimarc => ("010", "011"),
+    };
+    for field in &record.data_fields {
+        let values = |code: char| field.subfields.iter().filter(move |s| s.code == code).map(|s| s.value.trim());
+        match field.tag.as_str() {
+            tag if tag == isbn_tag => expression.extend(values('a').filter_map(normalize_isbn).map(|isbn| format!("isbn {}", isbn))),
+            tag if tag == issn_tag => expression.extend(values('a').map(|issn| format!("issn {}", issn.to_uppercase()))),
+            "035" if format != MarcFormat::Unimarc => expression.extend(values('a').map(|number| format!("control {}", compact(number)))),
+            "776" if format != MarcFormat::Unimarc => expression.extend(values('w').map(|number| format!("control {}", compact(number)))),
+            "765" | "767" | "775" if format != MarcFormat::Unimarc => work.extend(values('w').map(|number| format!("control {}", compact(number)))),
+            "130" | "240" if format != MarcFormat::Unimarc => work.extend(values('1').chain(values('0')).map(|uri| format!("authority {}", uri))),
+            "500" if format == MarcFormat::Unimarc => work.extend(values('3').map(|id| format!("authority {}", id))),
+            _ => {}
+        }
+    }
+    (expression, work)
+}
+
+/// Control number without spaces, e.g. `(DLC)2001012345` for `(DLC)   2001012345`
+fn compact(number: &str) -> String {
+    number.split_whitespace().collect()
+}
+
+/// Content type and language of an expression: leader/06 and the language code
+fn expression_facets(record: &Record, format: MarcFormat) -> (char, String) {
+    let language = match format {
+        MarcFormat::Marc21 | MarcFormat::MarcXml => record.control_fields.iter().find(|f| f.tag == "008").and_then(|f| f.value.get(35..38)).map(str::to_string),
+        MarcFormat::Unimarc => record.data_fields.iter().find(|f| f.tag == "101").and_then(|f| f.subfields.iter().find(|s| s.code == 'a')).map(|s| s.value.clone()),
+    };
+    (record.leader.record_type, language.unwrap_or_default().trim().to_lowercase())
+}
+
+/// Groups of records sharing keys, transitively
+struct Forest {
+    parents: Vec<usize>,
+}
+
+impl Forest {
+    fn new(size: usize) -> Self {
+        Self { parents: (0..size).collect() }
+    }
+
+    fn root(&mut self, mut i: usize) -> usize {
+        while self.parents[i] != i {
+            self.parents[i] = self.parents[self.parents[i]];
+            i = self.parents[i];
+        }
+        i
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.root(a), self.root(b));
+        // The earlier record represents the group
+        self.parents[a.max(b)] = a.min(b);
+    }
+
+    /// Join the records sharing a key
+    fn join_keys<'a>(&mut self, keys: impl Iterator<Item = (usize, &'a String)>) {
+        let mut first: HashMap<&str, usize> = HashMap::new();
+        for (i, key) in keys {
+            match first.get(key.as_str()) {
+                Some(&j) => self.union(i, j),
+                None => {
+                    first.insert(key, i);
+                }
+            }
+        }
+    }
+
+    /// Cluster number of each record, numbered in the order of their first record
+    fn numbers(&mut self) -> Vec<usize> {
+        let mut numbers = HashMap::new();
+        (0..self.parents.len())
+            .map(|i| {
+                let root = self.root(i);
+                let next = numbers.len();
+                *numbers.entry(root).or_insert(next)
+            })
+            .collect()
+    }
+}
+
+/// Group records into works, and works into expressions
+///
+/// Records of the same work with the same content type (leader/06) and
+/// language (008/35-37, or 101 `$a` in UNIMARC) are one expression, as are
+/// records linked by an ISBN, ISSN, control number or 776 link, which describe
+/// the same content. Records without a title and links form clusters of their own.
+pub fn cluster_works(records: &[Record], format: MarcFormat) -> WorkClusters {
+    let links: Vec<(Vec<String>, Vec<String>)> = records.iter().map(|record| link_keys(record, format)).collect();
+    let work_keys: Vec<Option<String>> = records.iter().map(|record| record.work_key(format).map(|key| format!("work {}", key))).collect();
+
+    let mut works = Forest::new(records.len());
+    works.join_keys(links.iter().enumerate().flat_map(|(i, (expression, work))| expression.iter().chain(work).chain(&work_keys[i]).map(move |key| (i, key))));
+
+    let mut expressions = Forest::new(records.len());
+    let facets: Vec<String> = (0..records.len())
+        .map(|i| {
+            let (content, language) = expression_facets(&records[i], format);
+            format!("expression {}/{}/{}", works.root(i), content, language)
+        })
+        .collect();
+    expressions.join_keys(links.iter().enumerate().flat_map(|(i, (expression, _))| expression.iter().chain(std::iter::once(&facets[i])).map(move |key| (i, key))));
+
+    WorkClusters {
+        works: works.numbers(),
+        expressions: expressions.numbers(),
+    }
+}
@@ -0,0 +1,99 @@
+use crate::record::Record;
+
+/// MARC geographic area code (as found in 043 $a)
+///
+/// Codes are seven characters long and hierarchical: a continent or group letter,
+/// an optional region letter, a two-letter country, then either a region letter
+/// within the country or a two-letter first-order subdivision, padded with
+/// hyphens (e.g. `n-us-ny`, `n-usn--`, `nwcu---`, `e------`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GeographicAreaCode {
+    pub code: String,
+}
+
+impl GeographicAreaCode {
+    /// Parse a code, restoring trailing hyphens that are sometimes dropped (`e-fr` becomes `e-fr---`)
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        if value.is_empty() || value.len() > 7 || !value.chars().all(|c| c.is_ascii_lowercase() || c == '-') {
+            return Err(format!("Invalid geographic area code: {:?}", value));
+        }
+        let code = format!("{:-<7}", value);
+        let bytes = code.as_bytes();
+        let pair_ok = |a: u8, b: u8| (a == b'-') == (b == b'-');
+        // A region letter within a country excludes a subdivision
+        let region_ok = bytes[4] == b'-' || (bytes[3] != b'-' && bytes[5] == b'-');
+        if bytes[0] == b'-' || !region_ok || !pair_ok(bytes[5], bytes[6]) || (bytes[3] == b'-' && bytes[5] != b'-') {
+            return Err(format!("Invalid geographic area code: {:?}", value));
+        }
+        Ok(Self { code })
+    }
+
+    /// Continent or group level code (e.g. `n------`)
+    pub fn continent(&self) -> String {
+        format!("{:-<7}", &self.code[..1])
+    }
+
+    /// Region level code (e.g. `nw-----`), when the code carries a region letter
+    pub fn region(&self) -> Option<String> {
+        (self.code.as_bytes()[1] != b'-').then(|| format!("{:-<7}", &self.code[..2]))
+    }
+
+    /// Country level code (e.g. `n-us---`), when the code names a country
+    pub fn country(&self) -> Option<String> {
+        let bytes = self.code.as_bytes();
+        (bytes[2] != b'-' && bytes[3] != b'-').then(|| format!("{:-<7}", &self.code[..4]))
+    }
+
+    /// First-order subdivision code (the code itself), when present
+    pub fn subdivision(&self) -> Option<String> {
+        (self.code.as_bytes()[5] != b'-').then(|| self.code.clone())
+    }
+
+    /// Codes from the broadest to the most specific level, ending with the code itself
+    pub fn hierarchy(&self) -> Vec<String> {
+        let mut levels = vec![self.continent()];
+        levels.extend(self.region());
+        levels.extend(self.country());
+        levels.extend(self.subdivision());
+        if levels.last() != Some(&self.code) {
+            levels.push(self.code.clone());
+        }
+        levels
+    }
+
+    /// Name of the area, if the code is in the bundled list
+    pub fn name(&self) -> Option<&'static str> {
+        geographic_area_name(&self.code)
+    }
+}
+
+impl std::fmt::Display for GeographicAreaCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.code)
+    }
+}
+
+/// Name of a geographic area code from the bundled list
+///
+/// The list is the Library of Congress MARC Code List for Geographic Areas,
+/// without its obsolete codes; it is generated at build time from `data/gacs.xml`.
+pub fn geographic_area_name(code: &str) -> Option<&'static str> {
+    let code = format!("{:-<7}", code.trim());
+    GEOGRAPHIC_AREAS.binary_search_by(|(c, _)| (*c).cmp(code.as_str())).ok().map(|i| GEOGRAPHIC_AREAS[i].1)
+}
+
+impl Record {
+    /// Geographic area codes from 043 $a; invalid values are skipped
+    pub fn geographic_areas(&self) -> Vec<GeographicAreaCode> {
+        self.data_fields
+            .iter()
+            .filter(|f| f.tag == "043")
+            .flat_map(|f| f.subfields.iter().filter(|s| s.code == 'a'))
+            .filter_map(|s| GeographicAreaCode::parse(&s.value).ok())
+            .collect()
+    }
+}
+
+// MARC code list for geographic areas (code, name), ordered by code
+include!(concat!(env!("OUT_DIR"), "/geographic_areas.rs"));
@@ -1,5 +1,6 @@
 use crate::format::FormatEncoding;
 use crate::parser::{parse, ParseError};
+use crate::reader::RecordReader;
 use crate::record::Record;
 use crate::writer::{write, WriteError};
 use std::io::{Read, Write};
@@ -26,29 +27,116 @@ pub fn from_str_many(data: &str, format_encoding: FormatEncoding) -> Result<Vec<
 }
 
 /// Deserialize a single MARC record from a reader
-pub fn from_reader<R: Read>(mut reader: R, format_encoding: FormatEncoding) -> Result<Record, ParseError> {
-    let mut buffer = Vec::new();
-    reader.read_to_end(&mut buffer).map_err(|e| ParseError::Other(format!("IO error: {}", e)))?;
-    from_slice(&buffer, format_encoding)
+///
+/// Only the bytes of the first record are consumed (see [`RecordReader`]), so
+/// the rest of the input stays unread.
+pub fn from_reader<R: Read>(reader: R, format_encoding: FormatEncoding) -> Result<Record, ParseError> {
+    iter_from_reader(reader, format_encoding)
+        .next()
+        .unwrap_or_else(|| Err(ParseError::Other("No record found in data".to_string())))
 }
 
 /// Deserialize MARC records from a reader
-pub fn from_reader_many<R: Read>(mut reader: R, format_encoding: FormatEncoding) -> Result<Vec<Record>, ParseError> {
-    let mut buffer = Vec::new();
-    reader.read_to_end(&mut buffer).map_err(|e| ParseError::Other(format!("IO error: {}", e)))?;
-    from_slice_many(&buffer, format_encoding)
+pub fn from_reader_many<R: Read>(reader: R, format_encoding: FormatEncoding) -> Result<Vec<Record>, ParseError> {
+    iter_from_reader(reader, format_encoding).collect()
+}
+
+/// Deserialize MARC records lazily from a reader, one record at a time
+pub fn iter_from_reader<R: Read>(reader: R, format_encoding: FormatEncoding) -> RecordReader<R> {
+    RecordReader::new(reader, format_encoding)
+}
+
+/// Deserialize MARC-in-JSON records lazily from JSON Lines input, one record per line
+#[cfg(feature = "serde")]
+pub fn from_jsonl_reader<R: std::io::BufRead>(reader: R) -> crate::json::JsonLinesReader<R> {
+    crate::json::JsonLinesReader::new(reader)
+}
+
+/// Deserialize MARC-in-JSON records lazily from a JSON array, or from records written one after another
+#[cfg(feature = "serde")]
+pub fn from_json_reader<R: std::io::BufRead>(reader: R) -> crate::json::JsonReader<R> {
+    crate::json::JsonReader::new(reader)
+}
+
+/// Deserialize a struct from a record, reading each struct field from a [`MarcSpec`](crate::MarcSpec)
+///
+/// `mapping` pairs struct field names with spec strings. A field is read from the
+/// first selected value (numbers are parsed from it), `Option` fields are `None`
+/// when nothing is selected and sequence fields take every selected value.
+///
+/// ```
+/// use marc_rs::{serde_marc, DataField, Leader, Record, Subfield};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Book {
+///     title: String,
+///     isbn: Vec<String>,
+///     year: Option<u16>,
+/// }
+///
+/// let field = |tag: &str, code: char, value: &str| DataField {
+///     tag: tag.to_string(),
+///     ind1: ' ',
+///     ind2: ' ',
+///     subfields: vec![Subfield { code, value: value.to_string() }],
+/// };
+/// let record = Record {
+///     leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+///     control_fields: vec![],
+///     data_fields: vec![field("020", 'a', "9780441013593"), field("245", 'a', "Dune")],
+/// };
+///
+/// let book: Book = serde_marc::extract(&record, &[("title", "245$a"), ("isbn", "020$a"), ("year", "264$c")]).unwrap();
+/// assert_eq!(book.title, "Dune");
+/// assert_eq!(book.isbn, vec!["9780441013593"]);
+/// assert_eq!(book.year, None);
+/// ```
+#[cfg(feature = "serde")]
+pub fn extract<T: serde::de::DeserializeOwned>(record: &Record, mapping: &[(&str, &str)]) -> Result<T, String> {
+    crate::extract::extract(record, mapping)
+}
+
+/// JSON Schema of the default `Serialize` output of [`Record`]
+///
+/// Use [`SerdeOptions::json_schema`](crate::SerdeOptions::json_schema) for
+/// other shapes.
+#[cfg(feature = "serde")]
+pub fn json_schema() -> serde_json::Value {
+    crate::json::SerdeOptions::default().json_schema()
 }
 
 /// Serialize a single MARC record to a writer
 pub fn to_writer<W: Write>(record: &Record, format_encoding: FormatEncoding, writer: &mut W) -> Result<(), WriteError> {
-    write(&[record.clone()], format_encoding, writer)
+    write(std::iter::once(record), format_encoding, writer)
 }
 
 /// Serialize multiple MARC records to a writer
-pub fn to_writer_many<W: Write>(records: &[Record], format_encoding: FormatEncoding, writer: &mut W) -> Result<(), WriteError> {
+///
+/// Accepts any iterator of record references, e.g. a slice or records borrowed
+/// from a larger structure.
+pub fn to_writer_many<'a, W, I>(records: I, format_encoding: FormatEncoding, writer: &mut W) -> Result<(), WriteError>
+where
+    W: Write,
+    I: IntoIterator<Item = &'a Record>,
+{
     write(records, format_encoding, writer)
 }
 
+/// Serialize records as JSON Lines: one MARC-in-JSON record per line
+#[cfg(feature = "serde")]
+pub fn to_jsonl_writer<'a, W, I>(records: I, writer: &mut W) -> Result<(), WriteError>
+where
+    W: Write,
+    I: IntoIterator<Item = &'a Record>,
+{
+    for record in records {
+        serde_json::to_writer(&mut *writer, &crate::json::MarcJson(record))?;
+        writer.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
 /// Serialize a single MARC record to a byte vector
 pub fn to_vec(record: &Record, format_encoding: FormatEncoding) -> Result<Vec<u8>, WriteError> {
     let mut buffer = Vec::new();
@@ -84,3 +172,62 @@ pub fn to_record(record: &Record, format_encoding: FormatEncoding) -> Result<Vec
 pub fn to_records(records: &[Record], format_encoding: FormatEncoding) -> Result<Vec<u8>, WriteError> {
     to_vec_many(records, format_encoding)
 }
+
+/// Serialize an error as `{"kind", "message", "display"}`, preceded by
+/// `"record_index"` and `"offset"` when a location is given
+#[cfg(feature = "serde")]
+pub(crate) fn serialize_error<S: serde::Serializer>(
+    location: Option<(Option<usize>, Option<u64>)>,
+    kind: &str,
+    message: Option<&str>,
+    display: &str,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    use serde::ser::SerializeMap;
+    let mut map = serializer.serialize_map(None)?;
+    if let Some((record_index, offset)) = location {
+        map.serialize_entry("record_index", &record_index)?;
+        map.serialize_entry("offset", &offset)?;
+    }
+    map.serialize_entry("kind", kind)?;
+    map.serialize_entry("message", &message)?;
+    map.serialize_entry("display", display)?;
+    map.end()
+}
+
+/// Read the `kind` and `message` of an error serialized by [`serialize_error`]
+#[cfg(feature = "serde")]
+pub(crate) fn deserialize_error<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<(String, Option<String>), D::Error> {
+    use serde::de::{self, MapAccess, Visitor};
+    use std::fmt;
+
+    struct ErrorVisitor;
+
+    impl<'de> Visitor<'de> for ErrorVisitor {
+        type Value = (String, Option<String>);
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("an error object with kind and message")
+        }
+
+        fn visit_map<V>(self, mut map: V) -> Result<Self::Value, V::Error>
+        where
+            V: MapAccess<'de>,
+        {
+            let mut kind = None;
+            let mut message = None;
+            while let Some(key) = map.next_key::<String>()? {
+                match key.as_str() {
+                    "kind" => kind = Some(map.next_value()?),
+                    "message" => message = map.next_value()?,
+                    _ => {
+                        let _ = map.next_value::<de::IgnoredAny>()?;
+                    }
+                }
+            }
+            Ok((kind.ok_or_else(|| de::Error::missing_field("kind"))?, message))
+        }
+    }
+
+    deserializer.deserialize_map(ErrorVisitor)
+}
@@ -0,0 +1,422 @@
+//! Standard identifiers: ISBN, ISSN and LCCN.
+//!
+//! [`Isbn`] validates check digits, converts between ISBN-10 and ISBN-13 and
+//! reads 020 values with their qualifiers. Hyphenation needs the ranges
+//! published by the International ISBN Agency as `RangeMessage.xml`, which
+//! change as agencies allocate new blocks; load them with [`IsbnRanges::parse`].
+//!
+//! [`Issn`] validates check digits, and [`Lccn`] brings Library of Congress
+//! control numbers to their normalized form, e.g. `n79021164` for `n 79-21164`.
+//!
+//! ```
+//! use marc_rs::Isbn;
+//!
+//! let (isbn, qualifier) = Isbn::extract("0-618-26030-7 (pbk.)").unwrap();
+//! assert_eq!(isbn.as_str(), "9780618260300");
+//! assert_eq!(isbn.isbn10().unwrap(), "0618260307");
+//! assert_eq!(qualifier.as_deref(), Some("pbk."));
+//! assert!(Isbn::parse("0-618-26030-8").is_err());
+//! ```
+
+use crate::format::MarcFormat;
+use crate::parser::ParseError;
+use crate::record::Record;
+
+/// Error reading a standard identifier
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IdentifierError {
+    /// Wrong number of digits
+    InvalidLength(usize),
+    /// A character that is not a digit, a hyphen or a final `X`
+    InvalidCharacter(char),
+    /// The check digit does not match the other digits
+    InvalidCheckDigit { expected: char, found: char },
+    /// An ISBN-13 not starting with 978 or 979, or an LCCN prefix that is not 1 to 3 letters
+    InvalidPrefix(String),
+}
+
+impl std::fmt::Display for IdentifierError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IdentifierError::InvalidLength(length) => write!(f, "Invalid length: {} digits", length),
+            IdentifierError::InvalidCharacter(c) => write!(f, "Invalid character: {:?}", c),
+            IdentifierError::InvalidCheckDigit { expected, found } => write!(f, "Invalid check digit: expected {}, found {}", expected, found),
+            IdentifierError::InvalidPrefix(prefix) => write!(f, "Invalid prefix: {}", prefix),
+        }
+    }
+}
+
+impl std::error::Error for IdentifierError {}
+
+/// An ISBN, kept as its 13 digits
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Isbn {
+    digits: String,
+}
+
+impl Isbn {
+    /// Read an ISBN-10 or ISBN-13, hyphens allowed, and check its check digit
+    pub fn parse(value: &str) -> Result<Self, IdentifierError> {
+        let value: String = value.trim().chars().filter(|c| *c != '-').collect::<String>().to_uppercase();
+        if let Some(c) = value.chars().enumerate().find(|&(i, c)| !(c.is_ascii_digit() || c == 'X' && i == 9 && value.len() == 10)).map(|(_, c)| c) {
+            return Err(IdentifierError::InvalidCharacter(c));
+        }
+        let (body, expected) = match value.len() {
+            10 => (&value[..9], check_digit10(&value[..9])),
+            13 if value.starts_with("978") || value.starts_with("979") => (&value[..12], check_digit13(&value[..12])),
+            13 => return Err(IdentifierError::InvalidPrefix(value[..3].to_string())),
+            length => return Err(IdentifierError::InvalidLength(length)),
+        };
+        let found = value.chars().last().expect("ISBNs have digits");
+        if found != expected {
+            return Err(IdentifierError::InvalidCheckDigit { expected, found });
+        }
+        let digits = match value.len() {
+            10 => format!("978{}{}", body, check_digit13(&format!("978{}", body))),
+            _ => value,
+        };
+        Ok(Self { digits })
+    }
+
+    /// Read the ISBN at the start of an 020 `$a`, with the qualifier that may follow it
+    ///
+    /// `0-618-26030-7 (pbk. : alk. paper)` gives the ISBN and `pbk. : alk. paper`.
+    pub fn extract(value: &str) -> Result<(Self, Option<String>), IdentifierError> {
+        let value = value.trim();
+        let (isbn, rest) = value.split_once(char::is_whitespace).unwrap_or((value, ""));
+        let qualifier = rest.trim().trim_start_matches(':').trim().trim_start_matches('(').trim_end_matches(')').trim();
+        Ok((Self::parse(isbn)?, Some(qualifier.to_string()).filter(|q| !q.is_empty())))
+    }
+
+    /// The 13 digits
+    pub fn as_str(&self) -> &str {
+        &self.digits
+    }
+
+    /// ISBN-10 form, for ISBNs starting with 978
+    pub fn isbn10(&self) -> Option<String> {
+        let body = self.digits.strip_prefix("978")?.get(..9)?;
+        Some(format!("{}{}", body, check_digit10(body)))
+    }
+
+    /// Hyphenated ISBN-13, e.g. `978-0-618-26030-0`; `None` when the ranges do not cover it
+    pub fn hyphenated(&self, ranges: &IsbnRanges) -> Option<String> {
+        let (group, registrant, publication) = ranges.split(&self.digits)?;
+        Some(format!("{}-{}-{}-{}-{}", &self.digits[..3], group, registrant, publication, &self.digits[12..]))
+    }
+
+    /// Hyphenated ISBN-10, e.g. `0-618-26030-7`, for ISBNs starting with 978
+    pub fn hyphenated10(&self, ranges: &IsbnRanges) -> Option<String> {
+        let isbn10 = self.isbn10()?;
+        let (group, registrant, publication) = ranges.split(&self.digits)?;
+        Some(format!("{}-{}-{}-{}", group, registrant, publication, &isbn10[9..]))
+    }
+}
+
+impl std::fmt::Display for Isbn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.digits)
+    }
+}
+
+impl std::str::FromStr for Isbn {
+    type Err = IdentifierError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+fn check_digit10(body: &str) -> char {
+    let sum: u32 = body.bytes().enumerate().map(|(i, b)| (b - b'0') as u32 * (10 - i as u32)).sum();
+    match (11 - sum % 11) % 11 {
+        10 => 'X',
+        digit => char::from_digit(digit, 10).expect("digit"),
+    }
+}
+
+fn check_digit13(body: &str) -> char {
+    let sum: u32 = body.bytes().enumerate().map(|(i, b)| (b - b'0') as u32 * if i % 2 == 0 { 1 } else { 3 }).sum();
+    char::from_digit((10 - sum % 10) % 10, 10).expect("digit")
+}
+
+/// ISBN-13 digits of the ISBN at the start of a value, without checking its check digit
+///
+/// For matching records, where an ISBN with a wrong check digit still
+/// identifies the same book: `2-07-036822-X (pbk.)` gives `9782070368228`.
+/// Values with non-ASCII characters are not ISBNs.
+pub fn normalize_isbn(value: &str) -> Option<String> {
+    let isbn: String = value.split_whitespace().next()?.chars().filter(|c| *c != '-').collect::<String>().to_uppercase();
+    if !isbn.is_ascii() {
+        return None;
+    }
+    match isbn.len() {
+        13 if isbn.bytes().all(|b| b.is_ascii_digit()) => Some(isbn),
+        10 if isbn[..9].bytes().all(|b| b.is_ascii_digit()) => {
+            let body = format!("978{}", &isbn[..9]);
+            Some(format!("{}{}", body, check_digit13(&body)))
+        }
+        _ => None,
+    }
+}
+
+/// Rules of one prefix: ranges of the next seven digits, and the length of the element they start
+#[derive(Debug, Clone)]
+struct RangeRules {
+    /// Prefix digits, without hyphens, e.g. `978` or `9780`
+    prefix: String,
+    rules: Vec<(u32, u32, usize)>,
+}
+
+impl RangeRules {
+    /// Length of the element after the prefix in `digits`
+    fn length(&self, digits: &str) -> Option<usize> {
+        let next: u32 = digits.get(self.prefix.len()..self.prefix.len() + 7)?.parse().ok()?;
+        self.rules.iter().find(|(low, high, _)| (*low..=*high).contains(&next)).map(|(_, _, length)| *length).filter(|length| *length > 0)
+    }
+}
+
+/// ISBN ranges of the International ISBN Agency, to hyphenate ISBNs
+#[derive(Debug, Clone, Default)]
+pub struct IsbnRanges {
+    prefixes: Vec<RangeRules>,
+    groups: Vec<RangeRules>,
+}
+
+impl IsbnRanges {
+    /// Read `RangeMessage.xml`, as downloaded from <https://www.isbn-international.org/range_file_generation>
+    pub fn parse(xml: &str) -> Result<Self, ParseError> {
+        use quick_xml::events::Event;
+        use quick_xml::Reader;
+
+        let mut reader = Reader::from_str(xml);
+        reader.trim_text(true);
+        let mut ranges = IsbnRanges::default();
+        let mut path: Vec<String> = Vec::new();
+        let mut current: Option<RangeRules> = None;
+        let mut range: Option<(u32, u32)> = None;
+
+        loop {
+            match reader.read_event() {
+                Ok(Event::Start(e)) => {
+                    let name = String::from_utf8_lossy(e.local_name().as_ref()).into_owned();
+                    if name == "EAN.UCC" || name == "Group" {
+                        current = Some(RangeRules { prefix: String::new(), rules: Vec::new() });
+                    }
+                    path.push(name);
+                }
+                Ok(Event::Text(e)) => {
+                    let text = e.unescape().map_err(|e| ParseError::InvalidXml(e.to_string()))?;
+                    let text = text.trim();
+                    let (Some(rules), Some(element)) = (current.as_mut(), path.last()) else { continue };
+                    match element.as_str() {
+                        "Prefix" => rules.prefix = text.replace('-', ""),
+                        "Range" => {
+                            let (low, high) = text.split_once('-').ok_or_else(|| ParseError::InvalidXml(format!("invalid range {}", text)))?;
+                            let number = |n: &str| n.parse::<u32>().map_err(|_| ParseError::InvalidXml(format!("invalid range {}", text)));
+                            range = Some((number(low)?, number(high)?));
+                        }
+                        "Length" => {
+                            let length = text.parse().map_err(|_| ParseError::InvalidXml(format!("invalid length {}", text)))?;
+                            if let Some((low, high)) = range.take() {
+                                rules.rules.push((low, high, length));
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                Ok(Event::End(_)) => {
+                    match path.pop().as_deref() {
+                        Some("EAN.UCC") => ranges.prefixes.extend(current.take()),
+                        Some("Group") => ranges.groups.extend(current.take()),
+                        _ => {}
+                    }
+                }
+                Ok(Event::Eof) => break,
+                Err(e) => return Err(ParseError::InvalidXml(e.to_string())),
+                _ => {}
+            }
+        }
+        if ranges.prefixes.is_empty() {
+            return Err(ParseError::InvalidXml("no EAN.UCC prefix ranges".to_string()));
+        }
+        Ok(ranges)
+    }
+
+    /// Registration group, registrant and publication elements of an ISBN-13
+    fn split<'a>(&self, digits: &'a str) -> Option<(&'a str, &'a str, &'a str)> {
+        let prefix = self.prefixes.iter().find(|rules| digits.starts_with(&rules.prefix))?;
+        let group_length = prefix.length(digits)?;
+        let group_end = 3 + group_length;
+        let group = self.groups.iter().find(|rules| rules.prefix == digits[..group_end])?;
+        let registrant_end = group_end + group.length(digits)?;
+        if registrant_end >= 12 {
+            return None;
+        }
+        Some((&digits[3..group_end], &digits[group_end..registrant_end], &digits[registrant_end..12]))
+    }
+}
+
+/// An ISSN, kept as its 8 characters
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Issn {
+    digits: String,
+}
+
+impl Issn {
+    /// Read an ISSN, with or without its hyphen, and check its check digit
+    pub fn parse(value: &str) -> Result<Self, IdentifierError> {
+        let value: String = value.trim().chars().filter(|c| *c != '-').collect::<String>().to_uppercase();
+        if let Some(c) = value.chars().enumerate().find(|&(i, c)| !(c.is_ascii_digit() || c == 'X' && i == 7)).map(|(_, c)| c) {
+            return Err(IdentifierError::InvalidCharacter(c));
+        }
+        if value.len() != 8 {
+            return Err(IdentifierError::InvalidLength(value.len()));
+        }
+        let sum: u32 = value[..7].bytes().enumerate().map(|(i, b)| (b - b'0') as u32 * (8 - i as u32)).sum();
+        let expected = match (11 - sum % 11) % 11 {
+            10 => 'X',
+            digit => char::from_digit(digit, 10).expect("digit"),
+        };
+        let found = value.chars().last().expect("ISSNs have digits");
+        if found != expected {
+            return Err(IdentifierError::InvalidCheckDigit { expected, found });
+        }
+        Ok(Self { digits: value })
+    }
+
+    /// The 8 characters, without hyphen
+    pub fn as_str(&self) -> &str {
+        &self.digits
+    }
+}
+
+impl std::fmt::Display for Issn {
+    /// Hyphenated form, e.g. `0028-0836`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", &self.digits[..4], &self.digits[4..])
+    }
+}
+
+impl std::str::FromStr for Issn {
+    type Err = IdentifierError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// A Library of Congress control number, kept in its normalized form
+///
+/// Normalization follows the LCCN namespace rules: blanks are removed, as is
+/// anything from a `/`, and the serial number after a hyphen is left-filled
+/// with zeros to 6 digits. The result is an optional lowercase prefix followed
+/// by a 2-digit year and 6-digit serial number (3-letter prefix at most), or a
+/// 4-digit year and serial number (2-letter prefix at most) since 2001.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Lccn {
+    normalized: String,
+}
+
+impl Lccn {
+    /// Read an LCCN as found in 010 `$a` or written by hand, e.g. `   85153773 ` or `2001-1114`
+    pub fn parse(value: &str) -> Result<Self, IdentifierError> {
+        let value: String = value.chars().filter(|c| !c.is_whitespace()).collect();
+        let value = value.split('/').next().unwrap_or_default();
+        let normalized = match value.split_once('-') {
+            Some((year, serial)) if serial.len() <= 6 && serial.bytes().all(|b| b.is_ascii_digit()) => format!("{}{:0>6}", year, serial),
+            Some((_, serial)) => return Err(serial.chars().find(|c| !c.is_ascii_digit()).map_or(IdentifierError::InvalidLength(serial.len()), IdentifierError::InvalidCharacter)),
+            None => value.to_string(),
+        }
+        .to_lowercase();
+        let digits_start = normalized.find(|c: char| c.is_ascii_digit()).unwrap_or(normalized.len());
+        let (prefix, digits) = normalized.split_at(digits_start);
+        if let Some(c) = prefix.chars().find(|c| !c.is_ascii_lowercase()).or_else(|| digits.chars().find(|c| !c.is_ascii_digit())) {
+            return Err(IdentifierError::InvalidCharacter(c));
+        }
+        let max_prefix = match digits.len() {
+            8 => 3,
+            10 => 2,
+            length => return Err(IdentifierError::InvalidLength(length)),
+        };
+        if prefix.len() > max_prefix {
+            return Err(IdentifierError::InvalidPrefix(prefix.to_string()));
+        }
+        Ok(Self { normalized })
+    }
+
+    /// Normalized form, e.g. `n79021164` or `2001001114`
+    pub fn as_str(&self) -> &str {
+        &self.normalized
+    }
+
+    /// Fixed-length form of 010 `$a`: the prefix left-justified in 3 characters,
+    /// 8 digits and a blank, or in 2 characters followed by 10 digits
+    pub fn to_marc(&self) -> String {
+        let digits_start = self.normalized.find(|c: char| c.is_ascii_digit()).unwrap_or_default();
+        let (prefix, digits) = self.normalized.split_at(digits_start);
+        match digits.len() {
+            8 => format!("{:<3}{} ", prefix, digits),
+            _ => format!("{:<2}{}", prefix, digits),
+        }
+    }
+}
+
+impl std::fmt::Display for Lccn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.normalized)
+    }
+}
+
+impl std::str::FromStr for Lccn {
+    type Err = IdentifierError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl Record {
+    /// Valid ISBNs of the record, from 020 `$a` (010 `$a` in UNIMARC)
+    ///
+    /// Invalid and cancelled ISBNs (`$z`) are left out.
+    pub fn isbns(&self, format: MarcFormat) -> Vec<Isbn> {
+        let tag = match format {
+            MarcFormat::Marc21 | MarcFormat::MarcXml => "020",
+            MarcFormat::Unimarc => "010",
+        };
+        self.data_fields
+            .iter()
+            .filter(|f| f.tag == tag)
+            .flat_map(|f| f.subfields.iter().filter(|s| s.code == 'a'))
+            .filter_map(|s| Isbn::extract(&s.value).ok().map(|(isbn, _)| isbn))
+            .collect()
+    }
+    /// Valid ISSNs of the record, from 022 `$a` (011 `$a` in UNIMARC)
+    ///
+    /// Incorrect and cancelled ISSNs (`$y`, `$z`) and ISSN-L (`$l`) are left out.
+    pub fn issns(&self, format: MarcFormat) -> Vec<Issn> {
+        let tag = match format {
+            MarcFormat::Marc21 | MarcFormat::MarcXml => "022",
+            MarcFormat::Unimarc => "011",
+        };
+        self.data_fields
+            .iter()
+            .filter(|f| f.tag == tag)
+            .flat_map(|f| f.subfields.iter().filter(|s| s.code == 'a'))
+            .filter_map(|s| Issn::parse(&s.value).ok())
+            .collect()
+    }
+
+    /// LCCN of the record, from 010 `$a`; always `None` in UNIMARC, which has no LCCN field
+    pub fn lccn(&self, format: MarcFormat) -> Option<Lccn> {
+        if format == MarcFormat::Unimarc {
+            return None;
+        }
+        self.data_fields
+            .iter()
+            .filter(|f| f.tag == "010")
+            .flat_map(|f| f.subfields.iter().filter(|s| s.code == 'a'))
+            .find_map(|s| Lccn::parse(&s.value).ok())
+    }
+}
@@ -0,0 +1,216 @@
+//! Compact records for large resident collections.
+//!
+//! A [`CompactRecord`] stores tags as three bytes and values as shared
+//! `Arc<str>` taken from an [`Interner`], so a value repeated across records,
+//! such as a publisher name or a subject heading, is held once. Compare to
+//! [`Record`], which owns a `String` per tag and value: keep `Record` for
+//! editing, and compact records when millions of them stay in memory, e.g. for
+//! matching or deduplication. Equality checks first compare the shared pointers.
+//! `cargo bench --bench intern` measures both on the sample files.
+//!
+//! ```
+//! use marc_rs::{parse_mrk, Interner};
+//!
+//! let records = parse_mrk("=LDR  00000nam a2200000 a 4500\n=260  \\\\$aLondon :$bAllen & Unwin\n\n=LDR  00000nam a2200000 a 4500\n=260  \\\\$aLondon :$bAllen & Unwin\n").unwrap();
+//! let mut interner = Interner::new();
+//! let compact: Vec<_> = records.iter().map(|r| interner.compact(r).unwrap()).collect();
+//! assert_eq!(interner.len(), 2);
+//! assert_eq!(compact[0], compact[1]);
+//! assert_eq!(compact[1].to_record(), records[1]);
+//! ```
+
+use crate::record::{ControlField, DataField, Leader, Record, Subfield};
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::sync::Arc;
+
+/// Values longer than this, in bytes, are not interned by default: they seldom repeat
+const DEFAULT_MAX_LEN: usize = 128;
+
+/// Field tag stored inline, e.g. `245`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Tag([u8; 3]);
+
+impl Tag {
+    /// Get a tag from three ASCII characters
+    pub fn new(tag: &str) -> Option<Self> {
+        match tag.as_bytes() {
+            &[a, b, c] if tag.is_ascii() => Some(Tag([a, b, c])),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        std::str::from_utf8(&self.0).expect("tags are ASCII")
+    }
+
+    /// True for the tags of control fields, below 010
+    pub fn is_control(&self) -> bool {
+        self.0 < *b"010"
+    }
+}
+
+impl Display for Tag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl PartialEq<str> for Tag {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other.as_bytes()
+    }
+}
+
+impl PartialEq<&str> for Tag {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == other.as_bytes()
+    }
+}
+
+/// Shared string values, compared by pointer first
+#[derive(Debug, Clone, Eq, PartialOrd, Ord)]
+pub struct InternedStr(Arc<str>);
+
+impl InternedStr {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq for InternedStr {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0) || self.0 == other.0
+    }
+}
+
+impl std::hash::Hash for InternedStr {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+impl Display for InternedStr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Control field of a [`CompactRecord`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CompactControlField {
+    pub tag: Tag,
+    pub value: InternedStr,
+}
+
+/// Data field of a [`CompactRecord`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CompactDataField {
+    pub tag: Tag,
+    pub ind1: char,
+    pub ind2: char,
+    pub subfields: Box<[(char, InternedStr)]>,
+}
+
+/// Record with inline tags and shared values, see [`Interner::compact`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CompactRecord {
+    pub leader: Leader,
+    pub control_fields: Box<[CompactControlField]>,
+    pub data_fields: Box<[CompactDataField]>,
+}
+
+impl CompactRecord {
+    /// The record, with its own strings
+    pub fn to_record(&self) -> Record {
+        Record {
+            leader: self.leader.clone(),
+            control_fields: self.control_fields.iter().map(|f| ControlField { tag: f.tag.to_string(), value: f.value.to_string() }).collect(),
+            data_fields: self
+                .data_fields
+                .iter()
+                .map(|f| DataField {
+                    tag: f.tag.to_string(),
+                    ind1: f.ind1,
+                    ind2: f.ind2,
+                    subfields: f.subfields.iter().map(|(code, value)| Subfield { code: *code, value: value.to_string() }).collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Pool of shared values, for compacting records
+#[derive(Debug, Clone)]
+pub struct Interner {
+    values: HashSet<Arc<str>>,
+    max_len: usize,
+}
+
+impl Default for Interner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Interner {
+    /// An interner sharing values of up to 128 bytes
+    pub fn new() -> Self {
+        Self::with_max_len(DEFAULT_MAX_LEN)
+    }
+
+    /// An interner sharing values of up to `max_len` bytes; longer values get their own allocation
+    pub fn with_max_len(max_len: usize) -> Self {
+        Self { values: HashSet::new(), max_len }
+    }
+
+    /// Shared copy of a value
+    pub fn intern(&mut self, value: &str) -> InternedStr {
+        if value.len() > self.max_len {
+            return InternedStr(Arc::from(value));
+        }
+        if let Some(shared) = self.values.get(value) {
+            return InternedStr(Arc::clone(shared));
+        }
+        let shared: Arc<str> = Arc::from(value);
+        self.values.insert(Arc::clone(&shared));
+        InternedStr(shared)
+    }
+
+    /// Number of distinct values held
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Total length of the distinct values held, in bytes
+    pub fn bytes(&self) -> usize {
+        self.values.iter().map(|value| value.len()).sum()
+    }
+
+    /// Compact a record; fails on a tag that is not three ASCII characters
+    pub fn compact(&mut self, record: &Record) -> Result<CompactRecord, String> {
+        let tag = |tag: &str| Tag::new(tag).ok_or_else(|| format!("Invalid tag {:?}", tag));
+        let control_fields = record.control_fields.iter().map(|f| Ok(CompactControlField { tag: tag(&f.tag)?, value: self.intern(&f.value) })).collect::<Result<_, String>>()?;
+        let data_fields = record
+            .data_fields
+            .iter()
+            .map(|f| {
+                Ok(CompactDataField {
+                    tag: tag(&f.tag)?,
+                    ind1: f.ind1,
+                    ind2: f.ind2,
+                    subfields: f.subfields.iter().map(|s| (s.code, self.intern(&s.value))).collect(),
+                })
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(CompactRecord {
+            leader: record.leader.clone(),
+            control_fields,
+            data_fields,
+        })
+    }
+}
@@ -0,0 +1,1141 @@
+//! MARC-in-JSON representation of records.
+//!
+//! The default `Serialize`/`Deserialize` implementations of [`Record`] mirror the
+//! Rust structs. Wrapping a record in [`MarcJson`] selects the standard
+//! MARC-in-JSON shape instead, as produced and consumed by other MARC tools:
+//!
+//! ```json
+//! {
+//!   "leader": "00000nam a2200000 a 4500",
+//!   "fields": [
+//!     { "001": "12345" },
+//!     { "245": { "ind1": "1", "ind2": "0", "subfields": [ { "a": "Title" } ] } }
+//!   ]
+//! }
+//! ```
+//!
+//! Deserialization also accepts the variants found in harvested data: indicators
+//! as `indicator1`/`indicator2` or a two-character `indicators` string (blank when
+//! missing), and subfields as `{ "code": "a", "value": ... }` objects,
+//! `["a", ...]` pairs or a single object keyed by code.
+//!
+//! Control fields are written before data fields. [`SerdeOptions`] gives finer
+//! control over the shape, for both representations.
+//!
+//! [`JsonLinesReader`] and `serde_marc::to_jsonl_writer` handle JSON Lines
+//! streams of MARC-in-JSON records, one per line. [`JsonReader`] reads a JSON
+//! array of records, or pretty-printed records one after another.
+
+use crate::parser::ParseError;
+use crate::record::{ControlField, DataField, Leader, Record, Subfield};
+use serde::de::{self, Deserialize, Deserializer, MapAccess, Visitor};
+use serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
+use std::borrow::{Borrow, Cow};
+use std::fmt;
+
+/// Wrapper selecting the MARC-in-JSON representation of a record
+///
+/// Serializes any `T: Borrow<Record>` (a `Record` or a `&Record`) and
+/// deserializes into `MarcJson<Record>`.
+///
+/// ```
+/// use marc_rs::{MarcJson, Record};
+///
+/// let json = r#"{"leader":"00000nam a2200000 a 4500","fields":[{"001":"12345"},{"245":{"ind1":"1","ind2":"0","subfields":[{"a":"Title"}]}}]}"#;
+/// let MarcJson(record): MarcJson<Record> = serde_json::from_str(json).unwrap();
+/// assert_eq!(record.data_fields[0].subfields[0].value, "Title");
+/// assert_eq!(serde_json::to_string(&MarcJson(&record)).unwrap(), json);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarcJson<T>(pub T);
+
+impl<T> MarcJson<T> {
+    /// Unwrap the inner value
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: Borrow<Record>> Serialize for MarcJson<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        SerdeOptions::marc_json().wrap(self.0.borrow()).serialize(serializer)
+    }
+}
+
+/// Overall shape of a serialized record
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordShape {
+    /// `leader`, `control_fields` and `data_fields` keys, as the `Record` struct
+    Struct,
+    /// `leader` and a `fields` array of tag-keyed objects (MARC-in-JSON)
+    MarcJson,
+}
+
+/// Representation of the leader
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeaderRepresentation {
+    /// The 24-character leader string
+    String,
+    /// An object with one key per leader element
+    Object,
+}
+
+/// Order in which fields are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldOrder {
+    /// Record order (control fields, then data fields)
+    Preserve,
+    /// Sorted by tag, keeping record order among fields with the same tag
+    Tag,
+}
+
+/// Naming of multi-word keys (`control_fields`, `record_length`, ...)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyCase {
+    /// `control_fields`
+    Snake,
+    /// `controlFields`
+    Camel,
+}
+
+/// Options controlling the serialized shape of a record
+///
+/// The default matches the `Serialize` implementation of [`Record`];
+/// [`SerdeOptions::marc_json`] matches [`MarcJson`], as read by pymarc and FOLIO.
+/// Options are applied by serializing the wrapper returned by [`SerdeOptions::wrap`].
+///
+/// ```
+/// use marc_rs::{KeyCase, Leader, LeaderRepresentation, Record, SerdeOptions};
+///
+/// let record = Record {
+///     leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+///     control_fields: vec![],
+///     data_fields: vec![],
+/// };
+/// let options = SerdeOptions {
+///     leader: LeaderRepresentation::String,
+///     key_case: KeyCase::Camel,
+///     ..SerdeOptions::default()
+/// };
+/// let json = serde_json::to_string(&options.wrap(&record)).unwrap();
+/// assert_eq!(json, r#"{"leader":"00000nam a2200000 a 4500","controlFields":[],"dataFields":[]}"#);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SerdeOptions {
+    pub shape: RecordShape,
+    pub leader: LeaderRepresentation,
+    pub field_order: FieldOrder,
+    /// Key naming for the struct shape and the leader object
+    pub key_case: KeyCase,
+    /// Leave out data fields that have no subfields
+    pub skip_empty_data_fields: bool,
+    /// Leave out empty `control_fields`, `data_fields`, `fields` and `subfields` arrays
+    pub skip_empty_arrays: bool,
+    /// Write the keys of every object in alphabetical order, for diff-stable output
+    pub canonical_key_order: bool,
+}
+
+impl Default for SerdeOptions {
+    fn default() -> Self {
+        Self {
+            shape: RecordShape::Struct,
+            leader: LeaderRepresentation::Object,
+            field_order: FieldOrder::Preserve,
+            key_case: KeyCase::Snake,
+            skip_empty_data_fields: false,
+            skip_empty_arrays: false,
+            canonical_key_order: false,
+        }
+    }
+}
+
+impl SerdeOptions {
+    /// MARC-in-JSON with a leader string, as used by pymarc, ruby-marc and FOLIO
+    pub fn marc_json() -> Self {
+        Self {
+            shape: RecordShape::MarcJson,
+            leader: LeaderRepresentation::String,
+            ..Self::default()
+        }
+    }
+
+    /// Wrap a value so that it serializes with these options
+    pub fn wrap<T>(self, value: T) -> WithOptions<T> {
+        WithOptions { value, options: self }
+    }
+
+    /// JSON Schema (draft 2020-12) of records serialized with these options
+    ///
+    /// ```
+    /// use marc_rs::SerdeOptions;
+    ///
+    /// let schema = SerdeOptions::marc_json().json_schema();
+    /// assert_eq!(schema["properties"]["leader"]["minLength"], 24);
+    /// assert_eq!(schema["required"], serde_json::json!(["leader", "fields"]));
+    /// ```
+    pub fn json_schema(&self) -> serde_json::Value {
+        use serde_json::{json, Map, Value};
+
+        let character = json!({ "type": "string", "minLength": 1, "maxLength": 1 });
+        let tag = json!({ "type": "string", "pattern": TAG_PATTERN });
+
+        let leader = match self.leader {
+            LeaderRepresentation::String => json!({ "type": "string", "minLength": 24, "maxLength": 24 }),
+            LeaderRepresentation::Object => {
+                let mut properties = Map::new();
+                for (name, numeric) in LEADER_ELEMENTS {
+                    let schema = if numeric { json!({ "type": "integer", "minimum": 0 }) } else { character.clone() };
+                    properties.insert(self.key(name).into_owned(), schema);
+                }
+                let required: Vec<Value> = properties.keys().cloned().map(Value::String).collect();
+                json!({ "type": "object", "required": required, "properties": properties })
+            }
+        };
+
+        // Arrays left out by `skip_empty_arrays` are optional
+        let required_arrays = |keys: &[String]| if self.skip_empty_arrays { Vec::new() } else { keys.to_vec() };
+        let mut data_field_required = vec!["ind1".to_string(), "ind2".to_string()];
+        data_field_required.extend(required_arrays(&["subfields".to_string()]));
+
+        let (fields, required) = match self.shape {
+            RecordShape::Struct => {
+                let control_field = json!({
+                    "type": "object",
+                    "required": ["tag", "value"],
+                    "properties": { "tag": tag, "value": { "type": "string" } },
+                });
+                let subfield = json!({
+                    "type": "object",
+                    "required": ["code", "value"],
+                    "properties": { "code": character, "value": { "type": "string" } },
+                });
+                let required = [vec!["tag".to_string()], data_field_required].concat();
+                let data_field = json!({
+                    "type": "object",
+                    "required": required,
+                    "properties": {
+                        "tag": tag,
+                        "ind1": character,
+                        "ind2": character,
+                        "subfields": { "type": "array", "items": subfield },
+                    },
+                });
+                let (control_fields, data_fields) = (self.key("control_fields").into_owned(), self.key("data_fields").into_owned());
+                let mut properties = Map::new();
+                properties.insert(control_fields.clone(), json!({ "type": "array", "items": control_field }));
+                properties.insert(data_fields.clone(), json!({ "type": "array", "items": data_field }));
+                (properties, required_arrays(&[control_fields, data_fields]))
+            }
+            RecordShape::MarcJson => {
+                let subfield = json!({
+                    "type": "object",
+                    "minProperties": 1,
+                    "maxProperties": 1,
+                    "patternProperties": { "^.$": { "type": "string" } },
+                    "additionalProperties": false,
+                });
+                let data_field = json!({
+                    "type": "object",
+                    "required": data_field_required,
+                    "properties": {
+                        "ind1": character,
+                        "ind2": character,
+                        "subfields": { "type": "array", "items": subfield },
+                    },
+                });
+                let field = json!({
+                    "type": "object",
+                    "minProperties": 1,
+                    "maxProperties": 1,
+                    "patternProperties": { TAG_PATTERN: { "oneOf": [{ "type": "string" }, data_field] } },
+                    "additionalProperties": false,
+                });
+                let mut properties = Map::new();
+                properties.insert("fields".to_string(), json!({ "type": "array", "items": field }));
+                (properties, required_arrays(&["fields".to_string()]))
+            }
+        };
+
+        let mut properties = Map::new();
+        properties.insert("leader".to_string(), leader);
+        properties.extend(fields);
+        let mut all_required = vec!["leader".to_string()];
+        all_required.extend(required);
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": "MARC record",
+            "type": "object",
+            "required": all_required,
+            "properties": properties,
+        })
+    }
+
+    fn key(&self, name: &'static str) -> Cow<'static, str> {
+        match self.key_case {
+            KeyCase::Snake => Cow::Borrowed(name),
+            KeyCase::Camel => {
+                let mut key = String::with_capacity(name.len());
+                let mut upper = false;
+                for c in name.chars() {
+                    if c == '_' {
+                        upper = true;
+                    } else if upper {
+                        key.push(c.to_ascii_uppercase());
+                        upper = false;
+                    } else {
+                        key.push(c);
+                    }
+                }
+                Cow::Owned(key)
+            }
+        }
+    }
+
+    fn data_fields<'a>(&self, record: &'a Record) -> Vec<&'a DataField> {
+        let mut fields: Vec<&DataField> = record.data_fields.iter().filter(|f| !self.skip_empty_data_fields || !f.subfields.is_empty()).collect();
+        if self.field_order == FieldOrder::Tag {
+            fields.sort_by(|a, b| a.tag.cmp(&b.tag));
+        }
+        fields
+    }
+
+    fn control_fields<'a>(&self, record: &'a Record) -> Vec<&'a ControlField> {
+        let mut fields: Vec<&ControlField> = record.control_fields.iter().collect();
+        if self.field_order == FieldOrder::Tag {
+            fields.sort_by(|a, b| a.tag.cmp(&b.tag));
+        }
+        fields
+    }
+}
+
+/// Pattern of field tags in JSON schemas
+const TAG_PATTERN: &str = "^[0-9A-Za-z]{3}$";
+
+/// Leader elements in serialization order, with whether they are numbers
+const LEADER_ELEMENTS: [(&str, bool); 16] = [
+    ("record_length", true),
+    ("record_status", false),
+    ("record_type", false),
+    ("bibliographic_level", false),
+    ("type_of_control", false),
+    ("character_coding_scheme", false),
+    ("indicator_count", true),
+    ("subfield_code_count", true),
+    ("base_address_of_data", true),
+    ("encoding_level", false),
+    ("descriptive_cataloging_form", false),
+    ("multipart_resource_record_level", false),
+    ("length_of_length_of_field_portion", true),
+    ("length_of_starting_character_position_portion", true),
+    ("length_of_implementation_defined_portion", true),
+    ("undefined", false),
+];
+
+/// A value serialized with [`SerdeOptions`]
+///
+/// Serializes any `T: Borrow<Record>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WithOptions<T> {
+    pub value: T,
+    pub options: SerdeOptions,
+}
+
+impl<T: Borrow<Record>> Serialize for WithOptions<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let record = self.value.borrow();
+        let options = &self.options;
+        let control_fields = Seq(options.control_fields(record));
+        let data_fields: Vec<StructDataField> = options.data_fields(record).into_iter().map(|f| StructDataField(f, options)).collect();
+
+        let mut parts = vec![(Cow::Borrowed("leader"), RecordPart::Leader)];
+        match options.shape {
+            RecordShape::Struct => {
+                if !(options.skip_empty_arrays && control_fields.0.is_empty()) {
+                    parts.push((options.key("control_fields"), RecordPart::ControlFields));
+                }
+                if !(options.skip_empty_arrays && data_fields.is_empty()) {
+                    parts.push((options.key("data_fields"), RecordPart::DataFields));
+                }
+            }
+            RecordShape::MarcJson => {
+                if !(options.skip_empty_arrays && control_fields.0.is_empty() && data_fields.is_empty()) {
+                    parts.push((Cow::Borrowed("fields"), RecordPart::Fields));
+                }
+            }
+        }
+        if options.canonical_key_order {
+            parts.sort_by(|a, b| a.0.cmp(&b.0));
+        }
+
+        let mut map = serializer.serialize_map(Some(parts.len()))?;
+        for (key, part) in parts {
+            match part {
+                RecordPart::Leader => map.serialize_entry(&key, &LeaderValue(&record.leader, options))?,
+                RecordPart::ControlFields => map.serialize_entry(&key, &control_fields)?,
+                RecordPart::DataFields => map.serialize_entry(&key, &data_fields)?,
+                RecordPart::Fields => map.serialize_entry(&key, &Fields(record, options))?,
+            }
+        }
+        map.end()
+    }
+}
+
+/// Top-level entries of a serialized record
+enum RecordPart {
+    Leader,
+    ControlFields,
+    DataFields,
+    Fields,
+}
+
+/// Sequence of references, serialized with the items' own implementation
+struct Seq<'a, T>(Vec<&'a T>);
+
+impl<T: Serialize> Serialize for Seq<'_, T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(&self.0)
+    }
+}
+
+struct LeaderValue<'a>(&'a Leader, &'a SerdeOptions);
+
+impl Serialize for LeaderValue<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let (leader, options) = (self.0, self.1);
+        if options.leader == LeaderRepresentation::String {
+            return serializer.serialize_str(&String::from_utf8_lossy(&leader.to_bytes()));
+        }
+
+        let elements = [
+            LeaderElement::Number(leader.record_length),
+            LeaderElement::Character(leader.record_status),
+            LeaderElement::Character(leader.record_type),
+            LeaderElement::Character(leader.bibliographic_level),
+            LeaderElement::Character(leader.type_of_control),
+            LeaderElement::Character(leader.character_coding_scheme),
+            LeaderElement::Digit(leader.indicator_count),
+            LeaderElement::Digit(leader.subfield_code_count),
+            LeaderElement::Number(leader.base_address_of_data),
+            LeaderElement::Character(leader.encoding_level),
+            LeaderElement::Character(leader.descriptive_cataloging_form),
+            LeaderElement::Character(leader.multipart_resource_record_level),
+            LeaderElement::Digit(leader.length_of_length_of_field_portion),
+            LeaderElement::Digit(leader.length_of_starting_character_position_portion),
+            LeaderElement::Digit(leader.length_of_implementation_defined_portion),
+            LeaderElement::Character(leader.undefined),
+        ];
+        let mut entries: Vec<(Cow<str>, LeaderElement)> = LEADER_ELEMENTS.iter().map(|(name, _)| options.key(name)).zip(elements).collect();
+        if options.canonical_key_order {
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+        }
+
+        let mut map = serializer.serialize_map(Some(entries.len()))?;
+        for (key, element) in &entries {
+            map.serialize_entry(key, element)?;
+        }
+        map.end()
+    }
+}
+
+/// Value of a leader element, serialized with the type of its `Leader` field
+enum LeaderElement {
+    Number(u32),
+    Digit(u8),
+    Character(char),
+}
+
+impl Serialize for LeaderElement {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            LeaderElement::Number(value) => serializer.serialize_u32(*value),
+            LeaderElement::Digit(value) => serializer.serialize_u8(*value),
+            LeaderElement::Character(value) => serializer.serialize_char(*value),
+        }
+    }
+}
+
+struct StructDataField<'a>(&'a DataField, &'a SerdeOptions);
+
+impl Serialize for StructDataField<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let (field, options) = (self.0, self.1);
+        let with_subfields = !(options.skip_empty_arrays && field.subfields.is_empty());
+        let mut map = serializer.serialize_map(None)?;
+        if !options.canonical_key_order {
+            map.serialize_entry("tag", &field.tag)?;
+        }
+        map.serialize_entry("ind1", &field.ind1)?;
+        map.serialize_entry("ind2", &field.ind2)?;
+        if with_subfields {
+            map.serialize_entry("subfields", &field.subfields)?;
+        }
+        if options.canonical_key_order {
+            map.serialize_entry("tag", &field.tag)?;
+        }
+        map.end()
+    }
+}
+
+struct Fields<'a>(&'a Record, &'a SerdeOptions);
+
+impl Serialize for Fields<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let (record, options) = (self.0, self.1);
+        let control_fields = options.control_fields(record);
+        let data_fields = options.data_fields(record);
+        let mut seq = serializer.serialize_seq(Some(control_fields.len() + data_fields.len()))?;
+        for field in control_fields {
+            seq.serialize_element(&ControlFieldEntry(field))?;
+        }
+        for field in data_fields {
+            seq.serialize_element(&DataFieldEntry(field, options))?;
+        }
+        seq.end()
+    }
+}
+
+struct ControlFieldEntry<'a>(&'a ControlField);
+
+impl Serialize for ControlFieldEntry<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_entry(&self.0.tag, &self.0.value)?;
+        map.end()
+    }
+}
+
+struct DataFieldEntry<'a>(&'a DataField, &'a SerdeOptions);
+
+impl Serialize for DataFieldEntry<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_entry(&self.0.tag, &DataFieldBody(self.0, self.1))?;
+        map.end()
+    }
+}
+
+/// Keys are already in alphabetical order
+struct DataFieldBody<'a>(&'a DataField, &'a SerdeOptions);
+
+impl Serialize for DataFieldBody<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let (field, options) = (self.0, self.1);
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("ind1", &field.ind1)?;
+        map.serialize_entry("ind2", &field.ind2)?;
+        if !(options.skip_empty_arrays && field.subfields.is_empty()) {
+            map.serialize_entry("subfields", &Subfields(&field.subfields))?;
+        }
+        map.end()
+    }
+}
+
+struct Subfields<'a>(&'a [Subfield]);
+
+impl Serialize for Subfields<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+        for subfield in self.0 {
+            seq.serialize_element(&SubfieldEntry(subfield))?;
+        }
+        seq.end()
+    }
+}
+
+struct SubfieldEntry<'a>(&'a Subfield);
+
+impl Serialize for SubfieldEntry<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_entry(&self.0.code, &self.0.value)?;
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for MarcJson<Record> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct RecordVisitor;
+
+        impl<'de> Visitor<'de> for RecordVisitor {
+            type Value = MarcJson<Record>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a MARC-in-JSON record")
+            }
+
+            fn visit_map<V>(self, mut map: V) -> Result<Self::Value, V::Error>
+            where
+                V: MapAccess<'de>,
+            {
+                let mut leader: Option<String> = None;
+                let mut fields: Option<Vec<FieldEntry>> = None;
+
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "leader" => {
+                            if leader.is_some() {
+                                return Err(de::Error::duplicate_field("leader"));
+                            }
+                            leader = Some(map.next_value()?);
+                        }
+                        "fields" => {
+                            if fields.is_some() {
+                                return Err(de::Error::duplicate_field("fields"));
+                            }
+                            fields = Some(map.next_value()?);
+                        }
+                        _ => {
+                            let _ = map.next_value::<de::IgnoredAny>()?;
+                        }
+                    }
+                }
+
+                let leader = leader.ok_or_else(|| de::Error::missing_field("leader"))?;
+                if !leader.is_ascii() {
+                    return Err(de::Error::invalid_value(de::Unexpected::Str(&leader), &"an ASCII leader string"));
+                }
+                let leader = Leader::from_bytes(leader.as_bytes()).map_err(de::Error::custom)?;
+
+                let mut record = Record {
+                    leader,
+                    control_fields: Vec::new(),
+                    data_fields: Vec::new(),
+                };
+                // `fields` is left out of empty records by `SerdeOptions::skip_empty_arrays`
+                for field in fields.unwrap_or_default() {
+                    match field {
+                        FieldEntry::Control(field) => record.control_fields.push(field),
+                        FieldEntry::Data(field) => record.data_fields.push(field),
+                    }
+                }
+                Ok(MarcJson(record))
+            }
+        }
+
+        deserializer.deserialize_map(RecordVisitor)
+    }
+}
+
+/// One element of the `fields` array: `{ "tag": value }`
+enum FieldEntry {
+    Control(ControlField),
+    Data(DataField),
+}
+
+impl<'de> Deserialize<'de> for FieldEntry {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct FieldEntryVisitor;
+
+        impl<'de> Visitor<'de> for FieldEntryVisitor {
+            type Value = FieldEntry;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an object with a single tag key")
+            }
+
+            fn visit_map<V>(self, mut map: V) -> Result<FieldEntry, V::Error>
+            where
+                V: MapAccess<'de>,
+            {
+                let tag: String = map.next_key()?.ok_or_else(|| de::Error::invalid_length(0, &self))?;
+                let entry = match map.next_value::<FieldBody>()? {
+                    FieldBody::Control(value) => FieldEntry::Control(ControlField { tag, value }),
+                    FieldBody::Data { ind1, ind2, subfields } => FieldEntry::Data(DataField { tag, ind1, ind2, subfields }),
+                };
+                if map.next_key::<de::IgnoredAny>()?.is_some() {
+                    return Err(de::Error::invalid_length(2, &self));
+                }
+                Ok(entry)
+            }
+        }
+
+        deserializer.deserialize_map(FieldEntryVisitor)
+    }
+}
+
+/// Value of a field entry: a string for control fields, an object for data fields
+enum FieldBody {
+    Control(String),
+    Data { ind1: char, ind2: char, subfields: Vec<Subfield> },
+}
+
+impl<'de> Deserialize<'de> for FieldBody {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct FieldBodyVisitor;
+
+        impl<'de> Visitor<'de> for FieldBodyVisitor {
+            type Value = FieldBody;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a control field string or a data field object")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<FieldBody, E>
+            where
+                E: de::Error,
+            {
+                Ok(FieldBody::Control(value.to_string()))
+            }
+
+            fn visit_string<E>(self, value: String) -> Result<FieldBody, E>
+            where
+                E: de::Error,
+            {
+                Ok(FieldBody::Control(value))
+            }
+
+            fn visit_map<V>(self, mut map: V) -> Result<FieldBody, V::Error>
+            where
+                V: MapAccess<'de>,
+            {
+                let mut ind1 = None;
+                let mut ind2 = None;
+                let mut subfields: Option<SubfieldsOwned> = None;
+
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "ind1" | "indicator1" => ind1 = Some(map.next_value::<Indicator>()?.0),
+                        "ind2" | "indicator2" => ind2 = Some(map.next_value::<Indicator>()?.0),
+                        "indicators" => {
+                            let indicators: String = map.next_value()?;
+                            let mut chars = indicators.chars();
+                            ind1 = Some(chars.next().unwrap_or(' '));
+                            ind2 = Some(chars.next().unwrap_or(' '));
+                        }
+                        "subfields" => subfields = Some(map.next_value()?),
+                        _ => {
+                            let _ = map.next_value::<de::IgnoredAny>()?;
+                        }
+                    }
+                }
+
+                Ok(FieldBody::Data {
+                    ind1: ind1.unwrap_or(' '),
+                    ind2: ind2.unwrap_or(' '),
+                    subfields: subfields.map(|s| s.0).unwrap_or_default(),
+                })
+            }
+        }
+
+        deserializer.deserialize_any(FieldBodyVisitor)
+    }
+}
+
+/// An indicator: a one-character string, blank when empty, or a digit
+struct Indicator(char);
+
+impl<'de> Deserialize<'de> for Indicator {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct IndicatorVisitor;
+
+        impl<'de> Visitor<'de> for IndicatorVisitor {
+            type Value = Indicator;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an indicator character")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Indicator, E>
+            where
+                E: de::Error,
+            {
+                let mut chars = value.chars();
+                match (chars.next(), chars.next()) {
+                    (None, _) => Ok(Indicator(' ')),
+                    (Some(c), None) => Ok(Indicator(c)),
+                    _ => Err(de::Error::invalid_value(de::Unexpected::Str(value), &self)),
+                }
+            }
+
+            fn visit_u64<E>(self, value: u64) -> Result<Indicator, E>
+            where
+                E: de::Error,
+            {
+                char::from_digit(value as u32, 10)
+                    .filter(|_| value < 10)
+                    .map(Indicator)
+                    .ok_or_else(|| de::Error::invalid_value(de::Unexpected::Unsigned(value), &self))
+            }
+
+            fn visit_i64<E>(self, value: i64) -> Result<Indicator, E>
+            where
+                E: de::Error,
+            {
+                u64::try_from(value)
+                    .map_err(|_| de::Error::invalid_value(de::Unexpected::Signed(value), &self))
+                    .and_then(|v| self.visit_u64(v))
+            }
+
+            fn visit_unit<E>(self) -> Result<Indicator, E>
+            where
+                E: de::Error,
+            {
+                Ok(Indicator(' '))
+            }
+        }
+
+        deserializer.deserialize_any(IndicatorVisitor)
+    }
+}
+
+/// The `subfields` of a data field
+///
+/// Accepts an array of `{ "a": value }` objects (MARC-in-JSON), of
+/// `{ "code": "a", "value": value }` objects or of `["a", value]` pairs, as well
+/// as a single `{ "a": value, "b": value }` object.
+struct SubfieldsOwned(Vec<Subfield>);
+
+impl<'de> Deserialize<'de> for SubfieldsOwned {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct SubfieldsVisitor;
+
+        impl<'de> Visitor<'de> for SubfieldsVisitor {
+            type Value = SubfieldsOwned;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an array of subfields or an object of subfield codes")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<SubfieldsOwned, A::Error>
+            where
+                A: de::SeqAccess<'de>,
+            {
+                let mut subfields = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                while let Some(entry) = seq.next_element::<SubfieldEntryOwned>()? {
+                    subfields.push(entry.0);
+                }
+                Ok(SubfieldsOwned(subfields))
+            }
+
+            fn visit_map<V>(self, mut map: V) -> Result<SubfieldsOwned, V::Error>
+            where
+                V: MapAccess<'de>,
+            {
+                let mut subfields = Vec::new();
+                while let Some((code, value)) = map.next_entry::<char, String>()? {
+                    subfields.push(Subfield { code, value });
+                }
+                Ok(SubfieldsOwned(subfields))
+            }
+        }
+
+        deserializer.deserialize_any(SubfieldsVisitor)
+    }
+}
+
+/// One element of the `subfields` array: `{ "code": value }`, `{ "code": "a", "value": value }` or `["a", value]`
+struct SubfieldEntryOwned(Subfield);
+
+impl<'de> Deserialize<'de> for SubfieldEntryOwned {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct SubfieldVisitor;
+
+        impl<'de> Visitor<'de> for SubfieldVisitor {
+            type Value = SubfieldEntryOwned;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a subfield object or a [code, value] pair")
+            }
+
+            fn visit_map<V>(self, mut map: V) -> Result<SubfieldEntryOwned, V::Error>
+            where
+                V: MapAccess<'de>,
+            {
+                let (key, value): (String, String) = map.next_entry()?.ok_or_else(|| de::Error::invalid_length(0, &self))?;
+                let (code, value) = match map.next_entry::<String, String>()? {
+                    None => (key, value),
+                    // `{ "code": "a", "value": ... }`, in either order
+                    Some((second_key, second_value)) => {
+                        let entry = match (key.as_str(), second_key.as_str()) {
+                            ("code", "value") => (value, second_value),
+                            ("value", "code") => (second_value, value),
+                            _ => return Err(de::Error::invalid_length(2, &self)),
+                        };
+                        if map.next_key::<de::IgnoredAny>()?.is_some() {
+                            return Err(de::Error::invalid_length(3, &self));
+                        }
+                        entry
+                    }
+                };
+
+                let mut chars = code.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(code), None) => Ok(SubfieldEntryOwned(Subfield { code, value })),
+                    _ => Err(de::Error::invalid_value(de::Unexpected::Str(&code), &"a subfield code")),
+                }
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<SubfieldEntryOwned, A::Error>
+            where
+                A: de::SeqAccess<'de>,
+            {
+                let code: char = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(0, &self))?;
+                let value: String = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(1, &self))?;
+                if seq.next_element::<de::IgnoredAny>()?.is_some() {
+                    return Err(de::Error::invalid_length(3, &self));
+                }
+                Ok(SubfieldEntryOwned(Subfield { code, value }))
+            }
+        }
+
+        deserializer.deserialize_any(SubfieldVisitor)
+    }
+}
+
+/// Iterator reading MARC-in-JSON records from JSON Lines input, one record per line
+///
+/// Blank lines are skipped. The iterator stops after the first error, which
+/// gives the 1-based line number.
+pub struct JsonLinesReader<R: std::io::BufRead> {
+    reader: R,
+    line: String,
+    line_number: usize,
+    done: bool,
+}
+
+impl<R: std::io::BufRead> JsonLinesReader<R> {
+    /// Create a reader over JSON Lines input
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            line: String::new(),
+            line_number: 0,
+            done: false,
+        }
+    }
+
+    /// Number of lines read so far
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    /// Unwrap the underlying reader
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    fn read_record(&mut self) -> Result<Option<Record>, ParseError> {
+        loop {
+            self.line.clear();
+            let read = self
+                .reader
+                .read_line(&mut self.line)
+                .map_err(|e| ParseError::Other(format!("IO error: {}", e)))?;
+            if read == 0 {
+                return Ok(None);
+            }
+            self.line_number += 1;
+            if self.line.trim().is_empty() {
+                continue;
+            }
+            return serde_json::from_str::<MarcJson<Record>>(&self.line)
+                .map(|json| Some(json.0))
+                .map_err(|e| ParseError::Other(format!("Line {}: {}", self.line_number, e)));
+        }
+    }
+}
+
+impl<R: std::io::BufRead> Iterator for JsonLinesReader<R> {
+    type Item = Result<Record, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.read_record() {
+            Ok(Some(record)) => Some(Ok(record)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Iterator reading MARC-in-JSON records from a JSON document
+///
+/// Accepts a top-level array of records, as well as records written one after
+/// another (pretty-printed, or one per line as JSON Lines). Records are read one
+/// at a time, without loading the whole array. The iterator stops after the first
+/// error, which gives the 1-based record number.
+///
+/// ```
+/// use marc_rs::JsonReader;
+///
+/// let json = r#"[
+///   {"leader": "00000nam a2200000 a 4500", "fields": [{"001": "1"}]},
+///   {"leader": "00000nam a2200000 a 4500", "fields": [{"001": "2"}]}
+/// ]"#;
+/// let ids: Vec<String> = JsonReader::new(json.as_bytes()).map(|r| r.unwrap().control_fields[0].value.clone()).collect();
+/// assert_eq!(ids, ["1", "2"]);
+/// ```
+pub struct JsonReader<R: std::io::BufRead> {
+    reader: R,
+    record_number: usize,
+    /// `None` until the first non-blank byte tells whether the input is an array
+    in_array: Option<bool>,
+    done: bool,
+}
+
+impl<R: std::io::BufRead> JsonReader<R> {
+    /// Create a reader over a JSON array or a stream of JSON records
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            record_number: 0,
+            in_array: None,
+            done: false,
+        }
+    }
+
+    /// Number of records read so far
+    pub fn record_number(&self) -> usize {
+        self.record_number
+    }
+
+    /// Unwrap the underlying reader
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Next byte other than whitespace, left unconsumed
+    fn peek_byte(&mut self) -> Result<Option<u8>, ParseError> {
+        loop {
+            let buffer = self.reader.fill_buf().map_err(|e| ParseError::Other(format!("IO error: {}", e)))?;
+            match buffer.iter().position(|b| !b.is_ascii_whitespace()) {
+                Some(position) => {
+                    let byte = buffer[position];
+                    self.reader.consume(position);
+                    return Ok(Some(byte));
+                }
+                None if buffer.is_empty() => return Ok(None),
+                None => {
+                    let length = buffer.len();
+                    self.reader.consume(length);
+                }
+            }
+        }
+    }
+
+    fn read_record(&mut self) -> Result<Option<Record>, ParseError> {
+        let in_array = match self.in_array {
+            Some(in_array) => in_array,
+            None => {
+                let in_array = self.peek_byte()? == Some(b'[');
+                if in_array {
+                    self.reader.consume(1);
+                }
+                self.in_array = Some(in_array);
+                in_array
+            }
+        };
+        match self.peek_byte()? {
+            Some(b',') if in_array && self.record_number > 0 => {
+                self.reader.consume(1);
+                if self.peek_byte()? != Some(b'{') {
+                    return Err(ParseError::Other(format!("Record {}: expected a record after ','", self.record_number + 1)));
+                }
+            }
+            Some(b']') if in_array => {
+                self.reader.consume(1);
+                return match self.peek_byte()? {
+                    None => Ok(None),
+                    Some(_) => Err(ParseError::Other("Trailing data after the record array".to_string())),
+                };
+            }
+            None if in_array => return Err(ParseError::Other("Unterminated record array".to_string())),
+            None => return Ok(None),
+            Some(_) if in_array && self.record_number > 0 => {
+                return Err(ParseError::Other(format!("Record {}: expected ',' or ']'", self.record_number + 1)));
+            }
+            Some(_) => {}
+        }
+
+        // A record is an object: the deserializer stops at its closing brace
+        // without reading ahead, so the next record is left in the reader.
+        self.record_number += 1;
+        let mut deserializer = serde_json::Deserializer::from_reader(&mut self.reader);
+        MarcJson::<Record>::deserialize(&mut deserializer)
+            .map(|json| Some(json.0))
+            .map_err(|e| ParseError::Other(format!("Record {}: {}", self.record_number, e)))
+    }
+}
+
+impl<R: std::io::BufRead> Iterator for JsonReader<R> {
+    type Item = Result<Record, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.read_record() {
+            Ok(Some(record)) => Some(Ok(record)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
@@ -0,0 +1,626 @@
+use crate::format::MarcFormat;
+use crate::record::Record;
+
+/// Entry of the MARC code list for languages
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Language {
+    /// Three-letter MARC code (ISO 639-2/B based)
+    pub code: &'static str,
+    /// English name
+    pub name: &'static str,
+    /// Two-letter ISO 639-1 code, when one exists
+    pub iso639_1: Option<&'static str>,
+}
+
+/// Discontinued MARC language code and the code that replaced it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiscontinuedLanguage {
+    pub code: &'static str,
+    pub name: &'static str,
+    /// Current code to use instead, if the language was not simply dropped
+    pub replaced_by: Option<&'static str>,
+}
+
+impl Language {
+    /// Look up a current MARC language code (case-insensitive)
+    pub fn from_code(code: &str) -> Option<&'static Language> {
+        let code = code.trim();
+        LANGUAGES.iter().find(|l| l.code.eq_ignore_ascii_case(code))
+    }
+
+    /// Look up a language by its ISO 639-1 code
+    pub fn from_iso639_1(code: &str) -> Option<&'static Language> {
+        let code = code.trim();
+        LANGUAGES.iter().find(|l| l.iso639_1.is_some_and(|c| c.eq_ignore_ascii_case(code)))
+    }
+
+    /// Whether the code is a current MARC language code
+    pub fn is_valid_code(code: &str) -> bool {
+        Language::from_code(code).is_some()
+    }
+
+    /// English name of a current MARC language code
+    pub fn name_of(code: &str) -> Option<&'static str> {
+        Language::from_code(code).map(|l| l.name)
+    }
+
+    /// ISO 639-1 equivalent of a MARC language code
+    pub fn iso639_1_of(code: &str) -> Option<&'static str> {
+        Language::from_code(code).and_then(|l| l.iso639_1)
+    }
+
+    /// Look up a discontinued code (e.g. "scr" for Croatian, now "hrv")
+    pub fn discontinued(code: &str) -> Option<&'static DiscontinuedLanguage> {
+        let code = code.trim();
+        DISCONTINUED_LANGUAGES.iter().find(|l| l.code.eq_ignore_ascii_case(code))
+    }
+
+    /// All current MARC language codes, ordered by code
+    pub fn all() -> &'static [Language] {
+        LANGUAGES
+    }
+}
+
+impl Record {
+    /// Language codes of the item, in record order and without duplicates
+    ///
+    /// MARC21 codes come from 008/35-37 then 041 $a and $d (pre-2001 records that
+    /// concatenate codes in a single subfield are split); UNIMARC codes come from 101 $a.
+    /// Blank, fill (`|||`) and unparseable values are ignored.
+    pub fn language_codes(&self, format: MarcFormat) -> Vec<String> {
+        let mut codes = Vec::new();
+        let mut push = |code: &str| {
+            let code = code.to_ascii_lowercase();
+            if code.len() == 3 && code.chars().all(|c| c.is_ascii_alphabetic()) && !codes.contains(&code) {
+                codes.push(code);
+            }
+        };
+
+        let (tag, subfields): (&str, &[char]) = match format {
+            MarcFormat::Marc21 | MarcFormat::MarcXml => {
+                if let Some(field) = self.control_fields.iter().find(|f| f.tag == "008") {
+                    if let Ok(code) = field.chars(35..38) {
+                        push(code);
+                    }
+                }
+                ("041", &['a', 'd'])
+            }
+            MarcFormat::Unimarc => ("101", &['a']),
+        };
+
+        for field in self.data_fields.iter().filter(|f| f.tag == tag) {
+            for subfield in field.subfields.iter().filter(|s| subfields.contains(&s.code)) {
+                let value = subfield.value.trim();
+                if value.is_ascii() && value.len() % 3 == 0 {
+                    for i in (0..value.len()).step_by(3) {
+                        push(&value[i..i + 3]);
+                    }
+                }
+            }
+        }
+
+        codes
+    }
+}
+
+/// MARC code list for languages
+const LANGUAGES: &[Language] = &[
+    Language { code: "aar", name: "Afar", iso639_1: Some("aa") },
+    Language { code: "abk", name: "Abkhaz", iso639_1: Some("ab") },
+    Language { code: "ace", name: "Achinese", iso639_1: None },
+    Language { code: "ach", name: "Acoli", iso639_1: None },
+    Language { code: "ada", name: "Adangme", iso639_1: None },
+    Language { code: "ady", name: "Adygei", iso639_1: None },
+    Language { code: "afa", name: "Afroasiatic (Other)", iso639_1: None },
+    Language { code: "afh", name: "Afrihili (Artificial language)", iso639_1: None },
+    Language { code: "afr", name: "Afrikaans", iso639_1: Some("af") },
+    Language { code: "ain", name: "Ainu", iso639_1: None },
+    Language { code: "aka", name: "Akan", iso639_1: Some("ak") },
+    Language { code: "akk", name: "Akkadian", iso639_1: None },
+    Language { code: "alb", name: "Albanian", iso639_1: Some("sq") },
+    Language { code: "ale", name: "Aleut", iso639_1: None },
+    Language { code: "alg", name: "Algonquian (Other)", iso639_1: None },
+    Language { code: "alt", name: "Altai", iso639_1: None },
+    Language { code: "amh", name: "Amharic", iso639_1: Some("am") },
+    Language { code: "ang", name: "English, Old (ca. 450-1100)", iso639_1: None },
+    Language { code: "anp", name: "Angika", iso639_1: None },
+    Language { code: "apa", name: "Apache languages", iso639_1: None },
+    Language { code: "ara", name: "Arabic", iso639_1: Some("ar") },
+    Language { code: "arc", name: "Aramaic", iso639_1: None },
+    Language { code: "arg", name: "Aragonese", iso639_1: Some("an") },
+    Language { code: "arm", name: "Armenian", iso639_1: Some("hy") },
+    Language { code: "arn", name: "Mapuche", iso639_1: None },
+    Language { code: "arp", name: "Arapaho", iso639_1: None },
+    Language { code: "art", name: "Artificial (Other)", iso639_1: None },
+    Language { code: "arw", name: "Arawak", iso639_1: None },
+    Language { code: "asm", name: "Assamese", iso639_1: Some("as") },
+    Language { code: "ast", name: "Bable", iso639_1: None },
+    Language { code: "ath", name: "Athapascan (Other)", iso639_1: None },
+    Language { code: "aus", name: "Australian languages", iso639_1: None },
+    Language { code: "ava", name: "Avaric", iso639_1: Some("av") },
+    Language { code: "ave", name: "Avestan", iso639_1: Some("ae") },
+    Language { code: "awa", name: "Awadhi", iso639_1: None },
+    Language { code: "aym", name: "Aymara", iso639_1: Some("ay") },
+    Language { code: "aze", name: "Azerbaijani", iso639_1: Some("az") },
+    Language { code: "bad", name: "Banda languages", iso639_1: None },
+    Language { code: "bai", name: "Bamileke languages", iso639_1: None },
+    Language { code: "bak", name: "Bashkir", iso639_1: Some("ba") },
+    Language { code: "bal", name: "Baluchi", iso639_1: None },
+    Language { code: "bam", name: "Bambara", iso639_1: Some("bm") },
+    Language { code: "ban", name: "Balinese", iso639_1: None },
+    Language { code: "baq", name: "Basque", iso639_1: Some("eu") },
+    Language { code: "bas", name: "Basa", iso639_1: None },
+    Language { code: "bat", name: "Baltic (Other)", iso639_1: None },
+    Language { code: "bej", name: "Beja", iso639_1: None },
+    Language { code: "bel", name: "Belarusian", iso639_1: Some("be") },
+    Language { code: "bem", name: "Bemba", iso639_1: None },
+    Language { code: "ben", name: "Bengali", iso639_1: Some("bn") },
+    Language { code: "ber", name: "Berber (Other)", iso639_1: None },
+    Language { code: "bho", name: "Bhojpuri", iso639_1: None },
+    Language { code: "bih", name: "Bihari (Other)", iso639_1: Some("bh") },
+    Language { code: "bik", name: "Bikol", iso639_1: None },
+    Language { code: "bin", name: "Edo", iso639_1: None },
+    Language { code: "bis", name: "Bislama", iso639_1: Some("bi") },
+    Language { code: "bla", name: "Siksika", iso639_1: None },
+    Language { code: "bnt", name: "Bantu (Other)", iso639_1: None },
+    Language { code: "bos", name: "Bosnian", iso639_1: Some("bs") },
+    Language { code: "bra", name: "Braj", iso639_1: None },
+    Language { code: "bre", name: "Breton", iso639_1: Some("br") },
+    Language { code: "btk", name: "Batak", iso639_1: None },
+    Language { code: "bua", name: "Buriat", iso639_1: None },
+    Language { code: "bug", name: "Bugis", iso639_1: None },
+    Language { code: "bul", name: "Bulgarian", iso639_1: Some("bg") },
+    Language { code: "bur", name: "Burmese", iso639_1: Some("my") },
+    Language { code: "byn", name: "Bilin", iso639_1: None },
+    Language { code: "cad", name: "Caddo", iso639_1: None },
+    Language { code: "cai", name: "Central American Indian (Other)", iso639_1: None },
+    Language { code: "car", name: "Carib", iso639_1: None },
+    Language { code: "cat", name: "Catalan", iso639_1: Some("ca") },
+    Language { code: "cau", name: "Caucasian (Other)", iso639_1: None },
+    Language { code: "ceb", name: "Cebuano", iso639_1: None },
+    Language { code: "cel", name: "Celtic (Other)", iso639_1: None },
+    Language { code: "cha", name: "Chamorro", iso639_1: Some("ch") },
+    Language { code: "chb", name: "Chibcha", iso639_1: None },
+    Language { code: "che", name: "Chechen", iso639_1: Some("ce") },
+    Language { code: "chg", name: "Chagatai", iso639_1: None },
+    Language { code: "chi", name: "Chinese", iso639_1: Some("zh") },
+    Language { code: "chk", name: "Chuukese", iso639_1: None },
+    Language { code: "chm", name: "Mari", iso639_1: None },
+    Language { code: "chn", name: "Chinook jargon", iso639_1: None },
+    Language { code: "cho", name: "Choctaw", iso639_1: None },
+    Language { code: "chp", name: "Chipewyan", iso639_1: None },
+    Language { code: "chr", name: "Cherokee", iso639_1: None },
+    Language { code: "chu", name: "Church Slavic", iso639_1: Some("cu") },
+    Language { code: "chv", name: "Chuvash", iso639_1: Some("cv") },
+    Language { code: "chy", name: "Cheyenne", iso639_1: None },
+    Language { code: "cmc", name: "Chamic languages", iso639_1: None },
+    Language { code: "cnr", name: "Montenegrin", iso639_1: None },
+    Language { code: "cop", name: "Coptic", iso639_1: None },
+    Language { code: "cor", name: "Cornish", iso639_1: Some("kw") },
+    Language { code: "cos", name: "Corsican", iso639_1: Some("co") },
+    Language { code: "cpe", name: "Creoles and Pidgins, English-based (Other)", iso639_1: None },
+    Language { code: "cpf", name: "Creoles and Pidgins, French-based (Other)", iso639_1: None },
+    Language { code: "cpp", name: "Creoles and Pidgins, Portuguese-based (Other)", iso639_1: None },
+    Language { code: "cre", name: "Cree", iso639_1: Some("cr") },
+    Language { code: "crh", name: "Crimean Tatar", iso639_1: None },
+    Language { code: "crp", name: "Creoles and Pidgins (Other)", iso639_1: None },
+    Language { code: "csb", name: "Kashubian", iso639_1: None },
+    Language { code: "cus", name: "Cushitic (Other)", iso639_1: None },
+    Language { code: "cze", name: "Czech", iso639_1: Some("cs") },
+    Language { code: "dak", name: "Dakota", iso639_1: None },
+    Language { code: "dan", name: "Danish", iso639_1: Some("da") },
+    Language { code: "dar", name: "Dargwa", iso639_1: None },
+    Language { code: "day", name: "Dayak", iso639_1: None },
+    Language { code: "del", name: "Delaware", iso639_1: None },
+    Language { code: "den", name: "Slavey", iso639_1: None },
+    Language { code: "dgr", name: "Dogrib", iso639_1: None },
+    Language { code: "din", name: "Dinka", iso639_1: None },
+    Language { code: "div", name: "Divehi", iso639_1: Some("dv") },
+    Language { code: "doi", name: "Dogri", iso639_1: None },
+    Language { code: "dra", name: "Dravidian (Other)", iso639_1: None },
+    Language { code: "dsb", name: "Lower Sorbian", iso639_1: None },
+    Language { code: "dua", name: "Duala", iso639_1: None },
+    Language { code: "dum", name: "Dutch, Middle (ca. 1050-1350)", iso639_1: None },
+    Language { code: "dut", name: "Dutch", iso639_1: Some("nl") },
+    Language { code: "dyu", name: "Dyula", iso639_1: None },
+    Language { code: "dzo", name: "Dzongkha", iso639_1: Some("dz") },
+    Language { code: "efi", name: "Efik", iso639_1: None },
+    Language { code: "egy", name: "Egyptian", iso639_1: None },
+    Language { code: "eka", name: "Ekajuk", iso639_1: None },
+    Language { code: "elx", name: "Elamite", iso639_1: None },
+    Language { code: "eng", name: "English", iso639_1: Some("en") },
+    Language { code: "enm", name: "English, Middle (1100-1500)", iso639_1: None },
+    Language { code: "epo", name: "Esperanto", iso639_1: Some("eo") },
+    Language { code: "est", name: "Estonian", iso639_1: Some("et") },
+    Language { code: "ewe", name: "Ewe", iso639_1: Some("ee") },
+    Language { code: "ewo", name: "Ewondo", iso639_1: None },
+    Language { code: "fan", name: "Fang", iso639_1: None },
+    Language { code: "fao", name: "Faroese", iso639_1: Some("fo") },
+    Language { code: "fat", name: "Fanti", iso639_1: None },
+    Language { code: "fij", name: "Fijian", iso639_1: Some("fj") },
+    Language { code: "fil", name: "Filipino", iso639_1: None },
+    Language { code: "fin", name: "Finnish", iso639_1: Some("fi") },
+    Language { code: "fiu", name: "Finno-Ugrian (Other)", iso639_1: None },
+    Language { code: "fon", name: "Fon", iso639_1: None },
+    Language { code: "fre", name: "French", iso639_1: Some("fr") },
+    Language { code: "frm", name: "French, Middle (ca. 1300-1600)", iso639_1: None },
+    Language { code: "fro", name: "French, Old (ca. 842-1300)", iso639_1: None },
+    Language { code: "frr", name: "North Frisian", iso639_1: None },
+    Language { code: "frs", name: "East Frisian", iso639_1: None },
+    Language { code: "fry", name: "Frisian", iso639_1: Some("fy") },
+    Language { code: "ful", name: "Fula", iso639_1: Some("ff") },
+    Language { code: "fur", name: "Friulian", iso639_1: None },
+    Language { code: "gaa", name: "Gã", iso639_1: None },
+    Language { code: "gay", name: "Gayo", iso639_1: None },
+    Language { code: "gba", name: "Gbaya", iso639_1: None },
+    Language { code: "gem", name: "Germanic (Other)", iso639_1: None },
+    Language { code: "geo", name: "Georgian", iso639_1: Some("ka") },
+    Language { code: "ger", name: "German", iso639_1: Some("de") },
+    Language { code: "gez", name: "Ethiopic", iso639_1: None },
+    Language { code: "gil", name: "Gilbertese", iso639_1: None },
+    Language { code: "gla", name: "Scottish Gaelic", iso639_1: Some("gd") },
+    Language { code: "gle", name: "Irish", iso639_1: Some("ga") },
+    Language { code: "glg", name: "Galician", iso639_1: Some("gl") },
+    Language { code: "glv", name: "Manx", iso639_1: Some("gv") },
+    Language { code: "gmh", name: "German, Middle High (ca. 1050-1500)", iso639_1: None },
+    Language { code: "goh", name: "German, Old High (ca. 750-1050)", iso639_1: None },
+    Language { code: "gon", name: "Gondi", iso639_1: None },
+    Language { code: "gor", name: "Gorontalo", iso639_1: None },
+    Language { code: "got", name: "Gothic", iso639_1: None },
+    Language { code: "grb", name: "Grebo", iso639_1: None },
+    Language { code: "grc", name: "Greek, Ancient (to 1453)", iso639_1: None },
+    Language { code: "gre", name: "Greek, Modern (1453-)", iso639_1: Some("el") },
+    Language { code: "grn", name: "Guarani", iso639_1: Some("gn") },
+    Language { code: "gsw", name: "Swiss German", iso639_1: None },
+    Language { code: "guj", name: "Gujarati", iso639_1: Some("gu") },
+    Language { code: "gwi", name: "Gwich'in", iso639_1: None },
+    Language { code: "hai", name: "Haida", iso639_1: None },
+    Language { code: "hat", name: "Haitian French Creole", iso639_1: Some("ht") },
+    Language { code: "hau", name: "Hausa", iso639_1: Some("ha") },
+    Language { code: "haw", name: "Hawaiian", iso639_1: None },
+    Language { code: "heb", name: "Hebrew", iso639_1: Some("he") },
+    Language { code: "her", name: "Herero", iso639_1: Some("hz") },
+    Language { code: "hil", name: "Hiligaynon", iso639_1: None },
+    Language { code: "him", name: "Western Pahari languages", iso639_1: None },
+    Language { code: "hin", name: "Hindi", iso639_1: Some("hi") },
+    Language { code: "hit", name: "Hittite", iso639_1: None },
+    Language { code: "hmn", name: "Hmong", iso639_1: None },
+    Language { code: "hmo", name: "Hiri Motu", iso639_1: Some("ho") },
+    Language { code: "hrv", name: "Croatian", iso639_1: Some("hr") },
+    Language { code: "hsb", name: "Upper Sorbian", iso639_1: None },
+    Language { code: "hun", name: "Hungarian", iso639_1: Some("hu") },
+    Language { code: "hup", name: "Hupa", iso639_1: None },
+    Language { code: "iba", name: "Iban", iso639_1: None },
+    Language { code: "ibo", name: "Igbo", iso639_1: Some("ig") },
+    Language { code: "ice", name: "Icelandic", iso639_1: Some("is") },
+    Language { code: "ido", name: "Ido", iso639_1: Some("io") },
+    Language { code: "iii", name: "Sichuan Yi", iso639_1: Some("ii") },
+    Language { code: "ijo", name: "Ijo", iso639_1: None },
+    Language { code: "iku", name: "Inuktitut", iso639_1: Some("iu") },
+    Language { code: "ile", name: "Interlingue", iso639_1: Some("ie") },
+    Language { code: "ilo", name: "Iloko", iso639_1: None },
+    Language { code: "ina", name: "Interlingua (International Auxiliary Language Association)", iso639_1: Some("ia") },
+    Language { code: "inc", name: "Indic (Other)", iso639_1: None },
+    Language { code: "ind", name: "Indonesian", iso639_1: Some("id") },
+    Language { code: "ine", name: "Indo-European (Other)", iso639_1: None },
+    Language { code: "inh", name: "Ingush", iso639_1: None },
+    Language { code: "ipk", name: "Inupiaq", iso639_1: Some("ik") },
+    Language { code: "ira", name: "Iranian (Other)", iso639_1: None },
+    Language { code: "iro", name: "Iroquoian (Other)", iso639_1: None },
+    Language { code: "ita", name: "Italian", iso639_1: Some("it") },
+    Language { code: "jav", name: "Javanese", iso639_1: Some("jv") },
+    Language { code: "jbo", name: "Lojban (Artificial language)", iso639_1: None },
+    Language { code: "jpn", name: "Japanese", iso639_1: Some("ja") },
+    Language { code: "jpr", name: "Judeo-Persian", iso639_1: None },
+    Language { code: "jrb", name: "Judeo-Arabic", iso639_1: None },
+    Language { code: "kaa", name: "Kara-Kalpak", iso639_1: None },
+    Language { code: "kab", name: "Kabyle", iso639_1: None },
+    Language { code: "kac", name: "Kachin", iso639_1: None },
+    Language { code: "kal", name: "Kalâtdlisut", iso639_1: Some("kl") },
+    Language { code: "kam", name: "Kamba", iso639_1: None },
+    Language { code: "kan", name: "Kannada", iso639_1: Some("kn") },
+    Language { code: "kar", name: "Karen languages", iso639_1: None },
+    Language { code: "kas", name: "Kashmiri", iso639_1: Some("ks") },
+    Language { code: "kau", name: "Kanuri", iso639_1: Some("kr") },
+    Language { code: "kaw", name: "Kawi", iso639_1: None },
+    Language { code: "kaz", name: "Kazakh", iso639_1: Some("kk") },
+    Language { code: "kbd", name: "Kabardian", iso639_1: None },
+    Language { code: "kha", name: "Khasi", iso639_1: None },
+    Language { code: "khi", name: "Khoisan (Other)", iso639_1: None },
+    Language { code: "khm", name: "Khmer", iso639_1: Some("km") },
+    Language { code: "kho", name: "Khotanese", iso639_1: None },
+    Language { code: "kik", name: "Kikuyu", iso639_1: Some("ki") },
+    Language { code: "kin", name: "Kinyarwanda", iso639_1: Some("rw") },
+    Language { code: "kir", name: "Kyrgyz", iso639_1: Some("ky") },
+    Language { code: "kmb", name: "Kimbundu", iso639_1: None },
+    Language { code: "kok", name: "Konkani", iso639_1: None },
+    Language { code: "kom", name: "Komi", iso639_1: Some("kv") },
+    Language { code: "kon", name: "Kongo", iso639_1: Some("kg") },
+    Language { code: "kor", name: "Korean", iso639_1: Some("ko") },
+    Language { code: "kos", name: "Kosraean", iso639_1: None },
+    Language { code: "kpe", name: "Kpelle", iso639_1: None },
+    Language { code: "krc", name: "Karachay-Balkar", iso639_1: None },
+    Language { code: "krl", name: "Karelian", iso639_1: None },
+    Language { code: "kro", name: "Kru (Other)", iso639_1: None },
+    Language { code: "kru", name: "Kurukh", iso639_1: None },
+    Language { code: "kua", name: "Kuanyama", iso639_1: Some("kj") },
+    Language { code: "kum", name: "Kumyk", iso639_1: None },
+    Language { code: "kur", name: "Kurdish", iso639_1: Some("ku") },
+    Language { code: "kut", name: "Kootenai", iso639_1: None },
+    Language { code: "lad", name: "Ladino", iso639_1: None },
+    Language { code: "lah", name: "Lahndā", iso639_1: None },
+    Language { code: "lam", name: "Lamba (Zambia and Congo)", iso639_1: None },
+    Language { code: "lao", name: "Lao", iso639_1: Some("lo") },
+    Language { code: "lat", name: "Latin", iso639_1: Some("la") },
+    Language { code: "lav", name: "Latvian", iso639_1: Some("lv") },
+    Language { code: "lez", name: "Lezgian", iso639_1: None },
+    Language { code: "lim", name: "Limburgish", iso639_1: Some("li") },
+    Language { code: "lin", name: "Lingala", iso639_1: Some("ln") },
+    Language { code: "lit", name: "Lithuanian", iso639_1: Some("lt") },
+    Language { code: "lol", name: "Mongo-Nkundu", iso639_1: None },
+    Language { code: "loz", name: "Lozi", iso639_1: None },
+    Language { code: "ltz", name: "Luxembourgish", iso639_1: Some("lb") },
+    Language { code: "lua", name: "Luba-Lulua", iso639_1: None },
+    Language { code: "lub", name: "Luba-Katanga", iso639_1: Some("lu") },
+    Language { code: "lug", name: "Ganda", iso639_1: Some("lg") },
+    Language { code: "lui", name: "Luiseño", iso639_1: None },
+    Language { code: "lun", name: "Lunda", iso639_1: None },
+    Language { code: "luo", name: "Luo (Kenya and Tanzania)", iso639_1: None },
+    Language { code: "lus", name: "Mizo", iso639_1: None },
+    Language { code: "mac", name: "Macedonian", iso639_1: Some("mk") },
+    Language { code: "mad", name: "Madurese", iso639_1: None },
+    Language { code: "mag", name: "Magahi", iso639_1: None },
+    Language { code: "mah", name: "Marshallese", iso639_1: Some("mh") },
+    Language { code: "mai", name: "Maithili", iso639_1: None },
+    Language { code: "mak", name: "Makasar", iso639_1: None },
+    Language { code: "mal", name: "Malayalam", iso639_1: Some("ml") },
+    Language { code: "man", name: "Mandingo", iso639_1: None },
+    Language { code: "mao", name: "Maori", iso639_1: Some("mi") },
+    Language { code: "map", name: "Austronesian (Other)", iso639_1: None },
+    Language { code: "mar", name: "Marathi", iso639_1: Some("mr") },
+    Language { code: "mas", name: "Maasai", iso639_1: None },
+    Language { code: "may", name: "Malay", iso639_1: Some("ms") },
+    Language { code: "mdf", name: "Moksha", iso639_1: None },
+    Language { code: "mdr", name: "Mandar", iso639_1: None },
+    Language { code: "men", name: "Mende", iso639_1: None },
+    Language { code: "mga", name: "Irish, Middle (ca. 1100-1550)", iso639_1: None },
+    Language { code: "mic", name: "Micmac", iso639_1: None },
+    Language { code: "min", name: "Minangkabau", iso639_1: None },
+    Language { code: "mis", name: "Miscellaneous languages", iso639_1: None },
+    Language { code: "mkh", name: "Mon-Khmer (Other)", iso639_1: None },
+    Language { code: "mlg", name: "Malagasy", iso639_1: Some("mg") },
+    Language { code: "mlt", name: "Maltese", iso639_1: Some("mt") },
+    Language { code: "mnc", name: "Manchu", iso639_1: None },
+    Language { code: "mni", name: "Manipuri", iso639_1: None },
+    Language { code: "mno", name: "Manobo languages", iso639_1: None },
+    Language { code: "moh", name: "Mohawk", iso639_1: None },
+    Language { code: "mon", name: "Mongolian", iso639_1: Some("mn") },
+    Language { code: "mos", name: "Mooré", iso639_1: None },
+    Language { code: "mul", name: "Multiple languages", iso639_1: None },
+    Language { code: "mun", name: "Munda (Other)", iso639_1: None },
+    Language { code: "mus", name: "Creek", iso639_1: None },
+    Language { code: "mwl", name: "Mirandese", iso639_1: None },
+    Language { code: "mwr", name: "Marwari", iso639_1: None },
+    Language { code: "myn", name: "Mayan languages", iso639_1: None },
+    Language { code: "myv", name: "Erzya", iso639_1: None },
+    Language { code: "nah", name: "Nahuatl", iso639_1: None },
+    Language { code: "nai", name: "North American Indian (Other)", iso639_1: None },
+    Language { code: "nap", name: "Neapolitan Italian", iso639_1: None },
+    Language { code: "nau", name: "Nauru", iso639_1: Some("na") },
+    Language { code: "nav", name: "Navajo", iso639_1: Some("nv") },
+    Language { code: "nbl", name: "Ndebele (South Africa)", iso639_1: Some("nr") },
+    Language { code: "nde", name: "Ndebele (Zimbabwe)", iso639_1: Some("nd") },
+    Language { code: "ndo", name: "Ndonga", iso639_1: Some("ng") },
+    Language { code: "nds", name: "Low German", iso639_1: None },
+    Language { code: "nep", name: "Nepali", iso639_1: Some("ne") },
+    Language { code: "new", name: "Newari", iso639_1: None },
+    Language { code: "nia", name: "Nias", iso639_1: None },
+    Language { code: "nic", name: "Niger-Kordofanian (Other)", iso639_1: None },
+    Language { code: "niu", name: "Niuean", iso639_1: None },
+    Language { code: "nno", name: "Norwegian (Nynorsk)", iso639_1: Some("nn") },
+    Language { code: "nob", name: "Norwegian (Bokmål)", iso639_1: Some("nb") },
+    Language { code: "nog", name: "Nogai", iso639_1: None },
+    Language { code: "non", name: "Old Norse", iso639_1: None },
+    Language { code: "nor", name: "Norwegian", iso639_1: Some("no") },
+    Language { code: "nqo", name: "N'Ko", iso639_1: None },
+    Language { code: "nso", name: "Northern Sotho", iso639_1: None },
+    Language { code: "nub", name: "Nubian languages", iso639_1: None },
+    Language { code: "nwc", name: "Newari, Old", iso639_1: None },
+    Language { code: "nya", name: "Nyanja", iso639_1: Some("ny") },
+    Language { code: "nym", name: "Nyamwezi", iso639_1: None },
+    Language { code: "nyn", name: "Nyankole", iso639_1: None },
+    Language { code: "nyo", name: "Nyoro", iso639_1: None },
+    Language { code: "nzi", name: "Nzima", iso639_1: None },
+    Language { code: "oci", name: "Occitan (post-1500)", iso639_1: Some("oc") },
+    Language { code: "oji", name: "Ojibwa", iso639_1: Some("oj") },
+    Language { code: "ori", name: "Oriya", iso639_1: Some("or") },
+    Language { code: "orm", name: "Oromo", iso639_1: Some("om") },
+    Language { code: "osa", name: "Osage", iso639_1: None },
+    Language { code: "oss", name: "Ossetic", iso639_1: Some("os") },
+    Language { code: "ota", name: "Turkish, Ottoman", iso639_1: None },
+    Language { code: "oto", name: "Otomian languages", iso639_1: None },
+    Language { code: "paa", name: "Papuan (Other)", iso639_1: None },
+    Language { code: "pag", name: "Pangasinan", iso639_1: None },
+    Language { code: "pal", name: "Pahlavi", iso639_1: None },
+    Language { code: "pam", name: "Pampanga", iso639_1: None },
+    Language { code: "pan", name: "Panjabi", iso639_1: Some("pa") },
+    Language { code: "pap", name: "Papiamento", iso639_1: None },
+    Language { code: "pau", name: "Palauan", iso639_1: None },
+    Language { code: "peo", name: "Old Persian (ca. 600-400 B.C.)", iso639_1: None },
+    Language { code: "per", name: "Persian", iso639_1: Some("fa") },
+    Language { code: "phi", name: "Philippine (Other)", iso639_1: None },
+    Language { code: "phn", name: "Phoenician", iso639_1: None },
+    Language { code: "pli", name: "Pali", iso639_1: Some("pi") },
+    Language { code: "pol", name: "Polish", iso639_1: Some("pl") },
+    Language { code: "pon", name: "Pohnpeian", iso639_1: None },
+    Language { code: "por", name: "Portuguese", iso639_1: Some("pt") },
+    Language { code: "pra", name: "Prakrit languages", iso639_1: None },
+    Language { code: "pro", name: "Provençal (to 1500)", iso639_1: None },
+    Language { code: "pus", name: "Pushto", iso639_1: Some("ps") },
+    Language { code: "que", name: "Quechua", iso639_1: Some("qu") },
+    Language { code: "raj", name: "Rajasthani", iso639_1: None },
+    Language { code: "rap", name: "Rapanui", iso639_1: None },
+    Language { code: "rar", name: "Rarotongan", iso639_1: None },
+    Language { code: "roa", name: "Romance (Other)", iso639_1: None },
+    Language { code: "roh", name: "Raeto-Romance", iso639_1: Some("rm") },
+    Language { code: "rom", name: "Romani", iso639_1: None },
+    Language { code: "rum", name: "Romanian", iso639_1: Some("ro") },
+    Language { code: "run", name: "Rundi", iso639_1: Some("rn") },
+    Language { code: "rup", name: "Aromanian", iso639_1: None },
+    Language { code: "rus", name: "Russian", iso639_1: Some("ru") },
+    Language { code: "sad", name: "Sandawe", iso639_1: None },
+    Language { code: "sag", name: "Sango (Ubangi Creole)", iso639_1: Some("sg") },
+    Language { code: "sah", name: "Yakut", iso639_1: None },
+    Language { code: "sai", name: "South American Indian (Other)", iso639_1: None },
+    Language { code: "sal", name: "Salishan languages", iso639_1: None },
+    Language { code: "sam", name: "Samaritan Aramaic", iso639_1: None },
+    Language { code: "san", name: "Sanskrit", iso639_1: Some("sa") },
+    Language { code: "sas", name: "Sasak", iso639_1: None },
+    Language { code: "sat", name: "Santali", iso639_1: None },
+    Language { code: "scn", name: "Sicilian Italian", iso639_1: None },
+    Language { code: "sco", name: "Scots", iso639_1: None },
+    Language { code: "sel", name: "Selkup", iso639_1: None },
+    Language { code: "sem", name: "Semitic (Other)", iso639_1: None },
+    Language { code: "sga", name: "Irish, Old (to 1100)", iso639_1: None },
+    Language { code: "sgn", name: "Sign languages", iso639_1: None },
+    Language { code: "shn", name: "Shan", iso639_1: None },
+    Language { code: "sid", name: "Sidamo", iso639_1: None },
+    Language { code: "sin", name: "Sinhalese", iso639_1: Some("si") },
+    Language { code: "sio", name: "Siouan (Other)", iso639_1: None },
+    Language { code: "sit", name: "Sino-Tibetan (Other)", iso639_1: None },
+    Language { code: "sla", name: "Slavic (Other)", iso639_1: None },
+    Language { code: "slo", name: "Slovak", iso639_1: Some("sk") },
+    Language { code: "slv", name: "Slovenian", iso639_1: Some("sl") },
+    Language { code: "sma", name: "Southern Sami", iso639_1: None },
+    Language { code: "sme", name: "Northern Sami", iso639_1: Some("se") },
+    Language { code: "smi", name: "Sami", iso639_1: None },
+    Language { code: "smj", name: "Lule Sami", iso639_1: None },
+    Language { code: "smn", name: "Inari Sami", iso639_1: None },
+    Language { code: "smo", name: "Samoan", iso639_1: Some("sm") },
+    Language { code: "sms", name: "Skolt Sami", iso639_1: None },
+    Language { code: "sna", name: "Shona", iso639_1: Some("sn") },
+    Language { code: "snd", name: "Sindhi", iso639_1: Some("sd") },
+    Language { code: "snk", name: "Soninke", iso639_1: None },
+    Language { code: "sog", name: "Sogdian", iso639_1: None },
+    Language { code: "som", name: "Somali", iso639_1: Some("so") },
+    Language { code: "son", name: "Songhai", iso639_1: None },
+    Language { code: "sot", name: "Sotho", iso639_1: Some("st") },
+    Language { code: "spa", name: "Spanish", iso639_1: Some("es") },
+    Language { code: "srd", name: "Sardinian", iso639_1: Some("sc") },
+    Language { code: "srn", name: "Sranan", iso639_1: None },
+    Language { code: "srp", name: "Serbian", iso639_1: Some("sr") },
+    Language { code: "srr", name: "Serer", iso639_1: None },
+    Language { code: "ssa", name: "Nilo-Saharan (Other)", iso639_1: None },
+    Language { code: "ssw", name: "Swazi", iso639_1: Some("ss") },
+    Language { code: "suk", name: "Sukuma", iso639_1: None },
+    Language { code: "sun", name: "Sundanese", iso639_1: Some("su") },
+    Language { code: "sus", name: "Susu", iso639_1: None },
+    Language { code: "sux", name: "Sumerian", iso639_1: None },
+    Language { code: "swa", name: "Swahili", iso639_1: Some("sw") },
+    Language { code: "swe", name: "Swedish", iso639_1: Some("sv") },
+    Language { code: "syc", name: "Syriac", iso639_1: None },
+    Language { code: "syr", name: "Syriac, Modern", iso639_1: None },
+    Language { code: "tah", name: "Tahitian", iso639_1: Some("ty") },
+    Language { code: "tai", name: "Tai (Other)", iso639_1: None },
+    Language { code: "tam", name: "Tamil", iso639_1: Some("ta") },
+    Language { code: "tat", name: "Tatar", iso639_1: Some("tt") },
+    Language { code: "tel", name: "Telugu", iso639_1: Some("te") },
+    Language { code: "tem", name: "Temne", iso639_1: None },
+    Language { code: "ter", name: "Terena", iso639_1: None },
+    Language { code: "tet", name: "Tetum", iso639_1: None },
+    Language { code: "tgk", name: "Tajik", iso639_1: Some("tg") },
+    Language { code: "tgl", name: "Tagalog", iso639_1: Some("tl") },
+    Language { code: "tha", name: "Thai", iso639_1: Some("th") },
+    Language { code: "tib", name: "Tibetan", iso639_1: Some("bo") },
+    Language { code: "tig", name: "Tigré", iso639_1: None },
+    Language { code: "tir", name: "Tigrinya", iso639_1: Some("ti") },
+    Language { code: "tiv", name: "Tiv", iso639_1: None },
+    Language { code: "tkl", name: "Tokelauan", iso639_1: None },
+    Language { code: "tlh", name: "Klingon (Artificial language)", iso639_1: None },
+    Language { code: "tli", name: "Tlingit", iso639_1: None },
+    Language { code: "tmh", name: "Tamashek", iso639_1: None },
+    Language { code: "tog", name: "Tonga (Nyasa)", iso639_1: None },
+    Language { code: "ton", name: "Tongan", iso639_1: Some("to") },
+    Language { code: "tpi", name: "Tok Pisin", iso639_1: None },
+    Language { code: "tsi", name: "Tsimshian", iso639_1: None },
+    Language { code: "tsn", name: "Tswana", iso639_1: Some("tn") },
+    Language { code: "tso", name: "Tsonga", iso639_1: Some("ts") },
+    Language { code: "tuk", name: "Turkmen", iso639_1: Some("tk") },
+    Language { code: "tum", name: "Tumbuka", iso639_1: None },
+    Language { code: "tup", name: "Tupi languages", iso639_1: None },
+    Language { code: "tur", name: "Turkish", iso639_1: Some("tr") },
+    Language { code: "tut", name: "Altaic (Other)", iso639_1: None },
+    Language { code: "tvl", name: "Tuvaluan", iso639_1: None },
+    Language { code: "twi", name: "Twi", iso639_1: Some("tw") },
+    Language { code: "tyv", name: "Tuvinian", iso639_1: None },
+    Language { code: "udm", name: "Udmurt", iso639_1: None },
+    Language { code: "uga", name: "Ugaritic", iso639_1: None },
+    Language { code: "uig", name: "Uighur", iso639_1: Some("ug") },
+    Language { code: "ukr", name: "Ukrainian", iso639_1: Some("uk") },
+    Language { code: "umb", name: "Umbundu", iso639_1: None },
+    Language { code: "und", name: "Undetermined", iso639_1: None },
+    Language { code: "urd", name: "Urdu", iso639_1: Some("ur") },
+    Language { code: "uzb", name: "Uzbek", iso639_1: Some("uz") },
+    Language { code: "vai", name: "Vai", iso639_1: None },
+    Language { code: "ven", name: "Venda", iso639_1: Some("ve") },
+    Language { code: "vie", name: "Vietnamese", iso639_1: Some("vi") },
+    Language { code: "vol", name: "Volapük", iso639_1: Some("vo") },
+    Language { code: "vot", name: "Votic", iso639_1: None },
+    Language { code: "wak", name: "Wakashan languages", iso639_1: None },
+    Language { code: "wal", name: "Wolayta", iso639_1: None },
+    Language { code: "war", name: "Waray", iso639_1: None },
+    Language { code: "was", name: "Washoe", iso639_1: None },
+    Language { code: "wel", name: "Welsh", iso639_1: Some("cy") },
+    Language { code: "wen", name: "Sorbian (Other)", iso639_1: None },
+    Language { code: "wln", name: "Walloon", iso639_1: Some("wa") },
+    Language { code: "wol", name: "Wolof", iso639_1: Some("wo") },
+    Language { code: "xal", name: "Oirat", iso639_1: None },
+    Language { code: "xho", name: "Xhosa", iso639_1: Some("xh") },
+    Language { code: "yao", name: "Yao (Africa)", iso639_1: None },
+    Language { code: "yap", name: "Yapese", iso639_1: None },
+    Language { code: "yid", name: "Yiddish", iso639_1: Some("yi") },
+    Language { code: "yor", name: "Yoruba", iso639_1: Some("yo") },
+    Language { code: "ypk", name: "Yupik languages", iso639_1: None },
+    Language { code: "zap", name: "Zapotec", iso639_1: None },
+    Language { code: "zbl", name: "Blissymbolics", iso639_1: None },
+    Language { code: "zen", name: "Zenaga", iso639_1: None },
+    Language { code: "zha", name: "Zhuang", iso639_1: Some("za") },
+    Language { code: "znd", name: "Zande languages", iso639_1: None },
+    Language { code: "zul", name: "Zulu", iso639_1: Some("zu") },
+    Language { code: "zun", name: "Zuni", iso639_1: None },
+    Language { code: "zxx", name: "No linguistic content", iso639_1: None },
+    Language { code: "zza", name: "Zaza", iso639_1: None },
+];
+
+/// Discontinued MARC language codes
+const DISCONTINUED_LANGUAGES: &[DiscontinuedLanguage] = &[
+    DiscontinuedLanguage { code: "ajm", name: "Aljamía", replaced_by: None },
+    DiscontinuedLanguage { code: "cam", name: "Khmer", replaced_by: Some("khm") },
+    DiscontinuedLanguage { code: "esk", name: "Eskimo languages", replaced_by: None },
+    DiscontinuedLanguage { code: "esp", name: "Esperanto", replaced_by: Some("epo") },
+    DiscontinuedLanguage { code: "eth", name: "Ethiopic", replaced_by: Some("gez") },
+    DiscontinuedLanguage { code: "far", name: "Faroese", replaced_by: Some("fao") },
+    DiscontinuedLanguage { code: "fri", name: "Frisian", replaced_by: Some("fry") },
+    DiscontinuedLanguage { code: "gae", name: "Scottish Gaelic", replaced_by: Some("gla") },
+    DiscontinuedLanguage { code: "gag", name: "Galician", replaced_by: Some("glg") },
+    DiscontinuedLanguage { code: "gua", name: "Guarani", replaced_by: Some("grn") },
+    DiscontinuedLanguage { code: "int", name: "Interlingua (International Auxiliary Language Association)", replaced_by: Some("ina") },
+    DiscontinuedLanguage { code: "iri", name: "Irish", replaced_by: Some("gle") },
+    DiscontinuedLanguage { code: "kus", name: "Kusaie", replaced_by: Some("kos") },
+    DiscontinuedLanguage { code: "lan", name: "Occitan (post 1500)", replaced_by: Some("oci") },
+    DiscontinuedLanguage { code: "lap", name: "Sami", replaced_by: Some("smi") },
+    DiscontinuedLanguage { code: "mla", name: "Malagasy", replaced_by: Some("mlg") },
+    DiscontinuedLanguage { code: "max", name: "Manx", replaced_by: Some("glv") },
+    DiscontinuedLanguage { code: "mol", name: "Moldavian", replaced_by: Some("rum") },
+    DiscontinuedLanguage { code: "sao", name: "Samoan", replaced_by: Some("smo") },
+    DiscontinuedLanguage { code: "scc", name: "Serbian", replaced_by: Some("srp") },
+    DiscontinuedLanguage { code: "scr", name: "Croatian", replaced_by: Some("hrv") },
+    DiscontinuedLanguage { code: "sho", name: "Shona", replaced_by: Some("sna") },
+    DiscontinuedLanguage { code: "snh", name: "Sinhalese", replaced_by: Some("sin") },
+    DiscontinuedLanguage { code: "sso", name: "Sotho", replaced_by: Some("sot") },
+    DiscontinuedLanguage { code: "swz", name: "Swazi", replaced_by: Some("ssw") },
+    DiscontinuedLanguage { code: "tag", name: "Tagalog", replaced_by: Some("tgl") },
+    DiscontinuedLanguage { code: "taj", name: "Tajik", replaced_by: Some("tgk") },
+    DiscontinuedLanguage { code: "tar", name: "Tatar", replaced_by: Some("tat") },
+    DiscontinuedLanguage { code: "tsw", name: "Tswana", replaced_by: Some("tsn") },
+];
@@ -6,9 +6,23 @@
 //!
 //! - Support for MARC21, UNIMARC, and MARC XML formats
 //! - Multiple character encodings (UTF-8, MARC-8, ISO-8859-*)
-//! - Parse multiple records from a single buffer
+//! - Parse multiple records from a single buffer, or stream them from a reader
 //! - Write single or multiple records
 //! - Optional Serde support for serialization/deserialization
+//! - Optional `#[derive(MarcRecord)]` mapping of structs to records (`derive` feature)
+//! - Dublin Core and MODS crosswalks, CSL-JSON export, ONIX 3.0 import
+//! - Work and expression clustering (FRBR) for consolidated displays
+//! - Declarative record-processing pipelines: filter, transform, validate and split, in bounded memory
+//! - Compact records with inline tags and interned values, for large collections held in memory
+//! - Thread-safe duplicate index by control number, system number, ISBN or match key
+//! - Record patches: batch edits (add, delete, replace, copy, move) read from TOML or JSON
+//! - Collection profiling: tag, indicator and subfield frequencies, value lengths, encoding problems
+//! - Authority identifiers in `$0`/`$1`: parsing, validation, normalization and linking
+//! - Publication dates from coded dates and statement dates, with earliest and latest years
+//! - ISBN validation, ISBN-10/13 conversion and hyphenation; ISSN and LCCN validation and normalization
+//! - Optional OAI-PMH harvesting of MARC XML records (`oai` feature)
+//! - Optional SRU search of MARC XML catalogues (`sru` feature)
+//! - Optional Z39.50 client for USMARC and UNIMARC targets (`z3950` feature)
 //!
 //! ## Examples
 //!
@@ -73,16 +87,100 @@
 //! - [MARC XML Schema](https://www.loc.gov/standards/marcxml/schema/MARC21slim.xsd)
 //! - [UNIMARC Manual](https://www.transition-bibliographique.fr/unimarc/manuel-unimarc-format-bibliographique/)
 
+pub mod authority_link;
+pub mod convert;
+pub mod dates;
+pub mod dedup;
+pub mod diff;
+pub mod electronic_location;
 pub mod encoding;
+#[cfg(feature = "serde")]
+mod extract;
 pub mod fields;
+pub mod filing;
+pub mod fixed_fields;
+pub mod flat;
 pub mod format;
+pub mod frbr;
+pub mod geographic;
+pub mod identifiers;
+pub mod intern;
+#[cfg(feature = "serde")]
+pub mod json;
+pub mod language;
+pub mod linkage;
+pub mod lint;
+pub mod mapping;
+pub mod matching;
+pub mod mrk;
+#[cfg(feature = "oai")]
+pub mod oai;
 pub mod parser;
+pub mod patch;
+pub mod pipeline;
+pub mod profile;
+pub mod publication;
+pub mod punctuation;
+pub mod rda;
+pub mod reader;
 pub mod record;
+pub mod recovery;
+pub mod relator;
+pub mod spec;
+#[cfg(feature = "sru")]
+pub mod sru;
+pub mod validation;
 pub mod writer;
+#[cfg(feature = "z3950")]
+pub mod z3950;
 pub mod helpers;
+pub use authority_link::*;
+pub use convert::*;
+pub use dates::*;
+pub use dedup::*;
+pub use diff::*;
+pub use electronic_location::*;
 pub use encoding::*;
 pub use fields::*;
+pub use filing::*;
+pub use fixed_fields::*;
+pub use flat::*;
 pub use format::*;
+pub use frbr::*;
+pub use geographic::*;
+pub use identifiers::*;
+pub use intern::*;
+#[cfg(feature = "serde")]
+pub use json::*;
+pub use language::*;
+pub use linkage::*;
+pub use lint::*;
+pub use mapping::{FromRecord, ToRecord};
+pub use matching::*;
+pub use mrk::*;
+#[cfg(feature = "oai")]
+pub use oai::*;
 pub use parser::*;
+pub use patch::*;
+pub use pipeline::*;
+pub use profile::*;
+pub use publication::*;
+pub use punctuation::*;
+pub use rda::*;
+pub use reader::*;
 pub use record::*;
+pub use recovery::*;
+pub use relator::*;
+pub use spec::*;
+#[cfg(feature = "sru")]
+pub use sru::*;
+pub use validation::*;
 pub use writer::*;
+#[cfg(feature = "z3950")]
+pub use z3950::*;
+
+#[cfg(feature = "serde")]
+pub use helpers as serde_marc;
+
+#[cfg(feature = "derive")]
+pub use marc_derive::MarcRecord;
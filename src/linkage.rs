@@ -0,0 +1,221 @@
+use std::fmt::Display;
+
+/// Script identification code carried in a `$6` linkage (e.g. `(3` for Arabic)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScriptCode {
+    /// `(3` - Arabic
+    Arabic,
+    /// `(B` - Latin
+    Latin,
+    /// `$1` - Chinese, Japanese, Korean
+    Cjk,
+    /// `(N` - Cyrillic
+    Cyrillic,
+    /// `(S` - Greek
+    Greek,
+    /// `(2` - Hebrew
+    Hebrew,
+    /// Any other (locally defined or unknown) script code
+    Other(String),
+}
+
+impl ScriptCode {
+    /// Get the code as it appears in the subfield
+    pub fn code(&self) -> &str {
+        match self {
+            ScriptCode::Arabic => "(3",
+            ScriptCode::Latin => "(B",
+            ScriptCode::Cjk => "$1",
+            ScriptCode::Cyrillic => "(N",
+            ScriptCode::Greek => "(S",
+            ScriptCode::Hebrew => "(2",
+            ScriptCode::Other(code) => code,
+        }
+    }
+}
+
+impl From<&str> for ScriptCode {
+    fn from(s: &str) -> Self {
+        match s {
+            "(3" => ScriptCode::Arabic,
+            "(B" => ScriptCode::Latin,
+            "$1" => ScriptCode::Cjk,
+            "(N" => ScriptCode::Cyrillic,
+            "(S" => ScriptCode::Greek,
+            "(2" => ScriptCode::Hebrew,
+            other => ScriptCode::Other(other.to_string()),
+        }
+    }
+}
+
+/// Parsed `$6` linkage subfield, e.g. `880-01/(2/r`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Linkage {
+    /// Tag of the associated field (`880` in a regular field, the regular tag in an 880)
+    pub linking_tag: String,
+    /// Occurrence number; `0` means there is no associated field
+    pub occurrence: u16,
+    /// Number of digits used for the occurrence number (usually 2)
+    pub occurrence_width: usize,
+    /// Script identification code, if present
+    pub script: Option<ScriptCode>,
+    /// True when the field orientation code is `r` (right-to-left)
+    pub right_to_left: bool,
+}
+
+impl Linkage {
+    /// Parse a `$6` value
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let mut parts = value.trim().splitn(3, '/');
+        let head = parts.next().unwrap_or_default();
+
+        let (linking_tag, occurrence) = head.split_once('-').ok_or_else(|| format!("Missing occurrence number in linkage: {}", value))?;
+        if linking_tag.len() != 3 || !linking_tag.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(format!("Invalid linking tag in linkage: {}", value));
+        }
+        if occurrence.is_empty() || !occurrence.chars().all(|c| c.is_ascii_digit()) {
+            return Err(format!("Invalid occurrence number in linkage: {}", value));
+        }
+        let occurrence_width = occurrence.len();
+        let occurrence = occurrence.parse::<u16>().map_err(|e| format!("Invalid occurrence number: {}", e))?;
+
+        let script = parts.next().filter(|s| !s.is_empty()).map(ScriptCode::from);
+
+        let right_to_left = match parts.next() {
+            None | Some("") => false,
+            Some("r") => true,
+            Some(other) => return Err(format!("Invalid field orientation code in linkage: {}", other)),
+        };
+
+        Ok(Linkage {
+            linking_tag: linking_tag.to_string(),
+            occurrence,
+            occurrence_width,
+            script,
+            right_to_left,
+        })
+    }
+
+    /// True when the occurrence number is `00` (880 field without an associated regular field)
+    pub fn is_unassociated(&self) -> bool {
+        self.occurrence == 0
+    }
+}
+
+impl Display for Linkage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{:0width$}", self.linking_tag, self.occurrence, width = self.occurrence_width)?;
+        match (&self.script, self.right_to_left) {
+            (Some(script), true) => write!(f, "/{}/r", script.code()),
+            (Some(script), false) => write!(f, "/{}", script.code()),
+            (None, true) => write!(f, "//r"),
+            (None, false) => Ok(()),
+        }
+    }
+}
+
+/// Field link type carried in a `$8` subfield
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldLinkType {
+    /// `a` - Action
+    Action,
+    /// `c` - Constituent item
+    ConstituentItem,
+    /// `p` - Metadata provenance
+    MetadataProvenance,
+    /// `r` - Reproduction
+    Reproduction,
+    /// `u` - General linking, type unspecified
+    GeneralLinking,
+    /// `x` - General sequencing
+    GeneralSequencing,
+}
+
+impl FieldLinkType {
+    /// Get the type from its code
+    pub fn from_code(code: char) -> Option<Self> {
+        match code {
+            'a' => Some(FieldLinkType::Action),
+            'c' => Some(FieldLinkType::ConstituentItem),
+            'p' => Some(FieldLinkType::MetadataProvenance),
+            'r' => Some(FieldLinkType::Reproduction),
+            'u' => Some(FieldLinkType::GeneralLinking),
+            'x' => Some(FieldLinkType::GeneralSequencing),
+            _ => None,
+        }
+    }
+
+    /// Get the code of this type
+    pub fn code(&self) -> char {
+        match self {
+            FieldLinkType::Action => 'a',
+            FieldLinkType::ConstituentItem => 'c',
+            FieldLinkType::MetadataProvenance => 'p',
+            FieldLinkType::Reproduction => 'r',
+            FieldLinkType::GeneralLinking => 'u',
+            FieldLinkType::GeneralSequencing => 'x',
+        }
+    }
+}
+
+/// Parsed `$8` field link and sequence number, e.g. `1.5\a`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldLink {
+    /// Link number shared by the linked fields
+    pub link_number: u32,
+    /// Sequence number within the link group, if present
+    pub sequence_number: Option<u32>,
+    /// Field link type, if present
+    pub link_type: Option<FieldLinkType>,
+}
+
+impl FieldLink {
+    /// Parse a `$8` value
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        let (numbers, link_type) = match value.split_once('\\') {
+            Some((numbers, "")) => (numbers, None),
+            Some((numbers, code)) => {
+                let mut chars = code.chars();
+                let link_type = chars
+                    .next()
+                    .and_then(FieldLinkType::from_code)
+                    .filter(|_| chars.next().is_none())
+                    .ok_or_else(|| format!("Invalid field link type in field link: {}", value))?;
+                (numbers, Some(link_type))
+            }
+            None => (value, None),
+        };
+
+        let (link_number, sequence_number) = match numbers.split_once('.') {
+            Some((link, sequence)) => (link, Some(sequence)),
+            None => (numbers, None),
+        };
+
+        let link_number = link_number
+            .parse::<u32>()
+            .map_err(|e| format!("Invalid link number in field link {}: {}", value, e))?;
+        let sequence_number = sequence_number
+            .map(|s| s.parse::<u32>().map_err(|e| format!("Invalid sequence number in field link {}: {}", value, e)))
+            .transpose()?;
+
+        Ok(FieldLink {
+            link_number,
+            sequence_number,
+            link_type,
+        })
+    }
+}
+
+impl Display for FieldLink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.link_number)?;
+        if let Some(sequence) = self.sequence_number {
+            write!(f, ".{}", sequence)?;
+        }
+        if let Some(link_type) = self.link_type {
+            write!(f, "\\{}", link_type.code())?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,168 @@
+//! Lint rules: cataloguing policies checked on top of structural validation.
+//!
+//! A [`RuleSet`] is a list of [`Rule`]s, each reporting [`Issue`]s with its own
+//! severity: required or forbidden fields, fields that must not repeat, allowed
+//! values, well-formed ISBNs, and the [`Validator`] checks themselves. Named
+//! sets cover common needs, see [`RuleSet::named`].
+//!
+//! ```
+//! use marc_rs::{DataField, Leader, MarcFormat, MarcSpec, Record, Rule, RuleKind, RuleSet, Severity, Subfield};
+//!
+//! let record = Record {
+//!     leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+//!     control_fields: vec![],
+//!     data_fields: vec![DataField {
+//!         tag: "020".to_string(),
+//!         ind1: ' ',
+//!         ind2: ' ',
+//!         subfields: vec![Subfield { code: 'a', value: "2-07-036822-9 (pbk.)".to_string() }],
+//!     }],
+//! };
+//!
+//! let issues = RuleSet::named("marc21-minimal").unwrap().check(&record, MarcFormat::Marc21);
+//! let messages: Vec<String> = issues.iter().map(|issue| issue.to_string()).collect();
+//! assert_eq!(messages, ["error 001: missing field", "error 008: missing field", "error 245$a: missing field", "error 020[0]$a: invalid ISBN \"2-07-036822-9\""]);
+//!
+//! let rules = RuleSet::new(vec![Rule::new(RuleKind::Forbidden(MarcSpec::parse("9..").unwrap()), Severity::Warning)]);
+//! assert!(rules.check(&record, MarcFormat::Marc21).is_empty());
+//! ```
+
+use crate::format::MarcFormat;
+use crate::identifiers::Isbn;
+use crate::record::Record;
+use crate::spec::{MarcSpec, SpecTarget};
+use crate::validation::{field_spec, Issue, Severity, Validator};
+
+/// What a [`Rule`] checks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleKind {
+    /// The [`Validator`] checks, reported with their own severities
+    Structure,
+    /// The spec must select a value, e.g. `245$a`
+    Required(MarcSpec),
+    /// The spec must select nothing, e.g. `9..`
+    Forbidden(MarcSpec),
+    /// Fields with a tag matching the spec's must not repeat, whatever the format says
+    NotRepeatable(MarcSpec),
+    /// Every value selected by the spec must be one of these, e.g. `LDR/06` in `a`, `c`, `m`
+    Values(MarcSpec, Vec<String>),
+    /// ISBNs (020$a, or 010$a in UNIMARC) must be ISBN-10 or ISBN-13 with a valid check digit
+    Isbn,
+}
+
+/// A check and the severity of the issues it reports
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pub kind: RuleKind,
+    pub severity: Severity,
+}
+
+impl Rule {
+    pub fn new(kind: RuleKind, severity: Severity) -> Self {
+        Self { kind, severity }
+    }
+
+    /// Issues of a record
+    pub fn check(&self, record: &Record, format: MarcFormat) -> Vec<Issue> {
+        let issue = |location: MarcSpec, message: String| Issue {
+            severity: self.severity,
+            location,
+            message,
+        };
+        match &self.kind {
+            RuleKind::Structure => Validator::new(format).validate(record),
+            RuleKind::Required(spec) if spec.values(record).is_empty() => vec![issue(spec.clone(), "missing field".to_string())],
+            RuleKind::Required(_) => Vec::new(),
+            // One issue per tag, e.g. 901 and 950 for 9..
+            RuleKind::Forbidden(spec) => distinct_tags(record, spec)
+                .into_iter()
+                .map(|tag| MarcSpec { tag: tag.to_string(), ..spec.clone() })
+                .filter(|spec| !spec.values(record).is_empty())
+                .map(|spec| issue(spec, "forbidden field".to_string()))
+                .collect(),
+            RuleKind::NotRepeatable(spec) => distinct_tags(record, spec)
+                .into_iter()
+                .filter(|tag| record.control_fields.iter().filter(|f| f.tag == *tag).count() + record.data_fields.iter().filter(|f| f.tag == *tag).count() > 1)
+                .map(|tag| issue(field_spec(tag, 1, SpecTarget::Field), "field is not repeatable".to_string()))
+                .collect(),
+            RuleKind::Values(spec, allowed) => spec.values(record).into_iter().filter(|value| !allowed.contains(value)).map(|value| issue(spec.clone(), format!("unexpected value {:?}", value))).collect(),
+            RuleKind::Isbn => {
+                let tag = match format {
+                    MarcFormat::Unimarc => "010",
+                    _ => "020",
+                };
+                record
+                    .data_fields
+                    .iter()
+                    .filter(|f| f.tag == tag)
+                    .enumerate()
+                    .flat_map(|(occurrence, field)| field.subfields.iter().filter(|s| s.code == 'a').map(move |s| (occurrence, s)))
+                    .filter_map(|(occurrence, subfield)| {
+                        let isbn = subfield.value.split_whitespace().next().unwrap_or_default();
+                        Isbn::parse(isbn).is_err().then(|| issue(field_spec(tag, occurrence, SpecTarget::Subfields(vec!['a'])), format!("invalid ISBN {:?}", isbn)))
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Rules checked together
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RuleSet {
+    pub rules: Vec<Rule>,
+}
+
+impl RuleSet {
+    /// Names of the sets returned by [`RuleSet::named`]
+    pub const NAMES: [&'static str; 3] = ["marc21-minimal", "unimarc-minimal", "structure"];
+
+    pub fn new(rules: Vec<Rule>) -> Self {
+        Self { rules }
+    }
+
+    /// Built-in set:
+    ///
+    /// - `marc21-minimal`: 001, 008 and 245$a present, a single 245, valid ISBNs
+    /// - `unimarc-minimal`: 001, 100 and 200$a present, a single 200, valid ISBNs
+    /// - `structure`: the [`Validator`] checks
+    pub fn named(name: &str) -> Option<Self> {
+        let spec = |spec: &str| MarcSpec::parse(spec).expect("valid built-in spec");
+        let required = |tag: &str| Rule::new(RuleKind::Required(spec(tag)), Severity::Error);
+        let rules = match name {
+            "marc21-minimal" => vec![
+                required("001"),
+                required("008"),
+                required("245$a"),
+                Rule::new(RuleKind::NotRepeatable(spec("245")), Severity::Error),
+                Rule::new(RuleKind::Isbn, Severity::Error),
+            ],
+            "unimarc-minimal" => vec![
+                required("001"),
+                required("100"),
+                required("200$a"),
+                Rule::new(RuleKind::NotRepeatable(spec("200")), Severity::Error),
+                Rule::new(RuleKind::Isbn, Severity::Error),
+            ],
+            "structure" => vec![Rule::new(RuleKind::Structure, Severity::Error)],
+            _ => return None,
+        };
+        Some(Self::new(rules))
+    }
+
+    /// Issues of a record, rule by rule
+    pub fn check(&self, record: &Record, format: MarcFormat) -> Vec<Issue> {
+        self.rules.iter().flat_map(|rule| rule.check(record, format)).collect()
+    }
+}
+
+/// Tags of the record matching the spec, each once, in record order
+fn distinct_tags<'a>(record: &'a Record, spec: &MarcSpec) -> Vec<&'a str> {
+    let mut tags: Vec<&str> = Vec::new();
+    for tag in record.control_fields.iter().map(|f| f.tag.as_str()).chain(record.data_fields.iter().map(|f| f.tag.as_str())) {
+        if spec.matches_tag(tag) && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
@@ -0,0 +1,113 @@
+//! Mapping between user structs and records.
+//!
+//! [`FromRecord`] and [`ToRecord`] can be implemented by hand, or derived with
+//! `#[derive(MarcRecord)]` when the `derive` feature is enabled:
+//!
+//! ```ignore
+//! use marc_rs::{FromRecord, MarcRecord, ToRecord};
+//!
+//! #[derive(MarcRecord)]
+//! #[marc(leader = "00000nam a2200000 a 4500")]
+//! struct Book {
+//!     #[marc(tag = "001")]
+//!     id: String,
+//!     #[marc(tag = "245", subfield = "a", ind1 = "1", ind2 = "0")]
+//!     title: String,
+//!     #[marc(tag = "245", subfield = "b")]
+//!     subtitle: Option<String>,
+//!     #[marc(tag = "020", subfield = "a")]
+//!     isbns: Vec<String>,
+//! }
+//! ```
+//!
+//! Field types are read with [`FromStr`] and written with [`ToString`]:
+//! `T` is required (first occurrence), `Option<T>` is optional and `Vec<T>`
+//! collects every occurrence. A struct field without `subfield` maps to a
+//! control field. When writing, `T` and `Option<T>` values sharing a tag are
+//! grouped in one field, while each `Vec<T>` value gets its own field.
+
+use crate::record::{ControlField, DataField, Leader, Record, Subfield};
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Build a value from a record
+pub trait FromRecord: Sized {
+    fn from_record(record: &Record) -> Result<Self, String>;
+}
+
+/// Build a record from a value
+pub trait ToRecord {
+    fn to_record(&self) -> Record;
+}
+
+/// Value of the first control field with the tag
+pub fn control_value<'a>(record: &'a Record, tag: &str) -> Option<&'a str> {
+    record.control_fields.iter().find(|f| f.tag == tag).map(|f| f.value.as_str())
+}
+
+/// Values of a subfield code across all fields with the tag, in record order
+pub fn subfield_values<'a>(record: &'a Record, tag: &str, code: char) -> Vec<&'a str> {
+    record
+        .data_fields
+        .iter()
+        .filter(|f| f.tag == tag)
+        .flat_map(|f| f.subfields.iter().filter(|s| s.code == code))
+        .map(|s| s.value.as_str())
+        .collect()
+}
+
+/// Parse a mapped value, naming its location in the error
+pub fn parse_value<T>(value: &str, location: &str) -> Result<T, String>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    value.trim().parse().map_err(|e| format!("{}: cannot parse {:?}: {}", location, value, e))
+}
+
+/// Create an empty record with the given leader
+pub fn new_record(leader: &str) -> Record {
+    Record {
+        leader: Leader::from_bytes(leader.as_bytes()).unwrap_or_else(|e| panic!("invalid leader {:?}: {}", leader, e)),
+        control_fields: Vec::new(),
+        data_fields: Vec::new(),
+    }
+}
+
+/// Add a control field
+pub fn push_control(record: &mut Record, tag: &str, value: String) {
+    record.control_fields.push(ControlField { tag: tag.to_string(), value });
+}
+
+/// Add a subfield to the first field with the tag, creating the field if needed
+pub fn push_subfield(record: &mut Record, tag: &str, ind1: char, ind2: char, code: char, value: String) {
+    let index = match record.data_fields.iter().position(|f| f.tag == tag) {
+        Some(index) => index,
+        None => {
+            record.data_fields.push(DataField {
+                tag: tag.to_string(),
+                ind1,
+                ind2,
+                subfields: Vec::new(),
+            });
+            record.data_fields.len() - 1
+        }
+    };
+    record.data_fields[index].subfields.push(Subfield { code, value });
+}
+
+/// Add a field with a single subfield
+pub fn push_field(record: &mut Record, tag: &str, ind1: char, ind2: char, code: char, value: String) {
+    record.data_fields.push(DataField {
+        tag: tag.to_string(),
+        ind1,
+        ind2,
+        subfields: vec![Subfield { code, value }],
+    });
+}
+
+/// Order control and data fields by tag, keeping the order of fields with the same tag
+pub fn finish_record(record: &mut Record) {
+    record.control_fields.sort_by(|a, b| a.tag.cmp(&b.tag));
+    record.data_fields.sort_by(|a, b| a.tag.cmp(&b.tag));
+}
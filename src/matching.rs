@@ -0,0 +1,90 @@
+//! Match keys: fingerprints of the publication a record describes.
+//!
+//! Records of the same publication from different sources rarely share their
+//! identifiers, but usually agree on the title, the main entry and the date.
+//! [`Record::match_key`] joins normalized forms of these, so duplicates can be
+//! found by comparing keys: case, punctuation, spacing, diacritics and
+//! non-filing articles make no difference.
+//!
+//! ```
+//! use marc_rs::{DataField, Leader, MarcFormat, Record, Subfield};
+//!
+//! let record = |ind2: char, title: &str, name: &str| Record {
+//!     leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+//!     control_fields: vec![],
+//!     data_fields: vec![
+//!         DataField { tag: "100".to_string(), ind1: '1', ind2: ' ', subfields: vec![Subfield { code: 'a', value: name.to_string() }] },
+//!         DataField { tag: "245".to_string(), ind1: '1', ind2, subfields: vec![Subfield { code: 'a', value: title.to_string() }] },
+//!     ],
+//! };
+//!
+//! let a = record('4', "The Misérables :", "Hugo, Victor,");
+//! let b = record('0', "Miserables", "HUGO, Victor");
+//! assert_eq!(a.match_key(MarcFormat::Marc21), b.match_key(MarcFormat::Marc21));
+//! assert_eq!(a.match_key(MarcFormat::Marc21).unwrap(), "miserables/hugovictor/");
+//! ```
+
+use crate::fixed_fields::GeneralProcessingData;
+use crate::format::MarcFormat;
+use crate::record::Record;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// Characters of the title kept in a match key
+const TITLE_LENGTH: usize = 30;
+/// Characters of the main entry kept in a match key
+const NAME_LENGTH: usize = 12;
+
+/// Letters and digits of `text`, lowercased and without diacritics
+pub fn match_text(text: &str) -> String {
+    text.nfkd().filter(|c| !is_combining_mark(*c) && c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+impl Record {
+    /// Match key `title/name/year` of the record, `None` without a title
+    ///
+    /// The title is the [filing form](Record::sort_title) of the title, the name
+    /// the `$a` of the main entry (100/110/111, or 700/710/720 in UNIMARC), both
+    /// reduced by [`match_text`] and truncated. The year is date 1 of the fixed
+    /// fields, or the first four digits of the publication date; the name and
+    /// the year are empty when missing.
+    pub fn match_key(&self, format: MarcFormat) -> Option<String> {
+        let title: String = match_text(&self.sort_title(format)?).chars().take(TITLE_LENGTH).collect();
+        if title.is_empty() {
+            return None;
+        }
+
+        let name_tags = match format {
+            MarcFormat::Marc21 | MarcFormat::MarcXml => ["100", "110", "111"],
+            MarcFormat::Unimarc => ["700", "710", "720"],
+        };
+        let name: String = self
+            .data_fields
+            .iter()
+            .filter(|f| name_tags.contains(&f.tag.as_str()))
+            .find_map(|f| f.subfields.iter().find(|s| s.code == 'a'))
+            .map(|s| match_text(&s.value).chars().take(NAME_LENGTH).collect())
+            .unwrap_or_default();
+
+        let year = self.match_year(format).unwrap_or_default();
+        Some(format!("{}/{}/{}", title, name, year))
+    }
+
+    fn match_year(&self, format: MarcFormat) -> Option<String> {
+        let fixed = match format {
+            MarcFormat::Marc21 | MarcFormat::MarcXml => self
+                .control_fields
+                .iter()
+                .find(|f| f.tag == "008")
+                .and_then(|f| f.value.get(7..11))
+                .filter(|date| date.bytes().all(|b| b.is_ascii_digit()))
+                .map(str::to_string),
+            MarcFormat::Unimarc => GeneralProcessingData::from_record(self).and_then(|data| data.ok()).and_then(|data| data.date1_year()).map(|year| format!("{:04}", year)),
+        };
+        fixed.or_else(|| {
+            let date = self.publication(format)?.date()?.to_string();
+            let digits: Vec<char> = date.chars().collect();
+            digits.windows(4).find(|w| w.iter().all(|c| c.is_ascii_digit())).map(|w| w.iter().collect())
+        })
+    }
+}
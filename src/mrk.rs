@@ -0,0 +1,295 @@
+//! MarcMaker text (`.mrk`), the line-based format edited with MarcEdit.
+//!
+//! ```text
+//! =LDR  00000nam\a2200000\a\4500
+//! =001  12345
+//! =245  10$aTitle /$cAuthor.
+//! ```
+//!
+//! Blanks in the leader, control fields and indicators are written as `\`.
+//! `$`, `\`, `{` and `}` in values are written as the mnemonics `{dollar}`,
+//! `{bsol}`, `{lcub}` and `{rcub}`. Records are separated by a blank line.
+
+use crate::parser::ParseError;
+use crate::record::{ControlField, DataField, Leader, Record, Subfield};
+use crate::writer::WriteError;
+use std::io::{BufRead, Write};
+
+/// Mnemonics of the characters that cannot appear literally in values
+const MNEMONICS: [(char, &str); 4] = [('$', "{dollar}"), ('\\', "{bsol}"), ('{', "{lcub}"), ('}', "{rcub}")];
+
+impl Record {
+    /// MarcMaker text of the record, ending with a blank line
+    pub fn to_mrk(&self) -> String {
+        let mut text = format!("=LDR  {}\n", leader_text(&self.leader));
+        for field in &self.control_fields {
+            text.push_str(&format!("={}  {}\n", field.tag, control_field_text(field)));
+        }
+        for field in &self.data_fields {
+            text.push_str(&format!("={}  {}\n", field.tag, data_field_text(field)));
+        }
+        text.push('\n');
+        text
+    }
+}
+
+/// MarcMaker text of a leader
+pub(crate) fn leader_text(leader: &Leader) -> String {
+    blanks_to_backslashes(&String::from_utf8_lossy(&leader.to_bytes()))
+}
+
+/// MarcMaker text of a control field value, without the tag
+pub(crate) fn control_field_text(field: &ControlField) -> String {
+    blanks_to_backslashes(&escape(&field.value))
+}
+
+/// MarcMaker text of a data field's indicators and subfields, without the tag
+pub(crate) fn data_field_text(field: &DataField) -> String {
+    let mut text = String::new();
+    text.push(blank_to_backslash(field.ind1));
+    text.push(blank_to_backslash(field.ind2));
+    for subfield in &field.subfields {
+        text.push('$');
+        text.push(subfield.code);
+        text.push_str(&escape(&subfield.value));
+    }
+    text
+}
+
+/// Write records as MarcMaker text
+pub fn write_mrk<'a, I>(records: I, output: &mut dyn Write) -> Result<(), WriteError>
+where
+    I: IntoIterator<Item = &'a Record>,
+{
+    for record in records {
+        output.write_all(record.to_mrk().as_bytes())?;
+    }
+    Ok(())
+}
+
+/// Parse every record of a MarcMaker text
+pub fn parse_mrk(text: &str) -> Result<Vec<Record>, ParseError> {
+    MrkReader::new(text.as_bytes()).collect()
+}
+
+/// Iterator reading MarcMaker records one at a time
+///
+/// The iterator stops after the first error.
+pub struct MrkReader<R: BufRead> {
+    reader: R,
+    line: String,
+    line_number: usize,
+    done: bool,
+}
+
+impl<R: BufRead> MrkReader<R> {
+    /// Create a reader over MarcMaker text
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            line: String::new(),
+            line_number: 0,
+            done: false,
+        }
+    }
+
+    /// Number of lines read so far
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    /// Unwrap the underlying reader
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    fn read_record(&mut self) -> Result<Option<Record>, ParseError> {
+        let mut leader = None;
+        let mut control_fields = Vec::new();
+        let mut data_fields = Vec::new();
+
+        loop {
+            self.line.clear();
+            let read = self.reader.read_line(&mut self.line).map_err(|e| ParseError::Other(format!("IO error: {}", e)))?;
+            let line = self.line.trim_end_matches(['\r', '\n']);
+            if read > 0 {
+                self.line_number += 1;
+            }
+
+            if line.trim().is_empty() {
+                if read > 0 && leader.is_none() && control_fields.is_empty() && data_fields.is_empty() {
+                    continue;
+                }
+                break;
+            }
+
+            let (tag, data) = split_line(line).map_err(|e| ParseError::InvalidField(format!("Line {}: {}", self.line_number, e)))?;
+            if tag == "LDR" {
+                let bytes = backslashes_to_blanks(data);
+                leader = Some(Leader::from_bytes(bytes.as_bytes()).map_err(|e| ParseError::InvalidLeader(format!("Line {}: {}", self.line_number, e)))?);
+            } else if tag < "010" {
+                control_fields.push(control_field(tag, data));
+            } else {
+                data_fields.push(data_field(tag, data));
+            }
+        }
+
+        if control_fields.is_empty() && data_fields.is_empty() && leader.is_none() {
+            return Ok(None);
+        }
+        let leader = leader.ok_or_else(|| ParseError::InvalidLeader(format!("Record ending at line {} has no =LDR line", self.line_number)))?;
+        Ok(Some(Record { leader, control_fields, data_fields }))
+    }
+}
+
+impl<R: BufRead> Iterator for MrkReader<R> {
+    type Item = Result<Record, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.read_record() {
+            Ok(Some(record)) => Some(Ok(record)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+impl ControlField {
+    /// Control field of a MarcMaker line, e.g. `=001  12345`
+    ///
+    /// ```
+    /// use marc_rs::ControlField;
+    ///
+    /// let field = ControlField::from_mrk(r"=008  850101s1985\\\\fr").unwrap();
+    /// assert_eq!(field.value, "850101s1985    fr");
+    /// ```
+    pub fn from_mrk(line: &str) -> Result<ControlField, String> {
+        match split_line(line)? {
+            (tag, data) if tag < "010" => Ok(control_field(tag, data)),
+            (tag, _) => Err(format!("{} is not a control field", tag)),
+        }
+    }
+}
+
+impl DataField {
+    /// Data field of a MarcMaker line, e.g. `=245  10$aTitle`
+    ///
+    /// ```
+    /// use marc_rs::DataField;
+    ///
+    /// let field = DataField::from_mrk(r"=650  \0$aCost{dollar}").unwrap();
+    /// assert_eq!((field.tag.as_str(), field.ind1, field.ind2), ("650", ' ', '0'));
+    /// assert_eq!(field.subfields[0].value, "Cost$");
+    /// ```
+    pub fn from_mrk(line: &str) -> Result<DataField, String> {
+        match split_line(line)? {
+            (tag, data) if tag >= "010" && tag != "LDR" => Ok(data_field(tag, data)),
+            (tag, _) => Err(format!("{} is not a data field", tag)),
+        }
+    }
+}
+
+/// Tag and data of a line
+fn split_line(line: &str) -> Result<(&str, &str), String> {
+    let rest = line.strip_prefix('=').ok_or("expected a line starting with =")?;
+    let tag = rest.get(..3).filter(|tag| tag.chars().count() == 3).ok_or("expected a 3 character tag")?;
+    // Two spaces separate the tag from the data; be lenient about the second one
+    let data = rest[3..].strip_prefix(' ').ok_or("expected a space after the tag")?;
+    Ok((tag, data.strip_prefix(' ').unwrap_or(data)))
+}
+
+fn control_field(tag: &str, data: &str) -> ControlField {
+    ControlField {
+        tag: tag.to_string(),
+        value: unescape(&backslashes_to_blanks(data)),
+    }
+}
+
+fn data_field(tag: &str, data: &str) -> DataField {
+    let mut chars = data.chars();
+    let ind1 = chars.next().map(backslash_to_blank).unwrap_or(' ');
+    let ind2 = chars.next().map(backslash_to_blank).unwrap_or(' ');
+    let subfields = chars
+        .as_str()
+        .split('$')
+        .skip(1)
+        .filter_map(|s| {
+            let mut chars = s.chars();
+            chars.next().map(|code| Subfield {
+                code,
+                value: unescape(chars.as_str()),
+            })
+        })
+        .collect();
+    DataField {
+        tag: tag.to_string(),
+        ind1,
+        ind2,
+        subfields,
+    }
+}
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match MNEMONICS.iter().find(|(m, _)| *m == c) {
+            Some((_, mnemonic)) => escaped.push_str(mnemonic),
+            None => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find('{') {
+        unescaped.push_str(&rest[..start]);
+        rest = &rest[start..];
+        match MNEMONICS.iter().find(|(_, mnemonic)| rest.starts_with(mnemonic)) {
+            Some((c, mnemonic)) => {
+                unescaped.push(*c);
+                rest = &rest[mnemonic.len()..];
+            }
+            None => {
+                unescaped.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    unescaped.push_str(rest);
+    unescaped
+}
+
+fn blank_to_backslash(c: char) -> char {
+    if c == ' ' {
+        '\\'
+    } else {
+        c
+    }
+}
+
+fn backslash_to_blank(c: char) -> char {
+    if c == '\\' {
+        ' '
+    } else {
+        c
+    }
+}
+
+fn blanks_to_backslashes(value: &str) -> String {
+    value.chars().map(blank_to_backslash).collect()
+}
+
+fn backslashes_to_blanks(value: &str) -> String {
+    value.chars().map(backslash_to_blank).collect()
+}
@@ -0,0 +1,309 @@
+//! OAI-PMH harvesting (`oai` feature).
+//!
+//! A [`Harvester`] lists the records of an OAI-PMH repository with the
+//! `ListRecords` verb, following resumption tokens from page to page, and yields
+//! each [`OaiRecord`] with its header and, unless deleted, its MARC record.
+//! [`parse_oai_response`] parses one response page, e.g. one saved to a file.
+//!
+//! ```no_run
+//! use marc_rs::Harvester;
+//!
+//! let harvester = Harvester::new("https://example.org/oai").set("theses").from("2024-01-01");
+//! for record in harvester {
+//!     let record = record.unwrap();
+//!     println!("{} {}", record.identifier, record.datestamp);
+//! }
+//! ```
+
+use crate::format::FormatEncoding;
+use crate::parser::parse_marc_xml;
+use crate::record::Record;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Attempts at a request the repository answered with 503 Service Unavailable
+const MAX_ATTEMPTS: usize = 5;
+
+/// Longest wait honoured from a Retry-After header
+const MAX_RETRY_AFTER: u64 = 300;
+
+/// Harvesting error type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HarvestError {
+    /// The request failed: connection, timeout or HTTP status
+    Http(String),
+    /// The repository answered with an OAI-PMH error, e.g. `badResumptionToken`
+    Oai { code: String, message: String },
+    /// The response is not a valid OAI-PMH response
+    InvalidResponse(String),
+}
+
+impl std::fmt::Display for HarvestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HarvestError::Http(msg) => write!(f, "HTTP error: {}", msg),
+            HarvestError::Oai { code, message } => write!(f, "OAI-PMH error {}: {}", code, message),
+            HarvestError::InvalidResponse(msg) => write!(f, "Invalid OAI-PMH response: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for HarvestError {}
+
+/// A record listed by a repository
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OaiRecord {
+    /// OAI identifier, e.g. `oai:example.org:1234`
+    pub identifier: String,
+    /// Date of the last change, as given by the repository
+    pub datestamp: String,
+    /// Sets the record belongs to
+    pub sets: Vec<String>,
+    /// True for a record deleted from the repository, which has no metadata
+    pub deleted: bool,
+    /// The MARC record, for metadata in MARC XML
+    pub record: Option<Record>,
+}
+
+/// One page of a `ListRecords` response
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct OaiPage {
+    pub records: Vec<OaiRecord>,
+    /// Token of the next page; none on the last page
+    pub resumption_token: Option<String>,
+    /// Number of records of the whole list, when the repository gives it
+    pub complete_list_size: Option<usize>,
+}
+
+/// Parse a `ListRecords` response
+///
+/// Metadata is read as MARC XML, with or without a namespace prefix. The
+/// `noRecordsMatch` error gives an empty page; other OAI-PMH errors are returned
+/// as [`HarvestError::Oai`].
+pub fn parse_oai_response(xml: &str) -> Result<OaiPage, HarvestError> {
+    let invalid = |e: quick_xml::Error| HarvestError::InvalidResponse(e.to_string());
+    let mut reader = Reader::from_str(xml);
+    let mut page = OaiPage::default();
+    let mut current: Option<OaiRecord> = None;
+
+    loop {
+        match reader.read_event().map_err(invalid)? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"record" => {
+                    current = Some(OaiRecord {
+                        identifier: String::new(),
+                        datestamp: String::new(),
+                        sets: Vec::new(),
+                        deleted: false,
+                        record: None,
+                    })
+                }
+                b"header" => {
+                    if let Some(record) = &mut current {
+                        record.deleted = attribute(&e, "status").as_deref() == Some("deleted");
+                    }
+                }
+                name @ (b"identifier" | b"datestamp" | b"setSpec") => {
+                    let text = text(&mut reader, &e)?;
+                    if let Some(record) = &mut current {
+                        match name {
+                            b"identifier" => record.identifier = text,
+                            b"datestamp" => record.datestamp = text,
+                            _ => record.sets.push(text),
+                        }
+                    }
+                }
+                b"metadata" => {
+                    let span = reader.read_to_end(e.name()).map_err(invalid)?;
+                    let records = parse_marc_xml(xml[span].as_bytes(), FormatEncoding::marc_xml()).map_err(|e| HarvestError::InvalidResponse(e.to_string()))?;
+                    if let Some(record) = &mut current {
+                        record.record = records.into_iter().next();
+                    }
+                }
+                b"resumptionToken" => {
+                    page.complete_list_size = attribute(&e, "completeListSize").and_then(|size| size.parse().ok());
+                    page.resumption_token = Some(text(&mut reader, &e)?).filter(|token| !token.is_empty());
+                }
+                b"error" => {
+                    let code = attribute(&e, "code").unwrap_or_default();
+                    let message = text(&mut reader, &e)?;
+                    if code == "noRecordsMatch" {
+                        return Ok(OaiPage::default());
+                    }
+                    return Err(HarvestError::Oai { code, message });
+                }
+                _ => {}
+            },
+            Event::Empty(e) => match e.local_name().as_ref() {
+                b"resumptionToken" => page.complete_list_size = attribute(&e, "completeListSize").and_then(|size| size.parse().ok()),
+                b"error" => {
+                    let code = attribute(&e, "code").unwrap_or_default();
+                    if code == "noRecordsMatch" {
+                        return Ok(OaiPage::default());
+                    }
+                    return Err(HarvestError::Oai { code, message: String::new() });
+                }
+                _ => {}
+            },
+            Event::End(e) if e.local_name().as_ref() == b"record" => {
+                if let Some(record) = current.take() {
+                    page.records.push(record);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(page)
+}
+
+/// Value of an attribute, unescaped
+fn attribute(element: &BytesStart, name: &str) -> Option<String> {
+    let attribute = element.try_get_attribute(name).ok()??;
+    attribute.unescape_value().ok().map(|value| value.into_owned())
+}
+
+/// Text of an element, unescaped and trimmed
+fn text(reader: &mut Reader<&[u8]>, element: &BytesStart) -> Result<String, HarvestError> {
+    let text = reader.read_text(element.name()).map_err(|e| HarvestError::InvalidResponse(e.to_string()))?;
+    let text = quick_xml::escape::unescape(&text).map_err(|e| HarvestError::InvalidResponse(e.to_string()))?;
+    Ok(text.trim().to_string())
+}
+
+/// Iterator over the records of an OAI-PMH repository, fetching pages as needed
+///
+/// Selective harvesting is set with [`set`](Self::set), [`from`](Self::from)
+/// and [`until`](Self::until). Repositories answering 503 Service Unavailable
+/// are asked again after the delay of their Retry-After header. The iterator
+/// stops after the first error; [`resumption_token`](Self::resumption_token)
+/// then gives the token to [`resume`](Self::resume) from.
+pub struct Harvester {
+    agent: ureq::Agent,
+    base_url: String,
+    metadata_prefix: String,
+    set: Option<String>,
+    from: Option<String>,
+    until: Option<String>,
+    token: Option<String>,
+    pending: VecDeque<OaiRecord>,
+    started: bool,
+    done: bool,
+    complete_list_size: Option<usize>,
+}
+
+impl Harvester {
+    /// Harvest the `marc21` metadata of a repository, e.g. `https://example.org/oai`
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new().timeout(Duration::from_secs(120)).build(),
+            base_url: base_url.to_string(),
+            metadata_prefix: "marc21".to_string(),
+            set: None,
+            from: None,
+            until: None,
+            token: None,
+            pending: VecDeque::new(),
+            started: false,
+            done: false,
+            complete_list_size: None,
+        }
+    }
+
+    /// Metadata format to request, e.g. `marcxml` or `unimarc`, which must be MARC XML
+    pub fn metadata_prefix(mut self, prefix: &str) -> Self {
+        self.metadata_prefix = prefix.to_string();
+        self
+    }
+
+    /// Only list the records of a set
+    pub fn set(mut self, set: &str) -> Self {
+        self.set = Some(set.to_string());
+        self
+    }
+
+    /// Only list the records changed on or after a date, `YYYY-MM-DD` or `YYYY-MM-DDThh:mm:ssZ`
+    pub fn from(mut self, date: &str) -> Self {
+        self.from = Some(date.to_string());
+        self
+    }
+
+    /// Only list the records changed on or before a date
+    pub fn until(mut self, date: &str) -> Self {
+        self.until = Some(date.to_string());
+        self
+    }
+
+    /// Start from the page of a resumption token, e.g. to go on with an interrupted harvest
+    pub fn resume(mut self, token: &str) -> Self {
+        self.token = Some(token.to_string());
+        self.started = true;
+        self
+    }
+
+    /// Token of the next page to fetch, if any
+    pub fn resumption_token(&self) -> Option<&str> {
+        self.token.as_deref()
+    }
+
+    /// Number of records of the whole list, once the repository gave it
+    pub fn complete_list_size(&self) -> Option<usize> {
+        self.complete_list_size
+    }
+
+    fn fetch_page(&mut self) -> Result<OaiPage, HarvestError> {
+        let mut request = self.agent.get(&self.base_url).query("verb", "ListRecords");
+        match &self.token {
+            Some(token) => request = request.query("resumptionToken", token),
+            None => {
+                request = request.query("metadataPrefix", &self.metadata_prefix);
+                for (name, value) in [("set", &self.set), ("from", &self.from), ("until", &self.until)] {
+                    if let Some(value) = value {
+                        request = request.query(name, value);
+                    }
+                }
+            }
+        }
+
+        let mut attempt = 1;
+        let response = loop {
+            match request.clone().call() {
+                Ok(response) => break response,
+                Err(ureq::Error::Status(503, response)) if attempt < MAX_ATTEMPTS => {
+                    let delay = response.header("Retry-After").and_then(|delay| delay.trim().parse().ok()).unwrap_or(10);
+                    std::thread::sleep(Duration::from_secs(delay.min(MAX_RETRY_AFTER)));
+                    attempt += 1;
+                }
+                Err(e) => return Err(HarvestError::Http(e.to_string())),
+            }
+        };
+        let body = response.into_string().map_err(|e| HarvestError::Http(e.to_string()))?;
+        parse_oai_response(&body)
+    }
+}
+
+impl Iterator for Harvester {
+    type Item = Result<OaiRecord, HarvestError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending.is_empty() {
+            if self.done || (self.started && self.token.is_none()) {
+                return None;
+            }
+            match self.fetch_page() {
+                Ok(page) => {
+                    self.started = true;
+                    self.token = page.resumption_token;
+                    self.complete_list_size = page.complete_list_size.or(self.complete_list_size);
+                    self.pending.extend(page.records);
+                }
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+        self.pending.pop_front().map(Ok)
+    }
+}
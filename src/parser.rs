@@ -1,6 +1,7 @@
-use crate::encoding::convert_to_utf8;
+use crate::encoding::decode_into;
 use crate::format::{FormatEncoding, MarcFormat};
 use crate::record::{ControlField, DataField, Leader, Record, Subfield};
+use memchr::{memchr, memchr2, memchr_iter};
 
 /// Parse error type
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -30,6 +31,71 @@ impl std::fmt::Display for ParseError {
 
 impl std::error::Error for ParseError {}
 
+impl ParseError {
+    /// Stable identifier of the error variant (`invalid_leader`, `unexpected_eof`, ...)
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ParseError::InvalidLeader(_) => "invalid_leader",
+            ParseError::InvalidRecordLength(_) => "invalid_record_length",
+            ParseError::InvalidField(_) => "invalid_field",
+            ParseError::InvalidEncoding(_) => "invalid_encoding",
+            ParseError::UnexpectedEof => "unexpected_eof",
+            ParseError::InvalidXml(_) => "invalid_xml",
+            ParseError::Other(_) => "other",
+        }
+    }
+
+    /// Detail message of the error, if the variant carries one
+    pub fn message(&self) -> Option<&str> {
+        match self {
+            ParseError::InvalidLeader(msg)
+            | ParseError::InvalidRecordLength(msg)
+            | ParseError::InvalidField(msg)
+            | ParseError::InvalidEncoding(msg)
+            | ParseError::InvalidXml(msg)
+            | ParseError::Other(msg) => Some(msg),
+            ParseError::UnexpectedEof => None,
+        }
+    }
+
+    /// Build an error from its [`kind`](Self::kind) and message
+    pub fn from_kind(kind: &str, message: Option<String>) -> Option<Self> {
+        let message = || message.clone().unwrap_or_default();
+        match kind {
+            "invalid_leader" => Some(ParseError::InvalidLeader(message())),
+            "invalid_record_length" => Some(ParseError::InvalidRecordLength(message())),
+            "invalid_field" => Some(ParseError::InvalidField(message())),
+            "invalid_encoding" => Some(ParseError::InvalidEncoding(message())),
+            "unexpected_eof" => Some(ParseError::UnexpectedEof),
+            "invalid_xml" => Some(ParseError::InvalidXml(message())),
+            "other" => Some(ParseError::Other(message())),
+            _ => None,
+        }
+    }
+}
+
+/// Serialized as `{"kind": "invalid_leader", "message": "..."}`
+#[cfg(feature = "serde")]
+impl serde::Serialize for ParseError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        crate::helpers::serialize_error(None, self.kind(), self.message(), &self.to_string(), serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ParseError {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let (kind, message) = crate::helpers::deserialize_error(deserializer)?;
+        ParseError::from_kind(&kind, message).ok_or_else(|| serde::de::Error::unknown_variant(&kind, &["invalid_leader", "invalid_record_length", "invalid_field", "invalid_encoding", "unexpected_eof", "invalid_xml", "other"]))
+    }
+}
+
 /// Parse MARC records from bytes
 pub fn parse(data: &[u8], format_encoding: FormatEncoding) -> Result<Vec<Record>, ParseError> {
     match format_encoding.format {
@@ -49,7 +115,7 @@ pub fn parse_marc21_binary(data: &[u8], format_encoding: FormatEncoding) -> Resu
             break; // Not enough data for a leader
         }
 
-        let leader = Leader::from_bytes(&data[offset..offset + 24]).map_err(|e| ParseError::InvalidLeader(e))?;
+        let leader = Leader::from_bytes(&data[offset..offset + 24]).map_err(ParseError::InvalidLeader)?;
 
         let record_length = leader.record_length as usize;
         if record_length == 0 || record_length > data.len() - offset {
@@ -70,36 +136,30 @@ pub fn parse_marc21_binary(data: &[u8], format_encoding: FormatEncoding) -> Resu
     Ok(records)
 }
 
-/// Parse a single MARC21 record
-fn parse_single_marc21_record(data: &[u8], leader: &Leader, format_encoding: FormatEncoding) -> Result<Record, ParseError> {
+/// Tag, length and starting position of a 12-byte directory entry
+fn directory_entry(entry: &[u8]) -> Result<(&str, usize, usize), ParseError> {
+    let tag = std::str::from_utf8(&entry[..3]).map_err(|e| ParseError::InvalidField(format!("Invalid tag: {}", e)))?;
+    let length = directory_number(&entry[3..7]).ok_or_else(|| ParseError::InvalidField(format!("Invalid length: {:?}", String::from_utf8_lossy(&entry[3..7]))))?;
+    let start = directory_number(&entry[7..12]).ok_or_else(|| ParseError::InvalidField(format!("Invalid start: {:?}", String::from_utf8_lossy(&entry[7..12]))))?;
+    Ok((tag, length, start))
+}
+
+/// Number written with ASCII digits
+fn directory_number(digits: &[u8]) -> Option<usize> {
+    digits.iter().try_fold(0, |n, digit| digit.is_ascii_digit().then(|| n * 10 + (digit - b'0') as usize))
+}
+
+/// Directory entries of a record with the data of their field
+fn fields<'a>(data: &'a [u8], leader: &Leader) -> Result<impl Iterator<Item = Result<(&'a str, &'a [u8]), ParseError>> + 'a, ParseError> {
     if data.len() < leader.base_address_of_data as usize {
         return Err(ParseError::UnexpectedEof);
     }
 
     let base_address = leader.base_address_of_data as usize;
-    let directory = &data[24..base_address];
+    let directory = &data[24.min(base_address)..base_address];
     let data_area = &data[base_address..];
-
-    let mut control_fields = Vec::new();
-    let mut data_fields = Vec::new();
-
-    let mut dir_offset = 0;
-    while dir_offset + 12 <= directory.len() {
-        let tag_bytes = &directory[dir_offset..dir_offset + 3];
-        let tag = std::str::from_utf8(tag_bytes).map_err(|e| ParseError::InvalidField(format!("Invalid tag: {}", e)))?;
-
-        let length_bytes = &directory[dir_offset + 3..dir_offset + 7];
-        let length = std::str::from_utf8(length_bytes)
-            .map_err(|e| ParseError::InvalidField(format!("Invalid length: {}", e)))?
-            .parse::<usize>()
-            .map_err(|e| ParseError::InvalidField(format!("Invalid length number: {}", e)))?;
-
-        let start_bytes = &directory[dir_offset + 7..dir_offset + 12];
-        let start = std::str::from_utf8(start_bytes)
-            .map_err(|e| ParseError::InvalidField(format!("Invalid start: {}", e)))?
-            .parse::<usize>()
-            .map_err(|e| ParseError::InvalidField(format!("Invalid start number: {}", e)))?;
-
+    Ok(directory.chunks_exact(12).map(move |entry| {
+        let (tag, length, start) = directory_entry(entry)?;
         if start + length > data_area.len() {
             return Err(ParseError::InvalidField(format!(
                 "Field extends beyond data area: start={}, length={}, data_len={}",
@@ -108,66 +168,146 @@ fn parse_single_marc21_record(data: &[u8], leader: &Leader, format_encoding: For
                 data_area.len()
             )));
         }
+        Ok((tag, &data_area[start..start + length]))
+    }))
+}
 
-        let field_data = &data_area[start..start + length];
+/// Parse a single MARC21 record
+fn parse_single_marc21_record(data: &[u8], leader: &Leader, format_encoding: FormatEncoding) -> Result<Record, ParseError> {
+    let mut record = Record {
+        leader: leader.clone(),
+        control_fields: Vec::new(),
+        data_fields: Vec::new(),
+    };
+    fill_record(&mut record, data, leader, format_encoding)?;
+    Ok(record)
+}
 
+/// Parse the fields of a record into `record`, reusing its fields, strings and vectors
+fn fill_record(record: &mut Record, data: &[u8], leader: &Leader, format_encoding: FormatEncoding) -> Result<(), ParseError> {
+    let decode = |bytes: &[u8], value: &mut String| {
+        value.clear();
+        decode_into(bytes, format_encoding.encoding, value).map_err(ParseError::InvalidEncoding)
+    };
+    // Sized from the directory, so that the vectors are allocated once
+    let entries = data.len().min(leader.base_address_of_data as usize).saturating_sub(24) / 12;
+    let control_count = data.get(24..24 + entries * 12).unwrap_or_default().chunks_exact(12).filter(|entry| &entry[..3] < b"010".as_slice()).count();
+    record.control_fields.reserve(control_count.saturating_sub(record.control_fields.len()));
+    record.data_fields.reserve((entries - control_count).saturating_sub(record.data_fields.len()));
+
+    let (mut control_count, mut data_count) = (0, 0);
+    for field in fields(data, leader)? {
+        let (tag, field_data) = field?;
         if tag < "010" {
-            // Control field
-            let value = convert_to_utf8(field_data, format_encoding.encoding).map_err(|e| ParseError::InvalidEncoding(e))?;
-            control_fields.push(ControlField { tag: tag.to_string(), value });
-        } else {
-            // Data field
-            if field_data.is_empty() {
-                dir_offset += 12;
-                continue;
+            // Control field, without its field terminator
+            if control_count == record.control_fields.len() {
+                record.control_fields.push(ControlField { tag: String::new(), value: String::new() });
             }
+            let field = &mut record.control_fields[control_count];
+            field.tag.clear();
+            field.tag.push_str(tag);
+            decode(field_data.strip_suffix(&[0x1E]).unwrap_or(field_data), &mut field.value)?;
+            control_count += 1;
+        } else if field_data.len() >= 2 {
+            if data_count == record.data_fields.len() {
+                record.data_fields.push(DataField {
+                    tag: String::new(),
+                    ind1: ' ',
+                    ind2: ' ',
+                    subfields: Vec::new(),
+                });
+            }
+            let field = &mut record.data_fields[data_count];
+            field.tag.clear();
+            field.tag.push_str(tag);
+            field.ind1 = field_data[0] as char;
+            field.ind2 = field_data[1] as char;
+            let spans = subfield_spans(&field_data[2..]);
+            field.subfields.reserve(memchr_iter(0x1F, &field_data[2..]).count().saturating_sub(field.subfields.len()));
+            let mut subfield_count = 0;
+            for (code, value) in spans {
+                if subfield_count == field.subfields.len() {
+                    field.subfields.push(Subfield { code, value: String::new() });
+                }
+                let subfield = &mut field.subfields[subfield_count];
+                subfield.code = code;
+                decode(value, &mut subfield.value)?;
+                subfield_count += 1;
+            }
+            field.subfields.truncate(subfield_count);
+            data_count += 1;
+        } else if !field_data.is_empty() {
+            return Err(ParseError::InvalidField(format!("Field {} is shorter than its indicators", tag)));
+        }
+    }
+    record.control_fields.truncate(control_count);
+    record.data_fields.truncate(data_count);
+    Ok(())
+}
 
-            let ind1 = field_data[0] as char;
-            let ind2 = field_data[1] as char;
-            let subfield_data = &field_data[2..];
-
-            let mut subfields = Vec::new();
-            let mut i = 0;
-            while i < subfield_data.len() {
-                if subfield_data[i] == 0x1F {
-                    // Subfield delimiter
-                    i += 1;
-                    if i >= subfield_data.len() {
-                        break;
-                    }
-                    let code = subfield_data[i] as char;
-                    i += 1;
-
-                    let value_start = i;
-                    while i < subfield_data.len() && subfield_data[i] != 0x1F && subfield_data[i] != 0x1E {
-                        i += 1;
-                    }
+/// Parse one binary record into `record`, reusing the capacity of its fields and values
+///
+/// For loops that parse, inspect and drop records one at a time: after the
+/// first records, parsing mostly overwrites strings and vectors in place
+/// instead of allocating them. `data` starts with a MARC21 or UNIMARC record;
+/// bytes after it are ignored. MARC XML records are parsed normally and replace
+/// `record`. On error, `record` holds part of the record.
+pub fn parse_into(record: &mut Record, data: &[u8], format_encoding: FormatEncoding) -> Result<(), ParseError> {
+    if format_encoding.format == MarcFormat::MarcXml {
+        *record = parse_marc_xml(data, format_encoding)?.into_iter().next().ok_or(ParseError::UnexpectedEof)?;
+        return Ok(());
+    }
 
-                    let value_bytes = &subfield_data[value_start..i];
-                    let value = convert_to_utf8(value_bytes, format_encoding.encoding).map_err(|e| ParseError::InvalidEncoding(e))?;
+    let leader = Leader::from_bytes(data.get(..24).ok_or(ParseError::UnexpectedEof)?).map_err(ParseError::InvalidLeader)?;
+    let record_length = leader.record_length as usize;
+    if record_length == 0 || record_length > data.len() {
+        return Err(ParseError::InvalidRecordLength(format!("Record length {} exceeds available data {}", record_length, data.len())));
+    }
+    fill_record(record, &data[..record_length], &leader, format_encoding)?;
+    record.leader = leader;
+    Ok(())
+}
 
-                    subfields.push(Subfield { code, value });
-                } else {
-                    i += 1;
-                }
-            }
+/// Code and value bytes of each subfield of a data field, after its indicators
+///
+/// Bytes before the first delimiter are ignored, and values end at the next
+/// delimiter or field terminator.
+struct SubfieldSpans<'a> {
+    data: &'a [u8],
+}
 
-            data_fields.push(DataField {
-                tag: tag.to_string(),
-                ind1,
-                ind2,
-                subfields,
-            });
-        }
+fn subfield_spans(data: &[u8]) -> SubfieldSpans<'_> {
+    SubfieldSpans { data }
+}
 
-        dir_offset += 12;
+impl<'a> Iterator for SubfieldSpans<'a> {
+    type Item = (char, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Delimiter, then code
+        let i = memchr(0x1F, self.data).filter(|i| i + 1 < self.data.len());
+        let Some(i) = i else {
+            self.data = &[];
+            return None;
+        };
+        let code = self.data[i + 1] as char;
+        let rest = &self.data[i + 2..];
+        let end = memchr2(0x1F, 0x1E, rest).unwrap_or(rest.len());
+        self.data = &rest[end..];
+        Some((code, &rest[..end]))
     }
+}
 
-    Ok(Record {
-        leader: leader.clone(),
-        control_fields,
-        data_fields,
-    })
+/// Parse the subfields of a data field, after its indicators
+///
+/// Bytes before the first delimiter are ignored, and values end at the next
+/// delimiter or field terminator.
+pub(crate) fn parse_subfields(data: &[u8], mut decode: impl FnMut(&[u8]) -> Result<String, ParseError>) -> Result<Vec<Subfield>, ParseError> {
+    let mut subfields = Vec::with_capacity(memchr_iter(0x1F, data).count());
+    for (code, value) in subfield_spans(data) {
+        subfields.push(Subfield { code, value: decode(value)? });
+    }
+    Ok(subfields)
 }
 
 /// Parse UNIMARC binary format
@@ -182,8 +322,8 @@ pub fn parse_marc_xml(data: &[u8], _format_encoding: FormatEncoding) -> Result<V
     use quick_xml::events::Event;
     use quick_xml::Reader;
 
+    // Text is kept as is: values may start or end with significant spaces
     let mut reader = Reader::from_str(std::str::from_utf8(data).map_err(|e| ParseError::InvalidXml(format!("Invalid UTF-8: {}", e)))?);
-    reader.trim_text(true);
 
     let mut records = Vec::new();
     let mut buf = Vec::new();
@@ -196,7 +336,7 @@ pub fn parse_marc_xml(data: &[u8], _format_encoding: FormatEncoding) -> Result<V
 
     loop {
         match reader.read_event_into(&mut buf) {
-            Ok(Event::Start(e)) => match e.name().as_ref() {
+            Ok(Event::Start(e)) => match e.local_name().as_ref() {
                 b"collection" => {
                     in_collection = true;
                 }
@@ -290,10 +430,10 @@ pub fn parse_marc_xml(data: &[u8], _format_encoding: FormatEncoding) -> Result<V
                 _ => {}
             },
             Ok(Event::Text(e)) => {
-                current_value = e.unescape().unwrap_or_default().to_string();
+                current_value.push_str(&e.unescape().unwrap_or_default());
             }
             Ok(Event::End(e)) => {
-                match e.name().as_ref() {
+                match e.local_name().as_ref() {
                     b"record" => {
                         if let Some(record) = current_record.take() {
                             records.push(record);
@@ -301,10 +441,11 @@ pub fn parse_marc_xml(data: &[u8], _format_encoding: FormatEncoding) -> Result<V
                     }
                     b"leader" => {
                         if let Some(ref mut record) = current_record {
-                            // Parse leader from string (24 bytes)
-                            if current_value.len() >= 24 {
-                                let leader_bytes = current_value.as_bytes()[..24].to_vec();
-                                record.leader = Leader::from_bytes(&leader_bytes).map_err(|e| ParseError::InvalidLeader(e))?;
+                            // Parse leader from string (24 bytes), which may be indented
+                            let leader = current_value.trim_start();
+                            if leader.len() >= 24 {
+                                let leader_bytes = leader.as_bytes()[..24].to_vec();
+                                record.leader = Leader::from_bytes(&leader_bytes).map_err(ParseError::InvalidLeader)?;
                             }
                         }
                     }
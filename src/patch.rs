@@ -0,0 +1,350 @@
+//! Record patches: batch edits declared as data.
+//!
+//! A [`Patch`] is a list of operations (add a field, delete fields or
+//! subfields, replace text, copy or move values), each optionally applied only
+//! where a [`SpecCondition`] holds. With the `serde` feature patches are read
+//! from JSON, or from any format converted to a JSON value, such as TOML:
+//!
+//! ```toml
+//! [[operation]]
+//! delete = "9.."
+//!
+//! [[operation]]
+//! delete = "650"
+//! if = "650$2=fast"
+//!
+//! [[operation]]
+//! replace = "020$a"
+//! find = "/[^0-9X]/"     # /.../ is a regular expression (regex feature)
+//! with = ""
+//!
+//! [[operation]]
+//! add = '=590  \\$aDigitized copy available'
+//! if = "856"
+//!
+//! [[operation]]
+//! move = "500$5"
+//! to = "561$5"
+//! ```
+//!
+//! `copy` takes a `to` as well. Deletions and replacements check a condition on
+//! their own tag against each field alone: `delete 650 if 650$2=fast` only
+//! deletes the FAST headings. Other conditions are checked against the whole record.
+//!
+//! ```
+//! use marc_rs::{parse_mrk, Patch, PatchOperation, PatchStep};
+//!
+//! let mut record = parse_mrk("=LDR  00000nam a2200000 a 4500\n=500  \\\\$aSigned by the author.$5DLC\n=650  \\7$aHobbits.$2fast\n").unwrap().remove(0);
+//! let patch = Patch {
+//!     steps: vec![
+//!         PatchStep { operation: PatchOperation::delete("650").unwrap(), condition: Some("650$2=fast".parse().unwrap()) },
+//!         PatchStep { operation: PatchOperation::move_to("500$5", "561$5").unwrap(), condition: None },
+//!     ],
+//! };
+//! assert!(patch.apply(&mut record));
+//! let tags: Vec<&str> = record.data_fields.iter().map(|f| f.tag.as_str()).collect();
+//! assert_eq!(tags, ["500", "561"]);
+//! ```
+
+use crate::record::{ControlField, DataField, Record, Subfield};
+use crate::spec::{MarcSpec, SpecCondition, SpecTarget};
+
+/// Text replaced by a patch: literal, or a regular expression written `/.../`
+#[derive(Debug, Clone)]
+pub enum Find {
+    Text(String),
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex),
+}
+
+impl Find {
+    /// Read a literal text, or a `/regular expression/` with the `regex` feature
+    pub fn parse(find: &str) -> Result<Self, String> {
+        match find.strip_prefix('/').and_then(|f| f.strip_suffix('/')) {
+            #[cfg(feature = "regex")]
+            Some(pattern) => regex::Regex::new(pattern).map(Find::Regex).map_err(|e| e.to_string()),
+            #[cfg(not(feature = "regex"))]
+            Some(_) => Err("regular expressions need the regex feature".to_string()),
+            None if find.is_empty() => Err("nothing to replace".to_string()),
+            None => Ok(Find::Text(find.to_string())),
+        }
+    }
+
+    /// Replace every match in a value
+    pub fn replace(&self, value: &mut String, replacement: &str) {
+        let replaced = match self {
+            Find::Text(text) => value.replace(text.as_str(), replacement),
+            #[cfg(feature = "regex")]
+            Find::Regex(regex) => regex.replace_all(value, replacement).into_owned(),
+        };
+        *value = replaced;
+    }
+}
+
+/// Field added by a patch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NewField {
+    Control(ControlField),
+    Data(DataField),
+}
+
+/// Operation of a patch
+#[derive(Debug, Clone)]
+pub enum PatchOperation {
+    /// Add a field, in tag order
+    Add(NewField),
+    /// Delete fields, or subfields (fields left without subfields are deleted)
+    Delete(MarcSpec),
+    /// Replace text in field or subfield values
+    Replace { spec: MarcSpec, find: Find, replacement: String },
+    /// Add a field with a `code` subfield (or a control field) for each value selected by `source`
+    Copy { source: MarcSpec, tag: String, code: Option<char> },
+    /// Copy, then delete the source fields or subfields
+    Move { source: MarcSpec, tag: String, code: Option<char> },
+}
+
+impl PatchOperation {
+    /// Add a field written as a MarcMaker line, e.g. `=590  \\$aDigitized`
+    pub fn add(mrk: &str) -> Result<Self, String> {
+        Ok(match mrk.get(1..4) {
+            Some(tag) if tag < "010" => PatchOperation::Add(NewField::Control(ControlField::from_mrk(mrk)?)),
+            _ => PatchOperation::Add(NewField::Data(DataField::from_mrk(mrk)?)),
+        })
+    }
+
+    /// Delete the fields (`650`, `9..`) or subfields (`245$h`) of a spec
+    pub fn delete(spec: &str) -> Result<Self, String> {
+        Ok(PatchOperation::Delete(edit_spec(spec)?))
+    }
+
+    /// Replace `find` by `replacement` in the values of a spec
+    pub fn replace(spec: &str, find: &str, replacement: &str) -> Result<Self, String> {
+        Ok(PatchOperation::Replace {
+            spec: edit_spec(spec)?,
+            find: Find::parse(find)?,
+            replacement: replacement.to_string(),
+        })
+    }
+
+    /// Copy the values of `source` to new `target` fields, e.g. `700$a` or `009`
+    pub fn copy(source: &str, target: &str) -> Result<Self, String> {
+        let (tag, code) = copy_target(target)?;
+        Ok(PatchOperation::Copy {
+            source: MarcSpec::parse(source)?,
+            tag,
+            code,
+        })
+    }
+
+    /// Move the values of `source` to new `target` fields
+    pub fn move_to(source: &str, target: &str) -> Result<Self, String> {
+        let (tag, code) = copy_target(target)?;
+        Ok(PatchOperation::Move {
+            source: edit_spec(source)?,
+            tag,
+            code,
+        })
+    }
+}
+
+/// Spec of the fields or subfields an operation changes
+fn edit_spec(spec: &str) -> Result<MarcSpec, String> {
+    let spec = MarcSpec::parse(spec)?;
+    if spec.tag == "LDR" || spec.index.is_some() || spec.positions.is_some() || matches!(spec.target, SpecTarget::Indicator(_)) {
+        return Err(format!("{}: only fields and subfields can be edited", spec));
+    }
+    Ok(spec)
+}
+
+/// Tag and subfield code of the fields a copy adds
+fn copy_target(target: &str) -> Result<(String, Option<char>), String> {
+    let target = edit_spec(target)?;
+    let code = match &target.target {
+        SpecTarget::Subfields(codes) if codes.len() == 1 && target.tag.as_str() >= "010" => Some(codes[0]),
+        SpecTarget::Field if target.tag.as_str() < "010" => None,
+        _ => return Err(format!("{}: copy to a subfield, e.g. 700$a, or a control field", target)),
+    };
+    if target.tag.contains('.') {
+        return Err(format!("{}: copy to a single tag", target));
+    }
+    Ok((target.tag, code))
+}
+
+/// Operation of a patch, optionally only applied where its condition holds
+#[derive(Debug, Clone)]
+pub struct PatchStep {
+    pub operation: PatchOperation,
+    pub condition: Option<SpecCondition>,
+}
+
+impl PatchStep {
+    /// Apply the operation to a record
+    pub fn apply(&self, record: &mut Record) {
+        let record_holds = self.condition.as_ref().is_none_or(|c| c.matches(record));
+        let leader = record.leader.clone();
+        let alone = |control_fields: Vec<ControlField>, data_fields: Vec<DataField>| Record {
+            leader: leader.clone(),
+            control_fields,
+            data_fields,
+        };
+        let control_holds = |field: &ControlField| match &self.condition {
+            Some(c) if c.spec.matches_tag(&field.tag) => c.matches(&alone(vec![field.clone()], vec![])),
+            _ => record_holds,
+        };
+        let data_holds = |field: &DataField| match &self.condition {
+            Some(c) if c.spec.matches_tag(&field.tag) => c.matches(&alone(vec![], vec![field.clone()])),
+            _ => record_holds,
+        };
+
+        match &self.operation {
+            PatchOperation::Add(field) if record_holds => match field {
+                NewField::Control(field) => insert_control(record, field.clone()),
+                NewField::Data(field) => insert_data(record, field.clone()),
+            },
+            PatchOperation::Add(_) => {}
+            PatchOperation::Delete(spec) => delete(record, spec, control_holds, data_holds),
+            PatchOperation::Replace { spec, find, replacement } => {
+                if spec.target == SpecTarget::Field {
+                    for field in record.control_fields.iter_mut().filter(|f| spec.matches_tag(&f.tag)) {
+                        if control_holds(field) {
+                            find.replace(&mut field.value, replacement);
+                        }
+                    }
+                }
+                for field in record.data_fields.iter_mut().filter(|f| spec.matches_tag(&f.tag)) {
+                    if !data_holds(field) {
+                        continue;
+                    }
+                    for subfield in &mut field.subfields {
+                        let selected = match &spec.target {
+                            SpecTarget::Subfields(codes) => codes.contains(&subfield.code),
+                            _ => true,
+                        };
+                        if selected {
+                            find.replace(&mut subfield.value, replacement);
+                        }
+                    }
+                }
+            }
+            PatchOperation::Copy { source, tag, code } | PatchOperation::Move { source, tag, code } if record_holds => {
+                let values = source.values(record);
+                if matches!(self.operation, PatchOperation::Move { .. }) {
+                    delete(record, source, |_| true, |_| true);
+                }
+                for value in values {
+                    match code {
+                        Some(code) => insert_data(
+                            record,
+                            DataField {
+                                tag: tag.clone(),
+                                ind1: ' ',
+                                ind2: ' ',
+                                subfields: vec![Subfield { code: *code, value }],
+                            },
+                        ),
+                        None => insert_control(record, ControlField { tag: tag.clone(), value }),
+                    }
+                }
+            }
+            PatchOperation::Copy { .. } | PatchOperation::Move { .. } => {}
+        }
+    }
+}
+
+/// Delete the fields or subfields of a spec, in the fields where the condition holds
+fn delete(record: &mut Record, spec: &MarcSpec, control_holds: impl Fn(&ControlField) -> bool, data_holds: impl Fn(&DataField) -> bool) {
+    match &spec.target {
+        SpecTarget::Subfields(codes) => {
+            record.data_fields.retain_mut(|field| {
+                if !spec.matches_tag(&field.tag) || !data_holds(field) {
+                    return true;
+                }
+                field.subfields.retain(|s| !codes.contains(&s.code));
+                !field.subfields.is_empty()
+            });
+        }
+        _ => {
+            record.control_fields.retain(|field| !spec.matches_tag(&field.tag) || !control_holds(field));
+            record.data_fields.retain(|field| !spec.matches_tag(&field.tag) || !data_holds(field));
+        }
+    }
+}
+
+/// Insert a field after the last one with a lower or equal tag
+fn insert_control(record: &mut Record, field: ControlField) {
+    let at = record.control_fields.iter().rposition(|f| f.tag <= field.tag).map_or(0, |i| i + 1);
+    record.control_fields.insert(at, field);
+}
+
+fn insert_data(record: &mut Record, field: DataField) {
+    let at = record.data_fields.iter().rposition(|f| f.tag <= field.tag).map_or(0, |i| i + 1);
+    record.data_fields.insert(at, field);
+}
+
+/// Operations applied in order to each record
+#[derive(Debug, Clone, Default)]
+pub struct Patch {
+    pub steps: Vec<PatchStep>,
+}
+
+impl Patch {
+    /// Apply the operations to a record, returning true if it changed
+    pub fn apply(&self, record: &mut Record) -> bool {
+        let original = record.clone();
+        for step in &self.steps {
+            step.apply(record);
+        }
+        *record != original
+    }
+
+    /// Read a patch from a JSON value: `{"operation": [{"delete": "9.."}, ...]}`
+    ///
+    /// Each operation has one of `add` (a MarcMaker line), `delete` (a spec),
+    /// `replace` (a spec, with `find` and `with`), `copy` or `move` (a spec,
+    /// with `to`), and an optional `if` condition.
+    #[cfg(feature = "serde")]
+    pub fn from_value(value: &serde_json::Value) -> Result<Self, String> {
+        let operations = match value.get("operation") {
+            Some(serde_json::Value::Array(operations)) => operations,
+            Some(_) => return Err("operation must be a list of operations".to_string()),
+            None => return Err("no operation list".to_string()),
+        };
+        let steps = operations.iter().enumerate().map(|(i, operation)| parse_step(operation).map_err(|e| format!("operation #{}: {}", i + 1, e))).collect::<Result<_, _>>()?;
+        Ok(Self { steps })
+    }
+
+    /// Read a patch from JSON text, see [`Patch::from_value`]
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        Self::from_value(&serde_json::from_str(json).map_err(|e| e.to_string())?)
+    }
+}
+
+#[cfg(feature = "serde")]
+fn parse_step(operation: &serde_json::Value) -> Result<PatchStep, String> {
+    let operation = operation.as_object().ok_or("not an object")?;
+    let string = |key: &str| match operation.get(key) {
+        Some(serde_json::Value::String(value)) => Ok(Some(value.as_str())),
+        Some(_) => Err(format!("{} must be a string", key)),
+        None => Ok(None),
+    };
+    let required = |key: &str, kind: &str| string(key)?.ok_or_else(|| format!("{} needs a {} key", kind, key));
+    if let Some(key) = operation.keys().find(|key| !["add", "delete", "replace", "copy", "move", "find", "with", "to", "if"].contains(&key.as_str())) {
+        return Err(format!("unknown key {}", key));
+    }
+
+    let kinds: Vec<&str> = ["add", "delete", "replace", "copy", "move"].into_iter().filter(|kind| operation.contains_key(*kind)).collect();
+    let operation = match kinds.as_slice() {
+        ["add"] => PatchOperation::add(required("add", "add")?)?,
+        ["delete"] => PatchOperation::delete(required("delete", "delete")?)?,
+        ["replace"] => PatchOperation::replace(required("replace", "replace")?, required("find", "replace")?, required("with", "replace")?)?,
+        ["copy"] => PatchOperation::copy(required("copy", "copy")?, required("to", "copy")?)?,
+        ["move"] => PatchOperation::move_to(required("move", "move")?, required("to", "move")?)?,
+        [] => return Err("expected one of add, delete, replace, copy or move".to_string()),
+        _ => return Err("expected a single operation".to_string()),
+    };
+    Ok(PatchStep {
+        operation,
+        condition: string("if")?.map(SpecCondition::parse).transpose()?,
+    })
+}
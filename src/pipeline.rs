@@ -0,0 +1,270 @@
+//! Record-processing pipelines: stages chained over a stream of records.
+//!
+//! A [`Pipeline`] filters, transforms and validates records, then routes each
+//! one to an output. Records are read and processed in batches, so memory stays
+//! bounded whatever the size of the source; with [`Pipeline::jobs`] the records
+//! of a batch are processed on several threads, and still written in order.
+//!
+//! ```
+//! use marc_rs::{parse_mrk, Pipeline, Severity, Validator, MarcFormat, Record};
+//!
+//! let records = parse_mrk(
+//!     "=LDR  00000nam a2200000 a 4500\n=001  1\n=008  000000s1954\\\\\\\\enk|||||||||||||||||eng\\d\n=245  10$aThe hobbit\n\n\
+//!      =LDR  00000nam a2200000 a 4500\n=001  2\n=008  000000s1972\\\\\\\\fr\\|||||||||||||||||fre\\d\n=245  13$aLe Hobbit\n\n\
+//!      =LDR  00000ngm a2200000 a 4500\n=001  3\n=245  00$aThe hobbit$h[videorecording]\n",
+//! )
+//! .unwrap();
+//! let validator = Validator::new(MarcFormat::Marc21);
+//! let pipeline = Pipeline::new()
+//!     .filter_spec("LDR/6=a".parse().unwrap())
+//!     .map(|mut record| {
+//!         // Drop the last-transaction date of the source system
+//!         record.control_fields.retain(|f| f.tag != "005");
+//!         record
+//!     })
+//!     .validate(move |record| validator.validate(record), Severity::Error)
+//!     .split_spec("008/35-37=fre".parse().unwrap());
+//!
+//! let (mut english, mut french): (Vec<Record>, Vec<Record>) = (Vec::new(), Vec::new());
+//! let report = pipeline.run(records.into_iter().map(Ok), &mut [&mut english, &mut french]).unwrap();
+//! assert_eq!((report.read, report.filtered, report.written.as_slice()), (3, 1, [1, 1].as_slice()));
+//! assert_eq!(french[0].control_fields[0].value, "2");
+//! ```
+
+use crate::parser::ParseError;
+use crate::record::Record;
+use crate::spec::SpecCondition;
+use crate::validation::{Issue, Severity};
+use crate::writer::{RecordWriter, WriteError};
+use std::io::Write;
+
+/// Records read and processed at a time, by default
+const DEFAULT_BATCH_SIZE: usize = 1000;
+
+/// Error stopping a pipeline
+#[derive(Debug)]
+pub enum PipelineError {
+    /// The source failed to read a record; `index` is its 0-based position in the source
+    Parse { index: usize, error: ParseError },
+    /// An output failed to write a record
+    Write(WriteError),
+    /// A record was routed to an output that does not exist
+    NoOutput(usize),
+}
+
+impl std::fmt::Display for PipelineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PipelineError::Parse { index, error } => write!(f, "Record #{}: {}", index + 1, error),
+            PipelineError::Write(e) => write!(f, "{}", e),
+            PipelineError::NoOutput(output) => write!(f, "No output #{}", output),
+        }
+    }
+}
+
+impl std::error::Error for PipelineError {}
+
+impl From<WriteError> for PipelineError {
+    fn from(e: WriteError) -> Self {
+        PipelineError::Write(e)
+    }
+}
+
+/// Destination of the records leaving a pipeline
+pub trait PipelineOutput {
+    /// Take one record
+    fn write(&mut self, record: Record) -> Result<(), WriteError>;
+}
+
+impl PipelineOutput for Vec<Record> {
+    fn write(&mut self, record: Record) -> Result<(), WriteError> {
+        self.push(record);
+        Ok(())
+    }
+}
+
+impl<W: Write> PipelineOutput for RecordWriter<W> {
+    fn write(&mut self, record: Record) -> Result<(), WriteError> {
+        RecordWriter::write(self, &record)
+    }
+}
+
+/// Counts of a pipeline run
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PipelineReport {
+    /// Records read from the source
+    pub read: usize,
+    /// Records dropped by a filter
+    pub filtered: usize,
+    /// Records dropped by a validation stage
+    pub rejected: usize,
+    /// Records written to each output
+    pub written: Vec<usize>,
+}
+
+type Check = Box<dyn Fn(&Record) -> Vec<Issue> + Send + Sync>;
+type Route = Box<dyn Fn(&Record) -> usize + Send + Sync>;
+
+enum Stage {
+    Filter(Box<dyn Fn(&Record) -> bool + Send + Sync>),
+    Map(Box<dyn Fn(Record) -> Record + Send + Sync>),
+    Validate(Check, Severity),
+}
+
+/// What became of a record
+enum Outcome {
+    Filtered,
+    Rejected,
+    Output(usize, Record),
+}
+
+/// Stages applied in order to each record, and the routing of the records kept
+///
+/// Without [`split`](Pipeline::split), all records go to the first output.
+pub struct Pipeline {
+    stages: Vec<Stage>,
+    route: Option<Route>,
+    jobs: usize,
+    batch_size: usize,
+}
+
+impl Default for Pipeline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Pipeline {
+    /// An empty pipeline, copying records to the first output on one thread
+    pub fn new() -> Self {
+        Self {
+            stages: Vec::new(),
+            route: None,
+            jobs: 1,
+            batch_size: DEFAULT_BATCH_SIZE,
+        }
+    }
+
+    /// Keep only the records for which `predicate` is true
+    pub fn filter(mut self, predicate: impl Fn(&Record) -> bool + Send + Sync + 'static) -> Self {
+        self.stages.push(Stage::Filter(Box::new(predicate)));
+        self
+    }
+
+    /// Keep only the records satisfying a condition, e.g. `008/35-37=fre`
+    pub fn filter_spec(self, condition: SpecCondition) -> Self {
+        self.filter(move |record| condition.matches(record))
+    }
+
+    /// Transform each record
+    pub fn map(mut self, transform: impl Fn(Record) -> Record + Send + Sync + 'static) -> Self {
+        self.stages.push(Stage::Map(Box::new(transform)));
+        self
+    }
+
+    /// Drop the records with an issue of `severity` or worse, e.g. `move |r| validator.validate(r)`
+    pub fn validate(mut self, check: impl Fn(&Record) -> Vec<Issue> + Send + Sync + 'static, severity: Severity) -> Self {
+        self.stages.push(Stage::Validate(Box::new(check), severity));
+        self
+    }
+
+    /// Route each record to the output of index `route(record)`, after all stages
+    pub fn split(mut self, route: impl Fn(&Record) -> usize + Send + Sync + 'static) -> Self {
+        self.route = Some(Box::new(route));
+        self
+    }
+
+    /// Route the records satisfying a condition to the second output, the others to the first
+    pub fn split_spec(self, condition: SpecCondition) -> Self {
+        self.split(move |record| condition.matches(record) as usize)
+    }
+
+    /// Number of threads processing records; stages must then be thread-safe, which their bounds ensure
+    pub fn jobs(mut self, jobs: usize) -> Self {
+        self.jobs = jobs.max(1);
+        self
+    }
+
+    /// Number of records read and processed at a time, bounding memory use
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Run the stages on one record
+    fn process(&self, mut record: Record) -> Outcome {
+        for stage in &self.stages {
+            match stage {
+                Stage::Filter(predicate) if !predicate(&record) => return Outcome::Filtered,
+                Stage::Filter(_) => {}
+                Stage::Map(transform) => record = transform(record),
+                Stage::Validate(check, severity) if check(&record).iter().any(|issue| issue.severity >= *severity) => return Outcome::Rejected,
+                Stage::Validate(..) => {}
+            }
+        }
+        let output = self.route.as_ref().map_or(0, |route| route(&record));
+        Outcome::Output(output, record)
+    }
+
+    /// Process a batch, on several threads when configured
+    fn process_batch(&self, batch: Vec<Record>) -> Vec<Outcome> {
+        if self.jobs == 1 || batch.len() < 2 {
+            return batch.into_iter().map(|record| self.process(record)).collect();
+        }
+        let chunk_size = batch.len().div_ceil(self.jobs);
+        let mut records = batch.into_iter();
+        let chunks: Vec<Vec<Record>> = (0..self.jobs).map(|_| records.by_ref().take(chunk_size).collect()).collect();
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = chunks.into_iter().map(|chunk| scope.spawn(move || chunk.into_iter().map(|record| self.process(record)).collect::<Vec<_>>())).collect();
+            handles.into_iter().flat_map(|handle| handle.join().unwrap_or_else(|e| std::panic::resume_unwind(e))).collect()
+        })
+    }
+
+    /// Run the pipeline over a source of records, writing the records kept to `outputs`
+    ///
+    /// A source error stops the run once the records before it are written.
+    pub fn run<I>(&self, source: I, outputs: &mut [&mut dyn PipelineOutput]) -> Result<PipelineReport, PipelineError>
+    where
+        I: IntoIterator<Item = Result<Record, ParseError>>,
+    {
+        let mut report = PipelineReport {
+            written: vec![0; outputs.len()],
+            ..PipelineReport::default()
+        };
+        let mut source = source.into_iter();
+        loop {
+            let mut batch = Vec::with_capacity(self.batch_size);
+            let mut error = None;
+            for result in source.by_ref() {
+                match result {
+                    Ok(record) => batch.push(record),
+                    Err(e) => {
+                        error = Some(PipelineError::Parse { index: report.read + batch.len(), error: e });
+                        break;
+                    }
+                }
+                if batch.len() == self.batch_size {
+                    break;
+                }
+            }
+            if batch.is_empty() && error.is_none() {
+                return Ok(report);
+            }
+
+            report.read += batch.len();
+            for outcome in self.process_batch(batch) {
+                match outcome {
+                    Outcome::Filtered => report.filtered += 1,
+                    Outcome::Rejected => report.rejected += 1,
+                    Outcome::Output(output, record) => {
+                        outputs.get_mut(output).ok_or(PipelineError::NoOutput(output))?.write(record)?;
+                        report.written[output] += 1;
+                    }
+                }
+            }
+            if let Some(error) = error {
+                return Err(error);
+            }
+        }
+    }
+}
@@ -0,0 +1,293 @@
+//! Collection profiling: statistics aggregated across a stream of records.
+//!
+//! A [`Profile`] counts record types, tags, indicators and subfield codes,
+//! value lengths, record sizes and encoding problems, for checking vendor
+//! files before loading them. With the `serde` feature it serializes as a report.
+//!
+//! ```
+//! use marc_rs::{parse_mrk, FormatEncoding, Profile};
+//!
+//! let records = parse_mrk("=LDR  00000nam a2200000 a 4500\n=001  1\n=245  10$aThe hobbit /$cTolkien.\n\n=LDR  00000ngm a2200000 a 4500\n=245  00$aThe hobbit$h[videorecording]\n").unwrap();
+//! let profile = Profile::from_records(&records, FormatEncoding::marc21_default());
+//! assert_eq!(profile.records, 2);
+//! assert_eq!(profile.record_types["am"], 1);
+//! assert_eq!(profile.tags["245"].ind1[&'1'], 1);
+//! assert_eq!(profile.tags["245"].subfields[&'a'].lengths.max, 12);
+//! ```
+
+use crate::format::{Encoding, FormatEncoding, MarcFormat};
+use crate::record::Record;
+use crate::writer::write_one;
+use std::collections::BTreeMap;
+use unicode_normalization::is_nfc;
+
+/// Distribution of lengths
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LengthHistogram {
+    /// Number of lengths counted
+    pub count: usize,
+    /// Shortest length, 0 when nothing was counted
+    pub min: usize,
+    /// Longest length
+    pub max: usize,
+    /// Sum of the lengths
+    pub total: usize,
+    /// Counts by power-of-two bucket: key `n` counts lengths from `n` to `2n - 1`, key 0 empty values
+    pub buckets: BTreeMap<usize, usize>,
+}
+
+impl LengthHistogram {
+    /// Count a length
+    pub fn add(&mut self, length: usize) {
+        self.min = if self.count == 0 { length } else { self.min.min(length) };
+        self.max = self.max.max(length);
+        self.count += 1;
+        self.total += length;
+        let bucket = match length {
+            0 => 0,
+            length => 1 << length.ilog2(),
+        };
+        *self.buckets.entry(bucket).or_default() += 1;
+    }
+
+    /// Average length, rounded down
+    pub fn average(&self) -> Option<usize> {
+        self.total.checked_div(self.count)
+    }
+}
+
+/// Statistics of a subfield code within a tag
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SubfieldProfile {
+    /// Records with the subfield
+    pub records: usize,
+    /// Occurrences of the subfield
+    pub occurrences: usize,
+    /// Lengths of the values, in characters
+    pub lengths: LengthHistogram,
+}
+
+/// Statistics of a tag
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TagProfile {
+    /// Records with the tag
+    pub records: usize,
+    /// Occurrences of the tag
+    pub occurrences: usize,
+    /// Occurrences by first indicator value; empty for control fields
+    pub ind1: BTreeMap<char, usize>,
+    /// Occurrences by second indicator value; empty for control fields
+    pub ind2: BTreeMap<char, usize>,
+    /// Subfield statistics by code; empty for control fields
+    pub subfields: BTreeMap<char, SubfieldProfile>,
+    /// Lengths of control field values, in characters
+    pub lengths: LengthHistogram,
+}
+
+/// Records with a possible encoding problem, by kind
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EncodingProfile {
+    /// Records with replacement characters (U+FFFD), left by decoding errors
+    pub replacement_characters: usize,
+    /// Records with values not in Unicode NFC
+    pub not_nfc: usize,
+    /// MARC21 records whose leader/09 does not match the encoding of the file
+    pub leader_mismatch: usize,
+    /// Records that cannot be written in the encoding of the file
+    pub not_encodable: usize,
+}
+
+/// Statistics of a collection of records
+#[derive(Debug, Clone)]
+pub struct Profile {
+    /// Format and encoding of the file the records come from
+    pub format: FormatEncoding,
+    /// Number of records
+    pub records: usize,
+    /// Records by leader/06-07, e.g. `am` for books
+    pub record_types: BTreeMap<String, usize>,
+    /// Statistics of each tag
+    pub tags: BTreeMap<String, TagProfile>,
+    /// ISO 2709 record lengths in bytes, in the encoding of the file (UTF-8 MARC21 for MARC XML)
+    pub sizes: LengthHistogram,
+    /// Encoding problems
+    pub encoding: EncodingProfile,
+}
+
+impl Profile {
+    /// An empty profile of records read in the given format and encoding
+    ///
+    /// Use MARC XML for records read from any text format: their sizes are
+    /// then UTF-8 MARC21 lengths, and their leader/09 is not checked.
+    pub fn new(format: FormatEncoding) -> Self {
+        Self {
+            format,
+            records: 0,
+            record_types: BTreeMap::new(),
+            tags: BTreeMap::new(),
+            sizes: LengthHistogram::default(),
+            encoding: EncodingProfile::default(),
+        }
+    }
+
+    /// Profile of a list of records
+    pub fn from_records<'a>(records: impl IntoIterator<Item = &'a Record>, format: FormatEncoding) -> Self {
+        let mut profile = Self::new(format);
+        for record in records {
+            profile.add(record);
+        }
+        profile
+    }
+
+    /// Add a record to the statistics
+    pub fn add(&mut self, record: &Record) {
+        self.records += 1;
+        *self.record_types.entry(format!("{}{}", record.leader.record_type, record.leader.bibliographic_level)).or_default() += 1;
+
+        let size_format = match self.format.format {
+            MarcFormat::MarcXml => FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8),
+            _ => self.format,
+        };
+        let mut bytes = Vec::new();
+        match write_one(record, size_format, &mut bytes) {
+            Ok(()) => self.sizes.add(bytes.len()),
+            Err(_) => self.encoding.not_encodable += 1,
+        }
+
+        // Tags and subfields seen in this record, to count records once
+        let mut seen: Vec<(&str, Option<char>)> = Vec::new();
+        for field in &record.control_fields {
+            let tag = self.tags.entry(field.tag.clone()).or_default();
+            tag.occurrences += 1;
+            tag.lengths.add(field.value.chars().count());
+            seen.push((&field.tag, None));
+        }
+        for field in &record.data_fields {
+            let tag = self.tags.entry(field.tag.clone()).or_default();
+            tag.occurrences += 1;
+            *tag.ind1.entry(field.ind1).or_default() += 1;
+            *tag.ind2.entry(field.ind2).or_default() += 1;
+            seen.push((&field.tag, None));
+            for subfield in &field.subfields {
+                let profile = tag.subfields.entry(subfield.code).or_default();
+                profile.occurrences += 1;
+                profile.lengths.add(subfield.value.chars().count());
+                seen.push((&field.tag, Some(subfield.code)));
+            }
+        }
+        seen.sort_unstable();
+        seen.dedup();
+        for (tag, code) in seen {
+            let tag = self.tags.get_mut(tag).expect("tag counted above");
+            match code {
+                None => tag.records += 1,
+                Some(code) => tag.subfields.get_mut(&code).expect("subfield counted above").records += 1,
+            }
+        }
+
+        let mut values = record.control_fields.iter().map(|f| f.value.as_str()).chain(record.data_fields.iter().flat_map(|f| f.subfields.iter().map(|s| s.value.as_str())));
+        if values.clone().any(|v| v.contains('\u{FFFD}')) {
+            self.encoding.replacement_characters += 1;
+        }
+        if !values.all(is_nfc) {
+            self.encoding.not_nfc += 1;
+        }
+        if self.format.format == MarcFormat::Marc21 && (record.leader.character_coding_scheme == 'a') != (self.format.encoding == Encoding::Utf8) {
+            self.encoding.leader_mismatch += 1;
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for LengthHistogram {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("LengthHistogram", 5)?;
+        state.serialize_field("count", &self.count)?;
+        state.serialize_field("min", &self.min)?;
+        state.serialize_field("max", &self.max)?;
+        state.serialize_field("average", &self.average())?;
+        state.serialize_field("buckets", &self.buckets)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for SubfieldProfile {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("SubfieldProfile", 3)?;
+        state.serialize_field("records", &self.records)?;
+        state.serialize_field("occurrences", &self.occurrences)?;
+        state.serialize_field("lengths", &self.lengths)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for TagProfile {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        // Control fields have lengths, data fields indicators and subfields
+        let mut state = serializer.serialize_struct("TagProfile", 5)?;
+        state.serialize_field("records", &self.records)?;
+        state.serialize_field("occurrences", &self.occurrences)?;
+        if self.subfields.is_empty() && self.ind1.is_empty() {
+            state.serialize_field("lengths", &self.lengths)?;
+        } else {
+            state.serialize_field("ind1", &self.ind1)?;
+            state.serialize_field("ind2", &self.ind2)?;
+            state.serialize_field("subfields", &self.subfields)?;
+        }
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for EncodingProfile {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("EncodingProfile", 4)?;
+        state.serialize_field("replacement_characters", &self.replacement_characters)?;
+        state.serialize_field("not_nfc", &self.not_nfc)?;
+        state.serialize_field("leader_mismatch", &self.leader_mismatch)?;
+        state.serialize_field("not_encodable", &self.not_encodable)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Profile {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("Profile", 7)?;
+        state.serialize_field("format", &self.format.format.to_string())?;
+        state.serialize_field("encoding", &self.format.encoding.to_string())?;
+        state.serialize_field("records", &self.records)?;
+        state.serialize_field("record_types", &self.record_types)?;
+        state.serialize_field("sizes", &self.sizes)?;
+        state.serialize_field("tags", &self.tags)?;
+        state.serialize_field("encoding_warnings", &self.encoding)?;
+        state.end()
+    }
+}
@@ -0,0 +1,256 @@
+use crate::format::MarcFormat;
+use crate::punctuation::strip_isbd_punctuation_for_tag;
+use crate::record::{DataField, Record, Subfield};
+
+/// Function of the entity named in a publication statement (264/214 second indicator)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PublicationFunction {
+    /// `0` - Production (unpublished resources)
+    Production,
+    /// `1` - Publication
+    Publication,
+    /// `2` - Distribution
+    Distribution,
+    /// `3` - Manufacture
+    Manufacture,
+    /// `4` - Copyright notice date
+    Copyright,
+}
+
+impl PublicationFunction {
+    /// Get the function from the 264/214 second indicator
+    pub fn from_indicator(ind: char) -> Option<Self> {
+        match ind {
+            '0' => Some(PublicationFunction::Production),
+            '1' => Some(PublicationFunction::Publication),
+            '2' => Some(PublicationFunction::Distribution),
+            '3' => Some(PublicationFunction::Manufacture),
+            '4' => Some(PublicationFunction::Copyright),
+            _ => None,
+        }
+    }
+
+    /// Get the 264/214 second indicator value of this function
+    pub fn indicator(&self) -> char {
+        match self {
+            PublicationFunction::Production => '0',
+            PublicationFunction::Publication => '1',
+            PublicationFunction::Distribution => '2',
+            PublicationFunction::Manufacture => '3',
+            PublicationFunction::Copyright => '4',
+        }
+    }
+}
+
+/// Field used to record publication statements
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PublicationConvention {
+    /// 260 - Publication, distribution, etc. (imprint), pre-RDA MARC21
+    Imprint,
+    /// 264 - Production, publication, distribution, manufacture, and copyright notice (RDA)
+    Rda,
+    /// 210 - Publication, distribution, etc. (UNIMARC)
+    UnimarcImprint,
+    /// 214 - Publication, production, distribution, manufacture and copyright notice (UNIMARC)
+    UnimarcRda,
+}
+
+impl PublicationConvention {
+    /// All conventions, in declaration order
+    pub const ALL: &'static [PublicationConvention] = &[
+        PublicationConvention::Imprint,
+        PublicationConvention::Rda,
+        PublicationConvention::UnimarcImprint,
+        PublicationConvention::UnimarcRda,
+    ];
+
+    /// Tag of the field used by this convention
+    pub fn tag(&self) -> &'static str {
+        match self {
+            PublicationConvention::Imprint => "260",
+            PublicationConvention::Rda => "264",
+            PublicationConvention::UnimarcImprint => "210",
+            PublicationConvention::UnimarcRda => "214",
+        }
+    }
+
+    /// Format the convention belongs to
+    pub fn format(&self) -> MarcFormat {
+        match self {
+            PublicationConvention::Imprint | PublicationConvention::Rda => MarcFormat::Marc21,
+            PublicationConvention::UnimarcImprint | PublicationConvention::UnimarcRda => MarcFormat::Unimarc,
+        }
+    }
+
+    /// Convention of a tag in the given format
+    pub fn from_tag(tag: &str, format: MarcFormat) -> Option<Self> {
+        let unimarc = format == MarcFormat::Unimarc;
+        PublicationConvention::ALL
+            .iter()
+            .copied()
+            .find(|c| c.tag() == tag && (c.format() == MarcFormat::Unimarc) == unimarc)
+    }
+
+    /// Subfield codes holding the place, the name and the date
+    fn subfield_codes(&self) -> (char, char, char) {
+        match self {
+            PublicationConvention::Imprint | PublicationConvention::Rda => ('a', 'b', 'c'),
+            PublicationConvention::UnimarcImprint | PublicationConvention::UnimarcRda => ('a', 'c', 'd'),
+        }
+    }
+}
+
+/// Format-independent view of a publication statement (260/264 in MARC21, 210/214 in UNIMARC)
+///
+/// Values are stored without their ISBD punctuation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicationStatement {
+    pub function: PublicationFunction,
+    /// Places of publication, production, etc.
+    pub places: Vec<String>,
+    /// Names of publishers, producers, etc.
+    pub publishers: Vec<String>,
+    /// Dates
+    pub dates: Vec<String>,
+}
+
+impl PublicationStatement {
+    /// Create a publication statement with no values
+    pub fn new(function: PublicationFunction) -> Self {
+        Self {
+            function,
+            places: Vec::new(),
+            publishers: Vec::new(),
+            dates: Vec::new(),
+        }
+    }
+
+    /// Read a 260/264 (MARC21) or 210/214 (UNIMARC) field
+    ///
+    /// 260 and 210 are read as [`PublicationFunction::Publication`], except a 210 whose
+    /// second indicator is `1` (not published), read as [`PublicationFunction::Production`].
+    /// Manufacture subfields of 260/210 are ignored.
+    pub fn from_field(field: &DataField, format: MarcFormat) -> Option<Self> {
+        let convention = PublicationConvention::from_tag(&field.tag, format)?;
+        let function = match convention {
+            PublicationConvention::Imprint => PublicationFunction::Publication,
+            PublicationConvention::UnimarcImprint if field.ind2 == '1' => PublicationFunction::Production,
+            PublicationConvention::UnimarcImprint => PublicationFunction::Publication,
+            PublicationConvention::Rda | PublicationConvention::UnimarcRda => PublicationFunction::from_indicator(field.ind2)?,
+        };
+
+        let (place, name, date) = convention.subfield_codes();
+        let values = |code: char| {
+            field
+                .subfields
+                .iter()
+                .filter(|s| s.code == code)
+                .map(|s| strip_isbd_punctuation_for_tag(&field.tag, &s.value).to_string())
+                .filter(|v| !v.is_empty())
+                .collect()
+        };
+
+        Some(Self {
+            function,
+            places: values(place),
+            publishers: values(name),
+            dates: values(date),
+        })
+    }
+
+    /// First place, if any
+    pub fn place(&self) -> Option<&str> {
+        self.places.first().map(|p| p.as_str())
+    }
+
+    /// First publisher, if any
+    pub fn publisher(&self) -> Option<&str> {
+        self.publishers.first().map(|p| p.as_str())
+    }
+
+    /// First date, if any
+    pub fn date(&self) -> Option<&str> {
+        self.dates.first().map(|d| d.as_str())
+    }
+
+    /// Build a field for this statement in the given convention
+    ///
+    /// MARC21 fields get ISBD punctuation (` ;`, ` :`, `,` and a closing period, except for
+    /// copyright dates); UNIMARC fields are written without it, as the subfield codes
+    /// generate the punctuation.
+    pub fn to_field(&self, convention: PublicationConvention) -> DataField {
+        let (place, name, date) = convention.subfield_codes();
+        let mut subfields: Vec<Subfield> = Vec::new();
+        for (code, values) in [(place, &self.places), (name, &self.publishers), (date, &self.dates)] {
+            subfields.extend(values.iter().map(|value| Subfield {
+                code,
+                value: value.clone(),
+            }));
+        }
+
+        if convention.format() == MarcFormat::Marc21 {
+            let next_codes: Vec<Option<char>> = subfields.iter().skip(1).map(|s| Some(s.code)).chain([None]).collect();
+            for (subfield, next) in subfields.iter_mut().zip(next_codes) {
+                match next {
+                    Some(c) if c == place => subfield.value.push_str(" ;"),
+                    Some(c) if c == name => subfield.value.push_str(" :"),
+                    Some(_) => subfield.value.push(','),
+                    None if self.function != PublicationFunction::Copyright && !subfield.value.ends_with(['.', '?', '!']) => {
+                        subfield.value.push('.')
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        let ind2 = match convention {
+            PublicationConvention::Imprint => ' ',
+            PublicationConvention::UnimarcImprint if self.function == PublicationFunction::Production => '1',
+            PublicationConvention::UnimarcImprint => ' ',
+            PublicationConvention::Rda | PublicationConvention::UnimarcRda => self.function.indicator(),
+        };
+
+        DataField {
+            tag: convention.tag().to_string(),
+            ind1: ' ',
+            ind2,
+            subfields,
+        }
+    }
+}
+
+impl Record {
+    /// All publication statements of the record, in record order
+    pub fn publication_statements(&self, format: MarcFormat) -> Vec<PublicationStatement> {
+        self.data_fields.iter().filter_map(|f| PublicationStatement::from_field(f, format)).collect()
+    }
+
+    /// First statement with the publication function, if any
+    pub fn publication(&self, format: MarcFormat) -> Option<PublicationStatement> {
+        self.publication_statements(format).into_iter().find(|s| s.function == PublicationFunction::Publication)
+    }
+
+    /// Replace the statements with the same function by `statement`, written in the given convention
+    ///
+    /// Existing 260/264 (or 210/214) fields of that function are removed, whichever
+    /// convention they use. The new field takes the place of the first removed field,
+    /// or is inserted in tag order.
+    pub fn set_publication_statement(&mut self, statement: &PublicationStatement, convention: PublicationConvention) {
+        let format = convention.format();
+        let mut position = None;
+        let mut i = 0;
+        while i < self.data_fields.len() {
+            let same_function = PublicationStatement::from_field(&self.data_fields[i], format).is_some_and(|s| s.function == statement.function);
+            if same_function {
+                self.data_fields.remove(i);
+                position.get_or_insert(i);
+            } else {
+                i += 1;
+            }
+        }
+
+        let field = statement.to_field(convention);
+        let position = position.unwrap_or_else(|| self.data_fields.iter().position(|f| f.tag > field.tag).unwrap_or(self.data_fields.len()));
+        self.data_fields.insert(position, field);
+    }
+}
@@ -0,0 +1,73 @@
+use crate::record::DataField;
+
+/// Abbreviations whose trailing period must be kept
+const ABBREVIATIONS: [&str; 22] = [
+    "ca", "cm", "col", "ed", "eds", "etc", "ill", "Inc", "Jr", "Ltd", "min", "mm", "no", "p", "pl", "port", "ports", "Sr", "v", "vol", "vols", "St",
+];
+
+/// Trailing marks removed from the values of a field, and whether a final period is removed too
+fn rules_for_tag(tag: &str) -> (&'static [char], bool) {
+    match tag {
+        // Physical description: the period is part of "p." / "cm."
+        "300" | "215" => (&[';', ':', '+'], false),
+        // Imprint: place, publisher and date separators
+        "260" | "264" | "210" | "214" => (&[';', ':', ','], true),
+        // Identifiers: only the qualifier separators
+        "010" | "011" | "020" | "022" | "024" => (&[';', ':'], false),
+        _ => (&['/', ':', ';', '=', ','], true),
+    }
+}
+
+/// Strip trailing ISBD punctuation (` /`, ` :`, ` ;`, ` =`, `,`, `.`) from a value
+pub fn strip_isbd_punctuation(value: &str) -> &str {
+    strip_trailing(value, &['/', ':', ';', '=', ','], true)
+}
+
+/// Strip trailing ISBD punctuation from a value using the rules for the given tag
+///
+/// For instance the final period of a 300 value (`xii, 345 p.`) is kept, while the
+/// one closing a 260 date (`2001.`) is removed.
+pub fn strip_isbd_punctuation_for_tag<'a>(tag: &str, value: &'a str) -> &'a str {
+    let (marks, strip_period) = rules_for_tag(tag);
+    strip_trailing(value, marks, strip_period)
+}
+
+fn strip_trailing<'a>(value: &'a str, marks: &[char], strip_period: bool) -> &'a str {
+    let mut value = value.trim_end();
+
+    loop {
+        if let Some(rest) = value.strip_suffix(|c: char| marks.contains(&c)) {
+            value = rest.trim_end();
+        } else if strip_period && value.ends_with('.') && !keeps_period(value) {
+            value = value[..value.len() - 1].trim_end();
+        } else {
+            return value;
+        }
+    }
+}
+
+/// True if the trailing period belongs to an initial, an abbreviation or an ellipsis
+fn keeps_period(value: &str) -> bool {
+    if value.ends_with("..") {
+        return true;
+    }
+
+    let word = value[..value.len() - 1].rsplit(|c: char| c.is_whitespace() || c == '(' || c == '[').next().unwrap_or("");
+    let mut chars = word.chars();
+    match (chars.next(), chars.next()) {
+        // Single letter initial, e.g. "Smith, J."
+        (Some(c), None) if c.is_alphabetic() => true,
+        _ => ABBREVIATIONS.contains(&word),
+    }
+}
+
+impl DataField {
+    /// Get the first value of a subfield with the trailing ISBD punctuation stripped
+    /// according to this field's tag
+    pub fn stripped_subfield(&self, code: char) -> Option<&str> {
+        self.subfields
+            .iter()
+            .find(|s| s.code == code)
+            .map(|s| strip_isbd_punctuation_for_tag(&self.tag, &s.value))
+    }
+}
@@ -0,0 +1,788 @@
+use crate::record::{DataField, Leader, Record};
+
+/// RDA content type (336 $b codes, source `rdacontent`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentType {
+    /// crd - cartographic dataset
+    CartographicDataset,
+    /// cri - cartographic image
+    CartographicImage,
+    /// crm - cartographic moving image
+    CartographicMovingImage,
+    /// crt - cartographic tactile image
+    CartographicTactileImage,
+    /// crn - cartographic tactile three-dimensional form
+    CartographicTactileThreeDimensionalForm,
+    /// crf - cartographic three-dimensional form
+    CartographicThreeDimensionalForm,
+    /// cod - computer dataset
+    ComputerDataset,
+    /// cop - computer program
+    ComputerProgram,
+    /// ntv - notated movement
+    NotatedMovement,
+    /// ntm - notated music
+    NotatedMusic,
+    /// prm - performed music
+    PerformedMusic,
+    /// snd - sounds
+    Sounds,
+    /// spw - spoken word
+    SpokenWord,
+    /// sti - still image
+    StillImage,
+    /// tci - tactile image
+    TactileImage,
+    /// tcm - tactile notated music
+    TactileNotatedMusic,
+    /// tcn - tactile notated movement
+    TactileNotatedMovement,
+    /// tct - tactile text
+    TactileText,
+    /// tcf - tactile three-dimensional form
+    TactileThreeDimensionalForm,
+    /// txt - text
+    Text,
+    /// tdf - three-dimensional form
+    ThreeDimensionalForm,
+    /// tdm - three-dimensional moving image
+    ThreeDimensionalMovingImage,
+    /// tdi - two-dimensional moving image
+    TwoDimensionalMovingImage,
+    /// xxx - other
+    Other,
+    /// zzz - unspecified
+    Unspecified,
+}
+
+impl ContentType {
+    /// All variants, in declaration order
+    pub const ALL: &'static [ContentType] = &[
+        ContentType::CartographicDataset,
+        ContentType::CartographicImage,
+        ContentType::CartographicMovingImage,
+        ContentType::CartographicTactileImage,
+        ContentType::CartographicTactileThreeDimensionalForm,
+        ContentType::CartographicThreeDimensionalForm,
+        ContentType::ComputerDataset,
+        ContentType::ComputerProgram,
+        ContentType::NotatedMovement,
+        ContentType::NotatedMusic,
+        ContentType::PerformedMusic,
+        ContentType::Sounds,
+        ContentType::SpokenWord,
+        ContentType::StillImage,
+        ContentType::TactileImage,
+        ContentType::TactileNotatedMusic,
+        ContentType::TactileNotatedMovement,
+        ContentType::TactileText,
+        ContentType::TactileThreeDimensionalForm,
+        ContentType::Text,
+        ContentType::ThreeDimensionalForm,
+        ContentType::ThreeDimensionalMovingImage,
+        ContentType::TwoDimensionalMovingImage,
+        ContentType::Other,
+        ContentType::Unspecified,
+    ];
+
+    /// `$2` source code for this vocabulary
+    pub const SOURCE: &'static str = "rdacontent";
+
+    /// Code, as recorded in $b
+    pub fn code(&self) -> &'static str {
+        match self {
+            ContentType::CartographicDataset => "crd",
+            ContentType::CartographicImage => "cri",
+            ContentType::CartographicMovingImage => "crm",
+            ContentType::CartographicTactileImage => "crt",
+            ContentType::CartographicTactileThreeDimensionalForm => "crn",
+            ContentType::CartographicThreeDimensionalForm => "crf",
+            ContentType::ComputerDataset => "cod",
+            ContentType::ComputerProgram => "cop",
+            ContentType::NotatedMovement => "ntv",
+            ContentType::NotatedMusic => "ntm",
+            ContentType::PerformedMusic => "prm",
+            ContentType::Sounds => "snd",
+            ContentType::SpokenWord => "spw",
+            ContentType::StillImage => "sti",
+            ContentType::TactileImage => "tci",
+            ContentType::TactileNotatedMusic => "tcm",
+            ContentType::TactileNotatedMovement => "tcn",
+            ContentType::TactileText => "tct",
+            ContentType::TactileThreeDimensionalForm => "tcf",
+            ContentType::Text => "txt",
+            ContentType::ThreeDimensionalForm => "tdf",
+            ContentType::ThreeDimensionalMovingImage => "tdm",
+            ContentType::TwoDimensionalMovingImage => "tdi",
+            ContentType::Other => "xxx",
+            ContentType::Unspecified => "zzz",
+        }
+    }
+
+    /// RDA term, as recorded in $a
+    pub fn term(&self) -> &'static str {
+        match self {
+            ContentType::CartographicDataset => "cartographic dataset",
+            ContentType::CartographicImage => "cartographic image",
+            ContentType::CartographicMovingImage => "cartographic moving image",
+            ContentType::CartographicTactileImage => "cartographic tactile image",
+            ContentType::CartographicTactileThreeDimensionalForm => "cartographic tactile three-dimensional form",
+            ContentType::CartographicThreeDimensionalForm => "cartographic three-dimensional form",
+            ContentType::ComputerDataset => "computer dataset",
+            ContentType::ComputerProgram => "computer program",
+            ContentType::NotatedMovement => "notated movement",
+            ContentType::NotatedMusic => "notated music",
+            ContentType::PerformedMusic => "performed music",
+            ContentType::Sounds => "sounds",
+            ContentType::SpokenWord => "spoken word",
+            ContentType::StillImage => "still image",
+            ContentType::TactileImage => "tactile image",
+            ContentType::TactileNotatedMusic => "tactile notated music",
+            ContentType::TactileNotatedMovement => "tactile notated movement",
+            ContentType::TactileText => "tactile text",
+            ContentType::TactileThreeDimensionalForm => "tactile three-dimensional form",
+            ContentType::Text => "text",
+            ContentType::ThreeDimensionalForm => "three-dimensional form",
+            ContentType::ThreeDimensionalMovingImage => "three-dimensional moving image",
+            ContentType::TwoDimensionalMovingImage => "two-dimensional moving image",
+            ContentType::Other => "other",
+            ContentType::Unspecified => "unspecified",
+        }
+    }
+
+    /// Look up a code
+    pub fn from_code(code: &str) -> Option<Self> {
+        let code = code.trim();
+        ContentType::ALL.iter().copied().find(|v| v.code() == code)
+    }
+
+    /// Look up a term, ignoring case and trailing punctuation
+    pub fn from_term(term: &str) -> Option<Self> {
+        let term = term.trim().trim_end_matches(['.', ',', ';']).trim();
+        ContentType::ALL.iter().copied().find(|v| v.term().eq_ignore_ascii_case(term))
+    }
+
+    /// Content type implied by leader/06 (type of record), for records without 336
+    pub fn from_leader(leader: &Leader) -> Option<Self> {
+        let content = match leader.record_type {
+            'a' | 't' => ContentType::Text,
+            'c' | 'd' => ContentType::NotatedMusic,
+            'e' | 'f' => ContentType::CartographicImage,
+            'g' => ContentType::TwoDimensionalMovingImage,
+            'i' => ContentType::Sounds,
+            'j' => ContentType::PerformedMusic,
+            'k' => ContentType::StillImage,
+            'm' => ContentType::ComputerDataset,
+            'r' => ContentType::ThreeDimensionalForm,
+            'o' | 'p' => ContentType::Other,
+            _ => return None,
+        };
+        Some(content)
+    }
+}
+
+/// RDA media type (337 $b codes, source `rdamedia`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaType {
+    /// s - audio
+    Audio,
+    /// c - computer
+    Computer,
+    /// h - microform
+    Microform,
+    /// p - microscopic
+    Microscopic,
+    /// g - projected
+    Projected,
+    /// e - stereographic
+    Stereographic,
+    /// n - unmediated
+    Unmediated,
+    /// v - video
+    Video,
+    /// x - other
+    Other,
+    /// z - unspecified
+    Unspecified,
+}
+
+impl MediaType {
+    /// All variants, in declaration order
+    pub const ALL: &'static [MediaType] = &[
+        MediaType::Audio,
+        MediaType::Computer,
+        MediaType::Microform,
+        MediaType::Microscopic,
+        MediaType::Projected,
+        MediaType::Stereographic,
+        MediaType::Unmediated,
+        MediaType::Video,
+        MediaType::Other,
+        MediaType::Unspecified,
+    ];
+
+    /// `$2` source code for this vocabulary
+    pub const SOURCE: &'static str = "rdamedia";
+
+    /// Code, as recorded in $b
+    pub fn code(&self) -> &'static str {
+        match self {
+            MediaType::Audio => "s",
+            MediaType::Computer => "c",
+            MediaType::Microform => "h",
+            MediaType::Microscopic => "p",
+            MediaType::Projected => "g",
+            MediaType::Stereographic => "e",
+            MediaType::Unmediated => "n",
+            MediaType::Video => "v",
+            MediaType::Other => "x",
+            MediaType::Unspecified => "z",
+        }
+    }
+
+    /// RDA term, as recorded in $a
+    pub fn term(&self) -> &'static str {
+        match self {
+            MediaType::Audio => "audio",
+            MediaType::Computer => "computer",
+            MediaType::Microform => "microform",
+            MediaType::Microscopic => "microscopic",
+            MediaType::Projected => "projected",
+            MediaType::Stereographic => "stereographic",
+            MediaType::Unmediated => "unmediated",
+            MediaType::Video => "video",
+            MediaType::Other => "other",
+            MediaType::Unspecified => "unspecified",
+        }
+    }
+
+    /// Look up a code
+    pub fn from_code(code: &str) -> Option<Self> {
+        let code = code.trim();
+        MediaType::ALL.iter().copied().find(|v| v.code() == code)
+    }
+
+    /// Look up a term, ignoring case and trailing punctuation
+    pub fn from_term(term: &str) -> Option<Self> {
+        let term = term.trim().trim_end_matches(['.', ',', ';']).trim();
+        MediaType::ALL.iter().copied().find(|v| v.term().eq_ignore_ascii_case(term))
+    }
+
+    /// Media type implied by the category of material in 007/00, for records without 337
+    pub fn from_007(value: &str) -> Option<Self> {
+        let media = match value.chars().next()? {
+            'a' | 'd' | 'f' | 'k' | 'q' | 't' => MediaType::Unmediated,
+            'c' => MediaType::Computer,
+            'g' | 'm' => MediaType::Projected,
+            'h' => MediaType::Microform,
+            's' => MediaType::Audio,
+            'v' => MediaType::Video,
+            'z' => MediaType::Unspecified,
+            _ => return None,
+        };
+        Some(media)
+    }
+}
+
+/// RDA carrier type (338 $b codes, source `rdacarrier`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CarrierType {
+    /// sg - audio cartridge
+    AudioCartridge,
+    /// se - audio cylinder
+    AudioCylinder,
+    /// sd - audio disc
+    AudioDisc,
+    /// si - sound-track reel
+    SoundTrackReel,
+    /// sq - audio roll
+    AudioRoll,
+    /// sw - audio wire reel
+    AudioWireReel,
+    /// ss - audiocassette
+    Audiocassette,
+    /// st - audiotape reel
+    AudiotapeReel,
+    /// sz - other audio carrier
+    OtherAudio,
+    /// ck - computer card
+    ComputerCard,
+    /// cb - computer chip cartridge
+    ComputerChipCartridge,
+    /// cd - computer disc
+    ComputerDisc,
+    /// ce - computer disc cartridge
+    ComputerDiscCartridge,
+    /// ca - computer tape cartridge
+    ComputerTapeCartridge,
+    /// cf - computer tape cassette
+    ComputerTapeCassette,
+    /// ch - computer tape reel
+    ComputerTapeReel,
+    /// cr - online resource
+    OnlineResource,
+    /// cz - other computer carrier
+    OtherComputer,
+    /// ha - aperture card
+    ApertureCard,
+    /// he - microfiche
+    Microfiche,
+    /// hf - microfiche cassette
+    MicroficheCassette,
+    /// hb - microfilm cartridge
+    MicrofilmCartridge,
+    /// hc - microfilm cassette
+    MicrofilmCassette,
+    /// hd - microfilm reel
+    MicrofilmReel,
+    /// hj - microfilm roll
+    MicrofilmRoll,
+    /// hh - microfilm slip
+    MicrofilmSlip,
+    /// hg - microopaque
+    Microopaque,
+    /// hz - other microform carrier
+    OtherMicroform,
+    /// pp - microscope slide
+    MicroscopeSlide,
+    /// pz - other microscopic carrier
+    OtherMicroscopic,
+    /// mc - film cartridge
+    FilmCartridge,
+    /// mf - film cassette
+    FilmCassette,
+    /// mr - film reel
+    FilmReel,
+    /// mo - film roll
+    FilmRoll,
+    /// gd - filmslip
+    Filmslip,
+    /// gf - filmstrip
+    Filmstrip,
+    /// gc - filmstrip cartridge
+    FilmstripCartridge,
+    /// gt - overhead transparency
+    OverheadTransparency,
+    /// gs - slide
+    Slide,
+    /// mz - other projected carrier
+    OtherProjected,
+    /// eh - stereograph card
+    StereographCard,
+    /// es - stereograph disc
+    StereographDisc,
+    /// ez - other stereographic carrier
+    OtherStereographic,
+    /// no - card
+    Card,
+    /// nn - flipchart
+    Flipchart,
+    /// na - roll
+    Roll,
+    /// nb - sheet
+    Sheet,
+    /// nc - volume
+    Volume,
+    /// nr - object
+    Object,
+    /// nz - other unmediated carrier
+    OtherUnmediated,
+    /// vc - video cartridge
+    VideoCartridge,
+    /// vf - videocassette
+    Videocassette,
+    /// vd - videodisc
+    Videodisc,
+    /// vr - videotape reel
+    VideotapeReel,
+    /// vz - other video carrier
+    OtherVideo,
+    /// zu - unspecified
+    Unspecified,
+}
+
+impl CarrierType {
+    /// All variants, in declaration order
+    pub const ALL: &'static [CarrierType] = &[
+        CarrierType::AudioCartridge,
+        CarrierType::AudioCylinder,
+        CarrierType::AudioDisc,
+        CarrierType::SoundTrackReel,
+        CarrierType::AudioRoll,
+        CarrierType::AudioWireReel,
+        CarrierType::Audiocassette,
+        CarrierType::AudiotapeReel,
+        CarrierType::OtherAudio,
+        CarrierType::ComputerCard,
+        CarrierType::ComputerChipCartridge,
+        CarrierType::ComputerDisc,
+        CarrierType::ComputerDiscCartridge,
+        CarrierType::ComputerTapeCartridge,
+        CarrierType::ComputerTapeCassette,
+        CarrierType::ComputerTapeReel,
+        CarrierType::OnlineResource,
+        CarrierType::OtherComputer,
+        CarrierType::ApertureCard,
+        CarrierType::Microfiche,
+        CarrierType::MicroficheCassette,
+        CarrierType::MicrofilmCartridge,
+        CarrierType::MicrofilmCassette,
+        CarrierType::MicrofilmReel,
+        CarrierType::MicrofilmRoll,
+        CarrierType::MicrofilmSlip,
+        CarrierType::Microopaque,
+        CarrierType::OtherMicroform,
+        CarrierType::MicroscopeSlide,
+        CarrierType::OtherMicroscopic,
+        CarrierType::FilmCartridge,
+        CarrierType::FilmCassette,
+        CarrierType::FilmReel,
+        CarrierType::FilmRoll,
+        CarrierType::Filmslip,
+        CarrierType::Filmstrip,
+        CarrierType::FilmstripCartridge,
+        CarrierType::OverheadTransparency,
+        CarrierType::Slide,
+        CarrierType::OtherProjected,
+        CarrierType::StereographCard,
+        CarrierType::StereographDisc,
+        CarrierType::OtherStereographic,
+        CarrierType::Card,
+        CarrierType::Flipchart,
+        CarrierType::Roll,
+        CarrierType::Sheet,
+        CarrierType::Volume,
+        CarrierType::Object,
+        CarrierType::OtherUnmediated,
+        CarrierType::VideoCartridge,
+        CarrierType::Videocassette,
+        CarrierType::Videodisc,
+        CarrierType::VideotapeReel,
+        CarrierType::OtherVideo,
+        CarrierType::Unspecified,
+    ];
+
+    /// `$2` source code for this vocabulary
+    pub const SOURCE: &'static str = "rdacarrier";
+
+    /// Code, as recorded in $b
+    pub fn code(&self) -> &'static str {
+        match self {
+            CarrierType::AudioCartridge => "sg",
+            CarrierType::AudioCylinder => "se",
+            CarrierType::AudioDisc => "sd",
+            CarrierType::SoundTrackReel => "si",
+            CarrierType::AudioRoll => "sq",
+            CarrierType::AudioWireReel => "sw",
+            CarrierType::Audiocassette => "ss",
+            CarrierType::AudiotapeReel => "st",
+            CarrierType::OtherAudio => "sz",
+            CarrierType::ComputerCard => "ck",
+            CarrierType::ComputerChipCartridge => "cb",
+            CarrierType::ComputerDisc => "cd",
+            CarrierType::ComputerDiscCartridge => "ce",
+            CarrierType::ComputerTapeCartridge => "ca",
+            CarrierType::ComputerTapeCassette => "cf",
+            CarrierType::ComputerTapeReel => "ch",
+            CarrierType::OnlineResource => "cr",
+            CarrierType::OtherComputer => "cz",
+            CarrierType::ApertureCard => "ha",
+            CarrierType::Microfiche => "he",
+            CarrierType::MicroficheCassette => "hf",
+            CarrierType::MicrofilmCartridge => "hb",
+            CarrierType::MicrofilmCassette => "hc",
+            CarrierType::MicrofilmReel => "hd",
+            CarrierType::MicrofilmRoll => "hj",
+            CarrierType::MicrofilmSlip => "hh",
+            CarrierType::Microopaque => "hg",
+            CarrierType::OtherMicroform => "hz",
+            CarrierType::MicroscopeSlide => "pp",
+            CarrierType::OtherMicroscopic => "pz",
+            CarrierType::FilmCartridge => "mc",
+            CarrierType::FilmCassette => "mf",
+            CarrierType::FilmReel => "mr",
+            CarrierType::FilmRoll => "mo",
+            CarrierType::Filmslip => "gd",
+            CarrierType::Filmstrip => "gf",
+            CarrierType::FilmstripCartridge => "gc",
+            CarrierType::OverheadTransparency => "gt",
+            CarrierType::Slide => "gs",
+            CarrierType::OtherProjected => "mz",
+            CarrierType::StereographCard => "eh",
+            CarrierType::StereographDisc => "es",
+            CarrierType::OtherStereographic => "ez",
+            CarrierType::Card => "no",
+            CarrierType::Flipchart => "nn",
+            CarrierType::Roll => "na",
+            CarrierType::Sheet => "nb",
+            CarrierType::Volume => "nc",
+            CarrierType::Object => "nr",
+            CarrierType::OtherUnmediated => "nz",
+            CarrierType::VideoCartridge => "vc",
+            CarrierType::Videocassette => "vf",
+            CarrierType::Videodisc => "vd",
+            CarrierType::VideotapeReel => "vr",
+            CarrierType::OtherVideo => "vz",
+            CarrierType::Unspecified => "zu",
+        }
+    }
+
+    /// RDA term, as recorded in $a
+    pub fn term(&self) -> &'static str {
+        match self {
+            CarrierType::AudioCartridge => "audio cartridge",
+            CarrierType::AudioCylinder => "audio cylinder",
+            CarrierType::AudioDisc => "audio disc",
+            CarrierType::SoundTrackReel => "sound-track reel",
+            CarrierType::AudioRoll => "audio roll",
+            CarrierType::AudioWireReel => "audio wire reel",
+            CarrierType::Audiocassette => "audiocassette",
+            CarrierType::AudiotapeReel => "audiotape reel",
+            CarrierType::OtherAudio => "other audio carrier",
+            CarrierType::ComputerCard => "computer card",
+            CarrierType::ComputerChipCartridge => "computer chip cartridge",
+            CarrierType::ComputerDisc => "computer disc",
+            CarrierType::ComputerDiscCartridge => "computer disc cartridge",
+            CarrierType::ComputerTapeCartridge => "computer tape cartridge",
+            CarrierType::ComputerTapeCassette => "computer tape cassette",
+            CarrierType::ComputerTapeReel => "computer tape reel",
+            CarrierType::OnlineResource => "online resource",
+            CarrierType::OtherComputer => "other computer carrier",
+            CarrierType::ApertureCard => "aperture card",
+            CarrierType::Microfiche => "microfiche",
+            CarrierType::MicroficheCassette => "microfiche cassette",
+            CarrierType::MicrofilmCartridge => "microfilm cartridge",
+            CarrierType::MicrofilmCassette => "microfilm cassette",
+            CarrierType::MicrofilmReel => "microfilm reel",
+            CarrierType::MicrofilmRoll => "microfilm roll",
+            CarrierType::MicrofilmSlip => "microfilm slip",
+            CarrierType::Microopaque => "microopaque",
+            CarrierType::OtherMicroform => "other microform carrier",
+            CarrierType::MicroscopeSlide => "microscope slide",
+            CarrierType::OtherMicroscopic => "other microscopic carrier",
+            CarrierType::FilmCartridge => "film cartridge",
+            CarrierType::FilmCassette => "film cassette",
+            CarrierType::FilmReel => "film reel",
+            CarrierType::FilmRoll => "film roll",
+            CarrierType::Filmslip => "filmslip",
+            CarrierType::Filmstrip => "filmstrip",
+            CarrierType::FilmstripCartridge => "filmstrip cartridge",
+            CarrierType::OverheadTransparency => "overhead transparency",
+            CarrierType::Slide => "slide",
+            CarrierType::OtherProjected => "other projected carrier",
+            CarrierType::StereographCard => "stereograph card",
+            CarrierType::StereographDisc => "stereograph disc",
+            CarrierType::OtherStereographic => "other stereographic carrier",
+            CarrierType::Card => "card",
+            CarrierType::Flipchart => "flipchart",
+            CarrierType::Roll => "roll",
+            CarrierType::Sheet => "sheet",
+            CarrierType::Volume => "volume",
+            CarrierType::Object => "object",
+            CarrierType::OtherUnmediated => "other unmediated carrier",
+            CarrierType::VideoCartridge => "video cartridge",
+            CarrierType::Videocassette => "videocassette",
+            CarrierType::Videodisc => "videodisc",
+            CarrierType::VideotapeReel => "videotape reel",
+            CarrierType::OtherVideo => "other video carrier",
+            CarrierType::Unspecified => "unspecified",
+        }
+    }
+
+    /// Look up a code
+    pub fn from_code(code: &str) -> Option<Self> {
+        let code = code.trim();
+        CarrierType::ALL.iter().copied().find(|v| v.code() == code)
+    }
+
+    /// Look up a term, ignoring case and trailing punctuation
+    pub fn from_term(term: &str) -> Option<Self> {
+        let term = term.trim().trim_end_matches(['.', ',', ';']).trim();
+        CarrierType::ALL.iter().copied().find(|v| v.term().eq_ignore_ascii_case(term))
+    }
+
+    /// Media type this carrier belongs to
+    pub fn media_type(&self) -> MediaType {
+        match self {
+            CarrierType::AudioCartridge
+            | CarrierType::AudioCylinder
+            | CarrierType::AudioDisc
+            | CarrierType::SoundTrackReel
+            | CarrierType::AudioRoll
+            | CarrierType::AudioWireReel
+            | CarrierType::Audiocassette
+            | CarrierType::AudiotapeReel
+            | CarrierType::OtherAudio => MediaType::Audio,
+            CarrierType::ComputerCard
+            | CarrierType::ComputerChipCartridge
+            | CarrierType::ComputerDisc
+            | CarrierType::ComputerDiscCartridge
+            | CarrierType::ComputerTapeCartridge
+            | CarrierType::ComputerTapeCassette
+            | CarrierType::ComputerTapeReel
+            | CarrierType::OnlineResource
+            | CarrierType::OtherComputer => MediaType::Computer,
+            CarrierType::ApertureCard
+            | CarrierType::Microfiche
+            | CarrierType::MicroficheCassette
+            | CarrierType::MicrofilmCartridge
+            | CarrierType::MicrofilmCassette
+            | CarrierType::MicrofilmReel
+            | CarrierType::MicrofilmRoll
+            | CarrierType::MicrofilmSlip
+            | CarrierType::Microopaque
+            | CarrierType::OtherMicroform => MediaType::Microform,
+            CarrierType::MicroscopeSlide | CarrierType::OtherMicroscopic => MediaType::Microscopic,
+            CarrierType::FilmCartridge
+            | CarrierType::FilmCassette
+            | CarrierType::FilmReel
+            | CarrierType::FilmRoll
+            | CarrierType::Filmslip
+            | CarrierType::Filmstrip
+            | CarrierType::FilmstripCartridge
+            | CarrierType::OverheadTransparency
+            | CarrierType::Slide
+            | CarrierType::OtherProjected => MediaType::Projected,
+            CarrierType::StereographCard | CarrierType::StereographDisc | CarrierType::OtherStereographic => MediaType::Stereographic,
+            CarrierType::Card
+            | CarrierType::Flipchart
+            | CarrierType::Roll
+            | CarrierType::Sheet
+            | CarrierType::Volume
+            | CarrierType::Object
+            | CarrierType::OtherUnmediated => MediaType::Unmediated,
+            CarrierType::VideoCartridge | CarrierType::Videocassette | CarrierType::Videodisc | CarrierType::VideotapeReel | CarrierType::OtherVideo => {
+                MediaType::Video
+            }
+            CarrierType::Unspecified => MediaType::Unspecified,
+        }
+    }
+
+    /// Carrier type implied by 007/00-01 (category and specific material designation), for records without 338
+    pub fn from_007(value: &str) -> Option<Self> {
+        let mut chars = value.chars();
+        let carrier = match (chars.next()?, chars.next()?) {
+            ('a', 'j') | ('k', _) => CarrierType::Sheet,
+            ('c', 'r') => CarrierType::OnlineResource,
+            ('c', 'o') | ('c', 'j') => CarrierType::ComputerDisc,
+            ('c', 'b') => CarrierType::ComputerChipCartridge,
+            ('c', 'a') => CarrierType::ComputerTapeCartridge,
+            ('c', 'f') => CarrierType::ComputerTapeCassette,
+            ('c', 'h') => CarrierType::ComputerTapeReel,
+            ('d', _) => CarrierType::Object,
+            ('g', 'c') => CarrierType::FilmstripCartridge,
+            ('g', 'd') => CarrierType::Filmslip,
+            ('g', 'f') | ('g', 'o') => CarrierType::Filmstrip,
+            ('g', 's') => CarrierType::Slide,
+            ('g', 't') => CarrierType::OverheadTransparency,
+            ('h', 'a') => CarrierType::ApertureCard,
+            ('h', 'b') => CarrierType::MicrofilmCartridge,
+            ('h', 'c') => CarrierType::MicrofilmCassette,
+            ('h', 'd') => CarrierType::MicrofilmReel,
+            ('h', 'e') => CarrierType::Microfiche,
+            ('h', 'f') => CarrierType::MicroficheCassette,
+            ('h', 'g') => CarrierType::Microopaque,
+            ('h', 'h') => CarrierType::MicrofilmSlip,
+            ('h', 'j') => CarrierType::MicrofilmRoll,
+            ('m', 'c') => CarrierType::FilmCartridge,
+            ('m', 'f') => CarrierType::FilmCassette,
+            ('m', 'o') => CarrierType::FilmRoll,
+            ('m', 'r') => CarrierType::FilmReel,
+            ('s', 'd') => CarrierType::AudioDisc,
+            ('s', 'e') => CarrierType::AudioCylinder,
+            ('s', 'g') => CarrierType::AudioCartridge,
+            ('s', 'i') => CarrierType::SoundTrackReel,
+            ('s', 'q') => CarrierType::AudioRoll,
+            ('s', 's') => CarrierType::Audiocassette,
+            ('s', 't') => CarrierType::AudiotapeReel,
+            ('s', 'w') => CarrierType::AudioWireReel,
+            ('t', 'a') | ('t', 'b') | ('t', 'c') | ('t', 'd') => CarrierType::Volume,
+            ('v', 'c') => CarrierType::VideoCartridge,
+            ('v', 'd') => CarrierType::Videodisc,
+            ('v', 'f') => CarrierType::Videocassette,
+            ('v', 'r') => CarrierType::VideotapeReel,
+            _ => return None,
+        };
+        Some(carrier)
+    }
+}
+
+impl Record {
+    /// Content types from RDA 336 fields, or derived from leader/06 when the record has none
+    pub fn content_types(&self) -> Vec<ContentType> {
+        if rda_fields(self, "336", ContentType::SOURCE).next().is_some() {
+            return rda_terms(self, "336", ContentType::SOURCE, ContentType::from_code, ContentType::from_term);
+        }
+        ContentType::from_leader(&self.leader).into_iter().collect()
+    }
+
+    /// Media types from RDA 337 fields, or derived from 007 when the record has none
+    pub fn media_types(&self) -> Vec<MediaType> {
+        if rda_fields(self, "337", MediaType::SOURCE).next().is_some() {
+            return rda_terms(self, "337", MediaType::SOURCE, MediaType::from_code, MediaType::from_term);
+        }
+        let mut types = Vec::new();
+        for media in self
+            .control_fields
+            .iter()
+            .filter(|f| f.tag == "007")
+            .filter_map(|f| MediaType::from_007(&f.value))
+        {
+            if !types.contains(&media) {
+                types.push(media);
+            }
+        }
+        types
+    }
+
+    /// Carrier types from RDA 338 fields, or derived from 007 when the record has none
+    pub fn carrier_types(&self) -> Vec<CarrierType> {
+        if rda_fields(self, "338", CarrierType::SOURCE).next().is_some() {
+            return rda_terms(self, "338", CarrierType::SOURCE, CarrierType::from_code, CarrierType::from_term);
+        }
+        let mut types = Vec::new();
+        for carrier in self
+            .control_fields
+            .iter()
+            .filter(|f| f.tag == "007")
+            .filter_map(|f| CarrierType::from_007(&f.value))
+        {
+            if !types.contains(&carrier) {
+                types.push(carrier);
+            }
+        }
+        types
+    }
+}
+
+/// 33X fields recording the RDA vocabulary `source`
+///
+/// Fields without `$2` are taken as RDA; fields from other vocabularies, such as
+/// `isbdcontent`, are skipped.
+fn rda_fields<'a>(record: &'a Record, tag: &'a str, source: &'a str) -> impl Iterator<Item = &'a DataField> {
+    record
+        .data_fields
+        .iter()
+        .filter(move |f| f.tag == tag && f.subfields.iter().filter(|s| s.code == '2').all(|s| s.value.trim().eq_ignore_ascii_case(source)))
+}
+
+/// Read the vocabulary values of the RDA 33X fields: $b codes, falling back to $a terms
+fn rda_terms<T: PartialEq>(record: &Record, tag: &str, source: &str, from_code: fn(&str) -> Option<T>, from_term: fn(&str) -> Option<T>) -> Vec<T> {
+    let mut values = Vec::new();
+    for field in rda_fields(record, tag, source) {
+        for value in rda_field_values(field, from_code, from_term) {
+            if !values.contains(&value) {
+                values.push(value);
+            }
+        }
+    }
+    values
+}
+
+fn rda_field_values<T>(field: &DataField, from_code: fn(&str) -> Option<T>, from_term: fn(&str) -> Option<T>) -> Vec<T> {
+    let codes: Vec<T> = field.subfields.iter().filter(|s| s.code == 'b').filter_map(|s| from_code(&s.value)).collect();
+    if !codes.is_empty() {
+        return codes;
+    }
+    field.subfields.iter().filter(|s| s.code == 'a').filter_map(|s| from_term(&s.value)).collect()
+}
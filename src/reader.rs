@@ -0,0 +1,318 @@
+use crate::format::{FormatEncoding, MarcFormat};
+use crate::parser::{parse, parse_into, ParseError};
+use crate::record::Record;
+use std::io::Read;
+
+/// Size of the chunks read while looking for the end of an XML record
+const XML_CHUNK_SIZE: usize = 8192;
+
+/// Iterator reading records one at a time from a reader
+///
+/// Binary records are read using the record length of their leader, so only
+/// the bytes of the current record are held in memory. MARC XML input is read
+/// in chunks until the end of the next `<record>` element, which may carry a
+/// namespace prefix such as `<marc:record>`. The iterator stops after the
+/// first error.
+pub struct RecordReader<R: Read> {
+    reader: R,
+    format_encoding: FormatEncoding,
+    buffer: Vec<u8>,
+    done: bool,
+    record_index: usize,
+    offset: u64,
+}
+
+impl<R: Read> RecordReader<R> {
+    /// Create a reader for the given format and encoding
+    pub fn new(reader: R, format_encoding: FormatEncoding) -> Self {
+        Self {
+            reader,
+            format_encoding,
+            buffer: Vec::new(),
+            done: false,
+            record_index: 0,
+            offset: 0,
+        }
+    }
+
+    /// Index of the next record, which is the failing one after an error
+    pub fn record_index(&self) -> usize {
+        self.record_index
+    }
+
+    /// Byte offset in the input where the next record starts
+    ///
+    /// For MARC XML this is the end of the previous `</record>` tag.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Report locating an error returned by this reader
+    pub fn report(&self, error: ParseError) -> ErrorReport<ParseError> {
+        ErrorReport {
+            record_index: Some(self.record_index),
+            offset: Some(self.offset),
+            error,
+        }
+    }
+
+    /// Unwrap the underlying reader
+    ///
+    /// For MARC XML, bytes read ahead of the last returned record are lost.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Skip the next `n` records without decoding them, returning how many were skipped
+    ///
+    /// Binary records are passed over using their record length and MARC XML
+    /// records by their end tag, so skipping is much faster than reading. Fewer
+    /// than `n` records are skipped at the end of the input.
+    pub fn skip_records(&mut self, n: usize) -> Result<usize, ParseError> {
+        let mut skipped = 0;
+        while skipped < n && !self.done {
+            let result = match self.format_encoding.format {
+                MarcFormat::Marc21 | MarcFormat::Unimarc => self.next_binary(),
+                MarcFormat::MarcXml => self.next_xml(),
+            };
+            match result {
+                Ok(Some(length)) => {
+                    if self.format_encoding.format == MarcFormat::MarcXml {
+                        self.buffer.drain(..length);
+                    }
+                    self.offset += length as u64;
+                    self.record_index += 1;
+                    skipped += 1;
+                }
+                Ok(None) => self.done = true,
+                Err(e) => {
+                    self.done = true;
+                    return Err(e);
+                }
+            }
+        }
+        Ok(skipped)
+    }
+
+    /// Read the next record into `record`, reusing its capacity (see [`parse_into`])
+    ///
+    /// Returns false at the end of the input. As with the iterator, reading
+    /// stops after the first error.
+    pub fn read_into(&mut self, record: &mut Record) -> Result<bool, ParseError> {
+        if self.done {
+            return Ok(false);
+        }
+
+        let result = match self.format_encoding.format {
+            MarcFormat::Marc21 | MarcFormat::Unimarc => match self.next_binary() {
+                Ok(Some(length)) => parse_into(record, &self.buffer, self.format_encoding).map(|()| {
+                    self.offset += length as u64;
+                    true
+                }),
+                Ok(None) => Ok(false),
+                Err(e) => Err(e),
+            },
+            MarcFormat::MarcXml => self.read_xml().map(|read| read.map(|read| *record = read).is_some()),
+        };
+        match result {
+            Ok(true) => self.record_index += 1,
+            Ok(false) | Err(_) => self.done = true,
+        }
+        result
+    }
+
+    /// Read the bytes of the next binary record into the buffer, returning its length
+    fn next_binary(&mut self) -> Result<Option<usize>, ParseError> {
+        let mut length = [0u8; 5];
+        let read = read_full(&mut self.reader, &mut length)?;
+        if length[..read].iter().all(|b| b.is_ascii_whitespace()) && read < length.len() {
+            // End of input, possibly after a trailing newline
+            return Ok(None);
+        }
+        if read < length.len() {
+            return Err(ParseError::UnexpectedEof);
+        }
+
+        let record_length = std::str::from_utf8(&length)
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .ok_or_else(|| ParseError::InvalidRecordLength(format!("Invalid record length {:?}", String::from_utf8_lossy(&length))))?;
+        if record_length < 24 {
+            return Err(ParseError::InvalidRecordLength(format!("Record length {} is shorter than the leader", record_length)));
+        }
+
+        self.buffer.clear();
+        self.buffer.extend_from_slice(&length);
+        self.buffer.resize(record_length, 0);
+        if read_full(&mut self.reader, &mut self.buffer[5..])? < record_length - 5 {
+            return Err(ParseError::UnexpectedEof);
+        }
+        Ok(Some(record_length))
+    }
+
+    fn read_binary(&mut self) -> Result<Option<Record>, ParseError> {
+        let Some(record_length) = self.next_binary()? else {
+            return Ok(None);
+        };
+        let record = parse(&self.buffer, self.format_encoding)?.into_iter().next();
+        self.offset += record_length as u64;
+        Ok(record)
+    }
+
+    /// Fill the buffer up to the end of the next `<record>` element, returning that end
+    fn next_xml(&mut self) -> Result<Option<usize>, ParseError> {
+        loop {
+            if let Some((start, name)) = find_record_start(&self.buffer) {
+                if let Some(end) = find_end_tag(&self.buffer[start..], name).map(|i| start + i) {
+                    return Ok(Some(end));
+                }
+            }
+
+            let mut chunk = [0u8; XML_CHUNK_SIZE];
+            let read = self.reader.read(&mut chunk).map_err(|e| ParseError::Other(format!("IO error: {}", e)))?;
+            if read == 0 {
+                return match find_record_start(&self.buffer) {
+                    Some(_) => Err(ParseError::UnexpectedEof),
+                    None => Ok(None),
+                };
+            }
+            self.buffer.extend_from_slice(&chunk[..read]);
+        }
+    }
+
+    fn read_xml(&mut self) -> Result<Option<Record>, ParseError> {
+        let Some(end) = self.next_xml()? else {
+            return Ok(None);
+        };
+        let (start, _) = find_record_start(&self.buffer).unwrap();
+        let records = parse(&self.buffer[start..end], self.format_encoding)?;
+        self.buffer.drain(..end);
+        self.offset += end as u64;
+        Ok(records.into_iter().next())
+    }
+}
+
+impl<R: Read> Iterator for RecordReader<R> {
+    type Item = Result<Record, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let result = match self.format_encoding.format {
+            MarcFormat::Marc21 | MarcFormat::Unimarc => self.read_binary(),
+            MarcFormat::MarcXml => self.read_xml(),
+        };
+        match result {
+            Ok(Some(record)) => {
+                self.record_index += 1;
+                Some(Ok(record))
+            }
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Error located in a stream of records, for structured error reporting
+///
+/// With the `serde` feature, serializes as
+/// `{"record_index": 3, "offset": 1024, "kind": "invalid_leader", "message": "...", "display": "..."}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorReport<E> {
+    /// 0-based index of the record in the input
+    pub record_index: Option<usize>,
+    /// Byte offset of the record in the input
+    pub offset: Option<u64>,
+    pub error: E,
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for ErrorReport<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.record_index, self.offset) {
+            (Some(index), Some(offset)) => write!(f, "record {} (offset {}): {}", index, offset, self.error),
+            (Some(index), None) => write!(f, "record {}: {}", index, self.error),
+            (None, Some(offset)) => write!(f, "offset {}: {}", offset, self.error),
+            (None, None) => write!(f, "{}", self.error),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for ErrorReport<ParseError> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let location = (self.record_index, self.offset);
+        crate::helpers::serialize_error(Some(location), self.error.kind(), self.error.message(), &self.error.to_string(), serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for ErrorReport<crate::writer::WriteError> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let location = (self.record_index, self.offset);
+        crate::helpers::serialize_error(Some(location), self.error.kind(), Some(self.error.message()), &self.error.to_string(), serializer)
+    }
+}
+
+/// Read until `buf` is full or the input ends, returning the number of bytes read
+fn read_full<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<usize, ParseError> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(ParseError::Other(format!("IO error: {}", e))),
+        }
+    }
+    Ok(filled)
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    memchr::memmem::find(haystack, needle)
+}
+
+/// Position and qualified name length of the first `<record>` start tag, with or without a namespace prefix
+fn find_record_start(data: &[u8]) -> Option<(usize, usize)> {
+    let mut offset = 0;
+    while let Some(i) = memchr::memchr(b'<', &data[offset..]) {
+        let start = offset + i;
+        let name_start = start + 1;
+        let name_length = data[name_start..].iter().position(|b| matches!(b, b'>' | b'/' | b' ' | b'\t' | b'\r' | b'\n'))?;
+        let name = &data[name_start..name_start + name_length];
+        let local_name = name.iter().rposition(|&b| b == b':').map_or(name, |colon| &name[colon + 1..]);
+        if local_name == b"record" && data[name_start + name_length] != b'/' {
+            return Some((start, name_length));
+        }
+        offset = name_start;
+    }
+    None
+}
+
+/// Position just after the `</name>` end tag matching the start tag at the beginning of `data`
+fn find_end_tag(data: &[u8], name_length: usize) -> Option<usize> {
+    let end_tag = [b"</", &data[1..1 + name_length]].concat();
+    let mut offset = 0;
+    while let Some(i) = find(&data[offset..], &end_tag) {
+        let after = offset + i + end_tag.len();
+        let close = data[after..].iter().position(|b| !b.is_ascii_whitespace())?;
+        if data[after + close] == b'>' {
+            return Some(after + close + 1);
+        }
+        offset = after;
+    }
+    None
+}
@@ -1,15 +1,198 @@
+use crate::linkage::{FieldLink, Linkage};
+use std::cmp::Ordering;
+use std::ops::{Bound, RangeBounds};
+use unicode_normalization::UnicodeNormalization;
+
 /// MARC record structure
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Record {
     pub leader: Leader,
     pub control_fields: Vec<ControlField>,
     pub data_fields: Vec<DataField>,
 }
 
+impl Record {
+    /// Canonical form of the record, used for order-insensitive comparison
+    ///
+    /// Fields are sorted by tag (then content), 005 is dropped, all values are
+    /// NFC-normalized and the computed leader lengths are reset to zero.
+    pub fn canonicalize(&self) -> Record {
+        let mut leader = self.leader.clone();
+        leader.record_length = 0;
+        leader.base_address_of_data = 0;
+
+        let mut control_fields: Vec<ControlField> = self
+            .control_fields
+            .iter()
+            .filter(|f| f.tag != "005")
+            .map(|f| ControlField {
+                tag: f.tag.clone(),
+                value: f.value.nfc().collect(),
+            })
+            .collect();
+        control_fields.sort_by(|a, b| a.tag.cmp(&b.tag).then_with(|| a.value.cmp(&b.value)));
+
+        let mut data_fields: Vec<DataField> = self
+            .data_fields
+            .iter()
+            .map(|f| DataField {
+                tag: f.tag.clone(),
+                ind1: f.ind1,
+                ind2: f.ind2,
+                subfields: f
+                    .subfields
+                    .iter()
+                    .map(|s| Subfield {
+                        code: s.code,
+                        value: s.value.nfc().collect(),
+                    })
+                    .collect(),
+            })
+            .collect();
+        data_fields.sort_by(compare_data_fields);
+
+        Record {
+            leader,
+            control_fields,
+            data_fields,
+        }
+    }
+
+    /// Compare two records ignoring field order, Unicode normalization form and 005
+    pub fn equivalent(&self, other: &Record) -> bool {
+        self.canonicalize() == other.canonicalize()
+    }
+
+    /// Get the `n`-th (zero-based) data field with the given tag
+    pub fn nth_field(&self, tag: &str, n: usize) -> Option<&DataField> {
+        self.data_fields.iter().filter(|f| f.tag == tag).nth(n)
+    }
+
+    /// Join the given subfields of the first field with the given tag
+    ///
+    /// Returns `None` when the field is absent or has none of the requested subfields.
+    pub fn join(&self, tag: &str, codes: &[char], separator: &str) -> Option<String> {
+        self.data_fields
+            .iter()
+            .find(|f| f.tag == tag)
+            .map(|f| f.join_subfields(codes, separator))
+            .filter(|joined| !joined.is_empty())
+    }
+
+    /// Copy of the record containing only the fields with the given tags
+    ///
+    /// The leader lengths are recomputed for the projected record.
+    pub fn project(&self, tags: &[&str]) -> Record {
+        self.filter_tags(|tag| tags.contains(&tag))
+    }
+
+    /// Copy of the record without the fields with the given tags
+    ///
+    /// The leader lengths are recomputed for the remaining fields.
+    pub fn without(&self, tags: &[&str]) -> Record {
+        self.filter_tags(|tag| !tags.contains(&tag))
+    }
+
+    fn filter_tags<F: Fn(&str) -> bool>(&self, keep: F) -> Record {
+        let mut record = Record {
+            leader: self.leader.clone(),
+            control_fields: self.control_fields.iter().filter(|f| keep(&f.tag)).cloned().collect(),
+            data_fields: self.data_fields.iter().filter(|f| keep(&f.tag)).cloned().collect(),
+        };
+        record.update_leader_lengths();
+        record
+    }
+
+    /// Recompute the leader record length and base address of data
+    ///
+    /// Lengths are those of the record serialized as UTF-8 binary MARC. The leader
+    /// holds five digits: lengths above 99999 saturate at 99999, and writing such
+    /// a record as binary MARC fails.
+    pub fn update_leader_lengths(&mut self) {
+        let field_count = self.control_fields.len() + self.data_fields.len();
+        let base_address = 24 + field_count * 12 + 1;
+
+        let control_length: usize = self.control_fields.iter().map(|f| f.value.len() + 1).sum();
+        let data_length: usize = self
+            .data_fields
+            .iter()
+            .map(|f| 3 + f.subfields.iter().map(|s| 1 + s.code.len_utf8() + s.value.len()).sum::<usize>())
+            .sum();
+
+        self.leader.base_address_of_data = base_address.min(MAX_LEADER_NUMBER as usize) as u32;
+        self.leader.record_length = (base_address + control_length + data_length + 1).min(MAX_LEADER_NUMBER as usize) as u32;
+    }
+
+    /// Stable 64-bit fingerprint of the canonicalized record
+    ///
+    /// Equivalent records (see [`Record::equivalent`]) share the same hash. The value
+    /// does not depend on the platform or compiler version, so it can be persisted.
+    pub fn content_hash(&self) -> u64 {
+        let canonical = self.canonicalize();
+        let mut hasher = Fnv1a::new();
+
+        hasher.write(&canonical.leader.to_bytes());
+        for field in &canonical.control_fields {
+            hasher.write(field.tag.as_bytes());
+            hasher.write(field.value.as_bytes());
+            hasher.write(&[0x1E]);
+        }
+        for field in &canonical.data_fields {
+            hasher.write(field.tag.as_bytes());
+            hasher.write(field.ind1.to_string().as_bytes());
+            hasher.write(field.ind2.to_string().as_bytes());
+            for subfield in &field.subfields {
+                hasher.write(&[0x1F]);
+                hasher.write(subfield.code.to_string().as_bytes());
+                hasher.write(subfield.value.as_bytes());
+            }
+            hasher.write(&[0x1E]);
+        }
+
+        hasher.finish()
+    }
+}
+
+/// 64-bit FNV-1a, used for stable content hashes
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+fn compare_data_fields(a: &DataField, b: &DataField) -> Ordering {
+    a.tag
+        .cmp(&b.tag)
+        .then_with(|| a.ind1.cmp(&b.ind1))
+        .then_with(|| a.ind2.cmp(&b.ind2))
+        .then_with(|| {
+            a.subfields
+                .iter()
+                .map(|s| (s.code, s.value.as_str()))
+                .cmp(b.subfields.iter().map(|s| (s.code, s.value.as_str())))
+        })
+}
+
+/// Largest record length or base address of data the five leader digits hold
+pub(crate) const MAX_LEADER_NUMBER: u32 = 99_999;
+
 /// MARC leader (24 bytes)
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Leader {
-    pub record_length: u16,
+    pub record_length: u32,
     pub record_status: char,
     pub record_type: char,
     pub bibliographic_level: char,
@@ -17,7 +200,7 @@ pub struct Leader {
     pub character_coding_scheme: char,
     pub indicator_count: u8,
     pub subfield_code_count: u8,
-    pub base_address_of_data: u16,
+    pub base_address_of_data: u32,
     pub encoding_level: char,
     pub descriptive_cataloging_form: char,
     pub multipart_resource_record_level: char,
@@ -34,8 +217,8 @@ impl Leader {
             return Err(format!("Leader must be 24 bytes, got {}", data.len()));
         }
 
-        let record_length = parse_u16(&data[0..5])?;
-        let base_address = parse_u16(&data[12..17])?;
+        let record_length = parse_leader_number(data, 0, "record_length")?;
+        let base_address = parse_leader_number(data, 12, "base_address_of_data")?;
 
         Ok(Leader {
             record_length,
@@ -44,19 +227,54 @@ impl Leader {
             bibliographic_level: data[7] as char,
             type_of_control: data[8] as char,
             character_coding_scheme: data[9] as char,
-            indicator_count: data[10] - b'0',
-            subfield_code_count: data[11] - b'0',
+            indicator_count: parse_leader_digit(data, 10, "indicator_count")?,
+            subfield_code_count: parse_leader_digit(data, 11, "subfield_code_count")?,
             base_address_of_data: base_address,
             encoding_level: data[17] as char,
             descriptive_cataloging_form: data[18] as char,
             multipart_resource_record_level: data[19] as char,
-            length_of_length_of_field_portion: data[20] - b'0',
-            length_of_starting_character_position_portion: data[21] - b'0',
-            length_of_implementation_defined_portion: data[22] - b'0',
+            length_of_length_of_field_portion: parse_leader_digit(data, 20, "length_of_length_of_field_portion")?,
+            length_of_starting_character_position_portion: parse_leader_digit(data, 21, "length_of_starting_character_position_portion")?,
+            length_of_implementation_defined_portion: parse_leader_digit(data, 22, "length_of_implementation_defined_portion")?,
             undefined: data[23] as char,
         })
     }
 
+    /// Check that every element fits its leader position: single-digit counts
+    /// and ASCII characters
+    pub fn validate(&self) -> Result<(), String> {
+        let digits = [
+            ("indicator_count", 10, self.indicator_count),
+            ("subfield_code_count", 11, self.subfield_code_count),
+            ("length_of_length_of_field_portion", 20, self.length_of_length_of_field_portion),
+            ("length_of_starting_character_position_portion", 21, self.length_of_starting_character_position_portion),
+            ("length_of_implementation_defined_portion", 22, self.length_of_implementation_defined_portion),
+        ];
+        for (name, position, value) in digits {
+            if value > 9 {
+                return Err(format!("Leader {} at position {}: expected a single digit, found {}", name, position, value));
+            }
+        }
+
+        let characters = [
+            ("record_status", 5, self.record_status),
+            ("record_type", 6, self.record_type),
+            ("bibliographic_level", 7, self.bibliographic_level),
+            ("type_of_control", 8, self.type_of_control),
+            ("character_coding_scheme", 9, self.character_coding_scheme),
+            ("encoding_level", 17, self.encoding_level),
+            ("descriptive_cataloging_form", 18, self.descriptive_cataloging_form),
+            ("multipart_resource_record_level", 19, self.multipart_resource_record_level),
+            ("undefined", 23, self.undefined),
+        ];
+        for (name, position, value) in characters {
+            if !value.is_ascii() {
+                return Err(format!("Leader {} at position {}: expected an ASCII character, found {:?}", name, position, value));
+            }
+        }
+        Ok(())
+    }
+
     /// Convert Leader to 24-byte string
     pub fn to_bytes(&self) -> Vec<u8> {
         let mut bytes = vec![0u8; 24];
@@ -84,20 +302,72 @@ impl Leader {
     }
 }
 
-fn parse_u16(bytes: &[u8]) -> Result<u16, String> {
-    let s = std::str::from_utf8(bytes).map_err(|e| format!("Invalid UTF-8: {}", e))?;
-    s.parse::<u16>().map_err(|e| format!("Invalid number: {}", e))
+/// Parse the 5-digit number starting at `position` of a leader
+fn parse_leader_number(data: &[u8], position: usize, name: &str) -> Result<u32, String> {
+    let mut value: u32 = 0;
+    for i in position..position + 5 {
+        value = value * 10 + parse_leader_digit(data, i, name)? as u32;
+    }
+    Ok(value)
+}
+
+/// Parse the digit at `position` of a leader
+fn parse_leader_digit(data: &[u8], position: usize, name: &str) -> Result<u8, String> {
+    match data[position] {
+        byte @ b'0'..=b'9' => Ok(byte - b'0'),
+        byte => Err(format!("Leader {} at position {}: expected a digit, found {:?}", name, position, byte as char)),
+    }
 }
 
 /// Control field (001-009)
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ControlField {
     pub tag: String,
     pub value: String,
 }
 
+impl ControlField {
+    /// Get the characters at the given (zero-based, character) positions
+    ///
+    /// Positions count characters rather than bytes, so multibyte data never panics.
+    pub fn chars<R: RangeBounds<usize>>(&self, range: R) -> Result<&str, String> {
+        let start = match range.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n + 1,
+            Bound::Unbounded => 0,
+        };
+        let char_count = self.value.chars().count();
+        let end = match range.end_bound() {
+            Bound::Included(&n) => n + 1,
+            Bound::Excluded(&n) => n,
+            Bound::Unbounded => char_count,
+        };
+
+        if start > end || end > char_count {
+            return Err(format!(
+                "Positions {}-{} out of range for {} (length {})",
+                start,
+                end.saturating_sub(1),
+                self.tag,
+                char_count
+            ));
+        }
+
+        let byte_offset = |pos: usize| self.value.char_indices().nth(pos).map(|(i, _)| i).unwrap_or(self.value.len());
+        Ok(&self.value[byte_offset(start)..byte_offset(end)])
+    }
+
+    /// Get the character at the given (zero-based) position
+    pub fn char_at(&self, pos: usize) -> Result<char, String> {
+        self.value
+            .chars()
+            .nth(pos)
+            .ok_or_else(|| format!("Position {} out of range for {} (length {})", pos, self.tag, self.value.chars().count()))
+    }
+}
+
 /// Data field (010-999)
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct DataField {
     pub tag: String,
     pub ind1: char,
@@ -105,8 +375,37 @@ pub struct DataField {
     pub subfields: Vec<Subfield>,
 }
 
+impl DataField {
+    /// Get the `n`-th (zero-based) subfield with the given code
+    pub fn nth_subfield(&self, code: char, n: usize) -> Option<&Subfield> {
+        self.subfields.iter().filter(|s| s.code == code).nth(n)
+    }
+
+    /// Join the values of the given subfields, in field order, with a separator
+    ///
+    /// Missing codes and empty values are skipped.
+    pub fn join_subfields(&self, codes: &[char], separator: &str) -> String {
+        self.subfields
+            .iter()
+            .filter(|s| codes.contains(&s.code) && !s.value.is_empty())
+            .map(|s| s.value.as_str())
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+
+    /// Parse the `$6` linkage subfield, if present
+    pub fn linkage(&self) -> Result<Option<Linkage>, String> {
+        self.subfields.iter().find(|s| s.code == '6').map(|s| Linkage::parse(&s.value)).transpose()
+    }
+
+    /// Parse all `$8` field link and sequence number subfields
+    pub fn field_links(&self) -> Result<Vec<FieldLink>, String> {
+        self.subfields.iter().filter(|s| s.code == '8').map(|s| FieldLink::parse(&s.value)).collect()
+    }
+}
+
 /// Subfield within a data field
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Subfield {
     pub code: char,
     pub value: String,
@@ -153,8 +452,8 @@ impl<'de> serde::Deserialize<'de> for Record {
                 let mut control_fields = None;
                 let mut data_fields = None;
 
-                while let Some(key) = map.next_key()? {
-                    match key {
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
                         "leader" => {
                             if leader.is_some() {
                                 return Err(de::Error::duplicate_field("leader"));
@@ -258,8 +557,8 @@ impl<'de> serde::Deserialize<'de> for Leader {
                 let mut length_of_implementation_defined_portion = None;
                 let mut undefined = None;
 
-                while let Some(key) = map.next_key()? {
-                    match key {
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
                         "record_length" => record_length = Some(map.next_value()?),
                         "record_status" => record_status = Some(map.next_value()?),
                         "record_type" => record_type = Some(map.next_value()?),
@@ -282,7 +581,7 @@ impl<'de> serde::Deserialize<'de> for Leader {
                     }
                 }
 
-                Ok(Leader {
+                let leader = Leader {
                     record_length: record_length.ok_or_else(|| de::Error::missing_field("record_length"))?,
                     record_status: record_status.ok_or_else(|| de::Error::missing_field("record_status"))?,
                     record_type: record_type.ok_or_else(|| de::Error::missing_field("record_type"))?,
@@ -300,7 +599,9 @@ impl<'de> serde::Deserialize<'de> for Leader {
                         .ok_or_else(|| de::Error::missing_field("length_of_starting_character_position_portion"))?,
                     length_of_implementation_defined_portion: length_of_implementation_defined_portion.ok_or_else(|| de::Error::missing_field("length_of_implementation_defined_portion"))?,
                     undefined: undefined.ok_or_else(|| de::Error::missing_field("undefined"))?,
-                })
+                };
+                leader.validate().map_err(de::Error::custom)?;
+                Ok(leader)
             }
         }
 
@@ -347,8 +648,8 @@ impl<'de> serde::Deserialize<'de> for ControlField {
                 let mut tag = None;
                 let mut value = None;
 
-                while let Some(key) = map.next_key()? {
-                    match key {
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
                         "tag" => {
                             if tag.is_some() {
                                 return Err(de::Error::duplicate_field("tag"));
@@ -421,8 +722,8 @@ impl<'de> serde::Deserialize<'de> for DataField {
                 let mut ind2 = None;
                 let mut subfields = None;
 
-                while let Some(key) = map.next_key()? {
-                    match key {
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
                         "tag" => {
                             if tag.is_some() {
                                 return Err(de::Error::duplicate_field("tag"));
@@ -505,8 +806,8 @@ impl<'de> serde::Deserialize<'de> for Subfield {
                 let mut code = None;
                 let mut value = None;
 
-                while let Some(key) = map.next_key()? {
-                    match key {
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
                         "code" => {
                             if code.is_some() {
                                 return Err(de::Error::duplicate_field("code"));
@@ -0,0 +1,248 @@
+//! Recovery of structurally damaged binary records.
+//!
+//! [`recover_binary`] rebuilds a record from ISO 2709 bytes whose leader lengths,
+//! leader constants, directory or terminators are wrong, listing the repairs it
+//! made. [`RecoveringReader`] splits its input on record terminators instead of
+//! trusting the record lengths, so one broken record does not hide the next ones.
+//! Writing a recovered record produces a well-formed one.
+//!
+//! ```
+//! use marc_rs::{recover_binary, write_one, DataField, Encoding, FormatEncoding, Leader, MarcFormat, Record, Subfield};
+//!
+//! let record = Record {
+//!     leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+//!     control_fields: vec![],
+//!     data_fields: vec![DataField {
+//!         tag: "245".to_string(),
+//!         ind1: '1',
+//!         ind2: '0',
+//!         subfields: vec![Subfield { code: 'a', value: "Titre".to_string() }],
+//!     }],
+//! };
+//! let format = FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8);
+//! let mut bytes = Vec::new();
+//! write_one(&record, format, &mut bytes).unwrap();
+//! // Wrong record length and indicator count, field starting at 3 instead of 0
+//! bytes[..5].copy_from_slice(b"00001");
+//! bytes[10] = b'3';
+//! bytes[31..36].copy_from_slice(b"00003");
+//!
+//! let recovered = recover_binary(&bytes, format).unwrap();
+//! assert_eq!(recovered.record.data_fields, record.data_fields);
+//! assert_eq!(recovered.repairs, ["record length \"00001\" corrected to \"00048\"", "indicator count \"3\" corrected to \"2\"", "directory lengths and offsets do not match the fields, recomputed"]);
+//! ```
+
+use crate::encoding::convert_to_utf8;
+use crate::format::{FormatEncoding, MarcFormat};
+use crate::parser::{parse_subfields, ParseError};
+use crate::record::{ControlField, DataField, Leader, Record};
+use std::io::BufRead;
+
+/// A record rebuilt from damaged bytes, and the repairs made
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recovered {
+    pub record: Record,
+    /// What was wrong and how it was fixed, e.g. `record length "00001" corrected to "00048"`; empty for a sound record
+    pub repairs: Vec<String>,
+}
+
+/// Rebuild a record from the bytes of one binary record, up to its record terminator
+///
+/// Lengths and offsets are recomputed from the terminators actually found: the
+/// directory ends at the first field terminator, and fields are taken in order
+/// when the directory entries do not match them. Leader constants (indicator and
+/// subfield code counts, entry map) are reset, and characters that cannot be
+/// decoded are replaced with U+FFFD. Fails only when no leader or directory can
+/// be found.
+pub fn recover_binary(data: &[u8], format_encoding: FormatEncoding) -> Result<Recovered, ParseError> {
+    let mut repairs = Vec::new();
+    // Line breaks between records
+    let data = data.trim_ascii_start();
+    let data = match data.strip_suffix(&[0x1D]) {
+        Some(data) => data,
+        None => {
+            repairs.push("missing record terminator".to_string());
+            data
+        }
+    };
+    if data.len() < 24 {
+        return Err(ParseError::InvalidLeader(format!("Leader must be 24 bytes, got {}", data.len())));
+    }
+    let directory_end = memchr::memchr(0x1E, &data[24..]).map(|i| 24 + i).ok_or_else(|| ParseError::InvalidField("No directory terminator".to_string()))?;
+    let leader = recover_leader(&data[..24], data.len() + 1, directory_end + 1, format_encoding.format, &mut repairs)?;
+
+    let directory = &data[24..directory_end];
+    if directory.len() % 12 != 0 {
+        repairs.push(format!("directory of {} bytes is not made of 12-byte entries, last {} byte(s) ignored", directory.len(), directory.len() % 12));
+    }
+    let data_area = &data[directory_end + 1..];
+    let fields: Vec<&[u8]> = data_area.split_inclusive(|&b| b == 0x1E).collect();
+    let entries: Vec<(&[u8], Option<&[u8]>)> = directory
+        .chunks_exact(12)
+        .map(|entry| {
+            let length = parse_digits(&entry[3..7]);
+            let start = parse_digits(&entry[7..12]);
+            let field = match (length, start) {
+                (Some(length), Some(start)) if length > 0 && start + length <= data_area.len() && data_area[start + length - 1] == 0x1E => Some(&data_area[start..start + length]),
+                _ => None,
+            };
+            (&entry[..3], field)
+        })
+        .collect();
+
+    let located: Vec<(&[u8], Option<&[u8]>)> = if entries.iter().all(|(_, field)| field.is_some()) {
+        entries
+    } else if entries.len() == fields.len() {
+        repairs.push("directory lengths and offsets do not match the fields, recomputed".to_string());
+        entries.iter().zip(fields).map(|((tag, _), field)| (*tag, Some(field))).collect()
+    } else {
+        entries
+    };
+
+    let mut control_fields = Vec::new();
+    let mut data_fields = Vec::new();
+    for (tag, field) in located {
+        let tag = match std::str::from_utf8(tag) {
+            Ok(tag) if tag.bytes().all(|b| b.is_ascii_alphanumeric()) => tag,
+            _ => {
+                repairs.push(format!("field with invalid tag {:?} dropped", String::from_utf8_lossy(tag)));
+                continue;
+            }
+        };
+        let Some(field) = field else {
+            repairs.push(format!("field {} dropped: its directory entry does not match the data", tag));
+            continue;
+        };
+        let field = match field.strip_suffix(&[0x1E]) {
+            Some(field) => field,
+            None => {
+                repairs.push(format!("missing field terminator of {}", tag));
+                field
+            }
+        };
+
+        let mut undecodable = false;
+        let mut decode = |bytes: &[u8]| {
+            convert_to_utf8(bytes, format_encoding.encoding).unwrap_or_else(|_| {
+                undecodable = true;
+                String::from_utf8_lossy(bytes).into_owned()
+            })
+        };
+        if tag < "010" {
+            control_fields.push(ControlField { tag: tag.to_string(), value: decode(field) });
+        } else if !field.is_empty() {
+            let (ind1, ind2, subfield_data) = match field {
+                [ind1, ind2, rest @ ..] if *ind1 != 0x1F && *ind2 != 0x1F => (*ind1 as char, *ind2 as char, rest),
+                _ => {
+                    repairs.push(format!("missing indicators of {} set to blanks", tag));
+                    (' ', ' ', field)
+                }
+            };
+            let subfields = parse_subfields(subfield_data, |bytes| Ok(decode(bytes)))?;
+            data_fields.push(DataField {
+                tag: tag.to_string(),
+                ind1,
+                ind2,
+                subfields,
+            });
+        }
+        if undecodable {
+            repairs.push(format!("characters of {} that cannot be decoded replaced with U+FFFD", tag));
+        }
+    }
+
+    Ok(Recovered {
+        record: Record {
+            leader,
+            control_fields,
+            data_fields,
+        },
+        repairs,
+    })
+}
+
+/// Leader with its lengths set to the actual ones and its constants reset
+fn recover_leader(bytes: &[u8], record_length: usize, base_address: usize, format: MarcFormat, repairs: &mut Vec<String>) -> Result<Leader, ParseError> {
+    if record_length > 99999 {
+        return Err(ParseError::InvalidRecordLength(format!("Record of {} bytes exceeds 99999", record_length)));
+    }
+    let mut bytes = bytes.to_vec();
+    let undefined = match format {
+        MarcFormat::Unimarc => " ",
+        _ => "0",
+    };
+    let expected = [
+        ("record length", 0..5, format!("{:05}", record_length)),
+        ("indicator count", 10..11, "2".to_string()),
+        ("subfield code count", 11..12, "2".to_string()),
+        ("base address of data", 12..17, format!("{:05}", base_address)),
+        ("entry map", 20..24, format!("450{}", undefined)),
+    ];
+    for (name, range, value) in expected {
+        if bytes[range.clone()] != *value.as_bytes() {
+            repairs.push(format!("{} {:?} corrected to {:?}", name, String::from_utf8_lossy(&bytes[range.clone()]), value));
+            bytes[range].copy_from_slice(value.as_bytes());
+        }
+    }
+    for position in [5, 6, 7, 8, 9, 17, 18, 19] {
+        if !bytes[position].is_ascii_graphic() && bytes[position] != b' ' {
+            repairs.push(format!("leader position {} {:?} replaced with a blank", position, bytes[position] as char));
+            bytes[position] = b' ';
+        }
+    }
+    Leader::from_bytes(&bytes).map_err(ParseError::InvalidLeader)
+}
+
+fn parse_digits(bytes: &[u8]) -> Option<usize> {
+    match bytes.iter().all(u8::is_ascii_digit) {
+        true => std::str::from_utf8(bytes).ok()?.parse().ok(),
+        false => None,
+    }
+}
+
+/// Iterator recovering binary records one at a time from a reader
+///
+/// Records are split on record terminators (0x1D), whatever their leaders say,
+/// and each is rebuilt with [`recover_binary`]. Unlike [`RecordReader`](crate::RecordReader),
+/// the iterator goes on after a record that cannot be recovered.
+pub struct RecoveringReader<R: BufRead> {
+    reader: R,
+    format_encoding: FormatEncoding,
+    buffer: Vec<u8>,
+    done: bool,
+}
+
+impl<R: BufRead> RecoveringReader<R> {
+    /// Create a reader for the given binary format and encoding
+    pub fn new(reader: R, format_encoding: FormatEncoding) -> Self {
+        Self {
+            reader,
+            format_encoding,
+            buffer: Vec::new(),
+            done: false,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for RecoveringReader<R> {
+    type Item = Result<Recovered, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        self.buffer.clear();
+        match self.reader.read_until(0x1D, &mut self.buffer) {
+            Ok(_) if self.buffer.trim_ascii().is_empty() => {
+                // End of input, possibly after a trailing newline
+                self.done = true;
+                None
+            }
+            Ok(_) => Some(recover_binary(&self.buffer, self.format_encoding)),
+            Err(e) => {
+                self.done = true;
+                Some(Err(ParseError::Other(format!("IO error: {}", e))))
+            }
+        }
+    }
+}
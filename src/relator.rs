@@ -0,0 +1,476 @@
+use crate::record::DataField;
+
+/// MARC relator, identifying the role of an agent in relation to a resource
+///
+/// Each relator has a MARC code (used in `$4` of MARC21 and as the value of
+/// relator URIs), an English label (the `$e` term) and, where one exists, the
+/// equivalent UNIMARC function code (Appendix C, used in `$4` of UNIMARC 7XX).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Relator {
+    /// act - Actor
+    Actor,
+    /// adp - Adapter
+    Adapter,
+    /// aft - Author of afterword, colophon, etc.
+    AuthorOfAfterword,
+    /// anm - Animator
+    Animator,
+    /// ann - Annotator
+    Annotator,
+    /// arr - Arranger of music
+    Arranger,
+    /// art - Artist
+    Artist,
+    /// aui - Author of introduction, etc.
+    AuthorOfIntroduction,
+    /// aus - Screenwriter
+    Screenwriter,
+    /// aut - Author
+    Author,
+    /// bnd - Binder
+    Binder,
+    /// bsl - Bookseller
+    Bookseller,
+    /// chr - Choreographer
+    Choreographer,
+    /// clb - Collaborator
+    Collaborator,
+    /// cll - Calligrapher
+    Calligrapher,
+    /// cmm - Commentator
+    Commentator,
+    /// cmp - Composer
+    Composer,
+    /// cnd - Conductor
+    Conductor,
+    /// com - Compiler
+    Compiler,
+    /// cph - Copyright holder
+    CopyrightHolder,
+    /// cre - Creator
+    Creator,
+    /// ctb - Contributor
+    Contributor,
+    /// ctg - Cartographer
+    Cartographer,
+    /// cur - Curator
+    Curator,
+    /// dgg - Degree granting institution
+    DegreeGrantingInstitution,
+    /// dis - Dissertant
+    Dissertant,
+    /// dnr - Donor
+    Donor,
+    /// drt - Director
+    Director,
+    /// dsr - Designer
+    Designer,
+    /// dst - Distributor
+    Distributor,
+    /// dte - Dedicatee
+    Dedicatee,
+    /// dto - Dedicator
+    Dedicator,
+    /// edt - Editor
+    Editor,
+    /// egr - Engraver
+    Engraver,
+    /// flm - Film editor
+    FilmEditor,
+    /// fmo - Former owner
+    FormerOwner,
+    /// fnd - Funder
+    Funder,
+    /// hnr - Honoree
+    Honoree,
+    /// ill - Illustrator
+    Illustrator,
+    /// ilu - Illuminator
+    Illuminator,
+    /// ive - Interviewee
+    Interviewee,
+    /// ivr - Interviewer
+    Interviewer,
+    /// lbt - Librettist
+    Librettist,
+    /// lyr - Lyricist
+    Lyricist,
+    /// nrt - Narrator
+    Narrator,
+    /// orm - Organizer
+    Organizer,
+    /// oth - Other
+    Other,
+    /// pbl - Publisher
+    Publisher,
+    /// pht - Photographer
+    Photographer,
+    /// pre - Presenter
+    Presenter,
+    /// prf - Performer
+    Performer,
+    /// pro - Producer
+    Producer,
+    /// prt - Printer
+    Printer,
+    /// rcp - Addressee
+    Addressee,
+    /// rev - Reviewer
+    Reviewer,
+    /// sad - Scientific advisor
+    ScientificAdvisor,
+    /// scl - Sculptor
+    Sculptor,
+    /// scr - Scribe
+    Scribe,
+    /// sll - Seller
+    Seller,
+    /// sng - Singer
+    Singer,
+    /// spn - Sponsor
+    Sponsor,
+    /// ths - Thesis advisor
+    ThesisAdvisor,
+    /// trl - Translator
+    Translator,
+    /// voc - Vocalist
+    Vocalist,
+    /// wam - Writer of accompanying material
+    WriterOfAccompanyingMaterial,
+    /// wst - Writer of supplementary textual content
+    Writer,
+}
+
+impl Relator {
+    /// All variants, ordered by code
+    pub const ALL: &'static [Relator] = &[
+        Relator::Actor,
+        Relator::Adapter,
+        Relator::AuthorOfAfterword,
+        Relator::Animator,
+        Relator::Annotator,
+        Relator::Arranger,
+        Relator::Artist,
+        Relator::AuthorOfIntroduction,
+        Relator::Screenwriter,
+        Relator::Author,
+        Relator::Binder,
+        Relator::Bookseller,
+        Relator::Choreographer,
+        Relator::Collaborator,
+        Relator::Calligrapher,
+        Relator::Commentator,
+        Relator::Composer,
+        Relator::Conductor,
+        Relator::Compiler,
+        Relator::CopyrightHolder,
+        Relator::Creator,
+        Relator::Contributor,
+        Relator::Cartographer,
+        Relator::Curator,
+        Relator::DegreeGrantingInstitution,
+        Relator::Dissertant,
+        Relator::Donor,
+        Relator::Director,
+        Relator::Designer,
+        Relator::Distributor,
+        Relator::Dedicatee,
+        Relator::Dedicator,
+        Relator::Editor,
+        Relator::Engraver,
+        Relator::FilmEditor,
+        Relator::FormerOwner,
+        Relator::Funder,
+        Relator::Honoree,
+        Relator::Illustrator,
+        Relator::Illuminator,
+        Relator::Interviewee,
+        Relator::Interviewer,
+        Relator::Librettist,
+        Relator::Lyricist,
+        Relator::Narrator,
+        Relator::Organizer,
+        Relator::Other,
+        Relator::Publisher,
+        Relator::Photographer,
+        Relator::Presenter,
+        Relator::Performer,
+        Relator::Producer,
+        Relator::Printer,
+        Relator::Addressee,
+        Relator::Reviewer,
+        Relator::ScientificAdvisor,
+        Relator::Sculptor,
+        Relator::Scribe,
+        Relator::Seller,
+        Relator::Singer,
+        Relator::Sponsor,
+        Relator::ThesisAdvisor,
+        Relator::Translator,
+        Relator::Vocalist,
+        Relator::WriterOfAccompanyingMaterial,
+        Relator::Writer,
+    ];
+
+    /// MARC relator code (e.g. "aut")
+    pub fn code(&self) -> &'static str {
+        match self {
+            Relator::Actor => "act",
+            Relator::Adapter => "adp",
+            Relator::AuthorOfAfterword => "aft",
+            Relator::Animator => "anm",
+            Relator::Annotator => "ann",
+            Relator::Arranger => "arr",
+            Relator::Artist => "art",
+            Relator::AuthorOfIntroduction => "aui",
+            Relator::Screenwriter => "aus",
+            Relator::Author => "aut",
+            Relator::Binder => "bnd",
+            Relator::Bookseller => "bsl",
+            Relator::Choreographer => "chr",
+            Relator::Collaborator => "clb",
+            Relator::Calligrapher => "cll",
+            Relator::Commentator => "cmm",
+            Relator::Composer => "cmp",
+            Relator::Conductor => "cnd",
+            Relator::Compiler => "com",
+            Relator::CopyrightHolder => "cph",
+            Relator::Creator => "cre",
+            Relator::Contributor => "ctb",
+            Relator::Cartographer => "ctg",
+            Relator::Curator => "cur",
+            Relator::DegreeGrantingInstitution => "dgg",
+            Relator::Dissertant => "dis",
+            Relator::Donor => "dnr",
+            Relator::Director => "drt",
+            Relator::Designer => "dsr",
+            Relator::Distributor => "dst",
+            Relator::Dedicatee => "dte",
+            Relator::Dedicator => "dto",
+            Relator::Editor => "edt",
+            Relator::Engraver => "egr",
+            Relator::FilmEditor => "flm",
+            Relator::FormerOwner => "fmo",
+            Relator::Funder => "fnd",
+            Relator::Honoree => "hnr",
+            Relator::Illustrator => "ill",
+            Relator::Illuminator => "ilu",
+            Relator::Interviewee => "ive",
+            Relator::Interviewer => "ivr",
+            Relator::Librettist => "lbt",
+            Relator::Lyricist => "lyr",
+            Relator::Narrator => "nrt",
+            Relator::Organizer => "orm",
+            Relator::Other => "oth",
+            Relator::Publisher => "pbl",
+            Relator::Photographer => "pht",
+            Relator::Presenter => "pre",
+            Relator::Performer => "prf",
+            Relator::Producer => "pro",
+            Relator::Printer => "prt",
+            Relator::Addressee => "rcp",
+            Relator::Reviewer => "rev",
+            Relator::ScientificAdvisor => "sad",
+            Relator::Sculptor => "scl",
+            Relator::Scribe => "scr",
+            Relator::Seller => "sll",
+            Relator::Singer => "sng",
+            Relator::Sponsor => "spn",
+            Relator::ThesisAdvisor => "ths",
+            Relator::Translator => "trl",
+            Relator::Vocalist => "voc",
+            Relator::WriterOfAccompanyingMaterial => "wam",
+            Relator::Writer => "wst",
+        }
+    }
+
+    /// English label, as used in `$e` relator terms
+    pub fn label(&self) -> &'static str {
+        match self {
+            Relator::Actor => "Actor",
+            Relator::Adapter => "Adapter",
+            Relator::AuthorOfAfterword => "Author of afterword, colophon, etc.",
+            Relator::Animator => "Animator",
+            Relator::Annotator => "Annotator",
+            Relator::Arranger => "Arranger of music",
+            Relator::Artist => "Artist",
+            Relator::AuthorOfIntroduction => "Author of introduction, etc.",
+            Relator::Screenwriter => "Screenwriter",
+            Relator::Author => "Author",
+            Relator::Binder => "Binder",
+            Relator::Bookseller => "Bookseller",
+            Relator::Choreographer => "Choreographer",
+            Relator::Collaborator => "Collaborator",
+            Relator::Calligrapher => "Calligrapher",
+            Relator::Commentator => "Commentator",
+            Relator::Composer => "Composer",
+            Relator::Conductor => "Conductor",
+            Relator::Compiler => "Compiler",
+            Relator::CopyrightHolder => "Copyright holder",
+            Relator::Creator => "Creator",
+            Relator::Contributor => "Contributor",
+            Relator::Cartographer => "Cartographer",
+            Relator::Curator => "Curator",
+            Relator::DegreeGrantingInstitution => "Degree granting institution",
+            Relator::Dissertant => "Dissertant",
+            Relator::Donor => "Donor",
+            Relator::Director => "Director",
+            Relator::Designer => "Designer",
+            Relator::Distributor => "Distributor",
+            Relator::Dedicatee => "Dedicatee",
+            Relator::Dedicator => "Dedicator",
+            Relator::Editor => "Editor",
+            Relator::Engraver => "Engraver",
+            Relator::FilmEditor => "Film editor",
+            Relator::FormerOwner => "Former owner",
+            Relator::Funder => "Funder",
+            Relator::Honoree => "Honoree",
+            Relator::Illustrator => "Illustrator",
+            Relator::Illuminator => "Illuminator",
+            Relator::Interviewee => "Interviewee",
+            Relator::Interviewer => "Interviewer",
+            Relator::Librettist => "Librettist",
+            Relator::Lyricist => "Lyricist",
+            Relator::Narrator => "Narrator",
+            Relator::Organizer => "Organizer",
+            Relator::Other => "Other",
+            Relator::Publisher => "Publisher",
+            Relator::Photographer => "Photographer",
+            Relator::Presenter => "Presenter",
+            Relator::Performer => "Performer",
+            Relator::Producer => "Producer",
+            Relator::Printer => "Printer",
+            Relator::Addressee => "Addressee",
+            Relator::Reviewer => "Reviewer",
+            Relator::ScientificAdvisor => "Scientific advisor",
+            Relator::Sculptor => "Sculptor",
+            Relator::Scribe => "Scribe",
+            Relator::Seller => "Seller",
+            Relator::Singer => "Singer",
+            Relator::Sponsor => "Sponsor",
+            Relator::ThesisAdvisor => "Thesis advisor",
+            Relator::Translator => "Translator",
+            Relator::Vocalist => "Vocalist",
+            Relator::WriterOfAccompanyingMaterial => "Writer of accompanying material",
+            Relator::Writer => "Writer of supplementary textual content",
+        }
+    }
+
+    /// UNIMARC function code (e.g. "070" for author)
+    pub fn unimarc_code(&self) -> Option<&'static str> {
+        match self {
+            Relator::Actor => Some("005"),
+            Relator::Adapter => Some("010"),
+            Relator::AuthorOfAfterword => Some("075"),
+            Relator::Animator => Some("018"),
+            Relator::Annotator => Some("020"),
+            Relator::Arranger => Some("030"),
+            Relator::Artist => Some("040"),
+            Relator::AuthorOfIntroduction => Some("080"),
+            Relator::Screenwriter => Some("690"),
+            Relator::Author => Some("070"),
+            Relator::Binder => Some("110"),
+            Relator::Bookseller => Some("160"),
+            Relator::Choreographer => Some("200"),
+            Relator::Collaborator => Some("205"),
+            Relator::Calligrapher => Some("170"),
+            Relator::Commentator => Some("210"),
+            Relator::Composer => Some("230"),
+            Relator::Conductor => Some("250"),
+            Relator::Compiler => Some("220"),
+            Relator::CopyrightHolder => Some("260"),
+            Relator::Creator => None, // No UNIMARC function code
+            Relator::Contributor => None, // No UNIMARC function code
+            Relator::Cartographer => Some("180"),
+            Relator::Curator => Some("273"),
+            Relator::DegreeGrantingInstitution => Some("295"),
+            Relator::Dissertant => Some("305"),
+            Relator::Donor => Some("320"),
+            Relator::Director => Some("300"),
+            Relator::Designer => None, // No UNIMARC function code
+            Relator::Distributor => Some("310"),
+            Relator::Dedicatee => Some("280"),
+            Relator::Dedicator => Some("290"),
+            Relator::Editor => Some("340"),
+            Relator::Engraver => Some("350"),
+            Relator::FilmEditor => Some("370"),
+            Relator::FormerOwner => Some("390"),
+            Relator::Funder => Some("400"),
+            Relator::Honoree => Some("420"),
+            Relator::Illustrator => Some("440"),
+            Relator::Illuminator => Some("430"),
+            Relator::Interviewee => Some("460"),
+            Relator::Interviewer => Some("470"),
+            Relator::Librettist => Some("480"),
+            Relator::Lyricist => Some("520"),
+            Relator::Narrator => Some("550"),
+            Relator::Organizer => Some("557"),
+            Relator::Other => Some("570"),
+            Relator::Publisher => Some("650"),
+            Relator::Photographer => Some("600"),
+            Relator::Presenter => Some("605"),
+            Relator::Performer => Some("590"),
+            Relator::Producer => Some("630"),
+            Relator::Printer => Some("610"),
+            Relator::Addressee => Some("660"),
+            Relator::Reviewer => Some("675"),
+            Relator::ScientificAdvisor => Some("695"),
+            Relator::Sculptor => Some("705"),
+            Relator::Scribe => Some("700"),
+            Relator::Seller => Some("753"),
+            Relator::Singer => Some("721"),
+            Relator::Sponsor => Some("723"),
+            Relator::ThesisAdvisor => Some("727"),
+            Relator::Translator => Some("730"),
+            Relator::Vocalist => Some("755"),
+            Relator::WriterOfAccompanyingMaterial => Some("770"),
+            Relator::Writer => None, // No UNIMARC function code
+        }
+    }
+
+    /// Look up a relator by MARC code
+    ///
+    /// Accepts relator URIs (`http://id.loc.gov/vocabulary/relators/aut`) and ignores case.
+    pub fn from_code(code: &str) -> Option<Self> {
+        let code = code.trim();
+        let code = code.rsplit('/').next().unwrap_or(code);
+        Relator::ALL.iter().copied().find(|r| r.code().eq_ignore_ascii_case(code))
+    }
+
+    /// Look up a relator by UNIMARC function code
+    pub fn from_unimarc_code(code: &str) -> Option<Self> {
+        let code = code.trim();
+        Relator::ALL.iter().copied().find(|r| r.unimarc_code() == Some(code))
+    }
+
+    /// Look up a relator by English term, ignoring case and trailing ISBD punctuation
+    pub fn from_term(term: &str) -> Option<Self> {
+        let term = term.trim().trim_end_matches(['.', ',', ';', ':']).trim();
+        Relator::ALL.iter().copied().find(|r| r.label().eq_ignore_ascii_case(term))
+    }
+}
+
+impl DataField {
+    /// Relators expressed in the field, from `$4` codes then `$e` terms
+    ///
+    /// `$4` values may be MARC codes, relator URIs or UNIMARC function codes.
+    /// Unrecognised values are skipped and duplicates removed.
+    pub fn relators(&self) -> Vec<Relator> {
+        let mut relators = Vec::new();
+
+        for subfield in &self.subfields {
+            let relator = match subfield.code {
+                '4' => Relator::from_code(&subfield.value).or_else(|| Relator::from_unimarc_code(&subfield.value)),
+                'e' => Relator::from_term(&subfield.value),
+                _ => None,
+            };
+            if let Some(relator) = relator {
+                if !relators.contains(&relator) {
+                    relators.push(relator);
+                }
+            }
+        }
+
+        relators
+    }
+}
@@ -0,0 +1,368 @@
+use crate::record::{DataField, Record};
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Range of positions (field occurrences or characters); `None` as end means "to the last one"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpecRange {
+    pub start: SpecPosition,
+    pub end: Option<SpecPosition>,
+}
+
+/// Position in a [`SpecRange`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpecPosition {
+    /// 0-based position
+    At(usize),
+    /// `#` - the last position
+    Last,
+}
+
+impl SpecPosition {
+    fn resolve(&self, len: usize) -> Option<usize> {
+        match self {
+            SpecPosition::At(i) if *i < len => Some(*i),
+            SpecPosition::At(_) => None,
+            SpecPosition::Last => len.checked_sub(1),
+        }
+    }
+}
+
+impl SpecRange {
+    /// Index range covered in a sequence of `len` items, if any
+    fn resolve(&self, len: usize) -> Option<std::ops::RangeInclusive<usize>> {
+        let start = self.start.resolve(len)?;
+        let end = match self.end {
+            None => start,
+            Some(end) => end.resolve(len).unwrap_or(len.checked_sub(1)?),
+        };
+        (start <= end).then_some(start..=end)
+    }
+}
+
+/// What a spec selects inside the matching fields
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpecTarget {
+    /// The whole field: the control field value, or the data field subfields joined by spaces
+    Field,
+    /// First (`^1`) or second (`^2`) indicator
+    Indicator(u8),
+    /// Values of these subfield codes, in field order
+    Subfields(Vec<char>),
+}
+
+/// Subset of the MARCspec syntax, used to address values of a record
+///
+/// Supported forms:
+///
+/// - `245`, `6..` - fields by tag, `.` matching any character
+/// - `LDR` - the leader
+/// - `650[0]`, `650[1-2]`, `650[#]` - field occurrences (0-based, `#` for the last)
+/// - `008/35-37`, `LDR/6`, `245$a/0-3` - character positions (0-based, `#` for the last)
+/// - `245^1` - first or second indicator
+/// - `245$a`, `245$a$b`, `245$a-c` - subfields
+///
+/// ```
+/// use marc_rs::MarcSpec;
+///
+/// let spec: MarcSpec = "650[0]$a$x".parse().unwrap();
+/// assert_eq!(spec.tag, "650");
+/// assert_eq!(spec.to_string(), "650[0]$a$x");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarcSpec {
+    pub tag: String,
+    pub index: Option<SpecRange>,
+    pub target: SpecTarget,
+    pub positions: Option<SpecRange>,
+}
+
+impl MarcSpec {
+    /// Parse a spec
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let error = |message: &str| format!("Invalid spec {:?}: {}", spec, message);
+
+        let tag: String = spec.chars().take(3).collect();
+        if tag.chars().count() != 3 || !tag.chars().all(|c| c.is_ascii_alphanumeric() || c == '.') {
+            return Err(error("expected a 3 character tag"));
+        }
+        let mut rest = &spec[tag.len()..];
+
+        let mut index = None;
+        if let Some(after) = rest.strip_prefix('[') {
+            let close = after.find(']').ok_or_else(|| error("unclosed ["))?;
+            index = Some(parse_range(&after[..close]).ok_or_else(|| error("invalid index"))?);
+            rest = &after[close + 1..];
+        }
+
+        let mut target = SpecTarget::Field;
+        if let Some(after) = rest.strip_prefix('^') {
+            target = match after.chars().next() {
+                Some('1') => SpecTarget::Indicator(1),
+                Some('2') => SpecTarget::Indicator(2),
+                _ => return Err(error("indicator must be ^1 or ^2")),
+            };
+            rest = &after[1..];
+        } else if rest.starts_with('$') {
+            let mut codes = Vec::new();
+            while let Some(after) = rest.strip_prefix('$') {
+                let mut chars = after.chars();
+                let code = chars.next().ok_or_else(|| error("missing subfield code"))?;
+                rest = chars.as_str();
+                match rest.strip_prefix('-').and_then(|r| r.chars().next().map(|end| (end, &r[end.len_utf8()..]))) {
+                    Some((end, after_range)) if end > code => {
+                        codes.extend(code..=end);
+                        rest = after_range;
+                    }
+                    Some(_) => return Err(error("invalid subfield range")),
+                    None => codes.push(code),
+                }
+            }
+            target = SpecTarget::Subfields(codes);
+        }
+
+        let mut positions = None;
+        if let Some(after) = rest.strip_prefix('/') {
+            positions = Some(parse_range(after).ok_or_else(|| error("invalid character positions"))?);
+            rest = "";
+        }
+
+        if !rest.is_empty() {
+            return Err(error(&format!("unexpected {:?}", rest)));
+        }
+        if tag == "LDR" && (index.is_some() || target != SpecTarget::Field) {
+            return Err(error("the leader only supports character positions"));
+        }
+
+        Ok(Self { tag, index, target, positions })
+    }
+
+    /// True if the spec matches a tag (`.` matches any character)
+    pub fn matches_tag(&self, tag: &str) -> bool {
+        tag.len() == 3 && self.tag.chars().zip(tag.chars()).all(|(p, c)| p == '.' || p == c)
+    }
+
+    /// Values selected by the spec, in record order
+    pub fn values(&self, record: &Record) -> Vec<String> {
+        let mut values = Vec::new();
+
+        if self.tag == "LDR" {
+            values.push(String::from_utf8_lossy(&record.leader.to_bytes()).into_owned());
+        } else {
+            let controls: Vec<&str> = record.control_fields.iter().filter(|f| self.matches_tag(&f.tag)).map(|f| f.value.as_str()).collect();
+            let fields: Vec<&DataField> = record.data_fields.iter().filter(|f| self.matches_tag(&f.tag)).collect();
+
+            if self.target == SpecTarget::Field {
+                values.extend(select(&controls, self.index).into_iter().map(|v| v.to_string()));
+            }
+            for field in select(&fields, self.index) {
+                match &self.target {
+                    SpecTarget::Field => values.push(field.subfields.iter().map(|s| s.value.as_str()).collect::<Vec<_>>().join(" ")),
+                    SpecTarget::Indicator(1) => values.push(field.ind1.to_string()),
+                    SpecTarget::Indicator(_) => values.push(field.ind2.to_string()),
+                    SpecTarget::Subfields(codes) => values.extend(field.subfields.iter().filter(|s| codes.contains(&s.code)).map(|s| s.value.clone())),
+                }
+            }
+        }
+
+        match self.positions {
+            Some(positions) => values
+                .into_iter()
+                .filter_map(|v| {
+                    let chars: Vec<char> = v.chars().collect();
+                    positions.resolve(chars.len()).map(|range| chars[range].iter().collect())
+                })
+                .collect(),
+            None => values,
+        }
+    }
+
+    /// First value selected by the spec
+    pub fn first_value(&self, record: &Record) -> Option<String> {
+        self.values(record).into_iter().next()
+    }
+}
+
+fn select<T: Copy>(items: &[T], index: Option<SpecRange>) -> Vec<T> {
+    match index {
+        None => items.to_vec(),
+        Some(range) => range.resolve(items.len()).map(|r| items[r].to_vec()).unwrap_or_default(),
+    }
+}
+
+fn parse_position(value: &str) -> Option<SpecPosition> {
+    match value {
+        "#" => Some(SpecPosition::Last),
+        _ => value.parse().ok().map(SpecPosition::At),
+    }
+}
+
+fn parse_range(value: &str) -> Option<SpecRange> {
+    match value.split_once('-') {
+        Some((start, end)) => Some(SpecRange {
+            start: parse_position(start)?,
+            end: Some(parse_position(end)?),
+        }),
+        None => Some(SpecRange {
+            start: parse_position(value)?,
+            end: None,
+        }),
+    }
+}
+
+impl FromStr for MarcSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        MarcSpec::parse(s)
+    }
+}
+
+impl Display for SpecPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpecPosition::At(i) => write!(f, "{}", i),
+            SpecPosition::Last => write!(f, "#"),
+        }
+    }
+}
+
+impl Display for SpecRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.end {
+            Some(end) => write!(f, "{}-{}", self.start, end),
+            None => write!(f, "{}", self.start),
+        }
+    }
+}
+
+impl Display for MarcSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.tag)?;
+        if let Some(index) = self.index {
+            write!(f, "[{}]", index)?;
+        }
+        match &self.target {
+            SpecTarget::Field => {}
+            SpecTarget::Indicator(n) => write!(f, "^{}", n)?,
+            SpecTarget::Subfields(codes) => {
+                for code in codes {
+                    write!(f, "${}", code)?;
+                }
+            }
+        }
+        if let Some(positions) = self.positions {
+            write!(f, "/{}", positions)?;
+        }
+        Ok(())
+    }
+}
+
+/// Comparison of a [`SpecCondition`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpecOperator {
+    /// The spec selects at least one value
+    Exists,
+    /// `=` - one of the values equals the operand
+    Equals,
+    /// `!=` - none of the values equals the operand
+    NotEquals,
+    /// `~` - one of the values contains the operand, ignoring case
+    Contains,
+}
+
+/// Condition on the values selected by a [`MarcSpec`], used to filter records
+///
+/// Written `SPEC`, `SPEC=VALUE`, `SPEC!=VALUE` or `SPEC~VALUE`.
+///
+/// ```
+/// use marc_rs::SpecCondition;
+///
+/// let condition: SpecCondition = "008/35-37=fre".parse().unwrap();
+/// assert_eq!(condition.spec.to_string(), "008/35-37");
+/// assert_eq!(condition.value, "fre");
+/// assert_eq!(condition.to_string(), "008/35-37=fre");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpecCondition {
+    pub spec: MarcSpec,
+    pub operator: SpecOperator,
+    /// Operand, empty for [`SpecOperator::Exists`]
+    pub value: String,
+}
+
+impl SpecCondition {
+    /// Parse a condition
+    pub fn parse(condition: &str) -> Result<Self, String> {
+        // Subfield codes are single characters, so skip them when looking for the operator
+        let mut operator = None;
+        let mut chars = condition.char_indices().skip(3);
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '$' => {
+                    chars.next();
+                }
+                '=' => operator = Some((i, 1, SpecOperator::Equals)),
+                '~' => operator = Some((i, 1, SpecOperator::Contains)),
+                '!' if condition[i + 1..].starts_with('=') => operator = Some((i, 2, SpecOperator::NotEquals)),
+                _ => {}
+            }
+            if operator.is_some() {
+                break;
+            }
+        }
+
+        match operator {
+            Some((i, len, operator)) => Ok(Self {
+                spec: MarcSpec::parse(&condition[..i])?,
+                operator,
+                value: condition[i + len..].to_string(),
+            }),
+            None => Ok(Self {
+                spec: MarcSpec::parse(condition)?,
+                operator: SpecOperator::Exists,
+                value: String::new(),
+            }),
+        }
+    }
+
+    /// True if the record satisfies the condition
+    pub fn matches(&self, record: &Record) -> bool {
+        let values = self.spec.values(record);
+        match self.operator {
+            SpecOperator::Exists => !values.is_empty(),
+            SpecOperator::Equals => values.contains(&self.value),
+            SpecOperator::NotEquals => !values.contains(&self.value),
+            SpecOperator::Contains => {
+                let value = self.value.to_lowercase();
+                values.iter().any(|v| v.to_lowercase().contains(&value))
+            }
+        }
+    }
+}
+
+impl Display for SpecCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.operator {
+            SpecOperator::Exists => write!(f, "{}", self.spec),
+            SpecOperator::Equals => write!(f, "{}={}", self.spec, self.value),
+            SpecOperator::NotEquals => write!(f, "{}!={}", self.spec, self.value),
+            SpecOperator::Contains => write!(f, "{}~{}", self.spec, self.value),
+        }
+    }
+}
+
+impl FromStr for SpecCondition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SpecCondition::parse(s)
+    }
+}
+
+impl Record {
+    /// Values selected by a [`MarcSpec`] string
+    pub fn spec_values(&self, spec: &str) -> Result<Vec<String>, String> {
+        Ok(MarcSpec::parse(spec)?.values(self))
+    }
+}
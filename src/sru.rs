@@ -0,0 +1,213 @@
+//! SRU search (`sru` feature).
+//!
+//! An [`SruSearch`] sends a CQL query to an SRU server with the
+//! `searchRetrieve` operation, pages through the results and yields each
+//! MARC XML record. [`parse_sru_response`] parses one response page.
+//!
+//! ```no_run
+//! use marc_rs::SruSearch;
+//!
+//! let search = SruSearch::new("https://lx2.loc.gov/sru/voyager", "bath.isbn=9780261103252");
+//! for record in search.take(5) {
+//!     let record = record.unwrap();
+//!     println!("{:?}", record.join("245", &['a', 'b'], " "));
+//! }
+//! ```
+
+use crate::format::FormatEncoding;
+use crate::parser::parse_marc_xml;
+use crate::record::Record;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// SRU error type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SruError {
+    /// The request failed: connection, timeout or HTTP status
+    Http(String),
+    /// The server answered with a diagnostic, e.g. `info:srw/diagnostic/1/10` for a query syntax error
+    Diagnostic { uri: String, message: String },
+    /// The response is not a valid SRU response
+    InvalidResponse(String),
+}
+
+impl std::fmt::Display for SruError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SruError::Http(msg) => write!(f, "HTTP error: {}", msg),
+            SruError::Diagnostic { uri, message } if message.is_empty() => write!(f, "SRU diagnostic {}", uri),
+            SruError::Diagnostic { uri, message } => write!(f, "SRU diagnostic {}: {}", uri, message),
+            SruError::InvalidResponse(msg) => write!(f, "Invalid SRU response: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SruError {}
+
+/// One page of a `searchRetrieve` response
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SruPage {
+    /// Records of the page, in MARC XML; records in other schemas are left out
+    pub records: Vec<Record>,
+    /// Number of records matching the query
+    pub number_of_records: usize,
+    /// Position of the first record of the next page; none on the last page
+    pub next_record_position: Option<usize>,
+}
+
+/// Parse a `searchRetrieve` response
+///
+/// Record data may be packed as XML or as an escaped string. A diagnostic of a
+/// response with no records is returned as [`SruError::Diagnostic`].
+pub fn parse_sru_response(xml: &str) -> Result<SruPage, SruError> {
+    let invalid = |e: quick_xml::Error| SruError::InvalidResponse(e.to_string());
+    let mut reader = Reader::from_str(xml);
+    let mut page = SruPage::default();
+    let mut diagnostic: Option<(String, String)> = None;
+
+    loop {
+        match reader.read_event().map_err(invalid)? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"numberOfRecords" => page.number_of_records = text(&mut reader, &e)?.parse().map_err(|_| SruError::InvalidResponse("numberOfRecords is not a number".to_string()))?,
+                b"nextRecordPosition" => page.next_record_position = text(&mut reader, &e)?.parse().ok(),
+                b"recordData" => {
+                    let span = reader.read_to_end(e.name()).map_err(invalid)?;
+                    let data = xml[span].trim();
+                    let data = if data.starts_with('<') { data.into() } else { quick_xml::escape::unescape(data).map_err(|e| SruError::InvalidResponse(e.to_string()))? };
+                    let records = parse_marc_xml(data.as_bytes(), FormatEncoding::marc_xml()).map_err(|e| SruError::InvalidResponse(e.to_string()))?;
+                    page.records.extend(records);
+                }
+                b"diagnostic" => diagnostic = Some((String::new(), String::new())),
+                name @ (b"uri" | b"message") => {
+                    let text = text(&mut reader, &e)?;
+                    if let Some((uri, message)) = &mut diagnostic {
+                        match name {
+                            b"uri" => *uri = text,
+                            _ => *message = text,
+                        }
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    match diagnostic {
+        Some((uri, message)) if page.records.is_empty() => Err(SruError::Diagnostic { uri, message }),
+        _ => Ok(page),
+    }
+}
+
+/// Text of an element, unescaped and trimmed
+fn text(reader: &mut Reader<&[u8]>, element: &BytesStart) -> Result<String, SruError> {
+    let text = reader.read_text(element.name()).map_err(|e| SruError::InvalidResponse(e.to_string()))?;
+    let text = quick_xml::escape::unescape(&text).map_err(|e| SruError::InvalidResponse(e.to_string()))?;
+    Ok(text.trim().to_string())
+}
+
+/// Iterator over the records matching a CQL query, fetching pages as needed
+///
+/// Records are requested in the `marcxml` schema with SRU 1.1 unless set
+/// otherwise. The iterator stops after the first error.
+pub struct SruSearch {
+    agent: ureq::Agent,
+    base_url: String,
+    query: String,
+    version: String,
+    record_schema: String,
+    page_size: usize,
+    position: Option<usize>,
+    pending: VecDeque<Record>,
+    number_of_records: Option<usize>,
+}
+
+impl SruSearch {
+    /// Search a server, e.g. `https://lx2.loc.gov/sru/voyager`, with a CQL query
+    pub fn new(base_url: &str, query: &str) -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new().timeout(Duration::from_secs(60)).build(),
+            base_url: base_url.to_string(),
+            query: query.to_string(),
+            version: "1.1".to_string(),
+            record_schema: "marcxml".to_string(),
+            page_size: 10,
+            position: Some(1),
+            pending: VecDeque::new(),
+            number_of_records: None,
+        }
+    }
+
+    /// SRU version to request, e.g. `1.2` or `2.0`
+    pub fn version(mut self, version: &str) -> Self {
+        self.version = version.to_string();
+        self
+    }
+
+    /// Record schema to request, which must be MARC XML, e.g. `marc21` or `unimarc`
+    pub fn record_schema(mut self, schema: &str) -> Self {
+        self.record_schema = schema.to_string();
+        self
+    }
+
+    /// Number of records requested per page
+    pub fn page_size(mut self, size: usize) -> Self {
+        self.page_size = size.max(1);
+        self
+    }
+
+    /// Position of the first record to fetch, from 1
+    pub fn start(mut self, position: usize) -> Self {
+        self.position = Some(position.max(1));
+        self
+    }
+
+    /// Number of records matching the query, once the first page is fetched
+    pub fn number_of_records(&self) -> Option<usize> {
+        self.number_of_records
+    }
+
+    fn fetch_page(&mut self, position: usize) -> Result<SruPage, SruError> {
+        let response = self
+            .agent
+            .get(&self.base_url)
+            .query("operation", "searchRetrieve")
+            .query("version", &self.version)
+            .query("query", &self.query)
+            .query("startRecord", &position.to_string())
+            .query("maximumRecords", &self.page_size.to_string())
+            .query("recordSchema", &self.record_schema)
+            .query("recordPacking", "xml")
+            .call()
+            .map_err(|e| SruError::Http(e.to_string()))?;
+        let body = response.into_string().map_err(|e| SruError::Http(e.to_string()))?;
+        parse_sru_response(&body)
+    }
+}
+
+impl Iterator for SruSearch {
+    type Item = Result<Record, SruError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending.is_empty() {
+            let position = self.position.take()?;
+            match self.fetch_page(position) {
+                Ok(page) => {
+                    // Some servers leave out nextRecordPosition before the last page
+                    let next = position + page.records.len();
+                    self.position = match page.next_record_position {
+                        _ if page.records.is_empty() => None,
+                        Some(next) => Some(next),
+                        None => Some(next).filter(|next| *next <= page.number_of_records),
+                    };
+                    self.number_of_records = Some(page.number_of_records);
+                    self.pending.extend(page.records);
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        self.pending.pop_front().map(Ok)
+    }
+}
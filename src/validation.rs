@@ -0,0 +1,301 @@
+//! Structural validation of records against the format definitions.
+//!
+//! [`Validator`] checks a record's leader, tags, indicators and subfields using
+//! the field [`definitions`](crate::fields::field_definition), the indicator
+//! tables and an optional [`LocalFieldRegistry`], and returns one [`Issue`] per
+//! problem, located with a [`MarcSpec`].
+//!
+//! ```
+//! use marc_rs::{DataField, Leader, MarcFormat, Record, Severity, Subfield, Validator};
+//!
+//! let title = DataField {
+//!     tag: "245".to_string(),
+//!     ind1: '1',
+//!     ind2: 'x',
+//!     subfields: vec![Subfield { code: 'a', value: "Title".to_string() }],
+//! };
+//! let record = Record {
+//!     leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+//!     control_fields: vec![],
+//!     data_fields: vec![title.clone(), title],
+//! };
+//!
+//! let issues = Validator::new(MarcFormat::Marc21).validate(&record);
+//! assert_eq!(issues[0].to_string(), "error 245[0]^2: invalid second indicator 'x'");
+//! assert_eq!(issues[1].to_string(), "error 245[1]: field is not repeatable");
+//! assert!(issues.iter().all(|issue| issue.severity == Severity::Error));
+//! ```
+
+use crate::fields::definitions::field_definition;
+use crate::fields::indicators::{indicators, Indicator};
+use crate::fields::local::LocalFieldRegistry;
+use crate::fields::metadata::{field_status, FieldStatus};
+use crate::format::MarcFormat;
+use crate::record::{DataField, Record};
+use crate::spec::{MarcSpec, SpecPosition, SpecRange, SpecTarget};
+use std::fmt::Display;
+
+/// How serious a validation issue is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Suspicious but usable (obsolete or undefined tags, empty values, ...)
+    Warning,
+    /// Breaks the format rules (invalid indicators, repeated non-repeatable fields, ...)
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Warning => f.pad("warning"),
+            Severity::Error => f.pad("error"),
+        }
+    }
+}
+
+/// A problem found in a record
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    pub severity: Severity,
+    /// Where the problem is: `LDR/10`, `245[1]`, `245[0]^2`, `650[0]$x`, ...
+    ///
+    /// Field occurrences are counted per tag, from 0.
+    pub location: MarcSpec,
+    pub message: String,
+}
+
+impl Issue {
+    fn error(location: MarcSpec, message: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            location,
+            message,
+        }
+    }
+
+    fn warning(location: MarcSpec, message: String) -> Self {
+        Self {
+            severity: Severity::Warning,
+            location,
+            message,
+        }
+    }
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}: {}", self.severity, self.location, self.message)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Severity {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Issue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("Issue", 4)?;
+        state.serialize_field("severity", &self.severity)?;
+        state.serialize_field("tag", &self.location.tag)?;
+        state.serialize_field("location", &self.location.to_string())?;
+        state.serialize_field("message", &self.message)?;
+        state.end()
+    }
+}
+
+/// Checks records against the definitions of a format
+///
+/// Reported problems:
+///
+/// - errors: leader counts and entry map that are not the ISO 2709 values,
+///   malformed tags, control and data field tags swapped, repeated
+///   non-repeatable fields or subfields, invalid indicators, data fields
+///   without subfields, invalid subfield codes, MARC21 008 of the wrong length
+/// - warnings: obsolete or undefined tags, undefined subfield codes, empty values
+///
+/// Local tags (e.g. 9XX) are only checked when registered with
+/// [`local_fields`](Validator::local_fields).
+#[derive(Debug, Clone)]
+pub struct Validator {
+    format: MarcFormat,
+    local_fields: LocalFieldRegistry,
+}
+
+impl Validator {
+    /// Create a validator for the given format
+    pub fn new(format: MarcFormat) -> Self {
+        Self {
+            format,
+            local_fields: LocalFieldRegistry::new(),
+        }
+    }
+
+    /// Check local fields against these definitions
+    pub fn local_fields(mut self, registry: LocalFieldRegistry) -> Self {
+        self.local_fields = registry;
+        self
+    }
+
+    /// Format the records are checked against
+    pub fn format(&self) -> MarcFormat {
+        self.format
+    }
+
+    /// All the problems of a record, leader first then fields in record order
+    pub fn validate(&self, record: &Record) -> Vec<Issue> {
+        let mut issues = Vec::new();
+        self.validate_leader(record, &mut issues);
+
+        for (i, field) in record.control_fields.iter().enumerate() {
+            let occurrence = record.control_fields[..i].iter().filter(|f| f.tag == field.tag).count();
+            let location = field_spec(&field.tag, occurrence, SpecTarget::Field);
+            if !is_control_tag(&field.tag) {
+                issues.push(Issue::error(location.clone(), format!("{} is not a control field tag", field.tag)));
+            }
+            self.validate_tag(&field.tag, occurrence, &location, &mut issues);
+            if field.value.is_empty() {
+                issues.push(Issue::warning(location, "empty control field".to_string()));
+            } else if field.tag == "008" && matches!(self.format, MarcFormat::Marc21 | MarcFormat::MarcXml) && field.value.chars().count() != 40 {
+                issues.push(Issue::error(location, format!("expected 40 characters, found {}", field.value.chars().count())));
+            }
+        }
+
+        for (i, field) in record.data_fields.iter().enumerate() {
+            let occurrence = record.data_fields[..i].iter().filter(|f| f.tag == field.tag).count();
+            self.validate_data_field(field, occurrence, &mut issues);
+        }
+
+        issues
+    }
+
+    fn validate_leader(&self, record: &Record, issues: &mut Vec<Issue>) {
+        let leader = &record.leader;
+        if let Err(e) = leader.validate() {
+            issues.push(Issue::error(leader_spec(None), e));
+        }
+
+        let counts = [
+            (10, "indicator count", leader.indicator_count, 2),
+            (11, "subfield code count", leader.subfield_code_count, 2),
+            (20, "length of the length-of-field portion", leader.length_of_length_of_field_portion, 4),
+            (21, "length of the starting-character-position portion", leader.length_of_starting_character_position_portion, 5),
+            (22, "length of the implementation-defined portion", leader.length_of_implementation_defined_portion, 0),
+        ];
+        for (position, name, value, expected) in counts {
+            if value != expected {
+                issues.push(Issue::error(leader_spec(Some(position)), format!("{} must be {}, found {}", name, expected, value)));
+            }
+        }
+    }
+
+    fn validate_tag(&self, tag: &str, occurrence: usize, location: &MarcSpec, issues: &mut Vec<Issue>) {
+        let status = field_status(tag, self.format);
+        let definition = field_definition(tag, self.format);
+
+        if occurrence == 1 && !self.local_fields.is_repeatable(tag, self.format) {
+            issues.push(Issue::error(location.clone(), "field is not repeatable".to_string()));
+        }
+        match status {
+            FieldStatus::Obsolete => issues.push(Issue::warning(location.clone(), "obsolete field".to_string())),
+            FieldStatus::Current if definition.is_none() && occurrence == 0 => issues.push(Issue::warning(location.clone(), "undefined field".to_string())),
+            _ => {}
+        }
+    }
+
+    fn validate_data_field(&self, field: &DataField, occurrence: usize, issues: &mut Vec<Issue>) {
+        let location = field_spec(&field.tag, occurrence, SpecTarget::Field);
+        if field.tag.len() != 3 || !field.tag.bytes().all(|b| b.is_ascii_alphanumeric()) {
+            issues.push(Issue::error(location, format!("invalid tag {:?}", field.tag)));
+            return;
+        }
+        if is_control_tag(&field.tag) {
+            issues.push(Issue::error(location.clone(), format!("{} is a control field tag", field.tag)));
+        }
+        self.validate_tag(&field.tag, occurrence, &location, issues);
+
+        if let Some(definitions) = indicators(&field.tag, self.format) {
+            let positions: [(u8, &str, &Indicator, char); 2] = [(1, "first", &definitions.first, field.ind1), (2, "second", &definitions.second, field.ind2)];
+            for (position, name, definition, value) in positions {
+                if !definition.is_valid(value) {
+                    issues.push(Issue::error(field_spec(&field.tag, occurrence, SpecTarget::Indicator(position)), format!("invalid {} indicator {:?}", name, value)));
+                }
+            }
+        }
+
+        if field.subfields.is_empty() {
+            issues.push(Issue::error(location, "field has no subfields".to_string()));
+            return;
+        }
+
+        let local = self.local_fields.get(&field.tag);
+        let definition = field_definition(&field.tag, self.format).filter(|d| !d.subfields.is_empty());
+        for (i, subfield) in field.subfields.iter().enumerate() {
+            let location = field_spec(&field.tag, occurrence, SpecTarget::Subfields(vec![subfield.code]));
+            if !subfield.code.is_ascii_lowercase() && !subfield.code.is_ascii_digit() {
+                issues.push(Issue::error(location, format!("invalid subfield code {:?}", subfield.code)));
+                continue;
+            }
+
+            let repeatable = match (local, definition) {
+                (Some(local), _) => local.get_subfield(subfield.code).map(|s| s.repeatable),
+                (None, Some(definition)) => definition.subfield(subfield.code).map(|s| s.repeatable),
+                (None, None) => Some(true),
+            };
+            let repeated = field.subfields[..i].iter().filter(|s| s.code == subfield.code).count() == 1;
+            match repeatable {
+                None if !field.subfields[..i].iter().any(|s| s.code == subfield.code) => issues.push(Issue::warning(location.clone(), "undefined subfield".to_string())),
+                Some(false) if repeated => issues.push(Issue::error(location.clone(), "subfield is not repeatable".to_string())),
+                _ => {}
+            }
+            if subfield.value.is_empty() {
+                issues.push(Issue::warning(location, "empty subfield".to_string()));
+            }
+        }
+    }
+}
+
+/// Check a record against the definitions of a format, without local field definitions
+pub fn validate_record(record: &Record, format: MarcFormat) -> Vec<Issue> {
+    Validator::new(format).validate(record)
+}
+
+/// Tags 001 to 009 (and 00X) hold control fields
+fn is_control_tag(tag: &str) -> bool {
+    tag.len() == 3 && tag.starts_with("00")
+}
+
+fn leader_spec(position: Option<usize>) -> MarcSpec {
+    MarcSpec {
+        tag: "LDR".to_string(),
+        index: None,
+        target: SpecTarget::Field,
+        positions: position.map(|p| SpecRange {
+            start: SpecPosition::At(p),
+            end: None,
+        }),
+    }
+}
+
+pub(crate) fn field_spec(tag: &str, occurrence: usize, target: SpecTarget) -> MarcSpec {
+    MarcSpec {
+        tag: tag.to_string(),
+        index: Some(SpecRange {
+            start: SpecPosition::At(occurrence),
+            end: None,
+        }),
+        target,
+        positions: None,
+    }
+}
@@ -1,6 +1,6 @@
 use crate::encoding::convert_from_encoding;
 use crate::format::{FormatEncoding, MarcFormat};
-use crate::record::Record;
+use crate::record::{Record, MAX_LEADER_NUMBER};
 use std::io::Write;
 
 /// Write error type
@@ -25,12 +25,77 @@ impl std::fmt::Display for WriteError {
 
 impl std::error::Error for WriteError {}
 
+impl WriteError {
+    /// Stable identifier of the error variant (`io_error`, `invalid_record`, ...)
+    pub fn kind(&self) -> &'static str {
+        match self {
+            WriteError::IoError(_) => "io_error",
+            WriteError::InvalidRecord(_) => "invalid_record",
+            WriteError::InvalidEncoding(_) => "invalid_encoding",
+            WriteError::Other(_) => "other",
+        }
+    }
+
+    /// Detail message of the error
+    pub fn message(&self) -> &str {
+        match self {
+            WriteError::IoError(msg) | WriteError::InvalidRecord(msg) | WriteError::InvalidEncoding(msg) | WriteError::Other(msg) => msg,
+        }
+    }
+
+    /// Build an error from its [`kind`](Self::kind) and message
+    pub fn from_kind(kind: &str, message: String) -> Option<Self> {
+        match kind {
+            "io_error" => Some(WriteError::IoError(message)),
+            "invalid_record" => Some(WriteError::InvalidRecord(message)),
+            "invalid_encoding" => Some(WriteError::InvalidEncoding(message)),
+            "other" => Some(WriteError::Other(message)),
+            _ => None,
+        }
+    }
+}
+
+/// Serialized as `{"kind": "invalid_record", "message": "..."}`
+#[cfg(feature = "serde")]
+impl serde::Serialize for WriteError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        crate::helpers::serialize_error(None, self.kind(), Some(self.message()), &self.to_string(), serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for WriteError {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let (kind, message) = crate::helpers::deserialize_error(deserializer)?;
+        WriteError::from_kind(&kind, message.unwrap_or_default())
+            .ok_or_else(|| serde::de::Error::unknown_variant(&kind, &["io_error", "invalid_record", "invalid_encoding", "other"]))
+    }
+}
+
 impl From<std::io::Error> for WriteError {
     fn from(e: std::io::Error) -> Self {
         WriteError::IoError(e.to_string())
     }
 }
 
+/// Errors of the underlying writer stay I/O errors, so a closed output stops the caller
+#[cfg(feature = "serde")]
+impl From<serde_json::Error> for WriteError {
+    fn from(e: serde_json::Error) -> Self {
+        if e.is_io() {
+            WriteError::IoError(e.to_string())
+        } else {
+            WriteError::Other(format!("JSON error: {}", e))
+        }
+    }
+}
+
 impl From<quick_xml::Error> for WriteError {
     fn from(e: quick_xml::Error) -> Self {
         WriteError::Other(format!("XML error: {}", e))
@@ -38,7 +103,13 @@ impl From<quick_xml::Error> for WriteError {
 }
 
 /// Write MARC records to output
-pub fn write(records: &[Record], format_encoding: FormatEncoding, output: &mut dyn Write) -> Result<(), WriteError> {
+///
+/// Accepts any iterator of record references (`&[Record]`, `&Vec<Record>`,
+/// `std::iter::once(&record)`...), so records are never copied.
+pub fn write<'a, I>(records: I, format_encoding: FormatEncoding, output: &mut dyn Write) -> Result<(), WriteError>
+where
+    I: IntoIterator<Item = &'a Record>,
+{
     match format_encoding.format {
         MarcFormat::Marc21 => write_marc21_binary(records, format_encoding, output),
         MarcFormat::Unimarc => write_unimarc_binary(records, format_encoding, output),
@@ -48,11 +119,14 @@ pub fn write(records: &[Record], format_encoding: FormatEncoding, output: &mut d
 
 /// Write a single record (convenience function)
 pub fn write_one(record: &Record, format_encoding: FormatEncoding, output: &mut dyn Write) -> Result<(), WriteError> {
-    write(&[record.clone()], format_encoding, output)
+    write(std::iter::once(record), format_encoding, output)
 }
 
 /// Write MARC21 binary format
-pub fn write_marc21_binary(records: &[Record], format_encoding: FormatEncoding, output: &mut dyn Write) -> Result<(), WriteError> {
+pub fn write_marc21_binary<'a, I>(records: I, format_encoding: FormatEncoding, output: &mut dyn Write) -> Result<(), WriteError>
+where
+    I: IntoIterator<Item = &'a Record>,
+{
     for record in records {
         write_single_marc21_binary(record, format_encoding, output)?;
     }
@@ -67,33 +141,30 @@ fn write_single_marc21_binary(record: &Record, format_encoding: FormatEncoding,
 
     // Write control fields
     for field in &record.control_fields {
-        let value_bytes = convert_from_encoding(&field.value, format_encoding.encoding).map_err(|e| WriteError::InvalidEncoding(e))?;
+        let value_bytes = convert_from_encoding(&field.value, format_encoding.encoding).map_err(WriteError::InvalidEncoding)?;
         let start = data_area.len();
         data_area.extend_from_slice(&value_bytes);
         data_area.push(0x1E); // Field terminator
 
-        directory_entries.push((field.tag.clone(), start, value_bytes.len() + 1));
+        directory_entries.push((field.tag.as_str(), start, value_bytes.len() + 1));
     }
 
     // Write data fields
     for field in &record.data_fields {
-        let mut field_data = Vec::new();
-        field_data.push(field.ind1 as u8);
-        field_data.push(field.ind2 as u8);
+        let start = data_area.len();
+        data_area.push(field.ind1 as u8);
+        data_area.push(field.ind2 as u8);
 
         for subfield in &field.subfields {
-            field_data.push(0x1F); // Subfield delimiter
-            field_data.push(subfield.code as u8);
-            let value_bytes = convert_from_encoding(&subfield.value, format_encoding.encoding).map_err(|e| WriteError::InvalidEncoding(e))?;
-            field_data.extend_from_slice(&value_bytes);
+            data_area.push(0x1F); // Subfield delimiter
+            data_area.push(subfield.code as u8);
+            let value_bytes = convert_from_encoding(&subfield.value, format_encoding.encoding).map_err(WriteError::InvalidEncoding)?;
+            data_area.extend_from_slice(&value_bytes);
         }
 
-        field_data.push(0x1E); // Field terminator
-
-        let start = data_area.len();
-        data_area.extend_from_slice(&field_data);
+        data_area.push(0x1E); // Field terminator
 
-        directory_entries.push((field.tag.clone(), start, field_data.len()));
+        directory_entries.push((field.tag.as_str(), start, data_area.len() - start));
     }
 
     data_area.push(0x1D); // Record terminator
@@ -105,17 +176,26 @@ fn write_single_marc21_binary(record: &Record, format_encoding: FormatEncoding,
         if tag_bytes.len() != 3 {
             return Err(WriteError::InvalidRecord(format!("Invalid tag length: {}", tag)));
         }
+        if *length > 9999 {
+            return Err(WriteError::InvalidRecord(format!("Field {} of {} bytes exceeds 9999", tag, length)));
+        }
         directory.extend_from_slice(tag_bytes);
         directory.extend_from_slice(format!("{:04}{:05}", length, start).as_bytes());
     }
+    directory.push(0x1E); // Directory terminator
 
     // Calculate base address
     let base_address = 24 + directory.len();
 
+    let record_length = base_address + data_area.len();
+    if record_length > MAX_LEADER_NUMBER as usize {
+        return Err(WriteError::InvalidRecord(format!("Record of {} bytes exceeds {}", record_length, MAX_LEADER_NUMBER)));
+    }
+
     // Update leader
     let mut leader = record.leader.clone();
-    leader.base_address_of_data = base_address as u16;
-    leader.record_length = (base_address + data_area.len()) as u16;
+    leader.base_address_of_data = base_address as u32;
+    leader.record_length = record_length as u32;
 
     // Write leader
     let leader_bytes = leader.to_bytes();
@@ -131,14 +211,22 @@ fn write_single_marc21_binary(record: &Record, format_encoding: FormatEncoding,
 }
 
 /// Write UNIMARC binary format
-pub fn write_unimarc_binary(records: &[Record], format_encoding: FormatEncoding, output: &mut dyn Write) -> Result<(), WriteError> {
+pub fn write_unimarc_binary<'a, I>(records: I, format_encoding: FormatEncoding, output: &mut dyn Write) -> Result<(), WriteError>
+where
+    I: IntoIterator<Item = &'a Record>,
+{
     // UNIMARC uses the same binary structure as MARC21
     write_marc21_binary(records, format_encoding, output)
 }
 
 /// Write MARC XML format
-pub fn write_marc_xml(records: &[Record], _format_encoding: FormatEncoding, output: &mut dyn Write) -> Result<(), WriteError> {
-    use quick_xml::events::{BytesEnd, BytesStart, Event};
+///
+/// More than one record is wrapped in a `<collection>` element.
+pub fn write_marc_xml<'a, I>(records: I, _format_encoding: FormatEncoding, output: &mut dyn Write) -> Result<(), WriteError>
+where
+    I: IntoIterator<Item = &'a Record>,
+{
+    use quick_xml::events::{BytesEnd, Event};
     use quick_xml::Writer;
 
     let mut writer = Writer::new(output);
@@ -146,61 +234,143 @@ pub fn write_marc_xml(records: &[Record], _format_encoding: FormatEncoding, outp
     // Write XML declaration
     writer.write_event(Event::Decl(quick_xml::events::BytesDecl::new("1.0", Some("UTF-8"), None)))?;
 
-    if records.len() > 1 {
-        // Write collection wrapper
-        let mut collection_start = BytesStart::new("collection");
-        collection_start.push_attribute(("xmlns", "http://www.loc.gov/MARC21/slim"));
-        writer.write_event(Event::Start(collection_start))?;
+    let mut records = records.into_iter().peekable();
+    let first = records.next();
+    let is_collection = records.peek().is_some();
+
+    if is_collection {
+        write_xml_collection_start(&mut writer)?;
     }
 
-    for record in records {
-        // Write record
-        let mut record_start = BytesStart::new("record");
-        record_start.push_attribute(("xmlns", "http://www.loc.gov/MARC21/slim"));
-        writer.write_event(Event::Start(record_start))?;
-
-        // Write leader
-        let leader_bytes = record.leader.to_bytes();
-        let leader_str = std::str::from_utf8(&leader_bytes).map_err(|e| WriteError::Other(format!("Invalid leader UTF-8: {}", e)))?;
-        let leader_start = BytesStart::new("leader");
-        writer.write_event(Event::Start(leader_start))?;
-        writer.write_event(Event::Text(quick_xml::events::BytesText::from_escaped(leader_str)))?;
-        writer.write_event(Event::End(BytesEnd::new("leader")))?;
-
-        // Write control fields
-        for field in &record.control_fields {
-            let mut field_start = BytesStart::new("controlfield");
-            field_start.push_attribute(("tag", field.tag.as_str()));
-            writer.write_event(Event::Start(field_start.clone()))?;
-            writer.write_event(Event::Text(quick_xml::events::BytesText::from_escaped(&field.value)))?;
-            writer.write_event(Event::End(BytesEnd::new("controlfield")))?;
+    for record in first.into_iter().chain(records) {
+        write_xml_record(&mut writer, record)?;
+    }
+
+    if is_collection {
+        writer.write_event(Event::End(BytesEnd::new("collection")))?;
+    }
+
+    Ok(())
+}
+
+fn write_xml_collection_start<W: Write>(writer: &mut quick_xml::Writer<W>) -> Result<(), WriteError> {
+    use quick_xml::events::{BytesStart, Event};
+
+    let mut collection_start = BytesStart::new("collection");
+    collection_start.push_attribute(("xmlns", "http://www.loc.gov/MARC21/slim"));
+    writer.write_event(Event::Start(collection_start))?;
+    Ok(())
+}
+
+/// Write a single `<record>` element
+fn write_xml_record<W: Write>(writer: &mut quick_xml::Writer<W>, record: &Record) -> Result<(), WriteError> {
+    use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+
+    let mut record_start = BytesStart::new("record");
+    record_start.push_attribute(("xmlns", "http://www.loc.gov/MARC21/slim"));
+    writer.write_event(Event::Start(record_start))?;
+
+    // Write leader
+    let leader_bytes = record.leader.to_bytes();
+    let leader_str = std::str::from_utf8(&leader_bytes).map_err(|e| WriteError::Other(format!("Invalid leader UTF-8: {}", e)))?;
+    writer.write_event(Event::Start(BytesStart::new("leader")))?;
+    writer.write_event(Event::Text(BytesText::new(leader_str)))?;
+    writer.write_event(Event::End(BytesEnd::new("leader")))?;
+
+    // Write control fields
+    for field in &record.control_fields {
+        let mut field_start = BytesStart::new("controlfield");
+        field_start.push_attribute(("tag", field.tag.as_str()));
+        writer.write_event(Event::Start(field_start))?;
+        writer.write_event(Event::Text(BytesText::new(&field.value)))?;
+        writer.write_event(Event::End(BytesEnd::new("controlfield")))?;
+    }
+
+    // Write data fields
+    for field in &record.data_fields {
+        let mut field_start = BytesStart::new("datafield");
+        field_start.push_attribute(("tag", field.tag.as_str()));
+        field_start.push_attribute(("ind1", field.ind1.to_string().as_str()));
+        field_start.push_attribute(("ind2", field.ind2.to_string().as_str()));
+        writer.write_event(Event::Start(field_start))?;
+
+        for subfield in &field.subfields {
+            let mut subfield_start = BytesStart::new("subfield");
+            subfield_start.push_attribute(("code", subfield.code.to_string().as_str()));
+            writer.write_event(Event::Start(subfield_start))?;
+            writer.write_event(Event::Text(BytesText::new(&subfield.value)))?;
+            writer.write_event(Event::End(BytesEnd::new("subfield")))?;
         }
 
-        // Write data fields
-        for field in &record.data_fields {
-            let mut field_start = BytesStart::new("datafield");
-            field_start.push_attribute(("tag", field.tag.as_str()));
-            field_start.push_attribute(("ind1", field.ind1.to_string().as_str()));
-            field_start.push_attribute(("ind2", field.ind2.to_string().as_str()));
-            writer.write_event(Event::Start(field_start))?;
-
-            for subfield in &field.subfields {
-                let mut subfield_start = BytesStart::new("subfield");
-                subfield_start.push_attribute(("code", subfield.code.to_string().as_str()));
-                writer.write_event(Event::Start(subfield_start.clone()))?;
-                writer.write_event(Event::Text(quick_xml::events::BytesText::from_escaped(&subfield.value)))?;
-                writer.write_event(Event::End(BytesEnd::new("subfield")))?;
-            }
+        writer.write_event(Event::End(BytesEnd::new("datafield")))?;
+    }
 
-            writer.write_event(Event::End(BytesEnd::new("datafield")))?;
+    writer.write_event(Event::End(BytesEnd::new("record")))?;
+    Ok(())
+}
+
+/// Writer emitting records one at a time, for output streams of unknown length
+///
+/// Binary records are written as they come. MARC XML output is always wrapped in
+/// a `<collection>` element, closed by [`RecordWriter::finish`].
+pub struct RecordWriter<W: Write> {
+    output: W,
+    format_encoding: FormatEncoding,
+    count: usize,
+}
+
+impl<W: Write> RecordWriter<W> {
+    /// Create a writer for the given format and encoding
+    pub fn new(output: W, format_encoding: FormatEncoding) -> Self {
+        Self {
+            output,
+            format_encoding,
+            count: 0,
         }
+    }
 
-        writer.write_event(Event::End(BytesEnd::new("record")))?;
+    /// Number of records written so far
+    pub fn count(&self) -> usize {
+        self.count
     }
 
-    if records.len() > 1 {
-        writer.write_event(Event::End(BytesEnd::new("collection")))?;
+    /// Format and encoding of the output
+    pub fn format_encoding(&self) -> FormatEncoding {
+        self.format_encoding
     }
 
-    Ok(())
+    /// Write one record
+    pub fn write(&mut self, record: &Record) -> Result<(), WriteError> {
+        match self.format_encoding.format {
+            MarcFormat::Marc21 | MarcFormat::Unimarc => write_single_marc21_binary(record, self.format_encoding, &mut self.output)?,
+            MarcFormat::MarcXml => {
+                if self.count == 0 {
+                    self.start_collection()?;
+                }
+                write_xml_record(&mut quick_xml::Writer::new(&mut self.output), record)?;
+            }
+        }
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Close the output (the `<collection>` element for MARC XML) and flush it
+    pub fn finish(mut self) -> Result<W, WriteError> {
+        if self.format_encoding.format == MarcFormat::MarcXml {
+            if self.count == 0 {
+                self.start_collection()?;
+            }
+            self.output.write_all(b"</collection>\n")?;
+        }
+        self.output.flush()?;
+        Ok(self.output)
+    }
+
+    fn start_collection(&mut self) -> Result<(), WriteError> {
+        use quick_xml::events::{BytesDecl, Event};
+
+        let mut writer = quick_xml::Writer::new(&mut self.output);
+        writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+        write_xml_collection_start(&mut writer)
+    }
 }
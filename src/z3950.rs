@@ -0,0 +1,523 @@
+//! Z39.50 search and retrieval (`z3950` feature).
+//!
+//! A [`Z3950Client`] opens an association with a Z39.50 target, searches a
+//! database with a query in prefix query format (PQF, as in `yaz-client`) and
+//! presents the matching records in USMARC or UNIMARC syntax. Only the init,
+//! search, present and close services are implemented, over plain TCP, with
+//! the BER encoding of the Z39.50-1995 protocol data units.
+//!
+//! ```no_run
+//! use marc_rs::Z3950Client;
+//!
+//! let mut client = Z3950Client::connect("lx2.loc.gov:210", "LCDB").unwrap();
+//! let hits = client.search("@attr 1=7 9780261103252").unwrap();
+//! for record in client.present(1, hits.min(5)).unwrap() {
+//!     println!("{:?}", record.join("245", &['a', 'b'], " "));
+//! }
+//! ```
+
+use crate::format::{Encoding, FormatEncoding, MarcFormat};
+use crate::parser::{parse_marc21_binary, parse_unimarc_binary};
+use crate::record::Record;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+/// Z39.50 error type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Z3950Error {
+    /// The connection failed or timed out
+    Io(String),
+    /// The target refused the association or failed the search without a diagnostic
+    Failed(String),
+    /// The query is not valid PQF, or uses features not supported here
+    Query(String),
+    /// The target answered with a diagnostic, e.g. Bib-1 condition 114 for an unsupported use attribute
+    Diagnostic { condition: i64, addinfo: String },
+    /// The target closed the association
+    Closed(String),
+    /// The response is not a valid protocol data unit, or not the one expected
+    InvalidResponse(String),
+}
+
+impl std::fmt::Display for Z3950Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Z3950Error::Io(msg) => write!(f, "Z39.50 connection error: {}", msg),
+            Z3950Error::Failed(msg) => write!(f, "Z39.50 request failed: {}", msg),
+            Z3950Error::Query(msg) => write!(f, "Invalid PQF query: {}", msg),
+            Z3950Error::Diagnostic { condition, addinfo } if addinfo.is_empty() => write!(f, "Z39.50 diagnostic {}", condition),
+            Z3950Error::Diagnostic { condition, addinfo } => write!(f, "Z39.50 diagnostic {}: {}", condition, addinfo),
+            Z3950Error::Closed(msg) => write!(f, "Z39.50 association closed by the target: {}", msg),
+            Z3950Error::InvalidResponse(msg) => write!(f, "Invalid Z39.50 response: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for Z3950Error {}
+
+impl From<std::io::Error> for Z3950Error {
+    fn from(e: std::io::Error) -> Self {
+        Z3950Error::Io(e.to_string())
+    }
+}
+
+/// Syntax in which records are requested
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordSyntax {
+    /// MARC21, decoded as UTF-8 or MARC-8 from leader/09
+    Usmarc,
+    /// UNIMARC, decoded as UTF-8
+    Unimarc,
+}
+
+impl RecordSyntax {
+    /// Object identifier of the syntax
+    fn oid(&self) -> &'static [u32] {
+        match self {
+            RecordSyntax::Usmarc => &[1, 2, 840, 10003, 5, 10],
+            RecordSyntax::Unimarc => &[1, 2, 840, 10003, 5, 1],
+        }
+    }
+
+    /// Get the syntax from its name, e.g. `usmarc`, `marc21` or `unimarc`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "usmarc" | "marc21" => Some(RecordSyntax::Usmarc),
+            "unimarc" => Some(RecordSyntax::Unimarc),
+            _ => None,
+        }
+    }
+}
+
+const BIB1: &[u32] = &[1, 2, 840, 10003, 3, 1];
+const RESULT_SET: &str = "default";
+
+/// A Z39.50 association with one database of a target
+///
+/// Searches create the result set `default`, replacing the previous one;
+/// records are presented with the full (`F`) element set.
+pub struct Z3950Client {
+    stream: TcpStream,
+    database: String,
+    syntax: RecordSyntax,
+}
+
+impl Z3950Client {
+    /// Connect to a target, e.g. `lx2.loc.gov:210`, and initialize an association to search a database
+    pub fn connect(address: &str, database: &str) -> Result<Self, Z3950Error> {
+        Self::open(address, database, None)
+    }
+
+    /// Connect to a target that requires a user and password
+    pub fn connect_with_credentials(address: &str, database: &str, user: &str, password: &str) -> Result<Self, Z3950Error> {
+        Self::open(address, database, Some((user, password)))
+    }
+
+    fn open(address: &str, database: &str, credentials: Option<(&str, &str)>) -> Result<Self, Z3950Error> {
+        let stream = TcpStream::connect(address)?;
+        stream.set_read_timeout(Some(Duration::from_secs(60)))?;
+        stream.set_write_timeout(Some(Duration::from_secs(60)))?;
+        let mut client = Self {
+            stream,
+            database: database.to_string(),
+            syntax: RecordSyntax::Usmarc,
+        };
+
+        let mut request = Vec::new();
+        // Versions 1 to 3; search and present services
+        request.extend(tlv(CONTEXT, 3, &[0x05, 0xE0]));
+        request.extend(tlv(CONTEXT, 4, &[0x06, 0xC0]));
+        request.extend(tlv(CONTEXT, 5, &integer(1 << 20)));
+        request.extend(tlv(CONTEXT, 6, &integer(1 << 20)));
+        if let Some((user, password)) = credentials {
+            let id_pass = [tlv(CONTEXT, 1, user.as_bytes()), tlv(CONTEXT, 2, password.as_bytes())].concat();
+            request.extend(tlv(CONTEXT | CONSTRUCTED, 7, &tlv(UNIVERSAL | CONSTRUCTED, 16, &id_pass)));
+        }
+        request.extend(tlv(CONTEXT, 110, b"marc-rs"));
+        request.extend(tlv(CONTEXT, 111, b"marc-rs"));
+        request.extend(tlv(CONTEXT, 112, env!("CARGO_PKG_VERSION").as_bytes()));
+
+        let response = client.exchange(20, &request, 21)?;
+        match find(&response, CONTEXT, 12) {
+            Some(result) if result.content.first().is_some_and(|b| *b != 0) => Ok(client),
+            _ => Err(Z3950Error::Failed("the target refused the initialization".to_string())),
+        }
+    }
+
+    /// Syntax of the records to present, USMARC unless set otherwise
+    pub fn set_record_syntax(&mut self, syntax: RecordSyntax) {
+        self.syntax = syntax;
+    }
+
+    /// Search the database with a PQF query and return the number of hits
+    ///
+    /// Supported PQF: terms (quoted or not) with numeric `@attr` type=value
+    /// pairs, and the `@and`, `@or` and `@not` operators, all in the Bib-1
+    /// attribute set, e.g. `@and @attr 1=1003 tolkien @attr 1=4 hobbit`.
+    pub fn search(&mut self, query: &str) -> Result<usize, Z3950Error> {
+        let rpn = parse_pqf(query)?;
+        let query = tlv(CONTEXT | CONSTRUCTED, 1, &[tlv(UNIVERSAL, 6, &oid(BIB1)), rpn].concat());
+
+        let mut request = Vec::new();
+        // Present no records with the search response
+        request.extend(tlv(CONTEXT, 13, &integer(0)));
+        request.extend(tlv(CONTEXT, 14, &integer(1)));
+        request.extend(tlv(CONTEXT, 15, &integer(0)));
+        request.extend(tlv(CONTEXT, 16, &[0xFF]));
+        request.extend(tlv(CONTEXT, 17, RESULT_SET.as_bytes()));
+        request.extend(tlv(CONTEXT | CONSTRUCTED, 18, &tlv(CONTEXT, 105, self.database.as_bytes())));
+        request.extend(tlv(CONTEXT, 104, &oid(self.syntax.oid())));
+        request.extend(tlv(CONTEXT | CONSTRUCTED, 21, &query));
+
+        let response = self.exchange(22, &request, 23)?;
+        let status = find(&response, CONTEXT, 22).is_some_and(|status| status.content.first().is_some_and(|b| *b != 0));
+        if !status {
+            return Err(diagnostic(&response)?.unwrap_or_else(|| Z3950Error::Failed("the search failed".to_string())));
+        }
+        let count = find(&response, CONTEXT, 23).ok_or_else(|| Z3950Error::InvalidResponse("no result count".to_string()))?;
+        usize::try_from(decode_integer(count.content)).map_err(|_| Z3950Error::InvalidResponse("negative result count".to_string()))
+    }
+
+    /// Present `count` records of the last search, from position `start` (from 1)
+    ///
+    /// Records the target cannot deliver, reported with surrogate diagnostics,
+    /// are left out.
+    pub fn present(&mut self, start: usize, count: usize) -> Result<Vec<Record>, Z3950Error> {
+        let mut request = Vec::new();
+        request.extend(tlv(CONTEXT, 31, RESULT_SET.as_bytes()));
+        request.extend(tlv(CONTEXT, 30, &integer(start as i64)));
+        request.extend(tlv(CONTEXT, 29, &integer(count as i64)));
+        request.extend(tlv(CONTEXT | CONSTRUCTED, 19, &tlv(CONTEXT, 0, b"F")));
+        request.extend(tlv(CONTEXT, 104, &oid(self.syntax.oid())));
+
+        let response = self.exchange(24, &request, 25)?;
+        if let Some(error) = diagnostic(&response)? {
+            return Err(error);
+        }
+        let mut records = Vec::new();
+        let Some(list) = find(&response, CONTEXT | CONSTRUCTED, 28) else { return Ok(records) };
+        for name_plus_record in children(list.content)? {
+            let Some(record) = find(name_plus_record.content, CONTEXT | CONSTRUCTED, 1) else { continue };
+            // Surrogate diagnostics are [2]
+            let Some(external) = find(record.content, CONTEXT | CONSTRUCTED, 1) else { continue };
+            let data = match children(external.content)?.into_iter().find(|e| e.class == CONTEXT && e.number == 1) {
+                Some(octets) if !octets.constructed => octets.content.to_vec(),
+                // Octets may be sent as segments
+                Some(segments) => children(segments.content)?.iter().flat_map(|s| s.content.iter().copied()).collect(),
+                None => return Err(Z3950Error::InvalidResponse("record is not octet-aligned".to_string())),
+            };
+            records.extend(self.decode(&data)?);
+        }
+        Ok(records)
+    }
+
+    /// Close the association
+    pub fn close(mut self) -> Result<(), Z3950Error> {
+        let request = tlv(CONTEXT, 211, &integer(0));
+        self.stream.write_all(&tlv(CONTEXT | CONSTRUCTED, 48, &request))?;
+        Ok(())
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<Record>, Z3950Error> {
+        let format_encoding = match self.syntax {
+            RecordSyntax::Usmarc if data.get(9) == Some(&b'a') => FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8),
+            RecordSyntax::Usmarc => FormatEncoding::marc21_default(),
+            RecordSyntax::Unimarc => FormatEncoding::unimarc_default(),
+        };
+        let records = match self.syntax {
+            RecordSyntax::Usmarc => parse_marc21_binary(data, format_encoding),
+            RecordSyntax::Unimarc => parse_unimarc_binary(data, format_encoding),
+        };
+        records.map_err(|e| Z3950Error::InvalidResponse(e.to_string()))
+    }
+
+    /// Send a request PDU and read the response PDU with the expected tag
+    fn exchange(&mut self, request_tag: u32, content: &[u8], response_tag: u32) -> Result<Vec<u8>, Z3950Error> {
+        self.stream.write_all(&tlv(CONTEXT | CONSTRUCTED, request_tag, content))?;
+        let pdu = read_pdu(&mut self.stream)?;
+        let (response, _) = read_tlv(&pdu)?;
+        match response.number {
+            number if number == response_tag => Ok(response.content.to_vec()),
+            48 => {
+                let message = find(response.content, CONTEXT, 3).map(|m| String::from_utf8_lossy(m.content).into_owned());
+                let reason = find(response.content, CONTEXT, 211).map_or(0, |r| decode_integer(r.content));
+                Err(Z3950Error::Closed(message.unwrap_or_else(|| format!("reason {}", reason))))
+            }
+            number => Err(Z3950Error::InvalidResponse(format!("unexpected PDU [{}]", number))),
+        }
+    }
+}
+
+/// Non-surrogate diagnostic of a search or present response
+fn diagnostic(response: &[u8]) -> Result<Option<Z3950Error>, Z3950Error> {
+    let format = match (find(response, CONTEXT | CONSTRUCTED, 130), find(response, CONTEXT | CONSTRUCTED, 205)) {
+        (Some(single), _) => single.content.to_vec(),
+        (None, Some(multiple)) => match children(multiple.content)?.first() {
+            Some(first) => first.content.to_vec(),
+            None => return Ok(None),
+        },
+        (None, None) => return Ok(None),
+    };
+    let elements = children(&format)?;
+    let condition = elements.iter().find(|e| e.class == UNIVERSAL && e.number == 2).map_or(0, |e| decode_integer(e.content));
+    // VisibleString in version 2, GeneralString in version 3
+    let addinfo = elements.iter().find(|e| e.class == UNIVERSAL && matches!(e.number, 26 | 27)).map(|e| String::from_utf8_lossy(e.content).into_owned());
+    Ok(Some(Z3950Error::Diagnostic { condition, addinfo: addinfo.unwrap_or_default() }))
+}
+
+/// A node of a PQF query
+enum Rpn {
+    Term { attributes: Vec<(i64, i64)>, term: String },
+    Operation { operator: u32, left: Box<Rpn>, right: Box<Rpn> },
+}
+
+/// Parse a PQF query into the BER encoding of an RPN structure
+fn parse_pqf(query: &str) -> Result<Vec<u8>, Z3950Error> {
+    let tokens = tokenize(query)?;
+    let mut position = 0;
+    if tokens.first().is_some_and(|t| t == "@attrset") {
+        if !tokens.get(1).is_some_and(|set| set.eq_ignore_ascii_case("bib-1")) {
+            return Err(Z3950Error::Query("only the bib-1 attribute set is supported".to_string()));
+        }
+        position = 2;
+    }
+    let rpn = parse_rpn(&tokens, &mut position)?;
+    if position < tokens.len() {
+        return Err(Z3950Error::Query(format!("unexpected {:?} after the query", tokens[position])));
+    }
+    Ok(encode_rpn(&rpn))
+}
+
+/// Split a PQF query into tokens, keeping quoted terms whole
+fn tokenize(query: &str) -> Result<Vec<String>, Z3950Error> {
+    let mut tokens = Vec::new();
+    let mut chars = query.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' {
+            chars.next();
+            let mut token = String::new();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => token.extend(chars.next()),
+                    Some(c) => token.push(c),
+                    None => return Err(Z3950Error::Query("unterminated quote".to_string())),
+                }
+            }
+            tokens.push(token);
+        } else {
+            let mut token = String::new();
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                token.push(c);
+            }
+            tokens.push(token);
+        }
+    }
+    Ok(tokens)
+}
+
+fn parse_rpn(tokens: &[String], position: &mut usize) -> Result<Rpn, Z3950Error> {
+    let token = tokens.get(*position).ok_or_else(|| Z3950Error::Query("missing term".to_string()))?;
+    let operator = match token.as_str() {
+        "@and" => Some(0),
+        "@or" => Some(1),
+        "@not" => Some(2),
+        _ => None,
+    };
+    if let Some(operator) = operator {
+        *position += 1;
+        let left = parse_rpn(tokens, position)?;
+        let right = parse_rpn(tokens, position)?;
+        return Ok(Rpn::Operation { operator, left: Box::new(left), right: Box::new(right) });
+    }
+
+    let mut attributes = Vec::new();
+    while tokens.get(*position).is_some_and(|t| t == "@attr") {
+        *position += 1;
+        let mut attribute = tokens.get(*position).ok_or_else(|| Z3950Error::Query("missing attribute after @attr".to_string()))?;
+        if !attribute.contains('=') {
+            if !attribute.eq_ignore_ascii_case("bib-1") {
+                return Err(Z3950Error::Query(format!("unsupported attribute set {}", attribute)));
+            }
+            *position += 1;
+            attribute = tokens.get(*position).ok_or_else(|| Z3950Error::Query("missing attribute after @attr".to_string()))?;
+        }
+        let (kind, value) = attribute.split_once('=').and_then(|(kind, value)| Some((kind.parse().ok()?, value.parse().ok()?))).ok_or_else(|| Z3950Error::Query(format!("attribute {:?} is not type=value with numbers", attribute)))?;
+        attributes.push((kind, value));
+        *position += 1;
+    }
+    let term = tokens.get(*position).ok_or_else(|| Z3950Error::Query("missing term".to_string()))?;
+    if term.starts_with('@') {
+        return Err(Z3950Error::Query(format!("unsupported operator {}", term)));
+    }
+    *position += 1;
+    Ok(Rpn::Term { attributes, term: term.clone() })
+}
+
+fn encode_rpn(rpn: &Rpn) -> Vec<u8> {
+    match rpn {
+        Rpn::Term { attributes, term } => {
+            let attributes: Vec<u8> = attributes.iter().flat_map(|&(kind, value)| tlv(UNIVERSAL | CONSTRUCTED, 16, &[tlv(CONTEXT, 120, &integer(kind)), tlv(CONTEXT, 121, &integer(value))].concat())).collect();
+            let operand = [tlv(CONTEXT | CONSTRUCTED, 44, &attributes), tlv(CONTEXT, 45, term.as_bytes())].concat();
+            tlv(CONTEXT | CONSTRUCTED, 0, &tlv(CONTEXT | CONSTRUCTED, 102, &operand))
+        }
+        Rpn::Operation { operator, left, right } => {
+            let operator = tlv(CONTEXT | CONSTRUCTED, 46, &tlv(CONTEXT, *operator, &[]));
+            tlv(CONTEXT | CONSTRUCTED, 1, &[encode_rpn(left), encode_rpn(right), operator].concat())
+        }
+    }
+}
+
+const UNIVERSAL: u8 = 0x00;
+const CONTEXT: u8 = 0x80;
+const CONSTRUCTED: u8 = 0x20;
+
+/// BER encoding of an element: `class` holds the class and constructed bits
+fn tlv(class: u8, number: u32, content: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(content.len() + 6);
+    if number < 31 {
+        out.push(class | number as u8);
+    } else {
+        out.push(class | 0x1F);
+        let mut groups = vec![(number & 0x7F) as u8];
+        let mut rest = number >> 7;
+        while rest > 0 {
+            groups.push(0x80 | (rest & 0x7F) as u8);
+            rest >>= 7;
+        }
+        out.extend(groups.iter().rev());
+    }
+    if content.len() < 0x80 {
+        out.push(content.len() as u8);
+    } else {
+        let length = content.len().to_be_bytes();
+        let length = &length[length.iter().position(|b| *b != 0).unwrap_or(length.len() - 1)..];
+        out.push(0x80 | length.len() as u8);
+        out.extend(length);
+    }
+    out.extend(content);
+    out
+}
+
+/// Shortest two's complement encoding of an integer
+fn integer(value: i64) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    let mut start = 0;
+    while start < 7 && ((bytes[start] == 0 && bytes[start + 1] & 0x80 == 0) || (bytes[start] == 0xFF && bytes[start + 1] & 0x80 != 0)) {
+        start += 1;
+    }
+    bytes[start..].to_vec()
+}
+
+fn decode_integer(content: &[u8]) -> i64 {
+    let initial = if content.first().is_some_and(|b| b & 0x80 != 0) { -1 } else { 0 };
+    content.iter().take(8).fold(initial, |value, b| (value << 8) | *b as i64)
+}
+
+fn oid(arcs: &[u32]) -> Vec<u8> {
+    let mut out = vec![(arcs[0] * 40 + arcs[1]) as u8];
+    for &arc in &arcs[2..] {
+        let mut groups = vec![(arc & 0x7F) as u8];
+        let mut rest = arc >> 7;
+        while rest > 0 {
+            groups.push(0x80 | (rest & 0x7F) as u8);
+            rest >>= 7;
+        }
+        out.extend(groups.iter().rev());
+    }
+    out
+}
+
+/// A decoded BER element
+struct Tlv<'a> {
+    class: u8,
+    constructed: bool,
+    number: u32,
+    content: &'a [u8],
+}
+
+/// Decode the element at the start of `data`, returning it and the bytes after it
+fn read_tlv(data: &[u8]) -> Result<(Tlv<'_>, &[u8]), Z3950Error> {
+    let truncated = || Z3950Error::InvalidResponse("truncated element".to_string());
+    let (&first, mut rest) = data.split_first().ok_or_else(truncated)?;
+    let mut number = (first & 0x1F) as u32;
+    if number == 0x1F {
+        number = 0;
+        loop {
+            let (&b, tail) = rest.split_first().ok_or_else(truncated)?;
+            rest = tail;
+            number = (number << 7) | (b & 0x7F) as u32;
+            if b & 0x80 == 0 {
+                break;
+            }
+        }
+    }
+    let (&b, tail) = rest.split_first().ok_or_else(truncated)?;
+    rest = tail;
+    let length = match b {
+        0x80 => return Err(Z3950Error::InvalidResponse("indefinite lengths are not supported".to_string())),
+        b if b & 0x80 == 0 => b as usize,
+        b => {
+            let count = (b & 0x7F) as usize;
+            if count > 4 || rest.len() < count {
+                return Err(truncated());
+            }
+            let (bytes, tail) = rest.split_at(count);
+            rest = tail;
+            bytes.iter().fold(0, |length, b| (length << 8) | *b as usize)
+        }
+    };
+    if rest.len() < length {
+        return Err(truncated());
+    }
+    let (content, rest) = rest.split_at(length);
+    Ok((Tlv { class: first & 0xC0, constructed: first & CONSTRUCTED != 0, number, content }, rest))
+}
+
+/// Elements of a constructed element's content
+fn children(mut content: &[u8]) -> Result<Vec<Tlv<'_>>, Z3950Error> {
+    let mut elements = Vec::new();
+    while !content.is_empty() {
+        let (element, rest) = read_tlv(content)?;
+        elements.push(element);
+        content = rest;
+    }
+    Ok(elements)
+}
+
+/// First child with a class (and constructed bit) and tag number; none if the content is not valid
+fn find(content: &[u8], class: u8, number: u32) -> Option<Tlv<'_>> {
+    children(content).ok()?.into_iter().find(|e| e.class == class & 0xC0 && e.constructed == (class & CONSTRUCTED != 0) && e.number == number)
+}
+
+/// Read one whole PDU from the stream
+fn read_pdu(stream: &mut impl Read) -> Result<Vec<u8>, Z3950Error> {
+    let mut byte = [0u8; 1];
+    let mut read_byte = |pdu: &mut Vec<u8>| -> Result<u8, Z3950Error> {
+        stream.read_exact(&mut byte)?;
+        pdu.push(byte[0]);
+        Ok(byte[0])
+    };
+    let mut pdu = Vec::new();
+    if read_byte(&mut pdu)? & 0x1F == 0x1F {
+        while read_byte(&mut pdu)? & 0x80 != 0 {}
+    }
+    let length = match read_byte(&mut pdu)? {
+        0x80 => return Err(Z3950Error::InvalidResponse("indefinite lengths are not supported".to_string())),
+        b if b & 0x80 == 0 => b as usize,
+        b => {
+            let mut length = 0;
+            for _ in 0..(b & 0x7F).min(4) {
+                length = (length << 8) | read_byte(&mut pdu)? as usize;
+            }
+            length
+        }
+    };
+    let start = pdu.len();
+    pdu.resize(start + length, 0);
+    stream.read_exact(&mut pdu[start..])?;
+    Ok(pdu)
+}
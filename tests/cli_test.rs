@@ -0,0 +1,1047 @@
+#![cfg(feature = "cli")]
+
+use marc_rs::*;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+/// Data field with the given indicators and subfields
+fn field(tag: &str, ind1: char, ind2: char, subfields: &[(char, &str)]) -> DataField {
+    DataField {
+        tag: tag.to_string(),
+        ind1,
+        ind2,
+        subfields: subfields.iter().map(|(code, value)| Subfield { code: *code, value: value.to_string() }).collect(),
+    }
+}
+
+fn record(id: &str, title: &str) -> Record {
+    Record {
+        leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+        control_fields: vec![ControlField {
+            tag: "001".to_string(),
+            value: id.to_string(),
+        }],
+        data_fields: vec![field("245", '1', '0', &[('a', title)])],
+    }
+}
+
+/// Write records to a file in the temporary directory, unique per test
+fn write_file(name: &str, records: &[Record], format_encoding: FormatEncoding) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("marc-cli-{}-{}", std::process::id(), name));
+    std::fs::write(&path, serde_marc::to_vec_many(records, format_encoding).unwrap()).unwrap();
+    path
+}
+
+/// Run the tool, ignoring any configuration file of the user
+fn marc(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_marc")).args(args).env("MARC_CONFIG", "").output().unwrap()
+}
+
+/// Run the tool with `input` piped to its stdin
+fn marc_stdin(args: &[&str], input: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_marc")).args(args).env("MARC_CONFIG", "").stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn().unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    // Write in small pieces, as a pipe may deliver them, until the tool stops reading
+    for chunk in input.chunks(7) {
+        if stdin.write_all(chunk).and_then(|_| stdin.flush()).is_err() {
+            break;
+        }
+    }
+    drop(stdin);
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn test_cli_view() {
+    let path = write_file("view.mrc", &[record("1", "First"), record("2", "Second")], FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8));
+    let path = path.to_str().unwrap();
+
+    let output = marc(&["view", path]);
+    assert!(output.status.success());
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(text.contains("Format: marc21, Encoding: utf8"));
+    assert!(text.contains("  245 10 $aSecond  Title statement"));
+    assert!(text.contains("  001    2         Control number"));
+    assert!(text.contains("2 record(s)"));
+    // Colors are only used on a terminal
+    assert!(!text.contains('\x1b'));
+
+    let output = marc(&["view", path, "--to", "xml"]);
+    let records = serde_marc::from_slice_many(&output.stdout, FormatEncoding::marc_xml()).unwrap();
+    assert_eq!(records[1].control_fields[0].value, "2");
+
+    // JSON Lines: one MARC-in-JSON object per line
+    let output = marc(&["view", path, "--to", "jsonl"]);
+    let text = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<serde_json::Value> = text.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[1]["fields"][0]["001"], "2");
+
+    let output = marc(&["view", path, "--from", "nope"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("Unknown format: nope"));
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_cli_convert() {
+    let mut records = vec![record("1", "Dollars $ & {braces}"), record("2", "Second")];
+    records[1].leader.character_coding_scheme = ' ';
+    let path = write_file("convert.mrc", &records, FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8));
+    let input = path.to_str().unwrap();
+
+    // Every format converts back to the same records
+    for (name, format) in [("convert.xml", None), ("convert.json", None), ("convert.jsonl", None), ("convert.mrk", None), ("convert.out", Some("unimarc:utf8"))] {
+        let output = std::env::temp_dir().join(format!("marc-cli-{}-{}", std::process::id(), name));
+        let output = output.to_str().unwrap();
+        let mut args = vec!["convert", input, "-o", output];
+        if let Some(format) = format {
+            args.extend(["--to", format]);
+        }
+        let result = marc(&args);
+        assert!(result.status.success(), "{}: {}", name, String::from_utf8_lossy(&result.stderr));
+        if format.is_none() {
+            // Text outputs are UTF-8
+            assert!(!std::fs::read_to_string(output).unwrap().contains("nam  22"), "{}", name);
+        }
+
+        let mut args = vec!["convert", output, "-o", input];
+        if format.is_some() {
+            args.extend(["--from", "unimarc:utf8", "--to", "marc21"]);
+        }
+        assert!(marc(&args).status.success(), "{}", name);
+        let read = serde_marc::from_slice_many(&std::fs::read(input).unwrap(), FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8)).unwrap();
+        assert_eq!(read.len(), 2, "{}", name);
+        assert_eq!(read[0].data_fields, records[0].data_fields, "{}", name);
+        // Leader position 9 follows the output encoding
+        assert_eq!(read[1].leader.character_coding_scheme, 'a', "{}", name);
+        std::fs::remove_file(output).unwrap();
+    }
+
+    // MARCXML keeps leading and trailing spaces of values
+    let mut padded = record("3", " Padded title ");
+    padded.control_fields.push(ControlField {
+        tag: "008".to_string(),
+        value: "200101s2020    fr            000 0 fre  ".to_string(),
+    });
+    let padded_input = write_file("convert-padded.mrc", std::slice::from_ref(&padded), FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8));
+    let xml = std::env::temp_dir().join(format!("marc-cli-{}-convert-padded.xml", std::process::id()));
+    assert!(marc(&["convert", padded_input.to_str().unwrap(), "-o", xml.to_str().unwrap()]).status.success());
+    assert!(marc(&["convert", xml.to_str().unwrap(), "-o", padded_input.to_str().unwrap()]).status.success());
+    let read = serde_marc::from_slice_many(&std::fs::read(&padded_input).unwrap(), FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8)).unwrap();
+    assert_eq!(read[0].control_fields, padded.control_fields);
+    assert_eq!(read[0].data_fields, padded.data_fields);
+    std::fs::remove_file(padded_input).unwrap();
+    std::fs::remove_file(xml).unwrap();
+
+    // The positional output file is a deprecated alias of --output
+    let output = std::env::temp_dir().join(format!("marc-cli-{}-convert-positional.xml", std::process::id()));
+    let result = marc(&["convert", input, output.to_str().unwrap()]);
+    assert!(result.status.success());
+    assert!(String::from_utf8(result.stderr).unwrap().contains("the positional output file is deprecated, use -o/--output"));
+    assert_eq!(serde_marc::from_slice_many(&std::fs::read(&output).unwrap(), FormatEncoding::marc_xml()).unwrap().len(), 2);
+    assert!(!marc(&["convert", input, output.to_str().unwrap(), "-o", "other.xml"]).status.success());
+    std::fs::remove_file(output).unwrap();
+
+    let result = marc(&["convert", input, "-o", "out.unknown"]);
+    assert!(String::from_utf8(result.stderr).unwrap().contains("Cannot infer the output format"));
+    std::fs::remove_file(input).unwrap();
+}
+
+#[test]
+fn test_cli_convert_json() {
+    let records = [record("1", "First"), record("2", "Second")];
+    let pretty = |record: &Record| serde_json::to_string_pretty(&MarcJson(record)).unwrap();
+    let array = format!("[\n{},\n{}\n]\n", pretty(&records[0]), pretty(&records[1]));
+    let single = pretty(&records[0]);
+    let output = std::env::temp_dir().join(format!("marc-cli-{}-convert-json.xml", std::process::id()));
+    let output = output.to_str().unwrap();
+
+    // An array of records and a single pretty-printed record, from a .json file or detected on stdin
+    for (name, json, count) in [("convert-array.json", &array, 2), ("convert-single.json", &single, 1)] {
+        let path = std::env::temp_dir().join(format!("marc-cli-{}-{}", std::process::id(), name));
+        std::fs::write(&path, json).unwrap();
+        let result = marc(&["convert", path.to_str().unwrap(), "-o", output]);
+        assert!(result.status.success(), "{}: {}", name, String::from_utf8_lossy(&result.stderr));
+        let read = serde_marc::from_slice_many(&std::fs::read(output).unwrap(), FormatEncoding::new(MarcFormat::MarcXml, Encoding::Utf8)).unwrap();
+        assert_eq!(read, records[..count], "{}", name);
+
+        let result = marc_stdin(&["view", "-", "--to", "jsonl"], json.as_bytes());
+        assert!(result.status.success(), "{}: {}", name, String::from_utf8_lossy(&result.stderr));
+        assert_eq!(serde_marc::from_jsonl_reader(result.stdout.as_slice()).count(), count, "{}", name);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    // jsonl stays line-delimited
+    let result = marc_stdin(&["view", "-", "--from", "jsonl"], single.as_bytes());
+    assert!(!result.status.success());
+    std::fs::remove_file(output).unwrap();
+}
+
+#[test]
+fn test_cli_convert_recursive() {
+    let format = FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8);
+    let dir = std::env::temp_dir().join(format!("marc-cli-{}-recursive", std::process::id()));
+    let (input, output) = (dir.join("in"), dir.join("out"));
+    std::fs::create_dir_all(input.join("2024/03")).unwrap();
+    std::fs::write(input.join("a.mrc"), serde_marc::to_vec(&record("1", "First"), format).unwrap()).unwrap();
+    std::fs::write(input.join("2024/03/b.mrc"), serde_marc::to_vec_many(&[record("2", "Second"), record("3", "Third")], format).unwrap()).unwrap();
+    std::fs::write(input.join("2024/notes.txt"), "not MARC").unwrap();
+
+    let output_path = output.to_str().unwrap();
+    let output_run = marc(&["convert", "--recursive", input.to_str().unwrap(), "-o", output_path, "--to", "xml", "--jobs", "2"]);
+    assert!(output_run.status.success(), "{}", String::from_utf8_lossy(&output_run.stderr));
+    assert!(String::from_utf8(output_run.stderr).unwrap().contains("Converted 3 record(s) in 2 of 2 file(s) to xml"));
+    let records = serde_marc::from_slice_many(&std::fs::read(output.join("2024/03/b.xml")).unwrap(), FormatEncoding::marc_xml()).unwrap();
+    assert_eq!(records.len(), 2);
+    assert!(output.join("a.xml").exists() && !output.join("2024/notes.xml").exists());
+
+    // Failed files are listed, the others converted
+    std::fs::write(input.join("2024/broken.mrc"), "00100nam").unwrap();
+    let output_run = marc(&["convert", "-r", input.to_str().unwrap(), "-o", output_path, "--to", "jsonl"]);
+    assert_eq!(output_run.status.code(), Some(2));
+    let stderr = String::from_utf8(output_run.stderr).unwrap();
+    assert!(stderr.contains("in 2 of 3 file(s)") && stderr.contains("1 file(s) failed:") && stderr.contains("broken.mrc: "), "{}", stderr);
+    assert!(output.join("2024/03/b.jsonl").exists());
+    std::fs::remove_file(input.join("2024/broken.mrc")).unwrap();
+
+    // Files that would share an output are reported instead of overwriting each other
+    std::fs::write(input.join("a.xml"), serde_marc::to_vec(&record("4", "Fourth"), FormatEncoding::marc_xml()).unwrap()).unwrap();
+    let output_run = marc(&["convert", "-r", input.to_str().unwrap(), "-o", output_path, "--to", "mrk"]);
+    assert_eq!(output_run.status.code(), Some(2));
+    let stderr = String::from_utf8(output_run.stderr).unwrap();
+    assert!(stderr.contains("in 1 of 3 file(s)") && stderr.contains("2 file(s) failed:"), "{}", stderr);
+    assert!(stderr.contains(&format!("a.mrc: {} is also the output of ", output.join("a.mrk").display())), "{}", stderr);
+    assert!(!output.join("a.mrk").exists() && output.join("2024/03/b.mrk").exists());
+
+    assert!(!marc(&["convert", "-r", input.to_str().unwrap(), "-o", output_path]).status.success());
+    assert!(!marc(&["convert", "-r", input.to_str().unwrap(), "-o", output_path, "--to", "xml", "--skip", "1"]).status.success());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_cli_exit_codes() {
+    let records = [record("1", "Café"), record("2", "日本"), record("3", "Lost \u{FFFD}")];
+    let path = write_file("exit.mrc", &records, FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8));
+    let input = path.to_str().unwrap();
+
+    // Records that cannot be encoded are skipped: errors
+    let output = marc(&["convert", input, "--to", "marc21:latin1", "--filter", "001!=3"]);
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Record #2: Invalid encoding"), "{}", stderr);
+    assert!(stderr.contains("Converted 1 record(s)") && stderr.contains("1 record(s) skipped"), "{}", stderr);
+    assert_eq!(serde_marc::from_slice_many(&output.stdout, FormatEncoding::new(MarcFormat::Marc21, Encoding::Iso8859_1)).unwrap().len(), 1);
+
+    // Replacement characters: warnings
+    assert_eq!(marc(&["convert", input, "--to", "xml"]).status.code(), Some(1));
+    assert_eq!(marc(&["convert", input, "--to", "xml", "--filter", "001!=3"]).status.code(), Some(0));
+
+    // Fatal: unreadable input, invalid arguments
+    assert_eq!(marc(&["validate", "missing.mrc"]).status.code(), Some(3));
+    assert_eq!(marc(&["convert", input]).status.code(), Some(3));
+    let help = marc(&["--help"]);
+    assert_eq!(help.status.code(), Some(0));
+    // Commands whose codes do not follow the clean/warnings/errors scale are listed
+    let help = String::from_utf8(help.stdout).unwrap();
+    assert!(help.contains("fixup           0 nothing to repair, 1 records repaired"));
+    assert!(help.contains("grep            0 records selected, 1 no record selected"));
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_cli_completions_config() {
+    let output = marc(&["completions", "bash"]);
+    assert!(output.status.success());
+    let script = String::from_utf8(output.stdout).unwrap();
+    assert!(script.contains("_marc()") && script.contains("convert"));
+
+    let path = write_file("config.mrc", &[record("1", "Title")], FormatEncoding::new(MarcFormat::Unimarc, Encoding::Utf8));
+    let config = std::env::temp_dir().join(format!("marc-cli-{}-config.toml", std::process::id()));
+    let with_config = |args: &[&str]| Command::new(env!("CARGO_BIN_EXE_marc")).args(args).env("MARC_CONFIG", &config).output().unwrap();
+
+    std::fs::write(&config, "binary-format = \"unimarc\"\n\n[output]\nformat = \"jsonl\"\n\n[display]\nprogress = false\n").unwrap();
+    let output = with_config(&["stats", path.to_str().unwrap()]);
+    assert!(String::from_utf8(output.stdout).unwrap().contains("Format: unimarc, Encoding: utf8"));
+    // No output extension: the configured output format
+    let output = with_config(&["convert", path.to_str().unwrap()]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(output.stdout.starts_with(b"{"));
+
+    // Local fields are labelled in views and checked by validate
+    let mut local = record("1", "Title");
+    local.data_fields.push(DataField {
+        tag: "949".to_string(),
+        ind1: ' ',
+        ind2: ' ',
+        subfields: vec![Subfield { code: 'a', value: "QA76".to_string() }, Subfield { code: 'a', value: "QA77".to_string() }],
+    });
+    let local_path = write_file("config-local.mrc", &[local], FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8));
+    std::fs::write(&config, "[local-fields.949]\nlabel = \"Local holdings\"\nsubfields = { a = { label = \"Call number\", repeatable = false } }\n").unwrap();
+    let output = with_config(&["view", local_path.to_str().unwrap()]);
+    assert!(String::from_utf8(output.stdout).unwrap().contains("  949 __ $aQA76 $aQA77  Local holdings"));
+    let output = with_config(&["validate", local_path.to_str().unwrap()]);
+    assert!(String::from_utf8(output.stdout).unwrap().contains("949[0]$a: subfield is not repeatable"));
+    assert_eq!(output.status.code(), Some(2));
+    std::fs::remove_file(local_path).unwrap();
+
+    std::fs::write(&config, "[output]\nfromat = \"xml\"\n").unwrap();
+    let output = with_config(&["stats", path.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8(output.stderr).unwrap().contains("unexpected key output.fromat"));
+    std::fs::remove_file(path).unwrap();
+    std::fs::remove_file(config).unwrap();
+}
+
+#[test]
+fn test_cli_stdio() {
+    let records = [record("1", "First"), record("2", "Second")];
+    let xml = serde_marc::to_vec_many(&records, FormatEncoding::marc_xml()).unwrap();
+
+    // The input format is detected from stdin
+    let output = marc_stdin(&["convert", "--to", "marc21", "-", "-o", "-"], &xml);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let read = serde_marc::from_slice_many(&output.stdout, FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8)).unwrap();
+    assert_eq!(read.len(), 2);
+    assert_eq!(read[1].data_fields, records[1].data_fields);
+
+    let output = marc_stdin(&["view", "-"], &xml);
+    assert!(String::from_utf8(output.stdout).unwrap().contains("  245 10 $aSecond"));
+
+    let output = marc_stdin(&["convert", "-", "-o", "-"], &xml);
+    assert!(String::from_utf8(output.stderr).unwrap().contains("Cannot infer the output format of -"));
+}
+
+#[test]
+fn test_cli_output() {
+    let path = write_file("output.mrc", &[record("1", "First"), record("2", "Second")], FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8));
+    let input = path.to_str().unwrap();
+    let output = std::env::temp_dir().join(format!("marc-cli-{}-output.xml", std::process::id()));
+
+    let result = marc(&["view", input, "--to", "xml", "-o", output.to_str().unwrap()]);
+    assert!(result.status.success());
+    assert!(result.stdout.is_empty());
+    let written = std::fs::read(&output).unwrap();
+    assert_eq!(serde_marc::from_slice_many(&written, FormatEncoding::marc_xml()).unwrap().len(), 2);
+
+    // A failed conversion leaves the existing output alone, without a temporary file
+    let mut truncated = std::fs::read(&path).unwrap();
+    truncated.extend_from_slice(b"00100nam a2200037 a 4500001");
+    let broken = std::env::temp_dir().join(format!("marc-cli-{}-output-broken.mrc", std::process::id()));
+    std::fs::write(&broken, truncated).unwrap();
+    let result = marc(&["convert", broken.to_str().unwrap(), "-o", output.to_str().unwrap()]);
+    assert!(!result.status.success());
+    assert_eq!(std::fs::read(&output).unwrap(), written);
+    let prefix = format!(".marc-cli-{}-output.xml", std::process::id());
+    assert!(!std::fs::read_dir(std::env::temp_dir()).unwrap().any(|entry| entry.unwrap().file_name().to_string_lossy().starts_with(&prefix)));
+
+    for path in [path, output, broken] {
+        std::fs::remove_file(path).unwrap();
+    }
+}
+
+#[test]
+fn test_cli_validate() {
+    let mut invalid = record("2", "Second");
+    invalid.data_fields[0].ind2 = 'x';
+    invalid.data_fields.push(DataField {
+        tag: "440".to_string(),
+        ind1: ' ',
+        ind2: '0',
+        subfields: vec![Subfield {
+            code: 'a',
+            value: "Series".to_string(),
+        }],
+    });
+    let format = FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8);
+    let clean = write_file("validate-clean.mrc", &[record("1", "First")], format);
+    let path = write_file("validate.mrc", &[record("1", "First"), invalid.clone()], format);
+    let path = path.to_str().unwrap();
+
+    let output = marc(&["validate", clean.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(String::from_utf8(output.stdout).unwrap().contains("1 record(s), 0 error(s), 0 warning(s)"));
+
+    let output = marc(&["validate", path]);
+    assert_eq!(output.status.code(), Some(2));
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(text.contains("Record #2 (2)\n  error   245[0]^2: invalid second indicator 'x'\n  warning 440[0]: obsolete field"));
+    assert!(text.contains("2 record(s), 1 error(s), 1 warning(s)"));
+
+    let output = marc(&["validate", path, "--format", "json"]);
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["record"], 2);
+    assert_eq!(report["id"], "2");
+    assert_eq!(report["issues"][0]["location"], "245[0]^2");
+    assert_eq!(report["issues"][1]["severity"], "warning");
+
+    // The report goes to --output instead of stdout
+    let report = std::env::temp_dir().join(format!("marc-cli-{}-validate.txt", std::process::id()));
+    let output = marc(&["validate", path, "-o", report.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+    assert!(std::fs::read_to_string(&report).unwrap().contains("2 record(s), 1 error(s), 1 warning(s)"));
+    std::fs::remove_file(report).unwrap();
+
+    invalid.data_fields[0].ind2 = '0';
+    std::fs::write(path, serde_marc::to_vec(&invalid, format).unwrap()).unwrap();
+    assert_eq!(marc(&["validate", path]).status.code(), Some(1));
+    assert_eq!(marc(&["validate", path, "--quiet"]).status.code(), Some(0));
+
+    std::fs::remove_file(clean).unwrap();
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_cli_lint() {
+    let mut first = record("1", "First");
+    for (tag, value) in [("020", "2-07-036822-9"), ("999", "Local")] {
+        first.data_fields.push(DataField {
+            tag: tag.to_string(),
+            ind1: ' ',
+            ind2: ' ',
+            subfields: vec![Subfield { code: 'a', value: value.to_string() }],
+        });
+    }
+    let path = write_file("lint.mrc", &[first, record("2", "Second")], FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8));
+    let input = path.to_str().unwrap();
+
+    let output = marc(&["lint", input]);
+    assert_eq!(output.status.code(), Some(2));
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(text.contains("Record #1 (1)\n  error   008: missing field\n  error   020[0]$a: invalid ISBN \"2-07-036822-9\"\n"), "{}", text);
+    assert!(text.ends_with("2 record(s), 3 error(s), 0 warning(s)\n"));
+
+    let rules = std::env::temp_dir().join(format!("marc-cli-{}-rules.toml", std::process::id()));
+    std::fs::write(&rules, "[[rule]]\nforbidden = \"9..\"\nseverity = \"warning\"\n\n[[rule]]\nvalues = [\"a\"]\nspec = \"LDR/06\"\n").unwrap();
+    let output = marc(&["lint", input, "--rules", rules.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(1));
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(text, "Record #1 (1)\n  warning 999: forbidden field\n2 record(s), 0 error(s), 1 warning(s)\n");
+
+    std::fs::write(&rules, "[[rule]]\nrequired = \"245$a\"\nisbn = true\n").unwrap();
+    assert!(String::from_utf8(marc(&["lint", input, "--rules", rules.to_str().unwrap()]).stderr).unwrap().contains("rule #1: expected a single check per rule"));
+    assert!(String::from_utf8(marc(&["lint", input, "--rules", "strict"]).stderr).unwrap().contains("Unknown rule set: strict"));
+    std::fs::remove_file(path).unwrap();
+    std::fs::remove_file(rules).unwrap();
+}
+
+#[test]
+fn test_cli_filter() {
+    let mut online = record("2", "Online history");
+    online.data_fields.push(DataField {
+        tag: "856".to_string(),
+        ind1: '4',
+        ind2: '0',
+        subfields: vec![Subfield {
+            code: 'u',
+            value: "https://example.org".to_string(),
+        }],
+    });
+    let format = FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8);
+    let path = write_file("filter.mrc", &[record("1", "History"), online, record("3", "Poems")], format);
+    let input = path.to_str().unwrap();
+
+    let ids = |args: &[&str]| -> Vec<String> {
+        let output = marc(&[&["view", input, "--to", "json"], args].concat());
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        serde_marc::from_json_reader(output.stdout.as_slice()).map(|r| r.unwrap().control_fields[0].value.clone()).collect()
+    };
+    assert_eq!(ids(&["--has", "856"]), vec!["2"]);
+    assert_eq!(ids(&["--filter", "245$a~history"]), vec!["1", "2"]);
+    assert_eq!(ids(&["--filter", "245$a~history", "--filter", "856$u!=https://example.org"]), vec!["1"]);
+    assert!(ids(&["--filter", "245$a=Novels"]).is_empty());
+
+    let text = String::from_utf8(marc(&["view", input, "--filter", "001=3"]).stdout).unwrap();
+    assert!(text.contains("Record #3") && text.contains("1 record(s)"));
+
+    let output = std::env::temp_dir().join(format!("marc-cli-{}-filter.xml", std::process::id()));
+    let result = marc(&["convert", input, "-o", output.to_str().unwrap(), "--has", "856"]);
+    assert!(String::from_utf8(result.stderr).unwrap().contains("Converted 1 record(s)"));
+    assert_eq!(serde_marc::from_slice_many(&std::fs::read(&output).unwrap(), FormatEncoding::marc_xml()).unwrap().len(), 1);
+
+    let result = marc(&["view", input, "--filter", "24=a"]);
+    assert!(!result.status.success());
+    assert!(String::from_utf8(result.stderr).unwrap().contains("Invalid spec"));
+
+    std::fs::remove_file(output).unwrap();
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_cli_encoding_report() {
+    let utf8 = FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8);
+    let latin1 = FormatEncoding::new(MarcFormat::Marc21, Encoding::Iso8859_1);
+    let mut data = serde_marc::to_vec_many(&[record("1", "Title"), record("2", "Été")], utf8).unwrap();
+    data.extend(serde_marc::to_vec_many(&[record("3", "Café"), record("4", "Été")], latin1).unwrap());
+    let path = std::env::temp_dir().join(format!("marc-cli-{}-encoding.mrc", std::process::id()));
+    std::fs::write(&path, data).unwrap();
+
+    let output = marc(&["encoding-report", path.to_str().unwrap()]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Records: 4"));
+    assert!(stdout.contains("ASCII only                     1\n"), "{}", stdout);
+    assert!(stdout.contains("UTF-8                          1  e.g. #2"));
+    assert!(stdout.contains("single-byte (ISO 8859)         2  e.g. #3, #4"));
+    assert!(stdout.contains("Records with invalid UTF-8: 2"));
+    assert!(stdout.contains("Suggested: --encoding iso8859-1 (the records mix several encodings)"));
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_cli_explode() {
+    let format = FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8);
+    let mut records = vec![record("b1", "First"), record("b/2", "Second"), record("b1", "Third"), record("", "Fourth")];
+    records[3].control_fields.clear();
+    let path = write_file("explode.mrc", &records, format);
+    let dir = std::env::temp_dir().join(format!("marc-cli-{}-explode", std::process::id()));
+    let title = |name: &str, format: FormatEncoding| -> String {
+        let record = serde_marc::from_slice(&std::fs::read(dir.join(name)).unwrap(), format).unwrap();
+        record.data_fields[0].subfields[0].value.clone()
+    };
+
+    let output = marc(&["explode", path.to_str().unwrap(), "--dir", dir.to_str().unwrap()]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8(output.stderr).unwrap().contains("Wrote 4 file(s)"));
+    assert_eq!(title("b1.mrc", format), "First");
+    assert_eq!(title("b_2.mrc", format), "Second");
+    assert_eq!(title("b1-2.mrc", format), "Third");
+    assert_eq!(title("000004.mrc", format), "Fourth");
+
+    let output = marc(&["explode", path.to_str().unwrap(), "--dir", dir.to_str().unwrap(), "--sequence", "--to", "xml", "--has", "001"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(title("000003.xml", FormatEncoding::marc_xml()), "Third");
+    assert!(!dir.join("000004.xml").exists());
+    std::fs::remove_dir_all(dir).unwrap();
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_cli_extract() {
+    let path = write_file("extract.mrc", &[record("1", "First"), record("2", "Second, part")], FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8));
+    let input = path.to_str().unwrap();
+
+    let output = marc(&["extract", input, "--columns", "001,245$a,245^1"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "001,245$a,245^1\n1,First,1\n2,\"Second, part\",1\n");
+
+    let output = marc(&["extract", input, "-c", "001", "-c", "245$a", "--delimiter", "tab", "--filter", "001=2"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "001\t245$a\n2\tSecond, part\n");
+
+    assert!(!marc(&["extract", input]).status.success());
+    let output = marc(&["extract", input, "--columns", "001", "--delimiter", "::"]);
+    assert!(String::from_utf8(output.stderr).unwrap().contains("Invalid delimiter"));
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_cli_range() {
+    let records: Vec<Record> = (1..=5).map(|i| record(&i.to_string(), "Title")).collect();
+    let path = write_file("range.mrc", &records, FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8));
+    let input = path.to_str().unwrap();
+
+    let output = marc(&["extract", input, "-c", "001", "--skip", "1", "--limit", "2"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "001\n2\n3\n");
+
+    // The limit counts the records written, after filtering
+    let output = marc(&["view", input, "--skip", "2", "--limit", "1", "--filter", "001!=3"]);
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(text.contains("Record #4") && text.contains("1 record(s)"));
+
+    let output = marc(&["view", input, "--to", "mrk", "--skip", "10"]);
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    assert!(!marc(&["view", input, "--limit", "0"]).status.success());
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_cli_count() {
+    let records: Vec<Record> = (1..=3).map(|i| record(&i.to_string(), "Title")).collect();
+    let path = write_file("count.mrc", &records, FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8));
+    let input = path.to_str().unwrap();
+
+    assert_eq!(String::from_utf8(marc(&["count", input]).stdout).unwrap(), "3\n");
+    assert_eq!(String::from_utf8(marc(&["count", input, "--terminators"]).stdout).unwrap(), "3\n");
+    for format in ["xml", "jsonl", "mrk"] {
+        let output = marc(&["view", input, "--to", format]);
+        assert_eq!(String::from_utf8(marc_stdin(&["count", "-"], &output.stdout).stdout).unwrap(), "3\n", "{}", format);
+    }
+
+    // A wrong record length stops the leader walk, not the terminator count
+    let mut bytes = std::fs::read(&path).unwrap();
+    bytes[4] = b'0';
+    std::fs::write(&path, bytes).unwrap();
+    assert!(String::from_utf8(marc(&["count", input]).stderr).unwrap().contains("Invalid record length"));
+    assert_eq!(String::from_utf8(marc(&["count", input, "--terminators"]).stdout).unwrap(), "3\n");
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_cli_dedup() {
+    let with = |mut record: Record, tag: &str, value: &str| {
+        record.data_fields.push(DataField {
+            tag: tag.to_string(),
+            ind1: ' ',
+            ind2: ' ',
+            subfields: vec![Subfield {
+                code: 'a',
+                value: value.to_string(),
+            }],
+        });
+        record
+    };
+    let records = [
+        with(record("1", "First"), "020", "2-07-036822-X (pbk.)"),
+        with(with(record("2", "Second"), "020", "978-2-07-036822-8"), "500", "Note"),
+        with(record("3", "The Third"), "035", "(OCoLC)42"),
+        with(record("4", "Third!"), "035", "(OCoLC)42"),
+        record("5", "FIRST"),
+    ];
+    let path = write_file("dedup.mrc", &records, FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8));
+    let input = path.to_str().unwrap();
+
+    let kept = |args: &[&str]| -> (Vec<String>, String) {
+        let output = marc(&[&["dedup", input, "--to", "jsonl"], args].concat());
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        let ids = serde_marc::from_jsonl_reader(output.stdout.as_slice()).map(|r| r.unwrap().control_fields[0].value.clone()).collect();
+        (ids, String::from_utf8(output.stderr).unwrap())
+    };
+    let (ids, report) = kept(&[]);
+    assert_eq!(ids, ["1", "2", "3", "5"]);
+    assert!(report.contains("Dropped record #4 (4): duplicate of record #3 (3)"));
+    assert!(report.contains("Kept 4 record(s), dropped 1 duplicate(s)"));
+
+    // ISBN-10 and ISBN-13 forms match; record 5 has the match key of record 1
+    assert_eq!(kept(&["--by", "isbn"]).0, ["1", "3", "4", "5"]);
+    assert_eq!(kept(&["--by", "isbn", "--keep", "last"]).0, ["2", "3", "4", "5"]);
+    assert_eq!(kept(&["--by", "isbn", "--keep", "fullest"]).0, ["2", "3", "4", "5"]);
+    assert_eq!(kept(&["--by", "isbn,match-key", "--keep", "last"]).0, ["3", "4", "5"]);
+
+    assert!(!marc(&["dedup", input, "--by", "title"]).status.success());
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_cli_edit() {
+    let mut first = record("1", "The First");
+    first.data_fields.push(field("650", ' ', '7', &[('a', "Cats"), ('2', "fast")]));
+    first.data_fields.push(field("650", ' ', '0', &[('a', "Cats")]));
+    first.data_fields.push(field("999", ' ', ' ', &[('a', "Local")]));
+    let path = write_file("edit.mrc", &[first, record("2", "Second")], FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8));
+    let script = std::env::temp_dir().join(format!("marc-cli-{}-edit.txt", std::process::id()));
+    std::fs::write(&script, "# Clean up\ndelete 9..\nadd \"=590  \\\\$aDigitized\" if 001=1\n\nreplace 245$a \"/^The /\" \"\"\ncopy 245$a 740$a\ndelete 650 if 650$2=fast\n").unwrap();
+
+    let output = marc(&["edit", path.to_str().unwrap(), "--to", "mrk", "--script", script.to_str().unwrap(), "--expression", "replace 245$a First Premier"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let text = String::from_utf8(output.stdout).unwrap();
+    let fields: Vec<&str> = text.lines().filter(|line| !line.starts_with("=LDR")).collect();
+    assert_eq!(fields, ["=001  1", "=245  10$aPremier", "=590  \\\\$aDigitized", "=650  \\0$aCats", "=740  \\\\$aFirst", "", "=001  2", "=245  10$aSecond", "=740  \\\\$aSecond", ""]);
+    assert!(String::from_utf8(output.stderr).unwrap().contains("Edited 2 of 2 record(s)"));
+
+    std::fs::write(&script, "delete 650\nrename 245 246\n").unwrap();
+    let output = marc(&["edit", path.to_str().unwrap(), "--script", script.to_str().unwrap()]);
+    assert!(String::from_utf8(output.stderr).unwrap().contains("line 2: unknown operation \"rename\""));
+
+    let patch = std::env::temp_dir().join(format!("marc-cli-{}-patch.toml", std::process::id()));
+    std::fs::write(&patch, "[[operation]]\ndelete = \"9..\"\n\n[[operation]]\nmove = \"650$2\"\nto = \"690$a\"\nif = \"001=1\"\n").unwrap();
+    let output = marc(&["edit", path.to_str().unwrap(), "--to", "mrk", "--patch", patch.to_str().unwrap(), "--expression", "delete 690 if 001=2"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let text = String::from_utf8(output.stdout).unwrap();
+    let fields: Vec<&str> = text.lines().filter(|line| !line.starts_with("=LDR")).collect();
+    assert_eq!(fields, ["=001  1", "=245  10$aThe First", "=650  \\7$aCats", "=650  \\0$aCats", "=690  \\\\$afast", "", "=001  2", "=245  10$aSecond", ""]);
+
+    std::fs::write(&patch, "[[operation]]\ncopy = \"245$a\"\n").unwrap();
+    let output = marc(&["edit", path.to_str().unwrap(), "--patch", patch.to_str().unwrap()]);
+    assert!(String::from_utf8(output.stderr).unwrap().contains("operation #1: copy needs a to key"));
+    std::fs::remove_file(path).unwrap();
+    std::fs::remove_file(script).unwrap();
+    std::fs::remove_file(patch).unwrap();
+}
+
+#[test]
+fn test_cli_fixup() {
+    let format = FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8);
+    let path = write_file("fixup.mrc", &[record("1", "First"), record("2", "Second")], format);
+    let fixed = std::env::temp_dir().join(format!("marc-cli-{}-fixed.mrc", std::process::id()));
+    let output = marc(&["fixup", path.to_str().unwrap(), "-o", fixed.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(0), "{}", String::from_utf8_lossy(&output.stderr));
+
+    // Wrong record length and indicator count in the first record
+    let mut data = std::fs::read(&path).unwrap();
+    data[..5].copy_from_slice(b"00100");
+    data[11] = b'1';
+    std::fs::write(&path, &data).unwrap();
+    assert!(serde_marc::from_slice_many(&data, format).is_err());
+
+    let output = marc(&["fixup", path.to_str().unwrap(), "-o", fixed.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Record #1: record length \"00100\" corrected"), "{}", stderr);
+    assert!(stderr.contains("Record #1: subfield code count \"1\" corrected to \"2\""));
+    assert!(stderr.contains("Repaired 1 of 2 record(s)"));
+    let records = serde_marc::from_slice_many(&std::fs::read(&fixed).unwrap(), format).unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[1].data_fields[0].subfields[0].value, "Second");
+
+    let output = marc(&["fixup", path.to_str().unwrap(), "--to", "xml", "-q"]);
+    assert_eq!(String::from_utf8(output.stderr).unwrap(), "Repaired 1 of 2 record(s)\n");
+    assert!(!marc(&["fixup", fixed.to_str().unwrap(), "--from", "xml"]).status.success());
+    std::fs::remove_file(fixed).unwrap();
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_cli_grep() {
+    let mut records = vec![record("1", "History of France"), record("2", "Poems"), record("3", "A history")];
+    records[1].data_fields[0].subfields.push(Subfield {
+        code: 'c',
+        value: "Victor Hugo".to_string(),
+    });
+    let path = write_file("grep.mrc", &records, FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8));
+    let input = path.to_str().unwrap();
+    let stdout = |args: &[&str]| String::from_utf8(marc(&[&["grep"], args].concat()).stdout).unwrap();
+
+    assert_eq!(stdout(&["History", input, "-l"]), "1\n");
+    assert_eq!(stdout(&["history", input, "-l", "-i"]), "1\n3\n");
+    assert_eq!(stdout(&["^(a|poe)", input, "-l", "-i", "-E"]), "2\n3\n");
+    assert_eq!(stdout(&["history", input, "-c", "-i", "-v"]), "1\n");
+    assert_eq!(stdout(&["Hugo", input, "-c", "--in", "245$a"]), "0\n");
+    assert_eq!(stdout(&["Hugo", input, "-c", "--in", "245$c"]), "1\n");
+    assert!(stdout(&["Hugo", input]).starts_with("=LDR  "));
+
+    // Exit code 1 when nothing matches, as grep does
+    let output = marc(&["grep", "Novels", input, "-l"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+    assert!(!marc(&["grep", "(", input, "-E"]).status.success());
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_cli_head_tail() {
+    let records: Vec<Record> = (1..=5).map(|i| record(&i.to_string(), "Title")).collect();
+    let path = write_file("head.mrc", &records, FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8));
+    let input = path.to_str().unwrap();
+    let ids = |output: Output| -> Vec<String> {
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        String::from_utf8(output.stdout).unwrap().lines().filter_map(|line| line.strip_prefix("=001  ")).map(str::to_string).collect()
+    };
+
+    assert_eq!(ids(marc(&["head", input, "-n", "2"])), ["1", "2"]);
+    assert_eq!(ids(marc(&["tail", input, "-n", "2"])), ["4", "5"]);
+    assert_eq!(ids(marc(&["tail", input, "-n", "9"])), ["1", "2", "3", "4", "5"]);
+    assert!(ids(marc(&["tail", input, "-n", "0"])).is_empty());
+
+    // Without a seekable binary file, tail reads through
+    let xml = marc(&["view", input, "--to", "xml"]).stdout;
+    assert_eq!(ids(marc_stdin(&["tail", "-", "-n", "3"], &xml)), ["3", "4", "5"]);
+    assert_eq!(ids(marc_stdin(&["head", "-", "-n", "1"], &xml)), ["1"]);
+
+    let output = marc(&["tail", input, "-n", "1", "--to", "jsonl"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap().lines().count(), 1);
+
+    // The output format follows the output file extension
+    let xml = std::env::temp_dir().join(format!("marc-cli-{}-head.xml", std::process::id()));
+    assert!(marc(&["head", input, "-n", "2", "-o", xml.to_str().unwrap()]).status.success());
+    assert_eq!(serde_marc::from_slice_many(&std::fs::read(&xml).unwrap(), FormatEncoding::marc_xml()).unwrap().len(), 2);
+    assert!(marc(&["tail", input, "-n", "1", "-o", xml.to_str().unwrap()]).status.success());
+    assert_eq!(serde_marc::from_slice_many(&std::fs::read(&xml).unwrap(), FormatEncoding::marc_xml()).unwrap()[0].control_fields, records[4].control_fields);
+    assert!(!marc(&["head", input, "-o", "head.unknown"]).status.success());
+    std::fs::remove_file(xml).unwrap();
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_cli_sample() {
+    let records: Vec<Record> = (1..=50).map(|i| record(&i.to_string(), "Title")).collect();
+    let path = write_file("sample.mrc", &records, FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8));
+    let sample = |args: &[&str]| -> Vec<u32> {
+        let output = marc(&[&["sample", path.to_str().unwrap(), "--to", "jsonl"], args].concat());
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        let records = serde_marc::from_jsonl_reader(output.stdout.as_slice()).map(|r| r.unwrap());
+        records.map(|r| r.control_fields[0].value.parse().unwrap()).collect()
+    };
+
+    // Distinct records, in file order, the same for the same seed
+    let drawn = sample(&["-n", "10", "--seed", "42"]);
+    assert_eq!(drawn.len(), 10);
+    assert!(drawn.windows(2).all(|pair| pair[0] < pair[1]));
+    assert_eq!(sample(&["-n", "10", "--seed", "42"]), drawn);
+    assert_ne!(sample(&["-n", "10", "--seed", "43"]), drawn);
+    assert_eq!(sample(&["-n", "100"]), (1..=50).collect::<Vec<u32>>());
+
+    let output = marc(&["sample", path.to_str().unwrap(), "-n", "3", "--seed", "7"]);
+    assert!(String::from_utf8(output.stderr).unwrap().contains("Sampled 3 of 50 record(s) (seed 7)"));
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_cli_sort() {
+    let mut records: Vec<Record> = ["10", "9", "x", "100", "9"].iter().zip(["The Zoo", "Apples", "Bees", "A Cat", "Dogs"]).map(|(id, title)| record(id, title)).collect();
+    records[0].data_fields[0].ind2 = '4';
+    records[3].data_fields[0].ind2 = '2';
+    records.push(Record {
+        data_fields: vec![],
+        ..record("", "")
+    });
+    records[5].control_fields.clear();
+    let path = write_file("sort.mrc", &records, FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8));
+    let input = path.to_str().unwrap();
+
+    let sorted = |args: &[&str]| -> Vec<String> {
+        let output = marc(&[&["sort", input, "--to", "jsonl"], args].concat());
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        let records = serde_marc::from_jsonl_reader(output.stdout.as_slice()).map(|r| r.unwrap());
+        records.map(|r| r.control_fields.first().map(|f| f.value.clone()).unwrap_or_default() + "/" + &r.data_fields.first().map(|f| f.subfields[0].value.clone()).unwrap_or_default()).collect()
+    };
+    // Records without a key come last; equal keys keep their order
+    assert_eq!(sorted(&[]), ["10/The Zoo", "100/A Cat", "9/Apples", "9/Dogs", "x/Bees", "/"]);
+    assert_eq!(sorted(&["--numeric"]), ["9/Apples", "9/Dogs", "10/The Zoo", "100/A Cat", "x/Bees", "/"]);
+    assert_eq!(sorted(&["--numeric", "--reverse"]), ["100/A Cat", "10/The Zoo", "9/Apples", "9/Dogs", "x/Bees", "/"]);
+    assert_eq!(sorted(&["--key", "title"]), ["9/Apples", "x/Bees", "100/A Cat", "9/Dogs", "10/The Zoo", "/"]);
+
+    // Spilling every record to disk gives the same order, without leftover files
+    let output = marc(&["sort", input, "--to", "jsonl", "--numeric", "--memory", "1", "--temp-dir", std::env::temp_dir().to_str().unwrap()]);
+    assert!(String::from_utf8(output.stderr).unwrap().contains("in 6 run(s) on disk"));
+    let spilled: Vec<Record> = serde_marc::from_jsonl_reader(output.stdout.as_slice()).map(|r| r.unwrap()).collect();
+    let titles: Vec<&str> = spilled.iter().map(|r| r.data_fields.first().map_or("", |f| f.subfields[0].value.as_str())).collect();
+    assert_eq!(titles, ["Apples", "Dogs", "The Zoo", "A Cat", "Bees", ""]);
+    assert_eq!(spilled[2].data_fields, records[0].data_fields);
+    let prefix = format!("marc-sort-{}", std::process::id());
+    assert!(!std::fs::read_dir(std::env::temp_dir()).unwrap().any(|entry| entry.unwrap().file_name().to_string_lossy().starts_with(&prefix)));
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_cli_stats() {
+    let mut decomposed = record("3", "Cafe\u{301}");
+    decomposed.leader.record_type = 'c';
+    decomposed.control_fields.push(ControlField {
+        tag: "008".to_string(),
+        value: "x".repeat(40),
+    });
+    let records = [record("1", "First"), record("2", "Second"), decomposed];
+    let path = write_file("stats.mrc", &records, FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8));
+
+    let output = marc(&["stats", path.to_str().unwrap()]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    // No progress bar when stderr is not a terminal
+    assert!(output.stderr.is_empty());
+    let text = String::from_utf8(output.stdout).unwrap();
+    let sizes: Vec<usize> = records.iter().map(|r| serde_marc::to_vec(r, FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8)).unwrap().len()).collect();
+    assert!(text.contains("Records: 3"));
+    assert!(text.contains(&format!("Record size: min {}, max {}, average {} bytes", sizes[0], sizes[2], sizes.iter().sum::<usize>() / 3)));
+    assert!(text.contains("  am         2  Books\n  cm         1  Music"));
+    assert!(text.contains("  001         3            3  Control number"));
+    assert!(text.contains("  008         1            1  Fixed-length data elements"));
+    assert!(text.contains("ENCODING WARNINGS\n  1 record(s) with values not in Unicode NFC"));
+
+    let text = String::from_utf8(marc(&["stats", path.to_str().unwrap(), "--has", "008"]).stdout).unwrap();
+    assert!(text.contains("Records: 1"));
+
+    let text = String::from_utf8(marc(&["stats", path.to_str().unwrap(), "--subfields"]).stdout).unwrap();
+    assert!(text.contains("  245         3            3  Title statement\n       ind1: '1' 3\n       ind2: '0' 3\n       $a         3            3  length 5-6, average 5\n"), "{}", text);
+
+    let output = marc(&["stats", path.to_str().unwrap(), "--format", "json"]);
+    let profile: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(profile["records"], 3);
+    assert_eq!(profile["record_types"]["am"], 2);
+    assert_eq!(profile["tags"]["001"]["lengths"]["max"], 1);
+    assert_eq!(profile["tags"]["245"]["subfields"]["a"]["occurrences"], 3);
+    assert_eq!(profile["encoding_warnings"]["not_nfc"], 1);
+
+    let report = std::env::temp_dir().join(format!("marc-cli-{}-stats.json", std::process::id()));
+    let output = marc(&["stats", path.to_str().unwrap(), "--format", "json", "--output", report.to_str().unwrap()]);
+    assert!(output.status.success() && output.stdout.is_empty());
+    let profile: serde_json::Value = serde_json::from_slice(&std::fs::read(&report).unwrap()).unwrap();
+    assert_eq!(profile["records"], 3);
+    std::fs::remove_file(report).unwrap();
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_cli_split() {
+    let format = FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8);
+    let mut records: Vec<Record> = (1..=5).map(|i| record(&i.to_string(), "Title")).collect();
+    for (record, library) in records.iter_mut().zip(["MAIN", "MAIN", "ANNEX", "MAIN"]) {
+        record.control_fields.push(ControlField {
+            tag: "003".to_string(),
+            value: library.to_string(),
+        });
+    }
+    let path = write_file("split.mrc", &records, format);
+    let dir = std::env::temp_dir().join(format!("marc-cli-{}-split", std::process::id()));
+    let read = |name: &str, format: FormatEncoding| -> Vec<String> {
+        let records = serde_marc::from_slice_many(&std::fs::read(dir.join(name)).unwrap(), format).unwrap();
+        records.iter().map(|r| r.control_fields[0].value.clone()).collect()
+    };
+
+    let output = marc(&["split", path.to_str().unwrap(), "-n", "2", "--dir", dir.to_str().unwrap(), "--prefix", "batch"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8(output.stdout).unwrap().lines().count(), 3);
+    assert!(String::from_utf8(output.stderr).unwrap().contains("Split 5 record(s) into 3 file(s)"));
+    assert_eq!(read("batch-0001.mrc", format), vec!["1", "2"]);
+    assert_eq!(read("batch-0003.mrc", format), vec!["5"]);
+
+    let output = marc(&["split", path.to_str().unwrap(), "--by", "003", "--dir", dir.to_str().unwrap(), "--prefix", "library", "--to", "xml"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(read("library-MAIN.xml", FormatEncoding::marc_xml()), vec!["1", "2", "4"]);
+    assert_eq!(read("library-ANNEX.xml", FormatEncoding::marc_xml()), vec!["3"]);
+    assert_eq!(read("library-none.xml", FormatEncoding::marc_xml()), vec!["5"]);
+
+    // Every record of a chunk is written once the size is reached
+    let size = serde_marc::to_vec(&records[0], format).unwrap().len() + 1;
+    let output = marc(&["split", path.to_str().unwrap(), "--size", &size.to_string(), "--dir", dir.to_str().unwrap(), "--prefix", "sized"]);
+    assert!(String::from_utf8(output.stderr).unwrap().contains("into 3 file(s)"));
+
+    assert!(!marc(&["split", path.to_str().unwrap()]).status.success());
+    assert!(!marc(&["split", path.to_str().unwrap(), "-n", "2", "--by", "003"]).status.success());
+    std::fs::remove_dir_all(dir).unwrap();
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_cli_merge() {
+    let mut vendor = record("v1", "Vendor copy");
+    vendor.data_fields.push(DataField {
+        tag: "035".to_string(),
+        ind1: ' ',
+        ind2: ' ',
+        subfields: vec![Subfield {
+            code: 'a',
+            value: "(OCoLC)42".to_string(),
+        }],
+    });
+    let mut duplicate = record("v2", "Duplicate");
+    duplicate.data_fields = vendor.data_fields.clone();
+    let first = write_file("merge-1.mrc", &[record("1", "First"), vendor], FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8));
+    let second = write_file("merge-2.xml", &[record("1", "Again"), duplicate, record("3", "Third")], FormatEncoding::marc_xml());
+    let output = std::env::temp_dir().join(format!("marc-cli-{}-merged.xml", std::process::id()));
+    let ids = |path: &PathBuf| -> Vec<String> {
+        let records = serde_marc::from_slice_many(&std::fs::read(path).unwrap(), FormatEncoding::marc_xml()).unwrap();
+        records.iter().map(|r| r.control_fields[0].value.clone()).collect()
+    };
+
+    let result = marc(&["merge", first.to_str().unwrap(), second.to_str().unwrap(), "-o", output.to_str().unwrap()]);
+    assert!(result.status.success(), "{}", String::from_utf8_lossy(&result.stderr));
+    assert_eq!(ids(&output), vec!["1", "v1", "1", "v2", "3"]);
+
+    let result = marc(&["merge", first.to_str().unwrap(), second.to_str().unwrap(), "-o", output.to_str().unwrap(), "--dedup"]);
+    assert!(String::from_utf8(result.stderr).unwrap().contains("Merged 3 record(s) from 2 file(s), 2 duplicate(s) dropped"));
+    assert_eq!(ids(&output), vec!["1", "v1", "3"]);
+
+    // Without an output file, records go to stdout in the format of the first input
+    let result = marc(&["merge", second.to_str().unwrap(), first.to_str().unwrap()]);
+    assert_eq!(serde_marc::from_slice_many(&result.stdout, FormatEncoding::marc_xml()).unwrap().len(), 5);
+
+    std::fs::remove_file(output).unwrap();
+    std::fs::remove_file(first).unwrap();
+    std::fs::remove_file(second).unwrap();
+}
+
+#[test]
+fn test_cli_diff() {
+    let with_005 = |mut record: Record, timestamp: &str| {
+        record.control_fields.push(ControlField {
+            tag: "005".to_string(),
+            value: timestamp.to_string(),
+        });
+        record
+    };
+    let format = FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8);
+    let old = write_file("diff-old.mrc", &[with_005(record("1", "Same"), "1"), record("2", "Old title"), record("3", "Removed")], format);
+    let new = write_file("diff-new.mrc", &[record("4", "Added"), record("2", "New title"), with_005(record("1", "Same"), "2")], format);
+    let (old, new) = (old.to_str().unwrap(), new.to_str().unwrap());
+
+    let output = marc(&["diff", old, new]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "~ 1\n    - 005  1\n    + 005  2\n~ 2\n    - 245  10$aOld title\n    + 245  10$aNew title\n- 3\n+ 4\n"
+    );
+    assert!(String::from_utf8(output.stderr).unwrap().contains("1 added, 1 removed, 2 changed, 0 unchanged"));
+
+    let output = marc(&["diff", old, new, "--ignore", "005", "--format", "json"]);
+    let lines: Vec<serde_json::Value> = output.stdout.split(|b| *b == b'\n').filter(|l| !l.is_empty()).map(|l| serde_json::from_slice(l).unwrap()).collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0]["key"], "2");
+    assert_eq!(lines[0]["changes"][0]["new"], "10$aNew title");
+    assert_eq!(lines[2]["status"], "added");
+    assert!(String::from_utf8(output.stderr).unwrap().contains("1 unchanged"));
+
+    let report = std::env::temp_dir().join(format!("marc-cli-{}-diff.txt", std::process::id()));
+    let output = marc(&["diff", old, new, "--ignore", "005", "-o", report.to_str().unwrap()]);
+    assert!(output.status.success() && output.stdout.is_empty());
+    assert_eq!(std::fs::read_to_string(&report).unwrap(), "~ 2\n    - 245  10$aOld title\n    + 245  10$aNew title\n- 3\n+ 4\n");
+    std::fs::remove_file(report).unwrap();
+
+    // A reader closing the pipe early, like `head`, ends the report normally
+    let many: Vec<Record> = (0..20000).map(|i| record(&i.to_string(), "Removed")).collect();
+    let many = write_file("diff-many.mrc", &many, format);
+    let mut child = Command::new(env!("CARGO_BIN_EXE_marc")).args(["diff", many.to_str().unwrap(), new]).env("MARC_CONFIG", "").stdout(Stdio::piped()).stderr(Stdio::piped()).spawn().unwrap();
+    drop(child.stdout.take());
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(!String::from_utf8(output.stderr).unwrap().contains("Error"));
+    std::fs::remove_file(many).unwrap();
+
+    std::fs::remove_file(old).unwrap();
+    std::fs::remove_file(new).unwrap();
+}
+
+#[test]
+fn test_cli_reencode() {
+    let utf8 = FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8);
+    let path = write_file("reencode.mrc", &[record("1", "Café"), record("2", "Ωmega")], utf8);
+    let output = std::env::temp_dir().join(format!("marc-cli-{}-reencoded.mrc", std::process::id()));
+
+    let result = marc(&["reencode", path.to_str().unwrap(), "-o", output.to_str().unwrap(), "--to", "iso8859-1"]);
+    assert!(result.status.success(), "{}", String::from_utf8_lossy(&result.stderr));
+    let stderr = String::from_utf8(result.stderr).unwrap();
+    assert!(stderr.contains("Record #2 (2): 1 character(s) cannot be encoded in iso8859-1"));
+    assert!(stderr.contains("Reencoded 2 record(s) from utf8 to iso8859-1, 1 lossy"));
+
+    let records = serde_marc::from_slice_many(&std::fs::read(&output).unwrap(), FormatEncoding::new(MarcFormat::Marc21, Encoding::Iso8859_1)).unwrap();
+    assert_eq!(records[0].data_fields[0].subfields[0].value, "Café");
+    assert_eq!(records[1].data_fields[0].subfields[0].value, "?mega");
+    assert_eq!(records[0].leader.character_coding_scheme, ' ');
+
+    let xml = write_file("reencode.xml", &[record("1", "Café")], FormatEncoding::marc_xml());
+    let result = marc(&["reencode", xml.to_str().unwrap(), "-o", output.to_str().unwrap(), "--to", "marc8"]);
+    assert!(String::from_utf8(result.stderr).unwrap().contains("only binary MARC21 and UNIMARC can be reencoded"));
+
+    std::fs::remove_file(xml).unwrap();
+    std::fs::remove_file(output).unwrap();
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_cli_watch() {
+    let dir = std::env::temp_dir().join(format!("marc-cli-{}-watch", std::process::id()));
+    let (incoming, done, converted) = (dir.join("incoming"), dir.join("done"), dir.join("converted"));
+    std::fs::create_dir_all(&incoming).unwrap();
+    std::fs::create_dir_all(&converted).unwrap();
+    let format = FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8);
+    std::fs::write(incoming.join("a.mrc"), serde_marc::to_vec(&record("1", "Title"), format).unwrap()).unwrap();
+    std::fs::write(incoming.join("notes.txt"), "not MARC").unwrap();
+
+    let target = format!("{}/{{stem}}.xml", converted.display());
+    let output = marc(&["watch", incoming.to_str().unwrap(), "--once", "--move-to", done.to_str().unwrap(), "--exec", "convert", "{}", "-o", &target, "--to", "xml"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(converted.join("a.xml").exists());
+    assert!(done.join("a.mrc").exists() && !incoming.join("a.mrc").exists());
+    assert!(incoming.join("notes.txt").exists());
+
+    // Failed files stay where they are
+    std::fs::write(incoming.join("b.mrc"), serde_marc::to_vec(&record("2", "Title"), format).unwrap()).unwrap();
+    let output = marc(&["watch", incoming.to_str().unwrap(), "--once", "--move-to", done.to_str().unwrap(), "--exec", "convert", "{}", "-o", "out.unknown"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stderr).unwrap().contains("Failed:"));
+    assert!(incoming.join("b.mrc").exists());
+
+    assert!(!marc(&["watch", incoming.to_str().unwrap(), "--once"]).status.success());
+    std::fs::remove_dir_all(dir).unwrap();
+}
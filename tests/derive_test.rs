@@ -0,0 +1,73 @@
+#[cfg(feature = "derive")]
+use marc_rs::*;
+
+#[cfg(feature = "derive")]
+#[derive(Debug, PartialEq, MarcRecord)]
+#[marc(leader = "00000nam a2200000 a 4500")]
+struct Book {
+    #[marc(tag = "001")]
+    id: String,
+    #[marc(tag = "245", subfield = "a", ind1 = "1", ind2 = "0")]
+    title: String,
+    #[marc(tag = "245", subfield = "b")]
+    subtitle: Option<String>,
+    #[marc(tag = "020", subfield = "a")]
+    isbns: Vec<String>,
+    #[marc(tag = "264", subfield = "c", ind2 = "1")]
+    year: Option<u16>,
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn test_derive_round_trip() {
+    let book = Book {
+        id: "b1".to_string(),
+        title: "Dune".to_string(),
+        subtitle: None,
+        isbns: vec!["9780441013593".to_string(), "0441013597".to_string()],
+        year: Some(1965),
+    };
+
+    let record = book.to_record();
+    assert_eq!(record.control_fields[0].value, "b1");
+    let tags: Vec<&str> = record.data_fields.iter().map(|f| f.tag.as_str()).collect();
+    assert_eq!(tags, vec!["020", "020", "245", "264"]);
+    assert_eq!((record.data_fields[2].ind1, record.data_fields[2].ind2), ('1', '0'));
+    assert_eq!(record.data_fields[3].ind2, '1');
+
+    assert_eq!(Book::from_record(&record).unwrap(), book);
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn test_derive_errors() {
+    let mut record = Record {
+        leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+        control_fields: vec![ControlField {
+            tag: "001".to_string(),
+            value: "b1".to_string(),
+        }],
+        data_fields: vec![],
+    };
+    assert_eq!(Book::from_record(&record).unwrap_err(), "missing 245$a");
+
+    record.data_fields.push(DataField {
+        tag: "245".to_string(),
+        ind1: '0',
+        ind2: '0',
+        subfields: vec![Subfield {
+            code: 'a',
+            value: "Title".to_string(),
+        }],
+    });
+    record.data_fields.push(DataField {
+        tag: "264".to_string(),
+        ind1: ' ',
+        ind2: '1',
+        subfields: vec![Subfield {
+            code: 'c',
+            value: "[1965?]".to_string(),
+        }],
+    });
+    assert!(Book::from_record(&record).unwrap_err().starts_with("264$c: cannot parse"));
+}
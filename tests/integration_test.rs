@@ -1,5 +1,15 @@
 use marc_rs::*;
 
+/// Data field with the given indicators and subfields
+fn field(tag: &str, ind1: char, ind2: char, subfields: &[(char, &str)]) -> DataField {
+    DataField {
+        tag: tag.to_string(),
+        ind1,
+        ind2,
+        subfields: subfields.iter().map(|(code, value)| Subfield { code: *code, value: value.to_string() }).collect(),
+    }
+}
+
 #[test]
 fn test_parse_empty() {
     let data = b"";
@@ -93,6 +103,19 @@ fn test_leader_to_from_bytes() {
     let parsed = Leader::from_bytes(&bytes).unwrap();
     assert_eq!(parsed.record_length, leader.record_length);
     assert_eq!(parsed.base_address_of_data, leader.base_address_of_data);
+    assert!(leader.validate().is_ok());
+
+    // Invalid numeric positions are reported instead of wrapping around
+    let error = Leader::from_bytes(b"00000nam a2x00000 a 4500").unwrap_err();
+    assert_eq!(error, "Leader subfield_code_count at position 11: expected a digit, found 'x'");
+    let error = Leader::from_bytes(b"000a0nam a2200000 a 4500").unwrap_err();
+    assert_eq!(error, "Leader record_length at position 3: expected a digit, found 'a'");
+    assert_eq!(Leader::from_bytes(b"99999nam a2200000 a 4500").unwrap().record_length, 99999);
+
+    let invalid = Leader { indicator_count: 12, ..leader.clone() };
+    assert!(invalid.validate().unwrap_err().contains("indicator_count at position 10"));
+    let invalid = Leader { encoding_level: 'é', ..leader };
+    assert!(invalid.validate().unwrap_err().contains("encoding_level at position 17"));
 }
 
 #[test]
@@ -105,11 +128,14 @@ fn test_field_enums() {
     assert_eq!(Edition::EditionStatement.tag(MarcFormat::Marc21), Some("250"));
     assert_eq!(Physical::PhysicalDescription.tag(MarcFormat::Marc21), Some("300"));
     assert_eq!(Series::SeriesStatement.tag(MarcFormat::Marc21), "490");
-    assert_eq!(Note::GeneralNote.tag(MarcFormat::Marc21), "500");
+    assert_eq!(Note::GeneralNote.tag(MarcFormat::Marc21), Some("500"));
     assert_eq!(Subject::SubjectTopicalTerm.tag(MarcFormat::Marc21), Some("650"));
-    assert_eq!(AddedEntry::PersonalName.tag(MarcFormat::Marc21), "700");
+    assert_eq!(AddedEntry::PersonalName.tag(MarcFormat::Marc21), Some("700"));
     assert_eq!(Linking::MainSeriesEntry.tag(MarcFormat::Marc21), Some("760"));
     assert_eq!(Control::ControlNumber.tag(MarcFormat::Marc21), Some("001"));
+    assert_eq!(Numbers::Isbn.tag(MarcFormat::Marc21), Some("020"));
+    assert_eq!(Holdings::Location.tag(MarcFormat::Marc21), Some("852"));
+    assert_eq!(Holdings::TextualHoldingsBasic.tag(MarcFormat::Marc21), Some("866"));
 
     // Test UNIMARC tags
     assert_eq!(MainEntry::PersonalName.tag(MarcFormat::Unimarc), "700");
@@ -118,6 +144,49 @@ fn test_field_enums() {
     assert_eq!(Physical::PhysicalDescription.tag(MarcFormat::Unimarc), Some("215"));
     assert_eq!(Series::SeriesStatement.tag(MarcFormat::Unimarc), "225");
     assert_eq!(Subject::SubjectTopicalTerm.tag(MarcFormat::Unimarc), Some("606"));
+    assert_eq!(Numbers::Isbn.tag(MarcFormat::Unimarc), Some("010"));
+    assert_eq!(Numbers::CatalogingSource.tag(MarcFormat::Unimarc), Some("801"));
+    assert_eq!(Numbers::Lccn.tag(MarcFormat::Unimarc), None);
+    assert_eq!(CodedInformation::LanguageOfResource.tag(MarcFormat::Unimarc), Some("101"));
+    assert_eq!(CodedInformation::LanguageOfResource.tag(MarcFormat::Marc21), Some("041"));
+    assert_eq!(CodedInformation::AntiquarianGeneral.tag(MarcFormat::Marc21), None);
+    assert_eq!(Holdings::ElectronicLocationAndAccess.tag(MarcFormat::Unimarc), Some("856"));
+    assert_eq!(Holdings::Location.tag(MarcFormat::Unimarc), None);
+}
+
+#[test]
+fn test_subfield_enums() {
+    // Each code a variant answers to is the code of that variant
+    macro_rules! assert_round_trip {
+        ($($subfields:ty),*) => {$(
+            let codes: Vec<char> = ('0'..='9').chain('a'..='z').filter(|c| <$subfields>::from_code(*c).is_some()).collect();
+            assert!(!codes.is_empty(), "{}", stringify!($subfields));
+            for code in codes {
+                let subfield = <$subfields>::from_code(code).unwrap();
+                assert_eq!(subfield.code(), code);
+                assert_eq!(char::from(subfield), code);
+            }
+        )*};
+    }
+    assert_round_trip!(Title245Sub, Name100Sub, Publication260Sub, Subject650Sub, Location856Sub);
+    assert_round_trip!(UnimarcTitle200Sub, UnimarcPublication210Sub, UnimarcSubject606Sub, UnimarcName700Sub);
+    assert_eq!(Title245Sub::from_code('z'), None);
+    assert_eq!(Subject650Sub::from_code('x'), Some(Subject650Sub::GeneralSubdivision));
+    assert_eq!(UnimarcName700Sub::RelatorCode.code(), '4');
+
+    let title = field("245", '1', '0', &[('a', "Dune /"), ('c', "Frank Herbert.")]);
+    assert_eq!(title.subfield(Title245Sub::StatementOfResponsibility), Some("Frank Herbert."));
+    assert_eq!(title.subfield(Title245Sub::RemainderOfTitle), None);
+    let subject = field("650", ' ', '0', &[('a', "Cats"), ('x', "Behavior"), ('x', "Psychology")]);
+    assert_eq!(subject.subfield_values(Subject650Sub::GeneralSubdivision).collect::<Vec<_>>(), ["Behavior", "Psychology"]);
+
+    let title = field("200", '1', ' ', &[('a', "Le petit prince"), ('e', "récit"), ('f', "Antoine de Saint-Exupéry")]);
+    assert_eq!(title.subfield(UnimarcTitle200Sub::TitleProper), Some("Le petit prince"));
+    assert_eq!(title.subfield(UnimarcTitle200Sub::FirstStatementOfResponsibility), Some("Antoine de Saint-Exupéry"));
+    assert_eq!(title.subfield(UnimarcTitle200Sub::ParallelTitleProper), None);
+    let name = field("700", ' ', '1', &[('a', "Saint-Exupéry"), ('b', "Antoine de"), ('4', "070")]);
+    assert_eq!(name.subfield_values(UnimarcName700Sub::PartOfName).collect::<Vec<_>>(), ["Antoine de"]);
+    assert_eq!(name.subfield(UnimarcName700Sub::RelatorCode), Some("070"));
 }
 
 #[test]
@@ -135,6 +204,54 @@ fn test_encoding_conversion() {
 }
 
 #[cfg(feature = "serde")]
+#[test]
+fn test_write_directory_terminator() {
+    let record = Record {
+        leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+        control_fields: vec![ControlField {
+            tag: "001".to_string(),
+            value: "123".to_string(),
+        }],
+        data_fields: vec![DataField {
+            tag: "245".to_string(),
+            ind1: '0',
+            ind2: '0',
+            subfields: vec![Subfield {
+                code: 'a',
+                value: "Title".to_string(),
+            }],
+        }],
+    };
+    let mut bytes = Vec::new();
+    write(&[record], FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8), &mut bytes).unwrap();
+
+    // Leader, two directory entries and the directory terminator
+    let base_address = 24 + 2 * 12 + 1;
+    assert_eq!(&bytes[12..17], format!("{:05}", base_address).as_bytes());
+    assert_eq!(bytes[base_address - 1], 0x1E);
+    assert_eq!(&bytes[base_address..base_address + 4], b"123\x1E");
+    assert_eq!(&bytes[0..5], format!("{:05}", bytes.len()).as_bytes());
+}
+
+#[test]
+fn test_parse_control_field_terminator() {
+    let record = Record {
+        leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+        control_fields: vec![ControlField {
+            tag: "001".to_string(),
+            value: "123".to_string(),
+        }],
+        data_fields: Vec::new(),
+    };
+    let format = FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8);
+    let mut bytes = Vec::new();
+    write(&[record], format, &mut bytes).unwrap();
+
+    // The field terminator used to be kept at the end of the value, as "123\x1E"
+    let parsed = parse(&bytes, format).unwrap();
+    assert_eq!(parsed[0].control_fields[0].value, "123");
+}
+
 #[test]
 fn test_serde_serialization() {
     use serde_json;
@@ -169,3 +286,2008 @@ fn test_serde_serialization() {
     let deserialized: Record = serde_json::from_str(&json).unwrap();
     assert_eq!(deserialized.control_fields[0].tag, record.control_fields[0].tag);
 }
+
+#[test]
+fn test_linkage_and_field_link_parsing() {
+    let linkage = Linkage::parse("880-01/(2/r").unwrap();
+    assert_eq!(linkage.linking_tag, "880");
+    assert_eq!(linkage.occurrence, 1);
+    assert_eq!(linkage.script, Some(ScriptCode::Hebrew));
+    assert!(linkage.right_to_left);
+    assert_eq!(linkage.to_string(), "880-01/(2/r");
+    assert!(Linkage::parse("245-00").unwrap().is_unassociated());
+    assert!(Linkage::parse("880").is_err());
+
+    let link = FieldLink::parse("1.5\\a").unwrap();
+    assert_eq!(link.link_number, 1);
+    assert_eq!(link.sequence_number, Some(5));
+    assert_eq!(link.link_type, Some(FieldLinkType::Action));
+    assert_eq!(link.to_string(), "1.5\\a");
+    assert!(FieldLink::parse("x\\c").is_err());
+
+    let field = DataField {
+        tag: "245".to_string(),
+        ind1: '1',
+        ind2: '0',
+        subfields: vec![
+            Subfield {
+                code: '6',
+                value: "880-02".to_string(),
+            },
+            Subfield {
+                code: '8',
+                value: "3\\c".to_string(),
+            },
+        ],
+    };
+    assert_eq!(field.linkage().unwrap().unwrap().occurrence, 2);
+    assert_eq!(field.field_links().unwrap()[0].link_type, Some(FieldLinkType::ConstituentItem));
+}
+
+#[test]
+fn test_record_equivalent() {
+    let leader = Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap();
+    let subject = |value: &str| DataField {
+        tag: "650".to_string(),
+        ind1: ' ',
+        ind2: '0',
+        subfields: vec![Subfield {
+            code: 'a',
+            value: value.to_string(),
+        }],
+    };
+
+    let a = Record {
+        leader: leader.clone(),
+        control_fields: vec![
+            ControlField {
+                tag: "001".to_string(),
+                value: "1".to_string(),
+            },
+            ControlField {
+                tag: "005".to_string(),
+                value: "20240101000000.0".to_string(),
+            },
+        ],
+        data_fields: vec![subject("Caf\u{e9}"), subject("History")],
+    };
+    let mut b = Record {
+        leader,
+        control_fields: vec![ControlField {
+            tag: "001".to_string(),
+            value: "1".to_string(),
+        }],
+        data_fields: vec![subject("History"), subject("Cafe\u{301}")],
+    };
+    b.leader.record_length = 123;
+
+    assert_ne!(a, b);
+    assert!(a.equivalent(&b));
+
+    b.data_fields.pop();
+    assert!(!a.equivalent(&b));
+}
+
+#[test]
+fn test_record_content_hash() {
+    use std::collections::HashSet;
+
+    let leader = Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap();
+    let a = Record {
+        leader: leader.clone(),
+        control_fields: vec![],
+        data_fields: vec![field("245", ' ', ' ', &[('a', "Title")]), field("650", ' ', ' ', &[('a', "Topic")])],
+    };
+    let b = Record {
+        leader,
+        control_fields: vec![ControlField {
+            tag: "005".to_string(),
+            value: "20240101000000.0".to_string(),
+        }],
+        data_fields: vec![field("650", ' ', ' ', &[('a', "Topic")]), field("245", ' ', ' ', &[('a', "Title")])],
+    };
+
+    assert_eq!(a.content_hash(), b.content_hash());
+    let mut c = a.clone();
+    c.data_fields[0].subfields[0].value = "Other".to_string();
+    assert_ne!(a.content_hash(), c.content_hash());
+
+    let set: HashSet<Record> = vec![a.clone(), a, c].into_iter().collect();
+    assert_eq!(set.len(), 2);
+}
+
+#[test]
+fn test_sort_title() {
+    let leader = Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap();
+    let marc21 = Record {
+        leader: leader.clone(),
+        control_fields: vec![],
+        data_fields: vec![DataField {
+            tag: "245".to_string(),
+            ind1: '1',
+            ind2: '4',
+            subfields: vec![
+                Subfield {
+                    code: 'a',
+                    value: "The old man and the sea /".to_string(),
+                },
+                Subfield {
+                    code: 'c',
+                    value: "Ernest Hemingway.".to_string(),
+                },
+            ],
+        }],
+    };
+    assert_eq!(marc21.sort_title(MarcFormat::Marc21).as_deref(), Some("old man and the sea /"));
+
+    let unimarc = Record {
+        leader,
+        control_fields: vec![],
+        data_fields: vec![DataField {
+            tag: "200".to_string(),
+            ind1: '1',
+            ind2: ' ',
+            subfields: vec![Subfield {
+                code: 'a',
+                value: "\u{98}Le \u{9c}petit prince".to_string(),
+            }],
+        }],
+    };
+    assert_eq!(unimarc.sort_title(MarcFormat::Unimarc).as_deref(), Some("petit prince"));
+    assert_eq!(strip_non_sorting("\u{88}The\u{89} Hobbit"), " Hobbit");
+}
+
+#[test]
+fn test_match_key() {
+    let records = parse_mrk(
+        "=LDR  00000nam a2200000 a 4500\n=008  800108s1999    fr            000 0 fre d\n=100  1\\$aDurand, Élise\n=245  13$aLa Cuisine française\n\n\
+         =LDR  00000nam a2200000 a 4500\n=245  00$aCuisine francaise /$cÉ. Durand.\n=264  \\1$aParis :$bEd.,$c[c1999]\n\n\
+         =LDR  00000nam a2200000 a 4500\n=100  1\\$aDurand, Elise\n",
+    )
+    .unwrap();
+    assert_eq!(records[0].match_key(MarcFormat::Marc21).as_deref(), Some("cuisinefrancaise/durandelise/1999"));
+    // The year falls back to the publication statement
+    assert_eq!(records[1].match_key(MarcFormat::Marc21).as_deref(), Some("cuisinefrancaise//1999"));
+    assert_eq!(records[2].match_key(MarcFormat::Marc21), None);
+    assert_eq!(match_text("Ça, c'est l'été ½"), "cacestlete12");
+}
+
+#[test]
+fn test_isbd_punctuation_stripping() {
+    assert_eq!(strip_isbd_punctuation("The old man and the sea /"), "The old man and the sea");
+    assert_eq!(strip_isbd_punctuation("Proceedings :"), "Proceedings");
+    assert_eq!(strip_isbd_punctuation("Smith, J."), "Smith, J.");
+    assert_eq!(strip_isbd_punctuation("Hemingway, Ernest,"), "Hemingway, Ernest");
+    assert_eq!(strip_isbd_punctuation("Collected works."), "Collected works");
+    assert_eq!(strip_isbd_punctuation_for_tag("300", "xii, 345 p. ;"), "xii, 345 p.");
+    assert_eq!(strip_isbd_punctuation_for_tag("260", "2001."), "2001");
+
+    let field = DataField {
+        tag: "245".to_string(),
+        ind1: '1',
+        ind2: '0',
+        subfields: vec![Subfield {
+            code: 'a',
+            value: "Dune :".to_string(),
+        }],
+    };
+    assert_eq!(field.stripped_subfield('a'), Some("Dune"));
+    assert_eq!(field.stripped_subfield('b'), None);
+}
+
+#[test]
+fn test_ordinal_access() {
+    let subject = |values: &[&str]| DataField {
+        tag: "650".to_string(),
+        ind1: ' ',
+        ind2: '0',
+        subfields: values
+            .iter()
+            .map(|v| Subfield {
+                code: 'a',
+                value: v.to_string(),
+            })
+            .collect(),
+    };
+    let record = Record {
+        leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+        control_fields: vec![],
+        data_fields: vec![subject(&["History"]), subject(&["Science", "Physics"])],
+    };
+
+    let second = record.nth_field("650", 1).unwrap();
+    assert_eq!(second.nth_subfield('a', 0).unwrap().value, "Science");
+    assert_eq!(second.nth_subfield('a', 1).unwrap().value, "Physics");
+    assert!(second.nth_subfield('a', 2).is_none());
+    assert!(record.nth_field("650", 2).is_none());
+    assert!(record.nth_field("245", 0).is_none());
+}
+
+#[test]
+fn test_join_subfields() {
+    let field = DataField {
+        tag: "245".to_string(),
+        ind1: '1',
+        ind2: '0',
+        subfields: vec![
+            Subfield {
+                code: 'a',
+                value: "Dune :".to_string(),
+            },
+            Subfield {
+                code: 'b',
+                value: "a novel /".to_string(),
+            },
+            Subfield {
+                code: 'c',
+                value: "Frank Herbert.".to_string(),
+            },
+        ],
+    };
+    assert_eq!(field.join_subfields(&['a', 'b'], " "), "Dune : a novel /");
+    assert_eq!(field.join_subfields(&['a', 'z', 'c'], " "), "Dune : Frank Herbert.");
+    assert_eq!(field.join_subfields(&['z'], " "), "");
+
+    let record = Record {
+        leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+        control_fields: vec![],
+        data_fields: vec![field],
+    };
+    assert_eq!(record.join("245", &['a', 'b', 'c'], " ").as_deref(), Some("Dune : a novel / Frank Herbert."));
+    assert_eq!(record.join("245", &['n'], " "), None);
+    assert_eq!(record.join("250", &['a'], " "), None);
+}
+
+#[test]
+fn test_record_projection() {
+    let record = Record {
+        leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+        control_fields: vec![ControlField {
+            tag: "001".to_string(),
+            value: "123".to_string(),
+        }],
+        data_fields: vec![field("245", ' ', ' ', &[('a', "value")]), field("650", ' ', ' ', &[('a', "value")]), field("949", ' ', ' ', &[('a', "value")])],
+    };
+
+    let brief = record.project(&["001", "245"]);
+    assert_eq!(brief.control_fields.len(), 1);
+    assert_eq!(brief.data_fields.len(), 1);
+    assert_eq!(brief.leader.base_address_of_data, 24 + 2 * 12 + 1);
+
+    let exchange = record.without(&["949"]);
+    assert_eq!(exchange.data_fields.len(), 2);
+
+    let bytes = helpers::to_vec(&exchange, FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8)).unwrap();
+    assert_eq!(exchange.leader.record_length as usize, bytes.len());
+
+    // Lengths up to the five leader digits are kept, longer ones saturate
+    let mut long = record.clone();
+    long.data_fields[0].subfields[0].value = "x".repeat(9000);
+    long.data_fields.extend(vec![long.data_fields[0].clone(); 8]);
+    let long = long.without(&["949"]);
+    let bytes = helpers::to_vec(&long, FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8)).unwrap();
+    assert!(bytes.len() > 65535);
+    assert_eq!(long.leader.record_length as usize, bytes.len());
+    assert_eq!(parse(&bytes, FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8)).unwrap()[0].leader.record_length as usize, bytes.len());
+
+    let mut too_long = long.clone();
+    too_long.data_fields.extend(vec![too_long.data_fields[0].clone(); 4]);
+    too_long.update_leader_lengths();
+    assert_eq!(too_long.leader.record_length, 99999);
+    assert!(matches!(helpers::to_vec(&too_long, FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8)), Err(WriteError::InvalidRecord(_))));
+}
+
+#[test]
+fn test_control_field_positions() {
+    let field = ControlField {
+        tag: "008".to_string(),
+        value: "850101s1984    fr é    b     001 0 fre d".to_string(),
+    };
+    assert_eq!(field.chars(0..6).unwrap(), "850101");
+    assert_eq!(field.char_at(6).unwrap(), 's');
+    assert_eq!(field.chars(7..=10).unwrap(), "1984");
+    assert_eq!(field.char_at(18).unwrap(), 'é');
+    assert_eq!(field.chars(18..20).unwrap(), "é ");
+    assert_eq!(field.chars(35..).unwrap(), "fre d");
+    assert!(field.chars(35..50).is_err());
+    assert!(field.char_at(40).is_err());
+}
+
+#[test]
+fn test_unimarc_general_processing_data() {
+    let value = "19980402d1993    m  y1frea0103    ba";
+    let data = GeneralProcessingData::parse(value).unwrap();
+    assert_eq!(data.date_entered, "19980402");
+    assert_eq!(data.publication_date_type, PublicationDateType::Monograph);
+    assert_eq!(data.date1_year(), Some(1993));
+    assert_eq!(data.date2_year(), None);
+    assert_eq!(data.target_audience, "m  ");
+    assert_eq!(data.cataloguing_language, "fre");
+    assert_eq!(data.character_sets, "0103");
+    assert_eq!(data.script_of_title, "ba");
+    assert_eq!(data.to_string(), value);
+
+    let record = Record {
+        leader: Leader::from_bytes(b"00000cam  2200000   4500").unwrap(),
+        control_fields: vec![],
+        data_fields: vec![data.to_field()],
+    };
+    assert_eq!(GeneralProcessingData::from_record(&record).unwrap().unwrap(), data);
+    assert!(GeneralProcessingData::parse("19980402z1993").is_err());
+}
+
+#[test]
+fn test_electronic_location() {
+    let field = DataField {
+        tag: "856".to_string(),
+        ind1: '4',
+        ind2: '0',
+        subfields: vec![
+            Subfield {
+                code: 'u',
+                value: "https://doi.org/10.11647/OBP.0001".to_string(),
+            },
+            Subfield {
+                code: 'y',
+                value: "Full text".to_string(),
+            },
+            Subfield {
+                code: 'z',
+                value: "Open access".to_string(),
+            },
+        ],
+    };
+    let record = Record {
+        leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+        control_fields: vec![],
+        data_fields: vec![field.clone()],
+    };
+
+    let locations = record.electronic_locations();
+    assert_eq!(locations.len(), 1);
+    let location = &locations[0];
+    assert_eq!(location.url(), Some("https://doi.org/10.11647/OBP.0001"));
+    assert_eq!(location.link_text.as_deref(), Some("Full text"));
+    assert_eq!(location.access_method, AccessMethod::Http);
+    assert_eq!(location.relationship, Relationship::Resource);
+    assert_eq!(location.to_field(), field);
+}
+
+#[test]
+fn test_authority_field_enums() {
+    assert_eq!(AuthorityHeading::TopicalTerm.tag(MarcFormat::Marc21), Some("150"));
+    assert_eq!(AuthorityHeading::TopicalTerm.tag(MarcFormat::Unimarc), Some("250"));
+    assert_eq!(AuthorityHeading::ChronologicalTerm.tag(MarcFormat::Unimarc), None);
+    assert_eq!(AuthorityTracing::SeeFromGeographicName.tag(MarcFormat::Marc21), Some("451"));
+    assert_eq!(AuthorityTracing::SeeAlsoFromGeographicName.tag(MarcFormat::Unimarc), Some("515"));
+    assert_eq!(AuthorityNote::SourceDataFound.tag(MarcFormat::Marc21), Some("670"));
+    assert_eq!(AuthorityNote::SourceDataFound.tag(MarcFormat::Unimarc), Some("810"));
+}
+
+#[test]
+fn test_identify_field_kind() {
+    assert_eq!(fields::identify("245", MarcFormat::Marc21), Some(FieldKind::Title(Title::TitleStatement)));
+    assert_eq!(fields::identify("245", MarcFormat::MarcXml), Some(FieldKind::Title(Title::TitleStatement)));
+    assert_eq!(fields::identify("200", MarcFormat::Unimarc), Some(FieldKind::Title(Title::TitleStatement)));
+    assert_eq!(fields::identify("100", MarcFormat::Marc21), Some(FieldKind::MainEntry(MainEntry::PersonalName)));
+    assert_eq!(
+        fields::identify("100", MarcFormat::Unimarc),
+        Some(FieldKind::CodedInformation(CodedInformation::GeneralProcessingData))
+    );
+    assert_eq!(fields::identify("041", MarcFormat::Marc21), Some(FieldKind::Numbers(Numbers::LanguageCode)));
+    assert_eq!(fields::identify("999", MarcFormat::Marc21), None);
+
+    for kind in FieldKind::all(MarcFormat::Marc21) {
+        if let Some(tag) = kind.tag(MarcFormat::Marc21) {
+            assert!(fields::identify(tag, MarcFormat::Marc21).is_some());
+        }
+    }
+}
+
+#[test]
+fn test_field_repeatability() {
+    assert_eq!(Title::TitleStatement.is_repeatable(MarcFormat::Marc21), Some(false));
+    assert_eq!(Title::TitleStatement.is_repeatable(MarcFormat::Unimarc), Some(false));
+    assert_eq!(Subject::SubjectTopicalTerm.is_repeatable(MarcFormat::Marc21), Some(true));
+    assert_eq!(Control::ControlNumber.is_repeatable(MarcFormat::Marc21), Some(false));
+    assert_eq!(Control::FixedLengthDataElementsAdditional.is_repeatable(MarcFormat::Unimarc), None);
+    assert_eq!(MainEntry::PersonalName.is_repeatable(MarcFormat::Unimarc), Some(false));
+    assert_eq!(AddedEntry::PersonalName.is_repeatable(MarcFormat::Marc21), Some(true));
+    assert!(!fields::is_repeatable("245", MarcFormat::Marc21));
+    assert!(fields::is_repeatable("949", MarcFormat::Marc21));
+}
+
+#[test]
+fn test_field_indicators() {
+    let title = Title::TitleStatement.indicators(MarcFormat::Marc21).unwrap();
+    assert!(title.first.is_valid('1'));
+    assert!(!title.first.is_valid(' '));
+    assert_eq!(title.second.label, "Nonfiling characters");
+    assert!(('0'..='9').all(|c| title.second.is_valid(c)));
+
+    let title = Title::TitleStatement.indicators(MarcFormat::Unimarc).unwrap();
+    assert_eq!(title.first.meaning('1'), Some("Title is significant"));
+    assert!(title.second.is_undefined());
+
+    assert_eq!(fields::indicators("264", MarcFormat::Marc21).unwrap().second.meaning('4'), Some("Copyright notice date"));
+    assert!(fields::indicators("949", MarcFormat::Marc21).is_none());
+    assert!(Control::FixedLengthDataElementsAdditional.indicators(MarcFormat::Unimarc).is_none());
+
+    // Every data field behind a field enum has its indicators described
+    for format in [MarcFormat::Marc21, MarcFormat::Unimarc] {
+        for kind in FieldKind::all(format) {
+            if let Some(tag) = kind.tag(format).filter(|tag| !tag.starts_with("00")) {
+                assert!(kind.indicators(format).is_some(), "{:?} has no indicators for {}", format, tag);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_field_status() {
+    assert_eq!(Series::SeriesTitle.status(MarcFormat::Marc21), Some(FieldStatus::Obsolete));
+    assert_eq!(Series::SeriesStatement.status(MarcFormat::Marc21), Some(FieldStatus::Current));
+    assert_eq!(Series::SeriesTitle.status(MarcFormat::Unimarc), Some(FieldStatus::Current));
+    assert_eq!(fields::field_status("949", MarcFormat::Marc21), FieldStatus::Local);
+    assert_eq!(fields::field_status("590", MarcFormat::Marc21), FieldStatus::Local);
+    assert_eq!(fields::field_status("490", MarcFormat::Marc21), FieldStatus::Current);
+    assert_eq!(fields::field_status("195", MarcFormat::Unimarc), FieldStatus::Local);
+}
+
+#[test]
+fn test_note_unimarc_mapping() {
+    assert_eq!(Note::GeneralNote.tag(MarcFormat::Unimarc), Some("300"));
+    assert_eq!(Note::BibliographyNote.tag(MarcFormat::Unimarc), Some("320"));
+    assert_eq!(Note::FormattedContentsNote.tag(MarcFormat::Unimarc), Some("327"));
+    assert_eq!(Note::Summary.tag(MarcFormat::Unimarc), Some("330"));
+    assert_eq!(Note::WithNote.tag(MarcFormat::Unimarc), None);
+    assert_eq!(Note::Summary.tag(MarcFormat::Marc21), Some("520"));
+
+    // Round trip through the reverse lookup
+    for note in Note::ALL {
+        if let Some(tag) = note.tag(MarcFormat::Unimarc) {
+            let kind = fields::identify(tag, MarcFormat::Unimarc);
+            assert!(matches!(kind, Some(FieldKind::Note(_))), "{} identified as {:?}", tag, kind);
+        }
+    }
+    assert_eq!(fields::identify("330", MarcFormat::Unimarc), Some(FieldKind::Note(Note::Summary)));
+}
+
+#[test]
+fn test_relators() {
+    assert_eq!(Relator::from_code("aut"), Some(Relator::Author));
+    assert_eq!(Relator::from_code("http://id.loc.gov/vocabulary/relators/trl"), Some(Relator::Translator));
+    assert_eq!(Relator::from_unimarc_code("070"), Some(Relator::Author));
+    assert_eq!(Relator::from_term("Illustrator."), Some(Relator::Illustrator));
+    assert_eq!(Relator::Editor.unimarc_code(), Some("340"));
+    assert_eq!(Relator::Contributor.unimarc_code(), None);
+    assert_eq!(Relator::Actor.unimarc_code(), Some("005"));
+    assert_eq!(Relator::from_unimarc_code("018"), Some(Relator::Animator));
+    assert_eq!(Relator::Translator.label(), "Translator");
+
+    let field = DataField {
+        tag: "700".to_string(),
+        ind1: '1',
+        ind2: ' ',
+        subfields: vec![
+            Subfield { code: 'a', value: "Doe, Jane,".to_string() },
+            Subfield { code: 'e', value: "editor,".to_string() },
+            Subfield { code: 'e', value: "translator.".to_string() },
+            Subfield { code: '4', value: "edt".to_string() },
+            Subfield { code: '4', value: "xyz".to_string() },
+        ],
+    };
+    assert_eq!(field.relators(), vec![Relator::Editor, Relator::Translator]);
+
+    let field = DataField {
+        tag: "701".to_string(),
+        ind1: ' ',
+        ind2: '1',
+        subfields: vec![Subfield { code: 'a', value: "Dupont".to_string() }, Subfield { code: '4', value: "440".to_string() }],
+    };
+    assert_eq!(field.relators(), vec![Relator::Illustrator]);
+}
+
+#[test]
+fn test_language_codes() {
+    assert_eq!(Language::name_of("fre"), Some("French"));
+    assert_eq!(Language::iso639_1_of("ger"), Some("de"));
+    assert_eq!(Language::from_iso639_1("en").map(|l| l.code), Some("eng"));
+    assert!(Language::is_valid_code("ENG"));
+    assert!(!Language::is_valid_code("scr"));
+    assert_eq!(Language::discontinued("scr").and_then(|l| l.replaced_by), Some("hrv"));
+
+    let mut record = Record {
+        leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+        control_fields: vec![ControlField { tag: "008".to_string(), value: "850101s1985    fr a          000 0 fre d".to_string() }],
+        data_fields: vec![DataField {
+            tag: "041".to_string(),
+            ind1: '1',
+            ind2: ' ',
+            subfields: vec![Subfield { code: 'a', value: "freeng".to_string() }, Subfield { code: 'h', value: "ger".to_string() }],
+        }],
+    };
+    assert_eq!(record.language_codes(MarcFormat::Marc21), vec!["fre", "eng"]);
+
+    record.data_fields = vec![DataField {
+        tag: "101".to_string(),
+        ind1: '1',
+        ind2: ' ',
+        subfields: vec![Subfield { code: 'a', value: "fre".to_string() }, Subfield { code: 'c', value: "eng".to_string() }],
+    }];
+    assert_eq!(record.language_codes(MarcFormat::Unimarc), vec!["fre"]);
+}
+
+#[test]
+fn test_geographic_area_codes() {
+    let code = GeographicAreaCode::parse("n-us-ny").unwrap();
+    assert_eq!(code.continent(), "n------");
+    assert_eq!(code.region(), None);
+    assert_eq!(code.country().as_deref(), Some("n-us---"));
+    assert_eq!(code.hierarchy(), vec!["n------", "n-us---", "n-us-ny"]);
+    assert_eq!(code.name(), Some("New York"));
+
+    let code = GeographicAreaCode::parse("nwcu").unwrap();
+    assert_eq!(code.code, "nwcu---");
+    assert_eq!(code.hierarchy(), vec!["n------", "nw-----", "nwcu---"]);
+    assert_eq!(geographic_area_name("nw-----"), Some("West Indies"));
+
+    assert_eq!(GeographicAreaCode::parse("e------").unwrap().hierarchy(), vec!["e------"]);
+    assert!(GeographicAreaCode::parse("N-US-NY").is_err());
+    assert!(GeographicAreaCode::parse("n-usny").is_err());
+
+    // Regions within a country
+    let code = GeographicAreaCode::parse("n-usn").unwrap();
+    assert_eq!(code.hierarchy(), vec!["n------", "n-us---", "n-usn--"]);
+    assert_eq!(code.name(), Some("New England"));
+    assert!(GeographicAreaCode::parse("n-usnny").is_err());
+    assert_eq!(geographic_area_name("a-ko"), Some("Korea (South)"));
+    assert_eq!(geographic_area_name("u-at-tm"), Some("Tasmania"));
+
+    let record = Record {
+        leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+        control_fields: vec![],
+        data_fields: vec![DataField {
+            tag: "043".to_string(),
+            ind1: ' ',
+            ind2: ' ',
+            subfields: vec![Subfield { code: 'a', value: "e-fr---".to_string() }, Subfield { code: 'a', value: "bogus!".to_string() }],
+        }],
+    };
+    let areas = record.geographic_areas();
+    assert_eq!(areas.len(), 1);
+    assert_eq!(areas[0].name(), Some("France"));
+}
+
+#[test]
+fn test_rda_types() {
+    assert_eq!(ContentType::from_code("txt"), Some(ContentType::Text));
+    assert_eq!(MediaType::from_term("Unmediated"), Some(MediaType::Unmediated));
+    assert_eq!(CarrierType::OnlineResource.code(), "cr");
+    assert_eq!(CarrierType::FilmReel.media_type(), MediaType::Projected);
+    assert_eq!(CarrierType::SOURCE, "rdacarrier");
+
+    let mut record = Record {
+        leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+        control_fields: vec![ControlField { tag: "007".to_string(), value: "cr |||||||||||".to_string() }],
+        data_fields: vec![],
+    };
+    assert_eq!(record.content_types(), vec![ContentType::Text]);
+    assert_eq!(record.media_types(), vec![MediaType::Computer]);
+    assert_eq!(record.carrier_types(), vec![CarrierType::OnlineResource]);
+
+    record.data_fields.push(DataField {
+        tag: "336".to_string(),
+        ind1: ' ',
+        ind2: ' ',
+        subfields: vec![Subfield { code: 'a', value: "still image".to_string() }, Subfield { code: '2', value: "rdacontent".to_string() }],
+    });
+    record.data_fields.push(DataField {
+        tag: "338".to_string(),
+        ind1: ' ',
+        ind2: ' ',
+        subfields: vec![Subfield { code: 'a', value: "volume".to_string() }, Subfield { code: 'b', value: "nc".to_string() }],
+    });
+    assert_eq!(record.content_types(), vec![ContentType::StillImage]);
+    assert_eq!(record.carrier_types(), vec![CarrierType::Volume]);
+
+    // Fields from other vocabularies are not read as RDA terms
+    record.data_fields.retain(|f| f.tag != "336");
+    record.data_fields.push(field("336", ' ', ' ', &[('a', "text"), ('b', "txt"), ('2', "isbdcontent")]));
+    record.data_fields.push(field("337", ' ', ' ', &[('a', "video"), ('2', "isbdmedia")]));
+    record.leader = Leader::from_bytes(b"00000nkm a2200000 a 4500").unwrap();
+    assert_eq!(record.content_types(), vec![ContentType::StillImage]);
+    assert_eq!(record.media_types(), vec![MediaType::Computer]);
+    record.data_fields.push(field("336", ' ', ' ', &[('a', "cartographic image"), ('2', "RDAcontent")]));
+    assert_eq!(record.content_types(), vec![ContentType::CartographicImage]);
+}
+
+#[test]
+fn test_local_field_registry() {
+    let mut registry = LocalFieldRegistry::new();
+    registry
+        .register(LocalField::new("949", "Local holdings").subfield('a', "Call number", false).subfield('i', "Barcode", true))
+        .register(LocalField::new("999", "System number").repeatable(false).subfield('c', "Record id", false));
+
+    assert!(!registry.is_repeatable("999", MarcFormat::Marc21));
+    assert!(!registry.is_repeatable("245", MarcFormat::Marc21));
+    assert_eq!(registry.label("999"), Some("System number"));
+    assert_eq!(registry.field_label("999", MarcFormat::Marc21), Some("System number"));
+    assert_eq!(registry.field_label("650", MarcFormat::Marc21), Some("Subject added entry - Topical term"));
+    assert_eq!(registry.field_label("998", MarcFormat::Marc21), None);
+    assert_eq!(registry.identify("949", MarcFormat::Marc21), Some(FieldIdentity::Local(registry.get("949").unwrap())));
+    assert_eq!(registry.identify("245", MarcFormat::Marc21), Some(FieldIdentity::Standard(FieldKind::Title(Title::TitleStatement))));
+    assert_eq!(registry.identify("998", MarcFormat::Marc21), None);
+
+    let record = Record {
+        leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+        control_fields: vec![],
+        data_fields: vec![
+            field("949", ' ', ' ', &[('a', "QA76"), ('a', "QA77"), ('i', "001"), ('i', "002"), ('z', "?")]),
+            field("999", ' ', ' ', &[('c', "1")]),
+            field("999", ' ', ' ', &[('c', "2")]),
+            field("998", ' ', ' ', &[('a', "x")]),
+        ],
+    };
+    assert_eq!(
+        registry.check_record(&record, MarcFormat::Marc21),
+        vec![
+            "949: subfield $a is not repeatable",
+            "949: undefined subfield $z",
+            "999: field is not repeatable",
+            "998: local field is not defined",
+        ]
+    );
+    assert_eq!(registry.describe(&record.data_fields[1]).unwrap(), "999 System number [  ]\n  $c Record id: 1");
+}
+
+#[test]
+fn test_documentation_url() {
+    assert_eq!(
+        FieldKind::Subject(Subject::SubjectTopicalTerm).documentation_url(MarcFormat::Marc21).as_deref(),
+        Some("https://www.loc.gov/marc/bibliographic/bd650.html")
+    );
+    assert_eq!(fields::documentation_url("949", MarcFormat::Marc21).as_deref(), Some("https://www.loc.gov/marc/bibliographic/bd9xx.html"));
+    assert_eq!(Title::TitleStatement.documentation_url(MarcFormat::Unimarc), None);
+    assert_eq!(Control::FixedLengthDataElementsAdditional.documentation_url(MarcFormat::Unimarc), None);
+}
+
+#[test]
+fn test_subject_source() {
+    assert_eq!(Subject::source(&field("650", ' ', '0', &[('a', "Chats")]), MarcFormat::Marc21), Some(SubjectSource::Lcsh));
+    assert_eq!(Subject::source(&field("650", ' ', '2', &[('a', "Chats")]), MarcFormat::Marc21), Some(SubjectSource::Mesh));
+    assert_eq!(Subject::source(&field("655", ' ', '7', &[('a', "Chats"), ('2', "lcgft")]), MarcFormat::Marc21), Some(SubjectSource::Lcgft));
+    assert_eq!(Subject::source(&field("650", ' ', '7', &[('a', "Chats"), ('2', "local")]), MarcFormat::Marc21), Some(SubjectSource::Other("local".to_string())));
+    assert_eq!(Subject::source(&field("653", ' ', ' ', &[('a', "Chats")]), MarcFormat::Marc21), None);
+    assert_eq!(Subject::source(&field("606", ' ', ' ', &[('a', "Chats"), ('2', "rameau")]), MarcFormat::Unimarc), Some(SubjectSource::Rameau));
+    assert_eq!(Subject::source(&field("600", ' ', '1', &[('a', "Chats")]), MarcFormat::Unimarc), None);
+    assert_eq!(SubjectSource::Mesh.indicator(), '2');
+    assert_eq!(SubjectSource::Gnd.indicator(), '7');
+}
+
+#[test]
+fn test_unimarc_responsibility() {
+    assert_eq!(MainEntry::MeetingName.tag(MarcFormat::Unimarc), "710");
+    assert_eq!(MainEntry::UniformTitle.tag(MarcFormat::Unimarc), "500");
+    assert_eq!(AddedEntry::PersonalName.tag(MarcFormat::Unimarc), Some("701"));
+    assert_eq!(AddedEntry::UncontrolledName.tag(MarcFormat::Unimarc), None);
+
+    let translator = field("702", ' ', '1', &[('a', "Dupont"), ('b', "Jean"), ('4', "730")]);
+    assert_eq!(Responsibility::of_field(&translator, MarcFormat::Unimarc), Some(Responsibility::Secondary));
+    assert_eq!(translator.relators(), vec![Relator::Translator]);
+    assert_eq!(MainEntry::entity_of(&translator, MarcFormat::Unimarc), Some(MainEntry::PersonalName));
+
+    let meeting = field("711", '1', '1', &[('a', "Colloque")]);
+    assert_eq!(MainEntry::entity_of(&meeting, MarcFormat::Unimarc), Some(MainEntry::MeetingName));
+    assert_eq!(Responsibility::of_field(&meeting, MarcFormat::Unimarc), Some(Responsibility::Alternative));
+
+    let editor = field("700", '1', '1', &[('a', "Doe, Jane,"), ('e', "editor.")]);
+    let coauthor = field("700", '1', '1', &[('a', "Roe, Richard.")]);
+    assert_eq!(Responsibility::of_field(&editor, MarcFormat::Marc21), Some(Responsibility::Secondary));
+    assert_eq!(Responsibility::of_field(&coauthor, MarcFormat::Marc21), Some(Responsibility::Alternative));
+    assert_eq!(Responsibility::Secondary.unimarc_tag(MainEntry::PersonalName), Some("702"));
+    assert_eq!(Responsibility::Primary.unimarc_tag(MainEntry::MeetingName), Some("710"));
+
+    // Family names round-trip through their 72X tags
+    let family = field("721", ' ', ' ', &[('a', "Bach"), ('f', "1600-1800")]);
+    let entity = MainEntry::entity_of(&family, MarcFormat::Unimarc).unwrap();
+    let level = Responsibility::of_field(&family, MarcFormat::Unimarc).unwrap();
+    assert_eq!((entity, level), (MainEntry::FamilyName, Responsibility::Alternative));
+    assert_eq!(level.unimarc_tag(entity), Some("721"));
+    assert_eq!(MainEntry::entity_of(&field("700", '3', ' ', &[('a', "Bach family.")]), MarcFormat::Marc21), Some(MainEntry::FamilyName));
+}
+
+#[test]
+fn test_field_definitions() {
+    let title = Title::TitleStatement.definition(MarcFormat::Marc21).unwrap();
+    assert_eq!(title.tag, "245");
+    assert_eq!(title.name, "Title statement");
+    assert!(!title.repeatable);
+    assert_eq!(title.subfield('a').map(|s| s.name), Some("Title"));
+    assert!(title.subfield('n').unwrap().repeatable);
+    // Names of the capitalized LoC field list are sentence-cased
+    assert_eq!(fields::field_definition("100", MarcFormat::Marc21).unwrap().name, "Main entry - Personal name");
+    assert_eq!(fields::field_definition("010", MarcFormat::Marc21).unwrap().name, "Library of Congress control number");
+
+    let title = fields::field_definition("200", MarcFormat::Unimarc).unwrap();
+    assert_eq!(title.subfield('f').map(|s| s.name), Some("First statement of responsibility"));
+
+    let tags: Vec<_> = fields::field_definitions(MarcFormat::Marc21).iter().map(|d| d.tag).collect();
+    assert!(tags.windows(2).all(|w| w[0] < w[1]));
+    assert!(fields::field_definition("949", MarcFormat::Marc21).is_none());
+    assert!(!fields::is_repeatable("310", MarcFormat::Marc21));
+}
+
+#[test]
+fn test_marc21_field_list_coverage() {
+    let definitions = fields::field_definitions(MarcFormat::Marc21);
+    assert_eq!(definitions.len(), 270);
+    for tag in ["072", "341", "347", "532", "758", "883"] {
+        assert!(fields::field_definition(tag, MarcFormat::Marc21).is_some(), "{} is not defined", tag);
+    }
+    for definition in definitions {
+        let obsolete = fields::field_status(definition.tag, MarcFormat::Marc21) == FieldStatus::Obsolete;
+        if definition.tag < "010" || definition.tag == "880" || obsolete {
+            continue;
+        }
+        assert!(fields::indicators(definition.tag, MarcFormat::Marc21).is_some(), "{} has no indicators", definition.tag);
+        assert!(!definition.subfields.is_empty(), "{} has no subfields", definition.tag);
+    }
+
+    // Obsolete fields are listed, and their status agrees with the list
+    for tag in ["400", "410", "411", "440", "755"] {
+        assert!(fields::field_definition(tag, MarcFormat::Marc21).is_some());
+        assert_eq!(fields::field_status(tag, MarcFormat::Marc21), FieldStatus::Obsolete);
+    }
+    assert_eq!(fields::field_status("490", MarcFormat::Marc21), FieldStatus::Current);
+}
+
+#[test]
+fn test_call_numbers() {
+    let record = Record {
+        leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+        control_fields: vec![],
+        data_fields: vec![
+            field("050", '0', '0', &[('a', "QA76.73.R87"), ('b', "B56 2020")]),
+            field("082", '0', '0', &[('a', "005.13/3"), ('2', "23")]),
+            field("090", '0', '0', &[('a', "PS3545.I345 Z5 1990")]),
+            field("245", '0', '0', &[('a', "Title")]),
+        ],
+    };
+
+    let numbers = record.call_numbers(MarcFormat::Marc21);
+    assert_eq!(numbers.len(), 3);
+    assert_eq!(numbers[0].to_string(), "QA76.73.R87 B56 2020");
+    assert_eq!(numbers[1].class, "005.133");
+    assert_eq!(numbers[1].source.as_deref(), Some("23"));
+    assert_eq!(numbers[2].class, "PS3545.I345");
+    assert_eq!(numbers[2].item.as_deref(), Some("Z5 1990"));
+    assert_eq!(record.class_numbers(Classification::DeweyDecimal, MarcFormat::Marc21), vec!["005.133"]);
+
+    let dewey = field("676", '0', '0', &[('a', "944.04"), ('v', "22")]);
+    let number = CallNumber::from_field(&dewey, MarcFormat::Unimarc).unwrap();
+    assert_eq!(number.scheme, Classification::DeweyDecimal);
+    assert_eq!(number.source.as_deref(), Some("22"));
+    assert_eq!(Classification::UniversalDecimal.tag(MarcFormat::Unimarc), Some("675"));
+}
+
+#[test]
+fn test_publication_statement() {
+    let subfield = |code: char, value: &str| Subfield { code, value: value.to_string() };
+    let mut record = Record {
+        leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+        control_fields: vec![],
+        data_fields: vec![
+            DataField {
+                tag: "245".to_string(),
+                ind1: '1',
+                ind2: '0',
+                subfields: vec![subfield('a', "Title.")],
+            },
+            DataField {
+                tag: "260".to_string(),
+                ind1: ' ',
+                ind2: ' ',
+                subfields: vec![subfield('a', "New York :"), subfield('b', "Norton,"), subfield('c', "2001.")],
+            },
+            DataField {
+                tag: "264".to_string(),
+                ind1: ' ',
+                ind2: '4',
+                subfields: vec![subfield('c', "©2000")],
+            },
+        ],
+    };
+
+    let statements = record.publication_statements(MarcFormat::Marc21);
+    assert_eq!(statements.len(), 2);
+    assert_eq!(statements[1].function, PublicationFunction::Copyright);
+    let publication = record.publication(MarcFormat::Marc21).unwrap();
+    assert_eq!(publication.place(), Some("New York"));
+    assert_eq!(publication.publisher(), Some("Norton"));
+    assert_eq!(publication.date(), Some("2001"));
+
+    // Rewrite the imprint as an RDA 264
+    record.set_publication_statement(&publication, PublicationConvention::Rda);
+    let field = &record.data_fields[1];
+    assert_eq!((field.tag.as_str(), field.ind2), ("264", '1'));
+    assert_eq!(field.join_subfields(&['a', 'b', 'c'], " "), "New York : Norton, 2001.");
+    assert_eq!(record.data_fields.len(), 3);
+
+    // UNIMARC 210 uses $c for the publisher and $d for the date, without ISBD punctuation
+    let unimarc = publication.to_field(PublicationConvention::UnimarcImprint);
+    assert_eq!(unimarc.tag, "210");
+    assert_eq!(unimarc.subfields[1], subfield('c', "Norton"));
+    assert_eq!(unimarc.subfields[2], subfield('d', "2001"));
+    assert_eq!(PublicationStatement::from_field(&unimarc, MarcFormat::Unimarc).unwrap(), publication);
+}
+
+#[test]
+fn test_material_configuration() {
+    assert_eq!(MaterialConfiguration::from_codes('a', 'm'), Some(MaterialConfiguration::Books));
+    assert_eq!(MaterialConfiguration::from_codes('a', 's'), Some(MaterialConfiguration::ContinuingResources));
+    assert_eq!(MaterialConfiguration::from_codes('j', 'm'), Some(MaterialConfiguration::Music));
+    assert_eq!(MaterialConfiguration::from_codes('a', ' '), None);
+    assert_eq!(MaterialConfiguration::Maps.form_of_item_position(), 29);
+
+    // Every configuration covers positions 0-39 without gaps
+    for configuration in MaterialConfiguration::ALL {
+        let mut next = 0;
+        for element in configuration.all_elements() {
+            assert_eq!(element.start, next, "{} at {}", configuration.code(), element.name);
+            next += element.length;
+        }
+        assert_eq!(next, 40);
+    }
+
+    let leader = Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap();
+    assert_eq!(leader.material_configuration(), Some(MaterialConfiguration::Books));
+    let value = "850423s1984    nyua   j      000 1 eng d";
+    let data = FixedLengthData::parse(value, &leader).unwrap();
+    assert_eq!(data.date1_year(), Some(1984));
+    assert_eq!(data.language, "eng");
+    assert_eq!(data.element("Target audience"), Some("j"));
+    assert_eq!(data.element("Literary form"), Some("1"));
+    assert_eq!(data.form_of_item(), Some(' '));
+    assert_eq!(data.to_string(), value);
+}
+
+#[test]
+fn test_marc_spec() {
+    let subfield = |code: char, value: &str| Subfield { code, value: value.to_string() };
+    let record = Record {
+        leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+        control_fields: vec![ControlField {
+            tag: "008".to_string(),
+            value: "850423s1984    nyua   j      000 1 eng d".to_string(),
+        }],
+        data_fields: vec![
+            DataField {
+                tag: "245".to_string(),
+                ind1: '1',
+                ind2: '4',
+                subfields: vec![subfield('a', "The title :"), subfield('b', "subtitle /"), subfield('c', "Author.")],
+            },
+            DataField {
+                tag: "650".to_string(),
+                ind1: ' ',
+                ind2: '0',
+                subfields: vec![subfield('a', "Cats"), subfield('x', "Behavior.")],
+            },
+            DataField {
+                tag: "651".to_string(),
+                ind1: ' ',
+                ind2: '0',
+                subfields: vec![subfield('a', "France")],
+            },
+        ],
+    };
+
+    let values = |spec: &str| record.spec_values(spec).unwrap();
+    assert_eq!(values("LDR/6"), vec!["a"]);
+    assert_eq!(values("008/35-37"), vec!["eng"]);
+    assert_eq!(values("245^2"), vec!["4"]);
+    assert_eq!(values("245$a-b"), vec!["The title :", "subtitle /"]);
+    assert_eq!(values("245$c/0-5"), vec!["Author"]);
+    assert_eq!(values("65.$a"), vec!["Cats", "France"]);
+    assert_eq!(values("65.[#]$a"), vec!["France"]);
+    assert_eq!(values("650"), vec!["Cats Behavior."]);
+    assert!(values("700$a").is_empty());
+
+    for spec in ["245$a$c", "650[0-1]", "008/0-5", "245^1", "LDR/#"] {
+        assert_eq!(MarcSpec::parse(spec).unwrap().to_string(), spec);
+    }
+    assert!(MarcSpec::parse("24").is_err());
+    assert!(MarcSpec::parse("245$c-a").is_err());
+    assert!(MarcSpec::parse("LDR$a").is_err());
+    assert!(MarcSpec::parse("245x").is_err());
+
+    let matches = |condition: &str| SpecCondition::parse(condition).unwrap().matches(&record);
+    assert!(matches("650$a=Cats"));
+    assert!(matches("008/35-37=eng"));
+    assert!(matches("65.$a~fran"));
+    assert!(matches("650$a!=Dogs"));
+    assert!(matches("245"));
+    assert!(!matches("856"));
+    assert!(!matches("650$a=cats"));
+    assert!(!matches("65.$a!=France"));
+    // Subfield codes are not taken for operators
+    assert_eq!(SpecCondition::parse("245$=").unwrap().operator, SpecOperator::Exists);
+    assert_eq!(SpecCondition::parse("245$a=x=y").unwrap().value, "x=y");
+    for condition in ["650$a=Cats", "245^2!=0", "650~cat", "856"] {
+        assert_eq!(SpecCondition::parse(condition).unwrap().to_string(), condition);
+    }
+    assert!(SpecCondition::parse("24=a").is_err());
+}
+
+#[test]
+fn test_mrk() {
+    let record = Record {
+        leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+        control_fields: vec![ControlField {
+            tag: "008".to_string(),
+            value: "850101s1985    xx".to_string(),
+        }],
+        data_fields: vec![DataField {
+            tag: "245".to_string(),
+            ind1: '1',
+            ind2: ' ',
+            subfields: vec![
+                Subfield {
+                    code: 'a',
+                    value: "Costs in $ {and} C:\\".to_string(),
+                },
+                Subfield {
+                    code: 'c',
+                    value: "Author.".to_string(),
+                },
+            ],
+        }],
+    };
+
+    let text = record.to_mrk();
+    assert_eq!(
+        text,
+        "=LDR  00000nam\\a2200000\\a\\4500\n=008  850101s1985\\\\\\\\xx\n=245  1\\$aCosts in {dollar} {lcub}and{rcub} C:{bsol}$cAuthor.\n\n"
+    );
+
+    let records = parse_mrk(&format!("\n{}{}", text, text.replace("=245  1\\", "=245  00"))).unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0], record);
+    assert_eq!((records[1].data_fields[0].ind1, records[1].data_fields[0].ind2), ('0', '0'));
+
+    let mut output = Vec::new();
+    write_mrk(&records, &mut output).unwrap();
+    assert_eq!(parse_mrk(std::str::from_utf8(&output).unwrap()).unwrap(), records);
+
+    assert!(parse_mrk("=245  10$aNo leader\n").unwrap_err().to_string().contains("no =LDR line"));
+    assert!(parse_mrk("=LDR  00000nam a2200000 a 4500\n245  10$aTitle\n").unwrap_err().to_string().contains("Line 2"));
+}
+
+#[test]
+fn test_record_writer() {
+    let record = Record {
+        leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+        control_fields: vec![ControlField {
+            tag: "001".to_string(),
+            value: "1 & 2 <3>".to_string(),
+        }],
+        data_fields: vec![],
+    };
+
+    let mut writer = RecordWriter::new(Vec::new(), FormatEncoding::marc_xml());
+    writer.write(&record).unwrap();
+    assert_eq!(writer.count(), 1);
+    let xml = writer.finish().unwrap();
+    let text = String::from_utf8(xml.clone()).unwrap();
+    assert!(text.contains("<collection") && text.contains("1 &amp; 2 &lt;3&gt;"));
+    assert_eq!(parse(&xml, FormatEncoding::marc_xml()).unwrap(), vec![record.clone()]);
+
+    // An empty XML output is still a well-formed collection
+    let xml = RecordWriter::new(Vec::new(), FormatEncoding::marc_xml()).finish().unwrap();
+    assert!(String::from_utf8(xml).unwrap().ends_with("</collection>\n"));
+
+    let format = FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8);
+    let mut writer = RecordWriter::new(Vec::new(), format);
+    writer.write(&record).unwrap();
+    writer.write(&record).unwrap();
+    assert_eq!(parse(&writer.finish().unwrap(), format).unwrap().len(), 2);
+}
+
+#[test]
+fn test_validation() {
+    let mut record = Record {
+        leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+        control_fields: vec![
+            ControlField {
+                tag: "001".to_string(),
+                value: "1".to_string(),
+            },
+            ControlField {
+                tag: "008".to_string(),
+                value: "850101s1985    xx".to_string(),
+            },
+        ],
+        data_fields: vec![
+            field("245", '1', '0', &[('a', "Title"), ('a', "Again")]),
+            field("650", ' ', '0', &[('a', "History"), ('x', "")]),
+            field("440", ' ', '0', &[('a', "Series")]),
+            field("949", ' ', ' ', &[('z', "Local")]),
+            field("500", ' ', ' ', &[]),
+        ],
+    };
+
+    let issues: Vec<String> = validate_record(&record, MarcFormat::Marc21).iter().map(|issue| issue.to_string()).collect();
+    assert_eq!(
+        issues,
+        vec![
+            "error 008[0]: expected 40 characters, found 17",
+            "error 245[0]$a: subfield is not repeatable",
+            "warning 650[0]$x: empty subfield",
+            "warning 440[0]: obsolete field",
+            "error 500[0]: field has no subfields",
+        ]
+    );
+
+    record.leader.indicator_count = 3;
+    record.data_fields.truncate(1);
+    record.data_fields[0].subfields.truncate(1);
+    record.data_fields.push(field("245", '1', 'x', &[('a', "Title")]));
+    let issues = validate_record(&record, MarcFormat::Marc21);
+    let locations: Vec<String> = issues.iter().map(|issue| issue.location.to_string()).collect();
+    assert_eq!(locations, vec!["LDR/10", "008[0]", "245[1]", "245[1]^2"]);
+    assert_eq!(issues.iter().map(|issue| issue.severity).max(), Some(Severity::Error));
+
+    // Registered local fields are checked against their definition
+    let mut registry = LocalFieldRegistry::new();
+    registry.register(LocalField::new("949", "Local holdings").repeatable(false).subfield('a', "Call number", false));
+    record.data_fields = vec![field("949", ' ', ' ', &[('z', "Local")]), field("949", ' ', ' ', &[('a', "1"), ('a', "2")])];
+    let issues: Vec<String> = Validator::new(MarcFormat::Marc21).local_fields(registry).validate(&record).iter().map(|issue| issue.to_string()).collect();
+    assert_eq!(
+        issues[2..],
+        ["warning 949[0]$z: undefined subfield", "error 949[1]: field is not repeatable", "error 949[1]$a: subfield is not repeatable"]
+    );
+}
+
+#[test]
+fn test_lint() {
+    let record = Record {
+        leader: Leader::from_bytes(b"00000cam a2200000 a 4500").unwrap(),
+        control_fields: vec![],
+        data_fields: vec![
+            field("010", ' ', ' ', &[('a', "978-2-07-036822-8")]),
+            field("010", ' ', ' ', &[('a', "2-07-036822-X")]),
+            field("010", ' ', ' ', &[('a', "2070368228")]),
+            field("200", ' ', ' ', &[('a', "Title")]),
+            field("200", ' ', ' ', &[('a', "Again")]),
+        ],
+    };
+
+    let issues: Vec<String> = RuleSet::named("unimarc-minimal").unwrap().check(&record, MarcFormat::Unimarc).iter().map(|issue| issue.to_string()).collect();
+    assert_eq!(issues, ["error 001: missing field", "error 100: missing field", "error 200[1]: field is not repeatable", "error 010[2]$a: invalid ISBN \"2070368228\""]);
+
+    let values = Rule::new(RuleKind::Values(MarcSpec::parse("LDR/06").unwrap(), vec!["c".to_string(), "e".to_string()]), Severity::Warning);
+    assert_eq!(values.check(&record, MarcFormat::Unimarc)[0].to_string(), "warning LDR/6: unexpected value \"a\"");
+    let structure = RuleSet::named("structure").unwrap();
+    assert_eq!(structure.check(&record, MarcFormat::Unimarc), Validator::new(MarcFormat::Unimarc).validate(&record));
+    assert!(RuleSet::named("strict").is_none());
+}
+
+#[test]
+fn test_recovering_reader() {
+    let format = FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8);
+    let record = |id: &str| Record {
+        leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+        control_fields: vec![ControlField { tag: "001".to_string(), value: id.to_string() }],
+        data_fields: vec![DataField {
+            tag: "245".to_string(),
+            ind1: '0',
+            ind2: '0',
+            subfields: vec![Subfield { code: 'a', value: "Title".to_string() }],
+        }],
+    };
+    let mut data = Vec::new();
+    write_one(&record("1"), format, &mut data).unwrap();
+    // Record length too long, swallowing the next record
+    data[..5].copy_from_slice(b"00090");
+    data.extend_from_slice(b"\r\n");
+    write_one(&record("2"), format, &mut data).unwrap();
+    data.extend_from_slice(b"\r\n0000");
+    // Indicators lost, record terminator missing
+    let mut broken = Vec::new();
+    write_one(&record("3"), format, &mut broken).unwrap();
+    broken.pop();
+    broken.drain(broken.len() - 10..broken.len() - 8);
+    data.extend_from_slice(&broken[4..]);
+    data.extend_from_slice(b"\x1dgarbage\x1d");
+
+    assert!(parse(&data, format).is_err());
+    let recovered: Vec<Result<Recovered, ParseError>> = RecoveringReader::new(data.as_slice(), format).collect();
+    assert_eq!(recovered.len(), 4);
+    let first = recovered[0].as_ref().unwrap();
+    assert_eq!(first.record.control_fields, record("1").control_fields);
+    assert_eq!(first.repairs, ["record length \"00090\" corrected to \"00062\""]);
+    assert!(recovered[1].as_ref().unwrap().repairs.is_empty());
+    let third = recovered[2].as_ref().unwrap();
+    assert_eq!(third.record.data_fields[0].subfields, record("3").data_fields[0].subfields);
+    assert!(third.repairs.contains(&"missing indicators of 245 set to blanks".to_string()), "{:?}", third.repairs);
+    assert!(recovered[3].is_err());
+}
+
+#[test]
+fn test_csv_writer() {
+    let subfield = |code: char, value: &str| Subfield { code, value: value.to_string() };
+    let record = Record {
+        leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+        control_fields: vec![ControlField {
+            tag: "001".to_string(),
+            value: "1".to_string(),
+        }],
+        data_fields: vec![
+            DataField {
+                tag: "245".to_string(),
+                ind1: '1',
+                ind2: '0',
+                subfields: vec![subfield('a', "Title, \"quoted\"")],
+            },
+            DataField {
+                tag: "020".to_string(),
+                ind1: ' ',
+                ind2: ' ',
+                subfields: vec![subfield('a', "0123456789")],
+            },
+            DataField {
+                tag: "020".to_string(),
+                ind1: ' ',
+                ind2: ' ',
+                subfields: vec![subfield('a', "9780123456786")],
+            },
+        ],
+    };
+    let columns: Vec<MarcSpec> = ["001", "245$a", "020$a", "856$u"].iter().map(|spec| MarcSpec::parse(spec).unwrap()).collect();
+
+    let mut writer = CsvWriter::new(Vec::new(), columns.clone());
+    writer.write(&record).unwrap();
+    writer.write(&record).unwrap();
+    assert_eq!(writer.count(), 2);
+    let csv = String::from_utf8(writer.finish().unwrap()).unwrap();
+    let row = "1,\"Title, \"\"quoted\"\"\",0123456789 | 9780123456786,\n";
+    assert_eq!(csv, format!("001,245$a,020$a,856$u\n{}{}", row, row));
+
+    let mut writer = CsvWriter::new(Vec::new(), columns).delimiter('\t').separator(";");
+    writer.write(&record).unwrap();
+    let tsv = String::from_utf8(writer.finish().unwrap()).unwrap();
+    assert_eq!(tsv.lines().nth(1), Some("1\t\"Title, \"\"quoted\"\"\"\t0123456789;9780123456786\t"));
+}
+
+#[test]
+fn test_record_diff() {
+    let old = Record {
+        leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+        control_fields: vec![ControlField {
+            tag: "005".to_string(),
+            value: "20240101000000.0".to_string(),
+        }],
+        data_fields: vec![field("245", ' ', '0', &[('a', "Title")]), field("650", ' ', '0', &[('a', "Cats")]), field("650", ' ', '0', &[('a', "Dogs")]), field("500", ' ', '0', &[('a', "Note $5")])],
+    };
+    assert!(old.diff(&old).is_empty());
+
+    let mut new = old.clone();
+    new.leader.record_length = 1234;
+    new.control_fields[0].value = "20250101000000.0".to_string();
+    new.data_fields = vec![field("650", ' ', '0', &[('a', "Dogs")]), field("245", ' ', '0', &[('a', "Title")]), field("650", ' ', '0', &[('a', "Birds")]), field("700", ' ', '0', &[('a', "Author")])];
+
+    let changes: Vec<String> = old.diff(&new).iter().map(|change| change.to_string()).collect();
+    assert_eq!(
+        changes,
+        vec![
+            "- 005  20240101000000.0\n+ 005  20250101000000.0",
+            "- 650  \\0$aCats\n+ 650  \\0$aBirds",
+            "- 500  \\0$aNote {dollar}5",
+            "+ 700  \\0$aAuthor",
+        ]
+    );
+    let kinds: Vec<ChangeKind> = old.diff(&new).iter().map(|change| change.kind()).collect();
+    assert_eq!(kinds, vec![ChangeKind::Changed, ChangeKind::Changed, ChangeKind::Removed, ChangeKind::Added]);
+    assert_eq!(old.without(&["005"]).diff(&new.without(&["005"])).len(), 3);
+
+    new.leader.record_status = 'c';
+    assert_eq!(old.diff(&new)[0].tag, "LDR");
+}
+
+#[test]
+fn test_replace_unencodable() {
+    let (text, replaced) = replace_unencodable("Café", Encoding::Iso8859_1, '?');
+    assert!(matches!(text, std::borrow::Cow::Borrowed("Café")));
+    assert_eq!(replaced, 0);
+    assert_eq!(replace_unencodable("Ωμέγα café", Encoding::Iso8859_1, '?'), ("????? café".into(), 5));
+    assert_eq!(replace_unencodable("Ωμέγα", Encoding::Utf8, '?').1, 0);
+
+    let mut record = Record {
+        leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+        control_fields: vec![],
+        data_fields: vec![DataField {
+            tag: "245".to_string(),
+            ind1: '0',
+            ind2: '0',
+            subfields: vec![Subfield {
+                code: 'a',
+                value: "Ελλάδα".to_string(),
+            }],
+        }],
+    };
+    assert_eq!(record.replace_unencodable(Encoding::Iso8859_7, '?'), 0);
+    assert_eq!(record.replace_unencodable(Encoding::Iso8859_2, '_'), 6);
+    assert_eq!(record.data_fields[0].subfields[0].value, "______");
+}
+
+#[test]
+fn test_guess_encoding() {
+    assert_eq!(guess_encoding(b"Title"), EncodingGuess::Ascii);
+    assert_eq!(guess_encoding("Été à Paris".as_bytes()), EncodingGuess::Utf8);
+    assert_eq!(guess_encoding("Ã‰tÃ© Ã  Paris".as_bytes()), EncodingGuess::DoubleUtf8);
+    // MARC-8 acute accent before the letter, ISO 8859-1 accented letters
+    assert_eq!(guess_encoding(b"\xe2Ete \xe1a Paris"), EncodingGuess::Marc8);
+    assert_eq!(guess_encoding(b"\xc9t\xe9 \xe0 Paris"), EncodingGuess::SingleByte);
+    assert_eq!(guess_encoding(b"\xc9t\xe9s"), EncodingGuess::SingleByte);
+    assert_eq!(guess_encoding(b"\x1b(NTitle\x1b(B \xa1"), EncodingGuess::Marc8);
+}
+
+#[test]
+fn test_dublin_core_crosswalk() {
+    let record = &parse_mrk(
+        "=LDR  00000nam a2200000 a 4500
+=008  020101s2001\\\\\\\\nyu\\\\\\\\\\\\\\\\\\\\\\000\\1\\eng\\d
+=020  \\\\$a9780618260300 (pbk.)
+=100  1\\$aTolkien, J. R. R.$q(John Ronald Reuel),$d1892-1973.
+=245  14$aThe hobbit :$bor, There and back again /$cJ.R.R. Tolkien.
+=260  \\\\$aBoston :$bHoughton Mifflin,$c2001.
+=300  \\\\$a330 p. ;$c18 cm.
+=500  \\\\$aOriginally published: 1937.
+=540  \\\\$aPublic domain.
+=650  \\0$aDragons$vFiction.
+=651  \\0$aMiddle Earth (Imaginary place)$vFiction.
+=700  1\\$aAnderson, Douglas A.
+=856  40$uhttps://example.org/hobbit/$qtext/html
+",
+    )
+    .unwrap()[0];
+
+    let dc = to_dc(record);
+    assert_eq!(dc.titles, vec!["The hobbit : or, There and back again"]);
+    assert_eq!(dc.creators, vec!["Tolkien, J. R. R. (John Ronald Reuel), 1892-1973"]);
+    assert_eq!(dc.contributors, vec!["Anderson, Douglas A."]);
+    assert_eq!(dc.subjects, vec!["Dragons--Fiction"]);
+    assert_eq!(dc.coverages, vec!["Middle Earth (Imaginary place)--Fiction"]);
+    assert_eq!(dc.descriptions, vec!["Originally published: 1937"]);
+    assert_eq!(dc.publishers, vec!["Houghton Mifflin"]);
+    assert_eq!(dc.dates, vec!["2001"]);
+    assert_eq!(dc.types, vec!["Text"]);
+    assert_eq!(dc.formats, vec!["text/html"]);
+    assert_eq!(dc.identifiers, vec!["URN:ISBN:9780618260300", "https://example.org/hobbit/"]);
+    assert_eq!(dc.languages, vec!["eng"]);
+    assert_eq!(dc.rights, vec!["Public domain"]);
+
+    let xml = dc.to_xml();
+    assert!(xml.starts_with(r#"<oai_dc:dc xmlns:oai_dc="http://www.openarchives.org/OAI/2.0/oai_dc/" xmlns:dc="http://purl.org/dc/elements/1.1/""#));
+    assert!(xml.contains("<dc:title>The hobbit : or, There and back again</dc:title><dc:creator>"));
+    assert!(xml.ends_with("<dc:rights>Public domain</dc:rights></oai_dc:dc>"));
+
+    // Back to MARC, and to the same description
+    let marc = from_dc(&dc);
+    assert_eq!(marc.leader.record_type, 'a');
+    assert_eq!(marc.join("245", &['a'], ""), Some("The hobbit : or, There and back again".to_string()));
+    assert_eq!(marc.join("260", &['b', 'c'], " "), Some("Houghton Mifflin, 2001.".to_string()));
+    assert_eq!(marc.join("020", &['a'], ""), Some("9780618260300".to_string()));
+    assert_eq!(marc.nth_field("856", 1).unwrap().join_subfields(&['u'], ""), "https://example.org/hobbit/");
+    let tags: Vec<&str> = marc.data_fields.iter().map(|f| f.tag.as_str()).collect();
+    assert!(tags.windows(2).all(|pair| pair[0] <= pair[1]));
+    let again = to_dc(&marc);
+    assert_eq!(again.coverages, Vec::<String>::new());
+    assert_eq!(again.descriptions, vec!["Middle Earth (Imaginary place)--Fiction", "Originally published: 1937"]);
+    assert_eq!(DublinCore { coverages: dc.coverages.clone(), descriptions: dc.descriptions.clone(), ..again }, dc);
+}
+
+#[test]
+fn test_mods_crosswalk() {
+    let record = &parse_mrk(
+        "=LDR  00000nab a2200000 a 4500
+=001  art42
+=020  \\\\$a9780618260300 (pbk.)$z0618260307
+=100  1\\$aTolkien, J. R. R.,$d1892-1973,$eauthor.$4aut$1http://viaf.org/viaf/95218067
+=245  14$aThe hobbit :$bor, There and back again /$cJ.R.R. Tolkien.
+=260  \\\\$aBoston :$bHoughton Mifflin,$c2001.
+=300  \\\\$a330 p. ;$c18 cm.
+=600  10$aBilbo Baggins$c(Fictitious character)$vFiction.
+=650  \\0$aDragons$zMiddle Earth$vFiction.
+=650  \\7$aFantasy$2fast
+=655  \\7$aFantasy fiction.$2lcgft
+=700  1\\$aAnderson, Douglas A.,$eeditor.
+=773  0\\$tTolkien studies$gVol. 1 (2004), p. 1-20$x1547-3155
+",
+    )
+    .unwrap()[0];
+
+    let mods = to_mods(record);
+    assert_eq!(mods.record_identifier.as_deref(), Some("art42"));
+    assert_eq!(mods.type_of_resource.as_deref(), Some("text"));
+    assert_eq!(mods.titles[0].non_sort.as_deref(), Some("The "));
+    assert_eq!(mods.titles[0].title, "hobbit");
+    assert_eq!(mods.titles[0].sub_title.as_deref(), Some("or, There and back again"));
+
+    let author = &mods.names[0];
+    assert!(author.primary);
+    assert_eq!((author.kind, author.name.as_str(), author.date.as_deref()), (NameType::Personal, "Tolkien, J. R. R.", Some("1892-1973")));
+    assert_eq!(author.roles, vec![Role::from(Relator::Author)]);
+    assert_eq!(author.value_uri.as_deref(), Some("http://viaf.org/viaf/95218067"));
+    assert_eq!(mods.names[1].roles, vec![Role { term: Some("editor".to_string()), code: Some("edt".to_string()) }]);
+
+    assert_eq!(mods.origin_info.publishers, vec!["Houghton Mifflin"]);
+    assert_eq!(mods.extent.as_deref(), Some("330 p. ; 18 cm."));
+    assert_eq!(
+        mods.subjects[1],
+        ModsSubject {
+            authority: Some(SubjectSource::Lcsh),
+            terms: vec![SubjectTerm::Topic("Dragons".to_string()), SubjectTerm::Geographic("Middle Earth".to_string()), SubjectTerm::Genre("Fiction".to_string())]
+        }
+    );
+    assert_eq!(mods.subjects[0].terms[0], SubjectTerm::Name(NameType::Personal, "Bilbo Baggins (Fictitious character)".to_string()));
+    assert_eq!(mods.subjects[2].authority, Some(SubjectSource::Fast));
+    assert_eq!(mods.genres, vec![Genre { value: "Fantasy fiction".to_string(), authority: Some(SubjectSource::Lcgft) }]);
+    assert_eq!(
+        mods.identifiers,
+        vec![
+            ModsIdentifier { kind: "isbn".to_string(), value: "9780618260300".to_string(), invalid: false },
+            ModsIdentifier { kind: "isbn".to_string(), value: "0618260307".to_string(), invalid: true },
+        ]
+    );
+    let host = &mods.related_items[0];
+    assert_eq!(host.kind, Some(RelatedItemType::Host));
+    assert_eq!((host.title.as_deref(), host.part.as_deref()), (Some("Tolkien studies"), Some("Vol. 1 (2004), p. 1-20")));
+    assert_eq!(host.identifiers[0].value, "1547-3155");
+
+    let xml = mods.to_xml();
+    assert!(xml.starts_with(r#"<mods xmlns="http://www.loc.gov/mods/v3" version="3.8"><titleInfo><nonSort>The </nonSort><title>hobbit</title>"#));
+    assert!(xml.contains(r#"<name type="personal" usage="primary" valueURI="http://viaf.org/viaf/95218067"><namePart>Tolkien, J. R. R.</namePart><namePart type="date">1892-1973</namePart><role><roleTerm type="text" authority="marcrelator">author</roleTerm><roleTerm type="code" authority="marcrelator">aut</roleTerm></role></name>"#));
+    assert!(xml.contains(r#"<subject authority="lcsh"><topic>Dragons</topic><geographic>Middle Earth</geographic><genre>Fiction</genre></subject>"#));
+    assert!(xml.contains(r#"<relatedItem type="host"><titleInfo><title>Tolkien studies</title></titleInfo><identifier type="issn">1547-3155</identifier><part><text>Vol. 1 (2004), p. 1-20</text></part></relatedItem>"#));
+
+    // Back to MARC, and to the same description
+    let marc = from_mods(&mods);
+    assert_eq!(marc.nth_field("245", 0).map(|f| (f.ind1, f.ind2)), Some(('1', '4')));
+    assert_eq!(marc.join("100", &['a', 'd', 'e', '4'], "|"), Some("Tolkien, J. R. R.|1892-1973|author|aut".to_string()));
+    assert_eq!(marc.nth_field("650", 1).unwrap().join_subfields(&['a', '2'], "|"), "Fantasy|fast");
+    assert_eq!(marc.join("773", &['t', 'g', 'x'], "|"), Some("Tolkien studies|Vol. 1 (2004), p. 1-20|1547-3155".to_string()));
+    assert_eq!(to_mods(&marc), mods);
+}
+
+#[test]
+fn test_csl_item() {
+    let records = parse_mrk(
+        "=LDR  00000nam a2200000 a 4500
+=001  book1
+=008  020101s2001\\\\\\\\mau\\\\\\\\\\\\\\\\\\\\\\000\\1\\eng\\d
+=020  \\\\$a9780618260300 (pbk.)
+=100  1\\$aTolkien, J. R. R.,$d1892-1973.
+=245  14$aThe hobbit :$bor, There and back again /$cJ.R.R. Tolkien.
+=250  \\\\$a1st Houghton Mifflin ed.
+=264  \\1$aBoston :$bHoughton Mifflin,$c[2001]
+=300  \\\\$axii, 330 p. ;$c18 cm.
+=700  1\\$aAnderson, Douglas A.,$eeditor.
+=700  1\\$aLee, Alan,$eillustrator.
+
+=LDR  00000nab a2200000 a 4500
+=001  art1
+=024  7\\$a10.1353/tks.2004.0003$2doi
+=100  1\\$aDrout, Michael D. C.
+=245  10$aTolkien's prose style and its literary and rhetorical effects.
+=773  0\\$tTolkien studies$gVol. 1, no. 1 (2004), p. 137-163$x1547-3155
+",
+    )
+    .unwrap();
+
+    let book = to_csl(&records[0]);
+    assert_eq!((book.id.as_str(), book.kind.as_str()), ("book1", "book"));
+    assert_eq!(book.title.as_deref(), Some("The hobbit: or, There and back again"));
+    assert_eq!(book.authors, vec![CslName::personal("Tolkien, J. R. R.")]);
+    assert_eq!(book.authors[0].given.as_deref(), Some("J. R. R."));
+    assert_eq!(book.editors, vec![CslName::personal("Anderson, Douglas A.")]);
+    // Illustrators have no CSL variable here
+    assert_eq!(book.translators, vec![]);
+    assert_eq!((book.issued, book.publisher.as_deref(), book.publisher_place.as_deref()), (Some(2001), Some("Houghton Mifflin"), Some("Boston")));
+    assert_eq!((book.number_of_pages.as_deref(), book.isbn.as_deref()), (Some("330"), Some("9780618260300")));
+    assert_eq!(book.edition.as_deref(), Some("1st Houghton Mifflin ed."));
+    assert_eq!(book.language.as_deref(), Some("en"));
+
+    let article = to_csl(&records[1]);
+    assert_eq!(article.kind, "article-journal");
+    assert_eq!(article.container_title.as_deref(), Some("Tolkien studies"));
+    assert_eq!((article.volume.as_deref(), article.issue.as_deref(), article.page.as_deref()), (Some("1"), Some("1"), Some("137-163")));
+    assert_eq!(article.doi.as_deref(), Some("10.1353/tks.2004.0003"));
+    assert_eq!(article.issued, None);
+
+    #[cfg(feature = "serde")]
+    {
+        let json = book.to_json();
+        assert_eq!(json["type"], "book");
+        assert_eq!(json["author"], serde_json::json!([{ "family": "Tolkien", "given": "J. R. R." }]));
+        assert_eq!(json["issued"], serde_json::json!({ "date-parts": [[2001]] }));
+        assert_eq!(json["ISBN"], "9780618260300");
+        assert!(json.get("DOI").is_none());
+        assert_eq!(article.to_json()["container-title"], "Tolkien studies");
+    }
+}
+
+#[test]
+fn test_onix_import() {
+    let onix = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ONIXMessage release="3.0" xmlns="http://ns.editeur.org/onix/3.0/reference">
+  <Header><SentDateTime>20240301T1200</SentDateTime></Header>
+  <Product>
+    <RecordReference>com.example.9780000000002</RecordReference>
+    <ProductIdentifier><ProductIDType>15</ProductIDType><IDValue>9780000000002</IDValue></ProductIdentifier>
+    <ProductIdentifier><ProductIDType>03</ProductIDType><IDValue>9780000000002</IDValue></ProductIdentifier>
+    <ProductIdentifier><ProductIDType>06</ProductIDType><IDValue>10.1000/example</IDValue></ProductIdentifier>
+    <DescriptiveDetail>
+      <ProductComposition>00</ProductComposition>
+      <ProductForm>BB</ProductForm>
+      <Collection>
+        <CollectionType>10</CollectionType>
+        <CollectionSequence><CollectionSequenceType>02</CollectionSequenceType><CollectionSequenceNumber>3</CollectionSequenceNumber></CollectionSequence>
+        <TitleDetail><TitleType>01</TitleType><TitleElement><TitleElementLevel>02</TitleElementLevel><TitleText>Northern tales</TitleText></TitleElement></TitleDetail>
+      </Collection>
+      <TitleDetail>
+        <TitleType>01</TitleType>
+        <TitleElement>
+          <TitleElementLevel>01</TitleElementLevel>
+          <TitlePrefix>The</TitlePrefix>
+          <TitleWithoutPrefix>long winter</TitleWithoutPrefix>
+          <Subtitle>a novel</Subtitle>
+        </TitleElement>
+      </TitleDetail>
+      <Contributor>
+        <SequenceNumber>2</SequenceNumber>
+        <ContributorRole>B06</ContributorRole>
+        <NamesBeforeKey>Anna</NamesBeforeKey>
+        <KeyNames>Berg</KeyNames>
+      </Contributor>
+      <Contributor>
+        <SequenceNumber>1</SequenceNumber>
+        <ContributorRole>A01</ContributorRole>
+        <PersonNameInverted>Lind, Erik</PersonNameInverted>
+      </Contributor>
+      <EditionStatement>Second edition</EditionStatement>
+      <Language><LanguageRole>01</LanguageRole><LanguageCode>eng</LanguageCode></Language>
+      <Language><LanguageRole>02</LanguageRole><LanguageCode>swe</LanguageCode></Language>
+      <Extent><ExtentType>00</ExtentType><ExtentValue>352</ExtentValue><ExtentUnit>03</ExtentUnit></Extent>
+      <Subject><SubjectSchemeIdentifier>10</SubjectSchemeIdentifier><SubjectCode>FIC019000</SubjectCode><SubjectHeadingText>FICTION / Literary</SubjectHeadingText></Subject>
+      <Subject><SubjectSchemeIdentifier>20</SubjectSchemeIdentifier><SubjectHeadingText>winter; Sweden</SubjectHeadingText></Subject>
+    </DescriptiveDetail>
+    <CollateralDetail>
+      <TextContent>
+        <TextType>03</TextType>
+        <ContentAudience>00</ContentAudience>
+        <Text textformat="05"><p>A family waits out the <em>longest</em> winter.</p><p>Then spring comes.</p></Text>
+      </TextContent>
+    </CollateralDetail>
+    <PublishingDetail>
+      <Publisher><PublishingRole>01</PublishingRole><PublisherName>Example Press</PublisherName></Publisher>
+      <CityOfPublication>New York</CityOfPublication>
+      <CountryOfPublication>US</CountryOfPublication>
+      <PublishingStatus>04</PublishingStatus>
+      <PublishingDate><PublishingDateRole>01</PublishingDateRole><Date>20240315</Date></PublishingDate>
+    </PublishingDetail>
+  </Product>
+  <Product>
+    <RecordReference>com.example.ebook</RecordReference>
+    <DescriptiveDetail>
+      <ProductForm>ED</ProductForm>
+      <TitleDetail><TitleType>01</TitleType><TitleElement><TitleElementLevel>01</TitleElementLevel><TitleText>Field notes</TitleText></TitleElement></TitleDetail>
+      <Contributor><ContributorRole>B01</ContributorRole><CorporateName>Society of Examples</CorporateName></Contributor>
+    </DescriptiveDetail>
+    <PublishingDetail><PublishingStatus>02</PublishingStatus></PublishingDetail>
+  </Product>
+</ONIXMessage>"#;
+
+    let records = parse_onix(onix).unwrap();
+    assert_eq!(records.len(), 2);
+    let book = &records[0];
+    let expected = parse_mrk(
+        r"=LDR  00000nam a2200000 i 4500
+=001  com.example.9780000000002
+=008  240301s2024\\\\xxu|||||\|||||||| ||eng\d
+=020  \\$a9780000000002$qhardcover
+=024  7\$a10.1000/example$2doi
+=041  1\$aeng$hswe
+=100  1\$aLind, Erik$eauthor$4aut
+=245  14$aThe long winter$ba novel
+=250  \\$aSecond edition
+=264  \1$aNew York :$bExample Press,$c2024.
+=300  \\$a352 pages
+=336  \\$atext$btxt$2rdacontent
+=337  \\$aunmediated$bn$2rdamedia
+=338  \\$avolume$bnc$2rdacarrier
+=490  0\$aNorthern tales$v3
+=520  \\$aA family waits out the longest winter. Then spring comes.
+=650  \7$aFICTION / Literary$2bisacsh
+=653  \\$awinter$aSweden
+=700  1\$aBerg, Anna$etranslator$4trl
+",
+    )
+    .unwrap();
+    assert_eq!(book.control_fields, expected[0].control_fields);
+    assert_eq!(book.data_fields, expected[0].data_fields);
+    assert_eq!((book.leader.record_type, book.leader.encoding_level, book.leader.descriptive_cataloging_form), ('a', '7', 'i'));
+
+    let ebook = &records[1];
+    assert_eq!(ebook.leader.encoding_level, '8');
+    let fixed = &ebook.control_fields[1].value;
+    assert_eq!((fixed.len(), &fixed[6..11], &fixed[23..24], &fixed[35..38]), (40, "nuuuu", "o", "und"));
+    let editor = ebook.nth_field("710", 0).unwrap();
+    assert_eq!(editor.join_subfields(&['a', 'e'], " "), "Society of Examples editor");
+    assert_eq!(ebook.nth_field("245", 0).unwrap().ind1, '0');
+    assert_eq!(ebook.nth_field("337", 0).unwrap().subfields[0].value, "computer");
+
+    assert!(parse_onix("<ONIXmessage><product/></ONIXmessage>").is_err());
+    assert!(parse_onix("<collection/>").is_err());
+}
+
+#[test]
+fn test_cluster_works() {
+    let fixed = |language: &str| format!("000000s2001\\\\\\\\xxu|||||||||||||||||{}\\d", language);
+    let mrk = format!(
+        "=LDR  00000nam a2200000 a 4500\n=001  a1\n=008  {eng}\n=020  \\\\$a0-618-26030-0 (pbk.)\n=100  1\\$aTolkien, J. R. R.\n=245  14$aThe hobbit, or, There and back again\n\n\
+         =LDR  00000nam a2200000 a 4500\n=001  a2\n=008  {eng}\n=020  \\\\$a9780618260300\n=245  10$aHobbit\n\n\
+         =LDR  00000nam a2200000 a 4500\n=001  a3\n=008  {ger}\n=100  1\\$aTolkien, John Ronald Reuel\n=245  10$aDer kleine Hobbit\n=765  0\\$tThe hobbit$wa1\n\n\
+         =LDR  00000nim a2200000 a 4500\n=001  a4\n=008  {eng}\n=100  1\\$aTolkien, J.R.R.\n=245  14$aThe hobbit$h[sound recording]\n\n\
+         =LDR  00000nam a2200000 a 4500\n=001  a5\n=008  {eng}\n=245  00$aPoems\n=700  1\\$aAuden, W. H.\n\n\
+         =LDR  00000nam a2200000 a 4500\n=001  a6\n=008  {eng}\n=245  00$aPoems\n=700  1\\$aEliot, T. S.\n=776  08$iOnline version:$w(OCoLC)77\n\n\
+         =LDR  00000nam a2200000 a 4500\n=001  a7\n=008  {fre}\n=035  \\\\$a(OCoLC) 77\n=245  00$aPoems\n",
+        eng = fixed("eng"),
+        ger = fixed("ger"),
+        fre = fixed("fre"),
+    );
+    let records = parse_mrk(&mrk).unwrap();
+    assert_eq!(records[0].work_key(MarcFormat::Marc21).as_deref(), Some("hobbitorthereandbackagain/tolkienjrr"));
+    assert_eq!(records[4].work_key(MarcFormat::Marc21).as_deref(), Some("poems/audenwh"));
+
+    let clusters = cluster_works(&records, MarcFormat::Marc21);
+    // The ISBN links a2 and the translation link a3 to a1; a4 has a shorter title
+    assert_eq!(clusters.works, vec![0, 0, 0, 1, 2, 3, 3]);
+    // a1 and a2 share an ISBN; the 776 link makes a7 the same expression as a6 despite its language
+    assert_eq!(clusters.expressions, vec![0, 0, 1, 2, 3, 4, 4]);
+    assert_eq!((clusters.work_count(), clusters.expression_count()), (4, 5));
+    assert_eq!(clusters.work_members(0), vec![0, 1, 2]);
+    assert_eq!(clusters.expression_members(4), vec![5, 6]);
+}
+
+#[test]
+fn test_authority_ids() {
+    let parse = |value: &str| AuthorityId::parse(value).unwrap();
+    assert_eq!(parse("http://id.loc.gov/authorities/subjects/sh85076502.html"), AuthorityId::new(AuthoritySource::Lcsh, "sh85076502"));
+    assert_eq!(parse("(DLC)sh 85076502"), AuthorityId::new(AuthoritySource::Lcsh, "sh85076502"));
+    assert_eq!(parse("(OCoLC)fst01204052").uri().unwrap(), "http://id.worldcat.org/fast/1204052");
+    assert_eq!(parse("http://id.worldcat.org/fast/1204052").id, "fst01204052");
+    assert_eq!(parse("https://www.wikidata.org/wiki/Q892"), AuthorityId::new(AuthoritySource::Wikidata, "Q892"));
+    assert_eq!(parse("https://viaf.org/viaf/95218067/"), AuthorityId::new(AuthoritySource::Viaf, "95218067"));
+    assert_eq!(parse("(DE-588)118623761").uri().unwrap(), "https://d-nb.info/gnd/118623761");
+    assert_eq!(parse("(FrPaBN)11926200"), AuthorityId { source: None, id: "(FrPaBN)11926200".to_string(), real_world_object: false });
+    assert_eq!(AuthorityId::parse("  "), None);
+
+    assert!(parse("https://isni.org/isni/0000000121446579").is_valid());
+    assert!(!parse("https://isni.org/isni/0000000121446570").is_valid());
+    assert!(parse("https://orcid.org/0000-0002-1825-0097").is_valid());
+    assert!(parse("http://www.wikidata.org/entity/Q892").is_valid());
+    assert!(!AuthorityId::new(AuthoritySource::Wikidata, "892").is_valid());
+    assert!(!AuthorityId::new(AuthoritySource::Lcnaf, "sh85076502").is_valid());
+    assert!(AuthorityId::new(AuthoritySource::Idref, "02708511X").is_valid());
+    assert!(!AuthorityId::new(AuthoritySource::Idref, "0270851é").is_valid());
+    assert!(!parse("(FrPaBN)11926200").is_valid());
+
+    let mut record = parse_mrk(
+        "=LDR  00000nam a2200000 a 4500\n=100  1\\$aTolkien, J. R. R.,$eauthor.$0(DLC)n  79021164$1https://www.wikidata.org/wiki/Q892\n=245  14$aThe hobbit\n=650  \\0$aDragons$vFiction.$0http://id.loc.gov/authorities/subjects/sh85039359$0(DLC)sh 85039359\n=700  1\\$aAnderson, Douglas A.$5DLC\n=775  08$tLe Hobbit$w(DLC)2001012345\n",
+    )
+    .unwrap()
+    .remove(0);
+    let ids = record.authority_ids();
+    assert_eq!(ids.iter().map(|(i, id)| (*i, id.source)).collect::<Vec<_>>(), vec![(0, Some(AuthoritySource::Lcnaf)), (0, Some(AuthoritySource::Wikidata)), (2, Some(AuthoritySource::Lcsh)), (2, Some(AuthoritySource::Lcsh))]);
+    assert!(ids[1].1.real_world_object);
+
+    // URIs replace control numbers and the repeated subject identifier is dropped
+    assert_eq!(record.normalize_authority_ids(), 3);
+    assert_eq!(record.data_fields[0].join_subfields(&['0', '1'], " "), "http://id.loc.gov/authorities/names/n79021164 http://www.wikidata.org/entity/Q892");
+    assert_eq!(record.data_fields[2].subfields.len(), 3);
+    assert_eq!(record.normalize_authority_ids(), 0);
+
+    let added = record.link_headings(|field| match field.tag.as_str() {
+        "700" => vec![AuthorityId::new(AuthoritySource::Lcnaf, "n80022826")],
+        "100" => vec![AuthorityId::new(AuthoritySource::Lcnaf, "n79021164"), AuthorityId { real_world_object: true, ..AuthorityId::new(AuthoritySource::Viaf, "95218067") }],
+        _ => vec![AuthorityId::new(AuthoritySource::Lcnaf, "n00000000")],
+    });
+    // Only new identifiers are added, and not to the linking entry
+    assert_eq!(added, 3);
+    assert_eq!(record.data_fields[3].subfields.iter().map(|s| s.code).collect::<String>(), "a05");
+    assert_eq!(record.data_fields[0].subfields.last().unwrap().value, "http://viaf.org/viaf/95218067");
+    assert_eq!(record.data_fields[4].subfields.len(), 2);
+}
+
+#[test]
+fn test_isbn() {
+    let isbn = Isbn::parse("0-618-26030-7").unwrap();
+    assert_eq!(isbn.as_str(), "9780618260300");
+    assert_eq!(isbn.isbn10().as_deref(), Some("0618260307"));
+    assert_eq!(Isbn::parse("978-0-618-26030-0").unwrap(), isbn);
+    assert_eq!("207036822x".parse::<Isbn>().unwrap().to_string(), "9782070368228");
+    assert_eq!(Isbn::parse("979-10-90636-07-1").unwrap().isbn10(), None);
+    assert_eq!(Isbn::parse("0-618-26030-8"), Err(IdentifierError::InvalidCheckDigit { expected: '7', found: '8' }));
+    assert_eq!(Isbn::parse("9770618260300"), Err(IdentifierError::InvalidPrefix("977".to_string())));
+    assert_eq!(Isbn::parse("06182603"), Err(IdentifierError::InvalidLength(8)));
+    assert_eq!(Isbn::parse("06182X6030"), Err(IdentifierError::InvalidCharacter('X')));
+
+    let (isbn, qualifier) = Isbn::extract("2-07-036822-X (pbk. : alk. paper)").unwrap();
+    assert_eq!((isbn.as_str(), qualifier.as_deref()), ("9782070368228", Some("pbk. : alk. paper")));
+    assert_eq!(Isbn::extract(" 9780618260300 ").unwrap().1, None);
+    // Matching keys do not depend on the check digit
+    assert_eq!(normalize_isbn("2-07-036822-9 (pbk.)").as_deref(), Some("9782070368228"));
+    assert_eq!(normalize_isbn("20703682é"), None);
+
+    let ranges = IsbnRanges::parse(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<ISBNRangeMessage>
+  <MessageSource>International ISBN Agency</MessageSource>
+  <EAN.UCCPrefixes>
+    <EAN.UCC>
+      <Prefix>978</Prefix>
+      <Agency>International ISBN Agency</Agency>
+      <Rules>
+        <Rule><Range>0000000-5999999</Range><Length>1</Length></Rule>
+        <Rule><Range>6000000-6499999</Range><Length>3</Length></Rule>
+        <Rule><Range>6500000-6799999</Range><Length>2</Length></Rule>
+        <Rule><Range>6800000-6999999</Range><Length>0</Length></Rule>
+      </Rules>
+    </EAN.UCC>
+  </EAN.UCCPrefixes>
+  <RegistrationGroups>
+    <Group>
+      <Prefix>978-0</Prefix>
+      <Agency>English language</Agency>
+      <Rules>
+        <Rule><Range>0000000-1999999</Range><Length>2</Length></Rule>
+        <Rule><Range>2000000-6999999</Range><Length>3</Length></Rule>
+        <Rule><Range>7000000-8499999</Range><Length>4</Length></Rule>
+        <Rule><Range>8500000-8999999</Range><Length>5</Length></Rule>
+        <Rule><Range>9000000-9499999</Range><Length>6</Length></Rule>
+        <Rule><Range>9500000-9999999</Range><Length>7</Length></Rule>
+      </Rules>
+    </Group>
+    <Group>
+      <Prefix>978-2</Prefix>
+      <Agency>French language</Agency>
+      <Rules>
+        <Rule><Range>0000000-1999999</Range><Length>2</Length></Rule>
+        <Rule><Range>2000000-3499999</Range><Length>3</Length></Rule>
+      </Rules>
+    </Group>
+  </RegistrationGroups>
+</ISBNRangeMessage>"#,
+    )
+    .unwrap();
+    assert_eq!(isbn.hyphenated(&ranges).as_deref(), Some("978-2-07-036822-8"));
+    assert_eq!(isbn.hyphenated10(&ranges).as_deref(), Some("2-07-036822-X"));
+    assert_eq!(Isbn::parse("0618260307").unwrap().hyphenated(&ranges).as_deref(), Some("978-0-618-26030-0"));
+    // Unknown group, and a range not in use
+    assert_eq!(Isbn::parse("979-10-90636-07-1").unwrap().hyphenated(&ranges), None);
+    assert_eq!(Isbn::parse("978-3-16-148410-0").unwrap().hyphenated(&ranges), None);
+    assert!(IsbnRanges::parse("<ISBNRangeMessage/>").is_err());
+
+    let record = parse_mrk("=LDR  00000nam a2200000 a 4500\n=020  \\\\$a0618260307 (pbk.)$q(pbk.)\n=020  \\\\$a0618260308\n=020  \\\\$z0618260307\n=020  \\\\$a9780618260300\n").unwrap().remove(0);
+    // The invalid and cancelled ISBNs are left out
+    assert_eq!(record.isbns(MarcFormat::Marc21).iter().map(Isbn::as_str).collect::<Vec<_>>(), vec!["9780618260300", "9780618260300"]);
+}
+
+#[test]
+fn test_issn_lccn() {
+    let issn = Issn::parse("0028-0836").unwrap();
+    assert_eq!((issn.as_str(), issn.to_string()), ("00280836", "0028-0836".to_string()));
+    assert_eq!("2434-561x".parse::<Issn>().unwrap().to_string(), "2434-561X");
+    assert_eq!(Issn::parse("0028-0837"), Err(IdentifierError::InvalidCheckDigit { expected: '6', found: '7' }));
+    assert_eq!(Issn::parse("0028-083"), Err(IdentifierError::InvalidLength(7)));
+    assert_eq!(Issn::parse("002X-0836"), Err(IdentifierError::InvalidCharacter('X')));
+
+    for (value, normalized) in [("n78-890351", "n78890351"), ("n 78-89035", "n78089035"), ("   85153773 ", "85153773"), ("85-2 ", "85000002"), ("sh 85076502 //r86", "sh85076502"), ("2001-1114", "2001001114"), ("  2001012345", "2001012345"), ("N79-21164", "n79021164")] {
+        assert_eq!(Lccn::parse(value).unwrap().as_str(), normalized, "{}", value);
+    }
+    assert_eq!(Lccn::parse("85-1234567"), Err(IdentifierError::InvalidLength(7)));
+    assert_eq!(Lccn::parse("8515377"), Err(IdentifierError::InvalidLength(7)));
+    assert_eq!(Lccn::parse("abc2001012345"), Err(IdentifierError::InvalidPrefix("abc".to_string())));
+    assert_eq!(Lccn::parse("n79o21164"), Err(IdentifierError::InvalidCharacter('o')));
+    assert_eq!(Lccn::parse("n79021164").unwrap().to_marc(), "n  79021164 ");
+    assert_eq!(Lccn::parse("2001012345").unwrap().to_marc(), "  2001012345");
+
+    let record = parse_mrk("=LDR  00000nas a2200000 a 4500\n=010  \\\\$asn 78004456 $zsn 78004457\n=022  0\\$a0028-0836$lISSN 0028-0836$y0028-0837\n=022  \\\\$a1476-4687\n=022  \\\\$a1476-4688\n").unwrap().remove(0);
+    assert_eq!(record.issns(MarcFormat::Marc21).iter().map(Issn::to_string).collect::<Vec<_>>(), vec!["0028-0836", "1476-4687"]);
+    assert_eq!(record.lccn(MarcFormat::Marc21).unwrap().as_str(), "sn78004456");
+    assert_eq!(record.lccn(MarcFormat::Unimarc), None);
+}
+
+#[test]
+fn test_publication_dates() {
+    let dates = |mrk: &str, format: MarcFormat| parse_mrk(&format!("=LDR  00000nam a2200000 a 4500\n{}", mrk)).unwrap().remove(0).dates(format);
+
+    let single = dates("=008  000000s1954\\\\\\\\enk|||||||||||||||||eng\\d\n=260  \\\\$aLondon :$bAllen & Unwin,$c1954.\n", MarcFormat::Marc21);
+    assert_eq!((single.date_type, single.earliest, single.latest, single.ongoing), (DateType::Single, Some(1954), Some(1954), false));
+    assert_eq!((single.date1.as_str(), single.date2.as_str(), single.statement.as_deref()), ("1954", "", Some("1954")));
+    assert_eq!(single.year(), Some(1954));
+
+    let serial = dates("=008  000000c19709999xxu|||||||||||||||||eng\\d\n", MarcFormat::Marc21);
+    assert_eq!((serial.date_type, serial.earliest, serial.latest, serial.ongoing), (DateType::Range, Some(1970), None, true));
+    let ceased = dates("=008  000000d19701985xxu|||||||||||||||||eng\\d\n", MarcFormat::Marc21);
+    assert_eq!((ceased.earliest, ceased.latest, ceased.ongoing), (Some(1970), Some(1985), false));
+    let decade = dates("=008  000000s197uxxu|||||||||||||||||||eng\\d\n", MarcFormat::Marc21);
+    assert_eq!((decade.earliest, decade.latest), (Some(1970), Some(1979)));
+
+    let reprint = dates("=008  000000r19951954enk|||||||||||||||||eng\\d\n", MarcFormat::Marc21);
+    assert_eq!((reprint.date_type, reprint.earliest, reprint.original), (DateType::Reprint, Some(1995), Some(1954)));
+    let copyright = dates("=008  000000t20022001nyu|||||||||||||||||eng\\d\n=264  \\1$c2002\n=264  \\4$c©2001\n", MarcFormat::Marc21);
+    assert_eq!((copyright.date_type, copyright.earliest, copyright.copyright), (DateType::Copyright, Some(2002), Some(2001)));
+
+    // Statement dates, when the coded dates are missing or not coded
+    for (date, date_type, earliest, latest, ongoing) in [
+        ("c2001.", DateType::Single, Some(2001), Some(2001), false),
+        ("[1954?]", DateType::Questionable, Some(1954), Some(1954), false),
+        ("[195-?]", DateType::Questionable, Some(1950), Some(1959), false),
+        ("[19--]", DateType::Questionable, Some(1900), Some(1999), false),
+        ("1968-1972.", DateType::Range, Some(1968), Some(1972), false),
+        ("1968-", DateType::Range, Some(1968), None, true),
+        ("[between 1890 and 1899?]", DateType::Questionable, Some(1890), Some(1899), false),
+        ("1887 [i.e. 1888]", DateType::Single, Some(1888), Some(1888), false),
+        ("[n.d.]", DateType::Unknown, None, None, false),
+    ] {
+        let dates = dates(&format!("=008  000000||||||||xx\\|||||||||||||||||eng\\d\n=260  \\\\$aParis :$bGallimard,$c{}\n", date), MarcFormat::Marc21);
+        assert_eq!((dates.date_type, dates.earliest, dates.latest, dates.ongoing), (date_type, earliest, latest, ongoing), "{}", date);
+    }
+    let text = dates("=260  \\\\$c1995, c1994.\n", MarcFormat::Marc21);
+    assert_eq!((text.earliest, text.copyright), (Some(1995), Some(1994)));
+
+    let unimarc = dates("=100  \\\\$a20020101f19uu1925k  y0frey0103    ba\n=210  \\\\$aParis$cGallimard$d[19..]\n", MarcFormat::Unimarc);
+    assert_eq!((unimarc.date_type, unimarc.earliest, unimarc.latest), (DateType::Questionable, Some(1900), Some(1925)));
+    let unimarc = dates("=100  \\\\$a20020101a19709999k  y0frey0103    ba\n", MarcFormat::Unimarc);
+    assert_eq!((unimarc.date_type, unimarc.earliest, unimarc.latest, unimarc.ongoing), (DateType::Range, Some(1970), None, true));
+}
+
+#[test]
+fn test_profile() {
+    let records = parse_mrk(
+        "=LDR  00000nam a2200000 a 4500\n=001  123\n=245  10$aThe hobbit /$cJ.R.R. Tolkien.\n=650  \\0$aDragons$vFiction.\n=650  \\0$aMiddle Earth (Imaginary place)$vFiction.\n\n\
+         =LDR  00000nam a2200000 a 4500\n=001  12345\n=245  04$aThe lord of the rings\n\n\
+         =LDR  00000ngm  2200000 a 4500\n=245  00$aThe hobbit �\n",
+    )
+    .unwrap();
+    let mut profile = Profile::new(FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8));
+    for record in &records {
+        profile.add(record);
+    }
+    assert_eq!(profile.records, 3);
+    assert_eq!(profile.record_types.iter().map(|(k, v)| (k.as_str(), *v)).collect::<Vec<_>>(), vec![("am", 2), ("gm", 1)]);
+
+    let control = &profile.tags["001"];
+    assert_eq!((control.records, control.occurrences, control.lengths.min, control.lengths.max, control.lengths.average()), (2, 2, 3, 5, Some(4)));
+    assert!(control.ind1.is_empty() && control.subfields.is_empty());
+
+    let subjects = &profile.tags["650"];
+    assert_eq!((subjects.records, subjects.occurrences), (1, 2));
+    assert_eq!(subjects.ind2[&'0'], 2);
+    assert_eq!((subjects.subfields[&'v'].records, subjects.subfields[&'v'].occurrences), (1, 2));
+    assert_eq!(profile.tags["245"].ind2.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>(), vec![('0', 2), ('4', 1)]);
+    // Lengths 7 and 30 fall in the buckets 4-7 and 16-31
+    assert_eq!(subjects.subfields[&'a'].lengths.buckets.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>(), vec![(4, 1), (16, 1)]);
+
+    assert_eq!(profile.sizes.count, 3);
+    assert_eq!(profile.encoding.replacement_characters, 1);
+    // Leader/09 is blank (MARC-8) in a record read as UTF-8
+    assert_eq!(profile.encoding.leader_mismatch, 1);
+    assert_eq!(Profile::from_records(&records, FormatEncoding::marc_xml()).encoding.leader_mismatch, 0);
+}
+
+#[test]
+fn test_pipeline() {
+    let records: Vec<Record> = (0..25)
+        .map(|i| {
+            let mut record = parse_mrk(&format!("=LDR  00000nam a2200000 a 4500\n=001  {}\n=245  00$aTitle {}\n", i, i)).unwrap().remove(0);
+            if i % 5 == 0 {
+                record.data_fields[0].ind1 = 'x';
+            }
+            record
+        })
+        .collect();
+    let validator = Validator::new(MarcFormat::Marc21);
+    let pipeline = Pipeline::new()
+        .filter(|record| record.control_fields[0].value != "1")
+        .map(|mut record| {
+            record.data_fields[0].subfields[0].value.make_ascii_uppercase();
+            record
+        })
+        .validate(move |record| validator.validate(record).into_iter().filter(|issue| issue.location.tag == "245").collect(), Severity::Error)
+        .split(|record| record.control_fields[0].value.parse::<usize>().unwrap() % 2)
+        .jobs(4)
+        .batch_size(7);
+
+    let (mut even, mut odd) = (Vec::new(), Vec::new());
+    let report = pipeline.run(records.iter().cloned().map(Ok), &mut [&mut even, &mut odd]).unwrap();
+    assert_eq!(report, PipelineReport { read: 25, filtered: 1, rejected: 5, written: vec![10, 9] });
+    // Records stay in source order across batches and threads
+    assert_eq!(odd.iter().map(|r| r.control_fields[0].value.as_str()).collect::<Vec<_>>(), ["3", "7", "9", "11", "13", "17", "19", "21", "23"]);
+    assert_eq!(even[0].data_fields[0].subfields[0].value, "TITLE 2");
+
+    // A source error stops the run after the records read before it
+    let source = records.iter().take(3).cloned().map(Ok).chain([Err(ParseError::UnexpectedEof)]).chain(records.iter().cloned().map(Ok));
+    let mut output = RecordWriter::new(Vec::new(), FormatEncoding::marc_xml());
+    match Pipeline::new().run(source, &mut [&mut output]) {
+        Err(PipelineError::Parse { index: 3, error: ParseError::UnexpectedEof }) => {}
+        other => panic!("{:?}", other),
+    }
+    assert_eq!(output.count(), 3);
+
+    let error = Pipeline::new().split(|_| 1).run(records.iter().cloned().map(Ok), &mut [&mut Vec::new()]).unwrap_err();
+    assert_eq!(error.to_string(), "No output #1");
+}
+
+#[test]
+fn test_patch() {
+    let mrk = "=LDR  00000nam a2200000 a 4500\n=001  1\n=020  \\\\$a0-261-10221-4 (pbk.)\n=245  14$aThe hobbit$h[text]\n=500  \\\\$aSigned by the author.$5DLC\n=650  \\0$aHobbits.\n=650  \\7$aHobbits.$2fast\n=999  \\\\$aLocal\n";
+    let mut record = parse_mrk(mrk).unwrap().remove(0);
+    let step = |operation: Result<PatchOperation, String>, condition: Option<&str>| PatchStep {
+        operation: operation.unwrap(),
+        condition: condition.map(|c| c.parse().unwrap()),
+    };
+    let patch = Patch {
+        steps: vec![
+            step(PatchOperation::delete("9.."), None),
+            step(PatchOperation::delete("650"), Some("650$2=fast")),
+            step(PatchOperation::delete("245$h"), None),
+            step(PatchOperation::replace("020$a", " (pbk.)", ""), None),
+            step(PatchOperation::move_to("500$5", "561$5"), None),
+            step(PatchOperation::copy("001", "035$a"), None),
+            step(PatchOperation::add("=590  \\\\$aDigitized"), Some("001=2")),
+            step(PatchOperation::add("=003  OCoLC"), None),
+        ],
+    };
+    assert!(patch.apply(&mut record));
+    assert_eq!(record.control_fields.iter().map(|f| f.tag.as_str()).collect::<Vec<_>>(), ["001", "003"]);
+    let fields: Vec<String> = record.data_fields.iter().map(|f| format!("{} {}", f.tag, f.subfields.iter().map(|s| format!("${}{}", s.code, s.value)).collect::<String>())).collect();
+    assert_eq!(fields, ["020 $a0-261-10221-4", "035 $a1", "245 $aThe hobbit", "500 $aSigned by the author.", "561 $5DLC", "650 $aHobbits."]);
+    // Applying it again only copies 001 once more
+    assert!(patch.apply(&mut record));
+    assert_eq!(record.data_fields.iter().filter(|f| f.tag == "035").count(), 2);
+
+    assert_eq!(PatchOperation::delete("LDR").unwrap_err(), "LDR: only fields and subfields can be edited");
+    assert_eq!(PatchOperation::copy("245$a", "7..$a").unwrap_err(), "7..$a: copy to a single tag");
+    assert!(PatchOperation::move_to("245$a", "700$ab").is_err());
+    assert!(Find::parse("").is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_patch_json() {
+    let patch = Patch::from_json(r#"{"operation": [{"delete": "650", "if": "650$2=fast"}, {"copy": "245$a", "to": "740$a"}]}"#).unwrap();
+    assert_eq!(patch.steps.len(), 2);
+    let mut record = parse_mrk("=LDR  00000nam a2200000 a 4500\n=245  10$aThe hobbit\n=650  \\7$aHobbits.$2fast\n").unwrap().remove(0);
+    patch.apply(&mut record);
+    assert_eq!(record.data_fields.iter().map(|f| f.tag.as_str()).collect::<Vec<_>>(), ["245", "740"]);
+
+    assert_eq!(Patch::from_json(r#"{"operation": [{"delete": "9.."}, {"move": "500$5"}]}"#).unwrap_err(), "operation #2: move needs a to key");
+    assert_eq!(Patch::from_json(r#"{"operation": [{"delete": "9..", "add": "=590  \\$aX"}]}"#).unwrap_err(), "operation #1: expected a single operation");
+    assert_eq!(Patch::from_json(r#"{"operation": [{"remove": "9.."}]}"#).unwrap_err(), "operation #1: unknown key remove");
+    assert_eq!(Patch::from_json(r#"{"operations": []}"#).unwrap_err(), "no operation list");
+}
+
+#[test]
+fn test_intern() {
+    let mrk = "=LDR  00000nam a2200000 a 4500\n=001  1\n=260  \\\\$aLondon :$bAllen & Unwin,$c1937.\n=650  \\0$aHobbits.\n\n=LDR  00000nam a2200000 a 4500\n=001  2\n=260  \\\\$aLondon :$bAllen & Unwin,$c1954.\n=650  \\0$aHobbits.\n";
+    let records = parse_mrk(mrk).unwrap();
+    let mut interner = Interner::with_max_len(10);
+    let compact: Vec<CompactRecord> = records.iter().map(|r| interner.compact(r).unwrap()).collect();
+    // "Allen & Unwin," is too long to be shared
+    assert_eq!(interner.len(), 6);
+    assert_eq!(interner.bytes(), "1".len() + "London :".len() + "1937.".len() + "Hobbits.".len() + "2".len() + "1954.".len());
+    assert_eq!(compact[0].data_fields[1], compact[1].data_fields[1]);
+    assert_ne!(compact[0], compact[1]);
+    assert_eq!(compact[1].data_fields[0].tag, "260");
+    assert_eq!(compact[1].data_fields[0].subfields[1].1.as_str(), "Allen & Unwin,");
+    assert_eq!(compact.iter().map(CompactRecord::to_record).collect::<Vec<_>>(), records);
+
+    assert_eq!(Tag::new("245").unwrap().to_string(), "245");
+    assert!(Tag::new("008").unwrap().is_control() && !Tag::new("010").unwrap().is_control());
+    assert!(Tag::new("24").is_none() && Tag::new("2é").is_none());
+    let mut bad = records[0].clone();
+    bad.data_fields[0].tag = "2600".to_string();
+    assert_eq!(interner.compact(&bad).unwrap_err(), "Invalid tag \"2600\"");
+}
+
+#[test]
+fn test_parse_into() {
+    let records = parse_mrk("=LDR  00000nam a2200000 a 4500\n=001  1\n=008  000000s1954\\\\\\\\enk|||||||||||||||||eng\\d\n=245  14$aThe hobbit /$cTolkien.\n=650  \\0$aHobbits$vFiction.\n\n=LDR  00000nam a2200000 a 4500\n=001  2\n=245  00$aSecond\n\n=LDR  00000ngm a2200000 a 4500\n=001  3\n=245  00$aThird$h[videorecording]\n=500  \\\\$aNote.\n").unwrap();
+    let format = FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8);
+    let mut data = Vec::new();
+    write(&records, format, &mut data).unwrap();
+    let expected = parse(&data, format).unwrap();
+
+    let mut reader = RecordReader::new(data.as_slice(), format);
+    let mut record = Record {
+        leader: expected[0].leader.clone(),
+        control_fields: Vec::new(),
+        data_fields: Vec::new(),
+    };
+    let mut read = Vec::new();
+    let mut buffer = std::ptr::null();
+    while reader.read_into(&mut record).unwrap() {
+        read.push(record.clone());
+        // The second record is smaller than the first: its fields reuse the same vector
+        if read.len() == 2 {
+            assert_eq!(record.data_fields.as_ptr(), buffer);
+        }
+        buffer = record.data_fields.as_ptr();
+    }
+    assert_eq!(read, expected);
+    assert_eq!(reader.record_index(), 3);
+    assert!(!reader.read_into(&mut record).unwrap());
+
+    parse_into(&mut record, &data, format).unwrap();
+    assert_eq!(record, expected[0]);
+    assert_eq!(parse_into(&mut record, &data[..100], format), Err(ParseError::InvalidRecordLength(format!("Record length {} exceeds available data 100", expected[0].leader.record_length))));
+
+    let mut xml = Vec::new();
+    write(&records, FormatEncoding::marc_xml(), &mut xml).unwrap();
+    let mut reader = RecordReader::new(xml.as_slice(), FormatEncoding::marc_xml());
+    assert!(reader.read_into(&mut record).unwrap() && reader.read_into(&mut record).unwrap());
+    assert_eq!(record.control_fields[0].value, "2");
+}
+
+#[test]
+fn test_dedup_index() {
+    let records = parse_mrk(
+        "=LDR  00000nam a2200000 a 4500\n=001  a1\n=020  \\\\$a0261102214\n\n\
+         =LDR  00000nam a2200000 a 4500\n=001  b7\n=035  \\\\$a(OCoLC)123\n=020  \\\\$a978-0-261-10221-7 (pbk.)\n\n\
+         =LDR  00000nam a2200000 a 4500\n=001  c3\n=035  \\\\$a(OCoLC)123\n\n\
+         =LDR  00000nam a2200000 a 4500\n=001  \n",
+    )
+    .unwrap();
+    let keys = [DedupKey::ControlNumber, DedupKey::SystemNumber, DedupKey::Isbn];
+    assert_eq!(records[1].dedup_keys(&keys, MarcFormat::Marc21), vec!["001 b7", "035 (OCoLC)123", "isbn 9780261102217"]);
+    // An empty control number identifies nothing
+    assert!(records[3].dedup_keys(&keys, MarcFormat::Marc21).is_empty());
+    assert_eq!(DedupKey::parse("Match-Key"), Ok(DedupKey::MatchKey));
+    assert!(DedupKey::parse("245").is_err());
+
+    let index = DedupIndex::with_shards(4);
+    assert!(index.is_empty());
+    let seen: Vec<_> = records.iter().enumerate().map(|(i, r)| index.insert_record(i, r, &keys, MarcFormat::Marc21)).collect();
+    assert_eq!(seen, vec![None, Some(0), Some(1), None]);
+    assert_eq!(index.len(), 5);
+    assert_eq!(index.get("035 (OCoLC)123"), Some(1));
+    assert!(index.contains("001 c3") && !index.contains("001 d4"));
+    // Inserting again, or out of order, keeps the smallest id
+    assert_eq!(index.insert("001 c3", 2), None);
+    assert_eq!(index.insert("035 (OCoLC)123", 0), Some(1));
+    assert_eq!(index.get("035 (OCoLC)123"), Some(0));
+
+    // Fed from several threads in any order, the index ends up the same
+    let index = DedupIndex::new();
+    let many: Vec<Record> = (0..1000).map(|i| parse_mrk(&format!("=LDR  00000nam a2200000 a 4500\n=001  {}\n", i % 100)).unwrap().remove(0)).collect();
+    let duplicates = std::sync::atomic::AtomicUsize::new(0);
+    std::thread::scope(|scope| {
+        for worker in 0..4 {
+            let (index, many, duplicates) = (&index, &many, &duplicates);
+            scope.spawn(move || {
+                for i in (worker..many.len()).step_by(4).rev() {
+                    if index.insert_record(i, &many[i], &keys, MarcFormat::Marc21).is_some() {
+                        duplicates.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    }
+                }
+            });
+        }
+    });
+    assert_eq!(index.len(), 100);
+    assert_eq!(duplicates.into_inner(), 900);
+    assert!((0..100).all(|i| index.get(&format!("001 {}", i)) == Some(i)));
+}
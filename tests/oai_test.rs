@@ -0,0 +1,151 @@
+#![cfg(feature = "oai")]
+
+use marc_rs::*;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::mpsc::{self, Receiver};
+
+const PAGE_1: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<OAI-PMH xmlns="http://www.openarchives.org/OAI/2.0/">
+  <responseDate>2024-05-01T10:00:00Z</responseDate>
+  <request verb="ListRecords" metadataPrefix="marc21">https://example.org/oai</request>
+  <ListRecords>
+    <record>
+      <header>
+        <identifier>oai:example.org:1</identifier>
+        <datestamp>2024-03-02</datestamp>
+        <setSpec>theses</setSpec>
+        <setSpec>2024</setSpec>
+      </header>
+      <metadata>
+        <marc:record xmlns:marc="http://www.loc.gov/MARC21/slim">
+          <marc:leader>00000nam a2200000 a 4500</marc:leader>
+          <marc:controlfield tag="001">1</marc:controlfield>
+          <marc:datafield tag="245" ind1="1" ind2="0">
+            <marc:subfield code="a">Caf&#233; &amp; th&#233;</marc:subfield>
+          </marc:datafield>
+        </marc:record>
+      </metadata>
+    </record>
+    <record>
+      <header status="deleted">
+        <identifier>oai:example.org:2</identifier>
+        <datestamp>2024-03-05</datestamp>
+      </header>
+    </record>
+    <resumptionToken completeListSize="3" cursor="0">page:2</resumptionToken>
+  </ListRecords>
+</OAI-PMH>"#;
+
+const PAGE_2: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<OAI-PMH xmlns="http://www.openarchives.org/OAI/2.0/">
+  <ListRecords>
+    <record>
+      <header>
+        <identifier>oai:example.org:3</identifier>
+        <datestamp>2024-04-01</datestamp>
+      </header>
+      <metadata>
+        <record xmlns="http://www.loc.gov/MARC21/slim">
+          <leader>00000nam a2200000 a 4500</leader>
+          <controlfield tag="001">3</controlfield>
+        </record>
+      </metadata>
+    </record>
+    <resumptionToken completeListSize="3" cursor="2"/>
+  </ListRecords>
+</OAI-PMH>"#;
+
+/// Serve canned responses on a local port, in order, one per request
+///
+/// Returns the base URL and a receiver of the request lines.
+fn serve(responses: Vec<(&'static str, &'static str)>) -> (String, Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/oai", listener.local_addr().unwrap());
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        for ((status, body), stream) in responses.into_iter().zip(listener.incoming()) {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            sender.send(request_line.trim_end().to_string()).unwrap();
+            write!(stream, "HTTP/1.1 {}\r\nContent-Type: text/xml\r\nContent-Length: {}\r\nRetry-After: 0\r\nConnection: close\r\n\r\n{}", status, body.len(), body).unwrap();
+        }
+    });
+    (url, receiver)
+}
+
+#[test]
+fn test_parse_oai_response() {
+    let page = parse_oai_response(PAGE_1).unwrap();
+    assert_eq!(page.records.len(), 2);
+    assert_eq!(page.resumption_token.as_deref(), Some("page:2"));
+    assert_eq!(page.complete_list_size, Some(3));
+
+    let first = &page.records[0];
+    assert_eq!(first.identifier, "oai:example.org:1");
+    assert_eq!(first.datestamp, "2024-03-02");
+    assert_eq!(first.sets, vec!["theses", "2024"]);
+    assert!(!first.deleted);
+    let record = first.record.as_ref().unwrap();
+    assert_eq!(record.control_fields[0].value, "1");
+    assert_eq!(record.data_fields[0].subfields[0].value, "Café & thé");
+
+    let second = &page.records[1];
+    assert!(second.deleted);
+    assert_eq!(second.record, None);
+
+    // An empty token ends the list
+    let page = parse_oai_response(PAGE_2).unwrap();
+    assert_eq!(page.records.len(), 1);
+    assert_eq!(page.resumption_token, None);
+    assert_eq!(page.records[0].record.as_ref().unwrap().control_fields[0].value, "3");
+}
+
+#[test]
+fn test_parse_oai_errors() {
+    let no_records = r#"<OAI-PMH><error code="noRecordsMatch">Nothing changed</error></OAI-PMH>"#;
+    assert_eq!(parse_oai_response(no_records).unwrap(), OaiPage::default());
+
+    let bad_argument = r#"<OAI-PMH><error code="badArgument">Illegal date &apos;yesterday&apos;</error></OAI-PMH>"#;
+    assert_eq!(
+        parse_oai_response(bad_argument),
+        Err(HarvestError::Oai {
+            code: "badArgument".to_string(),
+            message: "Illegal date 'yesterday'".to_string()
+        })
+    );
+
+    assert!(matches!(parse_oai_response("<OAI-PMH><ListRecords></OAI-PMH>"), Err(HarvestError::InvalidResponse(_))));
+}
+
+#[test]
+fn test_harvester_follows_resumption_tokens() {
+    let (url, requests) = serve(vec![("200 OK", PAGE_1), ("503 Service Unavailable", ""), ("200 OK", PAGE_2)]);
+    let mut harvester = Harvester::new(&url).set("theses").from("2024-01-01");
+    let identifiers: Vec<String> = harvester.by_ref().map(|record| record.unwrap().identifier).collect();
+    assert_eq!(identifiers, vec!["oai:example.org:1", "oai:example.org:2", "oai:example.org:3"]);
+    assert_eq!(harvester.complete_list_size(), Some(3));
+    assert_eq!(harvester.resumption_token(), None);
+
+    let requests: Vec<String> = requests.try_iter().collect();
+    assert_eq!(requests[0], "GET /oai?verb=ListRecords&metadataPrefix=marc21&set=theses&from=2024-01-01 HTTP/1.1");
+    // The page refused with 503 is asked again
+    assert_eq!(requests[1], "GET /oai?verb=ListRecords&resumptionToken=page%3A2 HTTP/1.1");
+    assert_eq!(requests[2], requests[1]);
+}
+
+#[test]
+fn test_harvester_stops_on_error() {
+    let (url, _requests) = serve(vec![("200 OK", r#"<OAI-PMH><error code="badResumptionToken">Expired</error></OAI-PMH>"#)]);
+    let mut harvester = Harvester::new(&url).resume("page:2");
+    assert!(matches!(harvester.next(), Some(Err(HarvestError::Oai { code, .. })) if code == "badResumptionToken"));
+    assert_eq!(harvester.next(), None);
+    // The token of the failed page is kept, to resume from it later
+    assert_eq!(harvester.resumption_token(), Some("page:2"));
+}
@@ -142,6 +142,24 @@ fn test_serde_marc_to_writer() {
     let result = serde_marc::to_writer(&record, format, &mut buffer);
     assert!(result.is_ok());
     assert!(!buffer.is_empty());
+
+    // Borrowed records can be written without collecting them into a slice
+    struct Batch {
+        records: Vec<Record>,
+    }
+    let batch = Batch {
+        records: vec![record.clone(), record.clone()],
+    };
+    let mut streamed = Vec::new();
+    serde_marc::to_writer_many(batch.records.iter().filter(|r| !r.control_fields.is_empty()), format, &mut streamed).unwrap();
+    assert_eq!(streamed, serde_marc::to_vec_many(&batch.records, format).unwrap());
+
+    let mut xml = Vec::new();
+    write(std::iter::once(&record), FormatEncoding::marc_xml(), &mut xml).unwrap();
+    assert!(!String::from_utf8(xml).unwrap().contains("<collection"));
+    let mut xml = Vec::new();
+    write(batch.records.iter(), FormatEncoding::marc_xml(), &mut xml).unwrap();
+    assert!(String::from_utf8(xml).unwrap().contains("<collection"));
 }
 
 #[cfg(feature = "serde")]
@@ -191,3 +209,524 @@ fn test_serde_marc_to_records() {
     let bytes = result.unwrap();
     assert!(!bytes.is_empty());
 }
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_marc_in_json() {
+    let record = Record {
+        leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+        control_fields: vec![ControlField {
+            tag: "001".to_string(),
+            value: "12345".to_string(),
+        }],
+        data_fields: vec![DataField {
+            tag: "245".to_string(),
+            ind1: '1',
+            ind2: ' ',
+            subfields: vec![
+                Subfield {
+                    code: 'a',
+                    value: "Title :".to_string(),
+                },
+                Subfield {
+                    code: 'b',
+                    value: "subtitle.".to_string(),
+                },
+            ],
+        }],
+    };
+
+    let json = serde_json::to_value(MarcJson(&record)).unwrap();
+    assert_eq!(json["leader"], "00000nam a2200000 a 4500");
+    assert_eq!(json["fields"][0]["001"], "12345");
+    assert_eq!(json["fields"][1]["245"]["ind2"], " ");
+    assert_eq!(json["fields"][1]["245"]["subfields"][1]["b"], "subtitle.");
+
+    let MarcJson(parsed): MarcJson<Record> = serde_json::from_value(json).unwrap();
+    assert_eq!(parsed, record);
+
+    let invalid = r#"{"leader":"00000nam a2200000 a 4500","fields":[{"245":{"ind1":"1","ind2":"0","subfields":[{"a":"x","b":"y"}]}}]}"#;
+    assert!(serde_json::from_str::<MarcJson<Record>>(invalid).is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_options() {
+    let field = |tag: &str, subfields: Vec<Subfield>| DataField {
+        tag: tag.to_string(),
+        ind1: ' ',
+        ind2: ' ',
+        subfields,
+    };
+    let record = Record {
+        leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+        control_fields: vec![],
+        data_fields: vec![
+            field(
+                "650",
+                vec![Subfield {
+                    code: 'a',
+                    value: "Subject".to_string(),
+                }],
+            ),
+            field("500", vec![]),
+            field(
+                "245",
+                vec![Subfield {
+                    code: 'a',
+                    value: "Title".to_string(),
+                }],
+            ),
+        ],
+    };
+
+    // The default options produce the same JSON as the Record implementation
+    assert_eq!(serde_json::to_value(SerdeOptions::default().wrap(&record)).unwrap(), serde_json::to_value(&record).unwrap());
+
+    let options = SerdeOptions {
+        field_order: FieldOrder::Tag,
+        skip_empty_data_fields: true,
+        ..SerdeOptions::marc_json()
+    };
+    let json = serde_json::to_value(options.wrap(&record)).unwrap();
+    let fields = json["fields"].as_array().unwrap();
+    assert_eq!(fields.len(), 2);
+    assert_eq!(fields[0]["245"]["subfields"][0]["a"], "Title");
+    assert_eq!(fields[1]["650"]["subfields"][0]["a"], "Subject");
+
+    let camel = SerdeOptions {
+        key_case: KeyCase::Camel,
+        ..SerdeOptions::default()
+    };
+    let json = serde_json::to_value(camel.wrap(&record)).unwrap();
+    assert_eq!(json["leader"]["recordType"], "a");
+    assert_eq!(json["dataFields"][0]["tag"], "650");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_marc_from_reader_many_prefixed_xml() {
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<marc:collection xmlns:marc="http://www.loc.gov/MARC21/slim">
+  <marc:record>
+    <marc:leader>00000nam a2200000 a 4500</marc:leader>
+    <marc:controlfield tag="001">1</marc:controlfield>
+    <marc:datafield tag="245" ind1="0" ind2="0"><marc:subfield code="a">First</marc:subfield></marc:datafield>
+  </marc:record>
+  <marc:record>
+    <marc:leader>00000nam a2200000 a 4500</marc:leader>
+    <marc:controlfield tag="001">2</marc:controlfield>
+  </marc:record>
+</marc:collection>
+"#;
+    let expected = serde_marc::from_slice_many(xml.as_bytes(), FormatEncoding::marc_xml()).unwrap();
+    assert_eq!(expected.len(), 2);
+    let records = serde_marc::from_reader_many(std::io::Cursor::new(xml), FormatEncoding::marc_xml()).unwrap();
+    assert_eq!(records, expected);
+    assert_eq!(records[0].data_fields[0].subfields[0].value, "First");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_marc_iter_from_reader() {
+    use std::io::{Cursor, Read};
+
+    struct FailingReader;
+
+    impl Read for FailingReader {
+        fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::other("connection reset"))
+        }
+    }
+
+    let record = |id: &str| Record {
+        leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+        control_fields: vec![ControlField {
+            tag: "001".to_string(),
+            value: id.to_string(),
+        }],
+        data_fields: vec![DataField {
+            tag: "245".to_string(),
+            ind1: '0',
+            ind2: '0',
+            subfields: vec![Subfield {
+                code: 'a',
+                value: "Title".to_string(),
+            }],
+        }],
+    };
+    let records = vec![record("1"), record("2"), record("3")];
+
+    let format = FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8);
+    let bytes = serde_marc::to_vec_many(&records, format).unwrap();
+    let ids: Vec<String> = serde_marc::iter_from_reader(Cursor::new(&bytes), format)
+        .map(|r| r.unwrap().control_fields[0].value.clone())
+        .collect();
+    assert_eq!(ids, vec!["1", "2", "3"]);
+
+    // Records are returned before the rest of the input is read
+    let first_length = serde_marc::to_vec(&records[0], format).unwrap().len();
+    let mut iter = serde_marc::iter_from_reader(Cursor::new(&bytes[..first_length]).chain(FailingReader), format);
+    assert_eq!(iter.next().unwrap().unwrap().control_fields[0].value, "1");
+    assert!(iter.next().unwrap().is_err());
+    assert!(iter.next().is_none());
+
+    // from_reader stops after the first record, from_reader_many tolerates a trailing newline
+    let mut input = Cursor::new(&bytes[..first_length]).chain(FailingReader);
+    assert_eq!(serde_marc::from_reader(&mut input, format).unwrap().control_fields[0].value, "1");
+    let mut with_newline = bytes.clone();
+    with_newline.push(b'\n');
+    assert_eq!(serde_marc::from_reader_many(Cursor::new(with_newline), format).unwrap().len(), 3);
+    assert!(serde_marc::from_reader(Cursor::new(Vec::new()), format).is_err());
+
+    let xml = serde_marc::to_vec_many(&records, FormatEncoding::marc_xml()).unwrap();
+    let xml_records: Vec<Record> = serde_marc::iter_from_reader(Cursor::new(&xml), FormatEncoding::marc_xml()).map(|r| r.unwrap()).collect();
+    assert_eq!(xml_records.len(), 3);
+    assert_eq!(xml_records[2].control_fields[0].value, "3");
+
+    // Records are matched by local name, as served by SRU and OAI-PMH endpoints
+    let prefixed = String::from_utf8(xml.clone())
+        .unwrap()
+        .replace('<', "<marc:")
+        .replace("<marc:/", "</marc:")
+        .replace("<marc:?", "<?")
+        .replace("xmlns=", "xmlns:marc=");
+    assert!(prefixed.contains("<marc:record xmlns:marc=") && prefixed.contains("</marc:record>"));
+    let prefixed_records: Vec<Record> = serde_marc::iter_from_reader(Cursor::new(prefixed.as_bytes()), FormatEncoding::marc_xml()).map(|r| r.unwrap()).collect();
+    assert_eq!(prefixed_records, xml_records);
+    let spaced = prefixed.replace("</marc:record>", "</marc:record\n>");
+    assert_eq!(serde_marc::iter_from_reader(Cursor::new(spaced.as_bytes()), FormatEncoding::marc_xml()).count(), 3);
+
+    // Skipped records are not decoded, reading resumes after them
+    let mut iter = serde_marc::iter_from_reader(Cursor::new(&bytes), format);
+    assert_eq!(iter.skip_records(2).unwrap(), 2);
+    assert_eq!(iter.offset(), 2 * first_length as u64);
+    assert_eq!(iter.next().unwrap().unwrap().control_fields[0].value, "3");
+    assert_eq!(iter.skip_records(5).unwrap(), 0);
+    let xml = serde_marc::to_vec_many(&records, FormatEncoding::marc_xml()).unwrap();
+    let mut iter = serde_marc::iter_from_reader(Cursor::new(xml), FormatEncoding::marc_xml());
+    assert_eq!(iter.skip_records(5).unwrap(), 3);
+    assert!(iter.next().is_none());
+    let mut iter = serde_marc::iter_from_reader(Cursor::new(&bytes[..first_length + 10]), format);
+    assert!(iter.skip_records(2).is_err());
+    assert_eq!(iter.record_index(), 1);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_marc_extract() {
+    #[derive(Debug, serde::Deserialize)]
+    struct Summary {
+        id: String,
+        language: String,
+        year: u16,
+        subjects: Vec<String>,
+    }
+
+    let record = Record {
+        leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+        control_fields: vec![
+            ControlField {
+                tag: "001".to_string(),
+                value: "ocm123".to_string(),
+            },
+            ControlField {
+                tag: "008".to_string(),
+                value: "850423s1984    nyua   j      000 1 eng d".to_string(),
+            },
+        ],
+        data_fields: vec![],
+    };
+    let mapping = [("id", "001"), ("language", "008/35-37"), ("year", "008/7-10"), ("subjects", "650$a")];
+    let summary: Summary = serde_marc::extract(&record, &mapping).unwrap();
+    assert_eq!(summary.id, "ocm123");
+    assert_eq!(summary.language, "eng");
+    assert_eq!(summary.year, 1984);
+    assert!(summary.subjects.is_empty());
+
+    let error = serde_marc::extract::<Summary>(&record, &[("id", "001"), ("language", "041$a"), ("year", "008/7-10")]).unwrap_err();
+    assert!(error.contains("041$a: no value"), "{}", error);
+    assert!(serde_marc::extract::<Summary>(&record, &[("id", "0x")]).is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_flat_shapes() {
+    let subject = |value: &str| DataField {
+        tag: "650".to_string(),
+        ind1: ' ',
+        ind2: '0',
+        subfields: vec![Subfield {
+            code: 'a',
+            value: value.to_string(),
+        }],
+    };
+    let record = Record {
+        leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+        control_fields: vec![ControlField {
+            tag: "001".to_string(),
+            value: "r1".to_string(),
+        }],
+        data_fields: vec![subject("Cats"), subject("Dogs")],
+    };
+
+    let flat = serde_json::to_value(Flat::new(&record)).unwrap();
+    assert_eq!(flat["001"], "r1");
+    assert_eq!(flat["650$a"], "Cats | Dogs");
+    assert_eq!(flat["leader"], "00000nam a2200000 a 4500");
+
+    let rows = serde_json::to_value(FieldRows(&record)).unwrap();
+    assert_eq!(rows.as_array().unwrap().len(), 3);
+    assert_eq!(rows[0]["ind1"], serde_json::Value::Null);
+    assert_eq!(rows[2]["record_id"], "r1");
+    assert_eq!(rows[2]["occurrence"], 1);
+    assert_eq!(rows[2]["value"], "$aDogs");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_errors() {
+    let error = ParseError::InvalidLeader("Leader must be 24 bytes, got 3".to_string());
+    let json = serde_json::to_value(&error).unwrap();
+    assert_eq!(json["kind"], "invalid_leader");
+    assert_eq!(json["message"], "Leader must be 24 bytes, got 3");
+    assert_eq!(serde_json::from_value::<ParseError>(json).unwrap(), error);
+    assert_eq!(serde_json::from_str::<ParseError>(r#"{"kind":"unexpected_eof"}"#).unwrap(), ParseError::UnexpectedEof);
+    assert!(serde_json::from_str::<ParseError>(r#"{"kind":"nope"}"#).is_err());
+
+    let error = WriteError::InvalidRecord("Invalid tag length: 24".to_string());
+    let round_trip: WriteError = serde_json::from_value(serde_json::to_value(&error).unwrap()).unwrap();
+    assert_eq!(round_trip, error);
+
+    // Reports locate the failing record in a stream
+    let format = FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8);
+    let record = Record {
+        leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+        control_fields: vec![ControlField {
+            tag: "001".to_string(),
+            value: "1".to_string(),
+        }],
+        data_fields: vec![],
+    };
+    let mut bytes = serde_marc::to_vec(&record, format).unwrap();
+    let first_length = bytes.len();
+    bytes.extend_from_slice(b"00099xxxx");
+
+    let mut reader = serde_marc::iter_from_reader(std::io::Cursor::new(bytes), format);
+    assert!(reader.next().unwrap().is_ok());
+    let error = reader.next().unwrap().unwrap_err();
+    let report = serde_json::to_value(reader.report(error)).unwrap();
+    assert_eq!(report["record_index"], 1);
+    assert_eq!(report["offset"], first_length);
+    assert_eq!(report["kind"], "unexpected_eof");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_json_lines() {
+    let record = |id: &str| Record {
+        leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+        control_fields: vec![ControlField {
+            tag: "001".to_string(),
+            value: id.to_string(),
+        }],
+        data_fields: vec![DataField {
+            tag: "245".to_string(),
+            ind1: '1',
+            ind2: '0',
+            subfields: vec![Subfield {
+                code: 'a',
+                value: "Line\nbreak".to_string(),
+            }],
+        }],
+    };
+    let records = vec![record("1"), record("2")];
+
+    let mut output = Vec::new();
+    serde_marc::to_jsonl_writer(&records, &mut output).unwrap();
+    let text = String::from_utf8(output.clone()).unwrap();
+    assert_eq!(text.lines().count(), 2);
+    assert!(text.starts_with(r#"{"leader":"00000nam a2200000 a 4500","fields":[{"001":"1"}"#));
+
+    output.extend_from_slice(b"\n");
+    let read: Vec<Record> = serde_marc::from_jsonl_reader(output.as_slice()).map(|r| r.unwrap()).collect();
+    assert_eq!(read, records);
+
+    // A failing output is an I/O error, not a JSON error
+    let mut full: &mut [u8] = &mut [0; 8];
+    assert!(matches!(serde_marc::to_jsonl_writer(&records, &mut full), Err(WriteError::IoError(_))));
+
+    let mut reader = serde_marc::from_jsonl_reader(&b"\n{\"leader\": 1}\n"[..]);
+    let error = reader.next().unwrap().unwrap_err();
+    assert!(error.to_string().contains("Line 2"));
+    assert!(reader.next().is_none());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_json_reader() {
+    let record = |id: &str| Record {
+        leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+        control_fields: vec![ControlField {
+            tag: "001".to_string(),
+            value: id.to_string(),
+        }],
+        data_fields: vec![],
+    };
+    let records = vec![record("1"), record("2")];
+    let pretty = |record: &Record| serde_json::to_string_pretty(&MarcJson(record)).unwrap();
+    let read = |json: &str| serde_marc::from_json_reader(json.as_bytes()).collect::<Result<Vec<Record>, _>>();
+
+    // Arrays, pretty-printed records one after another, and JSON Lines
+    assert_eq!(read(&format!(" [\n{},\n{}\n]\n", pretty(&records[0]), pretty(&records[1]))).unwrap(), records);
+    assert_eq!(read(&format!("{}\n{}", pretty(&records[0]), pretty(&records[1]))).unwrap(), records);
+    let mut jsonl = Vec::new();
+    serde_marc::to_jsonl_writer(&records, &mut jsonl).unwrap();
+    assert_eq!(read(std::str::from_utf8(&jsonl).unwrap()).unwrap(), records);
+    assert_eq!(read("[]").unwrap(), vec![]);
+    assert_eq!(read("").unwrap(), vec![]);
+
+    assert_eq!(read(&format!("[{}", pretty(&records[0]))).unwrap_err().to_string(), "Parse error: Unterminated record array");
+    assert!(read(&format!("[{} {}]", pretty(&records[0]), pretty(&records[1]))).unwrap_err().to_string().contains("Record 2: expected ',' or ']'"));
+    assert!(read(&format!("[{}, 1]", pretty(&records[0]))).unwrap_err().to_string().contains("Record 2"));
+    let mut reader = serde_marc::from_json_reader(&b"[{\"leader\": 1}]"[..]);
+    assert!(reader.next().unwrap().unwrap_err().to_string().starts_with("Parse error: Record 1: "));
+    assert!(reader.next().is_none());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_json_schema() {
+    let record = Record {
+        leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+        control_fields: vec![],
+        data_fields: vec![],
+    };
+
+    // Every key of the serialized record is described and required by the schema
+    for options in [
+        SerdeOptions::default(),
+        SerdeOptions::marc_json(),
+        SerdeOptions {
+            key_case: KeyCase::Camel,
+            ..SerdeOptions::default()
+        },
+    ] {
+        let schema = options.json_schema();
+        let json = serde_json::to_value(options.wrap(&record)).unwrap();
+        let keys: Vec<&String> = json.as_object().unwrap().keys().collect();
+        let required: Vec<&str> = schema["required"].as_array().unwrap().iter().map(|k| k.as_str().unwrap()).collect();
+        assert_eq!(keys.len(), required.len());
+        for key in keys {
+            assert!(required.contains(&key.as_str()), "{} not in {:?}", key, required);
+            assert!(schema["properties"][key].is_object());
+        }
+        if let Some(leader) = json["leader"].as_object() {
+            for key in leader.keys() {
+                assert!(schema["properties"]["leader"]["properties"][key].is_object(), "{}", key);
+            }
+        }
+    }
+
+    assert_eq!(serde_marc::json_schema(), SerdeOptions::default().json_schema());
+    assert_eq!(serde_marc::json_schema()["properties"]["leader"]["properties"]["record_length"]["type"], "integer");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_marc_json_dialects() {
+    let expected = vec![
+        Subfield {
+            code: 'a',
+            value: "Title".to_string(),
+        },
+        Subfield {
+            code: 'c',
+            value: "Author".to_string(),
+        },
+    ];
+
+    for body in [
+        r#"{"ind1":"1","ind2":"0","subfields":[{"a":"Title"},{"c":"Author"}]}"#,
+        r#"{"indicator1":"1","indicator2":0,"subfields":{"a":"Title","c":"Author"}}"#,
+        r#"{"indicators":"10","subfields":[{"code":"a","value":"Title"},{"value":"Author","code":"c"}]}"#,
+        r#"{"ind1":"1","ind2":"0","subfields":[["a","Title"],["c","Author"]]}"#,
+    ] {
+        let json = format!(r#"{{"leader":"00000nam a2200000 a 4500","fields":[{{"245":{}}}]}}"#, body);
+        let MarcJson(record): MarcJson<Record> = serde_json::from_str(&json).unwrap_or_else(|e| panic!("{}: {}", body, e));
+        let field = &record.data_fields[0];
+        assert_eq!((field.ind1, field.ind2), ('1', '0'), "{}", body);
+        assert_eq!(field.subfields, expected, "{}", body);
+    }
+
+    // Missing or empty indicators are blanks
+    let json = r#"{"leader":"00000nam a2200000 a 4500","fields":[{"650":{"ind1":"","subfields":[{"a":"Topic"}]}}]}"#;
+    let MarcJson(record): MarcJson<Record> = serde_json::from_str(json).unwrap();
+    assert_eq!((record.data_fields[0].ind1, record.data_fields[0].ind2), (' ', ' '));
+
+    let json = r#"{"leader":"00000nam a2200000 a 4500","fields":[{"650":{"ind1":"12","subfields":[]}}]}"#;
+    assert!(serde_json::from_str::<MarcJson<Record>>(json).is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_leader_validation() {
+    let error = serde_json::from_str::<MarcJson<Record>>(r#"{"leader":"00000nam a2200000 a 45x0","fields":[]}"#).unwrap_err();
+    assert!(error.to_string().contains("length_of_implementation_defined_portion at position 22: expected a digit, found 'x'"), "{}", error);
+    let error = serde_json::from_str::<MarcJson<Record>>(r#"{"leader":"00000nam a2200000 a 450","fields":[]}"#).unwrap_err();
+    assert!(error.to_string().contains("Leader must be 24 bytes, got 23"), "{}", error);
+    assert!(serde_json::from_str::<MarcJson<Record>>(r#"{"leader":"00000nam a2200000 é 450","fields":[]}"#).is_err());
+
+    let leader = Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap();
+    let mut json = serde_json::to_value(&leader).unwrap();
+    assert_eq!(serde_json::from_value::<Leader>(json.clone()).unwrap(), leader);
+    json["indicator_count"] = serde_json::json!(10);
+    let error = serde_json::from_value::<Leader>(json).unwrap_err();
+    assert!(error.to_string().contains("indicator_count at position 10"), "{}", error);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_skip_empty_arrays_and_canonical_keys() {
+    let record = Record {
+        leader: Leader::from_bytes(b"00000nam a2200000 a 4500").unwrap(),
+        control_fields: vec![],
+        data_fields: vec![DataField {
+            tag: "245".to_string(),
+            ind1: '1',
+            ind2: '0',
+            subfields: vec![],
+        }],
+    };
+
+    let options = SerdeOptions {
+        leader: LeaderRepresentation::String,
+        skip_empty_arrays: true,
+        canonical_key_order: true,
+        ..SerdeOptions::default()
+    };
+    let json = serde_json::to_string(&options.wrap(&record)).unwrap();
+    assert_eq!(json, r#"{"data_fields":[{"ind1":"1","ind2":"0","tag":"245"}],"leader":"00000nam a2200000 a 4500"}"#);
+
+    let options = SerdeOptions {
+        canonical_key_order: true,
+        ..SerdeOptions::default()
+    };
+    let json = serde_json::to_string(&options.wrap(&record)).unwrap();
+    assert!(json.starts_with(r#"{"control_fields":[],"data_fields":[{"ind1":"1","ind2":"0","subfields":[],"tag":"245"}],"leader":{"base_address_of_data":0,"bibliographic_level":"m","#), "{}", json);
+
+    // MARC-in-JSON without empty arrays still deserializes
+    let options = SerdeOptions {
+        skip_empty_arrays: true,
+        ..SerdeOptions::marc_json()
+    };
+    let json = serde_json::to_string(&options.wrap(&record)).unwrap();
+    assert_eq!(json, r#"{"leader":"00000nam a2200000 a 4500","fields":[{"245":{"ind1":"1","ind2":"0"}}]}"#);
+    let MarcJson(read): MarcJson<Record> = serde_json::from_str(&json).unwrap();
+    assert_eq!(read, record);
+    let empty = Record { data_fields: vec![], ..record };
+    let json = serde_json::to_string(&options.wrap(&empty)).unwrap();
+    assert_eq!(json, r#"{"leader":"00000nam a2200000 a 4500"}"#);
+    assert_eq!(serde_json::from_str::<MarcJson<Record>>(&json).unwrap().0, empty);
+    assert_eq!(options.json_schema()["required"], serde_json::json!(["leader"]));
+}
@@ -0,0 +1,84 @@
+#![cfg(feature = "sru")]
+
+use marc_rs::*;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::mpsc::{self, Receiver};
+
+/// A response page with one record per control number, packed as XML
+fn page(numbers: &[&str], total: usize, next: Option<usize>) -> String {
+    let records: String = numbers
+        .iter()
+        .map(|n| format!(r#"<zs:record><zs:recordSchema>marcxml</zs:recordSchema><zs:recordPacking>xml</zs:recordPacking><zs:recordData><record xmlns="http://www.loc.gov/MARC21/slim"><leader>00000nam a2200000 a 4500</leader><controlfield tag="001">{}</controlfield></record></zs:recordData></zs:record>"#, n))
+        .collect();
+    let next = next.map_or(String::new(), |next| format!("<zs:nextRecordPosition>{}</zs:nextRecordPosition>", next));
+    format!(r#"<?xml version="1.0"?><zs:searchRetrieveResponse xmlns:zs="http://www.loc.gov/zing/srw/"><zs:version>1.1</zs:version><zs:numberOfRecords>{}</zs:numberOfRecords><zs:records>{}</zs:records>{}</zs:searchRetrieveResponse>"#, total, records, next)
+}
+
+/// Serve canned responses on a local port, in order, one per request
+///
+/// Returns the base URL and a receiver of the request lines.
+fn serve(responses: Vec<String>) -> (String, Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/sru", listener.local_addr().unwrap());
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        for (body, stream) in responses.into_iter().zip(listener.incoming()) {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            sender.send(request_line.trim_end().to_string()).unwrap();
+            write!(stream, "HTTP/1.1 200 OK\r\nContent-Type: text/xml\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", body.len(), body).unwrap();
+        }
+    });
+    (url, receiver)
+}
+
+#[test]
+fn test_parse_sru_response() {
+    let page = parse_sru_response(&page(&["1", "2"], 5, Some(3))).unwrap();
+    assert_eq!(page.number_of_records, 5);
+    assert_eq!(page.next_record_position, Some(3));
+    assert_eq!(page.records.iter().map(|r| r.control_fields[0].value.as_str()).collect::<Vec<_>>(), vec!["1", "2"]);
+
+    // Records packed as escaped strings
+    let string = r#"<searchRetrieveResponse><numberOfRecords>1</numberOfRecords><records><record><recordPacking>string</recordPacking><recordData>&lt;record&gt;&lt;leader&gt;00000nam a2200000 a 4500&lt;/leader&gt;&lt;datafield tag="245" ind1="0" ind2="0"&gt;&lt;subfield code="a"&gt;Caf&#233; &amp;amp; th&#233;&lt;/subfield&gt;&lt;/datafield&gt;&lt;/record&gt;</recordData></record></records></searchRetrieveResponse>"#;
+    let page = parse_sru_response(string).unwrap();
+    assert_eq!(page.records[0].data_fields[0].subfields[0].value, "Café & thé");
+    assert_eq!(page.next_record_position, None);
+}
+
+#[test]
+fn test_parse_sru_diagnostic() {
+    let diagnostic = r#"<zs:searchRetrieveResponse xmlns:zs="http://www.loc.gov/zing/srw/"><zs:numberOfRecords>0</zs:numberOfRecords><zs:diagnostics><diagnostic xmlns="http://www.loc.gov/zing/srw/diagnostic/"><uri>info:srw/diagnostic/1/10</uri><message>Query syntax error</message></diagnostic></zs:diagnostics></zs:searchRetrieveResponse>"#;
+    assert_eq!(
+        parse_sru_response(diagnostic),
+        Err(SruError::Diagnostic {
+            uri: "info:srw/diagnostic/1/10".to_string(),
+            message: "Query syntax error".to_string()
+        })
+    );
+
+    let no_match = parse_sru_response(&page(&[], 0, None)).unwrap();
+    assert_eq!(no_match, SruPage::default());
+}
+
+#[test]
+fn test_sru_search_pages() {
+    // The second page leaves out nextRecordPosition although records remain
+    let (url, requests) = serve(vec![page(&["1", "2"], 5, Some(3)), page(&["3", "4"], 5, None), page(&["5"], 5, None)]);
+    let mut search = SruSearch::new(&url, "dc.title=hobbit").page_size(2);
+    let numbers: Vec<String> = search.by_ref().map(|record| record.unwrap().control_fields[0].value.clone()).collect();
+    assert_eq!(numbers, vec!["1", "2", "3", "4", "5"]);
+    assert_eq!(search.number_of_records(), Some(5));
+
+    let requests: Vec<String> = requests.try_iter().collect();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(requests[0], "GET /sru?operation=searchRetrieve&version=1.1&query=dc.title%3Dhobbit&startRecord=1&maximumRecords=2&recordSchema=marcxml&recordPacking=xml HTTP/1.1");
+    assert!(requests[2].contains("&startRecord=5&"));
+}
@@ -0,0 +1,121 @@
+#![cfg(feature = "z3950")]
+
+use marc_rs::*;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc::{self, Receiver};
+
+/// BER element with a tag given as its encoded bytes
+fn tlv(tag: &[u8], content: &[u8]) -> Vec<u8> {
+    let mut out = tag.to_vec();
+    match content.len() {
+        n if n < 0x80 => out.push(n as u8),
+        n => out.extend([0x82, (n >> 8) as u8, n as u8]),
+    }
+    out.extend(content);
+    out
+}
+
+fn init_response(accepted: bool) -> Vec<u8> {
+    tlv(&[0xB5], &[tlv(&[0x83], &[0x05, 0xE0]), tlv(&[0x84], &[0x06, 0xC0]), tlv(&[0x85], &[0x10, 0x00, 0x00]), tlv(&[0x86], &[0x10, 0x00, 0x00]), tlv(&[0x8C], &[if accepted { 0xFF } else { 0x00 }])].concat())
+}
+
+/// A retrieval record in USMARC syntax
+fn usmarc(mrk: &str) -> Vec<u8> {
+    let mut record = Vec::new();
+    write_one(&parse_mrk(mrk).unwrap()[0], FormatEncoding::new(MarcFormat::Marc21, Encoding::Utf8), &mut record).unwrap();
+    let external = [tlv(&[0x06], &[0x2A, 0x86, 0x48, 0xCE, 0x13, 0x05, 0x0A]), tlv(&[0x81], &record)].concat();
+    tlv(&[0x30], &tlv(&[0xA1], &tlv(&[0xA1], &external)))
+}
+
+/// Read one request PDU, with a definite length
+fn read_pdu(stream: &mut impl Read) -> Option<Vec<u8>> {
+    let mut pdu = vec![0u8; 2];
+    stream.read_exact(&mut pdu).ok()?;
+    let length = match pdu[1] {
+        n if n < 0x80 => n as usize,
+        n => {
+            let mut bytes = vec![0u8; (n & 0x7F) as usize];
+            stream.read_exact(&mut bytes).ok()?;
+            pdu.extend(&bytes);
+            bytes.iter().fold(0, |length, b| (length << 8) | *b as usize)
+        }
+    };
+    let start = pdu.len();
+    pdu.resize(start + length, 0);
+    stream.read_exact(&mut pdu[start..]).ok()?;
+    Some(pdu)
+}
+
+/// Answer the requests of one connection with canned responses, in order
+///
+/// Returns the target address and a receiver of the request PDUs.
+fn serve(responses: Vec<Vec<u8>>) -> (String, Receiver<Vec<u8>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        for response in responses {
+            let Some(request) = read_pdu(&mut stream) else { return };
+            sender.send(request).unwrap();
+            stream.write_all(&response).unwrap();
+        }
+    });
+    (address, receiver)
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|w| w == needle)
+}
+
+#[test]
+fn test_z3950_search_and_present() {
+    let search = tlv(&[0xB7], &[tlv(&[0x97], &[0x03]), tlv(&[0x98], &[0x00]), tlv(&[0x99], &[0x01]), tlv(&[0x96], &[0xFF])].concat());
+    let surrogate = tlv(&[0x30], &tlv(&[0xA1], &tlv(&[0xA2], &tlv(&[0x30], &[tlv(&[0x06], &[0x2A, 0x86, 0x48, 0xCE, 0x13, 0x04, 0x01]), tlv(&[0x02], &[0x0E])].concat()))));
+    let records = [usmarc("=LDR  00000nam a2200000 a 4500\n=001  1\n=245  00$aThe hobbit\n"), surrogate, usmarc("=LDR  00000nam a2200000 a 4500\n=001  3\n=245  00$aLe Hobbit, édition illustrée\n")].concat();
+    let present = tlv(&[0xB9], &[tlv(&[0x98], &[0x02]), tlv(&[0x99], &[0x04]), tlv(&[0x9B], &[0x00]), tlv(&[0xBC], &records)].concat());
+    let (address, requests) = serve(vec![init_response(true), search, present]);
+
+    let mut client = Z3950Client::connect(&address, "Books").unwrap();
+    let init = requests.recv().unwrap();
+    assert_eq!(init[0], 0xB4);
+    assert!(contains(&init, b"marc-rs"));
+
+    assert_eq!(client.search("@and @attr 1=4 hobbit @attr 1=1003 \"tolkien, j\"").unwrap(), 3);
+    let search = requests.recv().unwrap();
+    assert_eq!(search[0], 0xB6);
+    assert!(contains(&search, &tlv(&[0x9F, 0x69], b"Books")));
+    // Use attribute 4 (title), then the term
+    assert!(contains(&search, &[tlv(&[0x9F, 0x78], &[0x01]), tlv(&[0x9F, 0x79], &[0x04])].concat()));
+    assert!(contains(&search, &tlv(&[0x9F, 0x2D], b"hobbit")));
+    assert!(contains(&search, &tlv(&[0x9F, 0x2D], b"tolkien, j")));
+    // @and
+    assert!(contains(&search, &[0xBF, 0x2E, 0x02, 0x80, 0x00]));
+
+    let records = client.present(1, 3).unwrap();
+    let present = requests.recv().unwrap();
+    assert_eq!(present[0], 0xB8);
+    assert!(contains(&present, &[0x9E, 0x01, 0x01, 0x9D, 0x01, 0x03]));
+    // The record with a surrogate diagnostic is left out
+    assert_eq!(records.iter().map(|r| r.control_fields[0].value.as_str()).collect::<Vec<_>>(), vec!["1", "3"]);
+    assert_eq!(records[1].data_fields[0].subfields[0].value, "Le Hobbit, édition illustrée");
+}
+
+#[test]
+fn test_z3950_diagnostics() {
+    let diagnostic = tlv(&[0xBF, 0x81, 0x02], &[tlv(&[0x06], &[0x2A, 0x86, 0x48, 0xCE, 0x13, 0x04, 0x01]), tlv(&[0x02], &[0x72]), tlv(&[0x1A], b"1=9999")].concat());
+    let search = tlv(&[0xB7], &[tlv(&[0x97], &[0x00]), tlv(&[0x98], &[0x00]), tlv(&[0x99], &[0x00]), tlv(&[0x96], &[0x00]), diagnostic].concat());
+    let (address, requests) = serve(vec![init_response(true), search]);
+
+    let mut client = Z3950Client::connect(&address, "Books").unwrap();
+    // Invalid queries are not sent
+    assert!(matches!(client.search("@and hobbit"), Err(Z3950Error::Query(_))));
+    assert!(matches!(client.search("@attr 1=title hobbit"), Err(Z3950Error::Query(_))));
+    assert!(matches!(client.search("@prox 0 1 0 2 k 2 tolkien hobbit"), Err(Z3950Error::Query(_))));
+    assert_eq!(client.search("@attr 1=9999 hobbit"), Err(Z3950Error::Diagnostic { condition: 114, addinfo: "1=9999".to_string() }));
+    assert_eq!(requests.iter().take(2).map(|pdu| pdu[0]).collect::<Vec<_>>(), vec![0xB4, 0xB6]);
+
+    let (address, _requests) = serve(vec![init_response(false)]);
+    assert!(matches!(Z3950Client::connect(&address, "Books"), Err(Z3950Error::Failed(_))));
+}